@@ -7,4 +7,21 @@ fn main() {
             .app_manifest(tauri_build::AppManifest::new()),
     )
     .expect("failed to run Tauri build script");
+
+    #[cfg(feature = "grpc")]
+    compile_grpc();
+}
+
+/// The gRPC service is generated from proto/hive.proto. Use the vendored protoc
+/// so building does not depend on one being installed.
+#[cfg(feature = "grpc")]
+fn compile_grpc() {
+    std::env::set_var(
+        "PROTOC",
+        protoc_bin_vendored::protoc_bin_path().expect("vendored protoc is unavailable"),
+    );
+    tonic_build::configure()
+        .build_client(false)
+        .compile_protos(&["proto/hive.proto"], &["proto"])
+        .expect("failed to compile proto/hive.proto");
 }
@@ -1,14 +1,23 @@
 //! Coordination and session-state actions behind the unified action registry.
 
+use std::collections::HashMap;
+
 use async_trait::async_trait;
 use schemars::schema::RootSchema;
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 
-use crate::coordination::{CoordinationMessage, MessageType, StateManager, WorkerStateInfo};
-use crate::pty::{AgentConfig, AgentRole, WorkerRole};
+use crate::adapters::SubmitKey;
+use crate::coordination::{
+    CoordinationMessage, InjectionError, MessageType, StateManager, WorkerStateInfo,
+};
+use crate::pty::{AgentConfig, AgentRole, AgentStatus, WorkerRole};
+use crate::session::plan_history::{self, PlanDiff};
+use crate::session::plan_parser::{self, Plan};
+use crate::storage::secrets::{self, KeyringSecrets};
 use crate::tauri_shim::Emitter;
+use crate::templates::{validate_template_name, TemplateEngine, TemplateError};
 
 use super::error::ActionError;
 use super::registry::{Action, ActionRegistry};
@@ -19,7 +28,14 @@ pub struct QueenInjectRequest {
     pub session_id: String,
     pub queen_id: String,
     pub target_worker_id: String,
+    #[serde(default)]
     pub message: String,
+    /// Named injection template (e.g. `check-inbox`) sent instead of `message`.
+    #[serde(default)]
+    pub template: Option<String>,
+    /// Extra placeholders for `template`; session context takes precedence.
+    #[serde(default)]
+    pub variables: HashMap<String, String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
@@ -36,6 +52,19 @@ pub struct AddWorkerRequest {
 pub struct OperatorInjectRequest {
     pub session_id: String,
     pub target_agent_id: String,
+    #[serde(default)]
+    pub message: String,
+    /// Named injection template (e.g. `commit-now`) sent instead of `message`.
+    #[serde(default)]
+    pub template: Option<String>,
+    /// Extra placeholders for `template`; session context takes precedence.
+    #[serde(default)]
+    pub variables: HashMap<String, String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct BroadcastRequest {
+    pub session_id: String,
     pub message: String,
 }
 
@@ -68,6 +97,17 @@ pub struct SessionPlan {
     pub raw_content: String,
 }
 
+/// Which instance the app window belongs to and where its API is served.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct InstanceInfo {
+    pub instance: u32,
+    /// The TCP port actually bound; `None` while the API is disabled or starting.
+    pub api_port: Option<u16>,
+    pub socket_path: Option<String>,
+    /// Token the UI sends on API writes; handed out over IPC only.
+    pub ui_token: String,
+}
+
 #[derive(Debug, Deserialize, JsonSchema)]
 struct EmptyInput {}
 
@@ -82,6 +122,9 @@ struct QueenSwitchBranchInput {
 struct CoordinationLogInput {
     session_id: String,
     limit: Option<usize>,
+    /// Only messages logged after this `seq`; `limit` is ignored when set.
+    #[serde(default)]
+    after_seq: Option<u64>,
 }
 
 #[derive(Debug, Deserialize, JsonSchema)]
@@ -90,6 +133,9 @@ struct LogCoordinationMessageInput {
     from: String,
     to: String,
     content: String,
+    /// IDs of uploaded coordination attachments the message refers to.
+    #[serde(default)]
+    attachments: Vec<String>,
 }
 
 #[derive(Debug, Deserialize, JsonSchema)]
@@ -97,6 +143,32 @@ struct SessionIdInput {
     session_id: String,
 }
 
+#[derive(Debug, Deserialize, JsonSchema)]
+struct AnswerQuestionInput {
+    session_id: String,
+    question_id: String,
+    answer: String,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+struct ListApprovalsInput {
+    /// Every session's parked requests when omitted.
+    session_id: Option<String>,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+struct ListUnacknowledgedInput {
+    session_id: String,
+    /// Every worker's messages when omitted.
+    agent_id: Option<String>,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+struct ApproveActionInput {
+    approval_id: String,
+    approved: bool,
+}
+
 #[derive(Debug, Deserialize, JsonSchema)]
 struct AssignTaskInput {
     session_id: String,
@@ -111,21 +183,120 @@ struct ListStoredSessionsInput {
     project_path: Option<String>,
 }
 
+#[derive(Debug, Deserialize, JsonSchema)]
+struct ImportLegacyLearningsInput {
+    project_path: String,
+    /// Session whose lessons receive the learnings; the global store when absent.
+    session_id: Option<String>,
+}
+
 #[derive(Debug, Deserialize, JsonSchema)]
 struct UpdateAppConfigInput {
     config: Value,
 }
 
+#[derive(Debug, Deserialize, JsonSchema)]
+struct SwitchProfileInput {
+    profile: String,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+struct SetSecretInput {
+    name: String,
+    value: String,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+struct SecretNameInput {
+    name: String,
+}
+
+/// A prompt template name such as `queen-hive` or `roles/backend`.
+#[derive(Debug, Deserialize, JsonSchema)]
+struct TemplateNameInput {
+    name: String,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+struct SaveTemplateInput {
+    name: String,
+    content: String,
+}
+
+/// Config profiles known to storage and the one currently in use.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct ProfileList {
+    pub active: String,
+    pub profiles: Vec<String>,
+}
+
 fn deserialize_input<T: for<'de> Deserialize<'de>>(input: Value) -> Result<T, ActionError> {
     serde_json::from_value(input)
         .map_err(|e| ActionError::bad_request(format!("Invalid input: {}", e)))
 }
 
+/// Best effort: the TASK has already reached the worker's terminal, so a task file
+/// that cannot be updated is logged rather than failing the request.
+fn activate_worker_task(ctx: &ActionContext, session_id: &str, worker_id: &str, task: &str) {
+    let controller = ctx.state.session_controller.read();
+    if let Err(e) = controller.activate_worker_task(session_id, worker_id, task) {
+        tracing::warn!("Failed to activate task file for {}: {}", worker_id, e);
+    }
+}
+
 fn serialize_output<T: Serialize>(value: T, label: &str) -> Result<Value, ActionError> {
     serde_json::to_value(value)
         .map_err(|e| ActionError::internal(format!("Failed to serialize {}: {}", label, e)))
 }
 
+fn template_engine(ctx: &ActionContext) -> TemplateEngine {
+    TemplateEngine::new(ctx.state.storage.templates_dir())
+}
+
+fn template_error(err: TemplateError) -> ActionError {
+    match err {
+        TemplateError::NotFound(name) => {
+            ActionError::not_found(format!("Prompt template {} not found", name))
+        }
+        TemplateError::Invalid(message) => ActionError::bad_request(message),
+        TemplateError::Io(err) => ActionError::internal(err.to_string()),
+    }
+}
+
+fn validate_template_name_input(name: &str) -> Result<(), ActionError> {
+    validate_template_name(name).map_err(template_error)
+}
+
+/// The text to inject: `message` as given, or `template` rendered with the
+/// session's context for `target_agent_id`.
+fn injection_text(
+    ctx: &ActionContext,
+    session_id: &str,
+    target_agent_id: &str,
+    message: &str,
+    template: Option<&str>,
+    variables: HashMap<String, String>,
+) -> Result<String, ActionError> {
+    let Some(template) = template.filter(|template| !template.trim().is_empty()) else {
+        return Ok(message.to_string());
+    };
+    if !message.trim().is_empty() {
+        return Err(ActionError::bad_request(
+            "Provide either message or template, not both",
+        ));
+    }
+    let controller = ctx.state.session_controller.read();
+    controller
+        .render_injection(session_id, target_agent_id, template, variables)
+        .map_err(|e| {
+            if e.contains("not found") {
+                ActionError::not_found(e)
+            } else {
+                ActionError::bad_request(e)
+            }
+        })
+}
+
 fn require_frontend(ctx: &ActionContext) -> Result<(), ActionError> {
     if matches!(ctx.caller, Caller::Frontend) {
         Ok(())
@@ -151,15 +322,28 @@ impl Action for QueenInject {
     async fn run(&self, ctx: &ActionContext, input: Value) -> Result<Value, ActionError> {
         require_frontend(ctx)?;
         let request: QueenInjectRequest = deserialize_input(input)?;
+        let message = injection_text(
+            ctx,
+            &request.session_id,
+            &request.target_worker_id,
+            &request.message,
+            request.template.as_deref(),
+            request.variables,
+        )?;
         let manager = ctx.state.injection_manager.read();
         manager
             .queen_inject(
                 &request.session_id,
                 &request.queen_id,
                 &request.target_worker_id,
-                &request.message,
+                &message,
             )
             .map_err(|e| ActionError::internal(e.to_string()))?;
+
+        // A plain TASK is the worker's assignment, so it also activates the task file.
+        if request.template.is_none() {
+            activate_worker_task(ctx, &request.session_id, &request.target_worker_id, &message);
+        }
         Ok(Value::Null)
     }
 }
@@ -228,15 +412,237 @@ impl Action for OperatorInject {
     async fn run(&self, ctx: &ActionContext, input: Value) -> Result<Value, ActionError> {
         require_frontend(ctx)?;
         let request: OperatorInjectRequest = deserialize_input(input)?;
+        let message = injection_text(
+            ctx,
+            &request.session_id,
+            &request.target_agent_id,
+            &request.message,
+            request.template.as_deref(),
+            request.variables,
+        )?;
         let manager = ctx.state.injection_manager.read();
         manager
+            .operator_inject(&request.session_id, &request.target_agent_id, &message)
+            .map_err(|e| ActionError::internal(e.to_string()))?;
+        Ok(Value::Null)
+    }
+}
+
+/// Inject one operator message into every agent of a session whose process is
+/// still running, e.g. to tell them all that the requirements changed.
+struct BroadcastToSession;
+
+#[async_trait]
+impl Action for BroadcastToSession {
+    fn name(&self) -> &'static str {
+        "coordination.broadcast_to_session"
+    }
+
+    fn input_schema(&self) -> RootSchema {
+        schemars::schema_for!(BroadcastRequest)
+    }
+
+    async fn run(&self, ctx: &ActionContext, input: Value) -> Result<Value, ActionError> {
+        require_frontend(ctx)?;
+        let request: BroadcastRequest = deserialize_input(input)?;
+        if request.message.trim().is_empty() {
+            return Err(ActionError::bad_request("message must not be empty"));
+        }
+        let session = ctx
+            .state
+            .session_controller
+            .read()
+            .get_session(&request.session_id)
+            .ok_or_else(|| {
+                ActionError::not_found(format!("Session {} not found", request.session_id))
+            })?;
+        let targets: Vec<(String, SubmitKey)> = session
+            .agents
+            .iter()
+            .filter(|agent| {
+                matches!(
+                    agent.status,
+                    AgentStatus::Starting
+                        | AgentStatus::Running
+                        | AgentStatus::Idle
+                        | AgentStatus::WaitingForInput(_)
+                )
+            })
+            .map(|agent| {
+                let submit = ctx.state.pty_manager.read().submit_key(&agent.id);
+                (agent.id.clone(), submit)
+            })
+            .collect();
+        if targets.is_empty() {
+            return Err(ActionError::conflict(format!(
+                "Session {} has no running agents",
+                request.session_id
+            )));
+        }
+
+        let report = ctx
+            .state
+            .injection_manager
+            .read()
+            .operator_broadcast(&request.session_id, &targets, &request.message)
+            .map_err(|e| match e {
+                InjectionError::RateLimited(message) => ActionError::conflict(message),
+                other => ActionError::internal(other.to_string()),
+            })?;
+        serialize_output(report, "broadcast report")
+    }
+}
+
+struct ListQuestions;
+
+#[async_trait]
+impl Action for ListQuestions {
+    fn name(&self) -> &'static str {
+        "coordination.list_questions"
+    }
+
+    fn input_schema(&self) -> RootSchema {
+        schemars::schema_for!(SessionIdInput)
+    }
+
+    async fn run(&self, ctx: &ActionContext, input: Value) -> Result<Value, ActionError> {
+        require_frontend(ctx)?;
+        let parsed: SessionIdInput = deserialize_input(input)?;
+        let questions = ctx
+            .state
+            .session_controller
+            .read()
+            .operator_questions(&parsed.session_id);
+        serialize_output(questions, "questions")
+    }
+}
+
+struct AnswerQuestion;
+
+#[async_trait]
+impl Action for AnswerQuestion {
+    fn name(&self) -> &'static str {
+        "coordination.answer_question"
+    }
+
+    fn input_schema(&self) -> RootSchema {
+        schemars::schema_for!(AnswerQuestionInput)
+    }
+
+    async fn run(&self, ctx: &ActionContext, input: Value) -> Result<Value, ActionError> {
+        require_frontend(ctx)?;
+        let request: AnswerQuestionInput = deserialize_input(input)?;
+        let answer = request.answer.trim();
+        if answer.is_empty() {
+            return Err(ActionError::bad_request("Answer must not be empty"));
+        }
+        let question_error = |e: String| {
+            if e.contains("not found") {
+                ActionError::not_found(e)
+            } else {
+                ActionError::conflict(e)
+            }
+        };
+
+        let question = ctx
+            .state
+            .session_controller
+            .read()
+            .pending_operator_question(&request.session_id, &request.question_id)
+            .map_err(question_error)?;
+        ctx.state
+            .injection_manager
+            .read()
             .operator_inject(
                 &request.session_id,
-                &request.target_agent_id,
-                &request.message,
+                &question.agent_id,
+                &format!("[OPERATOR ANSWER] {}", answer),
             )
             .map_err(|e| ActionError::internal(e.to_string()))?;
-        Ok(Value::Null)
+        let answered = ctx
+            .state
+            .session_controller
+            .read()
+            .answer_operator_question(&request.session_id, &request.question_id, answer)
+            .map_err(question_error)?;
+        serialize_output(answered, "question")
+    }
+}
+
+struct ListApprovals;
+
+#[async_trait]
+impl Action for ListApprovals {
+    fn name(&self) -> &'static str {
+        "coordination.list_approvals"
+    }
+
+    fn input_schema(&self) -> RootSchema {
+        schemars::schema_for!(ListApprovalsInput)
+    }
+
+    async fn run(&self, ctx: &ActionContext, input: Value) -> Result<Value, ActionError> {
+        require_frontend(ctx)?;
+        let parsed: ListApprovalsInput = deserialize_input(input)?;
+        serialize_output(
+            ctx.state.approvals.pending(parsed.session_id.as_deref()),
+            "approvals",
+        )
+    }
+}
+
+struct ApproveAction;
+
+#[async_trait]
+impl Action for ApproveAction {
+    fn name(&self) -> &'static str {
+        "coordination.approve_action"
+    }
+
+    fn input_schema(&self) -> RootSchema {
+        schemars::schema_for!(ApproveActionInput)
+    }
+
+    async fn run(&self, ctx: &ActionContext, input: Value) -> Result<Value, ActionError> {
+        require_frontend(ctx)?;
+        let parsed: ApproveActionInput = deserialize_input(input)?;
+        let request = ctx
+            .state
+            .approvals
+            .resolve(&parsed.approval_id, parsed.approved)
+            .map_err(ActionError::not_found)?;
+        tracing::info!(
+            "Operator {} approval request {} for session {}",
+            if parsed.approved { "approved" } else { "rejected" },
+            request.id,
+            request.session_id
+        );
+        serialize_output(request, "approval request")
+    }
+}
+
+struct ListUnacknowledged;
+
+#[async_trait]
+impl Action for ListUnacknowledged {
+    fn name(&self) -> &'static str {
+        "coordination.list_unacknowledged"
+    }
+
+    fn input_schema(&self) -> RootSchema {
+        schemars::schema_for!(ListUnacknowledgedInput)
+    }
+
+    async fn run(&self, ctx: &ActionContext, input: Value) -> Result<Value, ActionError> {
+        require_frontend(ctx)?;
+        let parsed: ListUnacknowledgedInput = deserialize_input(input)?;
+        let messages = ctx
+            .state
+            .injection_manager
+            .read()
+            .unacknowledged_messages(&parsed.session_id, parsed.agent_id.as_deref())
+            .map_err(|e| ActionError::internal(e.to_string()))?;
+        serialize_output(messages, "unacknowledged messages")
     }
 }
 
@@ -283,7 +689,7 @@ impl Action for AddWorker {
     async fn run(&self, ctx: &ActionContext, input: Value) -> Result<Value, ActionError> {
         require_frontend(ctx)?;
         let request: AddWorkerRequest = deserialize_input(input)?;
-        let controller = ctx.state.session_controller.write();
+        let controller = ctx.state.session_controller.read();
 
         let mut config = request.config;
         let normalize_opt_str = |value: Option<String>| {
@@ -313,6 +719,7 @@ impl Action for AddWorker {
         let queen_id = format!("{}-queen", request.session_id);
         let worker_state = WorkerStateInfo {
             id: agent_info.id.clone(),
+            label: agent_info.config.label.clone(),
             role: request.role,
             cli: agent_info.config.cli.clone(),
             status: "Running".to_string(),
@@ -338,6 +745,7 @@ impl Action for AddWorker {
                 })
                 .map(|a| WorkerStateInfo {
                     id: a.id.clone(),
+                    label: a.config.label.clone(),
                     role: a.config.role.clone().unwrap_or_default(),
                     cli: a.config.cli.clone(),
                     status: format!("{:?}", a.status),
@@ -372,9 +780,11 @@ impl Action for GetCoordinationLog {
         require_frontend(ctx)?;
         let parsed: CoordinationLogInput = deserialize_input(input)?;
         let manager = ctx.state.injection_manager.read();
-        let log = manager
-            .get_coordination_log(&parsed.session_id, parsed.limit)
-            .map_err(|e| ActionError::internal(e.to_string()))?;
+        let log = match parsed.after_seq {
+            Some(after_seq) => manager.get_coordination_log_after(&parsed.session_id, after_seq),
+            None => manager.get_coordination_log(&parsed.session_id, parsed.limit),
+        }
+        .map_err(|e| ActionError::internal(e.to_string()))?;
         serialize_output(log, "coordination log")
     }
 }
@@ -394,14 +804,15 @@ impl Action for LogCoordinationMessage {
     async fn run(&self, ctx: &ActionContext, input: Value) -> Result<Value, ActionError> {
         require_frontend(ctx)?;
         let parsed: LogCoordinationMessageInput = deserialize_input(input)?;
-        let coord_message = CoordinationMessage::new(
+        let mut coord_message = CoordinationMessage::new(
             &parsed.from,
             &parsed.to,
             &parsed.content,
             MessageType::System,
         );
+        coord_message.attachments = parsed.attachments;
         ctx.state
-            .storage
+            .backend
             .append_coordination_log(&parsed.session_id, &coord_message)
             .map_err(|e| ActionError::internal(e.to_string()))?;
         if let Some(app_handle) = ctx.state.app_handle.as_ref() {
@@ -461,6 +872,7 @@ impl Action for AssignTask {
                 &parsed.task,
             )
             .map_err(|e| ActionError::internal(e.to_string()))?;
+        activate_worker_task(ctx, &parsed.session_id, &parsed.worker_id, &parsed.task);
 
         let session_path = ctx.state.storage.session_dir(&parsed.session_id);
         let state_manager = StateManager::new(session_path);
@@ -511,6 +923,31 @@ impl Action for GetCurrentDirectory {
     }
 }
 
+struct GetInstanceInfo;
+
+#[async_trait]
+impl Action for GetInstanceInfo {
+    fn name(&self) -> &'static str {
+        "coordination.get_instance_info"
+    }
+
+    fn input_schema(&self) -> RootSchema {
+        schemars::schema_for!(EmptyInput)
+    }
+
+    async fn run(&self, ctx: &ActionContext, _input: Value) -> Result<Value, ActionError> {
+        require_frontend(ctx)?;
+        let info = InstanceInfo {
+            instance: ctx.state.storage.instance(),
+            api_port: crate::http::transport::serving_port(),
+            socket_path: crate::http::transport::serving_socket()
+                .map(|path| path.to_string_lossy().to_string()),
+            ui_token: ctx.state.ui_api_token.clone(),
+        };
+        serde_json::to_value(info).map_err(|e| ActionError::internal(e.to_string()))
+    }
+}
+
 struct ListStoredSessions;
 
 #[async_trait]
@@ -528,7 +965,7 @@ impl Action for ListStoredSessions {
         let parsed: ListStoredSessionsInput = deserialize_input(input)?;
         let sessions = ctx
             .state
-            .storage
+            .backend
             .list_sessions()
             .map_err(|e| ActionError::internal(e.to_string()))?;
 
@@ -559,6 +996,53 @@ impl Action for ListStoredSessions {
     }
 }
 
+struct ImportLegacyLearnings;
+
+#[async_trait]
+impl Action for ImportLegacyLearnings {
+    fn name(&self) -> &'static str {
+        "coordination.import_legacy_learnings"
+    }
+
+    fn input_schema(&self) -> RootSchema {
+        schemars::schema_for!(ImportLegacyLearningsInput)
+    }
+
+    fn validate_input(&self, input: &Value) -> Result<(), ActionError> {
+        let parsed: ImportLegacyLearningsInput = deserialize_input(input.clone())?;
+        if parsed.project_path.trim().is_empty() {
+            return Err(ActionError::bad_request("project_path must not be empty"));
+        }
+        if let Some(id) = parsed.session_id.as_deref() {
+            if id.contains("..") || id.contains('/') || id.contains('\\') {
+                return Err(ActionError::bad_request(
+                    "Invalid session ID: must not contain '..', '/', or '\\'",
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    async fn run(&self, ctx: &ActionContext, input: Value) -> Result<Value, ActionError> {
+        require_frontend(ctx)?;
+        let parsed: ImportLegacyLearningsInput = deserialize_input(input)?;
+        if let Some(session_id) = parsed.session_id.as_deref() {
+            if !ctx.state.storage.session_dir(session_id).is_dir() {
+                return Err(ActionError::not_found(format!("Session not found: {}", session_id)));
+            }
+        }
+        let report = ctx
+            .state
+            .storage
+            .import_legacy_learnings(
+                std::path::Path::new(&parsed.project_path),
+                parsed.session_id.as_deref(),
+            )
+            .map_err(|e| ActionError::internal(e.to_string()))?;
+        serialize_output(report, "legacy learnings import")
+    }
+}
+
 struct GetAppConfig;
 
 #[async_trait]
@@ -575,7 +1059,7 @@ impl Action for GetAppConfig {
         require_frontend(ctx)?;
         let config = ctx
             .state
-            .storage
+            .backend
             .load_config()
             .map_err(|e| ActionError::internal(e.to_string()))?;
         serialize_output(config, "app config")
@@ -600,13 +1084,261 @@ impl Action for UpdateAppConfig {
         let config = serde_json::from_value(parsed.config)
             .map_err(|e| ActionError::bad_request(format!("Invalid app config: {}", e)))?;
         ctx.state
-            .storage
+            .backend
             .save_config(&config)
             .map_err(|e| ActionError::internal(e.to_string()))?;
         Ok(Value::Null)
     }
 }
 
+struct ListProfiles;
+
+#[async_trait]
+impl Action for ListProfiles {
+    fn name(&self) -> &'static str {
+        "coordination.list_profiles"
+    }
+
+    fn input_schema(&self) -> RootSchema {
+        schemars::schema_for!(EmptyInput)
+    }
+
+    async fn run(&self, ctx: &ActionContext, _input: Value) -> Result<Value, ActionError> {
+        require_frontend(ctx)?;
+        let profiles = ctx
+            .state
+            .storage
+            .list_profiles()
+            .map_err(|e| ActionError::internal(e.to_string()))?;
+        serialize_output(
+            ProfileList {
+                active: ctx.state.storage.active_profile(),
+                profiles,
+            },
+            "profile list",
+        )
+    }
+}
+
+/// Switch the active config profile. The shared in-memory config follows immediately;
+/// the HTTP API keeps listening on its current port until the next launch.
+struct SwitchProfile;
+
+#[async_trait]
+impl Action for SwitchProfile {
+    fn name(&self) -> &'static str {
+        "coordination.switch_profile"
+    }
+
+    fn input_schema(&self) -> RootSchema {
+        schemars::schema_for!(SwitchProfileInput)
+    }
+
+    fn validate_input(&self, input: &Value) -> Result<(), ActionError> {
+        let parsed: SwitchProfileInput = deserialize_input(input.clone())?;
+        crate::storage::validate_profile_name(&parsed.profile).map_err(ActionError::bad_request)
+    }
+
+    async fn run(&self, ctx: &ActionContext, input: Value) -> Result<Value, ActionError> {
+        require_frontend(ctx)?;
+        let parsed: SwitchProfileInput = deserialize_input(input)?;
+        let config = ctx
+            .state
+            .storage
+            .switch_profile(&parsed.profile)
+            .map_err(|e| ActionError::internal(e.to_string()))?;
+        *ctx.state.config.write().await = config.clone();
+        serialize_output(config, "app config")
+    }
+}
+
+/// Store a keychain secret that `CliConfig.env` values reference as `${secret:NAME}`.
+/// Frontend-only: secret values never cross the HTTP API.
+struct SetSecret;
+
+#[async_trait]
+impl Action for SetSecret {
+    fn name(&self) -> &'static str {
+        "coordination.set_secret"
+    }
+
+    fn input_schema(&self) -> RootSchema {
+        schemars::schema_for!(SetSecretInput)
+    }
+
+    fn validate_input(&self, input: &Value) -> Result<(), ActionError> {
+        let parsed: SetSecretInput = deserialize_input(input.clone())?;
+        secrets::validate_secret_name(&parsed.name)
+            .map_err(|e| ActionError::bad_request(e.to_string()))
+    }
+
+    async fn run(&self, ctx: &ActionContext, input: Value) -> Result<Value, ActionError> {
+        require_frontend(ctx)?;
+        let parsed: SetSecretInput = deserialize_input(input)?;
+        KeyringSecrets::set(&parsed.name, &parsed.value)
+            .map_err(|e| ActionError::internal(e.to_string()))?;
+        Ok(Value::Null)
+    }
+}
+
+struct DeleteSecret;
+
+#[async_trait]
+impl Action for DeleteSecret {
+    fn name(&self) -> &'static str {
+        "coordination.delete_secret"
+    }
+
+    fn input_schema(&self) -> RootSchema {
+        schemars::schema_for!(SecretNameInput)
+    }
+
+    fn validate_input(&self, input: &Value) -> Result<(), ActionError> {
+        let parsed: SecretNameInput = deserialize_input(input.clone())?;
+        secrets::validate_secret_name(&parsed.name)
+            .map_err(|e| ActionError::bad_request(e.to_string()))
+    }
+
+    async fn run(&self, ctx: &ActionContext, input: Value) -> Result<Value, ActionError> {
+        require_frontend(ctx)?;
+        let parsed: SecretNameInput = deserialize_input(input)?;
+        KeyringSecrets::delete(&parsed.name).map_err(|e| ActionError::internal(e.to_string()))?;
+        Ok(Value::Null)
+    }
+}
+
+struct ListTemplates;
+
+#[async_trait]
+impl Action for ListTemplates {
+    fn name(&self) -> &'static str {
+        "coordination.list_templates"
+    }
+
+    fn input_schema(&self) -> RootSchema {
+        schemars::schema_for!(EmptyInput)
+    }
+
+    async fn run(&self, ctx: &ActionContext, _input: Value) -> Result<Value, ActionError> {
+        require_frontend(ctx)?;
+        serialize_output(template_engine(ctx).template_catalog(), "templates")
+    }
+}
+
+struct GetTemplate;
+
+#[async_trait]
+impl Action for GetTemplate {
+    fn name(&self) -> &'static str {
+        "coordination.get_template"
+    }
+
+    fn input_schema(&self) -> RootSchema {
+        schemars::schema_for!(TemplateNameInput)
+    }
+
+    fn validate_input(&self, input: &Value) -> Result<(), ActionError> {
+        let parsed: TemplateNameInput = deserialize_input(input.clone())?;
+        validate_template_name_input(&parsed.name)
+    }
+
+    async fn run(&self, ctx: &ActionContext, input: Value) -> Result<Value, ActionError> {
+        require_frontend(ctx)?;
+        let parsed: TemplateNameInput = deserialize_input(input)?;
+        let template = template_engine(ctx)
+            .load_template(&parsed.name)
+            .map_err(template_error)?;
+        serialize_output(template, "template")
+    }
+}
+
+struct SaveTemplate;
+
+#[async_trait]
+impl Action for SaveTemplate {
+    fn name(&self) -> &'static str {
+        "coordination.save_template"
+    }
+
+    fn input_schema(&self) -> RootSchema {
+        schemars::schema_for!(SaveTemplateInput)
+    }
+
+    fn validate_input(&self, input: &Value) -> Result<(), ActionError> {
+        let parsed: SaveTemplateInput = deserialize_input(input.clone())?;
+        validate_template_name_input(&parsed.name)?;
+        if parsed.content.trim().is_empty() {
+            return Err(ActionError::bad_request(
+                "template content must not be empty",
+            ));
+        }
+        Ok(())
+    }
+
+    async fn run(&self, ctx: &ActionContext, input: Value) -> Result<Value, ActionError> {
+        require_frontend(ctx)?;
+        let parsed: SaveTemplateInput = deserialize_input(input)?;
+        let engine = template_engine(ctx);
+        engine
+            .save_template(&parsed.name, &parsed.content)
+            .map_err(template_error)?;
+        let template = engine.load_template(&parsed.name).map_err(template_error)?;
+        serialize_output(template, "template")
+    }
+}
+
+struct DeleteTemplate;
+
+#[async_trait]
+impl Action for DeleteTemplate {
+    fn name(&self) -> &'static str {
+        "coordination.delete_template"
+    }
+
+    fn input_schema(&self) -> RootSchema {
+        schemars::schema_for!(TemplateNameInput)
+    }
+
+    fn validate_input(&self, input: &Value) -> Result<(), ActionError> {
+        let parsed: TemplateNameInput = deserialize_input(input.clone())?;
+        validate_template_name_input(&parsed.name)
+    }
+
+    async fn run(&self, ctx: &ActionContext, input: Value) -> Result<Value, ActionError> {
+        require_frontend(ctx)?;
+        let parsed: TemplateNameInput = deserialize_input(input)?;
+        let deleted = template_engine(ctx)
+            .delete_template(&parsed.name)
+            .map_err(template_error)?;
+        if !deleted {
+            return Err(ActionError::not_found(format!(
+                "Prompt template {} has no on-disk override",
+                parsed.name
+            )));
+        }
+        Ok(Value::Null)
+    }
+}
+
+/// The session's `plan.md`: in the project's session root, else in app storage.
+pub(super) fn session_plan_path(ctx: &ActionContext, session_id: &str) -> std::path::PathBuf {
+    let project_plan_path = {
+        let controller = ctx.state.session_controller.read();
+        controller.get_session(session_id).map(|session| {
+            session
+                .project_path
+                .join(".hive-manager")
+                .join(session_id)
+                .join("plan.md")
+        })
+    };
+
+    match project_plan_path {
+        Some(path) if path.exists() => path,
+        _ => ctx.state.storage.session_dir(session_id).join("plan.md"),
+    }
+}
+
 struct GetSessionPlan;
 
 #[async_trait]
@@ -622,32 +1354,7 @@ impl Action for GetSessionPlan {
     async fn run(&self, ctx: &ActionContext, input: Value) -> Result<Value, ActionError> {
         require_frontend(ctx)?;
         let parsed: SessionIdInput = deserialize_input(input)?;
-        let project_plan_path = {
-            let controller = ctx.state.session_controller.read();
-            controller.get_session(&parsed.session_id).map(|session| {
-                session
-                    .project_path
-                    .join(".hive-manager")
-                    .join(&parsed.session_id)
-                    .join("plan.md")
-            })
-        };
-
-        let plan_path = if let Some(ref path) = project_plan_path {
-            if path.exists() {
-                path.clone()
-            } else {
-                ctx.state
-                    .storage
-                    .session_dir(&parsed.session_id)
-                    .join("plan.md")
-            }
-        } else {
-            ctx.state
-                .storage
-                .session_dir(&parsed.session_id)
-                .join("plan.md")
-        };
+        let plan_path = session_plan_path(ctx, &parsed.session_id);
 
         if !plan_path.exists() {
             return Ok(Value::Null);
@@ -659,6 +1366,79 @@ impl Action for GetSessionPlan {
     }
 }
 
+struct GetSessionPlanStructured;
+
+#[async_trait]
+impl Action for GetSessionPlanStructured {
+    fn name(&self) -> &'static str {
+        "coordination.get_session_plan_structured"
+    }
+
+    fn input_schema(&self) -> RootSchema {
+        schemars::schema_for!(SessionIdInput)
+    }
+
+    async fn run(&self, ctx: &ActionContext, input: Value) -> Result<Value, ActionError> {
+        require_frontend(ctx)?;
+        let parsed: SessionIdInput = deserialize_input(input)?;
+        let plan_path = session_plan_path(ctx, &parsed.session_id);
+        let plan: Option<Plan> = plan_path
+            .parent()
+            .map(plan_parser::load)
+            .transpose()
+            .map_err(|e| ActionError::internal(format!("Failed to read plan.md: {}", e)))?
+            .flatten();
+        serialize_output(plan, "structured session plan")
+    }
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+struct PlanDiffInput {
+    session_id: String,
+    rev_a: u32,
+    rev_b: u32,
+}
+
+struct GetPlanDiff;
+
+#[async_trait]
+impl Action for GetPlanDiff {
+    fn name(&self) -> &'static str {
+        "coordination.get_plan_diff"
+    }
+
+    fn input_schema(&self) -> RootSchema {
+        schemars::schema_for!(PlanDiffInput)
+    }
+
+    async fn run(&self, ctx: &ActionContext, input: Value) -> Result<Value, ActionError> {
+        require_frontend(ctx)?;
+        let parsed: PlanDiffInput = deserialize_input(input)?;
+        let session_root = {
+            let controller = ctx.state.session_controller.read();
+            controller.get_session(&parsed.session_id).map(|session| {
+                session
+                    .project_path
+                    .join(".hive-manager")
+                    .join(&parsed.session_id)
+            })
+        }
+        .ok_or_else(|| {
+            ActionError::not_found(format!("Session not found: {}", parsed.session_id))
+        })?;
+
+        let diff: PlanDiff = plan_history::plan_diff(&session_root, parsed.rev_a, parsed.rev_b)
+            .map_err(|e| ActionError::internal(format!("Failed to read plan history: {}", e)))?
+            .ok_or_else(|| {
+                ActionError::not_found(format!(
+                    "Plan revision not found: {} or {}",
+                    parsed.rev_a, parsed.rev_b
+                ))
+            })?;
+        serialize_output(diff, "plan diff")
+    }
+}
+
 fn parse_plan_markdown(content: &str) -> SessionPlan {
     let mut title = String::new();
     let mut summary = String::new();
@@ -765,42 +1545,12 @@ fn parse_task_line(line: &str, counter: &mut i32) -> Option<PlanTask> {
 }
 
 fn extract_priority(text: &str) -> (String, Option<String>) {
-    let priorities = [
-        ("[HIGH]", "high"),
-        ("[P1]", "high"),
-        ("[CRITICAL]", "high"),
-        ("[MEDIUM]", "medium"),
-        ("[P2]", "medium"),
-        ("[MED]", "medium"),
-        ("[LOW]", "low"),
-        ("[P3]", "low"),
-    ];
-
-    for (marker, priority) in priorities {
-        if text
-            .split_whitespace()
-            .any(|token| token.eq_ignore_ascii_case(marker))
-        {
-            let cleaned = text
-                .split_whitespace()
-                .filter(|token| !token.eq_ignore_ascii_case(marker))
-                .collect::<Vec<_>>()
-                .join(" ");
-            return (cleaned, Some(priority.to_string()));
-        }
-    }
-
-    (text.to_string(), None)
+    let (title, priority) = plan_parser::split_priority(text);
+    (title, priority.map(|priority| priority.as_str().to_string()))
 }
 
 fn extract_assignee(text: &str) -> (String, Option<String>) {
-    for separator in ["->", "\u{2192}"] {
-        if let Some((title, assignee)) = text.split_once(separator) {
-            return (title.to_string(), Some(assignee.trim().to_string()));
-        }
-    }
-
-    (text.to_string(), None)
+    plan_parser::split_assignee(text)
 }
 
 #[cfg(test)]
@@ -832,6 +1582,12 @@ pub fn register(registry: &mut ActionRegistry) {
     registry.register(Box::new(QueenInject));
     registry.register(Box::new(QueenSwitchBranch));
     registry.register(Box::new(OperatorInject));
+    registry.register(Box::new(BroadcastToSession));
+    registry.register(Box::new(ListQuestions));
+    registry.register(Box::new(AnswerQuestion));
+    registry.register(Box::new(ListApprovals));
+    registry.register(Box::new(ApproveAction));
+    registry.register(Box::new(ListUnacknowledged));
     registry.register(Box::new(ReportWorkerStatus));
     registry.register(Box::new(AddWorker));
     registry.register(Box::new(GetCoordinationLog));
@@ -840,8 +1596,20 @@ pub fn register(registry: &mut ActionRegistry) {
     registry.register(Box::new(AssignTask));
     registry.register(Box::new(GetSessionStoragePath));
     registry.register(Box::new(GetCurrentDirectory));
+    registry.register(Box::new(GetInstanceInfo));
     registry.register(Box::new(ListStoredSessions));
+    registry.register(Box::new(ImportLegacyLearnings));
     registry.register(Box::new(GetAppConfig));
     registry.register(Box::new(UpdateAppConfig));
+    registry.register(Box::new(ListProfiles));
+    registry.register(Box::new(SwitchProfile));
+    registry.register(Box::new(SetSecret));
+    registry.register(Box::new(DeleteSecret));
+    registry.register(Box::new(ListTemplates));
+    registry.register(Box::new(GetTemplate));
+    registry.register(Box::new(SaveTemplate));
+    registry.register(Box::new(DeleteTemplate));
     registry.register(Box::new(GetSessionPlan));
+    registry.register(Box::new(GetSessionPlanStructured));
+    registry.register(Box::new(GetPlanDiff));
 }
@@ -0,0 +1,98 @@
+//! Editor actions behind the unified action registry.
+
+use async_trait::async_trait;
+use schemars::schema::RootSchema;
+use schemars::JsonSchema;
+use serde::Deserialize;
+use serde_json::{json, Value};
+use std::path::Path;
+
+use crate::storage::StorageError;
+use crate::workspace::editor;
+
+use super::error::ActionError;
+use super::registry::{Action, ActionRegistry};
+use super::ActionContext;
+
+/// Input for `editor.open_file`.
+#[derive(Debug, Deserialize, JsonSchema)]
+struct OpenFileInput {
+    /// Relative to the project of `session_id`, or absolute under that project or
+    /// one of the session's worktrees.
+    path: String,
+    /// 1-based; the first line when omitted.
+    line: Option<u32>,
+    /// 1-based; the first column when omitted.
+    column: Option<u32>,
+    session_id: String,
+}
+
+// ---------------------------------------------------------------------------
+// editor.open_file
+// ---------------------------------------------------------------------------
+
+struct OpenFile;
+
+#[async_trait]
+impl Action for OpenFile {
+    fn name(&self) -> &'static str {
+        "editor.open_file"
+    }
+
+    fn input_schema(&self) -> RootSchema {
+        schemars::schema_for!(OpenFileInput)
+    }
+
+    fn validate_input(&self, input: &Value) -> Result<(), ActionError> {
+        let parsed: OpenFileInput = deserialize_input(input.clone())?;
+        if parsed.path.trim().is_empty() {
+            return Err(ActionError::bad_request("path is required"));
+        }
+        if parsed.session_id.trim().is_empty() {
+            return Err(ActionError::bad_request("session_id is required"));
+        }
+        Ok(())
+    }
+
+    async fn run(&self, ctx: &ActionContext, input: Value) -> Result<Value, ActionError> {
+        let parsed: OpenFileInput = deserialize_input(input)?;
+        let roots = ctx
+            .state
+            .session_controller
+            .read()
+            .session_file_roots(&parsed.session_id)
+            .ok_or_else(|| {
+                ActionError::not_found(format!("Session not found: {}", parsed.session_id))
+            })?;
+        let file = editor::resolve_file(&roots, Path::new(parsed.path.trim()))
+            .map_err(|e| match e {
+                StorageError::Io(e) if e.kind() == std::io::ErrorKind::NotFound => {
+                    ActionError::not_found(format!("File not found: {}", parsed.path))
+                }
+                StorageError::InvalidPath(message) => ActionError::bad_request(message),
+                other => ActionError::internal(other.to_string()),
+            })?;
+
+        let config = ctx.state.config.read().await.editor.clone();
+        let line = parsed.line.unwrap_or(1);
+        let column = parsed.column.unwrap_or(1);
+        editor::open_in_editor(&config, &file, line, column).map_err(ActionError::internal)?;
+
+        Ok(json!({
+            "path": file.to_string_lossy(),
+            "line": line,
+            "column": column,
+            "editor": config.command,
+        }))
+    }
+}
+
+fn deserialize_input<T: for<'de> Deserialize<'de>>(input: Value) -> Result<T, ActionError> {
+    serde_json::from_value(input)
+        .map_err(|e| ActionError::bad_request(format!("Invalid input: {}", e)))
+}
+
+/// Register every editor action into the registry.
+pub fn register(registry: &mut ActionRegistry) {
+    registry.register(Box::new(OpenFile));
+}
@@ -3,16 +3,18 @@
 //! [`ActionError`] is the single error currency for every [`Action`](crate::actions::Action).
 //! It deliberately round-trips losslessly to BOTH of the surfaces that dispatch actions:
 //!
-//! - the Tauri `#[command]` layer, which speaks `Result<T, String>` — via [`ActionError::to_message`];
+//! - the Tauri `#[command]` layer, which speaks `Result<T, HiveError>` — via `impl From<ActionError> for HiveError`;
 //! - the Axum HTTP layer, which speaks [`ApiError`] — via `impl From<ActionError> for ApiError`.
 //!
 //! `ActionStatus` mirrors the categories `ApiError` needs so the conflict-with-details
-//! path used by completion flows survives the conversion.
+//! path used by completion flows survives the conversion. The finer [`ErrorCode`]
+//! defaults from the status and survives both conversions.
 
 use std::collections::HashMap;
 
 use serde_json::Value;
 
+use crate::error::{ErrorCode, HiveError};
 use crate::http::error::ApiError;
 
 /// Coarse category for an [`ActionError`], chosen to map cleanly onto both
@@ -34,16 +36,30 @@ pub enum ActionStatus {
 pub struct ActionError {
     pub status: ActionStatus,
     pub message: String,
+    /// Defaults to the code for `status`; see [`ActionError::with_code`].
+    pub code: ErrorCode,
     /// Optional structured details, preserved across the `ApiError` boundary
     /// (e.g. the 409 completion-blocked payload).
     pub details: Option<HashMap<String, Value>>,
 }
 
+impl ActionStatus {
+    fn default_code(self) -> ErrorCode {
+        match self {
+            Self::BadRequest => ErrorCode::ValidationFailed,
+            Self::NotFound => ErrorCode::NotFound,
+            Self::Conflict => ErrorCode::Conflict,
+            Self::Internal => ErrorCode::Internal,
+        }
+    }
+}
+
 impl ActionError {
     pub fn new(status: ActionStatus, message: impl Into<String>) -> Self {
         Self {
             status,
             message: message.into(),
+            code: status.default_code(),
             details: None,
         }
     }
@@ -67,23 +83,21 @@ impl ActionError {
 
     /// Build a conflict error carrying structured details (mirrors
     /// [`ApiError::conflict_with_details`]).
-    #[allow(dead_code)]
     pub fn conflict_with_details(
         message: impl Into<String>,
         details: HashMap<String, Value>,
     ) -> Self {
         Self {
-            status: ActionStatus::Conflict,
-            message: message.into(),
             details: Some(details),
+            ..Self::new(ActionStatus::Conflict, message)
         }
     }
 
-    /// Render for the Tauri side, which returns `Result<T, String>` to the
-    /// frontend. The plain message preserves the exact text the old
-    /// `#[command]` bodies returned.
-    pub fn to_message(&self) -> String {
-        self.message.clone()
+    /// Report a more specific code than the status implies (e.g. `GitFailed` for
+    /// an internal error).
+    pub fn with_code(mut self, code: ErrorCode) -> Self {
+        self.code = code;
+        self
     }
 }
 
@@ -124,6 +138,7 @@ impl From<ApiError> for ActionError {
         ActionError {
             status,
             message: error.message,
+            code: error.code,
             details: error.details,
         }
     }
@@ -134,7 +149,8 @@ impl From<ApiError> for ActionError {
 /// constructors, including the structured conflict path.
 impl From<ActionError> for ApiError {
     fn from(error: ActionError) -> Self {
-        match (error.status, error.details) {
+        let code = error.code;
+        let api_error = match (error.status, error.details) {
             (ActionStatus::BadRequest, _) => ApiError::bad_request(error.message),
             (ActionStatus::NotFound, _) => ApiError::not_found(error.message),
             (ActionStatus::Conflict, Some(details)) => {
@@ -144,6 +160,49 @@ impl From<ActionError> for ApiError {
                 ApiError::new(axum::http::StatusCode::CONFLICT, error.message)
             }
             (ActionStatus::Internal, _) => ApiError::internal(error.message),
+        };
+        api_error.with_code(code)
+    }
+}
+
+/// What the Tauri commands return to the frontend: the message the old
+/// `Result<T, String>` commands returned, plus the code and any details.
+impl From<ActionError> for HiveError {
+    fn from(error: ActionError) -> Self {
+        HiveError {
+            context: error.details.unwrap_or_default().into_iter().collect(),
+            ..HiveError::new(error.code, error.message)
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_code_survives_both_surfaces() {
+        let details = HashMap::from([("preflight".to_string(), Value::Bool(true))]);
+        let error = ActionError::conflict_with_details("Launch blocked", details)
+            .with_code(ErrorCode::CliMissing);
+
+        let hive = HiveError::from(error.clone());
+        assert_eq!(hive.code, ErrorCode::CliMissing);
+        assert_eq!(hive.message, "Launch blocked");
+        assert_eq!(hive.context["preflight"], Value::Bool(true));
+
+        let api = ApiError::from(error);
+        assert_eq!(api.status, axum::http::StatusCode::CONFLICT);
+        assert_eq!(api.code, ErrorCode::CliMissing);
+        assert_eq!(
+            ActionError::from(api).code,
+            ErrorCode::CliMissing,
+            "ApiError -> ActionError keeps the code"
+        );
+
+        assert_eq!(
+            HiveError::from(ActionError::bad_request("bad")).code,
+            ErrorCode::ValidationFailed
+        );
+    }
+}
@@ -15,6 +15,8 @@ use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 
+use crate::error::ErrorCode;
+
 use super::error::ActionError;
 use super::registry::{Action, ActionRegistry};
 use super::ActionContext;
@@ -196,12 +198,12 @@ fn deserialize_input<T: for<'de> Deserialize<'de>>(input: Value) -> Result<T, Ac
 }
 
 /// Map the string error from `run_git_in_dir`/parsers into an `ActionError`.
-/// A non-existent project path is a bad request; everything else is internal.
+/// A non-existent project path is a bad request; everything else is a git failure.
 fn git_err(message: String) -> ActionError {
     if message.starts_with("Project path does not exist") {
         ActionError::bad_request(message)
     } else {
-        ActionError::internal(message)
+        ActionError::internal(message).with_code(ErrorCode::GitFailed)
     }
 }
 
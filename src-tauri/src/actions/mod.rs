@@ -18,6 +18,7 @@
 
 pub mod context;
 pub mod coordination;
+pub mod editor;
 pub mod error;
 pub mod git;
 pub mod pty;
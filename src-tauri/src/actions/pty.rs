@@ -6,7 +6,9 @@ use schemars::JsonSchema;
 use serde::Deserialize;
 use serde_json::Value;
 
-use crate::pty::AgentRole;
+use crate::pty::shell::{self, ShellCommand};
+use crate::error::ErrorCode;
+use crate::pty::{AgentRole, PtyError, SessionRecordings};
 
 use super::error::ActionError;
 use super::registry::{Action, ActionRegistry};
@@ -25,20 +27,32 @@ enum CreatePtyRole {
 enum ScratchShell {
     Powershell,
     Cmd,
+    /// The user's own shell as a login shell (`$SHELL -l`, `cmd.exe` on Windows).
+    /// The frontend cannot know which that is, so it sends an empty command.
+    Login,
 }
 
 impl ScratchShell {
-    fn command(self) -> &'static str {
+    fn command_line(self) -> ShellCommand {
+        let fixed = |program: &str, args: &[&str]| ShellCommand {
+            program: program.to_string(),
+            args: args.iter().map(|arg| arg.to_string()).collect(),
+        };
         match self {
-            Self::Powershell => "powershell.exe",
-            Self::Cmd => "cmd.exe",
+            Self::Powershell => fixed("powershell.exe", &["-NoLogo"]),
+            Self::Cmd => fixed("cmd.exe", &[]),
+            Self::Login => shell::login_shell(),
         }
     }
 
-    fn args(self) -> &'static [&'static str] {
+    /// Whether `command args` is what the frontend sends for this shell.
+    fn matches(self, command: &str, args: &[String]) -> bool {
         match self {
-            Self::Powershell => &["-NoLogo"],
-            Self::Cmd => &[],
+            Self::Login => command.is_empty() && args.is_empty(),
+            _ => {
+                let expected = self.command_line();
+                command.eq_ignore_ascii_case(&expected.program) && args == expected.args
+            }
         }
     }
 }
@@ -84,6 +98,17 @@ struct PtyIdInput {
 #[derive(Debug, Deserialize, JsonSchema)]
 struct EmptyInput {}
 
+#[derive(Debug, Deserialize, JsonSchema)]
+struct SetRecordingInput {
+    session_id: String,
+    enabled: bool,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+struct SessionIdInput {
+    session_id: String,
+}
+
 fn deserialize_input<T: for<'de> Deserialize<'de>>(input: Value) -> Result<T, ActionError> {
     serde_json::from_value(input)
         .map_err(|e| ActionError::bad_request(format!("Invalid input: {}", e)))
@@ -100,6 +125,17 @@ fn check_data_size(data_len: usize) -> Result<(), ActionError> {
     Ok(())
 }
 
+/// A missing PTY is a not-found; failing to open or start one is a spawn failure.
+fn pty_err(error: PtyError) -> ActionError {
+    match error {
+        PtyError::NotFound(_) => ActionError::not_found(error.to_string()),
+        PtyError::CreateError(_) | PtyError::SpawnError(_) => {
+            ActionError::internal(error.to_string()).with_code(ErrorCode::SpawnFailed)
+        }
+        PtyError::IoError(_) => ActionError::internal(error.to_string()),
+    }
+}
+
 fn require_frontend(ctx: &ActionContext) -> Result<(), ActionError> {
     if matches!(ctx.caller, Caller::Frontend) {
         Ok(())
@@ -148,17 +184,17 @@ fn resolve_create_role(
                     "scratch PTY id must use the namespace {id_prefix}<unique-id-without-colons>"
                 )));
             }
-            if !input.command.eq_ignore_ascii_case(shell.command())
-                || !input
-                    .args
-                    .iter()
-                    .map(String::as_str)
-                    .eq(shell.args().iter().copied())
-            {
+            if !shell.matches(&input.command, &input.args) {
+                let expected = match shell {
+                    ScratchShell::Login => ShellCommand {
+                        program: String::new(),
+                        args: Vec::new(),
+                    },
+                    _ => shell.command_line(),
+                };
                 return Err(ActionError::bad_request(format!(
-                    "scratch shell metadata does not match {} {:?}",
-                    shell.command(),
-                    shell.args()
+                    "scratch shell metadata does not match {:?} {:?}",
+                    expected.program, expected.args
                 )));
             }
 
@@ -210,13 +246,20 @@ impl Action for CreatePty {
             .transpose()
             .map_err(ActionError::bad_request)?;
 
-        let args_refs: Vec<&str> = parsed.args.iter().map(String::as_str).collect();
+        let command_line = match parsed.shell {
+            Some(shell) if scratch_session_id.is_some() => shell.command_line(),
+            _ => ShellCommand {
+                program: parsed.command.clone(),
+                args: parsed.args.clone(),
+            },
+        };
+        let args_refs: Vec<&str> = command_line.args.iter().map(String::as_str).collect();
         let create_result = {
             let pty_manager = ctx.state.pty_manager.read();
             pty_manager.create_session(
                 parsed.id.clone(),
                 role,
-                &parsed.command,
+                &command_line.program,
                 &args_refs,
                 parsed.cwd.as_deref(),
                 parsed.cols,
@@ -228,7 +271,7 @@ impl Action for CreatePty {
             if scratch_creation_guard.is_some() {
                 session_controller.unregister_scratch_pty(&parsed.id);
             }
-            return Err(ActionError::internal(error.to_string()));
+            return Err(pty_err(error));
         }
 
         // Drop the barrier only after both the process and its ownership record exist.
@@ -260,7 +303,7 @@ impl Action for WritePty {
         let pty_manager = ctx.state.pty_manager.read();
         pty_manager
             .write(&parsed.id, parsed.data.as_bytes())
-            .map_err(|e| ActionError::internal(e.to_string()))?;
+            .map_err(pty_err)?;
         Ok(Value::Null)
     }
 }
@@ -288,7 +331,7 @@ impl Action for PastePty {
         let pty_manager = ctx.state.pty_manager.read();
         pty_manager
             .write_bracketed(&parsed.id, parsed.data.as_bytes())
-            .map_err(|e| ActionError::internal(e.to_string()))?;
+            .map_err(pty_err)?;
         Ok(Value::Null)
     }
 }
@@ -323,14 +366,14 @@ impl Action for InjectPty {
         );
 
         if parsed.send_enter {
-            let message_with_enter = format!("{}\r", parsed.message);
+            // The submit key goes after the paste; inside it, it would only add a newline.
             pty_manager
-                .write_bracketed(&parsed.id, message_with_enter.as_bytes())
-                .map_err(|e| ActionError::internal(e.to_string()))?;
+                .submit(&parsed.id, &parsed.message)
+                .map_err(pty_err)?;
         } else {
             pty_manager
                 .write_bracketed(&parsed.id, parsed.message.as_bytes())
-                .map_err(|e| ActionError::internal(e.to_string()))?;
+                .map_err(pty_err)?;
         }
 
         Ok(Value::Null)
@@ -355,7 +398,7 @@ impl Action for ResizePty {
         let pty_manager = ctx.state.pty_manager.read();
         pty_manager
             .resize(&parsed.id, parsed.cols, parsed.rows)
-            .map_err(|e| ActionError::internal(e.to_string()))?;
+            .map_err(pty_err)?;
         Ok(Value::Null)
     }
 }
@@ -382,7 +425,7 @@ impl Action for KillPty {
             .pty_manager
             .read()
             .kill(&parsed.id)
-            .map_err(|e| ActionError::internal(e.to_string()))?;
+            .map_err(pty_err)?;
         session_controller.unregister_scratch_pty(&parsed.id);
         Ok(Value::Null)
     }
@@ -429,6 +472,64 @@ impl Action for ListPtys {
     }
 }
 
+struct SetRecording;
+
+#[async_trait]
+impl Action for SetRecording {
+    fn name(&self) -> &'static str {
+        "pty.set_recording"
+    }
+
+    fn input_schema(&self) -> RootSchema {
+        schemars::schema_for!(SetRecordingInput)
+    }
+
+    async fn run(&self, ctx: &ActionContext, input: Value) -> Result<Value, ActionError> {
+        require_frontend(ctx)?;
+        let parsed: SetRecordingInput = deserialize_input(input)?;
+        ctx.state
+            .session_controller
+            .read()
+            .get_session(&parsed.session_id)
+            .ok_or_else(|| {
+                ActionError::not_found(format!("Session {} not found", parsed.session_id))
+            })?;
+        ctx.state
+            .pty_manager
+            .read()
+            .set_recording(&parsed.session_id, parsed.enabled)
+            .map_err(|e| ActionError::internal(e.to_string()))?;
+        Ok(Value::Null)
+    }
+}
+
+struct ListRecordings;
+
+#[async_trait]
+impl Action for ListRecordings {
+    fn name(&self) -> &'static str {
+        "pty.list_recordings"
+    }
+
+    fn input_schema(&self) -> RootSchema {
+        schemars::schema_for!(SessionIdInput)
+    }
+
+    async fn run(&self, ctx: &ActionContext, input: Value) -> Result<Value, ActionError> {
+        require_frontend(ctx)?;
+        let parsed: SessionIdInput = deserialize_input(input)?;
+        let pty_manager = ctx.state.pty_manager.read();
+        let recordings = pty_manager
+            .list_recordings(&parsed.session_id)
+            .map_err(pty_err)?;
+        serde_json::to_value(SessionRecordings {
+            enabled: pty_manager.is_recording(&parsed.session_id),
+            recordings,
+        })
+        .map_err(|e| ActionError::internal(format!("Failed to serialize recordings: {}", e)))
+    }
+}
+
 pub fn register(registry: &mut ActionRegistry) {
     registry.register(Box::new(CreatePty));
     registry.register(Box::new(WritePty));
@@ -438,4 +539,6 @@ pub fn register(registry: &mut ActionRegistry) {
     registry.register(Box::new(KillPty));
     registry.register(Box::new(PtyStatus));
     registry.register(Box::new(ListPtys));
+    registry.register(Box::new(SetRecording));
+    registry.register(Box::new(ListRecordings));
 }
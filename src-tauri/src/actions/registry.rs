@@ -14,6 +14,8 @@ use async_trait::async_trait;
 use schemars::schema::RootSchema;
 use serde_json::Value;
 
+use crate::session::slug;
+
 use super::context::ActionContext;
 use super::error::ActionError;
 
@@ -106,11 +108,41 @@ impl ActionRegistry {
             .get(name)
             .ok_or_else(|| ActionError::not_found(format!("Unknown action '{}'", name)))?;
 
+        let input = resolve_session_slugs(name, ctx, input)?;
         action.validate_input(&input)?;
         action.run(ctx, input).await
     }
 }
 
+/// Replace a session slug in the input's `session_id`, or in `id` for `session.*`
+/// actions, with the session's ID, so every action accepts either.
+fn resolve_session_slugs(
+    name: &str,
+    ctx: &ActionContext,
+    mut input: Value,
+) -> Result<Value, ActionError> {
+    let keys: &[&str] = if name.starts_with("session.") {
+        &["id", "session_id"]
+    } else {
+        &["session_id"]
+    };
+    if let Some(fields) = input.as_object_mut() {
+        for key in keys {
+            if let Some(Value::String(reference)) = fields.get_mut(*key) {
+                if slug::is_slug(reference) {
+                    *reference = ctx
+                        .state
+                        .session_controller
+                        .read()
+                        .resolve_session_ref(reference)
+                        .map_err(ActionError::conflict)?;
+                }
+            }
+        }
+    }
+    Ok(input)
+}
+
 /// The single registration point for all actions. Both the runtime (`lib.rs`)
 /// and the tests build the registry through this function so the action set is
 /// defined in exactly one place.
@@ -120,5 +152,6 @@ pub fn build_registry() -> ActionRegistry {
     super::git::register(&mut registry);
     super::pty::register(&mut registry);
     super::coordination::register(&mut registry);
+    super::editor::register(&mut registry);
     registry
 }
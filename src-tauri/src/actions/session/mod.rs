@@ -8,19 +8,33 @@ use schemars::schema::RootSchema;
 use schemars::JsonSchema;
 use serde::{Deserialize, Deserializer, Serialize};
 use serde_json::{json, Value};
-use std::path::PathBuf;
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
 
+use crate::cli::health::CliHealthRegistry;
+use crate::coordination::{CoordinationMessage, MessageDelivery};
 use crate::domain::{HiveLaunchKind, WorkspaceStrategy};
+use crate::error::ErrorCode;
 use crate::http::handlers::{validate_cli, validate_project_path};
+use crate::http::state::AppState;
+use crate::pty::AgentConfig;
+use crate::session::fusion_suggest;
+use crate::session::plan_parser::{self, Plan};
+use crate::session::slug;
 use crate::session::{
-    DebateLaunchConfig, FusionLaunchConfig, HiveLaunchConfig, ResearchLaunchConfig, Session,
-    SessionState, SessionType, SwarmLaunchConfig,
+    AgentHeartbeatInfo, DebateLaunchConfig, DirtyTreeAction, FusionLaunchConfig, FusionSpawnPlan,
+    HiveLaunchConfig, PhaseTimeouts, QueuedLaunchConfig, ResearchLaunchConfig, Session,
+    SessionController, SessionState, SessionType, SpawnQuota, SwarmLaunchConfig,
 };
-use crate::storage::{PersistedSession, SessionTypeInfo};
+use crate::storage::{PersistedSession, PreflightMode, SessionTypeInfo};
+use crate::workspace::git::{stash_changes, uncommitted_changes};
+use crate::workspace::github_issues::PlanIssueMode;
+use crate::workspace::project_artifacts;
 
 use super::error::ActionError;
 use super::registry::{Action, ActionRegistry};
-use super::ActionContext;
+use super::{ActionContext, Caller};
 
 const SESSION_COLOR_ALLOWLIST: &[&str] = &[
     "#7aa2f7", "#bb9af7", "#9ece6a", "#e0af68", "#7dcfff", "#f7768e", "#ff9e64", "#f7b1d1",
@@ -84,11 +98,29 @@ pub(crate) fn is_valid_hex_session_color(color: &str) -> bool {
         && color.chars().skip(1).all(|c| c.is_ascii_hexdigit())
 }
 
+fn validate_phase_timeouts(timeouts: &PhaseTimeouts) -> Result<(), ActionError> {
+    let budgets = [
+        ("planning_secs", timeouts.planning_secs),
+        ("worker_secs", timeouts.worker_secs),
+        ("judging_secs", timeouts.judging_secs),
+    ];
+    for (field, secs) in budgets {
+        if secs == Some(0) {
+            return Err(ActionError::bad_request(format!(
+                "phase_timeouts.{} must be greater than zero",
+                field
+            )));
+        }
+    }
+    Ok(())
+}
+
 /// Validate a `HiveLaunchConfig` (consolidated from session_commands.rs).
 pub(crate) fn validate_hive_launch_config(config: &HiveLaunchConfig) -> Result<(), ActionError> {
     validate_project_path(&config.project_path)?;
     validate_session_name(config.name.as_deref())?;
     validate_session_color(config.color.as_deref())?;
+    validate_phase_timeouts(&config.phase_timeouts)?;
     validate_cli(&config.queen_config.cli)?;
 
     if config.execution_policy.workspace_strategy == WorkspaceStrategy::None {
@@ -126,6 +158,17 @@ pub(crate) fn validate_hive_launch_config(config: &HiveLaunchConfig) -> Result<(
         }
     }
 
+    if let Some(review) = &config.review_stage {
+        if review.chain.iter().all(|role| role.trim().is_empty()) {
+            return Err(ActionError::bad_request(
+                "review_stage.chain must name at least one role",
+            ));
+        }
+        if let Some(agent_config) = &review.agent_config {
+            validate_cli(&agent_config.cli)?;
+        }
+    }
+
     Ok(())
 }
 
@@ -133,6 +176,7 @@ fn validate_debate_launch_config(config: &DebateLaunchConfig) -> Result<(), Acti
     validate_project_path(&config.project_path)?;
     validate_session_name(config.name.as_deref())?;
     validate_session_color(config.color.as_deref())?;
+    validate_phase_timeouts(&config.phase_timeouts)?;
     if config.topic.trim().is_empty() {
         return Err(ActionError::bad_request(
             "Debate launch requires a non-empty topic",
@@ -183,6 +227,7 @@ fn validate_swarm_launch_config(config: &SwarmLaunchConfig) -> Result<(), Action
     validate_project_path(&config.project_path)?;
     validate_session_name(config.name.as_deref())?;
     validate_session_color(config.color.as_deref())?;
+    validate_phase_timeouts(&config.phase_timeouts)?;
     validate_cli(&config.default_cli)?;
     validate_cli(&config.queen_config.cli)?;
     validate_cli(&config.planner_config.cli)?;
@@ -227,6 +272,7 @@ fn validate_fusion_launch_config(config: &FusionLaunchConfig) -> Result<(), Acti
     validate_project_path(&config.project_path)?;
     validate_session_name(config.name.as_deref())?;
     validate_session_color(config.color.as_deref())?;
+    validate_phase_timeouts(&config.phase_timeouts)?;
     validate_cli(&config.default_cli)?;
     validate_cli(&config.judge_config.cli)?;
 
@@ -285,9 +331,118 @@ struct UpdateMetadataInput {
     color: Option<Option<String>>,
 }
 
+/// Input for `session.queue_solo_task`.
+#[derive(Debug, Deserialize, JsonSchema)]
+struct QueueSoloTaskInput {
+    id: String,
+    /// Follow-up prompt handed to the Solo agent once it goes idle.
+    prompt: String,
+}
+
+/// Input for `session.promote_session`.
+#[derive(Debug, Deserialize, JsonSchema)]
+struct PromoteSessionInput {
+    id: String,
+    /// Workers spawned next to the former Solo agent, which becomes Worker 1.
+    #[serde(default)]
+    workers: Vec<AgentConfig>,
+}
+
+/// Input for `session.scale_workers`.
+#[derive(Debug, Deserialize, JsonSchema)]
+struct ScaleWorkersInput {
+    id: String,
+    /// Live workers the session should end up with.
+    target_count: u8,
+}
+
+/// Input for `session.link_plan_issues`.
+#[derive(Debug, Deserialize, JsonSchema)]
+struct LinkPlanIssuesInput {
+    id: String,
+    /// Issue the plan tasks hang off: a number, `#42` or an issue URL.
+    parent_issue: String,
+    /// `sub_issues` (the default) or `checklist`.
+    #[serde(default)]
+    mode: PlanIssueMode,
+}
+
+/// Input for `session.override_spawn_quota`.
+#[derive(Debug, Deserialize, JsonSchema)]
+struct OverrideSpawnQuotaInput {
+    id: String,
+    /// The new quota; an empty one lifts it.
+    #[serde(flatten)]
+    quota: SpawnQuota,
+}
+
+/// Input for `session.run_fusion_judges`.
+#[derive(Debug, Deserialize, JsonSchema)]
+struct RunFusionJudgesInput {
+    id: String,
+    /// One config per additional judge; empty re-runs the original judge config.
+    #[serde(default)]
+    judges: Vec<AgentConfig>,
+}
+
+/// Input for `session.list_checkpoints`.
+#[derive(Debug, Deserialize, JsonSchema)]
+struct ListCheckpointsInput {
+    id: String,
+}
+
+/// Input for `session.get_durations`.
+#[derive(Debug, Deserialize, JsonSchema)]
+struct GetSessionDurationsInput {
+    id: String,
+}
+
+/// Input for `session.compare`.
+#[derive(Debug, Deserialize, JsonSchema)]
+struct CompareSessionsInput {
+    a: String,
+    b: String,
+}
+
+/// Input for `session.suggest_fusion_variants`.
+#[derive(Debug, Deserialize, JsonSchema)]
+struct SuggestFusionVariantsInput {
+    task_description: String,
+}
+
+/// Input for `session.preview_agent_command`.
+#[derive(Debug, Deserialize, JsonSchema)]
+struct PreviewAgentCommandInput {
+    config: AgentConfig,
+    #[serde(default)]
+    cwd: Option<String>,
+    /// Prompt file the launch would pass; its read instruction ends the arguments.
+    #[serde(default)]
+    prompt_path: Option<String>,
+}
+
+/// Input for `session.cleanup_project_artifacts`.
+#[derive(Debug, Deserialize, JsonSchema)]
+struct CleanupProjectArtifactsInput {
+    project_path: String,
+    /// Only list what would be removed. Defaults to `true`.
+    dry_run: Option<bool>,
+    /// Also remove directories that no known session owns.
+    #[serde(default)]
+    include_unknown: bool,
+}
+
+/// Input for `session.ensure_project_gitignore`.
+#[derive(Debug, Deserialize, JsonSchema)]
+struct EnsureProjectGitignoreInput {
+    project_path: String,
+}
+
 #[derive(Debug, Serialize, Deserialize, JsonSchema)]
 struct SessionInfoOutput {
     id: String,
+    /// Short name accepted wherever the ID is (see `session::slug`).
+    slug: String,
     name: Option<String>,
     color: Option<String>,
     session_type: String,
@@ -313,6 +468,7 @@ where
 
 fn session_info_from_session(session: Session) -> SessionInfoOutput {
     SessionInfoOutput {
+        slug: slug::slug_for(&session.id),
         id: session.id,
         name: session.name,
         color: session.color,
@@ -332,6 +488,7 @@ fn session_info_from_session(session: Session) -> SessionInfoOutput {
 
 fn session_info_from_persisted(persisted: PersistedSession) -> SessionInfoOutput {
     SessionInfoOutput {
+        slug: slug::slug_for(&persisted.id),
         id: persisted.id,
         name: persisted.name,
         color: persisted.color,
@@ -472,7 +629,7 @@ impl Action for GetSessionInfo {
 
         let persisted = ctx
             .state
-            .storage
+            .backend
             .load_session(&parsed.id)
             .map_err(|_| ActionError::not_found(format!("Session {} not found", parsed.id)))?;
         serde_json::to_value(session_info_from_persisted(persisted))
@@ -550,6 +707,115 @@ impl Action for CloseSession {
     }
 }
 
+// ---------------------------------------------------------------------------
+// Launch queue
+// ---------------------------------------------------------------------------
+
+/// Check the launch's CLIs, then queue the launch instead when it would exceed
+/// the concurrency limits in `AppConfig`. Returns the `Queued` placeholder session
+/// to answer with.
+async fn admit_launch(
+    ctx: &ActionContext,
+    launch: QueuedLaunchConfig,
+) -> Result<Option<Value>, ActionError> {
+    let (limits, preflight) = {
+        let config = ctx.state.config.read().await;
+        (config.concurrency.clone(), config.preflight)
+    };
+    preflight_launch(&launch, preflight).await?;
+
+    let controller = ctx.state.session_controller.read();
+    if controller.can_launch_now(launch.agent_count(), &limits) {
+        return Ok(None);
+    }
+    let session = controller.enqueue_launch(launch);
+    serde_json::to_value(session)
+        .map(Some)
+        .map_err(|e| ActionError::internal(format!("Failed to serialize session: {}", e)))
+}
+
+/// Refuse a launch whose CLIs are missing or logged out when preflight blocks;
+/// otherwise only log what the checks found.
+async fn preflight_launch(
+    launch: &QueuedLaunchConfig,
+    mode: PreflightMode,
+) -> Result<(), ActionError> {
+    if mode == PreflightMode::Off {
+        return Ok(());
+    }
+    let report = CliHealthRegistry::preflight(&launch.clis()).await;
+    for warning in &report.warnings {
+        tracing::warn!("Launch preflight: {}", warning);
+    }
+    if !report.is_blocked() {
+        return Ok(());
+    }
+    let summary = report.blockers.join("; ");
+    if mode == PreflightMode::Warn {
+        tracing::warn!("Launching despite preflight problems: {}", summary);
+        return Ok(());
+    }
+    let details = HashMap::from([(
+        "preflight".to_string(),
+        serde_json::to_value(&report).unwrap_or(Value::Null),
+    )]);
+    Err(ActionError::conflict_with_details(
+        format!("Launch blocked by preflight checks: {}", summary),
+        details,
+    )
+    .with_code(ErrorCode::CliMissing))
+}
+
+/// Start queued launches that now fit under the concurrency limits, oldest
+/// first. Returns how many started.
+pub async fn start_queued_launches(state: &AppState) -> usize {
+    let limits = state.config.read().await.concurrency.clone();
+    let mut started = 0;
+    loop {
+        let Some(launch) = state
+            .session_controller
+            .read()
+            .next_startable_launch(&limits)
+        else {
+            break;
+        };
+        // Failures are logged and left on the placeholder session by the controller.
+        let result = state.session_controller.read().start_queued_launch(launch);
+        if let Ok((_, spawn_plan)) = result {
+            started += 1;
+            if let Some(plan) = spawn_plan {
+                spawn_fusion_variants_in_background(Arc::clone(&state.session_controller), plan);
+            }
+        }
+    }
+    started
+}
+
+/// Bring Fusion variants up in the background so the launch returns immediately;
+/// per-variant progress streams on `fusion-variant-progress`. Git runs async
+/// without the controller lock; only the PTY spawns hold it.
+pub(crate) fn spawn_fusion_variants_in_background(
+    controller: Arc<parking_lot::RwLock<SessionController>>,
+    mut plan: FusionSpawnPlan,
+) {
+    tokio::spawn(async move {
+        SessionController::prepare_fusion_worktrees(&controller, &mut plan).await;
+        let session_id = plan.session_id.clone();
+        let spawned =
+            tokio::task::spawn_blocking(move || controller.read().spawn_fusion_variants(&plan))
+                .await
+                .map_err(|e| e.to_string())
+                .and_then(|result| result.map(|_| ()));
+        if let Err(e) = spawned {
+            tracing::error!(
+                "Failed to spawn Fusion variants for session {}: {}",
+                session_id,
+                e
+            );
+        }
+    });
+}
+
 // ---------------------------------------------------------------------------
 // session.launch_hive
 // ---------------------------------------------------------------------------
@@ -579,6 +845,17 @@ impl Action for LaunchHive {
     async fn run(&self, ctx: &ActionContext, input: Value) -> Result<Value, ActionError> {
         let parsed: LegacyHiveLaunchInput = deserialize_input(input)?;
         let command = parsed.command.unwrap_or_else(|| "claude".to_string());
+        let queued = QueuedLaunchConfig::LegacyHive {
+            project_path: PathBuf::from(&parsed.project_path),
+            worker_count: parsed.worker_count.unwrap_or(3),
+            command: command.clone(),
+            prompt: parsed.task_description.clone(),
+            name: parsed.name.clone(),
+            color: parsed.color.clone(),
+        };
+        if let Some(placeholder) = admit_launch(ctx, queued).await? {
+            return Ok(placeholder);
+        }
         let session = {
             let controller = ctx.state.session_controller.read();
             controller
@@ -620,6 +897,10 @@ impl Action for LaunchHiveV2 {
 
     async fn run(&self, ctx: &ActionContext, input: Value) -> Result<Value, ActionError> {
         let config: HiveLaunchConfig = deserialize_input(input)?;
+        let queued = QueuedLaunchConfig::Hive(config.clone());
+        if let Some(placeholder) = admit_launch(ctx, queued).await? {
+            return Ok(placeholder);
+        }
         let session = {
             let controller = ctx.state.session_controller.read();
             controller
@@ -654,6 +935,10 @@ impl Action for LaunchResearch {
 
     async fn run(&self, ctx: &ActionContext, input: Value) -> Result<Value, ActionError> {
         let config: ResearchLaunchConfig = deserialize_input(input)?;
+        let queued = QueuedLaunchConfig::Research(config.clone());
+        if let Some(placeholder) = admit_launch(ctx, queued).await? {
+            return Ok(placeholder);
+        }
         let session = {
             let controller = ctx.state.session_controller.read();
             controller
@@ -688,6 +973,10 @@ impl Action for LaunchSwarm {
 
     async fn run(&self, ctx: &ActionContext, input: Value) -> Result<Value, ActionError> {
         let config: SwarmLaunchConfig = deserialize_input(input)?;
+        let queued = QueuedLaunchConfig::Swarm(config.clone());
+        if let Some(placeholder) = admit_launch(ctx, queued).await? {
+            return Ok(placeholder);
+        }
         let session = {
             let controller = ctx.state.session_controller.read();
             controller.launch_swarm(config).map_err(ActionError::from)?
@@ -720,6 +1009,10 @@ impl Action for LaunchSolo {
 
     async fn run(&self, ctx: &ActionContext, input: Value) -> Result<Value, ActionError> {
         let config: HiveLaunchConfig = deserialize_input(input)?;
+        let queued = QueuedLaunchConfig::Solo(config.clone());
+        if let Some(placeholder) = admit_launch(ctx, queued).await? {
+            return Ok(placeholder);
+        }
         let session = {
             let controller = ctx.state.session_controller.read();
             controller.launch_solo(config).map_err(ActionError::from)?
@@ -752,17 +1045,79 @@ impl Action for LaunchFusion {
 
     async fn run(&self, ctx: &ActionContext, input: Value) -> Result<Value, ActionError> {
         let config: FusionLaunchConfig = deserialize_input(input)?;
-        let session = {
+        let stash_commit = guard_dirty_tree(&config).await?;
+        let queued = QueuedLaunchConfig::Fusion(config.clone());
+        if let Some(placeholder) = admit_launch(ctx, queued).await? {
+            if let Some(stash_commit) = &stash_commit {
+                tracing::info!(
+                    "Stashed uncommitted changes before queued Fusion launch as {}",
+                    stash_commit
+                );
+            }
+            return Ok(placeholder);
+        }
+        let (session, spawn_plan) = {
             let controller = ctx.state.session_controller.read();
-            controller
+            let launched = controller
                 .launch_fusion(config)
-                .map_err(ActionError::from)?
+                .map_err(ActionError::from)?;
+            if let Some(stash_commit) = &stash_commit {
+                controller.record_fusion_stash(&launched.0.id, stash_commit);
+            }
+            launched
         };
+        if let Some(plan) = spawn_plan {
+            spawn_fusion_variants_in_background(Arc::clone(&ctx.state.session_controller), plan);
+        }
         serde_json::to_value(session)
             .map_err(|e| ActionError::internal(format!("Failed to serialize session: {}", e)))
     }
 }
 
+/// Fusion variants branch from a commit, so uncommitted changes in the project
+/// would silently be left out. Refuse the launch, or stash them first when the
+/// config asks to; returns the stash commit. A project git cannot read is left
+/// to `launch_fusion` to report.
+async fn guard_dirty_tree(config: &FusionLaunchConfig) -> Result<Option<String>, ActionError> {
+    let project = PathBuf::from(&config.project_path);
+    let action = config.dirty_tree;
+    tokio::task::spawn_blocking(move || {
+        let Ok(changes) = uncommitted_changes(&project) else {
+            return Ok(None);
+        };
+        if changes.is_empty() {
+            return Ok(None);
+        }
+        match action {
+            DirtyTreeAction::Abort => {
+                let shown: Vec<&str> = changes
+                    .iter()
+                    .take(5)
+                    .map(|line| line.get(3..).unwrap_or(line).trim())
+                    .collect();
+                let more = if changes.len() > shown.len() {
+                    format!(" and {} more", changes.len() - shown.len())
+                } else {
+                    String::new()
+                };
+                Err(ActionError::conflict(format!(
+                    "The project has uncommitted changes the variants would miss ({}{}). \
+                     Commit them, or relaunch with dirty_tree \"stash\".",
+                    shown.join(", "),
+                    more
+                )))
+            }
+            DirtyTreeAction::Stash => {
+                stash_changes(&project, "Hive Manager: before Fusion launch")
+                    .map(Some)
+                    .map_err(|e| ActionError::internal(format!("Failed to stash changes: {}", e)))
+            }
+        }
+    })
+    .await
+    .map_err(|e| ActionError::internal(format!("Dirty tree check failed: {}", e)))?
+}
+
 // ---------------------------------------------------------------------------
 // session.update_metadata
 // ---------------------------------------------------------------------------
@@ -790,6 +1145,10 @@ impl Action for LaunchDebate {
 
     async fn run(&self, ctx: &ActionContext, input: Value) -> Result<Value, ActionError> {
         let config: DebateLaunchConfig = deserialize_input(input)?;
+        let queued = QueuedLaunchConfig::Debate(config.clone());
+        if let Some(placeholder) = admit_launch(ctx, queued).await? {
+            return Ok(placeholder);
+        }
         let session = {
             let controller = ctx.state.session_controller.read();
             controller
@@ -883,6 +1242,723 @@ impl Action for UpdateSessionMetadataInfo {
     }
 }
 
+// ---------------------------------------------------------------------------
+// session.queue_solo_task
+// ---------------------------------------------------------------------------
+
+struct QueueSoloTask;
+
+#[async_trait]
+impl Action for QueueSoloTask {
+    fn name(&self) -> &'static str {
+        "session.queue_solo_task"
+    }
+
+    fn input_schema(&self) -> RootSchema {
+        schemars::schema_for!(QueueSoloTaskInput)
+    }
+
+    fn validate_input(&self, input: &Value) -> Result<(), ActionError> {
+        let parsed: QueueSoloTaskInput = deserialize_input(input.clone())?;
+        validate_session_id_input(&parsed.id)?;
+        if parsed.prompt.trim().is_empty() {
+            return Err(ActionError::bad_request("prompt must not be empty"));
+        }
+        Ok(())
+    }
+
+    async fn run(&self, ctx: &ActionContext, input: Value) -> Result<Value, ActionError> {
+        let parsed: QueueSoloTaskInput = deserialize_input(input)?;
+        let controller = ctx.state.session_controller.read();
+        controller
+            .queue_solo_task(&parsed.id, &parsed.prompt)
+            .map_err(|e| {
+                if e.starts_with("Session not found") {
+                    ActionError::not_found(e)
+                } else {
+                    ActionError::conflict(e)
+                }
+            })?;
+        Ok(json!({ "pending": controller.solo_task_queue(&parsed.id) }))
+    }
+}
+
+// ---------------------------------------------------------------------------
+// session.list_checkpoints
+// ---------------------------------------------------------------------------
+
+struct ListCheckpoints;
+
+#[async_trait]
+impl Action for ListCheckpoints {
+    fn name(&self) -> &'static str {
+        "session.list_checkpoints"
+    }
+
+    fn input_schema(&self) -> RootSchema {
+        schemars::schema_for!(ListCheckpointsInput)
+    }
+
+    fn validate_input(&self, input: &Value) -> Result<(), ActionError> {
+        let parsed: ListCheckpointsInput = deserialize_input(input.clone())?;
+        validate_session_id_input(&parsed.id)
+    }
+
+    async fn run(&self, ctx: &ActionContext, input: Value) -> Result<Value, ActionError> {
+        let parsed: ListCheckpointsInput = deserialize_input(input)?;
+        let controller = Arc::clone(&ctx.state.session_controller);
+        let checkpoints =
+            tokio::task::spawn_blocking(move || controller.read().list_checkpoints(&parsed.id))
+                .await
+                .map_err(|e| ActionError::internal(format!("Checkpoint listing failed: {}", e)))?
+                .map_err(|e| {
+                    if e.starts_with("Session not found") {
+                        ActionError::not_found(e)
+                    } else {
+                        ActionError::internal(e)
+                    }
+                })?;
+        serde_json::to_value(checkpoints)
+            .map_err(|e| ActionError::internal(format!("Failed to serialize checkpoints: {}", e)))
+    }
+}
+
+// ---------------------------------------------------------------------------
+// session.get_durations
+// ---------------------------------------------------------------------------
+
+struct GetSessionDurations;
+
+#[async_trait]
+impl Action for GetSessionDurations {
+    fn name(&self) -> &'static str {
+        "session.get_durations"
+    }
+
+    fn input_schema(&self) -> RootSchema {
+        schemars::schema_for!(GetSessionDurationsInput)
+    }
+
+    fn validate_input(&self, input: &Value) -> Result<(), ActionError> {
+        let parsed: GetSessionDurationsInput = deserialize_input(input.clone())?;
+        validate_session_id_input(&parsed.id)
+    }
+
+    async fn run(&self, ctx: &ActionContext, input: Value) -> Result<Value, ActionError> {
+        let parsed: GetSessionDurationsInput = deserialize_input(input)?;
+        let durations = ctx
+            .state
+            .session_controller
+            .read()
+            .get_session_durations(&parsed.id)
+            .map_err(ActionError::not_found)?;
+        serde_json::to_value(durations)
+            .map_err(|e| ActionError::internal(format!("Failed to serialize durations: {}", e)))
+    }
+}
+
+// ---------------------------------------------------------------------------
+// session.compare
+// ---------------------------------------------------------------------------
+
+struct CompareSessions;
+
+#[async_trait]
+impl Action for CompareSessions {
+    fn name(&self) -> &'static str {
+        "session.compare"
+    }
+
+    fn input_schema(&self) -> RootSchema {
+        schemars::schema_for!(CompareSessionsInput)
+    }
+
+    fn validate_input(&self, input: &Value) -> Result<(), ActionError> {
+        let parsed: CompareSessionsInput = deserialize_input(input.clone())?;
+        validate_session_id_input(&parsed.a)?;
+        validate_session_id_input(&parsed.b)
+    }
+
+    async fn run(&self, ctx: &ActionContext, input: Value) -> Result<Value, ActionError> {
+        let parsed: CompareSessionsInput = deserialize_input(input)?;
+        let comparison = ctx
+            .state
+            .session_controller
+            .read()
+            .compare_sessions(&parsed.a, &parsed.b)
+            .map_err(ActionError::not_found)?;
+        serde_json::to_value(comparison)
+            .map_err(|e| ActionError::internal(format!("Failed to serialize comparison: {}", e)))
+    }
+}
+
+// ---------------------------------------------------------------------------
+// session.get_cli_leaderboard
+// ---------------------------------------------------------------------------
+
+struct GetCliLeaderboard;
+
+#[async_trait]
+impl Action for GetCliLeaderboard {
+    fn name(&self) -> &'static str {
+        "session.get_cli_leaderboard"
+    }
+
+    fn input_schema(&self) -> RootSchema {
+        schemars::schema_for!(EmptyInput)
+    }
+
+    async fn run(&self, ctx: &ActionContext, _input: Value) -> Result<Value, ActionError> {
+        let storage = Arc::clone(&ctx.state.storage);
+        let leaderboard = tokio::task::spawn_blocking(move || storage.cli_leaderboard())
+            .await
+            .map_err(|e| ActionError::internal(format!("Leaderboard failed: {}", e)))?
+            .map_err(|e| ActionError::internal(format!("Failed to read fusion history: {}", e)))?;
+        serde_json::to_value(leaderboard)
+            .map_err(|e| ActionError::internal(format!("Failed to serialize leaderboard: {}", e)))
+    }
+}
+
+// ---------------------------------------------------------------------------
+// session.suggest_fusion_variants
+// ---------------------------------------------------------------------------
+
+struct SuggestFusionVariants;
+
+#[async_trait]
+impl Action for SuggestFusionVariants {
+    fn name(&self) -> &'static str {
+        "session.suggest_fusion_variants"
+    }
+
+    fn input_schema(&self) -> RootSchema {
+        schemars::schema_for!(SuggestFusionVariantsInput)
+    }
+
+    fn validate_input(&self, input: &Value) -> Result<(), ActionError> {
+        let parsed: SuggestFusionVariantsInput = deserialize_input(input.clone())?;
+        if parsed.task_description.trim().is_empty() {
+            return Err(ActionError::bad_request("task_description cannot be empty"));
+        }
+        Ok(())
+    }
+
+    async fn run(&self, ctx: &ActionContext, input: Value) -> Result<Value, ActionError> {
+        let parsed: SuggestFusionVariantsInput = deserialize_input(input)?;
+        // Configured CLIs at their default models, for when the history is thin.
+        let fallback = {
+            let config = ctx.state.config.read().await;
+            let mut clis: Vec<_> = config
+                .clis
+                .iter()
+                .map(|(name, cli)| {
+                    let model = cli.default_model.trim();
+                    (name.clone(), (!model.is_empty()).then(|| model.to_string()))
+                })
+                .collect();
+            clis.sort();
+            clis
+        };
+        let storage = Arc::clone(&ctx.state.storage);
+        let history = tokio::task::spawn_blocking(move || storage.fusion_history())
+            .await
+            .map_err(|e| ActionError::internal(format!("Suggestion failed: {}", e)))?
+            .map_err(|e| ActionError::internal(format!("Failed to read fusion history: {}", e)))?;
+        let variants = fusion_suggest::suggest(&history, &parsed.task_description, &fallback);
+        serde_json::to_value(variants)
+            .map_err(|e| ActionError::internal(format!("Failed to serialize variants: {}", e)))
+    }
+}
+
+// ---------------------------------------------------------------------------
+// session.preview_agent_command
+// ---------------------------------------------------------------------------
+
+struct PreviewAgentCommand;
+
+#[async_trait]
+impl Action for PreviewAgentCommand {
+    fn name(&self) -> &'static str {
+        "session.preview_agent_command"
+    }
+
+    fn input_schema(&self) -> RootSchema {
+        schemars::schema_for!(PreviewAgentCommandInput)
+    }
+
+    fn validate_input(&self, input: &Value) -> Result<(), ActionError> {
+        let parsed: PreviewAgentCommandInput = deserialize_input(input.clone())?;
+        validate_cli(&parsed.config.cli)?;
+        Ok(())
+    }
+
+    async fn run(&self, ctx: &ActionContext, input: Value) -> Result<Value, ActionError> {
+        // The preview exposes the operator's CLI configuration, so agents may not ask.
+        if !matches!(ctx.caller, Caller::Frontend) {
+            return Err(ActionError::bad_request(
+                "Agent commands can only be previewed through Tauri commands",
+            ));
+        }
+        let parsed: PreviewAgentCommandInput = deserialize_input(input)?;
+        let preview = ctx
+            .state
+            .session_controller
+            .read()
+            .preview_agent_command(
+                &parsed.config,
+                parsed.cwd.as_deref(),
+                parsed.prompt_path.as_deref(),
+            )
+            .map_err(ActionError::internal)?;
+        serde_json::to_value(preview)
+            .map_err(|e| ActionError::internal(format!("Failed to serialize preview: {}", e)))
+    }
+}
+
+// ---------------------------------------------------------------------------
+// session.cleanup_project_artifacts
+// ---------------------------------------------------------------------------
+
+struct CleanupProjectArtifacts;
+
+#[async_trait]
+impl Action for CleanupProjectArtifacts {
+    fn name(&self) -> &'static str {
+        "session.cleanup_project_artifacts"
+    }
+
+    fn input_schema(&self) -> RootSchema {
+        schemars::schema_for!(CleanupProjectArtifactsInput)
+    }
+
+    fn validate_input(&self, input: &Value) -> Result<(), ActionError> {
+        let parsed: CleanupProjectArtifactsInput = deserialize_input(input.clone())?;
+        validate_project_path(&parsed.project_path)?;
+        Ok(())
+    }
+
+    async fn run(&self, ctx: &ActionContext, input: Value) -> Result<Value, ActionError> {
+        // Cleanup deletes artifacts of any session in the project, so only the
+        // operator may run it.
+        if !matches!(ctx.caller, Caller::Frontend) {
+            return Err(ActionError::bad_request(
+                "Project artifacts can only be cleaned up through Tauri commands",
+            ));
+        }
+        let parsed: CleanupProjectArtifactsInput = deserialize_input(input)?;
+        let controller = Arc::clone(&ctx.state.session_controller);
+        let report = tokio::task::spawn_blocking(move || {
+            controller.read().cleanup_project_artifacts(
+                Path::new(&parsed.project_path),
+                parsed.dry_run.unwrap_or(true),
+                parsed.include_unknown,
+            )
+        })
+        .await
+        .map_err(|e| ActionError::internal(format!("Artifact cleanup failed: {}", e)))?;
+        serde_json::to_value(report)
+            .map_err(|e| ActionError::internal(format!("Failed to serialize report: {}", e)))
+    }
+}
+
+// ---------------------------------------------------------------------------
+// session.ensure_project_gitignore
+// ---------------------------------------------------------------------------
+
+struct EnsureProjectGitignore;
+
+#[async_trait]
+impl Action for EnsureProjectGitignore {
+    fn name(&self) -> &'static str {
+        "session.ensure_project_gitignore"
+    }
+
+    fn input_schema(&self) -> RootSchema {
+        schemars::schema_for!(EnsureProjectGitignoreInput)
+    }
+
+    fn validate_input(&self, input: &Value) -> Result<(), ActionError> {
+        let parsed: EnsureProjectGitignoreInput = deserialize_input(input.clone())?;
+        validate_project_path(&parsed.project_path)?;
+        Ok(())
+    }
+
+    async fn run(&self, ctx: &ActionContext, input: Value) -> Result<Value, ActionError> {
+        if !matches!(ctx.caller, Caller::Frontend) {
+            return Err(ActionError::bad_request(
+                "A project's .gitignore can only be updated through Tauri commands",
+            ));
+        }
+        let parsed: EnsureProjectGitignoreInput = deserialize_input(input)?;
+        let added = tokio::task::spawn_blocking(move || {
+            project_artifacts::ensure_gitignored(Path::new(&parsed.project_path))
+        })
+        .await
+        .map_err(|e| ActionError::internal(format!("Updating .gitignore failed: {}", e)))?
+        .map_err(ActionError::internal)?;
+        Ok(json!({ "added": added }))
+    }
+}
+
+// ---------------------------------------------------------------------------
+// session.promote_session
+// ---------------------------------------------------------------------------
+
+struct PromoteSession;
+
+#[async_trait]
+impl Action for PromoteSession {
+    fn name(&self) -> &'static str {
+        "session.promote_session"
+    }
+
+    fn input_schema(&self) -> RootSchema {
+        schemars::schema_for!(PromoteSessionInput)
+    }
+
+    fn validate_input(&self, input: &Value) -> Result<(), ActionError> {
+        let parsed: PromoteSessionInput = deserialize_input(input.clone())?;
+        validate_session_id_input(&parsed.id)?;
+        for worker in &parsed.workers {
+            validate_cli(&worker.cli)?;
+        }
+        Ok(())
+    }
+
+    async fn run(&self, ctx: &ActionContext, input: Value) -> Result<Value, ActionError> {
+        let parsed: PromoteSessionInput = deserialize_input(input)?;
+        let session = {
+            let controller = ctx.state.session_controller.read();
+            controller
+                .promote_session(&parsed.id, parsed.workers)
+                .map_err(|e| {
+                    if e.starts_with("Session not found") {
+                        ActionError::not_found(e)
+                    } else if e.contains("is not a Solo session") || e.contains("already finished")
+                    {
+                        ActionError::conflict(e)
+                    } else {
+                        ActionError::internal(e)
+                    }
+                })?
+        };
+        serde_json::to_value(session)
+            .map_err(|e| ActionError::internal(format!("Failed to serialize session: {}", e)))
+    }
+}
+
+// ---------------------------------------------------------------------------
+// session.scale_workers
+// ---------------------------------------------------------------------------
+
+struct ScaleWorkers;
+
+#[async_trait]
+impl Action for ScaleWorkers {
+    fn name(&self) -> &'static str {
+        "session.scale_workers"
+    }
+
+    fn input_schema(&self) -> RootSchema {
+        schemars::schema_for!(ScaleWorkersInput)
+    }
+
+    fn validate_input(&self, input: &Value) -> Result<(), ActionError> {
+        let parsed: ScaleWorkersInput = deserialize_input(input.clone())?;
+        validate_session_id_input(&parsed.id)
+    }
+
+    async fn run(&self, ctx: &ActionContext, input: Value) -> Result<Value, ActionError> {
+        let parsed: ScaleWorkersInput = deserialize_input(input)?;
+        let controller = Arc::clone(&ctx.state.session_controller);
+        let session_id = parsed.id.clone();
+        let report = tokio::task::spawn_blocking(move || {
+            controller
+                .read()
+                .scale_workers(&parsed.id, parsed.target_count)
+        })
+        .await
+        .map_err(|e| ActionError::internal(format!("Worker scaling failed: {}", e)))?
+        .map_err(|e| {
+            if e.starts_with("Session not found") {
+                ActionError::not_found(e)
+            } else if e.contains("is not a Hive session") || e.contains("already finished") {
+                ActionError::conflict(e)
+            } else {
+                ActionError::internal(e)
+            }
+        })?;
+
+        // The Queen did not ask for these changes, so tell her before she assigns work.
+        if !report.added.is_empty() || !report.retired.is_empty() {
+            let mut message = format!(
+                "The operator resized the worker pool to {}.",
+                report.worker_count
+            );
+            if !report.added.is_empty() {
+                message.push_str(&format!(" New on STANDBY: {}.", report.added.join(", ")));
+            }
+            if !report.retired.is_empty() {
+                message.push_str(&format!(
+                    " Retired (do not assign): {}.",
+                    report.retired.join(", ")
+                ));
+            }
+            let queen_id = format!("{}-queen", session_id);
+            let injection_manager = ctx.state.injection_manager.read();
+            if let Err(e) = injection_manager.operator_inject(&session_id, &queen_id, &message) {
+                tracing::warn!("Failed to tell {} about the worker scale: {}", queen_id, e);
+            }
+        }
+
+        serde_json::to_value(report)
+            .map_err(|e| ActionError::internal(format!("Failed to serialize report: {}", e)))
+    }
+}
+
+// ---------------------------------------------------------------------------
+// session.override_spawn_quota
+// ---------------------------------------------------------------------------
+
+struct OverrideSpawnQuota;
+
+#[async_trait]
+impl Action for OverrideSpawnQuota {
+    fn name(&self) -> &'static str {
+        "session.override_spawn_quota"
+    }
+
+    fn input_schema(&self) -> RootSchema {
+        schemars::schema_for!(OverrideSpawnQuotaInput)
+    }
+
+    fn validate_input(&self, input: &Value) -> Result<(), ActionError> {
+        let parsed: OverrideSpawnQuotaInput = deserialize_input(input.clone())?;
+        validate_session_id_input(&parsed.id)
+    }
+
+    async fn run(&self, ctx: &ActionContext, input: Value) -> Result<Value, ActionError> {
+        // The quota limits what the session's agents may spawn, so only the operator
+        // may change it.
+        if !matches!(ctx.caller, Caller::Frontend) {
+            return Err(ActionError::bad_request(
+                "Spawn quotas can only be overridden through Tauri commands",
+            ));
+        }
+        let parsed: OverrideSpawnQuotaInput = deserialize_input(input)?;
+        ctx.state
+            .session_controller
+            .read()
+            .override_spawn_quota(&parsed.id, parsed.quota.clone())
+            .map_err(ActionError::not_found)?;
+        Ok(json!({ "id": parsed.id, "spawn_quota": parsed.quota }))
+    }
+}
+
+// ---------------------------------------------------------------------------
+// session.run_fusion_judges
+// ---------------------------------------------------------------------------
+
+struct RunFusionJudges;
+
+#[async_trait]
+impl Action for RunFusionJudges {
+    fn name(&self) -> &'static str {
+        "session.run_fusion_judges"
+    }
+
+    fn input_schema(&self) -> RootSchema {
+        schemars::schema_for!(RunFusionJudgesInput)
+    }
+
+    fn validate_input(&self, input: &Value) -> Result<(), ActionError> {
+        let parsed: RunFusionJudgesInput = deserialize_input(input.clone())?;
+        validate_session_id_input(&parsed.id)?;
+        for judge in &parsed.judges {
+            if !judge.cli.trim().is_empty() {
+                validate_cli(&judge.cli)?;
+            }
+        }
+        Ok(())
+    }
+
+    async fn run(&self, ctx: &ActionContext, input: Value) -> Result<Value, ActionError> {
+        let parsed: RunFusionJudgesInput = deserialize_input(input)?;
+        let controller = Arc::clone(&ctx.state.session_controller);
+        let session_id = parsed.id.clone();
+        let judge_ids = tokio::task::spawn_blocking(move || {
+            controller
+                .read()
+                .spawn_fusion_judge_panel(&parsed.id, parsed.judges)
+        })
+        .await
+        .map_err(|e| ActionError::internal(format!("Fusion judge spawn failed: {}", e)))?
+        .map_err(|e| {
+            if e.starts_with("Session not found") {
+                ActionError::not_found(e)
+            } else {
+                ActionError::bad_request(e)
+            }
+        })?;
+        Ok(json!({ "session_id": session_id, "judge_ids": judge_ids }))
+    }
+}
+
+// ---------------------------------------------------------------------------
+// session.link_plan_issues
+// ---------------------------------------------------------------------------
+
+struct LinkPlanIssues;
+
+#[async_trait]
+impl Action for LinkPlanIssues {
+    fn name(&self) -> &'static str {
+        "session.link_plan_issues"
+    }
+
+    fn input_schema(&self) -> RootSchema {
+        schemars::schema_for!(LinkPlanIssuesInput)
+    }
+
+    fn validate_input(&self, input: &Value) -> Result<(), ActionError> {
+        let parsed: LinkPlanIssuesInput = deserialize_input(input.clone())?;
+        validate_session_id_input(&parsed.id)
+    }
+
+    async fn run(&self, ctx: &ActionContext, input: Value) -> Result<Value, ActionError> {
+        let parsed: LinkPlanIssuesInput = deserialize_input(input)?;
+        // Every task is a `gh` call; keep them off the async runtime.
+        let controller = Arc::clone(&ctx.state.session_controller);
+        let link = tokio::task::spawn_blocking(move || {
+            controller
+                .read()
+                .link_plan_issues(&parsed.id, &parsed.parent_issue, parsed.mode)
+        })
+        .await
+        .map_err(|e| ActionError::internal(format!("Linking plan issues failed: {}", e)))?
+        .map_err(|e| {
+            if e.starts_with("Session not found") {
+                ActionError::not_found(e)
+            } else if e.starts_with("Not a GitHub issue") {
+                ActionError::bad_request(e)
+            } else if e.contains("not ready") || e.contains("already linked") {
+                ActionError::conflict(e)
+            } else {
+                ActionError::internal(e)
+            }
+        })?;
+
+        serde_json::to_value(link)
+            .map_err(|e| ActionError::internal(format!("Failed to serialize link: {}", e)))
+    }
+}
+
+// ---------------------------------------------------------------------------
+// session.get_full_snapshot
+// ---------------------------------------------------------------------------
+
+/// Coordination messages in a full snapshot unless the caller asks for another number.
+const FULL_SNAPSHOT_COORDINATION_LIMIT: usize = 100;
+
+#[derive(Debug, Deserialize, JsonSchema)]
+struct FullSnapshotInput {
+    id: String,
+    /// Newest coordination messages to include; 100 when omitted.
+    coordination_limit: Option<usize>,
+}
+
+/// Everything the UI shows for one session, so its stores can be rebuilt after a
+/// reload in one call.
+#[derive(Debug, Serialize)]
+struct FullSessionSnapshot {
+    session: Session,
+    /// Agent ID to its last heartbeat.
+    heartbeats: BTreeMap<String, AgentHeartbeatInfo>,
+    /// TASK messages typed into workers that they have not acknowledged yet.
+    pending_injections: Vec<MessageDelivery>,
+    /// Worker agent ID to the status line of its task file.
+    task_statuses: BTreeMap<String, String>,
+    /// The newest coordination messages, oldest first.
+    coordination: Vec<CoordinationMessage>,
+    plan: PlanStatus,
+}
+
+#[derive(Debug, Serialize)]
+struct PlanStatus {
+    /// `None` until a plan has been written.
+    plan: Option<Plan>,
+    tasks_done: usize,
+    tasks_total: usize,
+}
+
+struct GetFullSnapshot;
+
+#[async_trait]
+impl Action for GetFullSnapshot {
+    fn name(&self) -> &'static str {
+        "session.get_full_snapshot"
+    }
+
+    fn input_schema(&self) -> RootSchema {
+        schemars::schema_for!(FullSnapshotInput)
+    }
+
+    fn validate_input(&self, input: &Value) -> Result<(), ActionError> {
+        let parsed: FullSnapshotInput = deserialize_input(input.clone())?;
+        validate_session_id_input(&parsed.id)
+    }
+
+    async fn run(&self, ctx: &ActionContext, input: Value) -> Result<Value, ActionError> {
+        let parsed: FullSnapshotInput = deserialize_input(input)?;
+        let (session, heartbeats) = {
+            let controller = ctx.state.session_controller.read();
+            let session = controller.get_session(&parsed.id).ok_or_else(|| {
+                ActionError::not_found(format!("Session {} not found", parsed.id))
+            })?;
+            (session, controller.get_heartbeat_info(&parsed.id))
+        };
+        let task_statuses = SessionController::worker_task_statuses(&session);
+
+        let (pending_injections, coordination) = {
+            let injection = ctx.state.injection_manager.read();
+            let pending = injection
+                .unacknowledged_messages(&parsed.id, None)
+                .map_err(|e| ActionError::internal(e.to_string()))?;
+            let limit = parsed
+                .coordination_limit
+                .unwrap_or(FULL_SNAPSHOT_COORDINATION_LIMIT);
+            let log = injection
+                .get_coordination_log(&parsed.id, Some(limit))
+                .map_err(|e| ActionError::internal(e.to_string()))?;
+            (pending, log)
+        };
+
+        let plan_path = super::coordination::session_plan_path(ctx, &parsed.id);
+        let plan = plan_path
+            .parent()
+            .map(plan_parser::load)
+            .transpose()
+            .map_err(|e| ActionError::internal(format!("Failed to read plan.md: {}", e)))?
+            .flatten();
+        let tasks = plan.as_ref().map(|plan| plan.tasks.as_slice()).unwrap_or_default();
+        let plan = PlanStatus {
+            tasks_done: tasks.iter().filter(|task| task.done).count(),
+            tasks_total: tasks.len(),
+            plan,
+        };
+
+        serde_json::to_value(FullSessionSnapshot {
+            session,
+            heartbeats: heartbeats.into_iter().collect(),
+            pending_injections,
+            task_statuses,
+            coordination,
+            plan,
+        })
+        .map_err(|e| ActionError::internal(format!("Failed to serialize session snapshot: {}", e)))
+    }
+}
+
 /// Register every session action into the registry.
 pub fn register(registry: &mut ActionRegistry) {
     registry.register(Box::new(ListSessions));
@@ -899,6 +1975,21 @@ pub fn register(registry: &mut ActionRegistry) {
     registry.register(Box::new(LaunchDebate));
     registry.register(Box::new(UpdateSessionMetadata));
     registry.register(Box::new(UpdateSessionMetadataInfo));
+    registry.register(Box::new(QueueSoloTask));
+    registry.register(Box::new(ListCheckpoints));
+    registry.register(Box::new(GetSessionDurations));
+    registry.register(Box::new(CompareSessions));
+    registry.register(Box::new(GetCliLeaderboard));
+    registry.register(Box::new(SuggestFusionVariants));
+    registry.register(Box::new(PreviewAgentCommand));
+    registry.register(Box::new(CleanupProjectArtifacts));
+    registry.register(Box::new(EnsureProjectGitignore));
+    registry.register(Box::new(PromoteSession));
+    registry.register(Box::new(ScaleWorkers));
+    registry.register(Box::new(LinkPlanIssues));
+    registry.register(Box::new(OverrideSpawnQuota));
+    registry.register(Box::new(RunFusionJudges));
+    registry.register(Box::new(GetFullSnapshot));
 }
 
 #[cfg(test)]
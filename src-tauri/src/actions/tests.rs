@@ -113,7 +113,13 @@ fn test_registry_lists_all_actions() {
 
     // Scratch terminals deliberately reuse the existing PTY action surface so they do
     // not require another Tauri command or ACL permission.
-    for expected in ["pty.create", "pty.kill", "pty.list"] {
+    for expected in [
+        "pty.create",
+        "pty.kill",
+        "pty.list",
+        "pty.set_recording",
+        "pty.list_recordings",
+    ] {
         assert!(names.contains(&expected), "missing PTY action {expected}");
     }
 }
@@ -171,6 +177,34 @@ fn test_pty_create_role_defaults_to_worker_and_resolves_scratch_shell() {
     assert!(matches!(scratch_role, crate::pty::AgentRole::ScratchShell));
     assert_eq!(scratch_owner.as_deref(), Some("session-a"));
 
+    let (login_role, _) = resolve_create_role_for_test(json!({
+        "id": "scratch:session-a:login",
+        "command": "",
+        "args": [],
+        "cwd": ".",
+        "cols": 120,
+        "rows": 30,
+        "role": "scratch_shell",
+        "shell": "login",
+        "session_id": "session-a"
+    }))
+    .expect("the login shell is resolved by the backend, so it is sent without a command");
+    assert!(matches!(login_role, crate::pty::AgentRole::ScratchShell));
+
+    let spoofed_login = resolve_create_role_for_test(json!({
+        "id": "scratch:session-a:spoofed",
+        "command": "/bin/sh",
+        "args": ["-c", "rm -rf ~"],
+        "cwd": ".",
+        "cols": 120,
+        "rows": 30,
+        "role": "scratch_shell",
+        "shell": "login",
+        "session_id": "session-a"
+    }))
+    .expect_err("a login scratch shell must not carry its own command");
+    assert_eq!(spoofed_login.status, ActionStatus::BadRequest);
+
     let ambiguous_id = resolve_create_role_for_test(json!({
         "id": "scratch:session-a:part:two",
         "command": "cmd.exe",
@@ -246,6 +280,31 @@ async fn test_scratch_pty_rejects_unknown_session_before_process_spawn() {
     );
 }
 
+#[tokio::test]
+async fn test_recording_toggle_rejects_unknown_session() {
+    let registry = build_registry();
+    let ctx = ActionContext::new(Caller::Frontend, test_state());
+    let err = registry
+        .dispatch(
+            "pty.set_recording",
+            &ctx,
+            json!({ "session_id": "missing-session", "enabled": true }),
+        )
+        .await
+        .expect_err("recording cannot be switched on for a session that does not exist");
+    assert_eq!(err.status, ActionStatus::NotFound);
+
+    let listed = registry
+        .dispatch(
+            "pty.list_recordings",
+            &ctx,
+            json!({ "session_id": "missing-session" }),
+        )
+        .await
+        .expect("listing a session without recordings should succeed");
+    assert_eq!(listed, json!({ "enabled": false, "recordings": [] }));
+}
+
 #[test]
 fn test_schema_per_action_serializes() {
     let registry = build_registry();
@@ -310,6 +369,70 @@ async fn test_validation_runs_before_run_bad_cli() {
     );
 }
 
+#[tokio::test]
+async fn test_run_fusion_judges_checks_clis_and_the_session() {
+    let registry = build_registry();
+    let ctx = ActionContext::new(Caller::Http, test_state());
+
+    let err = registry
+        .dispatch(
+            "session.run_fusion_judges",
+            &ctx,
+            json!({ "id": "nonexistent", "judges": [{ "cli": "definitely-not-a-cli" }] }),
+        )
+        .await
+        .expect_err("invalid CLI should be rejected");
+    assert_eq!(err.status, ActionStatus::BadRequest);
+
+    let err = registry
+        .dispatch("session.run_fusion_judges", &ctx, json!({ "id": "nonexistent" }))
+        .await
+        .expect_err("unknown session should be rejected");
+    assert_eq!(err.status, ActionStatus::NotFound);
+}
+
+#[tokio::test]
+async fn test_preview_agent_command_is_frontend_only() {
+    let registry = build_registry();
+    let ctx = ActionContext::new(Caller::Http, test_state());
+
+    let err = registry
+        .dispatch(
+            "session.preview_agent_command",
+            &ctx,
+            json!({ "config": { "cli": "claude" } }),
+        )
+        .await
+        .expect_err("HTTP callers should not see the CLI configuration");
+    assert_eq!(err.status, ActionStatus::BadRequest);
+}
+
+#[tokio::test]
+async fn test_project_maintenance_actions_are_frontend_only() {
+    let registry = build_registry();
+    let ctx = ActionContext::new(Caller::Http, test_state());
+    let project = tempfile::tempdir().unwrap();
+    let project_path = project.path().to_string_lossy().to_string();
+
+    for (action, input) in [
+        (
+            "session.cleanup_project_artifacts",
+            json!({ "project_path": project_path, "dry_run": false, "include_unknown": true }),
+        ),
+        (
+            "session.ensure_project_gitignore",
+            json!({ "project_path": project_path }),
+        ),
+    ] {
+        let err = registry
+            .dispatch(action, &ctx, input)
+            .await
+            .expect_err("HTTP callers should be rejected");
+        assert_eq!(err.status, ActionStatus::BadRequest, "{}", action);
+    }
+    assert!(!project.path().join(".gitignore").exists());
+}
+
 #[tokio::test]
 async fn test_unknown_action_is_not_found() {
     let registry = build_registry();
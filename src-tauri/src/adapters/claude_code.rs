@@ -1,6 +1,9 @@
 //! Claude Code CLI adapter implementation.
 
-use super::{AgentLaunchSpec, AgentSignal, BootstrapContext, CliAdapter, LaunchCommand};
+use super::{
+    percent_after, AgentLaunchSpec, AgentSignal, BootstrapContext, CliAdapter, ContextSignal,
+    LaunchCommand,
+};
 
 /// Claude Code CLI adapter.
 ///
@@ -111,6 +114,35 @@ impl CliAdapter for ClaudeCodeAdapter {
     fn model_flag(&self) -> Option<&'static str> {
         Some("--model")
     }
+
+    fn detect_context_signal(&self, line: &str) -> Option<ContextSignal> {
+        let line_lower = line.to_lowercase();
+
+        if line_lower.contains("conversation compacted")
+            || line_lower.contains("compacting conversation")
+        {
+            return Some(ContextSignal::Compacted);
+        }
+
+        // "Context left until auto-compact: 12%"
+        if let Some(percent) = percent_after(&line_lower, "context left until auto-compact") {
+            return Some(ContextSignal::Remaining { percent });
+        }
+
+        // "Context low (8% remaining) · Run /compact to compact & continue"
+        if line_lower.contains("context low") {
+            return Some(match percent_after(&line_lower, "context low") {
+                Some(percent) => ContextSignal::Remaining { percent },
+                None => ContextSignal::Low,
+            });
+        }
+
+        None
+    }
+
+    fn compact_command(&self) -> Option<&'static str> {
+        Some("/compact")
+    }
 }
 
 /// Extract tool name from a tool call line.
@@ -220,4 +252,27 @@ mod tests {
             Some("read_file".to_string())
         );
     }
+
+    #[test]
+    fn test_detect_context_signal() {
+        let adapter = ClaudeCodeAdapter;
+
+        assert_eq!(
+            adapter.detect_context_signal("Context left until auto-compact: 12%"),
+            Some(ContextSignal::Remaining { percent: 12 })
+        );
+        assert_eq!(
+            adapter.detect_context_signal("Context low (8% remaining) · Run /compact to compact"),
+            Some(ContextSignal::Remaining { percent: 8 })
+        );
+        assert_eq!(
+            adapter.detect_context_signal("Context low · Run /compact to compact & continue"),
+            Some(ContextSignal::Low)
+        );
+        assert_eq!(
+            adapter.detect_context_signal("✻ Conversation compacted · ctrl+o for history"),
+            Some(ContextSignal::Compacted)
+        );
+        assert_eq!(adapter.detect_context_signal("Reading src/context.rs"), None);
+    }
 }
@@ -1,6 +1,9 @@
 //! Codex CLI adapter implementation.
 
-use super::{AgentLaunchSpec, AgentSignal, BootstrapContext, CliAdapter, LaunchCommand};
+use super::{
+    percent_before, AgentLaunchSpec, AgentSignal, BootstrapContext, CliAdapter, ContextSignal,
+    LaunchCommand,
+};
 use crate::cli::CliRegistry;
 
 /// Codex CLI adapter.
@@ -117,6 +120,25 @@ impl CliAdapter for CodexAdapter {
     fn model_flag(&self) -> Option<&'static str> {
         Some("-m")
     }
+
+    fn detect_context_signal(&self, line: &str) -> Option<ContextSignal> {
+        let line_lower = line.to_lowercase();
+
+        if line_lower.contains("context compacted")
+            || line_lower.contains("conversation compacted")
+            || line_lower.contains("compact task completed")
+        {
+            return Some(ContextSignal::Compacted);
+        }
+
+        // Status line: "... · 37% context left"
+        percent_before(&line_lower, "context left")
+            .map(|percent| ContextSignal::Remaining { percent })
+    }
+
+    fn compact_command(&self) -> Option<&'static str> {
+        Some("/compact")
+    }
 }
 
 fn is_explicit_prompt_marker(line_lower: &str, trimmed: &str) -> bool {
@@ -220,4 +242,19 @@ mod tests {
             Some(AgentSignal::WaitingInput)
         );
     }
+
+    #[test]
+    fn test_detect_context_signal() {
+        let adapter = CodexAdapter;
+
+        assert_eq!(
+            adapter.detect_context_signal("  ⏎ send   Ctrl+J newline   37% context left"),
+            Some(ContextSignal::Remaining { percent: 37 })
+        );
+        assert_eq!(
+            adapter.detect_context_signal("Context compacted"),
+            Some(ContextSignal::Compacted)
+        );
+        assert_eq!(adapter.detect_context_signal("context left to do: none"), None);
+    }
 }
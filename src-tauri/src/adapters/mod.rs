@@ -32,9 +32,9 @@ pub const VALID_CLIS: &[&str] = &[
     "qwen",
 ];
 
-/// Validate a CLI name against the allowlist.
+/// Validate a CLI name against the allowlist and the CLIs defined in `clis/*.toml`.
 pub fn is_valid_cli(cli: &str) -> bool {
-    VALID_CLIS.contains(&cli)
+    VALID_CLIS.contains(&cli) || crate::cli::custom::is_defined(cli)
 }
 
 /// Specification for launching an agent process.
@@ -129,6 +129,65 @@ pub enum AgentSignal {
     Processing,
 }
 
+/// What a CLI's output says about its context window.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ContextSignal {
+    /// This much of the context window is left, in percent.
+    Remaining { percent: u8 },
+    /// The CLI warned that context is running low without giving a figure.
+    Low,
+    /// The CLI compacted its conversation history.
+    Compacted,
+}
+
+/// The percentage right after `marker` in `line`: `12` in
+/// "context left until auto-compact: 12%".
+fn percent_after(line: &str, marker: &str) -> Option<u8> {
+    let rest = &line[line.find(marker)? + marker.len()..];
+    let rest = rest.trim_start_matches(|c: char| c == ':' || c == '(' || c.is_whitespace());
+    let digits = &rest[..rest.find(|c: char| !c.is_ascii_digit())?];
+    if !rest[digits.len()..].starts_with('%') {
+        return None;
+    }
+    digits.parse::<u8>().ok().filter(|percent| *percent <= 100)
+}
+
+/// The percentage right before `marker` in `line`: `37` in "37% context left".
+fn percent_before(line: &str, marker: &str) -> Option<u8> {
+    let head = line[..line.find(marker)?].trim_end().strip_suffix('%')?;
+    let digits = &head[head
+        .rfind(|c: char| !c.is_ascii_digit())
+        .map_or(0, |index| index + 1)..];
+    digits.parse::<u8>().ok().filter(|percent| *percent <= 100)
+}
+
+/// Keystroke that submits what has been typed into a CLI's interactive prompt.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum SubmitKey {
+    /// Carriage return.
+    Enter,
+    /// `ESC` then carriage return, for prompts where plain Enter inserts a newline.
+    AltEnter,
+    /// Two carriage returns, for prompts whose first Enter only closes a completion
+    /// or paste preview.
+    DoubleEnter,
+    /// Line feed, for prompts that read a carriage return as part of the text.
+    CtrlJ,
+}
+
+impl SubmitKey {
+    pub fn bytes(self) -> &'static [u8] {
+        match self {
+            Self::Enter => b"\r",
+            Self::AltEnter => b"\x1b\r",
+            Self::DoubleEnter => b"\r\r",
+            Self::CtrlJ => b"\n",
+        }
+    }
+}
+
 /// Context for building bootstrap prompts.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct BootstrapContext {
@@ -182,6 +241,21 @@ pub trait CliAdapter: Send + Sync {
     fn prompt_flag(&self) -> Option<&'static str> {
         None
     }
+
+    /// Returns the keystroke that submits a message typed into the CLI.
+    fn submit_key(&self) -> SubmitKey {
+        SubmitKey::Enter
+    }
+
+    /// Detects context-window hints (space left, compaction) in CLI output.
+    fn detect_context_signal(&self, _line: &str) -> Option<ContextSignal> {
+        None
+    }
+
+    /// The command typed into the CLI to compact its conversation, if it has one.
+    fn compact_command(&self) -> Option<&'static str> {
+        None
+    }
 }
 
 /// Get the appropriate adapter for a CLI name.
@@ -142,7 +142,9 @@ fn collect_changed_files(worktree_path: &Path) -> Result<Vec<String>, StorageErr
     Ok(changed_files)
 }
 
-fn detect_test_results(worktree_path: &Path) -> Result<Option<serde_json::Value>, StorageError> {
+pub(crate) fn detect_test_results(
+    worktree_path: &Path,
+) -> Result<Option<serde_json::Value>, StorageError> {
     let candidates = [
         "test-results.json",
         "test-results.txt",
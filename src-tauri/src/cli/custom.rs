@@ -0,0 +1,342 @@
+//! CLIs defined by dropping a `clis/<name>.toml` file into the app data dir.
+//!
+//! Each file describes one CLI the built-in table does not know, such as an
+//! internal company agent, so adding one does not mean editing `config.json`
+//! by hand. The file stem is the CLI name used in launch configs:
+//!
+//! ```toml
+//! # clis/acme.toml
+//! command = "acme-agent"
+//! auto_approve_flag = "--yes"
+//! model_flag = "--model"
+//! default_model = "acme-large"
+//! flags = ["--no-telemetry"]
+//! behavior = "instruction-following"
+//! submit_key = "double_enter"
+//!
+//! [prompt]
+//! flag = "--prompt"
+//! max_inline_bytes = 16384
+//!
+//! [env]
+//! ACME_TOKEN = "${secret:acme-token}"
+//! ```
+//!
+//! Definitions are loaded and validated once at startup. A file that fails
+//! validation is skipped with a warning instead of stopping the app.
+
+use std::collections::{BTreeMap, HashMap};
+use std::path::Path;
+
+use parking_lot::RwLock;
+use serde::Deserialize;
+use thiserror::Error;
+
+use crate::adapters::{SubmitKey, VALID_CLIS};
+use crate::storage::CliConfig;
+
+use super::CliBehavior;
+
+/// Definitions loaded at startup, by CLI name.
+static CUSTOM_CLIS: RwLock<BTreeMap<String, CustomCli>> = RwLock::new(BTreeMap::new());
+
+#[derive(Error, Debug)]
+pub enum CustomCliError {
+    #[error("{0}: {1}")]
+    Read(String, std::io::Error),
+    #[error("{0}: {1}")]
+    Parse(String, toml::de::Error),
+    #[error("{0}: {1}")]
+    Invalid(String, String),
+}
+
+/// How a CLI acts under prompting; see [`CliBehavior`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum BehaviorProfile {
+    #[default]
+    ActionProne,
+    InstructionFollowing,
+    ExplicitPolling,
+    Interactive,
+}
+
+impl From<BehaviorProfile> for CliBehavior {
+    fn from(profile: BehaviorProfile) -> Self {
+        match profile {
+            BehaviorProfile::ActionProne => CliBehavior::ActionProne,
+            BehaviorProfile::InstructionFollowing => CliBehavior::InstructionFollowing,
+            BehaviorProfile::ExplicitPolling => CliBehavior::ExplicitPolling,
+            BehaviorProfile::Interactive => CliBehavior::Interactive,
+        }
+    }
+}
+
+/// How the CLI takes its initial prompt.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct PromptStrategy {
+    /// Flag placed before the prompt (`--prompt`); positional when absent.
+    #[serde(default)]
+    pub flag: Option<String>,
+    /// Largest prompt passed inline before it is spilled to a file.
+    #[serde(default)]
+    pub max_inline_bytes: Option<usize>,
+}
+
+/// One `clis/<name>.toml` file.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct CustomCli {
+    /// The file stem; not read from the file.
+    #[serde(skip)]
+    pub name: String,
+    pub command: String,
+    #[serde(default)]
+    pub auto_approve_flag: Option<String>,
+    #[serde(default)]
+    pub model_flag: Option<String>,
+    #[serde(default)]
+    pub default_model: Option<String>,
+    /// Passed on every launch, after the model.
+    #[serde(default)]
+    pub flags: Vec<String>,
+    #[serde(default)]
+    pub behavior: BehaviorProfile,
+    #[serde(default)]
+    pub prompt: PromptStrategy,
+    /// Key pressed after text is typed into the CLI; Enter when absent.
+    #[serde(default)]
+    pub submit_key: Option<SubmitKey>,
+    /// May reference keychain secrets as `${secret:NAME}`.
+    #[serde(default)]
+    pub env: HashMap<String, String>,
+    /// See `CliConfig::path_prepend`.
+    #[serde(default)]
+    pub path_prepend: Vec<String>,
+    /// See `CliConfig::env_allowlist`.
+    #[serde(default)]
+    pub env_allowlist: Option<Vec<String>>,
+}
+
+impl CustomCli {
+    /// Parse and validate the definition for CLI `name`.
+    pub fn parse(name: &str, source: &str) -> Result<Self, CustomCliError> {
+        let mut cli: CustomCli =
+            toml::from_str(source).map_err(|e| CustomCliError::Parse(name.to_string(), e))?;
+        cli.name = name.to_string();
+        cli.validate()?;
+        Ok(cli)
+    }
+
+    fn validate(&self) -> Result<(), CustomCliError> {
+        let invalid =
+            |reason: &str| CustomCliError::Invalid(self.name.clone(), reason.to_string());
+        let name_ok = self.name.starts_with(|c: char| c.is_ascii_alphanumeric())
+            && self
+                .name
+                .chars()
+                .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-' || c == '_');
+        if !name_ok {
+            return Err(invalid(
+                "file name must be lowercase letters, digits, '-' or '_'",
+            ));
+        }
+        if VALID_CLIS.contains(&self.name.as_str()) {
+            return Err(invalid("a built-in CLI already has this name"));
+        }
+        if self.command.trim().is_empty() {
+            return Err(invalid("command must not be empty"));
+        }
+        if self.prompt.max_inline_bytes == Some(0) {
+            return Err(invalid("prompt.max_inline_bytes must be greater than zero"));
+        }
+        let empty_flag = [&self.auto_approve_flag, &self.model_flag, &self.prompt.flag]
+            .into_iter()
+            .flatten()
+            .chain(&self.flags)
+            .any(|flag| flag.trim().is_empty());
+        if empty_flag {
+            return Err(invalid("flags must not be empty"));
+        }
+        if let Some(key) = self
+            .env
+            .keys()
+            .find(|key| key.is_empty() || key.contains(['=', '\0']))
+        {
+            return Err(invalid(&format!("invalid env var name {:?}", key)));
+        }
+        Ok(())
+    }
+
+    /// The model passed when the launch config names none.
+    pub fn model<'a>(&'a self, configured: Option<&'a str>) -> Option<&'a str> {
+        configured.or(self.default_model.as_deref())
+    }
+
+    /// Arguments before the prompt: auto-approve, model, then the fixed flags.
+    pub fn launch_args(&self, model: Option<&str>) -> Vec<String> {
+        let mut args: Vec<String> = self.auto_approve_flag.iter().cloned().collect();
+        if let (Some(flag), Some(model)) = (&self.model_flag, model) {
+            args.push(flag.clone());
+            args.push(model.to_string());
+        }
+        args.extend(self.flags.iter().cloned());
+        args
+    }
+
+    /// Append `prompt` the way this CLI takes it.
+    pub fn push_prompt(&self, args: &mut Vec<String>, prompt: String) {
+        if let Some(flag) = &self.prompt.flag {
+            args.push(flag.clone());
+        }
+        args.push(prompt);
+    }
+
+    /// The `config.json`-style entry, so env lookup and the registry see it.
+    pub fn cli_config(&self) -> CliConfig {
+        CliConfig {
+            command: self.command.clone(),
+            auto_approve_flag: self.auto_approve_flag.clone(),
+            model_flag: self.model_flag.clone(),
+            default_model: self.default_model.clone().unwrap_or_default(),
+            env: (!self.env.is_empty()).then(|| self.env.clone()),
+            submit_key: self.submit_key,
+            path_prepend: self.path_prepend.clone(),
+            env_allowlist: self.env_allowlist.clone(),
+        }
+    }
+}
+
+/// Parse every `*.toml` in `dir`. A missing directory holds no definitions.
+pub fn load_dir(dir: &Path) -> (Vec<CustomCli>, Vec<CustomCliError>) {
+    let mut clis = Vec::new();
+    let mut errors = Vec::new();
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return (clis, errors);
+    };
+    let mut paths: Vec<_> = entries
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| path.extension().is_some_and(|ext| ext == "toml"))
+        .collect();
+    paths.sort();
+    for path in paths {
+        let name = path
+            .file_stem()
+            .map(|stem| stem.to_string_lossy().to_string())
+            .unwrap_or_default();
+        let parsed = std::fs::read_to_string(&path)
+            .map_err(|e| CustomCliError::Read(name.clone(), e))
+            .and_then(|source| CustomCli::parse(&name, &source));
+        match parsed {
+            Ok(cli) => clis.push(cli),
+            Err(e) => errors.push(e),
+        }
+    }
+    (clis, errors)
+}
+
+/// Replace the loaded definitions.
+pub fn install(clis: Vec<CustomCli>) {
+    let mut table = CUSTOM_CLIS.write();
+    table.clear();
+    table.extend(clis.into_iter().map(|cli| (cli.name.clone(), cli)));
+}
+
+/// The definition for CLI `name`.
+pub fn get(name: &str) -> Option<CustomCli> {
+    CUSTOM_CLIS.read().get(name).cloned()
+}
+
+/// The definition whose command is `command`, for code that only sees what
+/// is actually spawned.
+pub fn by_command(command: &str) -> Option<CustomCli> {
+    CUSTOM_CLIS
+        .read()
+        .values()
+        .find(|cli| cli.command == command)
+        .cloned()
+}
+
+pub fn is_defined(name: &str) -> bool {
+    CUSTOM_CLIS.read().contains_key(name)
+}
+
+/// Every loaded definition, by name.
+pub fn all() -> Vec<CustomCli> {
+    CUSTOM_CLIS.read().values().cloned().collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_reads_a_full_definition() {
+        let cli = CustomCli::parse(
+            "acme",
+            r#"
+command = "acme-agent"
+auto_approve_flag = "--yes"
+model_flag = "--model"
+default_model = "acme-large"
+flags = ["--no-telemetry"]
+behavior = "explicit-polling"
+
+[prompt]
+flag = "--prompt"
+max_inline_bytes = 4096
+
+[env]
+ACME_TOKEN = "${secret:acme-token}"
+"#,
+        )
+        .unwrap();
+
+        assert_eq!(cli.behavior, BehaviorProfile::ExplicitPolling);
+        assert_eq!(
+            cli.launch_args(cli.model(None)),
+            vec!["--yes", "--model", "acme-large", "--no-telemetry"]
+        );
+        let mut args = Vec::new();
+        cli.push_prompt(&mut args, "go".to_string());
+        assert_eq!(args, vec!["--prompt", "go"]);
+        assert_eq!(cli.cli_config().env.unwrap()["ACME_TOKEN"], "${secret:acme-token}");
+    }
+
+    #[test]
+    fn test_load_dir_skips_invalid_files() {
+        let dir = tempfile::TempDir::new().unwrap();
+        std::fs::write(dir.path().join("acme.toml"), "command = \"acme-agent\"").unwrap();
+        std::fs::write(dir.path().join("broken.toml"), "command = 3").unwrap();
+        std::fs::write(dir.path().join("notes.txt"), "not a definition").unwrap();
+
+        let (clis, errors) = load_dir(dir.path());
+        assert_eq!(clis.len(), 1);
+        assert_eq!(clis[0].name, "acme");
+        assert_eq!(clis[0].behavior, BehaviorProfile::ActionProne);
+        assert_eq!(errors.len(), 1);
+        assert!(errors[0].to_string().starts_with("broken: "));
+
+        assert!(load_dir(&dir.path().join("missing")).0.is_empty());
+    }
+
+    #[test]
+    fn test_parse_rejects_invalid_definitions() {
+        let invalid = [
+            ("claude", "command = \"claude\""),
+            ("Acme", "command = \"acme\""),
+            ("acme", "command = \"  \""),
+            ("acme", "command = \"acme\"\nflags = [\"\"]"),
+            ("acme", "command = \"acme\"\n[prompt]\nmax_inline_bytes = 0"),
+            ("acme", "command = \"acme\"\nbehaviour = \"interactive\""),
+        ];
+        for (name, source) in invalid {
+            assert!(
+                CustomCli::parse(name, source).is_err(),
+                "{name}: {source:?} should be rejected"
+            );
+        }
+    }
+}
@@ -8,18 +8,20 @@ use std::time::Duration;
 use tokio::io::{AsyncRead, AsyncReadExt};
 use tokio::process::Command;
 
+use super::custom;
 use crate::adapters::VALID_CLIS;
+use crate::session::QueuedLaunchConfig;
+use crate::workspace::paths::{home_dir, WslTarget};
 
 const AUTH_PROBE_TIMEOUT: Duration = Duration::from_secs(3);
 const CURSOR_PROBE_TIMEOUT: Duration = Duration::from_secs(3);
 const PROBE_OUTPUT_CAP: usize = 16 * 1024;
 const PROBE_DETAIL_MAX_CHARS: usize = 240;
+const GH_LABEL: &str = "GitHub CLI";
 #[cfg(windows)]
 const REGISTRY_QUERY_TIMEOUT: Duration = Duration::from_secs(2);
 #[cfg(windows)]
 const REGISTRY_OUTPUT_CAP: usize = 64 * 1024;
-const DEFAULT_WSL_DISTRO: &str = "Ubuntu";
-const DEFAULT_WSL_BINARY_PATH: &str = "/root/.local/bin/agent";
 
 #[derive(Debug, Clone, Copy, Serialize, PartialEq, Eq)]
 #[serde(rename_all = "lowercase")]
@@ -45,22 +47,87 @@ pub struct CliHealthResponse {
     pub clis: Vec<CliHealth>,
 }
 
+/// Whether the CLIs a launch spawns can start agents without stopping at a login
+/// screen.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PreflightReport {
+    /// The launch's CLIs, then `gh`.
+    pub clis: Vec<CliHealth>,
+    /// CLIs that are missing or logged out. Their agents would never start work.
+    pub blockers: Vec<String>,
+    /// CLIs whose login could not be confirmed, and `gh` problems, which only
+    /// matter once a pull request is opened.
+    pub warnings: Vec<String>,
+}
+
+impl PreflightReport {
+    fn from_health(mut clis: Vec<CliHealth>, gh: CliHealth) -> Self {
+        let mut blockers = Vec::new();
+        let mut warnings = Vec::new();
+        for health in &clis {
+            let problem = format!("{}: {}", health.cli, health.detail);
+            if !health.resolved || health.logged_in == LoginStatus::No {
+                blockers.push(problem);
+            } else if health.logged_in == LoginStatus::Unknown {
+                warnings.push(problem);
+            }
+        }
+        if !gh.resolved || gh.logged_in == LoginStatus::No {
+            warnings.push(format!("gh: {} (needed to open pull requests)", gh.detail));
+        }
+        clis.push(gh);
+        Self {
+            clis,
+            blockers,
+            warnings,
+        }
+    }
+
+    pub fn is_blocked(&self) -> bool {
+        !self.blockers.is_empty()
+    }
+}
+
 pub struct CliHealthRegistry;
 
 impl CliHealthRegistry {
     pub async fn check_all() -> CliHealthResponse {
         let refreshed_path = refreshed_windows_path().await;
+        let custom = custom::all();
         let checks = VALID_CLIS
             .iter()
             .copied()
+            .chain(custom.iter().map(|cli| cli.name.as_str()))
             .map(|cli| Self::check_cli(cli, refreshed_path.as_deref()));
         CliHealthResponse {
             clis: join_all(checks).await,
         }
     }
 
+    /// Check the CLIs a launch will spawn, and `gh`.
+    pub async fn preflight(clis: &[String]) -> PreflightReport {
+        let refreshed_path = refreshed_windows_path().await;
+        let checks = clis
+            .iter()
+            .map(|cli| Self::check_cli(cli, refreshed_path.as_deref()));
+        let (clis, gh) =
+            futures::join!(join_all(checks), Self::check_gh(refreshed_path.as_deref()));
+        PreflightReport::from_health(clis, gh)
+    }
+
+    async fn check_gh(refreshed_path: Option<&OsStr>) -> CliHealth {
+        let Some(bin_path) = resolve_executable("gh") else {
+            let stale_path = resolve_from_refreshed_path("gh", refreshed_path);
+            return unresolved_cli_health("gh", "gh", stale_path);
+        };
+        let (logged_in, detail) = probe_login(&bin_path, &["auth", "status"], GH_LABEL).await;
+        resolved_cli_health("gh", bin_path, logged_in, detail)
+    }
+
     async fn check_cli(cli: &str, refreshed_path: Option<&OsStr>) -> CliHealth {
         let binary = executable_for_cli(cli);
+        let binary = binary.as_str();
         let binary_label = if cli == "cursor" { "WSL" } else { binary };
         let Some(bin_path) = resolve_executable(binary) else {
             let stale_path = resolve_from_refreshed_path(binary, refreshed_path);
@@ -72,6 +139,7 @@ impl CliHealthRegistry {
         }
 
         let (logged_in, detail) = match cli {
+            "claude" => claude_login_status(),
             "codex" => probe_login(&bin_path, &["login", "status"], "Codex").await,
             _ => (
                 LoginStatus::Unknown,
@@ -130,22 +198,87 @@ pub async fn get_cli_health_http() -> Json<CliHealthResponse> {
     Json(CliHealthRegistry::check_all().await)
 }
 
-fn executable_for_cli(cli: &str) -> &str {
+/// Claude Code has no non-interactive login probe (`/status` needs an interactive
+/// session), so look for the credentials it would start with instead.
+fn claude_login_status() -> (LoginStatus, String) {
+    let config_dir = std::env::var_os("CLAUDE_CONFIG_DIR")
+        .filter(|dir| !dir.is_empty())
+        .map(PathBuf::from)
+        .or_else(|| home_dir().map(|home| home.join(".claude")));
+    classify_claude_login(
+        |name| std::env::var_os(name).filter(|value| !value.is_empty()),
+        config_dir.as_deref(),
+        cfg!(target_os = "macos"),
+    )
+}
+
+fn classify_claude_login(
+    env: impl Fn(&str) -> Option<OsString>,
+    config_dir: Option<&Path>,
+    uses_keychain: bool,
+) -> (LoginStatus, String) {
+    for var in ["ANTHROPIC_API_KEY", "CLAUDE_CODE_OAUTH_TOKEN"] {
+        if env(var).is_some() {
+            return (LoginStatus::Yes, format!("Claude authenticates with {var}"));
+        }
+    }
+    for (var, provider) in [
+        ("CLAUDE_CODE_USE_BEDROCK", "Bedrock"),
+        ("CLAUDE_CODE_USE_VERTEX", "Vertex AI"),
+    ] {
+        if env(var).is_some() {
+            return (
+                LoginStatus::Unknown,
+                format!("Claude uses {provider}; its cloud credentials are not checked"),
+            );
+        }
+    }
+    if let Some(credentials) = config_dir
+        .map(|dir| dir.join(".credentials.json"))
+        .filter(|path| path.is_file())
+    {
+        return (
+            LoginStatus::Yes,
+            format!("Claude credentials found at {}", credentials.display()),
+        );
+    }
+    if uses_keychain {
+        (
+            LoginStatus::Unknown,
+            "Claude keeps its login in the macOS keychain, which is not checked".to_string(),
+        )
+    } else {
+        (
+            LoginStatus::No,
+            "No Claude login found; run `claude` and use /login".to_string(),
+        )
+    }
+}
+
+/// `preflight_check` - CLI readiness for a launch config, before launching it.
+#[tauri::command]
+pub async fn preflight_check(config: QueuedLaunchConfig) -> PreflightReport {
+    CliHealthRegistry::preflight(&config.clis()).await
+}
+
+pub async fn preflight_check_http(Json(config): Json<QueuedLaunchConfig>) -> Json<PreflightReport> {
+    Json(CliHealthRegistry::preflight(&config.clis()).await)
+}
+
+fn executable_for_cli(cli: &str) -> String {
     match cli {
-        "cursor" => "wsl",
-        _ => cli,
+        "cursor" => "wsl".to_string(),
+        _ => custom::get(cli)
+            .map(|custom| custom.command)
+            .unwrap_or_else(|| cli.to_string()),
     }
 }
 
 async fn check_cursor_health(cli: &str, wsl_path: PathBuf) -> CliHealth {
-    let distro = std::env::var("HIVE_WSL_DISTRO")
-        .ok()
-        .filter(|value| !value.trim().is_empty())
-        .unwrap_or_else(|| DEFAULT_WSL_DISTRO.to_string());
-    let binary_path = std::env::var("HIVE_WSL_BINARY_PATH")
-        .ok()
-        .filter(|value| !value.trim().is_empty())
-        .unwrap_or_else(|| DEFAULT_WSL_BINARY_PATH.to_string());
+    let WslTarget {
+        distro,
+        binary_path,
+    } = WslTarget::from_env();
     let args = [
         "-d".to_string(),
         distro.clone(),
@@ -265,6 +398,13 @@ fn classify_completed_login_probe(
         return (LoginStatus::No, "Not logged in".to_string());
     }
 
+    if label == GH_LABEL
+        && exit_code == Some(1)
+        && output.to_ascii_lowercase().contains("not logged in")
+    {
+        return (LoginStatus::No, output);
+    }
+
     let exit_label = exit_code
         .map(|code| format!("code {code}"))
         .unwrap_or_else(|| "an unknown status".to_string());
@@ -475,7 +615,7 @@ fn bounded_probe_detail(detail: String) -> String {
     detail.chars().take(PROBE_DETAIL_MAX_CHARS).collect()
 }
 
-fn resolve_executable(executable: &str) -> Option<PathBuf> {
+pub(crate) fn resolve_executable(executable: &str) -> Option<PathBuf> {
     let path = std::env::var_os("PATH")?;
     let pathext = std::env::var_os("PATHEXT");
     resolve_executable_in_path(executable, &path, pathext.as_deref())
@@ -850,6 +990,81 @@ mod tests {
         assert!(detail.contains("program missing"));
     }
 
+    #[test]
+    fn gh_login_probe_reports_logged_out_hosts_as_no() {
+        let empty = captured(b"");
+        let logged_out =
+            captured(b"You are not logged into any GitHub hosts. To log in, run: gh auth login\n");
+        let (status, detail) =
+            classify_completed_login_probe(false, Some(1), &empty, &logged_out, GH_LABEL);
+        assert_eq!(status, LoginStatus::No);
+        assert!(detail.starts_with("You are not logged into any GitHub hosts"));
+
+        let (status, _) = classify_completed_login_probe(false, Some(4), &empty, &empty, GH_LABEL);
+        assert_eq!(status, LoginStatus::Unknown);
+    }
+
+    #[test]
+    fn claude_login_is_read_from_env_and_credentials() {
+        let config_dir = tempfile::tempdir().expect("claude config dir");
+        let no_env = |_: &str| None;
+
+        let (status, _) = classify_claude_login(no_env, Some(config_dir.path()), false);
+        assert_eq!(status, LoginStatus::No);
+        let (status, _) = classify_claude_login(no_env, Some(config_dir.path()), true);
+        assert_eq!(status, LoginStatus::Unknown);
+
+        let api_key = |name: &str| (name == "ANTHROPIC_API_KEY").then(|| OsString::from("sk"));
+        let (status, detail) = classify_claude_login(api_key, None, false);
+        assert_eq!(status, LoginStatus::Yes);
+        assert!(detail.contains("ANTHROPIC_API_KEY"));
+
+        std::fs::write(config_dir.path().join(".credentials.json"), "{}")
+            .expect("write credentials");
+        let (status, _) = classify_claude_login(no_env, Some(config_dir.path()), false);
+        assert_eq!(status, LoginStatus::Yes);
+    }
+
+    #[test]
+    fn preflight_blocks_on_missing_or_logged_out_clis_and_only_warns_about_gh() {
+        let report = PreflightReport::from_health(
+            vec![
+                resolved_cli_health(
+                    "claude",
+                    PathBuf::from("claude"),
+                    LoginStatus::Yes,
+                    "ok".to_string(),
+                ),
+                resolved_cli_health(
+                    "codex",
+                    PathBuf::from("codex"),
+                    LoginStatus::No,
+                    "Not logged in".to_string(),
+                ),
+                resolved_cli_health(
+                    "droid",
+                    PathBuf::from("droid"),
+                    LoginStatus::Unknown,
+                    "no probe".to_string(),
+                ),
+                unresolved_cli_health("qwen", "qwen", None),
+            ],
+            unresolved_cli_health("gh", "gh", None),
+        );
+        assert!(report.is_blocked());
+        assert_eq!(
+            report.blockers,
+            vec![
+                "codex: Not logged in".to_string(),
+                "qwen: qwen was not found on the current PATH".to_string(),
+            ]
+        );
+        assert_eq!(report.warnings.len(), 2);
+        assert!(report.warnings[0].starts_with("droid:"));
+        assert!(report.warnings[1].starts_with("gh:"));
+        assert_eq!(report.clis.last().map(|gh| gh.cli.as_str()), Some("gh"));
+    }
+
     #[cfg(any(unix, windows))]
     fn synthetic_exit_status(code: i32) -> ExitStatus {
         #[cfg(unix)]
@@ -1,4 +1,5 @@
 // CLI registry module - infrastructure for future CLI management features
+pub mod custom;
 pub mod health;
 mod registry;
 
@@ -1,9 +1,13 @@
 use std::collections::HashMap;
+use std::path::Path;
 
+use crate::adapters::{get_adapter, SubmitKey};
 use crate::domain::{CapabilityCard, CapabilitySupport, DelegationPolicy, NativeDelegationMode};
 use crate::pty::AgentConfig;
 use crate::storage::{AppConfig, CliConfig};
 
+use super::custom;
+
 /// CLI behavioral profiles for characterizing how different CLI tools behave
 #[derive(Debug, Clone, PartialEq)]
 pub enum CliBehavior {
@@ -23,11 +27,34 @@ pub struct CliRegistry {
 }
 
 impl CliRegistry {
-    /// Create a new CLI registry with the given config
-    pub fn new(config: AppConfig) -> Self {
+    /// Create a new CLI registry with the given config. CLIs defined in
+    /// `clis/*.toml` are added unless `config.json` already names them.
+    pub fn new(mut config: AppConfig) -> Self {
+        for cli in custom::all() {
+            config
+                .clis
+                .entry(cli.name.clone())
+                .or_insert_with(|| cli.cli_config());
+        }
         Self { config }
     }
 
+    /// Load the CLI definitions in `dir` (`clis/` under the app data dir),
+    /// replacing any loaded before. Invalid files are logged and skipped.
+    /// Returns the names of the CLIs now defined.
+    pub fn load_custom_clis(dir: &Path) -> Vec<String> {
+        let (clis, errors) = custom::load_dir(dir);
+        for error in errors {
+            tracing::warn!("Skipping custom CLI definition {}", error);
+        }
+        let names: Vec<String> = clis.iter().map(|cli| cli.name.clone()).collect();
+        if !names.is_empty() {
+            tracing::info!("Loaded custom CLIs from {}: {}", dir.display(), names.join(", "));
+        }
+        custom::install(clis);
+        names
+    }
+
     /// Get CLI configuration for a specific CLI name
     pub fn get_cli(&self, name: &str) -> Option<&CliConfig> {
         self.config.clis.get(name)
@@ -134,6 +161,50 @@ impl CliRegistry {
         }
     }
 
+    /// Largest prompt, in bytes, a CLI reliably receives as an inline argument.
+    ///
+    /// Windows caps a whole command line at 32,767 UTF-16 units and some CLIs
+    /// truncate their initial prompt well before that, silently. Cursor is
+    /// relayed through `wsl`, which re-quotes every argument, so it gets the
+    /// tightest budget. Unprofiled CLIs get the conservative default.
+    pub fn max_inline_prompt_bytes(cli: &str) -> usize {
+        match cli {
+            "claude" | "codex" => 24 * 1024,
+            "opencode" | "qwen" | "droid" => 16 * 1024,
+            "cursor" => 8 * 1024,
+            _ => custom::by_command(cli)
+                .or_else(|| custom::get(cli))
+                .and_then(|custom| custom.prompt.max_inline_bytes)
+                .unwrap_or(8 * 1024),
+        }
+    }
+
+    /// Key that submits text typed into a CLI's prompt. `cli` may also be the
+    /// command the CLI runs as (`wsl` for Cursor). A `submit_key` set for the CLI
+    /// in `config.json` wins, then one in its `clis/*.toml` definition, then the
+    /// CLI adapter's own; anything else gets Enter.
+    pub fn submit_key(config: Option<&AppConfig>, cli: &str) -> SubmitKey {
+        let configured = config.and_then(|config| {
+            config
+                .clis
+                .get(cli)
+                .or_else(|| config.clis.values().find(|entry| entry.command == cli))
+                .and_then(|entry| entry.submit_key)
+        });
+        let built_in = match cli {
+            "wsl" => "cursor",
+            cli => cli,
+        };
+        configured
+            .or_else(|| {
+                custom::get(cli)
+                    .or_else(|| custom::by_command(cli))
+                    .and_then(|custom| custom.submit_key)
+            })
+            .or_else(|| get_adapter(built_in).ok().map(|adapter| adapter.submit_key()))
+            .unwrap_or(SubmitKey::Enter)
+    }
+
     /// Normalize known legacy model aliases at the CLI launch boundary.
     ///
     /// Older Hive Manager builds persisted `gpt-5.6`, while Codex sessions
@@ -228,7 +299,7 @@ impl CliRegistry {
 
     /// Update the config
     pub fn update_config(&mut self, config: AppConfig) {
-        self.config = config;
+        *self = Self::new(config);
     }
 
     /// Get the behavioral profile for a CLI
@@ -241,7 +312,9 @@ impl CliRegistry {
             // they need the same durable activation loop as OpenCode.
             "codex" | "opencode" => CliBehavior::ExplicitPolling,
             "droid" | "cursor" => CliBehavior::Interactive,
-            _ => CliBehavior::ActionProne, // Default to most constrained
+            _ => custom::get(cli)
+                .map(|custom| custom.behavior.into())
+                .unwrap_or(CliBehavior::ActionProne), // Default to most constrained
         }
     }
 
@@ -300,6 +373,9 @@ mod tests {
                 model_flag: Some("--model".to_string()),
                 default_model: "opus".to_string(),
                 env: None,
+                submit_key: None,
+                path_prepend: Vec::new(),
+                env_allowlist: None,
             },
         );
         clis.insert(
@@ -310,6 +386,9 @@ mod tests {
                 model_flag: None, // Cursor uses global model setting
                 default_model: "composer-2.5".to_string(),
                 env: None,
+                submit_key: None,
+                path_prepend: Vec::new(),
+                env_allowlist: None,
             },
         );
         clis.insert(
@@ -320,6 +399,9 @@ mod tests {
                 model_flag: None,        // Model selected via /model command in TUI
                 default_model: "glm-5.1".to_string(),
                 env: None,
+                submit_key: None,
+                path_prepend: Vec::new(),
+                env_allowlist: None,
             },
         );
         clis.insert(
@@ -330,6 +412,9 @@ mod tests {
                 model_flag: Some("-m".to_string()),
                 default_model: "qwen3-coder".to_string(),
                 env: None,
+                submit_key: None,
+                path_prepend: Vec::new(),
+                env_allowlist: None,
             },
         );
         clis.insert(
@@ -340,6 +425,9 @@ mod tests {
                 model_flag: Some("-m".to_string()),
                 default_model: "gpt-5.6-sol".to_string(),
                 env: None,
+                submit_key: None,
+                path_prepend: Vec::new(),
+                env_allowlist: None,
             },
         );
         clis.insert(
@@ -354,6 +442,9 @@ mod tests {
                     env.insert("OPENCODE_YOLO".to_string(), "true".to_string());
                     env
                 }),
+                submit_key: None,
+                path_prepend: Vec::new(),
+                env_allowlist: None,
             },
         );
 
@@ -363,9 +454,29 @@ mod tests {
             api: crate::storage::ApiConfig {
                 enabled: true,
                 port: 18800,
+                offline: false,
+                socket_path: None,
             },
             global_wiki_path: None,
             knowledge_wiki_folders: None,
+            checkpoints: Default::default(),
+            approvals: Default::default(),
+            grpc: Default::default(),
+            concurrency: Default::default(),
+            preflight: Default::default(),
+            diff_budget: Default::default(),
+            locale: None,
+            project_dna: Default::default(),
+            file_watch: Default::default(),
+            context_monitor: Default::default(),
+            fusion_worktrees: Default::default(),
+            exclude_session_artifacts: true,
+            auto_complete: Default::default(),
+            hooks: Vec::new(),
+            inbox_digest: Default::default(),
+            editor: Default::default(),
+            snapshots: Default::default(),
+            warm_pool: Default::default(),
         }
     }
 
@@ -381,6 +492,8 @@ mod tests {
             description: None,
             role: None,
             initial_prompt: None,
+            allowed_paths: Vec::new(),
+            denied_paths: Vec::new(),
         };
 
         let built = registry.build_command(&config).unwrap();
@@ -404,6 +517,8 @@ mod tests {
             description: None,
             role: None,
             initial_prompt: None,
+            allowed_paths: Vec::new(),
+            denied_paths: Vec::new(),
         };
 
         let built = registry
@@ -425,6 +540,8 @@ mod tests {
             description: None,
             role: None,
             initial_prompt: None,
+            allowed_paths: Vec::new(),
+            denied_paths: Vec::new(),
         };
 
         let built = registry.build_command(&config).unwrap();
@@ -444,6 +561,8 @@ mod tests {
             description: None,
             role: None,
             initial_prompt: None,
+            allowed_paths: Vec::new(),
+            denied_paths: Vec::new(),
         };
 
         let built = registry.build_command(&config).unwrap();
@@ -465,6 +584,8 @@ mod tests {
             description: None,
             role: None,
             initial_prompt: None,
+            allowed_paths: Vec::new(),
+            denied_paths: Vec::new(),
         };
 
         let built = registry.build_command(&config).unwrap();
@@ -486,6 +607,8 @@ mod tests {
             description: None,
             role: None,
             initial_prompt: None,
+            allowed_paths: Vec::new(),
+            denied_paths: Vec::new(),
         };
 
         let built = registry.build_command(&config).unwrap();
@@ -509,6 +632,8 @@ mod tests {
             description: None,
             role: None,
             initial_prompt: None,
+            allowed_paths: Vec::new(),
+            denied_paths: Vec::new(),
         };
 
         let built = registry.build_command(&config).unwrap();
@@ -696,6 +821,8 @@ mod tests {
                 description: None,
                 role: None,
                 initial_prompt: None,
+                allowed_paths: Vec::new(),
+                denied_paths: Vec::new(),
             };
             assert!(
                 matches!(
@@ -706,4 +833,30 @@ mod tests {
             );
         }
     }
+
+    #[test]
+    fn test_max_inline_prompt_bytes_is_tightest_for_relayed_and_unknown_clis() {
+        assert_eq!(CliRegistry::max_inline_prompt_bytes("claude"), 24 * 1024);
+        assert!(
+            CliRegistry::max_inline_prompt_bytes("cursor")
+                < CliRegistry::max_inline_prompt_bytes("opencode")
+        );
+        assert_eq!(
+            CliRegistry::max_inline_prompt_bytes("unknown-cli"),
+            CliRegistry::max_inline_prompt_bytes("cursor")
+        );
+    }
+
+    #[test]
+    fn test_submit_key_prefers_config_then_falls_back_to_the_adapter() {
+        let mut config = test_config();
+        config.clis.get_mut("cursor").unwrap().submit_key = Some(SubmitKey::CtrlJ);
+
+        assert_eq!(CliRegistry::submit_key(Some(&config), "cursor"), SubmitKey::CtrlJ);
+        assert_eq!(CliRegistry::submit_key(Some(&config), "wsl"), SubmitKey::CtrlJ);
+        assert_eq!(CliRegistry::submit_key(None, "wsl"), SubmitKey::Enter);
+        assert_eq!(CliRegistry::submit_key(Some(&config), "claude"), SubmitKey::Enter);
+        assert_eq!(CliRegistry::submit_key(None, "unknown-cli"), SubmitKey::Enter);
+        assert_eq!(SubmitKey::DoubleEnter.bytes(), b"\r\r");
+    }
 }
@@ -6,15 +6,22 @@ use serde_json::json;
 use tauri::State;
 
 use crate::actions::{ActionContext, ActionRegistry, Caller};
-use crate::coordination::{CoordinationMessage, InjectionManager, WorkerStateInfo};
+use crate::error::HiveError;
+use crate::coordination::{
+    ApprovalRequest, BroadcastReport, CoordinationMessage, InjectionManager, MessageDelivery,
+    WorkerStateInfo,
+};
 use crate::http::state::AppState;
-use crate::session::AgentInfo;
+use crate::session::plan_history::PlanDiff;
+use crate::session::plan_parser::Plan;
+use crate::session::{AgentInfo, OperatorQuestion};
 use crate::storage::SessionStorage;
+use crate::templates::{PromptTemplate, PromptTemplateInfo};
 
 #[allow(unused_imports)]
 pub use crate::actions::coordination::{
-    AddWorkerRequest, OperatorInjectRequest, PlanTask, QueenInjectRequest, SessionPlan,
-    WorkerStatusRequest,
+    AddWorkerRequest, OperatorInjectRequest, PlanTask, ProfileList, QueenInjectRequest,
+    SessionPlan, WorkerStatusRequest,
 };
 
 /// State wrapper for coordination.
@@ -30,13 +37,13 @@ async fn dispatch_coordination<T: DeserializeOwned>(
     state: Arc<AppState>,
     name: &str,
     input: serde_json::Value,
-) -> Result<T, String> {
+) -> Result<T, HiveError> {
     let ctx = ActionContext::new(Caller::Frontend, state);
     let value = registry
         .dispatch(name, &ctx, input)
         .await
-        .map_err(|e| e.to_message())?;
-    serde_json::from_value(value).map_err(|e| e.to_string())
+        .map_err(HiveError::from)?;
+    Ok(serde_json::from_value(value)?)
 }
 
 #[tauri::command]
@@ -44,7 +51,7 @@ pub async fn queen_inject(
     registry: State<'_, Arc<ActionRegistry>>,
     app_state: State<'_, Arc<AppState>>,
     request: QueenInjectRequest,
-) -> Result<(), String> {
+) -> Result<(), HiveError> {
     dispatch_coordination(
         &registry,
         Arc::clone(&app_state),
@@ -61,7 +68,7 @@ pub async fn queen_switch_branch(
     session_id: String,
     queen_id: String,
     branch: String,
-) -> Result<Vec<(String, bool)>, String> {
+) -> Result<Vec<(String, bool)>, HiveError> {
     dispatch_coordination(
         &registry,
         Arc::clone(&app_state),
@@ -80,7 +87,7 @@ pub async fn operator_inject(
     registry: State<'_, Arc<ActionRegistry>>,
     app_state: State<'_, Arc<AppState>>,
     request: OperatorInjectRequest,
-) -> Result<(), String> {
+) -> Result<(), HiveError> {
     dispatch_coordination(
         &registry,
         Arc::clone(&app_state),
@@ -90,13 +97,120 @@ pub async fn operator_inject(
     .await
 }
 
+/// Inject `message` into every running agent of the session at once.
+#[tauri::command]
+pub async fn broadcast_to_session(
+    registry: State<'_, Arc<ActionRegistry>>,
+    app_state: State<'_, Arc<AppState>>,
+    session_id: String,
+    message: String,
+) -> Result<BroadcastReport, HiveError> {
+    dispatch_coordination(
+        &registry,
+        Arc::clone(&app_state),
+        "coordination.broadcast_to_session",
+        json!({ "session_id": session_id, "message": message }),
+    )
+    .await
+}
+
+/// Questions agents in a session asked the operator, oldest first.
+#[tauri::command]
+pub async fn list_operator_questions(
+    registry: State<'_, Arc<ActionRegistry>>,
+    app_state: State<'_, Arc<AppState>>,
+    session_id: String,
+) -> Result<Vec<OperatorQuestion>, HiveError> {
+    dispatch_coordination(
+        &registry,
+        Arc::clone(&app_state),
+        "coordination.list_questions",
+        json!({ "session_id": session_id }),
+    )
+    .await
+}
+
+/// Answer an agent's question; the answer is typed into the agent's terminal.
+#[tauri::command]
+pub async fn answer_operator_question(
+    registry: State<'_, Arc<ActionRegistry>>,
+    app_state: State<'_, Arc<AppState>>,
+    session_id: String,
+    question_id: String,
+    answer: String,
+) -> Result<OperatorQuestion, HiveError> {
+    dispatch_coordination(
+        &registry,
+        Arc::clone(&app_state),
+        "coordination.answer_question",
+        json!({
+            "session_id": session_id,
+            "question_id": question_id,
+            "answer": answer,
+        }),
+    )
+    .await
+}
+
+/// Agent requests parked until the operator approves them; every session's when
+/// `session_id` is omitted.
+#[tauri::command]
+pub async fn list_pending_approvals(
+    registry: State<'_, Arc<ActionRegistry>>,
+    app_state: State<'_, Arc<AppState>>,
+    session_id: Option<String>,
+) -> Result<Vec<ApprovalRequest>, HiveError> {
+    dispatch_coordination(
+        &registry,
+        Arc::clone(&app_state),
+        "coordination.list_approvals",
+        json!({ "session_id": session_id }),
+    )
+    .await
+}
+
+/// Let a parked request go ahead (`approved`) or fail it with a 403.
+#[tauri::command]
+pub async fn approve_action(
+    registry: State<'_, Arc<ActionRegistry>>,
+    app_state: State<'_, Arc<AppState>>,
+    approval_id: String,
+    approved: bool,
+) -> Result<ApprovalRequest, HiveError> {
+    dispatch_coordination(
+        &registry,
+        Arc::clone(&app_state),
+        "coordination.approve_action",
+        json!({ "approval_id": approval_id, "approved": approved }),
+    )
+    .await
+}
+
+/// TASK messages a worker has not acknowledged yet; every worker's when
+/// `agent_id` is omitted.
+#[tauri::command]
+pub async fn list_unacknowledged_messages(
+    registry: State<'_, Arc<ActionRegistry>>,
+    app_state: State<'_, Arc<AppState>>,
+    session_id: String,
+    agent_id: Option<String>,
+) -> Result<Vec<MessageDelivery>, HiveError> {
+    dispatch_coordination(
+        &registry,
+        Arc::clone(&app_state),
+        "coordination.list_unacknowledged",
+        json!({ "session_id": session_id, "agent_id": agent_id }),
+    )
+    .await
+}
+
 #[allow(dead_code)]
 #[tauri::command]
 pub async fn report_worker_status(
     registry: State<'_, Arc<ActionRegistry>>,
     app_state: State<'_, Arc<AppState>>,
     request: WorkerStatusRequest,
-) -> Result<(), String> {
+) -> Result<(), HiveError> {
     dispatch_coordination(
         &registry,
         Arc::clone(&app_state),
@@ -111,7 +225,7 @@ pub async fn add_worker_to_session(
     registry: State<'_, Arc<ActionRegistry>>,
     app_state: State<'_, Arc<AppState>>,
     request: AddWorkerRequest,
-) -> Result<AgentInfo, String> {
+) -> Result<AgentInfo, HiveError> {
     dispatch_coordination(
         &registry,
         Arc::clone(&app_state),
@@ -127,12 +241,13 @@ pub async fn get_coordination_log(
     app_state: State<'_, Arc<AppState>>,
     session_id: String,
     limit: Option<usize>,
-) -> Result<Vec<CoordinationMessage>, String> {
+    after_seq: Option<u64>,
+) -> Result<Vec<CoordinationMessage>, HiveError> {
     dispatch_coordination(
         &registry,
         Arc::clone(&app_state),
         "coordination.get_log",
-        json!({ "session_id": session_id, "limit": limit }),
+        json!({ "session_id": session_id, "limit": limit, "after_seq": after_seq }),
     )
     .await
 }
@@ -145,7 +260,8 @@ pub async fn log_coordination_message(
     from: String,
     to: String,
     content: String,
-) -> Result<(), String> {
+    attachments: Option<Vec<String>>,
+) -> Result<(), HiveError> {
     dispatch_coordination(
         &registry,
         Arc::clone(&app_state),
@@ -155,6 +271,7 @@ pub async fn log_coordination_message(
             "from": from,
             "to": to,
             "content": content,
+            "attachments": attachments.unwrap_or_default(),
         }),
     )
     .await
@@ -165,7 +282,7 @@ pub async fn get_workers_state(
     registry: State<'_, Arc<ActionRegistry>>,
     app_state: State<'_, Arc<AppState>>,
     session_id: String,
-) -> Result<Vec<WorkerStateInfo>, String> {
+) -> Result<Vec<WorkerStateInfo>, HiveError> {
     dispatch_coordination(
         &registry,
         Arc::clone(&app_state),
@@ -184,7 +301,7 @@ pub async fn assign_task(
     worker_id: String,
     task: String,
     plan_task_id: Option<String>,
-) -> Result<(), String> {
+) -> Result<(), HiveError> {
     dispatch_coordination(
         &registry,
         Arc::clone(&app_state),
@@ -205,7 +322,7 @@ pub async fn get_session_storage_path(
     registry: State<'_, Arc<ActionRegistry>>,
     app_state: State<'_, Arc<AppState>>,
     session_id: String,
-) -> Result<String, String> {
+) -> Result<String, HiveError> {
     dispatch_coordination(
         &registry,
         Arc::clone(&app_state),
@@ -219,7 +336,7 @@ pub async fn get_session_storage_path(
 pub async fn get_current_directory(
     registry: State<'_, Arc<ActionRegistry>>,
     app_state: State<'_, Arc<AppState>>,
-) -> Result<String, String> {
+) -> Result<String, HiveError> {
     dispatch_coordination(
         &registry,
         Arc::clone(&app_state),
@@ -229,12 +346,26 @@ pub async fn get_current_directory(
     .await
 }
 
+#[tauri::command]
+pub async fn get_instance_info(
+    registry: State<'_, Arc<ActionRegistry>>,
+    app_state: State<'_, Arc<AppState>>,
+) -> Result<crate::actions::coordination::InstanceInfo, HiveError> {
+    dispatch_coordination(
+        &registry,
+        Arc::clone(&app_state),
+        "coordination.get_instance_info",
+        json!({}),
+    )
+    .await
+}
+
 #[tauri::command]
 pub async fn list_stored_sessions(
     registry: State<'_, Arc<ActionRegistry>>,
     app_state: State<'_, Arc<AppState>>,
     project_path: Option<String>,
-) -> Result<Vec<crate::storage::SessionSummary>, String> {
+) -> Result<Vec<crate::storage::SessionSummary>, HiveError> {
     dispatch_coordination(
         &registry,
         Arc::clone(&app_state),
@@ -244,11 +375,27 @@ pub async fn list_stored_sessions(
     .await
 }
 
+#[tauri::command]
+pub async fn import_legacy_learnings(
+    registry: State<'_, Arc<ActionRegistry>>,
+    app_state: State<'_, Arc<AppState>>,
+    project_path: String,
+    session_id: Option<String>,
+) -> Result<crate::storage::LegacyLearningsImport, HiveError> {
+    dispatch_coordination(
+        &registry,
+        Arc::clone(&app_state),
+        "coordination.import_legacy_learnings",
+        json!({ "project_path": project_path, "session_id": session_id }),
+    )
+    .await
+}
+
 #[tauri::command]
 pub async fn get_app_config(
     registry: State<'_, Arc<ActionRegistry>>,
     app_state: State<'_, Arc<AppState>>,
-) -> Result<crate::storage::AppConfig, String> {
+) -> Result<crate::storage::AppConfig, HiveError> {
     dispatch_coordination(
         &registry,
         Arc::clone(&app_state),
@@ -263,7 +410,7 @@ pub async fn update_app_config(
     registry: State<'_, Arc<ActionRegistry>>,
     app_state: State<'_, Arc<AppState>>,
     config: crate::storage::AppConfig,
-) -> Result<(), String> {
+) -> Result<(), HiveError> {
     dispatch_coordination(
         &registry,
         Arc::clone(&app_state),
@@ -273,12 +420,135 @@ pub async fn update_app_config(
     .await
 }
 
+#[tauri::command]
+pub async fn list_profiles(
+    registry: State<'_, Arc<ActionRegistry>>,
+    app_state: State<'_, Arc<AppState>>,
+) -> Result<ProfileList, HiveError> {
+    dispatch_coordination(
+        &registry,
+        Arc::clone(&app_state),
+        "coordination.list_profiles",
+        json!({}),
+    )
+    .await
+}
+
+#[tauri::command]
+pub async fn switch_profile(
+    registry: State<'_, Arc<ActionRegistry>>,
+    app_state: State<'_, Arc<AppState>>,
+    profile: String,
+) -> Result<crate::storage::AppConfig, HiveError> {
+    dispatch_coordination(
+        &registry,
+        Arc::clone(&app_state),
+        "coordination.switch_profile",
+        json!({ "profile": profile }),
+    )
+    .await
+}
+
+#[tauri::command]
+pub async fn set_secret(
+    registry: State<'_, Arc<ActionRegistry>>,
+    app_state: State<'_, Arc<AppState>>,
+    name: String,
+    value: String,
+) -> Result<(), HiveError> {
+    dispatch_coordination(
+        &registry,
+        Arc::clone(&app_state),
+        "coordination.set_secret",
+        json!({ "name": name, "value": value }),
+    )
+    .await
+}
+
+#[tauri::command]
+pub async fn delete_secret(
+    registry: State<'_, Arc<ActionRegistry>>,
+    app_state: State<'_, Arc<AppState>>,
+    name: String,
+) -> Result<(), HiveError> {
+    dispatch_coordination(
+        &registry,
+        Arc::clone(&app_state),
+        "coordination.delete_secret",
+        json!({ "name": name }),
+    )
+    .await
+}
+
+/// Built-in and on-disk prompt templates with the placeholders each expects.
+#[tauri::command]
+pub async fn list_templates(
+    registry: State<'_, Arc<ActionRegistry>>,
+    app_state: State<'_, Arc<AppState>>,
+) -> Result<Vec<PromptTemplateInfo>, HiveError> {
+    dispatch_coordination(
+        &registry,
+        Arc::clone(&app_state),
+        "coordination.list_templates",
+        json!({}),
+    )
+    .await
+}
+
+#[tauri::command]
+pub async fn get_template(
+    registry: State<'_, Arc<ActionRegistry>>,
+    app_state: State<'_, Arc<AppState>>,
+    name: String,
+) -> Result<PromptTemplate, HiveError> {
+    dispatch_coordination(
+        &registry,
+        Arc::clone(&app_state),
+        "coordination.get_template",
+        json!({ "name": name }),
+    )
+    .await
+}
+
+/// Save a prompt override to the templates directory.
+#[tauri::command]
+pub async fn save_template(
+    registry: State<'_, Arc<ActionRegistry>>,
+    app_state: State<'_, Arc<AppState>>,
+    name: String,
+    content: String,
+) -> Result<PromptTemplate, HiveError> {
+    dispatch_coordination(
+        &registry,
+        Arc::clone(&app_state),
+        "coordination.save_template",
+        json!({ "name": name, "content": content }),
+    )
+    .await
+}
+
+/// Remove a prompt override, restoring the built-in template if there is one.
+#[tauri::command]
+pub async fn delete_template(
+    registry: State<'_, Arc<ActionRegistry>>,
+    app_state: State<'_, Arc<AppState>>,
+    name: String,
+) -> Result<(), HiveError> {
+    dispatch_coordination(
+        &registry,
+        Arc::clone(&app_state),
+        "coordination.delete_template",
+        json!({ "name": name }),
+    )
+    .await
+}
+
 #[tauri::command]
 pub async fn get_session_plan(
     registry: State<'_, Arc<ActionRegistry>>,
     app_state: State<'_, Arc<AppState>>,
     session_id: String,
-) -> Result<Option<SessionPlan>, String> {
+) -> Result<Option<SessionPlan>, HiveError> {
     dispatch_coordination(
         &registry,
         Arc::clone(&app_state),
@@ -287,3 +557,35 @@ pub async fn get_session_plan(
     )
     .await
 }
+
+#[tauri::command]
+pub async fn get_session_plan_structured(
+    registry: State<'_, Arc<ActionRegistry>>,
+    app_state: State<'_, Arc<AppState>>,
+    session_id: String,
+) -> Result<Option<Plan>, HiveError> {
+    dispatch_coordination(
+        &registry,
+        Arc::clone(&app_state),
+        "coordination.get_session_plan_structured",
+        json!({ "session_id": session_id }),
+    )
+    .await
+}
+
+#[tauri::command]
+pub async fn get_plan_diff(
+    registry: State<'_, Arc<ActionRegistry>>,
+    app_state: State<'_, Arc<AppState>>,
+    session_id: String,
+    rev_a: u32,
+    rev_b: u32,
+) -> Result<PlanDiff, HiveError> {
+    dispatch_coordination(
+        &registry,
+        Arc::clone(&app_state),
+        "coordination.get_plan_diff",
+        json!({ "session_id": session_id, "rev_a": rev_a, "rev_b": rev_b }),
+    )
+    .await
+}
@@ -11,27 +11,27 @@ use serde_json::json;
 use tauri::State;
 
 use crate::actions::{ActionContext, ActionRegistry, Caller};
+use crate::error::HiveError;
 use crate::http::state::AppState;
 
 // Re-export the git value types from the action module so any existing importer
 // of `commands::git_commands::{BranchInfo, WorktreeInfo}` keeps compiling.
 pub use crate::actions::git::{BranchInfo, WorktreeInfo};
 
-/// Dispatch a git action with `caller = Frontend`, surfacing the action's
-/// message string on error (matching prior `Result<_, String>` behavior), and
-/// deserializing the JSON output into the typed return.
+/// Dispatch a git action with `caller = Frontend`, surfacing the action's error
+/// with its code, and deserializing the JSON output into the typed return.
 async fn dispatch_git<T: serde::de::DeserializeOwned>(
     registry: &ActionRegistry,
     state: Arc<AppState>,
     name: &str,
     input: serde_json::Value,
-) -> Result<T, String> {
+) -> Result<T, HiveError> {
     let ctx = ActionContext::new(Caller::Frontend, state);
     let value = registry
         .dispatch(name, &ctx, input)
         .await
-        .map_err(|e| e.to_message())?;
-    serde_json::from_value(value).map_err(|e| e.to_string())
+        .map_err(HiveError::from)?;
+    Ok(serde_json::from_value(value)?)
 }
 
 #[tauri::command]
@@ -39,7 +39,7 @@ pub async fn list_branches(
     registry: State<'_, Arc<ActionRegistry>>,
     app_state: State<'_, Arc<AppState>>,
     project_path: String,
-) -> Result<Vec<BranchInfo>, String> {
+) -> Result<Vec<BranchInfo>, HiveError> {
     dispatch_git(
         &registry,
         Arc::clone(&app_state),
@@ -54,7 +54,7 @@ pub async fn get_current_branch(
     registry: State<'_, Arc<ActionRegistry>>,
     app_state: State<'_, Arc<AppState>>,
     project_path: String,
-) -> Result<String, String> {
+) -> Result<String, HiveError> {
     dispatch_git(
         &registry,
         Arc::clone(&app_state),
@@ -70,7 +70,7 @@ pub async fn switch_branch(
     app_state: State<'_, Arc<AppState>>,
     project_path: String,
     branch: String,
-) -> Result<(), String> {
+) -> Result<(), HiveError> {
     dispatch_git(
         &registry,
         Arc::clone(&app_state),
@@ -85,7 +85,7 @@ pub async fn git_pull(
     registry: State<'_, Arc<ActionRegistry>>,
     app_state: State<'_, Arc<AppState>>,
     project_path: String,
-) -> Result<String, String> {
+) -> Result<String, HiveError> {
     dispatch_git(
         &registry,
         Arc::clone(&app_state),
@@ -100,7 +100,7 @@ pub async fn git_push(
     registry: State<'_, Arc<ActionRegistry>>,
     app_state: State<'_, Arc<AppState>>,
     project_path: String,
-) -> Result<String, String> {
+) -> Result<String, HiveError> {
     dispatch_git(
         &registry,
         Arc::clone(&app_state),
@@ -115,7 +115,7 @@ pub async fn git_fetch(
     registry: State<'_, Arc<ActionRegistry>>,
     app_state: State<'_, Arc<AppState>>,
     project_path: String,
-) -> Result<String, String> {
+) -> Result<String, HiveError> {
     dispatch_git(
         &registry,
         Arc::clone(&app_state),
@@ -132,7 +132,7 @@ pub async fn git_worktree_add(
     project_path: String,
     worktree_path: String,
     branch: String,
-) -> Result<(), String> {
+) -> Result<(), HiveError> {
     dispatch_git(
         &registry,
         Arc::clone(&app_state),
@@ -151,7 +151,7 @@ pub async fn git_worktree_list(
     registry: State<'_, Arc<ActionRegistry>>,
     app_state: State<'_, Arc<AppState>>,
     project_path: String,
-) -> Result<Vec<WorktreeInfo>, String> {
+) -> Result<Vec<WorktreeInfo>, HiveError> {
     dispatch_git(
         &registry,
         Arc::clone(&app_state),
@@ -167,7 +167,7 @@ pub async fn git_worktree_remove(
     app_state: State<'_, Arc<AppState>>,
     project_path: String,
     worktree_path: String,
-) -> Result<(), String> {
+) -> Result<(), HiveError> {
     dispatch_git(
         &registry,
         Arc::clone(&app_state),
@@ -182,7 +182,7 @@ pub async fn git_worktree_prune(
     registry: State<'_, Arc<ActionRegistry>>,
     app_state: State<'_, Arc<AppState>>,
     project_path: String,
-) -> Result<(), String> {
+) -> Result<(), HiveError> {
     dispatch_git(
         &registry,
         Arc::clone(&app_state),
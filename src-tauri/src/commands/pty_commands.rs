@@ -5,8 +5,9 @@ use std::sync::Arc;
 use tauri::State;
 
 use crate::actions::{ActionContext, ActionRegistry, Caller};
+use crate::error::HiveError;
 use crate::http::state::AppState;
-use crate::pty::{AgentRole, AgentStatus, PtyManager};
+use crate::pty::{AgentRole, AgentStatus, PtyManager, SessionRecordings};
 
 #[allow(dead_code)]
 pub struct PtyManagerState(pub Arc<RwLock<PtyManager>>);
@@ -20,13 +21,13 @@ async fn dispatch_pty<T: DeserializeOwned>(
     state: Arc<AppState>,
     name: &str,
     input: serde_json::Value,
-) -> Result<T, String> {
+) -> Result<T, HiveError> {
     let ctx = ActionContext::new(Caller::Frontend, state);
     let value = registry
         .dispatch(name, &ctx, input)
         .await
-        .map_err(|e| e.to_message())?;
-    serde_json::from_value(value).map_err(|e| e.to_string())
+        .map_err(HiveError::from)?;
+    Ok(serde_json::from_value(value)?)
 }
 
 #[tauri::command]
@@ -42,7 +43,7 @@ pub async fn create_pty(
     role: Option<String>,
     shell: Option<String>,
     session_id: Option<String>,
-) -> Result<String, String> {
+) -> Result<String, HiveError> {
     dispatch_pty(
         &registry,
         Arc::clone(&app_state),
@@ -68,7 +69,7 @@ pub async fn write_to_pty(
     app_state: State<'_, Arc<AppState>>,
     id: String,
     data: String,
-) -> Result<(), String> {
+) -> Result<(), HiveError> {
     dispatch_pty(
         &registry,
         Arc::clone(&app_state),
@@ -84,7 +85,7 @@ pub async fn paste_to_pty(
     app_state: State<'_, Arc<AppState>>,
     id: String,
     data: String,
-) -> Result<(), String> {
+) -> Result<(), HiveError> {
     dispatch_pty(
         &registry,
         Arc::clone(&app_state),
@@ -102,7 +103,7 @@ pub async fn inject_to_pty(
     id: String,
     message: String,
     send_enter: bool,
-) -> Result<(), String> {
+) -> Result<(), HiveError> {
     dispatch_pty(
         &registry,
         Arc::clone(&app_state),
@@ -119,7 +120,7 @@ pub async fn resize_pty(
     id: String,
     cols: u16,
     rows: u16,
-) -> Result<(), String> {
+) -> Result<(), HiveError> {
     dispatch_pty(
         &registry,
         Arc::clone(&app_state),
@@ -134,7 +135,7 @@ pub async fn kill_pty(
     registry: State<'_, Arc<ActionRegistry>>,
     app_state: State<'_, Arc<AppState>>,
     id: String,
-) -> Result<(), String> {
+) -> Result<(), HiveError> {
     dispatch_pty(
         &registry,
         Arc::clone(&app_state),
@@ -149,7 +150,7 @@ pub async fn get_pty_status(
     registry: State<'_, Arc<ActionRegistry>>,
     app_state: State<'_, Arc<AppState>>,
     id: String,
-) -> Result<Option<AgentStatus>, String> {
+) -> Result<Option<AgentStatus>, HiveError> {
     dispatch_pty(
         &registry,
         Arc::clone(&app_state),
@@ -163,6 +164,38 @@ pub async fn get_pty_status(
 pub async fn list_ptys(
     registry: State<'_, Arc<ActionRegistry>>,
     app_state: State<'_, Arc<AppState>>,
-) -> Result<Vec<(String, AgentRole, AgentStatus)>, String> {
+) -> Result<Vec<(String, AgentRole, AgentStatus)>, HiveError> {
     dispatch_pty(&registry, Arc::clone(&app_state), "pty.list", json!({})).await
 }
+
+/// Record the session's agent terminals to asciinema `.cast` files from now on.
+#[tauri::command]
+pub async fn set_session_recording(
+    registry: State<'_, Arc<ActionRegistry>>,
+    app_state: State<'_, Arc<AppState>>,
+    session_id: String,
+    enabled: bool,
+) -> Result<(), HiveError> {
+    dispatch_pty(
+        &registry,
+        Arc::clone(&app_state),
+        "pty.set_recording",
+        json!({ "session_id": session_id, "enabled": enabled }),
+    )
+    .await
+}
+
+#[tauri::command]
+pub async fn list_recordings(
+    registry: State<'_, Arc<ActionRegistry>>,
+    app_state: State<'_, Arc<AppState>>,
+    session_id: String,
+) -> Result<SessionRecordings, HiveError> {
+    dispatch_pty(
+        &registry,
+        Arc::clone(&app_state),
+        "pty.list_recordings",
+        json!({ "session_id": session_id }),
+    )
+    .await
+}
@@ -1,35 +1,37 @@
 use parking_lot::RwLock;
 use serde_json::json;
-use std::collections::{HashSet, VecDeque};
+use std::collections::{HashMap, HashSet, VecDeque};
 use std::fs;
 use std::path::{Path, PathBuf};
 use std::sync::Arc;
 use tauri::State;
 
+use crate::actions::session::spawn_fusion_variants_in_background;
 use crate::actions::{ActionContext, ActionRegistry, Caller};
+use crate::error::HiveError;
 use crate::http::state::AppState;
 use crate::pty::AgentConfig;
 use crate::session::{
-    DebateLaunchConfig, FusionLaunchConfig, HiveLaunchConfig, ResearchLaunchConfig, Session,
-    SessionController, SwarmLaunchConfig,
+    DebateLaunchConfig, FusionLaunchConfig, HiveLaunchConfig, OrphanedPtys, ResearchLaunchConfig,
+    Session, SessionController, SwarmLaunchConfig,
 };
+use crate::session::fusion_compose::{FusionComposeReport, FusionComposeRequest, FusionPick};
 
 pub struct SessionControllerState(pub Arc<RwLock<SessionController>>);
 
 /// Dispatch an action through the shared registry with `caller = Frontend`,
-/// returning the raw JSON value or the action's message string (the exact text
-/// the frontend `invoke()` already expects on error).
+/// returning the raw JSON value or the action's error with its code.
 async fn dispatch_frontend(
     registry: &ActionRegistry,
     state: Arc<AppState>,
     name: &str,
     input: serde_json::Value,
-) -> Result<serde_json::Value, String> {
+) -> Result<serde_json::Value, HiveError> {
     let ctx = ActionContext::new(Caller::Frontend, state);
     registry
         .dispatch(name, &ctx, input)
         .await
-        .map_err(|e| e.to_message())
+        .map_err(HiveError::from)
 }
 
 // SessionControllerState is Send + Sync because Arc<RwLock<T>> is Send + Sync when T is Send
@@ -39,9 +41,9 @@ unsafe impl Sync for SessionControllerState {}
 const SESSION_FILE_RESULT_CAP: usize = 100;
 const SESSION_FILE_VISIT_CAP: usize = 5_000;
 
-fn validate_session_id_for_command(session_id: &str) -> Result<(), String> {
+fn validate_session_id_for_command(session_id: &str) -> Result<(), HiveError> {
     if session_id.contains("..") || session_id.contains('/') || session_id.contains('\\') {
-        return Err("Invalid session ID format".to_string());
+        return Err(HiveError::validation("Invalid session ID format"));
     }
     Ok(())
 }
@@ -91,7 +93,7 @@ fn file_matches_query(path: &Path, query: &str) -> bool {
     path_text.contains(query) || name_text.contains(query)
 }
 
-fn list_files_under_roots(roots: Vec<PathBuf>, query: String) -> Result<Vec<String>, String> {
+fn list_files_under_roots(roots: Vec<PathBuf>, query: String) -> Result<Vec<String>, HiveError> {
     let canonical_roots = dedupe_canonical_roots(roots);
     let query = query.trim().to_lowercase();
     let mut pending: VecDeque<PathBuf> = canonical_roots.iter().cloned().collect();
@@ -149,7 +151,7 @@ pub async fn launch_hive(
     worker_count: u8,
     command: String,
     prompt: Option<String>,
-) -> Result<serde_json::Value, String> {
+) -> Result<serde_json::Value, HiveError> {
     dispatch_frontend(
         &registry,
         Arc::clone(&app_state),
@@ -174,7 +176,7 @@ pub async fn get_session(
     registry: State<'_, Arc<ActionRegistry>>,
     app_state: State<'_, Arc<AppState>>,
     id: String,
-) -> Result<serde_json::Value, String> {
+) -> Result<serde_json::Value, HiveError> {
     dispatch_frontend(
         &registry,
         Arc::clone(&app_state),
@@ -188,7 +190,7 @@ pub async fn get_session(
 pub async fn list_sessions(
     registry: State<'_, Arc<ActionRegistry>>,
     app_state: State<'_, Arc<AppState>>,
-) -> Result<serde_json::Value, String> {
+) -> Result<serde_json::Value, HiveError> {
     dispatch_frontend(&registry, Arc::clone(&app_state), "session.list", json!({})).await
 }
 
@@ -197,7 +199,7 @@ pub async fn stop_session(
     registry: State<'_, Arc<ActionRegistry>>,
     app_state: State<'_, Arc<AppState>>,
     id: String,
-) -> Result<(), String> {
+) -> Result<(), HiveError> {
     dispatch_frontend(
         &registry,
         Arc::clone(&app_state),
@@ -213,7 +215,7 @@ pub async fn close_session(
     registry: State<'_, Arc<ActionRegistry>>,
     app_state: State<'_, Arc<AppState>>,
     id: String,
-) -> Result<(), String> {
+) -> Result<(), HiveError> {
     dispatch_frontend(
         &registry,
         Arc::clone(&app_state),
@@ -229,9 +231,54 @@ pub async fn stop_agent(
     state: State<'_, SessionControllerState>,
     session_id: String,
     agent_id: String,
-) -> Result<(), String> {
+) -> Result<(), HiveError> {
     let controller = state.0.read();
-    controller.stop_agent(&session_id, &agent_id)
+    Ok(controller.stop_agent(&session_id, &agent_id)?)
+}
+
+#[tauri::command]
+pub async fn quarantine_agent(
+    state: State<'_, SessionControllerState>,
+    session_id: String,
+    agent_id: String,
+) -> Result<(), HiveError> {
+    let controller = state.0.read();
+    Ok(controller.quarantine_agent(&session_id, &agent_id)?)
+}
+
+#[tauri::command]
+pub async fn release_agent(
+    state: State<'_, SessionControllerState>,
+    session_id: String,
+    agent_id: String,
+) -> Result<(), HiveError> {
+    let controller = state.0.read();
+    Ok(controller.release_agent(&session_id, &agent_id)?)
+}
+
+#[tauri::command]
+pub async fn list_queued_sessions(
+    state: State<'_, SessionControllerState>,
+) -> Result<Vec<Session>, HiveError> {
+    let controller = state.0.read();
+    Ok(controller.list_queued_sessions())
+}
+
+#[tauri::command]
+pub async fn cancel_queued_session(
+    state: State<'_, SessionControllerState>,
+    session_id: String,
+) -> Result<(), HiveError> {
+    let controller = state.0.read();
+    Ok(controller.cancel_queued_session(&session_id)?)
+}
+
+#[tauri::command]
+pub async fn get_orphaned_ptys(
+    state: State<'_, SessionControllerState>,
+) -> Result<OrphanedPtys, HiveError> {
+    let controller = state.0.read();
+    Ok(controller.orphaned_ptys())
 }
 
 #[tauri::command]
@@ -239,8 +286,8 @@ pub async fn launch_hive_v2(
     registry: State<'_, Arc<ActionRegistry>>,
     app_state: State<'_, Arc<AppState>>,
     config: HiveLaunchConfig,
-) -> Result<serde_json::Value, String> {
-    let input = serde_json::to_value(config).map_err(|e| e.to_string())?;
+) -> Result<serde_json::Value, HiveError> {
+    let input = serde_json::to_value(config)?;
     dispatch_frontend(
         &registry,
         Arc::clone(&app_state),
@@ -255,8 +302,8 @@ pub async fn launch_research(
     registry: State<'_, Arc<ActionRegistry>>,
     app_state: State<'_, Arc<AppState>>,
     config: ResearchLaunchConfig,
-) -> Result<serde_json::Value, String> {
-    let input = serde_json::to_value(config).map_err(|e| e.to_string())?;
+) -> Result<serde_json::Value, HiveError> {
+    let input = serde_json::to_value(config)?;
     dispatch_frontend(
         &registry,
         Arc::clone(&app_state),
@@ -271,8 +318,8 @@ pub async fn launch_swarm(
     registry: State<'_, Arc<ActionRegistry>>,
     app_state: State<'_, Arc<AppState>>,
     config: SwarmLaunchConfig,
-) -> Result<serde_json::Value, String> {
-    let input = serde_json::to_value(config).map_err(|e| e.to_string())?;
+) -> Result<serde_json::Value, HiveError> {
+    let input = serde_json::to_value(config)?;
     dispatch_frontend(
         &registry,
         Arc::clone(&app_state),
@@ -293,7 +340,7 @@ pub async fn launch_solo(
     flags: Option<Vec<String>>,
     evaluator_cli: Option<String>,
     evaluator_model: Option<String>,
-) -> Result<serde_json::Value, String> {
+) -> Result<serde_json::Value, HiveError> {
     let agent_config = AgentConfig {
         cli: cli.clone(),
         model,
@@ -303,6 +350,8 @@ pub async fn launch_solo(
         description: None,
         role: None,
         initial_prompt: None,
+        allowed_paths: Vec::new(),
+        denied_paths: Vec::new(),
     };
 
     // Build evaluator_config: validate if provided, else fall back to cli silently
@@ -316,6 +365,8 @@ pub async fn launch_solo(
             description: None,
             role: None,
             initial_prompt: None,
+            allowed_paths: Vec::new(),
+            denied_paths: Vec::new(),
         })
     } else {
         None
@@ -338,9 +389,15 @@ pub async fn launch_solo(
         evaluator_config,
         qa_workers: None,
         smoke_test: false,
+        smoke_probe: false,
+        branch_name: None,
+        phase_timeouts: crate::session::PhaseTimeouts::default(),
+        review_stage: None,
+        spawn_quota: crate::session::SpawnQuota::default(),
+        background: false,
     };
 
-    let input = serde_json::to_value(config).map_err(|e| e.to_string())?;
+    let input = serde_json::to_value(config)?;
     dispatch_frontend(
         &registry,
         Arc::clone(&app_state),
@@ -350,13 +407,299 @@ pub async fn launch_solo(
     .await
 }
 
+/// Queue a follow-up prompt for a Solo session's agent; returns the pending prompts.
+#[tauri::command]
+pub async fn queue_solo_task(
+    registry: State<'_, Arc<ActionRegistry>>,
+    app_state: State<'_, Arc<AppState>>,
+    id: String,
+    prompt: String,
+) -> Result<serde_json::Value, HiveError> {
+    dispatch_frontend(
+        &registry,
+        Arc::clone(&app_state),
+        "session.queue_solo_task",
+        json!({ "id": id, "prompt": prompt }),
+    )
+    .await
+}
+
+/// Escalate a Solo session into a Hive, keeping its agent as Worker 1.
+#[tauri::command]
+pub async fn promote_session(
+    registry: State<'_, Arc<ActionRegistry>>,
+    app_state: State<'_, Arc<AppState>>,
+    id: String,
+    workers: Vec<AgentConfig>,
+) -> Result<serde_json::Value, HiveError> {
+    dispatch_frontend(
+        &registry,
+        Arc::clone(&app_state),
+        "session.promote_session",
+        json!({ "id": id, "workers": workers }),
+    )
+    .await
+}
+
+/// Spawn or retire idle workers until a Hive session has `target_count` of them.
+#[tauri::command]
+pub async fn scale_workers(
+    registry: State<'_, Arc<ActionRegistry>>,
+    app_state: State<'_, Arc<AppState>>,
+    id: String,
+    target_count: u8,
+) -> Result<serde_json::Value, HiveError> {
+    dispatch_frontend(
+        &registry,
+        Arc::clone(&app_state),
+        "session.scale_workers",
+        json!({ "id": id, "target_count": target_count }),
+    )
+    .await
+}
+
+/// Replace the worker spawn quota of a running session; an empty quota lifts it.
+#[tauri::command]
+pub async fn override_spawn_quota(
+    registry: State<'_, Arc<ActionRegistry>>,
+    app_state: State<'_, Arc<AppState>>,
+    id: String,
+    max_workers: Option<u8>,
+    per_role: Option<HashMap<String, u8>>,
+) -> Result<serde_json::Value, HiveError> {
+    dispatch_frontend(
+        &registry,
+        Arc::clone(&app_state),
+        "session.override_spawn_quota",
+        json!({ "id": id, "max_workers": max_workers, "per_role": per_role.unwrap_or_default() }),
+    )
+    .await
+}
+
+/// Re-run a Fusion session's judge, or add one second-opinion judge per config.
+#[tauri::command]
+pub async fn run_fusion_judges(
+    registry: State<'_, Arc<ActionRegistry>>,
+    app_state: State<'_, Arc<AppState>>,
+    id: String,
+    judges: Option<Vec<AgentConfig>>,
+) -> Result<serde_json::Value, HiveError> {
+    dispatch_frontend(
+        &registry,
+        Arc::clone(&app_state),
+        "session.run_fusion_judges",
+        json!({ "id": id, "judges": judges.unwrap_or_default() }),
+    )
+    .await
+}
+
+/// Everything the UI shows for a session in one payload, for rebuilding it after
+/// a reload.
+#[tauri::command]
+pub async fn get_full_session_snapshot(
+    registry: State<'_, Arc<ActionRegistry>>,
+    app_state: State<'_, Arc<AppState>>,
+    session_id: String,
+    coordination_limit: Option<usize>,
+) -> Result<serde_json::Value, HiveError> {
+    dispatch_frontend(
+        &registry,
+        Arc::clone(&app_state),
+        "session.get_full_snapshot",
+        json!({ "id": session_id, "coordination_limit": coordination_limit }),
+    )
+    .await
+}
+
+/// Mirror a ready plan's tasks onto a GitHub issue as sub-issues or a checklist.
+#[tauri::command]
+pub async fn link_plan_issues(
+    registry: State<'_, Arc<ActionRegistry>>,
+    app_state: State<'_, Arc<AppState>>,
+    id: String,
+    parent_issue: String,
+    mode: Option<String>,
+) -> Result<serde_json::Value, HiveError> {
+    dispatch_frontend(
+        &registry,
+        Arc::clone(&app_state),
+        "session.link_plan_issues",
+        json!({
+            "id": id,
+            "parent_issue": parent_issue,
+            "mode": mode.as_deref().unwrap_or("sub_issues"),
+        }),
+    )
+    .await
+}
+
+/// Open a file at a line in the operator's editor. A relative `path` is resolved
+/// against the project of `session_id`.
+#[tauri::command]
+pub async fn open_file_in_editor(
+    registry: State<'_, Arc<ActionRegistry>>,
+    app_state: State<'_, Arc<AppState>>,
+    path: String,
+    session_id: String,
+    line: Option<u32>,
+    column: Option<u32>,
+) -> Result<serde_json::Value, HiveError> {
+    dispatch_frontend(
+        &registry,
+        Arc::clone(&app_state),
+        "editor.open_file",
+        json!({ "path": path, "line": line, "column": column, "session_id": session_id }),
+    )
+    .await
+}
+
+/// List the WIP checkpoints recorded for a session's worker worktrees.
+#[tauri::command]
+pub async fn list_checkpoints(
+    registry: State<'_, Arc<ActionRegistry>>,
+    app_state: State<'_, Arc<AppState>>,
+    id: String,
+) -> Result<serde_json::Value, HiveError> {
+    dispatch_frontend(
+        &registry,
+        Arc::clone(&app_state),
+        "session.list_checkpoints",
+        json!({ "id": id }),
+    )
+    .await
+}
+
+/// Wall-clock time a session spent per phase and per agent.
+#[tauri::command]
+pub async fn get_session_durations(
+    registry: State<'_, Arc<ActionRegistry>>,
+    app_state: State<'_, Arc<AppState>>,
+    id: String,
+) -> Result<serde_json::Value, HiveError> {
+    dispatch_frontend(
+        &registry,
+        Arc::clone(&app_state),
+        "session.get_durations",
+        json!({ "id": id }),
+    )
+    .await
+}
+
+/// Two sessions side by side: time per phase, tasks completed, diff size and learnings.
+#[tauri::command]
+pub async fn compare_sessions(
+    registry: State<'_, Arc<ActionRegistry>>,
+    app_state: State<'_, Arc<AppState>>,
+    a: String,
+    b: String,
+) -> Result<serde_json::Value, HiveError> {
+    dispatch_frontend(
+        &registry,
+        Arc::clone(&app_state),
+        "session.compare",
+        json!({ "a": a, "b": b }),
+    )
+    .await
+}
+
+/// Fusion win rates and mean judge scores per CLI and model, across every session
+/// whose winner was merged.
+#[tauri::command]
+pub async fn get_cli_leaderboard(
+    registry: State<'_, Arc<ActionRegistry>>,
+    app_state: State<'_, Arc<AppState>>,
+) -> Result<serde_json::Value, HiveError> {
+    dispatch_frontend(
+        &registry,
+        Arc::clone(&app_state),
+        "session.get_cli_leaderboard",
+        json!({}),
+    )
+    .await
+}
+
+/// Two to four ready-to-launch Fusion variants for `task_description`, picked from
+/// the CLI leaderboard and the past runs on similar tasks.
+#[tauri::command]
+pub async fn suggest_fusion_variants(
+    registry: State<'_, Arc<ActionRegistry>>,
+    app_state: State<'_, Arc<AppState>>,
+    task_description: String,
+) -> Result<serde_json::Value, HiveError> {
+    dispatch_frontend(
+        &registry,
+        Arc::clone(&app_state),
+        "session.suggest_fusion_variants",
+        json!({ "task_description": task_description }),
+    )
+    .await
+}
+
+/// The command line, working directory and environment an agent with `config` would
+/// be spawned with, for debugging a launch; nothing is started.
+#[tauri::command]
+pub async fn preview_agent_command(
+    registry: State<'_, Arc<ActionRegistry>>,
+    app_state: State<'_, Arc<AppState>>,
+    config: AgentConfig,
+    cwd: Option<String>,
+    prompt_path: Option<String>,
+) -> Result<serde_json::Value, HiveError> {
+    dispatch_frontend(
+        &registry,
+        Arc::clone(&app_state),
+        "session.preview_agent_command",
+        json!({ "config": config, "cwd": cwd, "prompt_path": prompt_path }),
+    )
+    .await
+}
+
+/// Per-session `.hive-manager/` directories in `project_path` that finished sessions
+/// left behind; removed unless `dry_run` (the default) is set.
+#[tauri::command]
+pub async fn cleanup_project_artifacts(
+    registry: State<'_, Arc<ActionRegistry>>,
+    app_state: State<'_, Arc<AppState>>,
+    project_path: String,
+    dry_run: Option<bool>,
+    include_unknown: Option<bool>,
+) -> Result<serde_json::Value, HiveError> {
+    dispatch_frontend(
+        &registry,
+        Arc::clone(&app_state),
+        "session.cleanup_project_artifacts",
+        json!({
+            "project_path": project_path,
+            "dry_run": dry_run,
+            "include_unknown": include_unknown.unwrap_or(false),
+        }),
+    )
+    .await
+}
+
+/// Append any missing `.hive-manager/` entries to the project's `.gitignore`.
+#[tauri::command]
+pub async fn ensure_project_gitignore(
+    registry: State<'_, Arc<ActionRegistry>>,
+    app_state: State<'_, Arc<AppState>>,
+    project_path: String,
+) -> Result<serde_json::Value, HiveError> {
+    dispatch_frontend(
+        &registry,
+        Arc::clone(&app_state),
+        "session.ensure_project_gitignore",
+        json!({ "project_path": project_path }),
+    )
+    .await
+}
+
 #[tauri::command]
 pub async fn launch_fusion(
     registry: State<'_, Arc<ActionRegistry>>,
     app_state: State<'_, Arc<AppState>>,
     config: FusionLaunchConfig,
-) -> Result<serde_json::Value, String> {
-    let input = serde_json::to_value(config).map_err(|e| e.to_string())?;
+) -> Result<serde_json::Value, HiveError> {
+    let input = serde_json::to_value(config)?;
     dispatch_frontend(
         &registry,
         Arc::clone(&app_state),
@@ -371,8 +714,8 @@ pub async fn launch_debate(
     registry: State<'_, Arc<ActionRegistry>>,
     app_state: State<'_, Arc<AppState>>,
     config: DebateLaunchConfig,
-) -> Result<serde_json::Value, String> {
-    let input = serde_json::to_value(config).map_err(|e| e.to_string())?;
+) -> Result<serde_json::Value, HiveError> {
+    let input = serde_json::to_value(config)?;
     dispatch_frontend(
         &registry,
         Arc::clone(&app_state),
@@ -386,18 +729,21 @@ pub async fn launch_debate(
 pub async fn continue_after_planning(
     state: State<'_, SessionControllerState>,
     session_id: String,
-) -> Result<Session, String> {
-    let controller = state.0.read();
-    controller.continue_after_planning(&session_id)
+) -> Result<Session, HiveError> {
+    let (session, spawn_plan) = state.0.read().continue_after_planning(&session_id)?;
+    if let Some(plan) = spawn_plan {
+        spawn_fusion_variants_in_background(Arc::clone(&state.0), plan);
+    }
+    Ok(session)
 }
 
 #[tauri::command]
 pub async fn mark_plan_ready(
     state: State<'_, SessionControllerState>,
     session_id: String,
-) -> Result<(), String> {
+) -> Result<(), HiveError> {
     let controller = state.0.read();
-    controller.mark_plan_ready(&session_id)
+    Ok(controller.mark_plan_ready(&session_id)?)
 }
 
 #[tauri::command]
@@ -405,7 +751,7 @@ pub async fn resume_session(
     state: State<'_, SessionControllerState>,
     app_state: State<'_, Arc<AppState>>,
     session_id: String,
-) -> Result<Session, String> {
+) -> Result<Session, HiveError> {
     let session = {
         let controller = state.0.read();
         controller.resume_session(&session_id)?
@@ -427,12 +773,23 @@ pub async fn resume_session(
     Ok(session)
 }
 
+/// Load a session created by another install from `<project_path>/.hive-manager/<session_id>/`.
+#[tauri::command]
+pub async fn adopt_session(
+    state: State<'_, SessionControllerState>,
+    project_path: String,
+    session_id: String,
+) -> Result<Session, HiveError> {
+    let controller = state.0.read();
+    Ok(controller.adopt_session(Path::new(&project_path), &session_id)?)
+}
+
 /// #125: read the run journal + side-effect ledger for a session, for the resume modal.
 #[tauri::command]
 pub async fn get_run_journal(
     app_state: State<'_, Arc<AppState>>,
     session_id: String,
-) -> Result<serde_json::Value, String> {
+) -> Result<serde_json::Value, HiveError> {
     validate_session_id_for_command(&session_id)?;
     let store = crate::storage::RunJournalStore::new(Arc::clone(&app_state.app_state_db));
     let journal = store
@@ -449,14 +806,17 @@ pub async fn list_session_files(
     state: State<'_, SessionControllerState>,
     session_id: String,
     query: String,
-) -> Result<Vec<String>, String> {
+) -> Result<Vec<String>, HiveError> {
     validate_session_id_for_command(&session_id)?;
 
     let roots = {
         let controller = state.0.read();
         let session = controller
             .get_session(&session_id)
-            .ok_or_else(|| format!("Session not found: {}", session_id))?;
+            .ok_or_else(|| {
+                HiveError::not_found(format!("Session not found: {}", session_id))
+                    .with_context("session_id", session_id.as_str())
+            })?;
 
         let mut roots = vec![session.project_path];
         if let Some(worktree_path) = session.worktree_path {
@@ -470,6 +830,56 @@ pub async fn list_session_files(
         .map_err(|e| format!("Failed to list session files: {e}"))?
 }
 
+/// Push the session branch and open a pull request composed from the session report.
+/// Returns the PR URL, which is also recorded on the session.
+#[tauri::command]
+pub async fn create_pull_request(
+    state: State<'_, SessionControllerState>,
+    session_id: String,
+    title: Option<String>,
+    base: Option<String>,
+    draft: Option<bool>,
+) -> Result<String, HiveError> {
+    validate_session_id_for_command(&session_id)?;
+
+    let controller = Arc::clone(&state.0);
+    tauri::async_runtime::spawn_blocking(move || {
+        SessionController::create_pull_request(
+            &controller,
+            &session_id,
+            title.as_deref(),
+            base.as_deref(),
+            draft.unwrap_or(false),
+        )
+    })
+    .await
+    .map_err(|e| format!("Failed to create pull request: {e}"))?
+    .map_err(HiveError::from)
+}
+
+/// Cherry-pick commits and take files from several Fusion variants onto a new
+/// integration branch, reporting picks that conflicted.
+#[tauri::command]
+pub async fn compose_fusion_result(
+    state: State<'_, SessionControllerState>,
+    session_id: String,
+    picks: Vec<FusionPick>,
+    branch: Option<String>,
+) -> Result<FusionComposeReport, HiveError> {
+    validate_session_id_for_command(&session_id)?;
+
+    let controller = Arc::clone(&state.0);
+    let request = FusionComposeRequest { branch, picks };
+    tauri::async_runtime::spawn_blocking(move || {
+        controller
+            .read()
+            .compose_fusion_result(&session_id, &request)
+    })
+    .await
+    .map_err(|e| format!("Failed to compose fusion result: {e}"))?
+    .map_err(HiveError::from)
+}
+
 #[tauri::command]
 pub async fn update_session_metadata(
     registry: State<'_, Arc<ActionRegistry>>,
@@ -477,7 +887,7 @@ pub async fn update_session_metadata(
     id: String,
     name: Option<Option<String>>,
     color: Option<Option<String>>,
-) -> Result<serde_json::Value, String> {
+) -> Result<serde_json::Value, HiveError> {
     dispatch_frontend(
         &registry,
         Arc::clone(&app_state),
@@ -0,0 +1,252 @@
+//! Operator approval gates for dangerous agent requests.
+//!
+//! [`ApprovalConfig`] names the requests that need a human: spawning workers past
+//! a limit, pushing to protected branches, deleting protected files. When
+//! [`approval_reason`] flags a request, the HTTP handler parks it in the
+//! [`ApprovalGate`] and waits until the operator approves or rejects it (or the
+//! configured timeout rejects it for them).
+
+use std::collections::HashMap;
+
+use chrono::{DateTime, Utc};
+use parking_lot::Mutex;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use tokio::sync::oneshot;
+
+use crate::storage::ApprovalConfig;
+
+/// A request an agent wants to carry out.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum GatedAction {
+    /// Spawn the session's `worker_count`-th worker.
+    SpawnWorker {
+        worker_count: usize,
+    },
+    Push {
+        branch: String,
+    },
+    DeleteFiles {
+        paths: Vec<String>,
+    },
+}
+
+/// A parked request, as shown to the operator.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ApprovalRequest {
+    pub id: String,
+    pub session_id: String,
+    pub agent_id: Option<String>,
+    #[serde(flatten)]
+    pub action: GatedAction,
+    /// Why the policy gated it.
+    pub reason: String,
+    pub requested_at: DateTime<Utc>,
+}
+
+/// Why `action` needs operator approval under `policy`, or `None` when it may
+/// go ahead.
+pub fn approval_reason(policy: &ApprovalConfig, action: &GatedAction) -> Option<String> {
+    match action {
+        GatedAction::SpawnWorker { worker_count } => policy
+            .max_workers
+            .filter(|max| worker_count > max)
+            .map(|max| {
+                format!(
+                    "Spawning worker {} exceeds the limit of {} workers",
+                    worker_count, max
+                )
+            }),
+        GatedAction::Push { branch } => {
+            let branch = branch.strip_prefix("refs/heads/").unwrap_or(branch);
+            policy
+                .protected_branches
+                .iter()
+                .any(|pattern| glob_matches(pattern, branch))
+                .then(|| format!("Push to protected branch {}", branch))
+        }
+        GatedAction::DeleteFiles { paths } => {
+            let protected: Vec<&str> = paths
+                .iter()
+                .map(|path| path.trim_start_matches("./"))
+                .filter(|path| {
+                    policy
+                        .protected_paths
+                        .iter()
+                        .any(|pattern| glob_matches(pattern, path))
+                })
+                .collect();
+            (!protected.is_empty())
+                .then(|| format!("Deleting protected files: {}", protected.join(", ")))
+        }
+    }
+}
+
+/// `*` and `?` stay within one path segment; `**` spans any number of them.
+pub(crate) fn glob_matches(pattern: &str, text: &str) -> bool {
+    let mut regex = String::from("^");
+    let mut chars = pattern.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '*' if chars.peek() == Some(&'*') => {
+                chars.next();
+                if chars.peek() == Some(&'/') {
+                    chars.next();
+                    regex.push_str("(?:.*/)?");
+                } else {
+                    regex.push_str(".*");
+                }
+            }
+            '*' => regex.push_str("[^/]*"),
+            '?' => regex.push_str("[^/]"),
+            c => regex.push_str(&regex::escape(&c.to_string())),
+        }
+    }
+    regex.push('$');
+    Regex::new(&regex).is_ok_and(|regex| regex.is_match(text))
+}
+
+/// Requests waiting for the operator, each with the channel its handler awaits.
+#[derive(Default)]
+pub struct ApprovalGate {
+    pending: Mutex<HashMap<String, (ApprovalRequest, oneshot::Sender<bool>)>>,
+}
+
+impl ApprovalGate {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Park a request. The receiver yields the operator's decision; it errors if
+    /// the request is withdrawn first.
+    pub fn park(
+        &self,
+        session_id: &str,
+        agent_id: Option<&str>,
+        action: GatedAction,
+        reason: String,
+    ) -> (ApprovalRequest, oneshot::Receiver<bool>) {
+        let request = ApprovalRequest {
+            id: uuid::Uuid::new_v4().to_string(),
+            session_id: session_id.to_string(),
+            agent_id: agent_id.map(String::from),
+            action,
+            reason,
+            requested_at: Utc::now(),
+        };
+        let (tx, rx) = oneshot::channel();
+        self.pending
+            .lock()
+            .insert(request.id.clone(), (request.clone(), tx));
+        (request, rx)
+    }
+
+    /// Release a parked request with the operator's decision.
+    pub fn resolve(&self, approval_id: &str, approved: bool) -> Result<ApprovalRequest, String> {
+        let (request, tx) = self
+            .pending
+            .lock()
+            .remove(approval_id)
+            .ok_or_else(|| format!("Approval request not found: {}", approval_id))?;
+        // The handler may have timed out between the lookup and here; nothing waits then.
+        let _ = tx.send(approved);
+        Ok(request)
+    }
+
+    /// Drop a request nobody will answer any more (its handler timed out).
+    pub fn withdraw(&self, approval_id: &str) -> Option<ApprovalRequest> {
+        self.pending
+            .lock()
+            .remove(approval_id)
+            .map(|(request, _)| request)
+    }
+
+    /// Parked requests, oldest first; all sessions when `session_id` is `None`.
+    pub fn pending(&self, session_id: Option<&str>) -> Vec<ApprovalRequest> {
+        let mut requests: Vec<ApprovalRequest> = self
+            .pending
+            .lock()
+            .values()
+            .map(|(request, _)| request)
+            .filter(|request| session_id.is_none_or(|id| request.session_id == id))
+            .cloned()
+            .collect();
+        requests.sort_by_key(|request| request.requested_at);
+        requests
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn policy() -> ApprovalConfig {
+        ApprovalConfig {
+            max_workers: Some(3),
+            protected_branches: vec!["main".to_string(), "release/*".to_string()],
+            protected_paths: vec!["migrations/**".to_string(), "*.lock".to_string()],
+            ..ApprovalConfig::default()
+        }
+    }
+
+    #[test]
+    fn test_policy_gates_only_the_configured_requests() {
+        let policy = policy();
+        let gated = |action: GatedAction| approval_reason(&policy, &action).is_some();
+
+        assert!(!gated(GatedAction::SpawnWorker { worker_count: 3 }));
+        assert!(gated(GatedAction::SpawnWorker { worker_count: 4 }));
+        assert!(gated(GatedAction::Push {
+            branch: "refs/heads/main".to_string()
+        }));
+        assert!(gated(GatedAction::Push {
+            branch: "release/1.2".to_string()
+        }));
+        assert!(!gated(GatedAction::Push {
+            branch: "release/1.2/hotfix".to_string()
+        }));
+        assert!(!gated(GatedAction::Push {
+            branch: "feature/main".to_string()
+        }));
+        assert_eq!(
+            approval_reason(
+                &policy,
+                &GatedAction::DeleteFiles {
+                    paths: vec![
+                        "src/lib.rs".to_string(),
+                        "./migrations/2024/01_init.sql".to_string(),
+                        "Cargo.lock".to_string(),
+                        "vendor/Cargo.lock".to_string(),
+                    ],
+                }
+            ),
+            Some("Deleting protected files: migrations/2024/01_init.sql, Cargo.lock".to_string())
+        );
+        assert!(approval_reason(
+            &ApprovalConfig::default(),
+            &GatedAction::SpawnWorker { worker_count: 50 }
+        )
+        .is_none());
+    }
+
+    #[tokio::test]
+    async fn test_parked_request_waits_for_the_operator() {
+        let gate = ApprovalGate::new();
+        let (request, decision) = gate.park(
+            "session-1",
+            Some("worker-1"),
+            GatedAction::Push {
+                branch: "main".to_string(),
+            },
+            "Push to protected branch main".to_string(),
+        );
+        assert_eq!(gate.pending(Some("session-1")).len(), 1);
+        assert!(gate.pending(Some("session-2")).is_empty());
+
+        gate.resolve(&request.id, false).unwrap();
+        assert!(!decision.await.unwrap());
+        assert!(gate.pending(None).is_empty());
+        assert!(gate.resolve(&request.id, true).is_err());
+    }
+}
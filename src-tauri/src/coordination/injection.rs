@@ -1,13 +1,18 @@
+use std::collections::HashMap;
 use std::sync::Arc;
+use std::time::{Duration, Instant};
 
-use parking_lot::RwLock;
+use chrono::{DateTime, SecondsFormat, Utc};
+use parking_lot::{Mutex, RwLock};
+use serde::{Deserialize, Serialize};
 use thiserror::Error;
 
+use crate::adapters::SubmitKey;
 use crate::pty::PtyManager;
-use crate::storage::SessionStorage;
+use crate::storage::{ConversationMessage, SessionStorage};
 use crate::tauri_shim::{AppHandle, Emitter};
 
-use super::{CoordinationMessage, StateManager, WorkerStateInfo};
+use super::{CoordinationMessage, MessageDelivery, StateManager, WorkerStateInfo};
 
 #[derive(Debug, Error)]
 pub enum InjectionError {
@@ -19,17 +24,53 @@ pub enum InjectionError {
     AgentNotFound(String),
     #[error("Not authorized: {0}")]
     NotAuthorized(String),
+    #[error("Message not found: {0}")]
+    MessageNotFound(String),
     #[error("PTY error: {0}")]
     PtyError(String),
     #[error("Storage error: {0}")]
     StorageError(String),
+    #[error("Rate limited: {0}")]
+    RateLimited(String),
 }
 
+/// Shortest time between two operator broadcasts to the same session.
+pub const BROADCAST_COOLDOWN: Duration = Duration::from_secs(10);
+
+/// Where an operator broadcast landed.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BroadcastReport {
+    pub delivered: Vec<String>,
+    pub failed: Vec<BroadcastFailure>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BroadcastFailure {
+    pub agent_id: String,
+    pub error: String,
+}
+
+/// An agent whose conversation inbox is summarized by [`InjectionManager::inject_inbox_digests`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DigestTarget {
+    pub agent_id: String,
+    /// Conversation the agent reads as its inbox, e.g. `queen` or `worker-1`.
+    pub inbox: String,
+    pub submit: SubmitKey,
+}
+
+/// Longest message excerpt shown in an inbox digest.
+const DIGEST_EXCERPT_CHARS: usize = 120;
+
 /// Manages Queen injection and coordination
 pub struct InjectionManager {
     pty_manager: Arc<RwLock<PtyManager>>,
     storage: SessionStorage,
     app_handle: Option<AppHandle>,
+    /// Serializes read-modify-write passes over a session's delivery records.
+    deliveries_lock: Mutex<()>,
+    /// When each session last received an operator broadcast.
+    last_broadcast: Mutex<HashMap<String, Instant>>,
 }
 
 impl InjectionManager {
@@ -39,6 +80,8 @@ impl InjectionManager {
             pty_manager,
             storage,
             app_handle: None,
+            deliveries_lock: Mutex::new(()),
+            last_broadcast: Mutex::new(HashMap::new()),
         }
     }
 
@@ -68,14 +111,15 @@ impl InjectionManager {
         }
 
         // Log to coordination.log
-        let coord_message = if target_worker_id.ends_with("-evaluator") {
-            CoordinationMessage::milestone_ready(
+        let is_task = !target_worker_id.ends_with("-evaluator");
+        let coord_message = if is_task {
+            CoordinationMessage::task(
                 &format_agent_display(queen_id),
                 &format_agent_display(target_worker_id),
                 message,
             )
         } else {
-            CoordinationMessage::task(
+            CoordinationMessage::milestone_ready(
                 &format_agent_display(queen_id),
                 &format_agent_display(target_worker_id),
                 message,
@@ -87,9 +131,13 @@ impl InjectionManager {
             .map_err(|e| InjectionError::StorageError(e.to_string()))?;
 
         // Only persist watcher-visible state after PTY delivery succeeds.
-        self.write_to_agent(target_worker_id, message)?;
-
-        if target_worker_id.ends_with("-evaluator") {
+        if is_task {
+            // The ID lets the worker acknowledge this message in its PROGRESS update.
+            let tagged = format!("[MSG {}] {}", coord_message.id, message);
+            self.write_to_agent(target_worker_id, &tagged)?;
+            self.record_delivery(session_id, &coord_message.id, target_worker_id, message)?;
+        } else {
+            self.write_to_agent(target_worker_id, message)?;
             self.write_session_peer_message(session_id, |state| {
                 state.write_milestone_ready(queen_id, target_worker_id, message)
             })?;
@@ -185,12 +233,17 @@ impl InjectionManager {
         let message = format!("[BRANCH SWITCH] Switching all workers to branch: {}", branch);
         self.log_system_message(session_id, "ALL", &message)?;
 
-        // Ctrl+C first to interrupt any running command
-        let git_command = format!("\x03git switch {}", branch);
+        let git_command = format!("git switch {}", branch);
 
         let mut results = Vec::new();
         for worker_id in worker_ids {
-            let result = self.write_to_agent(worker_id, &git_command);
+            // Ctrl+C first to interrupt any running command
+            let result = self
+                .pty_manager
+                .read()
+                .write(worker_id, b"\x03")
+                .map_err(|e| InjectionError::PtyError(format!("Failed to write: {}", e)))
+                .and_then(|()| self.write_to_agent(worker_id, &git_command));
 
             let status = if result.is_ok() { "initiated" } else { "failed" };
             let log_msg = format!(
@@ -207,27 +260,21 @@ impl InjectionManager {
         Ok(results)
     }
 
-    /// Write a message to an agent's PTY and press Enter to submit
+    /// Write a message to an agent's PTY and press the key its CLI submits with
     pub fn write_to_agent(&self, agent_id: &str, message: &str) -> Result<(), InjectionError> {
         let pty_manager = self.pty_manager.read();
 
-        // Strip any existing line endings first
-        let clean_message = message.trim_end_matches(&['\r', '\n'][..]);
-
         tracing::info!("=== INJECTION START ===");
         tracing::info!("Target agent: {}", agent_id);
-        tracing::info!("Message: {:?}", clean_message);
-        tracing::info!("Message bytes: {:?}", clean_message.as_bytes());
+        tracing::info!("Message: {:?}", message);
 
-        // Write the message content with Enter appended
-        // On Windows ConPTY, Enter is typically just \r, but some apps need \n
-        // We'll send both \r\n to maximize compatibility
-        let message_with_enter = format!("{}\r\n", clean_message);
-
-        tracing::info!("Full message with enter: {:?}", message_with_enter.as_bytes());
+        // Pasted as one block and submitted on its own: a trailing newline inside
+        // the text is taken as part of the prompt by several CLIs and never sends.
+        let submit_key = pty_manager.submit_key(agent_id);
+        tracing::info!("Submit key: {:?}", submit_key);
 
         pty_manager
-            .write(agent_id, message_with_enter.as_bytes())
+            .submit(agent_id, message)
             .map_err(|e| InjectionError::PtyError(format!("Failed to write: {}", e)))?;
 
         tracing::info!("=== INJECTION COMPLETE ===");
@@ -242,15 +289,7 @@ impl InjectionManager {
         target_agent_id: &str,
         message: &str,
     ) -> Result<(), InjectionError> {
-        // Log to coordination.log
-        let coord_message = CoordinationMessage::system(
-            &format_agent_display(target_agent_id),
-            &format!("[OPERATOR] {}", message),
-        );
-
-        self.storage
-            .append_coordination_log(session_id, &coord_message)
-            .map_err(|e| InjectionError::StorageError(e.to_string()))?;
+        let coord_message = self.log_operator_message(session_id, target_agent_id, message)?;
 
         // Write to agent's PTY stdin
         self.write_to_agent(target_agent_id, message)?;
@@ -263,6 +302,110 @@ impl InjectionManager {
         Ok(())
     }
 
+    /// Operator chat with one agent: the message is pasted as a whole, so its newlines
+    /// stay in the prompt, then `submit` is pressed on its own.
+    pub fn operator_chat(
+        &self,
+        session_id: &str,
+        target_agent_id: &str,
+        message: &str,
+        submit: SubmitKey,
+    ) -> Result<(), InjectionError> {
+        let coord_message = self.log_operator_message(session_id, target_agent_id, message)?;
+
+        {
+            let pty_manager = self.pty_manager.read();
+            pty_manager
+                .write_bracketed(target_agent_id, message.trim_end().as_bytes())
+                .and_then(|()| pty_manager.write(target_agent_id, submit.bytes()))
+                .map_err(|e| InjectionError::PtyError(format!("Failed to write: {}", e)))?;
+        }
+
+        if let Some(ref app_handle) = self.app_handle {
+            let _ = app_handle.emit("coordination-message", &coord_message);
+        }
+
+        Ok(())
+    }
+
+    /// Operator broadcast to every agent in `targets`, each submitted with its own
+    /// CLI's key. Logged once as a SYSTEM message to ALL. A session accepts one
+    /// broadcast per [`BROADCAST_COOLDOWN`]; an agent whose PTY cannot be written
+    /// is reported in the result instead of stopping the rest.
+    pub fn operator_broadcast(
+        &self,
+        session_id: &str,
+        targets: &[(String, SubmitKey)],
+        message: &str,
+    ) -> Result<BroadcastReport, InjectionError> {
+        {
+            let now = Instant::now();
+            let mut last_broadcast = self.last_broadcast.lock();
+            if let Some(last) = last_broadcast.get(session_id) {
+                let elapsed = now.duration_since(*last);
+                if elapsed < BROADCAST_COOLDOWN {
+                    return Err(InjectionError::RateLimited(format!(
+                        "Session {} was broadcast to {}s ago; wait {}s before the next broadcast",
+                        session_id,
+                        elapsed.as_secs(),
+                        (BROADCAST_COOLDOWN - elapsed).as_secs().max(1)
+                    )));
+                }
+            }
+            last_broadcast.insert(session_id.to_string(), now);
+        }
+
+        let coord_message =
+            CoordinationMessage::system("ALL", &format!("[OPERATOR BROADCAST] {}", message));
+        self.storage
+            .append_coordination_log(session_id, &coord_message)
+            .map_err(|e| InjectionError::StorageError(e.to_string()))?;
+
+        let mut report = BroadcastReport::default();
+        {
+            let pty_manager = self.pty_manager.read();
+            for (agent_id, submit) in targets {
+                let sent = pty_manager
+                    .write_bracketed(agent_id, message.trim_end().as_bytes())
+                    .and_then(|()| pty_manager.write(agent_id, submit.bytes()));
+                match sent {
+                    Ok(()) => report.delivered.push(agent_id.clone()),
+                    Err(e) => {
+                        tracing::warn!("Failed to broadcast to {}: {}", agent_id, e);
+                        report.failed.push(BroadcastFailure {
+                            agent_id: agent_id.clone(),
+                            error: e.to_string(),
+                        });
+                    }
+                }
+            }
+        }
+
+        if let Some(ref app_handle) = self.app_handle {
+            let _ = app_handle.emit("coordination-message", &coord_message);
+        }
+
+        Ok(report)
+    }
+
+    fn log_operator_message(
+        &self,
+        session_id: &str,
+        target_agent_id: &str,
+        message: &str,
+    ) -> Result<CoordinationMessage, InjectionError> {
+        let coord_message = CoordinationMessage::system(
+            &format_agent_display(target_agent_id),
+            &format!("[OPERATOR] {}", message),
+        );
+
+        self.storage
+            .append_coordination_log(session_id, &coord_message)
+            .map_err(|e| InjectionError::StorageError(e.to_string()))?;
+
+        Ok(coord_message)
+    }
+
     /// Notify Queen of new worker availability (logs only, no PTY injection)
     /// Queen spawns workers via HTTP API, so she already knows - no need to inject back
     pub fn notify_queen_worker_added(
@@ -272,8 +415,11 @@ impl InjectionManager {
         worker: &WorkerStateInfo,
     ) -> Result<(), InjectionError> {
         let message = format!(
-            "[SYSTEM] New worker available: {} ({}) - {}",
-            worker.id, worker.role.label, worker.cli
+            "[SYSTEM] New worker available: {} \"{}\" ({}) - {}",
+            worker.id,
+            worker.display_name(),
+            worker.role.label,
+            worker.cli
         );
 
         // Log to coordination.log (for audit purposes)
@@ -345,7 +491,6 @@ impl InjectionManager {
     }
 
     /// Log a progress message from an agent
-    #[allow(dead_code)]
     pub fn log_progress(
         &self,
         session_id: &str,
@@ -365,6 +510,167 @@ impl InjectionManager {
         Ok(())
     }
 
+    /// Log a worker's PROGRESS update. Naming `message_id` acknowledges that TASK,
+    /// which must have been delivered to this worker; returns the acknowledged record.
+    pub fn record_progress(
+        &self,
+        session_id: &str,
+        agent_id: &str,
+        content: &str,
+        message_id: Option<&str>,
+    ) -> Result<Option<MessageDelivery>, InjectionError> {
+        let acknowledged = match message_id {
+            Some(message_id) => Some(self.acknowledge(session_id, agent_id, message_id)?),
+            None => None,
+        };
+
+        let content = content.trim();
+        let content = if content.starts_with("PROGRESS") {
+            content.to_string()
+        } else {
+            format!("PROGRESS: {}", content)
+        };
+        self.log_progress(session_id, agent_id, &content)?;
+
+        Ok(acknowledged)
+    }
+
+    /// TASK messages the worker (every worker when `agent_id` is `None`) has not
+    /// acknowledged yet, oldest first.
+    pub fn unacknowledged_messages(
+        &self,
+        session_id: &str,
+        agent_id: Option<&str>,
+    ) -> Result<Vec<MessageDelivery>, InjectionError> {
+        let deliveries = self
+            .storage
+            .load_message_deliveries(session_id)
+            .map_err(|e| InjectionError::StorageError(e.to_string()))?;
+        Ok(deliveries
+            .into_iter()
+            .filter(|delivery| delivery.acknowledged_at.is_none())
+            .filter(|delivery| agent_id.is_none_or(|id| delivery.to == id))
+            .collect())
+    }
+
+    fn record_delivery(
+        &self,
+        session_id: &str,
+        message_id: &str,
+        to: &str,
+        content: &str,
+    ) -> Result<(), InjectionError> {
+        let _guard = self.deliveries_lock.lock();
+        let mut deliveries = self
+            .storage
+            .load_message_deliveries(session_id)
+            .map_err(|e| InjectionError::StorageError(e.to_string()))?;
+        deliveries.push(MessageDelivery {
+            message_id: message_id.to_string(),
+            to: to.to_string(),
+            content: content.to_string(),
+            delivered_at: Utc::now(),
+            acknowledged_at: None,
+        });
+        self.storage
+            .save_message_deliveries(session_id, &deliveries)
+            .map_err(|e| InjectionError::StorageError(e.to_string()))
+    }
+
+    fn acknowledge(
+        &self,
+        session_id: &str,
+        agent_id: &str,
+        message_id: &str,
+    ) -> Result<MessageDelivery, InjectionError> {
+        let _guard = self.deliveries_lock.lock();
+        let mut deliveries = self
+            .storage
+            .load_message_deliveries(session_id)
+            .map_err(|e| InjectionError::StorageError(e.to_string()))?;
+        let delivery = deliveries
+            .iter_mut()
+            .find(|delivery| delivery.message_id == message_id && delivery.to == agent_id)
+            .ok_or_else(|| {
+                InjectionError::MessageNotFound(format!(
+                    "{} was not delivered to {}",
+                    message_id, agent_id
+                ))
+            })?;
+        if delivery.acknowledged_at.is_none() {
+            delivery.acknowledged_at = Some(Utc::now());
+        }
+        let acknowledged = delivery.clone();
+        self.storage
+            .save_message_deliveries(session_id, &deliveries)
+            .map_err(|e| InjectionError::StorageError(e.to_string()))?;
+        Ok(acknowledged)
+    }
+
+    /// Type a digest of each target's unread inbox messages into its PTY, then mark
+    /// them delivered so the next digest starts after them. Messages the agent sent
+    /// itself are not unread. Returns the agents that got a digest.
+    pub fn inject_inbox_digests(
+        &self,
+        session_id: &str,
+        targets: &[DigestTarget],
+        max_items: usize,
+    ) -> Result<Vec<String>, InjectionError> {
+        let mut cursors = self
+            .storage
+            .load_inbox_digest_cursors(session_id)
+            .map_err(|e| InjectionError::StorageError(e.to_string()))?;
+        let mut digested = Vec::new();
+        for target in targets {
+            let since = cursors.get(&target.inbox).copied();
+            let unread: Vec<ConversationMessage> = match self
+                .storage
+                .conversation_messages(session_id, &target.inbox)
+            {
+                Ok(messages) => messages
+                    .into_iter()
+                    .filter(|message| since.is_none_or(|since| message.timestamp > since))
+                    .filter(|message| message.from != target.inbox)
+                    .collect(),
+                Err(e) => {
+                    tracing::warn!("Failed to read inbox {}: {}", target.inbox, e);
+                    continue;
+                }
+            };
+            let Some(latest) = unread.iter().map(|message| message.timestamp).max() else {
+                continue;
+            };
+
+            let digest = inbox_digest(session_id, &target.inbox, since, &unread, max_items);
+            let sent = {
+                let pty_manager = self.pty_manager.read();
+                pty_manager
+                    .write_bracketed(&target.agent_id, digest.as_bytes())
+                    .and_then(|()| pty_manager.write(&target.agent_id, target.submit.bytes()))
+            };
+            if let Err(e) = sent {
+                tracing::warn!("Failed to send inbox digest to {}: {}", target.agent_id, e);
+                continue;
+            }
+            cursors.insert(target.inbox.clone(), latest);
+            digested.push(target.agent_id.clone());
+            if let Err(e) = self.log_system_message(
+                session_id,
+                &format_agent_display(&target.agent_id),
+                &format!("[INBOX] Digest of {} unread message(s) delivered", unread.len()),
+            ) {
+                tracing::warn!("Failed to log inbox digest: {}", e);
+            }
+        }
+
+        if !digested.is_empty() {
+            self.storage
+                .save_inbox_digest_cursors(session_id, &cursors)
+                .map_err(|e| InjectionError::StorageError(e.to_string()))?;
+        }
+        Ok(digested)
+    }
+
     /// Get the coordination log
     pub fn get_coordination_log(
         &self,
@@ -376,6 +682,17 @@ impl InjectionManager {
             .map_err(|e| InjectionError::StorageError(e.to_string()))
     }
 
+    /// Messages logged after the one numbered `after_seq`.
+    pub fn get_coordination_log_after(
+        &self,
+        session_id: &str,
+        after_seq: u64,
+    ) -> Result<Vec<CoordinationMessage>, InjectionError> {
+        self.storage
+            .read_coordination_log_after(session_id, after_seq)
+            .map_err(|e| InjectionError::StorageError(e.to_string()))
+    }
+
     /// Broadcast a message to all workers in a session
     #[allow(dead_code)]
     pub fn broadcast_to_workers(
@@ -408,6 +725,46 @@ impl InjectionManager {
 
 }
 
+/// The digest typed into an agent's PTY: who wrote each unread message and the
+/// start of it, oldest first, and how to read them in full.
+fn inbox_digest(
+    session_id: &str,
+    inbox: &str,
+    since: Option<DateTime<Utc>>,
+    unread: &[ConversationMessage],
+    max_items: usize,
+) -> String {
+    let shown = max_items.max(1);
+    let mut lines = vec![format!(
+        "[INBOX DIGEST] You have {} unread message(s) in your inbox:",
+        unread.len()
+    )];
+    for message in unread.iter().take(shown) {
+        let first_line = message.content.lines().next().unwrap_or("").trim();
+        let mut excerpt: String = first_line.chars().take(DIGEST_EXCERPT_CHARS).collect();
+        if excerpt.len() < message.content.trim().len() {
+            excerpt.push('…');
+        }
+        lines.push(format!(
+            "- @{} at {}: {}",
+            message.from,
+            message.timestamp.format("%H:%M UTC"),
+            excerpt
+        ));
+    }
+    if unread.len() > shown {
+        lines.push(format!("- ...and {} more", unread.len() - shown));
+    }
+    let query = since
+        .map(|since| format!("?since={}", since.to_rfc3339_opts(SecondsFormat::Millis, true)))
+        .unwrap_or_default();
+    lines.push(format!(
+        "Read them in full: curl -fsS \"http://localhost:18800/api/sessions/{}/conversations/{}{}\"",
+        session_id, inbox, query
+    ));
+    lines.join("\n")
+}
+
 fn is_qa_worker_id(agent_id: &str) -> bool {
     agent_id.contains("-qa-worker-")
 }
@@ -469,6 +826,120 @@ mod tests {
         assert_eq!(format_agent_display("abc123-planner-1-worker-2"), "WORKER-2");
     }
 
+    #[test]
+    fn test_progress_acknowledges_delivered_task() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let storage = SessionStorage::new_with_base(temp_dir.path().to_path_buf()).unwrap();
+        let manager = InjectionManager::new(Arc::new(RwLock::new(PtyManager::new())), storage);
+        let worker = "abc123-worker-1";
+
+        manager
+            .record_delivery("abc123", "msg-1", worker, "Implement the parser")
+            .unwrap();
+        manager
+            .record_delivery("abc123", "msg-2", "abc123-worker-2", "Write the tests")
+            .unwrap();
+        assert_eq!(
+            manager
+                .unacknowledged_messages("abc123", Some(worker))
+                .unwrap()
+                .len(),
+            1
+        );
+
+        assert!(matches!(
+            manager.record_progress("abc123", "abc123-worker-2", "started", Some("msg-1")),
+            Err(InjectionError::MessageNotFound(_))
+        ));
+        let acknowledged = manager
+            .record_progress("abc123", worker, "parser half done", Some("msg-1"))
+            .unwrap()
+            .unwrap();
+        assert_eq!(acknowledged.message_id, "msg-1");
+        assert!(acknowledged.acknowledged_at.is_some());
+
+        let pending = manager.unacknowledged_messages("abc123", None).unwrap();
+        assert_eq!(pending.len(), 1);
+        assert_eq!(pending[0].message_id, "msg-2");
+        let log = manager.get_coordination_log("abc123", None).unwrap();
+        assert_eq!(log.last().unwrap().content, "PROGRESS: parser half done");
+    }
+
+    #[test]
+    fn test_broadcast_is_logged_once_and_rate_limited_per_session() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let storage = SessionStorage::new_with_base(temp_dir.path().to_path_buf()).unwrap();
+        let manager = InjectionManager::new(Arc::new(RwLock::new(PtyManager::new())), storage);
+        let targets = vec![
+            ("abc123-queen".to_string(), SubmitKey::Enter),
+            ("abc123-worker-1".to_string(), SubmitKey::Enter),
+        ];
+
+        let report = manager
+            .operator_broadcast("abc123", &targets, "Stop, requirements changed")
+            .unwrap();
+        // No PTYs are running here, so every write fails and is reported.
+        assert!(report.delivered.is_empty());
+        assert_eq!(report.failed.len(), 2);
+        assert_eq!(report.failed[0].agent_id, "abc123-queen");
+
+        let log = manager.get_coordination_log("abc123", None).unwrap();
+        assert_eq!(log.len(), 1);
+        assert_eq!(log[0].to, "ALL");
+        assert_eq!(log[0].content, "[OPERATOR BROADCAST] Stop, requirements changed");
+
+        assert!(matches!(
+            manager.operator_broadcast("abc123", &targets, "again"),
+            Err(InjectionError::RateLimited(_))
+        ));
+        assert!(manager.operator_broadcast("def456", &targets, "other session").is_ok());
+    }
+
+    #[test]
+    fn test_inbox_digest_summarizes_unread_messages_and_waits_for_delivery() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let storage = SessionStorage::new_with_base(temp_dir.path().to_path_buf()).unwrap();
+        let conversations = storage.session_dir("abc123").join("conversations");
+        std::fs::create_dir_all(&conversations).unwrap();
+        std::fs::write(
+            conversations.join("worker-1.md"),
+            "---\n[2026-01-05T10:00:00Z] from @queen\nTake the parser next.\nDetails in the plan.\n\n\
+             ---\n[2026-01-05T10:01:00Z] from @worker-1\nOn it.\n\n\
+             ---\n[2026-01-05T10:02:00Z] from @worker-2\nI changed the lexer API.\n\n",
+        )
+        .unwrap();
+        let manager = InjectionManager::new(Arc::new(RwLock::new(PtyManager::new())), storage);
+
+        let unread: Vec<ConversationMessage> = manager
+            .storage
+            .conversation_messages("abc123", "worker-1")
+            .unwrap()
+            .into_iter()
+            .filter(|message| message.from != "worker-1")
+            .collect();
+        let digest = inbox_digest("abc123", "worker-1", None, &unread, 1);
+        assert!(digest.starts_with("[INBOX DIGEST] You have 2 unread message(s)"), "{digest}");
+        assert!(digest.contains("- @queen at 10:00 UTC: Take the parser next.…"), "{digest}");
+        assert!(digest.contains("- ...and 1 more"), "{digest}");
+        assert!(digest.ends_with("/api/sessions/abc123/conversations/worker-1\""), "{digest}");
+
+        // Without a running PTY nothing is delivered, so the messages stay unread.
+        let target = DigestTarget {
+            agent_id: "abc123-worker-1".to_string(),
+            inbox: "worker-1".to_string(),
+            submit: SubmitKey::Enter,
+        };
+        assert!(manager
+            .inject_inbox_digests("abc123", &[target], 5)
+            .unwrap()
+            .is_empty());
+        assert!(manager
+            .storage
+            .load_inbox_digest_cursors("abc123")
+            .unwrap()
+            .is_empty());
+    }
+
     #[test]
     fn test_role_boundaries() {
         assert!(is_qa_worker_id("abc123-qa-worker-2"));
@@ -1,8 +1,10 @@
+pub mod approvals;
 mod contracts;
 mod injection;
 pub mod queue_manager;
 mod state;
 
+pub use approvals::{ApprovalGate, ApprovalRequest, GatedAction};
 pub use contracts::*;
 pub use injection::*;
 pub use queue_manager::QueueManager;
@@ -29,20 +31,45 @@ pub enum MessageType {
 pub struct CoordinationMessage {
     pub id: String,
     pub timestamp: DateTime<Utc>,
+    /// Position in the session's coordination log, assigned when the message is
+    /// logged. `None` for messages not read back from a log, and for lines agents
+    /// appended to it directly.
+    #[serde(default)]
+    pub seq: Option<u64>,
     pub from: String,
     pub to: String,
     pub content: String,
+    /// IDs of the files in `coordination/attachments/` the message refers to. Logged
+    /// as `[ATTACH <id>]` tokens in the content.
+    #[serde(default)]
+    pub attachments: Vec<String>,
     pub message_type: MessageType,
 }
 
+/// A TASK injected into a worker's terminal. The worker acknowledges it by posting
+/// a PROGRESS update that names `message_id`; until then the Queen or operator can
+/// see it as possibly dropped and send it again.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct MessageDelivery {
+    pub message_id: String,
+    /// Agent ID of the worker the message was typed into.
+    pub to: String,
+    pub content: String,
+    pub delivered_at: DateTime<Utc>,
+    #[serde(default)]
+    pub acknowledged_at: Option<DateTime<Utc>>,
+}
+
 impl CoordinationMessage {
     pub fn new(from: &str, to: &str, content: &str, message_type: MessageType) -> Self {
         Self {
             id: uuid::Uuid::new_v4().to_string(),
             timestamp: Utc::now(),
+            seq: None,
             from: from.to_string(),
             to: to.to_string(),
             content: content.to_string(),
+            attachments: Vec::new(),
             message_type,
         }
     }
@@ -67,7 +94,6 @@ impl CoordinationMessage {
         Self::new(from, to, content, MessageType::QaVerdict)
     }
 
-    #[allow(dead_code)]
     pub fn progress(from: &str, content: &str) -> Self {
         Self::new(from, "LOG", content, MessageType::Progress)
     }
@@ -31,6 +31,9 @@ pub enum StateError {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct WorkerStateInfo {
     pub id: String,
+    /// The operator's display name for the worker ("Payments Backend"), if set.
+    #[serde(default)]
+    pub label: Option<String>,
     pub role: WorkerRole,
     pub cli: String,
     pub status: String,
@@ -40,11 +43,23 @@ pub struct WorkerStateInfo {
     pub last_heartbeat: Option<DateTime<Utc>>,
 }
 
+impl WorkerStateInfo {
+    /// The worker's label, or its ID when it has none.
+    pub fn display_name(&self) -> &str {
+        self.label
+            .as_deref()
+            .filter(|label| !label.trim().is_empty())
+            .unwrap_or(&self.id)
+    }
+}
+
 /// Agent hierarchy node
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct HierarchyNode {
     pub id: String,
     pub role: String,
+    #[serde(default)]
+    pub label: Option<String>,
     pub parent_id: Option<String>,
     pub children: Vec<String>,
 }
@@ -156,21 +171,31 @@ impl StateManager {
         } else {
             // Table header
             content.push_str("## Active Workers\n\n");
-            content.push_str("| ID | Role | CLI | Status | Current Task |\n");
-            content.push_str("|----|------|-----|--------|---------------|\n");
+            content.push_str("| ID | Name | Role | CLI | Status | Current Task |\n");
+            content.push_str("|----|------|------|-----|--------|---------------|\n");
 
             for worker in workers {
                 let task = worker.current_task.as_deref().unwrap_or("-");
                 content.push_str(&format!(
-                    "| {} | {} | {} | {} | {} |\n",
-                    worker.id, worker.role.label, worker.cli, worker.status, task
+                    "| {} | {} | {} | {} | {} | {} |\n",
+                    worker.id,
+                    worker.display_name(),
+                    worker.role.label,
+                    worker.cli,
+                    worker.status,
+                    task
                 ));
             }
 
             // Worker capabilities section
             content.push_str("\n## Worker Capabilities\n\n");
             for worker in workers {
-                content.push_str(&format!("### {} ({})\n", worker.id, worker.role.label));
+                content.push_str(&format!(
+                    "### {} - {} ({})\n",
+                    worker.id,
+                    worker.display_name(),
+                    worker.role.label
+                ));
                 content.push_str(&format!("- CLI: {}\n", worker.cli));
                 content.push_str(&format!("- Specialization: {}\n", self.get_role_description(&worker.role)));
                 content.push_str("\n");
@@ -213,6 +238,7 @@ impl StateManager {
             nodes.into_iter().filter(|n| n.role != "Queen" && n.role != "Evaluator" && !n.role.starts_with("QaWorker-")).map(|n| {
                 WorkerStateInfo {
                     id: n.id,
+                    label: n.label,
                     role: WorkerRole {
                         role_type: n.role.clone(),
                         label: n.role,
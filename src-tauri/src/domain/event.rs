@@ -34,6 +34,8 @@ pub enum EventType {
     WorkerClaimFailed,
     WorkerReclaimed,
     WorkerFinalized,
+    /// A time-boxed phase ran past its budget and grace period and was ended.
+    PhaseTimeout,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
@@ -177,15 +177,42 @@ pub struct ResumeReport {
     pub interrupted: Vec<RunJournalEntry>,
     /// Ledger effects that could not be confirmed and need human attention.
     pub uncertain: Vec<LedgerEntry>,
+    /// How each agent of a still-active session was restored.
+    #[serde(default)]
+    pub agents: Vec<AgentReconciliation>,
 }
 
 impl ResumeReport {
     /// True when there is nothing to surface (a clean resume).
     pub fn is_empty(&self) -> bool {
-        self.skipped.is_empty() && self.interrupted.is_empty() && self.uncertain.is_empty()
+        self.skipped.is_empty()
+            && self.interrupted.is_empty()
+            && self.uncertain.is_empty()
+            && self.agents.is_empty()
     }
 }
 
+/// What resume found for one agent of a session that had not finished.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AgentResumeState {
+    /// Its process is still running.
+    Running,
+    /// Its task file says COMPLETED.
+    Completed,
+    /// No process and no finished task: it has to be restarted.
+    MissingPty,
+}
+
+/// One agent's line in the [`ResumeReport`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AgentReconciliation {
+    pub agent_id: String,
+    pub state: AgentResumeState,
+    /// The worker's task-file status (`ACTIVE`, `COMPLETED`, ...), when it has one.
+    pub task_status: Option<String>,
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
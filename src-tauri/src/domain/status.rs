@@ -115,6 +115,7 @@ mod tests {
         assert_enum_round_trip(EventType::WorkerClaimFailed, "\"worker_claim_failed\"");
         assert_enum_round_trip(EventType::WorkerReclaimed, "\"worker_reclaimed\"");
         assert_enum_round_trip(EventType::WorkerFinalized, "\"worker_finalized\"");
+        assert_enum_round_trip(EventType::PhaseTimeout, "\"phase_timeout\"");
     }
 
     #[test]
@@ -0,0 +1,139 @@
+//! Error taxonomy shared by the Tauri commands and the HTTP API.
+//!
+//! Every failure that reaches the frontend or an agent carries an [`ErrorCode`]
+//! alongside its message, so callers can branch on the kind of failure (retry a
+//! spawn, offer to install a CLI, highlight an invalid field) instead of matching
+//! on message text. Tauri commands return [`HiveError`] itself, which serializes as
+//!
+//! ```json
+//! { "code": "spawn_failed", "message": "...", "retryable": true, "context": { ... } }
+//! ```
+//!
+//! and HTTP error bodies carry the same `code` and `retryable` fields next to
+//! their `error` message. [`ActionError`](crate::actions::ActionError) and
+//! [`ApiError`](crate::http::error::ApiError) both convert into it.
+
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+/// What kind of failure a [`HiveError`] reports.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ErrorCode {
+    /// The session, agent, PTY or file does not exist.
+    NotFound,
+    /// The input was malformed or failed validation.
+    ValidationFailed,
+    /// The request conflicts with the current state (e.g. a session that is not
+    /// in the right phase).
+    Conflict,
+    /// A PTY or agent process could not be started.
+    SpawnFailed,
+    /// A git command failed.
+    GitFailed,
+    /// A CLI the request needs is not installed or not logged in.
+    CliMissing,
+    /// Anything else.
+    Internal,
+}
+
+impl ErrorCode {
+    /// Whether the same request may succeed if simply tried again.
+    pub fn is_retryable(self) -> bool {
+        matches!(self, Self::SpawnFailed | Self::GitFailed | Self::Internal)
+    }
+}
+
+/// A failure with a machine-readable [`ErrorCode`] and optional context (the
+/// session or agent involved, the CLI that is missing, ...).
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, thiserror::Error)]
+#[error("{message}")]
+pub struct HiveError {
+    pub code: ErrorCode,
+    pub message: String,
+    pub retryable: bool,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub context: BTreeMap<String, Value>,
+}
+
+impl HiveError {
+    pub fn new(code: ErrorCode, message: impl Into<String>) -> Self {
+        Self {
+            code,
+            message: message.into(),
+            retryable: code.is_retryable(),
+            context: BTreeMap::new(),
+        }
+    }
+
+    pub fn not_found(message: impl Into<String>) -> Self {
+        Self::new(ErrorCode::NotFound, message)
+    }
+
+    pub fn validation(message: impl Into<String>) -> Self {
+        Self::new(ErrorCode::ValidationFailed, message)
+    }
+
+    pub fn spawn_failed(message: impl Into<String>) -> Self {
+        Self::new(ErrorCode::SpawnFailed, message)
+    }
+
+    pub fn internal(message: impl Into<String>) -> Self {
+        Self::new(ErrorCode::Internal, message)
+    }
+
+    /// Attach a context field, e.g. `.with_context("session_id", id)`.
+    pub fn with_context(mut self, key: impl Into<String>, value: impl Into<Value>) -> Self {
+        self.context.insert(key.into(), value.into());
+        self
+    }
+}
+
+/// Controller methods still report failures as bare strings; those are internal
+/// errors unless the caller knows better.
+impl From<String> for HiveError {
+    fn from(message: String) -> Self {
+        HiveError::internal(message)
+    }
+}
+
+impl From<&str> for HiveError {
+    fn from(message: &str) -> Self {
+        HiveError::internal(message)
+    }
+}
+
+impl From<serde_json::Error> for HiveError {
+    fn from(error: serde_json::Error) -> Self {
+        HiveError::internal(error.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_hive_error_serializes_code_retryable_and_context() {
+        let error = HiveError::spawn_failed("Failed to spawn command: claude")
+            .with_context("agent_id", "s1-worker-1");
+        assert_eq!(
+            serde_json::to_value(&error).unwrap(),
+            json!({
+                "code": "spawn_failed",
+                "message": "Failed to spawn command: claude",
+                "retryable": true,
+                "context": { "agent_id": "s1-worker-1" },
+            })
+        );
+        assert_eq!(error.to_string(), "Failed to spawn command: claude");
+
+        let plain = serde_json::to_value(HiveError::validation("bad id")).unwrap();
+        assert_eq!(plain["code"], "validation_failed");
+        assert_eq!(plain["retryable"], false);
+        assert!(plain.get("context").is_none());
+    }
+}
@@ -145,6 +145,19 @@ impl EventEmitter {
         })).await
     }
 
+    pub async fn emit_phase_timeout(
+        &self,
+        session_id: &str,
+        agent_id: Option<&str>,
+        phase: &str,
+        limit_secs: u64,
+    ) -> Result<(), String> {
+        self.emit(session_id, None, agent_id, EventType::PhaseTimeout, Severity::Warning, json!({
+            "phase": phase,
+            "limit_secs": limit_secs,
+        })).await
+    }
+
     async fn emit(
         &self,
         session_id: &str,
@@ -1,5 +1,7 @@
 mod bus;
 mod emitter;
+mod output;
 
 pub use bus::EventBus;
 pub use emitter::EventEmitter;
+pub use output::{take_utf8, OutputStreams};
@@ -0,0 +1,149 @@
+//! Per-agent output channels for remote viewers.
+//!
+//! `GET /api/sessions/{id}/output/stream?agents=a,b` streams the terminal output of
+//! the named agents only, instead of every PTY's. Its first SSE event, `subscribed`,
+//! carries the stream's ID; `POST /api/output-streams/{stream_id}` with
+//! `{"subscribe": [...], "unsubscribe": [...]}` then changes the agents while the
+//! stream stays open, so a viewer can move from the Queen to a worker without
+//! reconnecting. A stream's subscriptions end when the client disconnects.
+
+use std::collections::{BTreeSet, HashMap};
+use std::sync::Arc;
+
+use parking_lot::RwLock;
+
+#[derive(Debug)]
+struct OutputSubscription {
+    session_id: String,
+    agents: BTreeSet<String>,
+}
+
+/// The open output streams and the agents each one is subscribed to.
+#[derive(Debug, Default)]
+pub struct OutputStreams {
+    streams: RwLock<HashMap<String, OutputSubscription>>,
+}
+
+/// An open stream's subscriptions; they are dropped with it.
+pub struct OutputStreamHandle {
+    streams: Arc<OutputStreams>,
+    id: String,
+}
+
+impl OutputStreamHandle {
+    pub fn id(&self) -> &str {
+        &self.id
+    }
+
+    /// Whether output from `agent_id` should be sent on this stream.
+    pub fn wants(&self, agent_id: &str) -> bool {
+        self.streams
+            .streams
+            .read()
+            .get(&self.id)
+            .is_some_and(|stream| stream.agents.contains(agent_id))
+    }
+}
+
+impl Drop for OutputStreamHandle {
+    fn drop(&mut self) {
+        self.streams.streams.write().remove(&self.id);
+    }
+}
+
+impl OutputStreams {
+    /// Open a stream of `session_id` subscribed to `agents`.
+    pub fn open(
+        self: &Arc<Self>,
+        session_id: &str,
+        agents: impl IntoIterator<Item = String>,
+    ) -> OutputStreamHandle {
+        let id = uuid::Uuid::new_v4().to_string();
+        self.streams.write().insert(
+            id.clone(),
+            OutputSubscription {
+                session_id: session_id.to_string(),
+                agents: agents.into_iter().collect(),
+            },
+        );
+        OutputStreamHandle {
+            streams: Arc::clone(self),
+            id,
+        }
+    }
+
+    /// The session an open stream belongs to.
+    pub fn session_id(&self, stream_id: &str) -> Option<String> {
+        self.streams
+            .read()
+            .get(stream_id)
+            .map(|stream| stream.session_id.clone())
+    }
+
+    /// Subscribe an open stream to `subscribe` and drop `unsubscribe`, returning the
+    /// agents it is subscribed to afterwards. `None` when the stream is not open.
+    pub fn update(
+        &self,
+        stream_id: &str,
+        subscribe: &[String],
+        unsubscribe: &[String],
+    ) -> Option<Vec<String>> {
+        let mut streams = self.streams.write();
+        let stream = streams.get_mut(stream_id)?;
+        stream.agents.extend(subscribe.iter().cloned());
+        for agent_id in unsubscribe {
+            stream.agents.remove(agent_id);
+        }
+        Some(stream.agents.iter().cloned().collect())
+    }
+}
+
+/// Append `chunk` to `pending` and take the text that is complete. A character
+/// split across PTY reads stays in `pending` until its remaining bytes arrive;
+/// bytes that can never be valid UTF-8 are replaced.
+pub fn take_utf8(pending: &mut Vec<u8>, chunk: &[u8]) -> String {
+    pending.extend_from_slice(chunk);
+    let complete = match std::str::from_utf8(pending) {
+        Err(e) if e.error_len().is_none() => e.valid_up_to(),
+        _ => pending.len(),
+    };
+    let rest = pending.split_off(complete);
+    let text = String::from_utf8_lossy(pending).into_owned();
+    *pending = rest;
+    text
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_streams_follow_subscription_changes_until_dropped() {
+        let streams = Arc::new(OutputStreams::default());
+        let handle = streams.open("s", ["s-queen".to_string()]);
+        assert!(handle.wants("s-queen") && !handle.wants("s-worker-1"));
+        assert_eq!(streams.session_id(handle.id()).as_deref(), Some("s"));
+
+        let agents = streams
+            .update(handle.id(), &["s-worker-1".to_string()], &["s-queen".to_string()])
+            .unwrap();
+        assert_eq!(agents, vec!["s-worker-1"]);
+        assert!(handle.wants("s-worker-1") && !handle.wants("s-queen"));
+
+        let id = handle.id().to_string();
+        drop(handle);
+        assert_eq!(streams.session_id(&id), None);
+        assert_eq!(streams.update(&id, &[], &[]), None);
+    }
+
+    #[test]
+    fn test_take_utf8_holds_back_a_split_character() {
+        let mut pending = Vec::new();
+        let bytes = "ok ✓".as_bytes();
+        let (first, second) = bytes.split_at(bytes.len() - 1);
+        assert_eq!(take_utf8(&mut pending, first), "ok ");
+        assert_eq!(take_utf8(&mut pending, second), "✓");
+        assert!(pending.is_empty());
+        assert_eq!(take_utf8(&mut pending, b"\xffa"), "\u{fffd}a");
+    }
+}
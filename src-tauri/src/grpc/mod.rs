@@ -0,0 +1,277 @@
+//! gRPC interface next to the HTTP API.
+//!
+//! `proto/hive.proto` mirrors the session, worker and learnings routes. Each RPC
+//! calls the Axum handler behind the matching route, so both surfaces share
+//! validation, approval gating and storage. Compiled only with the `grpc` cargo
+//! feature and served only when `grpc.enabled` is set.
+
+use std::net::SocketAddr;
+use std::pin::Pin;
+use std::sync::Arc;
+
+use axum::extract::{Path, Query, State};
+use axum::http::{HeaderMap, StatusCode};
+use axum::Json;
+use futures::{Stream, StreamExt};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use tonic::{Request, Response, Status};
+
+use crate::http::error::ApiError;
+use crate::http::handlers::learnings::LearningsFilter;
+use crate::http::handlers::{
+    authorize_session_write, events, learnings, sessions, validate_session_id, workers,
+    UI_TOKEN_HEADER,
+};
+use crate::http::state::AppState;
+
+pub mod proto {
+    tonic::include_proto!("hive.v1");
+}
+
+use proto::hive_server::{Hive, HiveServer};
+use proto::{Empty, JsonReply, LearningsQuery, SessionEvent, SessionJson, SessionRef};
+
+#[cfg_attr(test, allow(dead_code))]
+pub async fn serve(state: Arc<AppState>, port: u16) -> Result<(), tonic::transport::Error> {
+    let addr = SocketAddr::from(([127, 0, 0, 1], port));
+    tonic::transport::Server::builder()
+        .add_service(HiveServer::new(HiveService::new(state)))
+        .serve(addr)
+        .await
+}
+
+pub struct HiveService {
+    state: Arc<AppState>,
+}
+
+fn to_status(err: ApiError) -> Status {
+    let message = err.message;
+    match err.status {
+        StatusCode::BAD_REQUEST => Status::invalid_argument(message),
+        StatusCode::UNAUTHORIZED => Status::unauthenticated(message),
+        StatusCode::FORBIDDEN => Status::permission_denied(message),
+        StatusCode::NOT_FOUND => Status::not_found(message),
+        StatusCode::CONFLICT => Status::failed_precondition(message),
+        _ => Status::internal(message),
+    }
+}
+
+fn reply<T: Serialize>(value: T) -> Result<Response<JsonReply>, Status> {
+    serde_json::to_string(&value)
+        .map(|json| Response::new(JsonReply { json }))
+        .map_err(|e| Status::internal(format!("Failed to serialize reply: {}", e)))
+}
+
+fn parse_body<T: DeserializeOwned>(json: &str) -> Result<T, Status> {
+    serde_json::from_str(json).map_err(|e| Status::invalid_argument(format!("Invalid body: {}", e)))
+}
+
+impl HiveService {
+    pub fn new(state: Arc<AppState>) -> Self {
+        Self { state }
+    }
+
+    /// The request's metadata as HTTP headers. The app's UI never calls gRPC, so the
+    /// UI token never stands in for a session token here.
+    fn write_headers<T>(request: &Request<T>) -> HeaderMap {
+        let mut headers = request.metadata().clone().into_headers();
+        headers.remove(UI_TOKEN_HEADER);
+        headers
+    }
+
+    /// The HTTP middleware's session token check.
+    fn authorize_write<T>(&self, request: &Request<T>, session_id: &str) -> Result<(), Status> {
+        validate_session_id(session_id).map_err(to_status)?;
+        let headers = Self::write_headers(request);
+        authorize_session_write(&self.state, session_id, &headers).map_err(to_status)
+    }
+}
+
+#[tonic::async_trait]
+impl Hive for HiveService {
+    async fn list_sessions(&self, _request: Request<Empty>) -> Result<Response<JsonReply>, Status> {
+        let Json(sessions) = sessions::list_sessions(State(Arc::clone(&self.state)))
+            .await
+            .map_err(to_status)?;
+        reply(sessions)
+    }
+
+    async fn get_session(
+        &self,
+        request: Request<SessionRef>,
+    ) -> Result<Response<JsonReply>, Status> {
+        let session_id = request.into_inner().session_id;
+        let Json(session) = sessions::get_session(State(Arc::clone(&self.state)), Path(session_id))
+            .await
+            .map_err(to_status)?;
+        reply(session)
+    }
+
+    async fn stop_session(
+        &self,
+        request: Request<SessionRef>,
+    ) -> Result<Response<JsonReply>, Status> {
+        self.authorize_write(&request, &request.get_ref().session_id)?;
+        let session_id = request.into_inner().session_id;
+        let Json(output) = sessions::stop_session(State(Arc::clone(&self.state)), Path(session_id))
+            .await
+            .map_err(to_status)?;
+        reply(output)
+    }
+
+    async fn close_session(
+        &self,
+        request: Request<SessionRef>,
+    ) -> Result<Response<JsonReply>, Status> {
+        self.authorize_write(&request, &request.get_ref().session_id)?;
+        let session_id = request.into_inner().session_id;
+        let Json(output) =
+            sessions::close_session(State(Arc::clone(&self.state)), Path(session_id))
+                .await
+                .map_err(to_status)?;
+        reply(output)
+    }
+
+    async fn list_workers(
+        &self,
+        request: Request<SessionRef>,
+    ) -> Result<Response<JsonReply>, Status> {
+        let session_id = request.into_inner().session_id;
+        let Json(workers) = workers::list_workers(State(Arc::clone(&self.state)), Path(session_id))
+            .await
+            .map_err(to_status)?;
+        reply(workers)
+    }
+
+    async fn add_worker(
+        &self,
+        request: Request<SessionJson>,
+    ) -> Result<Response<JsonReply>, Status> {
+        self.authorize_write(&request, &request.get_ref().session_id)?;
+        let SessionJson { session_id, json } = request.into_inner();
+        let body = parse_body(&json)?;
+        let (_, Json(worker)) =
+            workers::add_worker(State(Arc::clone(&self.state)), Path(session_id), Json(body))
+                .await
+                .map_err(to_status)?;
+        reply(worker)
+    }
+
+    async fn list_learnings(
+        &self,
+        request: Request<LearningsQuery>,
+    ) -> Result<Response<JsonReply>, Status> {
+        let LearningsQuery {
+            session_id,
+            category,
+            keywords,
+        } = request.into_inner();
+        let filter = Query(LearningsFilter { category, keywords });
+        let state = State(Arc::clone(&self.state));
+        let Json(learnings) = if session_id.is_empty() {
+            learnings::list_learnings(state, filter).await
+        } else {
+            learnings::list_learnings_for_session(state, Path(session_id), filter).await
+        }
+        .map_err(to_status)?;
+        reply(learnings)
+    }
+
+    async fn submit_learning(
+        &self,
+        request: Request<SessionJson>,
+    ) -> Result<Response<JsonReply>, Status> {
+        if !request.get_ref().session_id.is_empty() {
+            self.authorize_write(&request, &request.get_ref().session_id)?;
+        }
+        let headers = Self::write_headers(&request);
+        let SessionJson { session_id, json } = request.into_inner();
+        let body = Json(parse_body(&json)?);
+        let state = State(Arc::clone(&self.state));
+        let (_, Json(output)) = if session_id.is_empty() {
+            learnings::submit_learning(state, headers, body).await
+        } else {
+            learnings::submit_learning_for_session(state, Path(session_id), body).await
+        }
+        .map_err(to_status)?;
+        reply(output)
+    }
+
+    async fn get_project_dna(
+        &self,
+        request: Request<SessionRef>,
+    ) -> Result<Response<JsonReply>, Status> {
+        let session_id = request.into_inner().session_id;
+        let state = State(Arc::clone(&self.state));
+        let Json(dna) = if session_id.is_empty() {
+            learnings::get_project_dna(state).await
+        } else {
+            learnings::get_project_dna_for_session(state, Path(session_id)).await
+        }
+        .map_err(to_status)?;
+        reply(dna)
+    }
+
+    type StreamEventsStream = Pin<Box<dyn Stream<Item = Result<SessionEvent, Status>> + Send>>;
+
+    async fn stream_events(
+        &self,
+        request: Request<SessionRef>,
+    ) -> Result<Response<Self::StreamEventsStream>, Status> {
+        let session_id = request.into_inner().session_id;
+        validate_session_id(&session_id).map_err(to_status)?;
+
+        let stream = events::session_events(&self.state, session_id).map(|result| {
+            Ok(match result {
+                Ok(event) => SessionEvent {
+                    event_type: serde_json::to_string(&event.event_type)
+                        .unwrap_or_default()
+                        .trim_matches('"')
+                        .to_string(),
+                    json: serde_json::to_string(&event).unwrap_or_default(),
+                    dropped: 0,
+                },
+                Err(dropped) => {
+                    tracing::warn!("gRPC event stream lagged, dropped {} events", dropped);
+                    SessionEvent {
+                        event_type: "lagged".to_string(),
+                        json: String::new(),
+                        dropped,
+                    }
+                }
+            })
+        });
+
+        Ok(Response::new(Box::pin(stream)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_api_errors_keep_their_meaning_as_grpc_codes() {
+        let code = |err: ApiError| to_status(err).code();
+        assert_eq!(
+            code(ApiError::bad_request("x")),
+            tonic::Code::InvalidArgument
+        );
+        assert_eq!(code(ApiError::not_found("x")), tonic::Code::NotFound);
+        assert_eq!(
+            code(ApiError::new(StatusCode::UNAUTHORIZED, "x")),
+            tonic::Code::Unauthenticated
+        );
+        assert_eq!(
+            code(ApiError::new(StatusCode::FORBIDDEN, "x")),
+            tonic::Code::PermissionDenied
+        );
+        assert_eq!(code(ApiError::internal("x")), tonic::Code::Internal);
+
+        assert_eq!(
+            parse_body::<serde_json::Value>("{").unwrap_err().code(),
+            tonic::Code::InvalidArgument
+        );
+    }
+}
@@ -6,18 +6,33 @@ use axum::{
 use serde_json::{json, Value};
 use std::collections::HashMap;
 
+use crate::error::{ErrorCode, HiveError};
+
 pub struct ApiError {
     pub status: StatusCode,
     pub message: String,
+    /// Defaults to the code for `status`; see [`ApiError::with_code`].
+    pub code: ErrorCode,
     /// Optional structured details for enriched error responses (e.g., 409 completion blocked)
     pub details: Option<HashMap<String, Value>>,
 }
 
+/// The code an error with `status` carries unless it was given a more specific one.
+fn code_for_status(status: StatusCode) -> ErrorCode {
+    match status {
+        StatusCode::BAD_REQUEST | StatusCode::UNPROCESSABLE_ENTITY => ErrorCode::ValidationFailed,
+        StatusCode::NOT_FOUND => ErrorCode::NotFound,
+        StatusCode::CONFLICT => ErrorCode::Conflict,
+        _ => ErrorCode::Internal,
+    }
+}
+
 impl ApiError {
     pub fn new(status: StatusCode, message: impl Into<String>) -> Self {
         Self {
             status,
             message: message.into(),
+            code: code_for_status(status),
             details: None,
         }
     }
@@ -38,25 +53,47 @@ impl ApiError {
     /// Create a conflict error with structured details
     pub fn conflict_with_details(message: impl Into<String>, details: HashMap<String, Value>) -> Self {
         Self {
-            status: StatusCode::CONFLICT,
-            message: message.into(),
             details: Some(details),
+            ..Self::new(StatusCode::CONFLICT, message)
+        }
+    }
+
+    /// Report a more specific code than the status implies (e.g. `GitFailed` for a 500).
+    pub fn with_code(mut self, code: ErrorCode) -> Self {
+        self.code = code;
+        self
+    }
+}
+
+/// The HTTP status for each code, for errors that start life as a [`HiveError`].
+impl From<HiveError> for ApiError {
+    fn from(error: HiveError) -> Self {
+        let status = match error.code {
+            ErrorCode::NotFound => StatusCode::NOT_FOUND,
+            ErrorCode::ValidationFailed => StatusCode::BAD_REQUEST,
+            ErrorCode::Conflict | ErrorCode::CliMissing => StatusCode::CONFLICT,
+            ErrorCode::SpawnFailed | ErrorCode::GitFailed | ErrorCode::Internal => {
+                StatusCode::INTERNAL_SERVER_ERROR
+            }
+        };
+        let details = (!error.context.is_empty()).then(|| error.context.into_iter().collect());
+        Self {
+            status,
+            message: error.message,
+            code: error.code,
+            details,
         }
     }
 }
 
 impl IntoResponse for ApiError {
     fn into_response(self) -> Response {
-        let body = if let Some(details) = self.details {
-            // Merge message with details for structured response
-            let mut map = details;
-            map.insert("error".to_string(), json!(self.message));
-            Json(Value::Object(map.into_iter().map(|(k, v)| (k, v)).collect()))
-        } else {
-            Json(json!({
-                "error": self.message
-            }))
-        };
+        // Details are merged into the top level next to the message and code.
+        let mut map = self.details.unwrap_or_default();
+        map.insert("error".to_string(), json!(self.message));
+        map.insert("code".to_string(), json!(self.code));
+        map.insert("retryable".to_string(), json!(self.code.is_retryable()));
+        let body = Json(Value::Object(map.into_iter().collect()));
         (self.status, body).into_response()
     }
 }
@@ -6,6 +6,7 @@
 
 use axum::{
     extract::{Path, State},
+    http::HeaderMap,
     Json,
 };
 use serde::Serialize;
@@ -15,6 +16,7 @@ use std::sync::Arc;
 use crate::actions::render::envelope_for_action_result;
 use crate::actions::{ActionContext, Caller};
 use crate::http::error::ApiError;
+use crate::http::handlers::authorize_session_write;
 use crate::http::state::AppState;
 
 #[derive(Serialize)]
@@ -51,13 +53,19 @@ pub async fn list_actions(
 
 /// POST /api/actions/{name} — dispatch a registered action with caller = Http.
 /// The request body is the action's input JSON; the response is the action's
-/// raw output value wrapped in the `{ renderer?, data }` envelope.
+/// raw output value wrapped in the `{ renderer?, data }` envelope. Actions that
+/// target a session need that session's API token, as its own routes do.
 pub async fn dispatch_action(
     State(state): State<Arc<AppState>>,
     Path(name): Path<String>,
+    headers: HeaderMap,
     body: Option<Json<Value>>,
 ) -> Result<Json<Value>, ApiError> {
     let input = body.map(|Json(value)| value).unwrap_or(Value::Null);
+    let session_id = input.get("session_id").or_else(|| input.get("id"));
+    if let Some(session_id) = session_id.and_then(Value::as_str) {
+        authorize_session_write(&state, session_id, &headers)?;
+    }
     let ctx = ActionContext::new(Caller::Http, Arc::clone(&state));
     let output = state.registry().dispatch(&name, &ctx, input).await?;
     Ok(Json(envelope_for_action_result(&name, output)))
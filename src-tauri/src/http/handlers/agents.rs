@@ -1,17 +1,21 @@
 use std::sync::Arc;
+use std::time::Duration;
 
 use axum::{
     extract::{Path, State},
     http::StatusCode,
     Json,
 };
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
+use tokio::sync::broadcast::{self, error::RecvError};
+use tokio::time::Instant;
 
 use crate::{
+    adapters::SubmitKey,
     coordination::InjectionError,
     domain::{Agent, AgentRole, AgentStatus},
     http::{error::ApiError, state::AppState},
-    pty::{AgentRole as PtyAgentRole, AgentStatus as PtyAgentStatus},
+    pty::{AgentRole as PtyAgentRole, AgentStatus as PtyAgentStatus, OutputTail, PtyOutput},
 };
 
 use super::{
@@ -24,6 +28,42 @@ pub struct SendAgentInputRequest {
     pub input: String,
 }
 
+/// Default and maximum time spent capturing an agent's reply to a chat message.
+const DEFAULT_CHAT_TIMEOUT_MS: u64 = 30_000;
+const MAX_CHAT_TIMEOUT_MS: u64 = 120_000;
+/// A reply is complete once the agent has been silent this long. Agents keep
+/// redrawing a spinner while they work, so silence means they are done.
+const CHAT_REPLY_QUIET: Duration = Duration::from_secs(2);
+/// Lines of a captured reply that are returned.
+const CHAT_REPLY_LINES: usize = 200;
+
+/// POST /api/sessions/{id}/agents/{aid}/chat - Body
+#[derive(Debug, Deserialize)]
+pub struct AgentChatRequest {
+    pub message: String,
+    /// Overrides the keystroke the agent's CLI submits with.
+    #[serde(default)]
+    pub submit_key: Option<SubmitKey>,
+    /// Wait for the agent's reply and return it.
+    #[serde(default)]
+    pub capture_response: bool,
+    /// Upper bound on how long the reply is captured.
+    #[serde(default)]
+    pub timeout_ms: Option<u64>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct AgentChatResponse {
+    pub session_id: String,
+    pub agent_id: String,
+    pub submit_key: SubmitKey,
+    /// The agent's output after the message, escapes removed. `None` unless
+    /// `capture_response` was set.
+    pub response: Option<String>,
+    /// The agent printed nothing before the timeout.
+    pub timed_out: bool,
+}
+
 pub async fn list_agents_in_cell(
     State(state): State<Arc<AppState>>,
     Path((session_id, cell_id)): Path<(String, String)>,
@@ -49,11 +89,7 @@ pub async fn list_agents_in_cell(
             id: agent.id.clone(),
             cell_id: cell_id.clone(),
             role: map_agent_role(&agent.role),
-            label: agent
-                .config
-                .label
-                .clone()
-                .unwrap_or_else(|| agent.id.clone()),
+            label: agent.display_name().to_string(),
             cli: agent.config.cli.clone(),
             model: agent.config.model.clone(),
             status: map_agent_status(&agent.status),
@@ -84,7 +120,7 @@ pub async fn stop_agent(
         }
     }
 
-    let controller = state.session_controller.write();
+    let controller = state.session_controller.read();
     controller
         .stop_agent(&session_id, &agent_id)
         .map_err(ApiError::internal)?;
@@ -92,6 +128,49 @@ pub async fn stop_agent(
     Ok(StatusCode::NO_CONTENT)
 }
 
+fn map_quarantine_error(error: String) -> ApiError {
+    if error.contains("not found") {
+        ApiError::not_found(error)
+    } else {
+        ApiError::internal(error)
+    }
+}
+
+/// POST /api/sessions/{id}/agents/{aid}/quarantine - Block the agent's API access
+/// and coordination messages; its PTY keeps running for inspection
+pub async fn quarantine_agent(
+    State(state): State<Arc<AppState>>,
+    Path((session_id, agent_id)): Path<(String, String)>,
+) -> Result<StatusCode, ApiError> {
+    validate_session_id(&session_id)?;
+    validate_agent_id(&agent_id)?;
+
+    state
+        .session_controller
+        .read()
+        .quarantine_agent(&session_id, &agent_id)
+        .map_err(map_quarantine_error)?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// DELETE /api/sessions/{id}/agents/{aid}/quarantine - Lift the quarantine
+pub async fn release_agent(
+    State(state): State<Arc<AppState>>,
+    Path((session_id, agent_id)): Path<(String, String)>,
+) -> Result<StatusCode, ApiError> {
+    validate_session_id(&session_id)?;
+    validate_agent_id(&agent_id)?;
+
+    state
+        .session_controller
+        .read()
+        .release_agent(&session_id, &agent_id)
+        .map_err(map_quarantine_error)?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
 pub async fn send_agent_input(
     State(state): State<Arc<AppState>>,
     Path((session_id, agent_id)): Path<(String, String)>,
@@ -119,18 +198,7 @@ pub async fn send_agent_input(
         .injection_manager
         .read()
         .operator_inject(&session_id, &agent_id, &req.input)
-        .map_err(|error| match error {
-            InjectionError::SessionNotFound(id) => {
-                ApiError::not_found(format!("Session {} not found", id))
-            }
-            InjectionError::AgentNotFound(id) => {
-                ApiError::not_found(format!("Agent {} not found", id))
-            }
-            InjectionError::NotAuthorized(msg) => ApiError::bad_request(msg),
-            InjectionError::PtyError(msg) | InjectionError::StorageError(msg) => {
-                ApiError::internal(msg)
-            }
-        })?;
+        .map_err(map_injection_error)?;
 
     Ok((
         StatusCode::CREATED,
@@ -142,6 +210,114 @@ pub async fn send_agent_input(
     ))
 }
 
+/// POST /api/sessions/{id}/agents/{aid}/chat - Type a message into the agent's
+/// prompt, submit it with the keystroke its CLI expects, and optionally wait for
+/// the reply
+pub async fn chat_with_agent(
+    State(state): State<Arc<AppState>>,
+    Path((session_id, agent_id)): Path<(String, String)>,
+    Json(req): Json<AgentChatRequest>,
+) -> Result<Json<AgentChatResponse>, ApiError> {
+    validate_session_id(&session_id)?;
+    validate_agent_id(&agent_id)?;
+
+    if req.message.trim().is_empty() {
+        return Err(ApiError::bad_request("message must not be empty"));
+    }
+
+    {
+        let controller = state.session_controller.read();
+        let session = controller
+            .get_session(&session_id)
+            .ok_or_else(|| ApiError::not_found(format!("Session {} not found", session_id)))?;
+        if !session.agents.iter().any(|agent| agent.id == agent_id) {
+            return Err(ApiError::not_found(format!("Agent {} not found", agent_id)));
+        }
+    }
+    let submit_key = req
+        .submit_key
+        .unwrap_or_else(|| state.pty_manager.read().submit_key(&agent_id));
+
+    // Subscribe before writing so the start of the reply cannot be missed.
+    let output = req
+        .capture_response
+        .then(|| state.pty_manager.read().subscribe_output());
+
+    state
+        .injection_manager
+        .read()
+        .operator_chat(&session_id, &agent_id, &req.message, submit_key)
+        .map_err(map_injection_error)?;
+
+    let (response, timed_out) = match output {
+        Some(output) => {
+            let timeout = req
+                .timeout_ms
+                .unwrap_or(DEFAULT_CHAT_TIMEOUT_MS)
+                .min(MAX_CHAT_TIMEOUT_MS);
+            let reply = collect_reply(
+                output,
+                &agent_id,
+                Duration::from_millis(timeout),
+                CHAT_REPLY_QUIET,
+            )
+            .await;
+            let timed_out = reply.is_none();
+            (Some(reply.unwrap_or_default()), timed_out)
+        }
+        None => (None, false),
+    };
+
+    Ok(Json(AgentChatResponse {
+        session_id,
+        agent_id,
+        submit_key,
+        response,
+        timed_out,
+    }))
+}
+
+/// The agent's output until it has been quiet for `quiet` or `timeout` runs out,
+/// with escapes removed. `None` when it printed nothing at all.
+async fn collect_reply(
+    mut output: broadcast::Receiver<PtyOutput>,
+    agent_id: &str,
+    timeout: Duration,
+    quiet: Duration,
+) -> Option<String> {
+    let deadline = Instant::now() + timeout;
+    let mut tail: Option<OutputTail> = None;
+    let mut last_chunk_at: Option<Instant> = None;
+    loop {
+        let wait_until = last_chunk_at.map_or(deadline, |at| (at + quiet).min(deadline));
+        match tokio::time::timeout_at(wait_until, output.recv()).await {
+            Ok(Ok(chunk)) if chunk.id == agent_id => {
+                tail.get_or_insert_with(|| OutputTail::with_limit(CHAT_REPLY_LINES))
+                    .push(&chunk.data);
+                last_chunk_at = Some(Instant::now());
+            }
+            Ok(Ok(_)) | Ok(Err(RecvError::Lagged(_))) => {}
+            Ok(Err(RecvError::Closed)) | Err(_) => break,
+        }
+    }
+    tail.map(|tail| tail.into_lines().join("\n"))
+}
+
+fn map_injection_error(error: InjectionError) -> ApiError {
+    match error {
+        InjectionError::SessionNotFound(id) => {
+            ApiError::not_found(format!("Session {} not found", id))
+        }
+        InjectionError::AgentNotFound(id) => ApiError::not_found(format!("Agent {} not found", id)),
+        InjectionError::NotAuthorized(msg) => ApiError::bad_request(msg),
+        InjectionError::MessageNotFound(msg) => ApiError::not_found(msg),
+        InjectionError::RateLimited(msg) => ApiError::new(StatusCode::CONFLICT, msg),
+        InjectionError::PtyError(msg) | InjectionError::StorageError(msg) => {
+            ApiError::internal(msg)
+        }
+    }
+}
+
 fn map_agent_role(role: &PtyAgentRole) -> AgentRole {
     match role {
         PtyAgentRole::Queen => AgentRole::Queen,
@@ -163,6 +339,44 @@ fn map_agent_status(status: &PtyAgentStatus) -> AgentStatus {
         PtyAgentStatus::Running | PtyAgentStatus::Idle => AgentStatus::Running,
         PtyAgentStatus::WaitingForInput(_) => AgentStatus::WaitingInput,
         PtyAgentStatus::Completed => AgentStatus::Completed,
-        PtyAgentStatus::Error(_) => AgentStatus::Failed,
+        PtyAgentStatus::Crashed(_) | PtyAgentStatus::Error(_) => AgentStatus::Failed,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn chunk(id: &str, data: &str) -> PtyOutput {
+        PtyOutput {
+            id: id.to_string(),
+            data: data.as_bytes().to_vec(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_chat_reply_is_the_agents_own_output_until_it_goes_quiet() {
+        let (tx, rx) = broadcast::channel(16);
+        tx.send(chunk("worker-1", "Looking at it\r\n")).unwrap();
+        tx.send(chunk("worker-2", "unrelated\r\n")).unwrap();
+        tx.send(chunk("worker-1", "\x1b[32mDone\x1b[0m")).unwrap();
+
+        let reply = collect_reply(
+            rx,
+            "worker-1",
+            Duration::from_secs(5),
+            Duration::from_millis(50),
+        )
+        .await;
+        assert_eq!(reply.as_deref(), Some("Looking at it\nDone"));
+
+        let silent = collect_reply(
+            tx.subscribe(),
+            "worker-1",
+            Duration::from_millis(50),
+            Duration::from_millis(50),
+        )
+        .await;
+        assert_eq!(silent, None);
     }
 }
@@ -0,0 +1,110 @@
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    Json,
+};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::sync::Arc;
+use std::time::Duration;
+
+use super::{reject_quarantined_agent, validate_agent_id, validate_session_id};
+use crate::coordination::approvals::approval_reason;
+use crate::coordination::{ApprovalRequest, GatedAction};
+use crate::http::error::ApiError;
+use crate::http::state::AppState;
+
+/// POST /api/sessions/{id}/approvals - Body
+#[derive(Debug, Deserialize)]
+pub struct RequestApprovalRequest {
+    pub agent_id: String,
+    #[serde(flatten)]
+    pub action: GatedAction,
+}
+
+#[derive(Serialize)]
+pub struct ApprovalsResponse {
+    pub approvals: Vec<ApprovalRequest>,
+}
+
+/// Hold a request until the operator approves it, when the approval policy gates
+/// it. Returns immediately for requests the policy lets through.
+pub(crate) async fn require_approval(
+    state: &AppState,
+    session_id: &str,
+    agent_id: Option<&str>,
+    action: GatedAction,
+) -> Result<(), ApiError> {
+    let policy = state.config.read().await.approvals.clone();
+    let Some(reason) = approval_reason(&policy, &action) else {
+        return Ok(());
+    };
+
+    let (request, decision) = state
+        .approvals
+        .park(session_id, agent_id, action, reason.clone());
+    tracing::info!(
+        "Parked approval request {} for session {}: {}",
+        request.id,
+        session_id,
+        reason
+    );
+    state.emit_approval_required(&request);
+
+    match tokio::time::timeout(Duration::from_secs(policy.timeout_secs), decision).await {
+        Ok(Ok(true)) => Ok(()),
+        Ok(Ok(false)) | Ok(Err(_)) => Err(ApiError::new(
+            StatusCode::FORBIDDEN,
+            format!("Operator rejected the request: {}", reason),
+        )),
+        Err(_) => {
+            state.approvals.withdraw(&request.id);
+            Err(ApiError::new(
+                StatusCode::FORBIDDEN,
+                format!(
+                    "No operator approval within {}s: {}",
+                    policy.timeout_secs, reason
+                ),
+            ))
+        }
+    }
+}
+
+/// POST /api/sessions/{id}/approvals - An agent asks before pushing or deleting files.
+/// Blocks until the request is approved (200) or rejected (403).
+pub async fn request_approval(
+    State(state): State<Arc<AppState>>,
+    Path(session_id): Path<String>,
+    Json(req): Json<RequestApprovalRequest>,
+) -> Result<Json<Value>, ApiError> {
+    validate_session_id(&session_id)?;
+    validate_agent_id(&req.agent_id)?;
+    reject_quarantined_agent(&state, &session_id, &req.agent_id)?;
+    if state
+        .session_controller
+        .read()
+        .get_session(&session_id)
+        .is_none()
+    {
+        return Err(ApiError::not_found(format!(
+            "Session {} not found",
+            session_id
+        )));
+    }
+
+    require_approval(&state, &session_id, Some(&req.agent_id), req.action).await?;
+
+    Ok(Json(json!({ "approved": true })))
+}
+
+/// GET /api/sessions/{id}/approvals - Requests in the session waiting for the operator
+pub async fn list_approvals(
+    State(state): State<Arc<AppState>>,
+    Path(session_id): Path<String>,
+) -> Result<Json<ApprovalsResponse>, ApiError> {
+    validate_session_id(&session_id)?;
+
+    Ok(Json(ApprovalsResponse {
+        approvals: state.approvals.pending(Some(&session_id)),
+    }))
+}
@@ -39,6 +39,7 @@ fn session_type_from_persisted(session_type: &crate::storage::SessionTypeInfo) -
 
 fn session_state_from_persisted(state: &str) -> SessionState {
     match state {
+        "Queued" => SessionState::Queued,
         "Planning" => SessionState::Planning,
         "PlanReady" => SessionState::PlanReady,
         "Starting" => SessionState::Starting,
@@ -46,7 +47,6 @@ fn session_state_from_persisted(state: &str) -> SessionState {
         "SpawningDebateRound" => SessionState::SpawningDebateRound(0),
         "WaitingForDebateRound" => SessionState::WaitingForDebateRound(0),
         "SpawningJudge" => SessionState::SpawningJudge,
-        "Judging" => SessionState::Judging,
         "AwaitingVerdictSelection" => SessionState::AwaitingVerdictSelection,
         "MergingWinner" => SessionState::MergingWinner,
         "SpawningEvaluator" => SessionState::SpawningEvaluator,
@@ -113,10 +113,24 @@ fn session_state_from_persisted(state: &str) -> SessionState {
                 .unwrap_or(1);
             SessionState::WaitingForDebateRound(round)
         }
+        value if value.starts_with("Judging") => SessionState::Judging {
+            outstanding: value
+                .trim_start_matches("Judging")
+                .trim_start_matches(':')
+                .parse()
+                .unwrap_or(1),
+        },
         value if value.starts_with("QaInProgress") => {
             SessionState::QaInProgress { iteration: None }
         }
         value if value.starts_with("QaFailed") => SessionState::QaFailed { iteration: 1 },
+        value if value.starts_with("Reviewing") => {
+            let mut parts = value.trim_start_matches("Reviewing").splitn(3, ':').skip(1);
+            SessionState::Reviewing {
+                step: parts.next().and_then(|step| step.parse().ok()).unwrap_or(1),
+                role: parts.next().unwrap_or_default().to_string(),
+            }
+        }
         value if value.starts_with("Failed(") => SessionState::Failed(
             value
                 .trim_start_matches("Failed(")
@@ -170,6 +184,8 @@ fn session_from_persisted(persisted: PersistedSession) -> Session {
         worktree_branch: persisted.worktree_branch,
         no_git: persisted.no_git,
         resume_report: None,
+        pull_request_url: persisted.pull_request_url,
+        spawn_quota: persisted.spawn_quota,
     }
 }
 
@@ -181,7 +197,7 @@ fn load_session_for_cells(state: &AppState, session_id: &str) -> Result<Session,
         return Ok(session);
     }
 
-    match state.storage.load_session(session_id) {
+    match state.backend.load_session(session_id) {
         Ok(session) => Ok(session_from_persisted(session)),
         Err(StorageError::SessionNotFound(_)) => Err(ApiError::not_found(format!(
             "Session {} not found",
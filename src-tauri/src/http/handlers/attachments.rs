@@ -0,0 +1,96 @@
+//! Coordination attachments: files agents upload so a message can say "see the
+//! attached diff" and the agent reading it can fetch the diff.
+
+use axum::{
+    extract::{Multipart, Path, State},
+    http::header,
+    response::IntoResponse,
+    Json,
+};
+use std::sync::Arc;
+
+use super::validate_session_id;
+use crate::http::error::ApiError;
+use crate::http::state::AppState;
+use crate::storage::attachments::{self, Attachment, MAX_ATTACHMENT_BYTES};
+use crate::storage::StorageError;
+
+/// POST /api/sessions/{id}/coordination/attachments - Multipart upload of the
+/// `file` field; the response carries the `[ATTACH <id>]` reference to send
+pub async fn upload_attachment(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<String>,
+    mut multipart: Multipart,
+) -> Result<Json<Attachment>, ApiError> {
+    validate_session_id(&id)?;
+
+    while let Some(field) = multipart
+        .next_field()
+        .await
+        .map_err(|e| ApiError::bad_request(format!("Invalid multipart body: {e}")))?
+    {
+        if field.name() != Some("file") {
+            continue;
+        }
+        let file_name = field.file_name().unwrap_or_default().to_string();
+        let bytes = field
+            .bytes()
+            .await
+            .map_err(|e| ApiError::bad_request(format!("Failed to read the upload: {e}")))?;
+        if bytes.len() > MAX_ATTACHMENT_BYTES {
+            return Err(ApiError::bad_request(format!(
+                "Attachments are limited to {} bytes",
+                MAX_ATTACHMENT_BYTES
+            )));
+        }
+
+        let storage = Arc::clone(&state.storage);
+        let session_id = id.clone();
+        let saved = tokio::task::spawn_blocking(move || {
+            storage.save_attachment(&session_id, &file_name, &bytes)
+        })
+        .await
+        .map_err(|e| ApiError::internal(e.to_string()))?;
+        return match saved {
+            Ok(attachment) => Ok(Json(attachment)),
+            Err(StorageError::SessionNotFound(_)) => {
+                Err(ApiError::not_found(format!("Session {} not found", id)))
+            }
+            Err(e) => Err(ApiError::internal(format!("Failed to save attachment: {e}"))),
+        };
+    }
+
+    Err(ApiError::bad_request("The multipart body has no `file` field"))
+}
+
+/// GET /api/sessions/{id}/coordination/attachments - Attachments uploaded to the
+/// session, oldest first
+pub async fn list_attachments(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<String>,
+) -> Result<Json<Vec<Attachment>>, ApiError> {
+    validate_session_id(&id)?;
+    let storage = Arc::clone(&state.storage);
+    let attachments = tokio::task::spawn_blocking(move || storage.list_attachments(&id))
+        .await
+        .map_err(|e| ApiError::internal(e.to_string()))?
+        .map_err(|e| ApiError::internal(format!("Failed to list attachments: {e}")))?;
+    Ok(Json(attachments))
+}
+
+/// GET /api/sessions/{id}/coordination/attachments/{attachment_id} - The file itself
+pub async fn get_attachment(
+    State(state): State<Arc<AppState>>,
+    Path((id, attachment_id)): Path<(String, String)>,
+) -> Result<impl IntoResponse, ApiError> {
+    validate_session_id(&id)?;
+    let path = attachments::find(&state.storage.attachments_dir(&id), &attachment_id)
+        .ok_or_else(|| ApiError::not_found(format!("Attachment {} not found", attachment_id)))?;
+    let bytes = tokio::fs::read(&path)
+        .await
+        .map_err(|e| ApiError::internal(format!("Failed to read attachment: {e}")))?;
+    Ok((
+        [(header::CONTENT_TYPE, attachments::content_type(&attachment_id))],
+        bytes,
+    ))
+}
@@ -11,7 +11,7 @@ use std::sync::Arc;
 use crate::http::error::ApiError;
 use crate::http::state::AppState;
 use crate::storage::ConversationMessage;
-use super::{validate_agent_id, validate_session_id};
+use super::{reject_quarantined_agent, validate_agent_id, validate_session_id};
 
 const MAX_MESSAGE_CONTENT_LEN: usize = 1_048_576; // 1MB - allows large pastes
 const MAX_FROM_LEN: usize = 64;
@@ -72,6 +72,8 @@ pub async fn append_conversation(
     validate_agent_id(&agent_id)?;
     let from = sanitize_text(&req.from, MAX_FROM_LEN, "from")?;
     validate_agent_id(&from)?;
+    reject_quarantined_agent(&state, &session_id, &from)?;
+    reject_quarantined_agent(&state, &session_id, &agent_id)?;
     let content = sanitize_text(&req.content, MAX_MESSAGE_CONTENT_LEN, "content")?;
 
     let message = state
@@ -0,0 +1,199 @@
+//! Read-only HTML view of a session's coordination log, for watching a session
+//! from a browser without the desktop app. The page is rendered server-side and
+//! needs no scripts: filters are a plain GET form and the page reloads itself.
+
+use axum::{
+    extract::{Path, Query, State},
+    response::Html,
+};
+use serde::Deserialize;
+use std::fmt::Write as _;
+use std::sync::Arc;
+
+use super::validate_session_id;
+use crate::coordination::CoordinationMessage;
+use crate::http::error::ApiError;
+use crate::http::state::AppState;
+use crate::storage::coordination_stats::{category, CATEGORIES};
+
+const DEFAULT_LIMIT: usize = 500;
+const MAX_LIMIT: usize = 5000;
+const DEFAULT_REFRESH_SECS: u64 = 10;
+
+const STYLE: &str = "<style>
+body { font-family: system-ui, sans-serif; margin: 1.5rem; color: #1f2328; }
+h1 { font-size: 1.25rem; }
+form { display: flex; gap: 0.5rem; margin-bottom: 0.75rem; }
+.summary, .empty { color: #59636e; }
+table { border-collapse: collapse; width: 100%; font-size: 0.875rem; }
+th, td { text-align: left; vertical-align: top; padding: 0.3rem 0.5rem; border-bottom: 1px solid #d1d9e0; }
+td:nth-child(1), td:nth-child(2) { white-space: nowrap; }
+td:last-child { white-space: pre-wrap; word-break: break-word; }
+tr.task td:nth-child(2) { color: #0969da; }
+tr.progress td:nth-child(2) { color: #8250df; }
+tr.completion td:nth-child(2) { color: #1a7f37; }
+tr.error td:nth-child(2) { color: #d1242f; font-weight: 600; }
+tr.system td:nth-child(2) { color: #59636e; }
+</style>
+";
+
+#[derive(Debug, Deserialize, Default)]
+pub struct CoordinationViewQuery {
+    /// Message category (`TASK`, `ERROR`, ...), as counted by the stats endpoint.
+    #[serde(default)]
+    pub kind: Option<String>,
+    /// Only messages from or to this agent (`QUEEN`, `WORKER-1`).
+    #[serde(default)]
+    pub agent: Option<String>,
+    /// Only messages containing this text.
+    #[serde(default)]
+    pub q: Option<String>,
+    /// Most recent lines to read; 500 by default.
+    #[serde(default)]
+    pub limit: Option<usize>,
+    /// Seconds between reloads; 0 turns reloading off.
+    #[serde(default)]
+    pub refresh: Option<u64>,
+}
+
+impl CoordinationViewQuery {
+    fn matches(&self, message: &CoordinationMessage) -> bool {
+        non_blank(&self.kind).is_none_or(|kind| {
+            category(&message.from, &message.to, &message.content).eq_ignore_ascii_case(kind)
+        }) && non_blank(&self.agent).is_none_or(|agent| {
+            message.from.eq_ignore_ascii_case(agent) || message.to.eq_ignore_ascii_case(agent)
+        }) && non_blank(&self.q).is_none_or(|text| {
+            message
+                .content
+                .to_lowercase()
+                .contains(&text.to_lowercase())
+        })
+    }
+}
+
+fn non_blank(value: &Option<String>) -> Option<&str> {
+    value
+        .as_deref()
+        .map(str::trim)
+        .filter(|value| !value.is_empty())
+}
+
+/// GET /api/sessions/{id}/coordination/view?kind=&agent=&q=&limit=&refresh= - The
+/// coordination log as a self-contained HTML page, newest message first
+pub async fn view_coordination_log(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<String>,
+    Query(query): Query<CoordinationViewQuery>,
+) -> Result<Html<String>, ApiError> {
+    validate_session_id(&id)?;
+
+    let limit = query.limit.unwrap_or(DEFAULT_LIMIT).clamp(1, MAX_LIMIT);
+    let storage = Arc::clone(&state.backend);
+    let session_id = id.clone();
+    let messages = tokio::task::spawn_blocking(move || {
+        storage.read_coordination_log(&session_id, Some(limit))
+    })
+    .await
+    .map_err(|e| ApiError::internal(format!("Task join error: {e}")))?
+    .map_err(|e| ApiError::internal(format!("Failed to read coordination log: {e}")))?;
+
+    Ok(Html(render_page(&id, &messages, &query)))
+}
+
+fn render_page(
+    session_id: &str,
+    messages: &[CoordinationMessage],
+    query: &CoordinationViewQuery,
+) -> String {
+    let shown: Vec<&CoordinationMessage> = messages
+        .iter()
+        .rev()
+        .filter(|message| query.matches(message))
+        .collect();
+    let refresh = query.refresh.unwrap_or(DEFAULT_REFRESH_SECS);
+    let session_id = escape_html(session_id);
+
+    let mut html =
+        String::from("<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">\n");
+    if refresh > 0 {
+        let _ = writeln!(html, "<meta http-equiv=\"refresh\" content=\"{refresh}\">");
+    }
+    let _ = writeln!(html, "<title>Coordination log - {session_id}</title>");
+    html.push_str(STYLE);
+    html.push_str("</head>\n<body>\n");
+    let _ = writeln!(html, "<h1>Coordination log <code>{session_id}</code></h1>");
+
+    html.push_str(
+        "<form method=\"get\">\n<select name=\"kind\"><option value=\"\">All types</option>",
+    );
+    for kind in CATEGORIES {
+        let selected = query
+            .kind
+            .as_deref()
+            .is_some_and(|selected| selected.trim().eq_ignore_ascii_case(kind));
+        let _ = write!(
+            html,
+            "<option value=\"{kind}\"{}>{kind}</option>",
+            if selected { " selected" } else { "" }
+        );
+    }
+    let text = |value: &Option<String>| escape_html(value.as_deref().unwrap_or_default());
+    let _ = write!(
+        html,
+        "</select>\n<input name=\"agent\" placeholder=\"Agent\" value=\"{}\">\n\
+         <input name=\"q\" placeholder=\"Text\" value=\"{}\">\n\
+         <input type=\"hidden\" name=\"limit\" value=\"{}\">\n\
+         <input type=\"hidden\" name=\"refresh\" value=\"{}\">\n\
+         <button type=\"submit\">Filter</button>\n</form>\n",
+        text(&query.agent),
+        text(&query.q),
+        query.limit.unwrap_or(DEFAULT_LIMIT).clamp(1, MAX_LIMIT),
+        refresh
+    );
+    let _ = writeln!(
+        html,
+        "<p class=\"summary\">{} of the last {} messages</p>",
+        shown.len(),
+        messages.len()
+    );
+
+    if shown.is_empty() {
+        html.push_str("<p class=\"empty\">No messages.</p>\n");
+    } else {
+        html.push_str(
+            "<table>\n<thead><tr><th>Time (UTC)</th><th>Type</th><th>From</th><th>To</th>\
+             <th>Message</th></tr></thead>\n<tbody>\n",
+        );
+        for message in shown {
+            let kind = category(&message.from, &message.to, &message.content);
+            let _ = writeln!(
+                html,
+                "<tr class=\"{}\"><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td></tr>",
+                kind.to_ascii_lowercase(),
+                message.timestamp.format("%Y-%m-%d %H:%M:%S"),
+                kind,
+                escape_html(&message.from),
+                escape_html(&message.to),
+                escape_html(&message.content)
+            );
+        }
+        html.push_str("</tbody>\n</table>\n");
+    }
+    html.push_str("</body>\n</html>\n");
+    html
+}
+
+fn escape_html(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
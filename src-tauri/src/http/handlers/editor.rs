@@ -0,0 +1,30 @@
+use axum::{extract::State, http::HeaderMap, Json};
+use serde_json::Value;
+use std::sync::Arc;
+
+use super::{authorize_session_write, validate_session_id};
+use crate::actions::{ActionContext, Caller};
+use crate::http::error::ApiError;
+use crate::http::state::AppState;
+
+/// POST /api/open-file - Open `path` at `line` and `column` in the operator's editor
+/// (`editor` in `config.json`). The path must lie in the project or a worktree of
+/// `session_id`, and the request needs that session's API token.
+pub async fn open_file(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Json(input): Json<Value>,
+) -> Result<Json<Value>, ApiError> {
+    let session_id = input
+        .get("session_id")
+        .and_then(Value::as_str)
+        .ok_or_else(|| ApiError::bad_request("session_id is required"))?;
+    validate_session_id(session_id)?;
+    authorize_session_write(&state, session_id, &headers)?;
+    let ctx = ActionContext::new(Caller::Http, Arc::clone(&state));
+    let output = state
+        .registry()
+        .dispatch("editor.open_file", &ctx, input)
+        .await?;
+    Ok(Json(output))
+}
@@ -122,10 +122,12 @@ pub async fn add_evaluator(
         description: None,
         role: None,
         initial_prompt: req.initial_task,
+        allowed_paths: Vec::new(),
+        denied_paths: Vec::new(),
     };
 
     let evaluator_id = {
-        let controller = state.session_controller.write();
+        let controller = state.session_controller.read();
         controller
             .launch_evaluator(&session_id, config, false)
             .map_err(ApiError::internal)?
@@ -211,10 +213,12 @@ pub async fn add_qa_worker(
         description: None,
         role: None,
         initial_prompt: req.initial_task,
+        allowed_paths: Vec::new(),
+        denied_paths: Vec::new(),
     };
 
     let agent_info = {
-        let controller = state.session_controller.write();
+        let controller = state.session_controller.read();
         controller
             .add_qa_worker(
                 &session_id,
@@ -378,7 +382,7 @@ fn append_operator_log(state: &AppState, session_id: &str, action: &str, detail:
         "OPERATOR",
         &format!("[{}] Operator forced {} for session {}", action, detail, session_id),
     );
-    let _ = state.storage.append_coordination_log(session_id, &msg);
+    let _ = state.backend.append_coordination_log(session_id, &msg);
 }
 
 fn override_log_details(verdict: &str) -> Result<(&'static str, &'static str), ApiError> {
@@ -556,7 +560,7 @@ pub async fn post_verdict(
 
     let verdict_message = CoordinationMessage::qa_verdict(&evaluator_id, &queen_id, &verdict_content);
     if let Err(err) = state
-        .storage
+        .backend
         .append_coordination_log(&session_id, &verdict_message)
     {
         tracing::warn!(
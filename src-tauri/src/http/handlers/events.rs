@@ -1,17 +1,20 @@
 //! Event handlers: query and SSE streaming endpoints.
 
 use axum::{
-    extract::{Path, State},
+    extract::{Path, Query, State},
     response::sse::{Event, KeepAlive, Sse},
     Json,
 };
 use futures::stream::StreamExt;
+use serde::Deserialize;
+use std::collections::HashMap;
 use std::convert::Infallible;
-use std::sync::Arc;
+use tokio::sync::broadcast::error::RecvError;
 use tokio_stream::wrappers::BroadcastStream;
 use tokio_stream::wrappers::errors::BroadcastStreamRecvError;
 
 use crate::domain::event::Event as DomainEvent;
+use crate::events::take_utf8;
 use crate::http::error::ApiError;
 use crate::http::handlers::validate_session_id;
 use crate::http::state::AppState;
@@ -44,6 +47,24 @@ pub async fn get_events(
     Ok(Json(events))
 }
 
+/// Live events for one session. A lagging subscriber gets `Err(n)` for the `n`
+/// events it missed. Shared by the SSE route and the gRPC `StreamEvents` RPC.
+pub(crate) fn session_events(
+    state: &AppState,
+    session_id: String,
+) -> impl futures::Stream<Item = Result<DomainEvent, u64>> {
+    BroadcastStream::new(state.event_bus.subscribe()).filter_map(move |result| {
+        let sid = session_id.clone();
+        async move {
+            match result {
+                Ok(event) if event.session_id == sid => Some(Ok(event)),
+                Ok(_) => None, // Filtered out (different session)
+                Err(BroadcastStreamRecvError::Lagged(n)) => Some(Err(n)),
+            }
+        }
+    })
+}
+
 /// GET /api/sessions/{id}/stream
 /// SSE endpoint for real-time event streaming.
 pub async fn stream_events(
@@ -52,40 +73,156 @@ pub async fn stream_events(
 ) -> Result<Sse<impl futures::Stream<Item = Result<Event, Infallible>>>, ApiError> {
     validate_session_id(&session_id)?;
 
-    let event_bus = Arc::clone(&state.event_bus);
-    let session_id_filter = session_id.clone();
-
-    // Subscribe to all events and filter by session_id
-    let receiver = event_bus.subscribe();
-    let stream = BroadcastStream::new(receiver)
-        .filter_map(move |result| {
-            let sid = session_id_filter.clone();
-            async move {
-                match result {
-                    Ok(event) if event.session_id == sid => {
-                        // Serialize event to JSON for SSE data field
-                        let json = serde_json::to_string(&event).ok()?;
-                        let event_type = serde_json::to_string(&event.event_type)
-                            .ok()?
-                            .trim_matches('"')
-                            .to_string();
-                        
-                        Some(Ok(Event::default()
-                            .event(event_type)
-                            .data(json)))
+    let stream = session_events(&state, session_id).filter_map(|result| async move {
+        match result {
+            Ok(event) => {
+                // Serialize event to JSON for SSE data field
+                let json = serde_json::to_string(&event).ok()?;
+                let event_type = serde_json::to_string(&event.event_type)
+                    .ok()?
+                    .trim_matches('"')
+                    .to_string();
+
+                Some(Ok(Event::default().event(event_type).data(json)))
+            }
+            Err(n) => {
+                // Emit synthetic SSE frame for lagged clients
+                // CONTRACT: frontend expects event name 'lagged' and JSON {"dropped": N}
+                tracing::warn!("SSE client lagged, dropped {} events", n);
+                Some(Ok(Event::default()
+                    .event("lagged")
+                    .data(format!(r#"{{"dropped":{}}}"#, n))))
+            }
+        }
+    });
+
+    Ok(Sse::new(stream).keep_alive(KeepAlive::default()))
+}
+
+#[derive(Debug, Deserialize)]
+pub struct OutputStreamQuery {
+    /// Comma-separated IDs of the agents to stream from the start.
+    #[serde(default)]
+    pub agents: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct UpdateOutputStreamRequest {
+    #[serde(default)]
+    pub subscribe: Vec<String>,
+    #[serde(default)]
+    pub unsubscribe: Vec<String>,
+}
+
+/// Refuse agent IDs that are not agents of `session_id`.
+fn check_session_agents(
+    state: &AppState,
+    session_id: &str,
+    agents: &[String],
+) -> Result<(), ApiError> {
+    let controller = state.session_controller.read();
+    let session = controller
+        .get_session(session_id)
+        .ok_or_else(|| ApiError::not_found(format!("Session {} not found", session_id)))?;
+    match agents
+        .iter()
+        .find(|agent_id| !session.agents.iter().any(|agent| &agent.id == *agent_id))
+    {
+        Some(agent_id) => Err(ApiError::not_found(format!(
+            "Agent {} not found in session {}",
+            agent_id, session_id
+        ))),
+        None => Ok(()),
+    }
+}
+
+/// GET /api/sessions/{id}/output/stream?agents=a,b
+/// SSE stream of the terminal output of the subscribed agents only (see
+/// `events::output`). The first event, `subscribed`, names the stream for
+/// `POST /api/output-streams/{stream_id}`; each `output` event is
+/// `{"agent_id", "data"}`.
+pub async fn stream_agent_output(
+    State(state): State<std::sync::Arc<AppState>>,
+    Path(session_id): Path<String>,
+    Query(query): Query<OutputStreamQuery>,
+) -> Result<Sse<impl futures::Stream<Item = Result<Event, Infallible>>>, ApiError> {
+    validate_session_id(&session_id)?;
+    let mut agents: Vec<String> = query
+        .agents
+        .unwrap_or_default()
+        .split(',')
+        .map(str::trim)
+        .filter(|agent_id| !agent_id.is_empty())
+        .map(String::from)
+        .collect();
+    agents.sort();
+    agents.dedup();
+    check_session_agents(&state, &session_id, &agents)?;
+
+    // Subscribe before announcing the stream so no output after it is missed.
+    let output = state.pty_manager.read().subscribe_output();
+    let handle = state.output_streams.open(&session_id, agents.iter().cloned());
+    let subscribed = Event::default().event("subscribed").data(
+        serde_json::json!({
+            "stream_id": handle.id(),
+            "session_id": session_id,
+            "agents": agents,
+        })
+        .to_string(),
+    );
+
+    let chunks = futures::stream::unfold(
+        (output, handle, HashMap::<String, Vec<u8>>::new()),
+        |(mut output, handle, mut pending)| async move {
+            loop {
+                let event = match output.recv().await {
+                    Ok(chunk) if handle.wants(&chunk.id) => {
+                        let partial = pending.entry(chunk.id.clone()).or_default();
+                        let data = take_utf8(partial, &chunk.data);
+                        if data.is_empty() {
+                            continue;
+                        }
+                        Event::default().event("output").data(
+                            serde_json::json!({ "agent_id": chunk.id, "data": data }).to_string(),
+                        )
                     }
-                    Ok(_) => None, // Filtered out (different session)
-                    Err(BroadcastStreamRecvError::Lagged(n)) => {
-                        // Emit synthetic SSE frame for lagged clients
-                        // CONTRACT: frontend expects event name 'lagged' and JSON {"dropped": N}
-                        tracing::warn!("SSE client lagged, dropped {} events", n);
-                        Some(Ok(Event::default()
+                    Ok(_) => continue,
+                    Err(RecvError::Lagged(n)) => {
+                        tracing::warn!("Output stream client lagged, dropped {} chunks", n);
+                        Event::default()
                             .event("lagged")
-                            .data(format!(r#"{{"dropped":{}}}"#, n))))
+                            .data(format!(r#"{{"dropped":{}}}"#, n))
                     }
-                }
+                    Err(RecvError::Closed) => return None,
+                };
+                return Some((Ok(event), (output, handle, pending)));
             }
-        });
+        },
+    );
+    let stream = futures::stream::once(async move { Ok(subscribed) }).chain(chunks);
 
     Ok(Sse::new(stream).keep_alive(KeepAlive::default()))
 }
+
+/// POST /api/output-streams/{stream_id}
+/// Change the agents an open output stream follows. The stream ID is only known to
+/// the client holding the stream.
+pub async fn update_output_stream(
+    State(state): State<std::sync::Arc<AppState>>,
+    Path(stream_id): Path<String>,
+    Json(req): Json<UpdateOutputStreamRequest>,
+) -> Result<Json<serde_json::Value>, ApiError> {
+    let not_open = || ApiError::not_found(format!("Output stream {} is not open", stream_id));
+    let session_id = state.output_streams.session_id(&stream_id).ok_or_else(not_open)?;
+    check_session_agents(&state, &session_id, &req.subscribe)?;
+    let agents = state
+        .output_streams
+        .update(&stream_id, &req.subscribe, &req.unsubscribe)
+        .ok_or_else(not_open)?;
+
+    Ok(Json(serde_json::json!({
+        "stream_id": stream_id,
+        "session_id": session_id,
+        "agents": agents,
+    })))
+}
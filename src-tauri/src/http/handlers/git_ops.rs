@@ -0,0 +1,93 @@
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    Json,
+};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+
+use super::approvals::require_approval;
+use super::{reject_quarantined_agent, validate_agent_id, validate_session_id};
+use crate::coordination::GatedAction;
+use crate::http::error::ApiError;
+use crate::http::state::AppState;
+use crate::workspace::git_ops::{
+    GitOperation, GitOperationOutcome, GitOperationStatus, MAX_GIT_OPERATIONS,
+};
+
+/// POST /api/sessions/{id}/git - Body
+#[derive(Debug, Deserialize)]
+pub struct GitOperationsRequest {
+    pub agent_id: String,
+    pub operations: Vec<GitOperation>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct GitOperationsResponse {
+    /// Whether every operation went through.
+    pub ok: bool,
+    pub outcomes: Vec<GitOperationOutcome>,
+}
+
+/// POST /api/sessions/{id}/git - Commit, create branches and push on the Queen's behalf.
+/// Pushes go through the approval policy first; the batch stops at the first failure.
+pub async fn run_git_operations(
+    State(state): State<Arc<AppState>>,
+    Path(session_id): Path<String>,
+    Json(req): Json<GitOperationsRequest>,
+) -> Result<Json<GitOperationsResponse>, ApiError> {
+    validate_session_id(&session_id)?;
+    validate_agent_id(&req.agent_id)?;
+    if req.agent_id != format!("{}-queen", session_id) {
+        return Err(ApiError::new(
+            StatusCode::FORBIDDEN,
+            "Only the session's Queen may run git operations",
+        ));
+    }
+    reject_quarantined_agent(&state, &session_id, &req.agent_id)?;
+    if req.operations.is_empty() || req.operations.len() > MAX_GIT_OPERATIONS {
+        return Err(ApiError::bad_request(format!(
+            "Send between 1 and {} operations",
+            MAX_GIT_OPERATIONS
+        )));
+    }
+    if state
+        .session_controller
+        .read()
+        .get_session(&session_id)
+        .is_none()
+    {
+        return Err(ApiError::not_found(format!(
+            "Session {} not found",
+            session_id
+        )));
+    }
+
+    for operation in &req.operations {
+        if let GitOperation::Push { branch } = operation {
+            let action = GatedAction::Push {
+                branch: branch.clone(),
+            };
+            require_approval(&state, &session_id, Some(&req.agent_id), action).await?;
+        }
+    }
+
+    // Pushes hit the network; keep the batch off the async runtime.
+    let controller = Arc::clone(&state.session_controller);
+    let operations = req.operations;
+    let outcomes = tokio::task::spawn_blocking(move || {
+        controller
+            .read()
+            .run_queen_git_operations(&session_id, &operations)
+    })
+    .await
+    .map_err(|e| ApiError::internal(format!("Git operations task failed: {}", e)))?
+    .map_err(ApiError::bad_request)?;
+
+    Ok(Json(GitOperationsResponse {
+        ok: outcomes
+            .iter()
+            .all(|outcome| outcome.status == GitOperationStatus::Done),
+        outcomes,
+    }))
+}
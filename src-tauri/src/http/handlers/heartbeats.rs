@@ -10,6 +10,7 @@ use super::validate_agent_id;
 use super::validate_session_id;
 use crate::http::error::ApiError;
 use crate::http::state::AppState;
+use crate::session::{ContextPressure, OperatorQuestion};
 
 /// POST /api/sessions/{id}/heartbeat - Body
 #[derive(Debug, Deserialize)]
@@ -30,10 +31,14 @@ pub struct PostHeartbeatResponse {
 #[derive(Serialize)]
 pub struct ActiveAgentInfo {
     pub id: String,
+    /// The agent's label, or its ID when it has none.
+    pub label: String,
     pub role: String,
     pub last_activity: Option<String>,
     pub status: Option<String>,
     pub summary: Option<String>,
+    /// What the agent's CLI last said about its context window.
+    pub context: Option<ContextPressure>,
 }
 
 /// Session in active sessions list
@@ -43,6 +48,8 @@ pub struct ActiveSessionInfo {
     pub session_type: String,
     pub project_path: String,
     pub agents: Vec<ActiveAgentInfo>,
+    /// Questions agents asked the operator that are still unanswered.
+    pub pending_questions: Vec<OperatorQuestion>,
 }
 
 /// GET /api/sessions/active response
@@ -117,6 +124,7 @@ pub async fn get_active_sessions(
         .filter(|s| s.state.is_monitorable())
         .map(|session| {
             let agents_with_heartbeats = controller.get_heartbeat_info(&session.id);
+            let mut context_pressure = controller.context_pressure(&session.id);
             let agents: Vec<ActiveAgentInfo> = session
                 .agents
                 .iter()
@@ -124,10 +132,12 @@ pub async fn get_active_sessions(
                     let hb = agents_with_heartbeats.get(&a.id);
                     ActiveAgentInfo {
                         id: a.id.clone(),
+                        label: a.display_name().to_string(),
                         role: format!("{:?}", a.role),
                         last_activity: hb.map(|h| h.last_activity.to_rfc3339()),
                         status: hb.map(|h| h.status.clone()),
                         summary: hb.and_then(|h| h.summary.clone()),
+                        context: context_pressure.remove(&a.id),
                     }
                 })
                 .collect();
@@ -147,6 +157,7 @@ pub async fn get_active_sessions(
                 },
                 project_path: session.project_path.to_string_lossy().to_string(),
                 agents,
+                pending_questions: controller.pending_operator_questions(&session.id),
             }
         })
         .collect();
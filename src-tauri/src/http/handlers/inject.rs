@@ -1,25 +1,54 @@
 use axum::{
-    extract::{Path, State},
+    extract::{Path, Query, State},
     Json,
 };
+use std::collections::HashMap;
 use std::sync::Arc;
 use serde_json::{json, Value};
 use serde::Deserialize;
 use crate::http::error::ApiError;
 use crate::http::state::AppState;
-use super::{validate_agent_id, validate_session_id};
+use super::{reject_quarantined_agent, validate_agent_id, validate_session_id};
 
 #[derive(Deserialize)]
 pub struct OperatorInjectRequest {
     pub target_agent_id: String,
+    #[serde(default)]
     pub message: String,
+    /// Named injection template (e.g. `commit-now`) sent instead of `message`.
+    #[serde(default)]
+    pub template: Option<String>,
+    #[serde(default)]
+    pub variables: HashMap<String, String>,
 }
 
 #[derive(Deserialize)]
 pub struct QueenInjectRequest {
     pub queen_id: String,
     pub target_worker_id: String,
+    #[serde(default)]
     pub message: String,
+    /// Named injection template (e.g. `check-inbox`) sent instead of `message`.
+    #[serde(default)]
+    pub template: Option<String>,
+    #[serde(default)]
+    pub variables: HashMap<String, String>,
+}
+
+/// POST /api/sessions/{id}/coordination/progress - Body
+#[derive(Deserialize)]
+pub struct ProgressRequest {
+    pub agent_id: String,
+    pub content: String,
+    /// ID of the `[MSG <id>]` TASK this update acknowledges.
+    #[serde(default)]
+    pub message_id: Option<String>,
+}
+
+#[derive(Deserialize, Default)]
+pub struct UnacknowledgedQuery {
+    #[serde(default)]
+    pub agent_id: Option<String>,
 }
 
 #[derive(Deserialize)]
@@ -36,14 +65,18 @@ pub async fn operator_inject(
 ) -> Result<Json<Value>, ApiError> {
     validate_session_id(&id)?;
     validate_agent_id(&payload.target_agent_id)?;
+    let message = injection_text(
+        &state,
+        &id,
+        &payload.target_agent_id,
+        &payload.message,
+        payload.template.as_deref(),
+        payload.variables,
+    )?;
 
     let manager = state.injection_manager.read();
     manager
-        .operator_inject(
-            &id,
-            &payload.target_agent_id,
-            &payload.message,
-        )
+        .operator_inject(&id, &payload.target_agent_id, &message)
         .map_err(|e| ApiError::internal(e.to_string()))?;
 
     Ok(Json(json!({
@@ -60,16 +93,37 @@ pub async fn queen_inject(
     validate_session_id(&id)?;
     validate_agent_id(&payload.queen_id)?;
     validate_agent_id(&payload.target_worker_id)?;
+    reject_quarantined_agent(&state, &id, &payload.queen_id)?;
+    reject_quarantined_agent(&state, &id, &payload.target_worker_id)?;
+    let message = injection_text(
+        &state,
+        &id,
+        &payload.target_worker_id,
+        &payload.message,
+        payload.template.as_deref(),
+        payload.variables,
+    )?;
 
     let manager = state.injection_manager.read();
     manager
-        .queen_inject(
+        .queen_inject(&id, &payload.queen_id, &payload.target_worker_id, &message)
+        .map_err(map_injection_error)?;
+
+    // A plain TASK is the worker's assignment, so it also activates the task file.
+    // Templated nudges (`check-inbox`, ...) are not assignments.
+    if payload.template.is_none() {
+        if let Err(e) = state.session_controller.read().activate_worker_task(
             &id,
-            &payload.queen_id,
             &payload.target_worker_id,
-            &payload.message,
-        )
-        .map_err(map_injection_error)?;
+            &message,
+        ) {
+            tracing::warn!(
+                "Failed to activate task file for {}: {}",
+                payload.target_worker_id,
+                e
+            );
+        }
+    }
 
     Ok(Json(json!({
         "status": "success",
@@ -85,6 +139,8 @@ pub async fn evaluator_inject(
     validate_session_id(&id)?;
     validate_agent_id(&payload.evaluator_id)?;
     validate_agent_id(&payload.target_agent_id)?;
+    reject_quarantined_agent(&state, &id, &payload.evaluator_id)?;
+    reject_quarantined_agent(&state, &id, &payload.target_agent_id)?;
 
     let manager = state.injection_manager.read();
     manager
@@ -102,13 +158,93 @@ pub async fn evaluator_inject(
     })))
 }
 
+/// POST /api/sessions/{id}/coordination/progress - A worker reports progress,
+/// acknowledging the TASK named by `message_id`
+pub async fn post_progress(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<String>,
+    Json(payload): Json<ProgressRequest>,
+) -> Result<Json<Value>, ApiError> {
+    validate_session_id(&id)?;
+    validate_agent_id(&payload.agent_id)?;
+    reject_quarantined_agent(&state, &id, &payload.agent_id)?;
+    if payload.content.trim().is_empty() {
+        return Err(ApiError::bad_request("content must not be empty"));
+    }
+
+    let manager = state.injection_manager.read();
+    let acknowledged = manager
+        .record_progress(
+            &id,
+            &payload.agent_id,
+            &payload.content,
+            payload.message_id.as_deref(),
+        )
+        .map_err(map_injection_error)?;
+
+    Ok(Json(json!({ "acknowledged": acknowledged })))
+}
+
+/// GET /api/sessions/{id}/coordination/unacknowledged?agent_id= - TASK messages
+/// no worker has acknowledged, for the Queen or operator to send again
+pub async fn list_unacknowledged(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<String>,
+    Query(query): Query<UnacknowledgedQuery>,
+) -> Result<Json<Value>, ApiError> {
+    validate_session_id(&id)?;
+    if let Some(agent_id) = query.agent_id.as_deref() {
+        validate_agent_id(agent_id)?;
+    }
+
+    let messages = state
+        .injection_manager
+        .read()
+        .unacknowledged_messages(&id, query.agent_id.as_deref())
+        .map_err(map_injection_error)?;
+
+    Ok(Json(json!({ "messages": messages })))
+}
+
+/// The text to inject: `message` as given, or `template` rendered with the
+/// session's context for the target agent.
+fn injection_text(
+    state: &AppState,
+    session_id: &str,
+    target_agent_id: &str,
+    message: &str,
+    template: Option<&str>,
+    variables: HashMap<String, String>,
+) -> Result<String, ApiError> {
+    let Some(template) = template.filter(|template| !template.trim().is_empty()) else {
+        return Ok(message.to_string());
+    };
+    if !message.trim().is_empty() {
+        return Err(ApiError::bad_request(
+            "Provide either message or template, not both",
+        ));
+    }
+    state
+        .session_controller
+        .read()
+        .render_injection(session_id, target_agent_id, template, variables)
+        .map_err(|e| {
+            if e.contains("not found") {
+                ApiError::not_found(e)
+            } else {
+                ApiError::bad_request(e)
+            }
+        })
+}
+
 fn map_injection_error(error: crate::coordination::InjectionError) -> ApiError {
     match error {
         crate::coordination::InjectionError::NotAuthorized(message) => {
             ApiError::new(axum::http::StatusCode::FORBIDDEN, message)
         }
         crate::coordination::InjectionError::AgentNotFound(message)
-        | crate::coordination::InjectionError::SessionNotFound(message) => ApiError::not_found(message),
+        | crate::coordination::InjectionError::SessionNotFound(message)
+        | crate::coordination::InjectionError::MessageNotFound(message) => ApiError::not_found(message),
         other => ApiError::internal(other.to_string()),
     }
 }
@@ -1,6 +1,6 @@
 use axum::{
     extract::{Path, Query, State},
-    http::StatusCode,
+    http::{HeaderMap, StatusCode},
     Json,
 };
 use serde::Deserialize;
@@ -9,22 +9,34 @@ use std::collections::HashSet;
 use std::path::PathBuf;
 use std::sync::Arc;
 
-use super::validate_session_id;
+use super::{authorize_session_write, validate_session_id};
 use crate::http::error::ApiError;
 use crate::http::state::AppState;
-use crate::storage::{Learning, StorageError};
+use crate::storage::{Learning, StorageError, LEARNING_SCHEMA_VERSION};
 
-/// Request to submit a learning
+/// Request to submit a learning. Takes either the task shape (`task`, `outcome`,
+/// `insight`) or the judge shape (`content`, `category`). Every field is optional
+/// here so validation can name all the missing ones at once.
 #[derive(Debug, Deserialize)]
 pub struct SubmitLearningRequest {
-    pub session: String,
-    pub task: String,
-    pub outcome: String,
+    /// Defaults to the path's session on session-scoped routes.
+    pub session: Option<String>,
+    pub task: Option<String>,
+    pub outcome: Option<String>,
     #[serde(default)]
     pub keywords: Vec<String>,
-    pub insight: String,
+    #[serde(alias = "content")]
+    pub insight: Option<String>,
     #[serde(default)]
     pub files_touched: Vec<String>,
+    pub category: Option<String>,
+    pub confidence: Option<f64>,
+    #[serde(alias = "source")]
+    pub source_agent: Option<String>,
+}
+
+fn non_empty(value: &Option<String>) -> Option<&str> {
+    value.as_deref().map(str::trim).filter(|v| !v.is_empty())
 }
 
 #[derive(Debug, Deserialize, Default)]
@@ -56,26 +68,54 @@ fn resolve_project_path(state: &AppState) -> Result<PathBuf, ApiError> {
     Ok(first_path)
 }
 
-/// Validate SubmitLearningRequest fields (session, task, insight, outcome, files_touched).
-/// Shared by submit_learning and submit_learning_for_session.
-fn validate_submit_learning_request(req: &SubmitLearningRequest) -> Result<(), ApiError> {
-    if req.session.trim().is_empty() {
-        return Err(ApiError::bad_request("Session cannot be empty"));
+/// Validate a SubmitLearningRequest, filling `session` from `path_session` when
+/// the body leaves it out. Shared by submit_learning and submit_learning_for_session.
+fn validate_submit_learning_request(
+    req: &mut SubmitLearningRequest,
+    path_session: Option<&str>,
+) -> Result<(), ApiError> {
+    if non_empty(&req.session).is_none() {
+        req.session = path_session.map(String::from);
     }
-    if req.task.trim().is_empty() {
-        return Err(ApiError::bad_request("Task cannot be empty"));
+
+    let mut missing = Vec::new();
+    if non_empty(&req.session).is_none() {
+        missing.push("session");
     }
-    if req.insight.trim().is_empty() {
-        return Err(ApiError::bad_request("Insight cannot be empty"));
+    if non_empty(&req.insight).is_none() {
+        missing.push("insight (or content)");
     }
-    match req.outcome.as_str() {
-        "success" | "partial" | "failed" => {}
-        _ => {
+    // Without a category the learning is filed under its task and outcome.
+    if non_empty(&req.category).is_none() {
+        if non_empty(&req.task).is_none() {
+            missing.push("task");
+        }
+        if non_empty(&req.outcome).is_none() {
+            missing.push("outcome");
+        }
+    }
+    if !missing.is_empty() {
+        return Err(ApiError::bad_request(format!(
+            "Missing required learning fields: {}. Send either \
+             {{task, outcome, insight}} or {{content, category}}.",
+            missing.join(", ")
+        )));
+    }
+
+    if let Some(outcome) = non_empty(&req.outcome) {
+        if !matches!(outcome, "success" | "partial" | "failed") {
             return Err(ApiError::bad_request(
                 "Outcome must be one of: success, partial, failed",
             ));
         }
     }
+    if let Some(confidence) = req.confidence {
+        if !(0.0..=1.0).contains(&confidence) {
+            return Err(ApiError::bad_request(
+                "Confidence must be between 0.0 and 1.0",
+            ));
+        }
+    }
     for file_path in &req.files_touched {
         if file_path.contains("..") || file_path.starts_with('/') || file_path.contains('\\') {
             return Err(ApiError::bad_request(format!(
@@ -89,21 +129,27 @@ fn validate_submit_learning_request(req: &SubmitLearningRequest) -> Result<(), A
 
 /// Build a Learning from a validated SubmitLearningRequest.
 fn learning_from_request(req: SubmitLearningRequest) -> (Learning, String) {
+    let trimmed = |value: Option<String>| non_empty(&value).map(String::from);
     let learning_id = uuid::Uuid::new_v4().to_string();
     let learning = Learning {
         id: learning_id.clone(),
+        schema: LEARNING_SCHEMA_VERSION,
         date: chrono::Utc::now().format("%Y-%m-%d").to_string(),
-        session: req.session,
-        task: req.task,
-        outcome: req.outcome,
+        session: trimmed(req.session).unwrap_or_default(),
+        task: trimmed(req.task).unwrap_or_default(),
+        outcome: trimmed(req.outcome).unwrap_or_default(),
         keywords: req.keywords,
-        insight: req.insight,
+        insight: trimmed(req.insight).unwrap_or_default(),
         files_touched: req.files_touched,
+        category: trimmed(req.category).map(|c| c.to_lowercase()),
+        confidence: req.confidence,
+        source_agent: trimmed(req.source_agent),
     };
     (learning, learning_id)
 }
 
-/// Apply case-insensitive filtering on learnings by category and keywords
+/// Apply case-insensitive filtering on learnings by category and keywords. A
+/// category matches v2 categories and, for v1 records, the outcome.
 fn filter_learnings(learnings: Vec<Learning>, params: &LearningsFilter) -> Vec<Value> {
     let cat_lower = params.category.as_deref().map(|c| c.to_lowercase());
     let filter_kws: HashSet<String> = params
@@ -121,7 +167,11 @@ fn filter_learnings(learnings: Vec<Learning>, params: &LearningsFilter) -> Vec<V
         .into_iter()
         .filter(|learning| {
             if let Some(ref cat) = cat_lower {
-                if learning.outcome.to_lowercase() != *cat {
+                let category_matches = learning
+                    .category
+                    .as_deref()
+                    .is_some_and(|c| c.to_lowercase() == *cat);
+                if !category_matches && learning.outcome.to_lowercase() != *cat {
                     return false;
                 }
             }
@@ -138,6 +188,7 @@ fn filter_learnings(learnings: Vec<Learning>, params: &LearningsFilter) -> Vec<V
         .map(|learning| {
             json!({
                 "id": learning.id,
+                "schema": learning.schema,
                 "date": learning.date,
                 "session": learning.session,
                 "task": learning.task,
@@ -145,6 +196,9 @@ fn filter_learnings(learnings: Vec<Learning>, params: &LearningsFilter) -> Vec<V
                 "keywords": learning.keywords,
                 "insight": learning.insight,
                 "files_touched": learning.files_touched,
+                "category": learning.category,
+                "confidence": learning.confidence,
+                "source_agent": learning.source_agent,
             })
         })
         .collect()
@@ -158,7 +212,7 @@ fn ensure_session_exists(state: &AppState, session_id: &str) -> Result<(), ApiEr
         return Ok(());
     }
 
-    match state.storage.load_session(session_id) {
+    match state.backend.load_session(session_id) {
         Ok(_) => Ok(()),
         Err(StorageError::SessionNotFound(_)) => Err(ApiError::not_found(format!(
             "Session {} not found",
@@ -172,10 +226,27 @@ fn ensure_session_exists(state: &AppState, session_id: &str) -> Result<(), ApiEr
 /// DEPRECATED: Use POST /api/sessions/{session_id}/learnings for new code
 pub async fn submit_learning(
     State(state): State<Arc<AppState>>,
-    Json(req): Json<SubmitLearningRequest>,
+    headers: HeaderMap,
+    Json(mut req): Json<SubmitLearningRequest>,
 ) -> Result<(StatusCode, Json<Value>), ApiError> {
-    validate_submit_learning_request(&req)?;
+    validate_submit_learning_request(&mut req, None)?;
     let project_path = resolve_project_path(&state)?;
+    // The learning lands in the active sessions' project, so it must name one of them
+    // and carry that session's token.
+    let session_id = req.session.clone().unwrap_or_default();
+    let is_active = state
+        .session_controller
+        .read()
+        .list_sessions()
+        .iter()
+        .any(|session| session.id == session_id);
+    if !is_active {
+        return Err(ApiError::bad_request(format!(
+            "Session {} is not active",
+            session_id
+        )));
+    }
+    authorize_session_write(&state, &session_id, &headers)?;
     let (learning, learning_id) = learning_from_request(req);
 
     state
@@ -232,14 +303,14 @@ pub async fn get_project_dna(State(state): State<Arc<AppState>>) -> Result<Json<
 pub async fn submit_learning_for_session(
     State(state): State<Arc<AppState>>,
     Path(session_id): Path<String>,
-    Json(req): Json<SubmitLearningRequest>,
+    Json(mut req): Json<SubmitLearningRequest>,
 ) -> Result<(StatusCode, Json<Value>), ApiError> {
     validate_session_id(&session_id)?;
-    validate_submit_learning_request(&req)?;
+    validate_submit_learning_request(&mut req, Some(&session_id))?;
     let (learning, learning_id) = learning_from_request(req);
 
     state
-        .storage
+        .backend
         .append_learning_session(&session_id, &learning)
         .map_err(|e| ApiError::internal(format!("Failed to save learning: {}", e)))?;
 
@@ -263,7 +334,7 @@ pub async fn list_learnings_for_session(
 
     // Use session-scoped storage
     let learnings = state
-        .storage
+        .backend
         .read_learnings_session(&session_id)
         .map_err(|e| ApiError::internal(format!("Failed to read learnings: {}", e)))?;
 
@@ -283,7 +354,7 @@ pub async fn delete_learning_for_session(
     validate_session_id(&session_id)?;
 
     let found = state
-        .storage
+        .backend
         .delete_learning_session(&session_id, &learning_id)
         .map_err(|e| ApiError::internal(format!("Failed to delete learning: {}", e)))?;
 
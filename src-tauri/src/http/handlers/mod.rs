@@ -1,11 +1,16 @@
 pub mod actions;
 pub mod agents;
 pub mod application_state;
+pub mod approvals;
 pub mod artifacts;
+pub mod attachments;
 pub mod cells;
 pub mod conversations;
+pub mod coordination_view;
+pub mod editor;
 pub mod evaluator;
 pub mod events;
+pub mod git_ops;
 pub mod health;
 pub mod heartbeats;
 pub mod inject;
@@ -13,13 +18,21 @@ pub mod knowledge;
 pub mod learnings;
 pub mod planners;
 pub mod queue;
+pub mod questions;
 pub mod resolver;
+pub mod reviews;
+pub mod scratchpad;
 pub mod session_files;
 pub mod sessions;
+pub mod snapshots;
 pub mod templates;
 pub mod workers;
 
 use crate::http::error::ApiError;
+use crate::http::routes::is_allowed_browser_origin;
+use crate::http::state::AppState;
+use crate::session::{AGENT_ID_HEADER, SESSION_TOKEN_HEADER};
+use axum::http::{header::ORIGIN, HeaderMap, StatusCode};
 use std::collections::HashSet;
 
 // Must stay in lockstep with adapters/mod.rs::VALID_CLIS.
@@ -42,6 +55,80 @@ pub fn validate_session_id(session_id: &str) -> Result<(), ApiError> {
     Ok(())
 }
 
+/// Header the app's own UI sends `AppState::ui_api_token` in.
+pub const UI_TOKEN_HEADER: &str = "X-Hive-UI-Token";
+
+/// Whether a request comes from the app's own UI: it carries the UI token and, when a
+/// browser sent it, an allowed `Origin`. Any local process can set `Origin`, so the
+/// origin alone proves nothing; it only guards the token against cross-site use.
+pub fn is_ui_request(state: &AppState, headers: &HeaderMap) -> bool {
+    headers
+        .get(UI_TOKEN_HEADER)
+        .is_some_and(|token| token.as_bytes() == state.ui_api_token.as_bytes())
+        && headers.get(ORIGIN).is_none_or(is_allowed_browser_origin)
+}
+
+/// Reject a write to `session_id` that does not carry the session's API token, so one
+/// session's agents cannot act on another session. The app's own UI (see
+/// [`is_ui_request`]) and sessions that were never issued a token are let through.
+/// Writes naming a quarantined agent in the agent ID header are refused.
+pub fn authorize_session_write(
+    state: &AppState,
+    session_id: &str,
+    headers: &HeaderMap,
+) -> Result<(), ApiError> {
+    if is_ui_request(state, headers) {
+        return Ok(());
+    }
+    let expected = state
+        .session_controller
+        .read()
+        .issued_session_api_token(session_id);
+    if let Some(expected) = expected {
+        let presented = headers
+            .get(SESSION_TOKEN_HEADER)
+            .and_then(|value| value.to_str().ok());
+        if presented != Some(expected.as_str()) {
+            return Err(ApiError::new(
+                StatusCode::UNAUTHORIZED,
+                format!(
+                    "Missing or invalid {} for session {}",
+                    SESSION_TOKEN_HEADER, session_id
+                ),
+            ));
+        }
+    }
+
+    match headers
+        .get(AGENT_ID_HEADER)
+        .and_then(|value| value.to_str().ok())
+    {
+        Some(agent_id) => reject_quarantined_agent(state, session_id, agent_id),
+        None => Ok(()),
+    }
+}
+
+/// Turn away a request made by, or addressed to, an agent the operator has
+/// quarantined.
+pub fn reject_quarantined_agent(
+    state: &AppState,
+    session_id: &str,
+    agent_id: &str,
+) -> Result<(), ApiError> {
+    if state
+        .session_controller
+        .read()
+        .is_agent_quarantined(session_id, agent_id)
+    {
+        Err(ApiError::new(
+            StatusCode::FORBIDDEN,
+            format!("Agent {} is quarantined", agent_id),
+        ))
+    } else {
+        Ok(())
+    }
+}
+
 /// Validate cell_id to prevent path traversal and malformed names.
 pub fn validate_cell_id(cell_id: &str) -> Result<(), ApiError> {
     if cell_id.is_empty() || cell_id.len() > 64 {
@@ -135,13 +222,19 @@ pub fn validate_candidate_ids(candidate_ids: &[String]) -> Result<(), ApiError>
     Ok(())
 }
 
-/// Validate CLI against allowlist
+/// Validate CLI against allowlist, which includes the CLIs defined in `clis/*.toml`
 pub fn validate_cli(cli: &str) -> Result<(), ApiError> {
-    if !VALID_CLIS.contains(&cli) {
+    if !VALID_CLIS.contains(&cli) && !crate::cli::custom::is_defined(cli) {
+        let custom = crate::cli::custom::all();
+        let valid: Vec<&str> = VALID_CLIS
+            .iter()
+            .copied()
+            .chain(custom.iter().map(|cli| cli.name.as_str()))
+            .collect();
         return Err(ApiError::bad_request(format!(
             "Invalid CLI '{}'. Valid options: {}",
             cli,
-            VALID_CLIS.join(", ")
+            valid.join(", ")
         )));
     }
     Ok(())
@@ -5,6 +5,7 @@ use axum::{
 };
 use serde::{Deserialize, Serialize};
 use serde_json::{json, Value};
+use std::collections::HashMap;
 use std::sync::Arc;
 
 use crate::http::error::ApiError;
@@ -56,6 +57,9 @@ pub struct AddPlannerResponse {
 /// - Its own terminal window
 /// - Tool documentation for spawning workers
 /// - Knowledge of how many workers it should spawn
+///
+/// A domain the plan orders after unfinished domains is refused with 409 until a
+/// `[DOMAIN_UNBLOCKED]` notice says it may start.
 pub async fn add_planner(
     State(state): State<Arc<AppState>>,
     Path(session_id): Path<String>,
@@ -82,6 +86,8 @@ pub async fn add_planner(
         description: None,
         role: None,
         initial_prompt: None,
+        allowed_paths: Vec::new(),
+        denied_paths: Vec::new(),
     };
 
     // Convert worker configs (or create default based on worker_count)
@@ -102,6 +108,8 @@ pub async fn add_planner(
                     prompt_template: None,
                 }),
                 initial_prompt: None,
+                allowed_paths: Vec::new(),
+                denied_paths: Vec::new(),
             }
         }).collect()
     } else {
@@ -122,6 +130,8 @@ pub async fn add_planner(
                     prompt_template: None,
                 }),
                 initial_prompt: None,
+                allowed_paths: Vec::new(),
+                denied_paths: Vec::new(),
             }
         }).collect()
     };
@@ -130,7 +140,19 @@ pub async fn add_planner(
 
     // Add planner through session controller
     let (planner_id, planner_index) = {
-        let controller = state.session_controller.write();
+        let controller = state.session_controller.read();
+
+        let waiting_on = controller.domain_blockers(&session_id, &req.domain);
+        if !waiting_on.is_empty() {
+            return Err(ApiError::conflict_with_details(
+                format!(
+                    "Domain {} waits on {}; spawn its planner once they are complete",
+                    req.domain,
+                    waiting_on.join(", ")
+                ),
+                HashMap::from([("waiting_on".to_string(), json!(waiting_on))]),
+            ));
+        }
 
         let agent_info = controller
             .add_planner(&session_id, config, req.domain.clone(), workers)
@@ -0,0 +1,103 @@
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    Json,
+};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+
+use super::{reject_quarantined_agent, validate_agent_id, validate_session_id};
+use crate::http::error::ApiError;
+use crate::http::state::AppState;
+use crate::session::OperatorQuestion;
+
+/// POST /api/sessions/{id}/questions - Body
+#[derive(Debug, Deserialize)]
+pub struct AskOperatorRequest {
+    pub agent_id: String,
+    pub question: String,
+    #[serde(default)]
+    pub options: Vec<String>,
+}
+
+#[derive(Debug, Deserialize, Default)]
+pub struct QuestionsQuery {
+    /// Only questions still waiting for an answer.
+    #[serde(default)]
+    pub pending: bool,
+}
+
+#[derive(Serialize)]
+pub struct QuestionsResponse {
+    pub questions: Vec<OperatorQuestion>,
+}
+
+fn map_question_error(error: String) -> ApiError {
+    if error.contains("not found") {
+        ApiError::not_found(error)
+    } else if error.contains("already") {
+        ApiError::new(StatusCode::CONFLICT, error)
+    } else {
+        ApiError::bad_request(error)
+    }
+}
+
+/// POST /api/sessions/{id}/questions - An agent asks the operator a blocking question
+pub async fn ask_operator(
+    State(state): State<Arc<AppState>>,
+    Path(session_id): Path<String>,
+    Json(req): Json<AskOperatorRequest>,
+) -> Result<(StatusCode, Json<OperatorQuestion>), ApiError> {
+    validate_session_id(&session_id)?;
+    validate_agent_id(&req.agent_id)?;
+    reject_quarantined_agent(&state, &session_id, &req.agent_id)?;
+
+    let question = state
+        .session_controller
+        .read()
+        .ask_operator(&session_id, &req.agent_id, &req.question, req.options)
+        .map_err(map_question_error)?;
+
+    Ok((StatusCode::CREATED, Json(question)))
+}
+
+/// GET /api/sessions/{id}/questions?pending=true
+pub async fn list_questions(
+    State(state): State<Arc<AppState>>,
+    Path(session_id): Path<String>,
+    Query(query): Query<QuestionsQuery>,
+) -> Result<Json<QuestionsResponse>, ApiError> {
+    validate_session_id(&session_id)?;
+
+    let controller = state.session_controller.read();
+    if controller.get_session(&session_id).is_none() {
+        return Err(ApiError::not_found(format!(
+            "Session {} not found",
+            session_id
+        )));
+    }
+    let questions = if query.pending {
+        controller.pending_operator_questions(&session_id)
+    } else {
+        controller.operator_questions(&session_id)
+    };
+
+    Ok(Json(QuestionsResponse { questions }))
+}
+
+/// GET /api/sessions/{id}/questions/{question_id} - Lets an agent poll for its answer
+pub async fn get_question(
+    State(state): State<Arc<AppState>>,
+    Path((session_id, question_id)): Path<(String, String)>,
+) -> Result<Json<OperatorQuestion>, ApiError> {
+    validate_session_id(&session_id)?;
+
+    state
+        .session_controller
+        .read()
+        .operator_questions(&session_id)
+        .into_iter()
+        .find(|question| question.id == question_id)
+        .map(Json)
+        .ok_or_else(|| ApiError::not_found(format!("Question {} not found", question_id)))
+}
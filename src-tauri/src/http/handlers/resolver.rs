@@ -72,7 +72,7 @@ pub async fn launch_resolver(
         }
     } else {
         // Fall back to persisted session
-        match state.storage.load_session(&session_id) {
+        match state.backend.load_session(&session_id) {
             Ok(persisted) => match persisted.session_type {
                 crate::storage::SessionTypeInfo::Fusion { variants } => Some(variants),
                 crate::storage::SessionTypeInfo::Debate { variants } => Some(variants),
@@ -189,7 +189,7 @@ pub async fn get_resolver_output(
     } {
         true
     } else {
-        match state.storage.load_session(&session_id) {
+        match state.backend.load_session(&session_id) {
             Ok(_) => true,
             Err(StorageError::SessionNotFound(_)) => false,
             Err(err) => return Err(ApiError::internal(err.to_string())),
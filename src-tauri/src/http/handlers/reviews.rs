@@ -0,0 +1,33 @@
+//! Reviewers' structured findings (see `session::review_findings`).
+
+use axum::{
+    extract::{Path, State},
+    Json,
+};
+use std::sync::Arc;
+
+use super::validate_session_id;
+use crate::http::error::ApiError;
+use crate::http::state::AppState;
+use crate::session::review_findings::{self, WorkerReview};
+
+/// GET /api/sessions/{id}/reviews - Each reviewer's validated findings, by worker
+/// index, with the entries that failed validation listed under `errors`
+pub async fn list_reviews(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<String>,
+) -> Result<Json<Vec<WorkerReview>>, ApiError> {
+    validate_session_id(&id)?;
+    let session = {
+        let controller = state.session_controller.read();
+        controller.get_session(&id)
+    }
+    .ok_or_else(|| ApiError::not_found(format!("Session {} not found", id)))?;
+
+    let session_root = session.project_path.join(".hive-manager").join(&session.id);
+    let reviews = tokio::task::spawn_blocking(move || review_findings::load(&session_root))
+        .await
+        .map_err(|e| ApiError::internal(e.to_string()))?
+        .map_err(|e| ApiError::internal(format!("Failed to read reviews: {e}")))?;
+    Ok(Json(reviews))
+}
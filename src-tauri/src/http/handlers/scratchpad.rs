@@ -0,0 +1,101 @@
+//! The session scratchpad (see `storage::scratchpad`): values workers share that
+//! would not fit in a coordination message.
+
+use axum::{
+    extract::{Path, State},
+    http::HeaderMap,
+    Json,
+};
+use serde::Deserialize;
+use std::sync::Arc;
+
+use super::validate_session_id;
+use crate::http::error::ApiError;
+use crate::http::state::AppState;
+use crate::session::AGENT_ID_HEADER;
+use crate::storage::scratchpad::{self, ScratchpadEntry, MAX_VALUE_BYTES};
+use crate::storage::StorageError;
+
+#[derive(Debug, Deserialize)]
+pub struct PutScratchpadRequest {
+    pub value: String,
+}
+
+fn validate_key(key: &str) -> Result<(), ApiError> {
+    if scratchpad::is_valid_key(key) {
+        Ok(())
+    } else {
+        Err(ApiError::bad_request(format!(
+            "Invalid scratchpad key '{}': use up to 64 letters, digits, '.', '_' or '-'",
+            key
+        )))
+    }
+}
+
+/// GET /api/sessions/{id}/scratchpad - Every scratchpad entry, by key
+pub async fn list_entries(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<String>,
+) -> Result<Json<Vec<ScratchpadEntry>>, ApiError> {
+    validate_session_id(&id)?;
+    let storage = Arc::clone(&state.storage);
+    let entries = tokio::task::spawn_blocking(move || storage.list_scratchpad(&id))
+        .await
+        .map_err(|e| ApiError::internal(e.to_string()))?
+        .map_err(|e| ApiError::internal(format!("Failed to read the scratchpad: {e}")))?;
+    Ok(Json(entries))
+}
+
+/// GET /api/sessions/{id}/scratchpad/{key} - One entry
+pub async fn get_entry(
+    State(state): State<Arc<AppState>>,
+    Path((id, key)): Path<(String, String)>,
+) -> Result<Json<ScratchpadEntry>, ApiError> {
+    validate_session_id(&id)?;
+    validate_key(&key)?;
+    let storage = Arc::clone(&state.storage);
+    let lookup = key.clone();
+    tokio::task::spawn_blocking(move || storage.scratchpad_entry(&id, &lookup))
+        .await
+        .map_err(|e| ApiError::internal(e.to_string()))?
+        .map_err(|e| ApiError::internal(format!("Failed to read the scratchpad: {e}")))?
+        .map(Json)
+        .ok_or_else(|| ApiError::not_found(format!("Scratchpad key {} not found", key)))
+}
+
+/// PUT /api/sessions/{id}/scratchpad/{key} - Set the entry to `value`, replacing
+/// what was there
+pub async fn put_entry(
+    State(state): State<Arc<AppState>>,
+    Path((id, key)): Path<(String, String)>,
+    headers: HeaderMap,
+    Json(request): Json<PutScratchpadRequest>,
+) -> Result<Json<ScratchpadEntry>, ApiError> {
+    validate_session_id(&id)?;
+    validate_key(&key)?;
+    if request.value.len() > MAX_VALUE_BYTES {
+        return Err(ApiError::bad_request(format!(
+            "Scratchpad values are limited to {} bytes",
+            MAX_VALUE_BYTES
+        )));
+    }
+    let updated_by = headers
+        .get(AGENT_ID_HEADER)
+        .and_then(|value| value.to_str().ok())
+        .map(str::to_string);
+
+    let storage = Arc::clone(&state.storage);
+    let session_id = id.clone();
+    let saved = tokio::task::spawn_blocking(move || {
+        storage.put_scratchpad_entry(&session_id, &key, &request.value, updated_by.as_deref())
+    })
+    .await
+    .map_err(|e| ApiError::internal(e.to_string()))?;
+    match saved {
+        Ok(entry) => Ok(Json(entry)),
+        Err(StorageError::SessionNotFound(_)) => {
+            Err(ApiError::not_found(format!("Session {} not found", id)))
+        }
+        Err(e) => Err(ApiError::internal(format!("Failed to write the scratchpad: {e}"))),
+    }
+}
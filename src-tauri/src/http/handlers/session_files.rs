@@ -129,7 +129,7 @@ fn resolve_session_files_root(state: &AppState, session_id: &str) -> Result<Path
 
     let project_path = match live_project_path {
         Some(path) => Some(path),
-        None => match state.storage.load_session(session_id) {
+        None => match state.backend.load_session(session_id) {
             Ok(session) => Some(PathBuf::from(session.project_path)),
             Err(StorageError::SessionNotFound(_)) => None,
             Err(error) => return Err(ApiError::internal(error.to_string())),
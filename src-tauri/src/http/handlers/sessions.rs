@@ -1,7 +1,7 @@
 use crate::actions::{ActionContext, Caller};
 use crate::cli::CliRegistry;
 use axum::{
-    extract::{Path, State},
+    extract::{Path, Query, State},
     http::StatusCode,
     Json,
 };
@@ -15,9 +15,17 @@ use crate::http::state::AppState;
 use crate::pty::AgentConfig;
 use crate::session::{
     CompletionBlockedError, CompletionError, DebateDebaterConfig, DebateDebaterStatus,
-    DebateLaunchConfig, FusionLaunchConfig, FusionVariantConfig, FusionVariantStatus,
-    HiveLaunchConfig, QaWorkerConfig,
+    DebateLaunchConfig, DirtyTreeAction, FusionLaunchConfig, FusionVariantConfig,
+    FusionVariantStatus, FusionVerdictTally, HiveLaunchConfig, PhaseTimeouts, QaWorkerConfig,
+    ReviewStageConfig, SessionBranchStatus, SessionController, SpawnQuota,
 };
+use crate::session::comparison::SessionComparison;
+use crate::session::slug;
+use crate::session::durations::SessionDurations;
+use crate::session::fusion_compose::{FusionComposeReport, FusionComposeRequest};
+use crate::storage::api_usage::ApiUsageSummary;
+use crate::coordination::CoordinationMessage;
+use crate::storage::coordination_stats::CoordinationStats;
 
 async fn dispatch_session_action(
     state: &Arc<AppState>,
@@ -82,6 +90,8 @@ fn evaluator_config_from_request(
             description: None,
             role: None,
             initial_prompt: None,
+            allowed_paths: Vec::new(),
+            denied_paths: Vec::new(),
         }));
     }
 
@@ -96,6 +106,8 @@ fn evaluator_config_from_request(
             description: None,
             role: None,
             initial_prompt: None,
+            allowed_paths: Vec::new(),
+            denied_paths: Vec::new(),
         }));
     }
 
@@ -130,6 +142,8 @@ fn map_completion_error(error: CompletionError) -> ApiError {
 #[derive(Serialize, Deserialize)]
 pub struct SessionInfo {
     pub id: String,
+    /// Short name accepted wherever the ID is.
+    pub slug: String,
     pub name: Option<String>,
     pub color: Option<String>,
     pub session_type: String,
@@ -176,6 +190,8 @@ pub struct LaunchSwarmRequest {
     pub qa_workers: Option<Vec<QaWorkerConfig>>,
     pub name: Option<String>,
     pub color: Option<String>,
+    pub phase_timeouts: Option<PhaseTimeouts>,
+    pub background: Option<bool>,
 }
 
 #[derive(Deserialize)]
@@ -198,6 +214,12 @@ pub struct LaunchFusionRequest {
     pub default_model: Option<String>,
     pub name: Option<String>,
     pub color: Option<String>,
+    pub quorum: Option<u8>,
+    pub max_variant_retries: Option<u8>,
+    pub phase_timeouts: Option<PhaseTimeouts>,
+    /// What to do with uncommitted changes in the project; aborts by default.
+    pub dirty_tree: Option<DirtyTreeAction>,
+    pub background: Option<bool>,
 }
 
 #[derive(Deserialize)]
@@ -222,6 +244,8 @@ pub struct LaunchDebateRequest {
     pub default_model: Option<String>,
     pub name: Option<String>,
     pub color: Option<String>,
+    pub phase_timeouts: Option<PhaseTimeouts>,
+    pub background: Option<bool>,
 }
 
 #[derive(Deserialize)]
@@ -236,6 +260,8 @@ pub struct LaunchSoloRequest {
     pub evaluator_model: Option<String>,
     pub name: Option<String>,
     pub color: Option<String>,
+    pub branch_name: Option<String>,
+    pub background: Option<bool>,
 }
 
 #[derive(Deserialize)]
@@ -266,8 +292,20 @@ pub struct CreateSessionRequest {
     pub evaluator_model: Option<String>,
     pub qa_workers: Option<Vec<QaWorkerConfig>>,
     pub smoke_test: Option<bool>,
+    /// Hive only, with `smoke_test`: play the workers from the backend, no CLI.
+    pub smoke_probe: Option<bool>,
     pub name: Option<String>,
     pub color: Option<String>,
+    pub branch_name: Option<String>,
+    pub phase_timeouts: Option<PhaseTimeouts>,
+    /// Hive only: review chain to run once every worker has finished.
+    pub review_stage: Option<ReviewStageConfig>,
+    /// Hive only: caps on the workers the Queen may spawn.
+    pub spawn_quota: Option<SpawnQuota>,
+    /// Fusion only: what to do with uncommitted changes in the project.
+    pub dirty_tree: Option<DirtyTreeAction>,
+    /// Run the session's CLIs below normal OS priority.
+    pub background: Option<bool>,
 }
 
 #[derive(Deserialize)]
@@ -298,6 +336,34 @@ pub struct SelectFusionWinnerRequest {
     pub variant: String,
 }
 
+#[derive(Deserialize)]
+pub struct RunFusionJudgesRequest {
+    /// One entry per additional judge. Empty re-runs the original judge config.
+    #[serde(default)]
+    pub judges: Vec<AgentConfig>,
+}
+
+#[derive(Serialize)]
+pub struct RunFusionJudgesResponse {
+    pub session_id: String,
+    pub judge_ids: Vec<String>,
+}
+
+#[derive(Deserialize, Default)]
+pub struct CreatePullRequestRequest {
+    pub title: Option<String>,
+    /// Base branch; `gh` uses the repository default when omitted.
+    pub base: Option<String>,
+    #[serde(default)]
+    pub draft: bool,
+}
+
+#[derive(Serialize)]
+pub struct CreatePullRequestResponse {
+    pub session_id: String,
+    pub url: String,
+}
+
 #[derive(Serialize)]
 pub struct LaunchResponse {
     pub session_id: String,
@@ -317,6 +383,7 @@ pub struct FusionEvaluationResponse {
     pub state: String,
     pub report_path: String,
     pub report: Option<String>,
+    pub verdicts: FusionVerdictTally,
 }
 
 #[derive(Serialize)]
@@ -365,6 +432,8 @@ pub async fn create_session(
                 description: None,
                 role: None,
                 initial_prompt: None,
+                allowed_paths: Vec::new(),
+                denied_paths: Vec::new(),
             };
 
             let principal_cli_overridden = req.principal_cli.is_some();
@@ -396,6 +465,8 @@ pub async fn create_session(
                 description: None,
                 role: None,
                 initial_prompt: None,
+                allowed_paths: Vec::new(),
+                denied_paths: Vec::new(),
             };
             let workers = if let Some(workers) = req.workers {
                 for worker in &workers {
@@ -430,6 +501,12 @@ pub async fn create_session(
                 evaluator_config,
                 qa_workers: req.qa_workers,
                 smoke_test: req.smoke_test.unwrap_or(false),
+                smoke_probe: req.smoke_probe.unwrap_or(false),
+                branch_name: req.branch_name,
+                phase_timeouts: req.phase_timeouts.unwrap_or_default(),
+                review_stage: req.review_stage,
+                spawn_quota: req.spawn_quota.unwrap_or_default(),
+                background: req.background.unwrap_or(false),
             };
 
             let output = dispatch_session_action(
@@ -494,11 +571,18 @@ pub async fn create_session(
                     description: None,
                     role: None,
                     initial_prompt: None,
+                    allowed_paths: Vec::new(),
+                    denied_paths: Vec::new(),
                 },
                 queen_config: None,
                 with_planning: req.with_planning.unwrap_or(false),
                 default_cli,
                 default_model: req.default_model,
+                quorum: None,
+                max_variant_retries: 0,
+                phase_timeouts: req.phase_timeouts.unwrap_or_default(),
+                background: req.background.unwrap_or(false),
+                dirty_tree: req.dirty_tree.unwrap_or_default(),
             };
 
             let output = dispatch_session_action(
@@ -572,11 +656,15 @@ pub async fn create_session(
                     description: None,
                     role: None,
                     initial_prompt: None,
+                    allowed_paths: Vec::new(),
+                    denied_paths: Vec::new(),
                 },
                 queen_config: None,
                 with_planning: req.with_planning.unwrap_or(false),
                 default_cli,
                 default_model: req.default_model,
+                phase_timeouts: req.phase_timeouts.unwrap_or_default(),
+                background: req.background.unwrap_or(false),
             };
 
             let output = dispatch_session_action(
@@ -619,7 +707,7 @@ pub async fn list_sessions(
     State(state): State<Arc<AppState>>,
 ) -> Result<Json<SessionListResponse>, ApiError> {
     let persisted = state
-        .storage
+        .backend
         .list_sessions()
         .map_err(|e| ApiError::internal(e.to_string()))?;
 
@@ -630,6 +718,7 @@ pub async fn list_sessions(
             (
                 s.id.clone(),
                 SessionInfo {
+                    slug: slug::slug_for(&s.id),
                     id: s.id,
                     name: s.name,
                     color: s.color,
@@ -648,6 +737,7 @@ pub async fn list_sessions(
             session.id.clone(),
             SessionInfo {
                 id: session.id.clone(),
+                slug: slug::slug_for(&session.id),
                 name: session.name.clone(),
                 color: session.color.clone(),
                 session_type: match &session.session_type {
@@ -737,6 +827,8 @@ pub async fn launch_swarm(
         description: None,
         role: None,
         initial_prompt: None,
+        allowed_paths: Vec::new(),
+        denied_paths: Vec::new(),
     };
     let queen_config = req.queen_config.unwrap_or_else(|| default_config.clone());
     validate_cli(&queen_config.cli)?;
@@ -779,6 +871,8 @@ pub async fn launch_swarm(
         qa_workers: req.qa_workers,
         smoke_test: false,
         planners: vec![],
+        phase_timeouts: req.phase_timeouts.unwrap_or_default(),
+        background: req.background.unwrap_or(false),
     };
 
     let output = dispatch_session_action(
@@ -812,6 +906,8 @@ pub async fn launch_solo(
         description: None,
         role: None,
         initial_prompt: None,
+        allowed_paths: Vec::new(),
+        denied_paths: Vec::new(),
     };
 
     let evaluator_config = evaluator_config_from_request(
@@ -839,6 +935,12 @@ pub async fn launch_solo(
         evaluator_config,
         qa_workers: None,
         smoke_test: false,
+        smoke_probe: false,
+        branch_name: req.branch_name,
+        phase_timeouts: PhaseTimeouts::default(),
+        review_stage: None,
+        spawn_quota: SpawnQuota::default(),
+        background: req.background.unwrap_or(false),
     };
 
     let output = dispatch_session_action(
@@ -893,6 +995,8 @@ pub async fn launch_fusion(
         description: None,
         role: None,
         initial_prompt: None,
+        allowed_paths: Vec::new(),
+        denied_paths: Vec::new(),
     };
 
     let config = FusionLaunchConfig {
@@ -906,6 +1010,11 @@ pub async fn launch_fusion(
         with_planning: req.with_planning.unwrap_or(false),
         default_cli,
         default_model: req.default_model,
+        quorum: req.quorum,
+        max_variant_retries: req.max_variant_retries.unwrap_or(0),
+        phase_timeouts: req.phase_timeouts.unwrap_or_default(),
+        background: req.background.unwrap_or(false),
+        dirty_tree: req.dirty_tree.unwrap_or_default(),
     };
 
     let output = dispatch_session_action(
@@ -966,6 +1075,8 @@ pub async fn launch_debate(
         description: None,
         role: None,
         initial_prompt: None,
+        allowed_paths: Vec::new(),
+        denied_paths: Vec::new(),
     };
 
     let config = DebateLaunchConfig {
@@ -980,6 +1091,8 @@ pub async fn launch_debate(
         with_planning: req.with_planning.unwrap_or(false),
         default_cli,
         default_model: req.default_model,
+        phase_timeouts: req.phase_timeouts.unwrap_or_default(),
+        background: req.background.unwrap_or(false),
     };
 
     let output = dispatch_session_action(
@@ -1047,7 +1160,7 @@ pub async fn select_fusion_winner(
         return Err(ApiError::bad_request("variant cannot be empty"));
     }
 
-    let controller = state.session_controller.write();
+    let controller = state.session_controller.read();
     controller
         .select_fusion_winner(&id, &req.variant)
         .map_err(ApiError::internal)?;
@@ -1058,6 +1171,104 @@ pub async fn select_fusion_winner(
     })))
 }
 
+/// GET /api/sessions/{id}/branch - Session branch and whether worker commits landed on it
+pub async fn get_session_branch(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<String>,
+) -> Result<Json<SessionBranchStatus>, ApiError> {
+    validate_session_id(&id)?;
+
+    {
+        let controller = state.session_controller.read();
+        let session = controller
+            .get_session(&id)
+            .ok_or_else(|| ApiError::not_found(format!("Session {} not found", id)))?;
+        if session.worktree_branch.is_none() {
+            return Err(ApiError::bad_request(format!(
+                "Session {} has no session branch",
+                id
+            )));
+        }
+    }
+
+    // One `git merge-base` per worker commit; keep them off the async runtime.
+    let controller = Arc::clone(&state.session_controller);
+    let status = tokio::task::spawn_blocking(move || {
+        SessionController::get_session_branch_status(&controller, &id)
+    })
+    .await
+    .map_err(|e| ApiError::internal(format!("Branch status task failed: {}", e)))?
+    .map_err(ApiError::internal)?;
+    Ok(Json(status))
+}
+
+/// POST /api/sessions/{id}/pull-request - Push the session branch and open a PR
+pub async fn create_pull_request(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<String>,
+    Json(req): Json<CreatePullRequestRequest>,
+) -> Result<Json<CreatePullRequestResponse>, ApiError> {
+    validate_session_id(&id)?;
+
+    {
+        let controller = state.session_controller.read();
+        let session = controller
+            .get_session(&id)
+            .ok_or_else(|| ApiError::not_found(format!("Session {} not found", id)))?;
+        if session.worktree_branch.is_none() {
+            return Err(ApiError::bad_request(format!(
+                "Session {} has no session branch",
+                id
+            )));
+        }
+    }
+
+    // Pushing and `gh pr create` hit the network; keep them off the async runtime.
+    let controller = Arc::clone(&state.session_controller);
+    let session_id = id.clone();
+    let url = tokio::task::spawn_blocking(move || {
+        SessionController::create_pull_request(
+            &controller,
+            &session_id,
+            req.title.as_deref(),
+            req.base.as_deref(),
+            req.draft,
+        )
+    })
+    .await
+    .map_err(|e| ApiError::internal(format!("Pull request task failed: {}", e)))?
+    .map_err(ApiError::internal)?;
+
+    Ok(Json(CreatePullRequestResponse {
+        session_id: id,
+        url,
+    }))
+}
+
+/// POST /api/sessions/{id}/fusion/compose - Build an integration branch from picked
+/// commits and files of several variants
+pub async fn compose_fusion_result(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<String>,
+    Json(req): Json<FusionComposeRequest>,
+) -> Result<Json<FusionComposeReport>, ApiError> {
+    validate_session_id(&id)?;
+    if state.session_controller.read().get_session(&id).is_none() {
+        return Err(ApiError::not_found(format!("Session {} not found", id)));
+    }
+
+    // Worktree creation and cherry-picks run git; keep them off the async runtime.
+    let controller = Arc::clone(&state.session_controller);
+    let report = tokio::task::spawn_blocking(move || {
+        controller.read().compose_fusion_result(&id, &req)
+    })
+    .await
+    .map_err(|e| ApiError::internal(format!("Compose task failed: {}", e)))?
+    .map_err(ApiError::bad_request)?;
+
+    Ok(Json(report))
+}
+
 /// GET /api/sessions/{id}/fusion/status - Get fusion variant statuses
 pub async fn get_fusion_status(
     State(state): State<Arc<AppState>>,
@@ -1097,7 +1308,7 @@ pub async fn get_fusion_evaluation(
         return Err(ApiError::not_found(format!("Session {} not found", id)));
     }
 
-    let (report_path, report) = controller
+    let (report_path, report, verdicts) = controller
         .get_fusion_evaluation(&id)
         .map_err(ApiError::internal)?;
     let state_str = controller
@@ -1110,6 +1321,72 @@ pub async fn get_fusion_evaluation(
         state: state_str,
         report_path,
         report,
+        verdicts,
+    }))
+}
+
+/// POST /api/sessions/{id}/fusion/judges - Re-run the judge or add second-opinion judges
+pub async fn run_fusion_judges(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<String>,
+    Json(req): Json<RunFusionJudgesRequest>,
+) -> Result<Json<RunFusionJudgesResponse>, ApiError> {
+    validate_session_id(&id)?;
+    for judge in &req.judges {
+        if !judge.cli.trim().is_empty() {
+            validate_cli(&judge.cli)?;
+        }
+    }
+
+    let controller = state.session_controller.read();
+    if controller.get_session(&id).is_none() {
+        return Err(ApiError::not_found(format!("Session {} not found", id)));
+    }
+
+    let judge_ids = controller
+        .spawn_fusion_judge_panel(&id, req.judges)
+        .map_err(ApiError::bad_request)?;
+
+    Ok(Json(RunFusionJudgesResponse {
+        session_id: id,
+        judge_ids,
+    }))
+}
+
+/// POST /api/sessions/{id}/fusion/variants/{index}/retry - Respawn a variant on a fresh worktree
+pub async fn retry_fusion_variant(
+    State(state): State<Arc<AppState>>,
+    Path((id, index)): Path<(String, u8)>,
+) -> Result<Json<FusionStatusResponse>, ApiError> {
+    validate_session_id(&id)?;
+
+    if state.session_controller.read().get_session(&id).is_none() {
+        return Err(ApiError::not_found(format!("Session {} not found", id)));
+    }
+
+    // Resetting the variant worktree runs git; keep it off the async runtime.
+    let controller = Arc::clone(&state.session_controller);
+    let session_id = id.clone();
+    tokio::task::spawn_blocking(move || {
+        SessionController::retry_fusion_variant(&controller, &session_id, index)
+    })
+    .await
+    .map_err(|e| ApiError::internal(format!("Fusion variant retry task failed: {}", e)))?
+    .map_err(ApiError::bad_request)?;
+
+    let controller = state.session_controller.read();
+    let variants = controller
+        .get_fusion_variant_statuses(&id)
+        .map_err(ApiError::internal)?;
+    let state_str = controller
+        .get_session(&id)
+        .map(|s| format!("{:?}", s.state))
+        .unwrap_or_else(|| "Unknown".to_string());
+
+    Ok(Json(FusionStatusResponse {
+        session_id: id,
+        state: state_str,
+        variants,
     }))
 }
 
@@ -1206,6 +1483,124 @@ pub async fn complete_session(
     })))
 }
 
+/// Query for GET /api/sessions/{id}/coordination
+#[derive(Debug, Deserialize, Default)]
+pub struct CoordinationLogQuery {
+    /// The `cursor` of the previous read; only messages logged after it are returned.
+    #[serde(default)]
+    pub after_seq: Option<u64>,
+    /// Most recent messages to return when reading without a cursor.
+    #[serde(default)]
+    pub limit: Option<usize>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct CoordinationLogPage {
+    pub messages: Vec<CoordinationMessage>,
+    /// Pass back as `after_seq` to get only what is logged next.
+    pub cursor: u64,
+}
+
+/// GET /api/sessions/{id}/coordination?after_seq=N — the coordination log, or only
+/// the messages logged since the cursor a previous read returned.
+pub async fn get_coordination_log(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<String>,
+    Query(query): Query<CoordinationLogQuery>,
+) -> Result<Json<CoordinationLogPage>, ApiError> {
+    validate_session_id(&id)?;
+
+    let after_seq = query.after_seq;
+    let storage = Arc::clone(&state.backend);
+    let messages = tokio::task::spawn_blocking(move || match after_seq {
+        Some(after_seq) => storage.read_coordination_log_after(&id, after_seq),
+        None => storage.read_coordination_log(&id, query.limit),
+    })
+    .await
+    .map_err(|e| ApiError::internal(format!("Task join error: {e}")))?
+    .map_err(|e| ApiError::internal(format!("Failed to read coordination log: {e}")))?;
+
+    let cursor = messages
+        .iter()
+        .filter_map(|message| message.seq)
+        .chain(after_seq)
+        .max()
+        .unwrap_or(0);
+    Ok(Json(CoordinationLogPage { messages, cursor }))
+}
+
+/// GET /api/sessions/{id}/coordination/stats — message counts by type and sender,
+/// per-worker TASK→COMPLETION times and error rates for the coordination log.
+pub async fn get_coordination_stats(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<String>,
+) -> Result<Json<CoordinationStats>, ApiError> {
+    validate_session_id(&id)?;
+
+    let storage = Arc::clone(&state.storage);
+    let stats = tokio::task::spawn_blocking(move || storage.coordination_stats(&id))
+        .await
+        .map_err(|e| ApiError::internal(format!("Task join error: {e}")))?
+        .map_err(|e| ApiError::internal(format!("Failed to read coordination stats: {e}")))?;
+
+    Ok(Json(stats))
+}
+
+/// GET /api/sessions/{id}/api-usage — API calls per agent and endpoint, with error
+/// counts and latency, from the session's `api-usage.jsonl`.
+pub async fn get_api_usage(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<String>,
+) -> Result<Json<ApiUsageSummary>, ApiError> {
+    validate_session_id(&id)?;
+
+    let storage = Arc::clone(&state.storage);
+    let summary = tokio::task::spawn_blocking(move || storage.api_usage_summary(&id))
+        .await
+        .map_err(|e| ApiError::internal(format!("Task join error: {e}")))?
+        .map_err(|e| ApiError::internal(format!("Failed to read API usage: {e}")))?;
+
+    Ok(Json(summary))
+}
+
+/// GET /api/sessions/{id}/durations — wall-clock time per phase (Planning, Worker N
+/// active, Judging, ...) and per agent, for comparing Hive, Swarm and Fusion runs.
+pub async fn get_session_durations(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<String>,
+) -> Result<Json<SessionDurations>, ApiError> {
+    validate_session_id(&id)?;
+
+    let controller = state.session_controller.read();
+    controller
+        .get_session_durations(&id)
+        .map(Json)
+        .map_err(ApiError::not_found)
+}
+
+/// Query for GET /api/sessions/compare.
+#[derive(Debug, Deserialize)]
+pub struct CompareSessionsQuery {
+    pub a: String,
+    pub b: String,
+}
+
+/// GET /api/sessions/compare?a={id}&b={id} — phase durations, tasks completed, diff
+/// size and learnings of two sessions side by side, with `b - a` time deltas.
+pub async fn compare_sessions(
+    State(state): State<Arc<AppState>>,
+    Query(query): Query<CompareSessionsQuery>,
+) -> Result<Json<SessionComparison>, ApiError> {
+    validate_session_id(&query.a)?;
+    validate_session_id(&query.b)?;
+
+    let controller = state.session_controller.read();
+    controller
+        .compare_sessions(&query.a, &query.b)
+        .map(Json)
+        .map_err(ApiError::not_found)
+}
+
 /// Response body for the run-journal endpoint.
 #[derive(Debug, Serialize)]
 pub struct RunJournalResponse {
@@ -0,0 +1,52 @@
+use axum::{
+    extract::{Path, State},
+    Json,
+};
+use serde::Serialize;
+use std::sync::Arc;
+
+use super::validate_session_id;
+use crate::http::error::ApiError;
+use crate::http::state::AppState;
+use crate::session::snapshots::{self, SessionSnapshot, SNAPSHOTS_DIR};
+
+#[derive(Debug, Serialize)]
+pub struct SnapshotListResponse {
+    pub session_id: String,
+    /// Snapshot IDs, oldest first. Each ID is the UTC time it was taken.
+    pub snapshots: Vec<String>,
+}
+
+/// GET /api/sessions/{id}/snapshots - IDs of the session's recorded snapshots.
+pub async fn list_snapshots(
+    State(state): State<Arc<AppState>>,
+    Path(session_id): Path<String>,
+) -> Result<Json<SnapshotListResponse>, ApiError> {
+    validate_session_id(&session_id)?;
+    let dir = state.storage.session_dir(&session_id).join(SNAPSHOTS_DIR);
+    let ids = snapshots::list(&dir)
+        .map_err(|e| ApiError::internal(format!("Failed to list snapshots: {}", e)))?;
+    Ok(Json(SnapshotListResponse {
+        session_id,
+        snapshots: ids,
+    }))
+}
+
+/// GET /api/sessions/{id}/snapshots/{at} - The newest snapshot taken at or before
+/// `at`, a snapshot ID or an RFC 3339 time. Read-only; the live session is untouched.
+pub async fn get_snapshot(
+    State(state): State<Arc<AppState>>,
+    Path((session_id, at)): Path<(String, String)>,
+) -> Result<Json<SessionSnapshot>, ApiError> {
+    validate_session_id(&session_id)?;
+    let dir = state.storage.session_dir(&session_id).join(SNAPSHOTS_DIR);
+    snapshots::read_at(&dir, &at)
+        .map_err(|e| ApiError::internal(format!("Failed to read snapshot: {}", e)))?
+        .map(Json)
+        .ok_or_else(|| {
+            ApiError::not_found(format!(
+                "No snapshot of session {} at or before {}",
+                session_id, at
+            ))
+        })
+}
@@ -5,10 +5,14 @@ use axum::{
     http::StatusCode,
     Json,
 };
+use serde::Deserialize;
 
 use crate::{
     http::{error::ApiError, state::AppState},
-    templates::{builtin_role_packs, builtin_session_templates, SessionTemplate, TemplateCatalog},
+    templates::{
+        builtin_role_packs, builtin_session_templates, PromptTemplate, PromptTemplateInfo,
+        SessionTemplate, TemplateCatalog, TemplateEngine, TemplateError,
+    },
 };
 
 use super::validate_template_id;
@@ -27,9 +31,88 @@ pub async fn list_templates(
     Ok(Json(TemplateCatalog {
         templates,
         role_packs: builtin_role_packs(),
+        prompt_templates: prompt_engine(&state).template_catalog(),
     }))
 }
 
+#[derive(Debug, Deserialize)]
+pub struct SavePromptTemplateRequest {
+    pub content: String,
+}
+
+fn prompt_engine(state: &AppState) -> TemplateEngine {
+    TemplateEngine::new(state.storage.templates_dir())
+}
+
+fn prompt_template_error(name: &str, err: TemplateError) -> ApiError {
+    match err {
+        TemplateError::NotFound(_) => {
+            ApiError::not_found(format!("Prompt template {} not found", name))
+        }
+        TemplateError::Invalid(message) => ApiError::bad_request(message),
+        TemplateError::Io(err) => ApiError::internal(err.to_string()),
+    }
+}
+
+/// Placeholders a prompt template expects. Names with folders are passed
+/// URL-encoded, e.g. `roles%2Fbackend`.
+pub async fn get_template_variables(
+    State(state): State<Arc<AppState>>,
+    Path(name): Path<String>,
+) -> Result<Json<PromptTemplateInfo>, ApiError> {
+    prompt_engine(&state)
+        .template_info(&name)
+        .map(Json)
+        .map_err(|err| prompt_template_error(&name, err))
+}
+
+pub async fn list_prompt_templates(
+    State(state): State<Arc<AppState>>,
+) -> Json<Vec<PromptTemplateInfo>> {
+    Json(prompt_engine(&state).template_catalog())
+}
+
+pub async fn get_prompt_template(
+    State(state): State<Arc<AppState>>,
+    Path(name): Path<String>,
+) -> Result<Json<PromptTemplate>, ApiError> {
+    prompt_engine(&state)
+        .load_template(&name)
+        .map(Json)
+        .map_err(|err| prompt_template_error(&name, err))
+}
+
+/// Write an on-disk override (or a new template) that the engine renders from now on.
+pub async fn save_prompt_template(
+    State(state): State<Arc<AppState>>,
+    Path(name): Path<String>,
+    Json(request): Json<SavePromptTemplateRequest>,
+) -> Result<Json<PromptTemplate>, ApiError> {
+    let engine = prompt_engine(&state);
+    engine
+        .save_template(&name, &request.content)
+        .and_then(|()| engine.load_template(&name))
+        .map(Json)
+        .map_err(|err| prompt_template_error(&name, err))
+}
+
+/// Delete an on-disk template; a built-in one it overrode takes effect again.
+pub async fn delete_prompt_template(
+    State(state): State<Arc<AppState>>,
+    Path(name): Path<String>,
+) -> Result<StatusCode, ApiError> {
+    let deleted = prompt_engine(&state)
+        .delete_template(&name)
+        .map_err(|err| prompt_template_error(&name, err))?;
+    if !deleted {
+        return Err(ApiError::not_found(format!(
+            "Prompt template {} has no on-disk override",
+            name
+        )));
+    }
+    Ok(StatusCode::NO_CONTENT)
+}
+
 pub async fn get_template(
     State(state): State<Arc<AppState>>,
     Path(template_id): Path<String>,
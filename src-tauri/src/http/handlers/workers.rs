@@ -8,9 +8,10 @@ use serde_json::{json, Value};
 use std::collections::HashMap;
 use std::sync::Arc;
 
-use super::{validate_cli, validate_session_id};
+use super::approvals::require_approval;
+use super::{reject_quarantined_agent, validate_cli, validate_session_id};
 use crate::cli::CliRegistry;
-use crate::coordination::{StateManager, WorkerStateInfo};
+use crate::coordination::{GatedAction, StateManager, WorkerStateInfo};
 use crate::http::error::ApiError;
 use crate::http::state::AppState;
 use crate::pty::{AgentConfig, AgentRole, WorkerRole};
@@ -52,6 +53,12 @@ pub struct AddWorkerRequest {
     pub flags: Option<Vec<String>>,
     /// Initial task/prompt for the worker
     pub initial_task: Option<String>,
+    /// Globs the worker may change; empty allows everything not denied
+    #[serde(default)]
+    pub allowed_paths: Vec<String>,
+    /// Globs the worker must not change
+    #[serde(default)]
+    pub denied_paths: Vec<String>,
     /// Parent agent ID (defaults to Queen)
     pub parent_id: Option<String>,
 }
@@ -67,6 +74,9 @@ pub struct AddWorkerResponse {
 }
 
 /// POST /api/sessions/{id}/workers - Add a new worker to a session
+///
+/// A worker the session's spawn quota has no room for is refused with 409, naming the
+/// role, the limit and the live count (see `session::spawn_quota`).
 pub async fn add_worker(
     State(state): State<Arc<AppState>>,
     Path(session_id): Path<String>,
@@ -83,8 +93,15 @@ pub async fn add_worker(
         model: requested_model,
         flags: requested_flags,
         initial_task,
+        allowed_paths,
+        denied_paths,
         parent_id,
     } = req;
+    // Workers are spawned on behalf of their parent, the Queen unless named.
+    let spawner = parent_id
+        .clone()
+        .unwrap_or_else(|| format!("{}-queen", session_id));
+    reject_quarantined_agent(&state, &session_id, &spawner)?;
 
     let principal_defaults = {
         let controller = state.session_controller.read();
@@ -92,6 +109,22 @@ pub async fn add_worker(
     }
     .ok_or_else(|| ApiError::not_found(format!("Session {} not found", session_id)))?;
 
+    // Held until the worker is in the session, so a concurrent request sees it.
+    let quota_reservation = state
+        .session_controller
+        .read()
+        .reserve_spawn_quota(&session_id, &role_type);
+    let quota_reservation = match quota_reservation {
+        Ok(reservation) => reservation,
+        Err(exceeded) => {
+            let mut details: HashMap<String, Value> = HashMap::new();
+            details.insert("role_type".to_string(), json!(exceeded.role_type));
+            details.insert("limit".to_string(), json!(exceeded.limit));
+            details.insert("live".to_string(), json!(exceeded.live));
+            return Err(ApiError::conflict_with_details(exceeded.to_string(), details));
+        }
+    };
+
     let inherits_principal_defaults = match requested_cli.as_deref() {
         None => true,
         Some(requested) => requested == principal_defaults.cli.as_str(),
@@ -140,6 +173,8 @@ pub async fn add_worker(
         description,
         role: Some(role.clone()),
         initial_prompt: initial_task.clone(),
+        allowed_paths,
+        denied_paths,
     };
 
     // #126: enqueue + atomically claim the worker BEFORE spawning. The queue table is the
@@ -160,6 +195,15 @@ pub async fn add_worker(
             .unwrap_or(0);
         (existing + 1) as u8
     };
+    require_approval(
+        &state,
+        &session_id,
+        parent_id.as_deref(),
+        GatedAction::SpawnWorker {
+            worker_count: predicted_index as usize,
+        },
+    )
+    .await?;
     let predicted_worker_id = format!("{}-worker-{}", session_id, predicted_index);
     let queue_id = predicted_worker_id.clone();
     let payload = json!({
@@ -205,7 +249,7 @@ pub async fn add_worker(
 
     // Add worker through session controller
     let (worker_id, worker_index) = {
-        let controller = state.session_controller.write();
+        let controller = state.session_controller.read();
 
         let agent_info = controller
             .add_worker(&session_id, config, role.clone(), parent_id)
@@ -221,6 +265,7 @@ pub async fn add_worker(
 
         (agent_info.id, index)
     };
+    drop(quota_reservation);
 
     // Update workers.md file
     let session_path = state.storage.session_dir(&session_id);
@@ -236,6 +281,7 @@ pub async fn add_worker(
                 .filter(|a| matches!(a.role, AgentRole::Worker { .. }))
                 .map(|a| WorkerStateInfo {
                     id: a.id.clone(),
+                    label: a.config.label.clone(),
                     role: a.config.role.clone().unwrap_or_default(),
                     cli: a.config.cli.clone(),
                     status: format!("{:?}", a.status),
@@ -253,6 +299,7 @@ pub async fn add_worker(
     let queen_id = format!("{}-queen", session_id);
     let worker_state = WorkerStateInfo {
         id: worker_id.clone(),
+        label: Some(role.label.clone()),
         role: role.clone(),
         cli: cli.clone(),
         status: "Running".to_string(),
@@ -318,6 +365,7 @@ pub async fn list_workers(
             .to_string();
             Ok(json!({
                 "id": a.id,
+                "label": a.display_name(),
                 "role": a.config.role.as_ref().map(|r| &r.label).unwrap_or(&"Worker".to_string()),
                 "cli": a.config.cli,
                 "status": format!("{:?}", a.status),
@@ -332,3 +380,56 @@ pub async fn list_workers(
         "count": workers.len()
     })))
 }
+
+/// Statuses a worker may report for its own task.
+const REPORTABLE_TASK_STATUSES: &[&str] = &["COMPLETED", "BLOCKED"];
+
+/// POST /api/sessions/{id}/tasks/{worker}/status - Body
+#[derive(Debug, Deserialize)]
+pub struct ReportTaskStatusRequest {
+    /// COMPLETED or BLOCKED
+    pub status: String,
+    /// The result, or what the worker is blocked on
+    pub summary: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ReportTaskStatusResponse {
+    pub worker_id: String,
+    pub status: String,
+    pub task_file: String,
+}
+
+/// POST /api/sessions/{id}/tasks/{worker}/status - Worker N marks its own task
+/// COMPLETED or BLOCKED without editing the task file itself
+pub async fn report_task_status(
+    State(state): State<Arc<AppState>>,
+    Path((session_id, worker)): Path<(String, u8)>,
+    Json(req): Json<ReportTaskStatusRequest>,
+) -> Result<Json<ReportTaskStatusResponse>, ApiError> {
+    validate_session_id(&session_id)?;
+    let status = req.status.trim().to_ascii_uppercase();
+    if !REPORTABLE_TASK_STATUSES.contains(&status.as_str()) {
+        return Err(ApiError::bad_request("Status must be one of: COMPLETED, BLOCKED"));
+    }
+    if req.summary.trim().is_empty() {
+        return Err(ApiError::bad_request("summary cannot be empty"));
+    }
+    let worker_id = format!("{}-worker-{}", session_id, worker);
+    reject_quarantined_agent(&state, &session_id, &worker_id)?;
+
+    let controller = state.session_controller.read();
+    if controller.get_session(&session_id).is_none() {
+        return Err(ApiError::not_found(format!("Session {} not found", session_id)));
+    }
+    let task_file = controller
+        .report_worker_task_status(&session_id, worker, &status, &req.summary)
+        .map_err(ApiError::internal)?
+        .ok_or_else(|| ApiError::not_found(format!("Worker {} not found", worker_id)))?;
+
+    Ok(Json(ReportTaskStatusResponse {
+        worker_id,
+        status,
+        task_file: task_file.to_string_lossy().to_string(),
+    }))
+}
@@ -1,19 +1,45 @@
 pub mod error;
 pub mod handlers;
+pub mod outbox;
 pub mod routes;
 pub mod state;
+pub mod transport;
 #[cfg(test)]
 pub mod tests;
 
 use crate::http::routes::create_router;
 use crate::http::state::AppState;
+use std::io;
 use std::net::SocketAddr;
 use std::sync::Arc;
+use tokio::net::TcpListener;
 
+/// Ports tried from the configured one upwards when it is taken, such as by
+/// another Hive Manager instance.
+pub const PORT_ATTEMPTS: u16 = 20;
+
+/// Serve the API on `port`, or on the next free port after it. Agents are told
+/// the port actually bound (see `transport::for_agents`).
 #[cfg_attr(test, allow(dead_code))]
 pub async fn serve(state: Arc<AppState>, port: u16) -> Result<(), std::io::Error> {
     let app = create_router(state);
-    let addr = SocketAddr::from(([127, 0, 0, 1], port));
-    let listener = tokio::net::TcpListener::bind(addr).await?;
+    let listener = bind_free_port(port).await?;
+    let bound = listener.local_addr()?.port();
+    if bound != port {
+        tracing::warn!("Port {} is taken; serving the HTTP API on port {}", port, bound);
+    }
+    transport::set_serving_port(bound);
     axum::serve(listener, app).await
 }
+
+async fn bind_free_port(port: u16) -> io::Result<TcpListener> {
+    let mut taken = None;
+    for candidate in (0..PORT_ATTEMPTS).filter_map(|offset| port.checked_add(offset)) {
+        match TcpListener::bind(SocketAddr::from(([127, 0, 0, 1], candidate))).await {
+            Ok(listener) => return Ok(listener),
+            Err(e) if e.kind() == io::ErrorKind::AddrInUse => taken = Some(e),
+            Err(e) => return Err(e),
+        }
+    }
+    Err(taken.unwrap_or_else(|| io::Error::from(io::ErrorKind::AddrInUse)))
+}
@@ -0,0 +1,217 @@
+//! File-based stand-in for the HTTP API on machines where agents cannot reach it.
+//!
+//! With `api.offline`, agent prompts replace the curl instructions with an outbox:
+//! an agent writes each call as `{"method", "path", "agent_id", "body"}` to a `.json`
+//! file in `sessions/<id>/outbox/` of app storage, where `path` is relative to
+//! `/api/sessions/<id>/`. Every second each session's outbox is drained through the
+//! same router the HTTP server uses, carrying the session's API token, so heartbeats,
+//! learnings and worker spawns take the exact path they take over HTTP. The response
+//! is written to `outbox/replies/<file name>` and the request file is removed. A file
+//! that cannot be read or parsed gets an error reply and is moved to `outbox/failed/`,
+//! so it never holds up the requests behind it.
+
+use std::fs;
+use std::io;
+use std::path::Path;
+use std::time::{Duration, SystemTime};
+
+use axum::body::Body;
+use axum::http::{Method, Request};
+use axum::Router;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use tower::ServiceExt;
+
+use crate::http::state::AppState;
+use crate::session::{AGENT_ID_HEADER, SESSION_TOKEN_HEADER};
+
+pub const REPLIES_DIR: &str = "replies";
+pub const FAILED_DIR: &str = "failed";
+
+/// A file that cannot be read or parsed yet may still be being written; it is
+/// answered with an error only once it is this old.
+const UNPARSED_GRACE: Duration = Duration::from_secs(5);
+
+/// Largest response body written to a reply file.
+const MAX_REPLY_BYTES: usize = 4 * 1024 * 1024;
+
+#[derive(Debug, Deserialize)]
+struct OutboxRequest {
+    #[serde(default = "default_method")]
+    method: String,
+    path: String,
+    #[serde(default)]
+    agent_id: Option<String>,
+    #[serde(default)]
+    body: Option<Value>,
+}
+
+fn default_method() -> String {
+    "POST".to_string()
+}
+
+#[derive(Debug, Serialize)]
+struct OutboxReply {
+    status: u16,
+    body: Value,
+}
+
+impl OutboxReply {
+    fn error(status: u16, message: impl Into<String>) -> Self {
+        Self {
+            status,
+            body: serde_json::json!({ "error": message.into() }),
+        }
+    }
+}
+
+/// The router URI for `path`, which may not leave `/api/sessions/<session_id>/`.
+fn request_uri(session_id: &str, path: &str) -> Result<String, String> {
+    let path = path.trim().trim_start_matches('/');
+    let route = path.split('?').next().unwrap_or_default();
+    if route.is_empty() || route.split('/').any(|segment| segment == "..") || route.contains('\\')
+    {
+        return Err(format!("Invalid path: {}", path));
+    }
+    Ok(format!("/api/sessions/{}/{}", session_id, path))
+}
+
+async fn send(
+    router: &Router,
+    session_id: &str,
+    token: Option<&str>,
+    request: OutboxRequest,
+) -> OutboxReply {
+    let uri = match request_uri(session_id, &request.path) {
+        Ok(uri) => uri,
+        Err(e) => return OutboxReply::error(400, e),
+    };
+    let Ok(method) = Method::from_bytes(request.method.to_ascii_uppercase().as_bytes()) else {
+        return OutboxReply::error(400, format!("Invalid method: {}", request.method));
+    };
+    let mut builder = Request::builder()
+        .method(method)
+        .uri(uri)
+        .header("content-type", "application/json");
+    if let Some(token) = token {
+        builder = builder.header(SESSION_TOKEN_HEADER, token);
+    }
+    if let Some(agent_id) = request.agent_id.as_deref() {
+        builder = builder.header(AGENT_ID_HEADER, agent_id);
+    }
+    let body = request.body.map(|body| body.to_string()).unwrap_or_default();
+    let http_request = match builder.body(Body::from(body)) {
+        Ok(http_request) => http_request,
+        Err(e) => return OutboxReply::error(400, e.to_string()),
+    };
+
+    let response = match router.clone().oneshot(http_request).await {
+        Ok(response) => response,
+        Err(e) => return OutboxReply::error(500, e.to_string()),
+    };
+    let status = response.status().as_u16();
+    let bytes = match axum::body::to_bytes(response.into_body(), MAX_REPLY_BYTES).await {
+        Ok(bytes) => bytes,
+        Err(e) => return OutboxReply::error(500, format!("Failed to read the response: {}", e)),
+    };
+    let body = serde_json::from_slice(&bytes)
+        .unwrap_or_else(|_| Value::String(String::from_utf8_lossy(&bytes).into_owned()));
+    OutboxReply { status, body }
+}
+
+/// Take the request file `path` out of `outbox`, removing it once served or moving it
+/// to `failed/` when it was not, and write `reply` for it.
+fn settle(outbox: &Path, path: &Path, reply: &OutboxReply, served: bool) -> io::Result<()> {
+    let Some(name) = path.file_name() else {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("Not a file: {}", path.display()),
+        ));
+    };
+    // The request goes first: one that was served must not be sent again just
+    // because its reply could not be written.
+    if served {
+        fs::remove_file(path)?;
+    } else {
+        let failed = outbox.join(FAILED_DIR);
+        fs::create_dir_all(&failed)?;
+        fs::rename(path, failed.join(name))?;
+    }
+    let replies = outbox.join(REPLIES_DIR);
+    fs::create_dir_all(&replies)?;
+    let mut temp = tempfile::NamedTempFile::new_in(&replies)?;
+    serde_json::to_writer_pretty(&mut temp, reply)?;
+    temp.persist(replies.join(name)).map_err(|e| e.error)?;
+    Ok(())
+}
+
+/// Send every request file in `outbox` through `router` as `session_id`'s agents,
+/// oldest first. Returns the number answered.
+pub async fn drain(
+    router: &Router,
+    session_id: &str,
+    token: Option<&str>,
+    outbox: &Path,
+) -> io::Result<usize> {
+    let entries = match fs::read_dir(outbox) {
+        Ok(entries) => entries,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(0),
+        Err(e) => return Err(e),
+    };
+    let mut files: Vec<(SystemTime, std::path::PathBuf)> = entries
+        .filter_map(|entry| {
+            let entry = entry.ok()?;
+            let path = entry.path();
+            let is_request = entry.file_type().ok()?.is_file()
+                && path.extension().is_some_and(|extension| extension == "json");
+            is_request.then(|| (entry.metadata().ok()?.modified().ok()?, path))
+        })
+        .collect();
+    files.sort();
+
+    let mut answered = 0;
+    for (modified, path) in files {
+        let settling = modified.elapsed().unwrap_or_default() < UNPARSED_GRACE;
+        let request = fs::read_to_string(&path)
+            .map_err(|e| format!("Unreadable outbox request: {}", e))
+            .and_then(|contents| {
+                serde_json::from_str::<OutboxRequest>(&contents)
+                    .map_err(|e| format!("Invalid outbox request: {}", e))
+            });
+        let (reply, served) = match request {
+            Ok(request) => (send(router, session_id, token, request).await, true),
+            Err(_) if settling => continue,
+            Err(e) => (OutboxReply::error(400, e), false),
+        };
+        match settle(outbox, &path, &reply, served) {
+            Ok(()) => answered += 1,
+            Err(e) => tracing::warn!("Failed to answer outbox request {}: {}", path.display(), e),
+        }
+    }
+    Ok(answered)
+}
+
+/// Drain the outbox of every session in memory.
+pub async fn drain_all(router: &Router, state: &AppState) -> usize {
+    let sessions: Vec<(String, Option<String>)> = {
+        let controller = state.session_controller.read();
+        controller
+            .list_sessions()
+            .into_iter()
+            .map(|session| {
+                let token = controller.issued_session_api_token(&session.id);
+                (session.id, token)
+            })
+            .collect()
+    };
+
+    let mut answered = 0;
+    for (session_id, token) in sessions {
+        let outbox = state.storage.outbox_dir(&session_id);
+        match drain(router, &session_id, token.as_deref(), &outbox).await {
+            Ok(count) => answered += count,
+            Err(e) => tracing::warn!("Failed to drain the outbox of {}: {}", session_id, e),
+        }
+    }
+    answered
+}
@@ -1,19 +1,29 @@
 use crate::http::handlers::{
-    actions, agents, application_state, artifacts, cells, conversations, evaluator, events, health,
-    heartbeats, inject, knowledge, learnings, planners, queue, resolver, session_files, sessions,
-    templates, workers,
+    actions, agents, application_state, approvals, artifacts, attachments,
+    authorize_session_write, cells, conversations, coordination_view, editor, evaluator, events,
+    git_ops, health, heartbeats, inject, is_ui_request, knowledge, learnings, planners,
+    questions, queue, resolver, reviews, scratchpad, session_files, sessions, snapshots,
+    templates, validate_session_id, workers,
 };
+use crate::http::error::ApiError;
 use crate::http::state::AppState;
 use crate::cli::health as cli_health;
+use crate::session::slug;
+use crate::session::{AGENT_ID_HEADER, SESSION_TOKEN_HEADER};
+use crate::storage::api_usage::ApiCall;
+use crate::storage::attachments::MAX_ATTACHMENT_BYTES;
 use axum::{
     body::Body,
-    http::{header::ORIGIN, HeaderValue, Request, StatusCode},
+    extract::{DefaultBodyLimit, MatchedPath, State},
+    http::{header::ORIGIN, HeaderValue, Method, Request, StatusCode, Uri},
     middleware::{self, Next},
     response::{IntoResponse, Response},
     routing::{delete, get, post},
     Router,
 };
 use std::sync::Arc;
+use std::time::Instant;
+use tower::Layer;
 use tower_http::cors::{AllowOrigin, Any, CorsLayer};
 
 const ALLOWED_BROWSER_ORIGINS: &[&str] = &[
@@ -23,7 +33,7 @@ const ALLOWED_BROWSER_ORIGINS: &[&str] = &[
     "http://localhost:1420",
 ];
 
-fn is_allowed_browser_origin(origin: &HeaderValue) -> bool {
+pub(crate) fn is_allowed_browser_origin(origin: &HeaderValue) -> bool {
     ALLOWED_BROWSER_ORIGINS
         .iter()
         .any(|allowed| origin.as_bytes() == allowed.as_bytes())
@@ -41,6 +51,108 @@ async fn reject_disallowed_browser_origin(request: Request<Body>, next: Next) ->
     next.run(request).await
 }
 
+/// Writes under `/api/sessions/{id}` must carry that session's API token; reads stay open.
+async fn require_session_token(
+    State(state): State<Arc<AppState>>,
+    request: Request<Body>,
+    next: Next,
+) -> Response {
+    let is_write = !matches!(
+        *request.method(),
+        Method::GET | Method::HEAD | Method::OPTIONS
+    );
+    if is_write {
+        if let Some(session_id) = session_id_from_path(request.uri().path()) {
+            if let Err(err) = authorize_session_write(&state, session_id, request.headers()) {
+                return err.into_response();
+            }
+        }
+    }
+
+    next.run(request).await
+}
+
+/// Record each call under `/api/sessions/{id}` in that session's API usage log,
+/// rejected ones included. The app's own UI is not an agent and is left out.
+async fn log_api_usage(
+    State(state): State<Arc<AppState>>,
+    request: Request<Body>,
+    next: Next,
+) -> Response {
+    let from_ui = is_ui_request(&state, request.headers());
+    let session_id = match session_id_from_path(request.uri().path()) {
+        Some(session_id) if !from_ui => session_id.to_string(),
+        _ => return next.run(request).await,
+    };
+    let method = request.method().to_string();
+    let endpoint = request
+        .extensions()
+        .get::<MatchedPath>()
+        .map_or_else(|| request.uri().path(), MatchedPath::as_str)
+        .to_string();
+    let agent_id = request
+        .headers()
+        .get(AGENT_ID_HEADER)
+        .and_then(|value| value.to_str().ok())
+        .map(String::from);
+    let token_presented = request.headers().contains_key(SESSION_TOKEN_HEADER);
+
+    let started = Instant::now();
+    let response = next.run(request).await;
+    let call = ApiCall {
+        timestamp: chrono::Utc::now(),
+        method,
+        endpoint,
+        agent_id,
+        token_presented,
+        status: response.status().as_u16(),
+        latency_ms: started.elapsed().as_millis() as u64,
+    };
+
+    let storage = Arc::clone(&state.storage);
+    let logged =
+        tokio::task::spawn_blocking(move || storage.record_api_call(&session_id, &call)).await;
+    if let Ok(Err(e)) = logged {
+        tracing::warn!("Failed to record API call: {}", e);
+    }
+    response
+}
+
+/// Rewrite `/api/sessions/{slug}/...` to the session's ID. Runs ahead of routing, so
+/// every route taking a session ID also takes the session's slug.
+async fn resolve_session_slug(
+    State(state): State<Arc<AppState>>,
+    mut request: Request<Body>,
+    next: Next,
+) -> Response {
+    let Some(reference) = session_id_from_path(request.uri().path())
+        .filter(|reference| slug::is_slug(reference))
+        .map(String::from)
+    else {
+        return next.run(request).await;
+    };
+    let session_id = match state.session_controller.read().resolve_session_ref(&reference) {
+        Ok(session_id) => session_id,
+        Err(e) => return ApiError::new(StatusCode::CONFLICT, e).into_response(),
+    };
+    let rest = &request.uri().path()["/api/sessions/".len() + reference.len()..];
+    let mut rewritten = format!("/api/sessions/{}{}", session_id, rest);
+    if let Some(query) = request.uri().query() {
+        rewritten = format!("{}?{}", rewritten, query);
+    }
+    if let Ok(uri) = rewritten.parse::<Uri>() {
+        *request.uri_mut() = uri;
+    }
+    next.run(request).await
+}
+
+fn session_id_from_path(path: &str) -> Option<&str> {
+    path.strip_prefix("/api/sessions/")?
+        .split('/')
+        .next()
+        .filter(|id| !id.is_empty() && validate_session_id(id).is_ok())
+}
+
 pub fn create_router(state: Arc<AppState>) -> Router {
     let cors = CorsLayer::new()
         .allow_origin(AllowOrigin::predicate(|origin, _| {
@@ -49,19 +161,22 @@ pub fn create_router(state: Arc<AppState>) -> Router {
         .allow_methods(Any)
         .allow_headers(Any);
 
-    Router::new()
+    let api = Router::new()
         .route("/health", get(health::health_check))
         .route("/api/cli-health", get(cli_health::get_cli_health_http))
+        .route("/api/preflight", post(cli_health::preflight_check_http))
         // Unified action registry surface (the future agent/MCP entrypoint).
         // GET lists every action + schema; POST dispatches any action (caller=Http).
         .route("/api/actions", get(actions::list_actions))
         .route("/api/actions/{name}", post(actions::dispatch_action))
+        .route("/api/open-file", post(editor::open_file))
         .route(
             "/api/sessions",
             get(sessions::list_sessions).post(sessions::create_session),
         )
         // Heartbeat routes (active must be before {id} to match)
         .route("/api/sessions/active", get(heartbeats::get_active_sessions))
+        .route("/api/sessions/compare", get(sessions::compare_sessions))
         .route(
             "/api/sessions/{id}/heartbeat",
             post(heartbeats::post_heartbeat),
@@ -73,6 +188,15 @@ pub fn create_router(state: Arc<AppState>) -> Router {
                 .delete(sessions::stop_session),
         )
         .route("/api/sessions/{id}/launch", post(sessions::launch_session))
+        .route(
+            "/api/sessions/{id}/branch",
+            get(sessions::get_session_branch),
+        )
+        .route("/api/sessions/{id}/git", post(git_ops::run_git_operations))
+        .route(
+            "/api/sessions/{id}/pull-request",
+            post(sessions::create_pull_request),
+        )
         .route("/api/sessions/hive", post(sessions::launch_hive))
         .route("/api/sessions/swarm", post(sessions::launch_swarm))
         .route("/api/sessions/solo", post(sessions::launch_solo))
@@ -82,6 +206,10 @@ pub fn create_router(state: Arc<AppState>) -> Router {
             "/api/sessions/{id}/fusion/select-winner",
             post(sessions::select_fusion_winner),
         )
+        .route(
+            "/api/sessions/{id}/fusion/compose",
+            post(sessions::compose_fusion_result),
+        )
         .route(
             "/api/sessions/{id}/fusion/status",
             get(sessions::get_fusion_status),
@@ -90,6 +218,14 @@ pub fn create_router(state: Arc<AppState>) -> Router {
             "/api/sessions/{id}/fusion/evaluation",
             get(sessions::get_fusion_evaluation),
         )
+        .route(
+            "/api/sessions/{id}/fusion/judges",
+            post(sessions::run_fusion_judges),
+        )
+        .route(
+            "/api/sessions/{id}/fusion/variants/{index}/retry",
+            post(sessions::retry_fusion_variant),
+        )
         .route(
             "/api/sessions/{id}/debate/status",
             get(sessions::get_debate_status),
@@ -106,6 +242,7 @@ pub fn create_router(state: Arc<AppState>) -> Router {
             "/api/sessions/{id}/resolver/launch",
             post(resolver::launch_resolver),
         )
+        .route("/api/sessions/{id}/reviews", get(reviews::list_reviews))
         .route("/api/sessions/{id}/stop", post(sessions::stop_session))
         .route("/api/sessions/{id}/close", post(sessions::close_session))
         .route(
@@ -115,6 +252,10 @@ pub fn create_router(state: Arc<AppState>) -> Router {
         // Worker routes
         .route("/api/sessions/{id}/workers", get(workers::list_workers))
         .route("/api/sessions/{id}/workers", post(workers::add_worker))
+        .route(
+            "/api/sessions/{id}/tasks/{worker}/status",
+            post(workers::report_task_status),
+        )
         // Read-only session artifact browser
         .route(
             "/api/sessions/{id}/files",
@@ -124,6 +265,15 @@ pub fn create_router(state: Arc<AppState>) -> Router {
             "/api/sessions/{id}/files/content",
             get(session_files::read_session_file),
         )
+        // Recorded orchestrator state, read-only (see `session::snapshots`)
+        .route(
+            "/api/sessions/{id}/snapshots",
+            get(snapshots::list_snapshots),
+        )
+        .route(
+            "/api/sessions/{id}/snapshots/{at}",
+            get(snapshots::get_snapshot),
+        )
         // Durable run-queue snapshot (#126)
         .route("/api/sessions/{id}/queue", get(queue::get_queue))
         // Evaluator routes
@@ -181,6 +331,14 @@ pub fn create_router(state: Arc<AppState>) -> Router {
             "/api/sessions/{id}/agents/{aid}/input",
             post(agents::send_agent_input),
         )
+        .route(
+            "/api/sessions/{id}/agents/{aid}/chat",
+            post(agents::chat_with_agent),
+        )
+        .route(
+            "/api/sessions/{id}/agents/{aid}/quarantine",
+            post(agents::quarantine_agent).delete(agents::release_agent),
+        )
         .route(
             "/api/sessions/{id}/cells/{cid}/artifacts",
             get(artifacts::list_artifacts).post(artifacts::post_artifact),
@@ -193,6 +351,20 @@ pub fn create_router(state: Arc<AppState>) -> Router {
             "/api/templates/{id}",
             get(templates::get_template).delete(templates::delete_template),
         )
+        .route(
+            "/api/templates/{id}/variables",
+            get(templates::get_template_variables),
+        )
+        .route(
+            "/api/templates/prompts",
+            get(templates::list_prompt_templates),
+        )
+        .route(
+            "/api/templates/prompts/{name}",
+            get(templates::get_prompt_template)
+                .put(templates::save_prompt_template)
+                .delete(templates::delete_prompt_template),
+        )
         // Learning routes (legacy - work when single project active)
         .route("/api/learnings", get(learnings::list_learnings))
         .route("/api/learnings", post(learnings::submit_learning))
@@ -226,9 +398,54 @@ pub fn create_router(state: Arc<AppState>) -> Router {
             "/api/sessions/{id}/conversations/{agent}/append",
             post(conversations::append_conversation),
         )
+        // Approval gate routes
+        .route(
+            "/api/sessions/{id}/approvals",
+            get(approvals::list_approvals).post(approvals::request_approval),
+        )
+        // Operator question routes
+        .route(
+            "/api/sessions/{id}/questions",
+            get(questions::list_questions).post(questions::ask_operator),
+        )
+        .route(
+            "/api/sessions/{id}/questions/{question_id}",
+            get(questions::get_question),
+        )
         // Event routes
         .route("/api/sessions/{id}/events", get(events::get_events))
         .route("/api/sessions/{id}/stream", get(events::stream_events))
+        .route(
+            "/api/sessions/{id}/output/stream",
+            get(events::stream_agent_output),
+        )
+        .route(
+            "/api/output-streams/{stream_id}",
+            post(events::update_output_stream),
+        )
+        // Coordination log, read whole or from a cursor
+        .route(
+            "/api/sessions/{id}/coordination",
+            get(sessions::get_coordination_log),
+        )
+        // Coordination log analytics, updated as messages are appended
+        .route(
+            "/api/sessions/{id}/coordination/stats",
+            get(sessions::get_coordination_stats),
+        )
+        .route(
+            "/api/sessions/{id}/api-usage",
+            get(sessions::get_api_usage),
+        )
+        // Read-only HTML view of the coordination log for browsers
+        .route(
+            "/api/sessions/{id}/coordination/view",
+            get(coordination_view::view_coordination_log),
+        )
+        .route(
+            "/api/sessions/{id}/durations",
+            get(sessions::get_session_durations),
+        )
         // Run journal + ledger (#125): per-step status for a resumable run
         .route(
             "/api/sessions/{id}/run-journal",
@@ -259,7 +476,47 @@ pub fn create_router(state: Arc<AppState>) -> Router {
             "/api/sessions/{id}/inject/evaluator",
             post(inject::evaluator_inject),
         )
+        // TASK delivery acknowledgements
+        .route(
+            "/api/sessions/{id}/coordination/progress",
+            post(inject::post_progress),
+        )
+        .route(
+            "/api/sessions/{id}/coordination/unacknowledged",
+            get(inject::list_unacknowledged),
+        )
+        // Files coordination messages refer to with `[ATTACH <id>]`
+        .route(
+            "/api/sessions/{id}/coordination/attachments",
+            get(attachments::list_attachments)
+                .post(attachments::upload_attachment)
+                // Room for the multipart framing around a maximum-size file.
+                .layer(DefaultBodyLimit::max(MAX_ATTACHMENT_BYTES + 64 * 1024)),
+        )
+        .route(
+            "/api/sessions/{id}/coordination/attachments/{attachment_id}",
+            get(attachments::get_attachment),
+        )
+        // Shared values too long for a coordination message
+        .route("/api/sessions/{id}/scratchpad", get(scratchpad::list_entries))
+        .route(
+            "/api/sessions/{id}/scratchpad/{key}",
+            get(scratchpad::get_entry).put(scratchpad::put_entry),
+        )
+        .layer(middleware::from_fn_with_state(
+            Arc::clone(&state),
+            require_session_token,
+        ))
+        .layer(middleware::from_fn_with_state(
+            Arc::clone(&state),
+            log_api_usage,
+        ))
         .layer(cors)
         .layer(middleware::from_fn(reject_disallowed_browser_origin))
-        .with_state(state)
+        .with_state(Arc::clone(&state));
+
+    // Slugs are resolved around the router rather than as a route layer, which only
+    // runs after the path parameters have been extracted.
+    Router::new()
+        .fallback_service(middleware::from_fn_with_state(state, resolve_session_slug).layer(api))
 }
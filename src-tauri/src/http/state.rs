@@ -5,21 +5,28 @@ use tokio::sync::RwLock;
 
 use crate::actions::render::envelope_for_content;
 use crate::actions::ActionRegistry;
-use crate::coordination::{InjectionManager, QueueManager};
+use crate::coordination::{ApprovalGate, ApprovalRequest, InjectionManager, QueueManager};
 use crate::domain::event::{Event, EventType, Severity};
-use crate::events::EventBus;
+use crate::events::{EventBus, OutputStreams};
 use crate::pty::PtyManager;
 use crate::session::SessionController;
 use crate::storage::ConversationMessage;
-use crate::storage::{AppConfig, ApplicationStateDb, SessionStorage};
+use crate::storage::{AppConfig, ApplicationStateDb, SessionStorage, Storage};
 
 #[allow(dead_code)]
 pub struct AppState {
     pub config: Arc<RwLock<AppConfig>>,
     pub pty_manager: Arc<PLRwLock<PtyManager>>,
+    /// Every request-time controller method takes `&self` and locks its own sessions
+    /// map only briefly, so handlers take `read()` here. `write()` is reserved for
+    /// startup wiring: a queued writer would stall every list/get behind in-flight
+    /// launches that are busy spawning PTYs.
     pub session_controller: Arc<PLRwLock<SessionController>>,
     pub injection_manager: Arc<PLRwLock<InjectionManager>>,
     pub storage: Arc<SessionStorage>,
+    /// Sessions, learnings, coordination log and config. The same `SessionStorage`
+    /// unless replaced with [`AppState::with_backend`].
+    pub backend: Arc<dyn Storage>,
     pub event_bus: Arc<EventBus>,
     pub app_state_db: Arc<ApplicationStateDb>,
     /// Durable sub-agent run queue (#126). The `agent_run_queue` table is the source of
@@ -31,6 +38,13 @@ pub struct AppState {
     /// exists and then have it attached once (avoids a construction-order cycle:
     /// the registry's actions reach back into `AppState` via `ActionContext`).
     pub registry: std::sync::OnceLock<Arc<ActionRegistry>>,
+    /// Agent requests parked until the operator approves them.
+    pub approvals: Arc<ApprovalGate>,
+    /// Open per-agent output streams and the agents each one follows.
+    pub output_streams: Arc<OutputStreams>,
+    /// Secret the app's own UI sends in `UI_TOKEN_HEADER`. It is handed to the webview
+    /// over Tauri IPC only, so no other local process can present it.
+    pub ui_api_token: String,
 }
 
 impl AppState {
@@ -50,15 +64,32 @@ impl AppState {
             pty_manager,
             session_controller,
             injection_manager,
+            backend: storage.clone(),
             storage,
             event_bus,
             app_state_db,
             queue_manager,
             app_handle,
             registry: std::sync::OnceLock::new(),
+            approvals: Arc::new(ApprovalGate::new()),
+            output_streams: Arc::new(OutputStreams::default()),
+            ui_api_token: uuid::Uuid::new_v4().simple().to_string(),
         }
     }
 
+    /// Serve sessions, learnings, coordination log and config from `backend`
+    /// instead of the filesystem `SessionStorage`.
+    pub fn with_backend(mut self, backend: Arc<dyn Storage>) -> Self {
+        self.backend = backend;
+        self
+    }
+
+    /// Use `token` as the UI's API token instead of a random one.
+    pub fn with_ui_api_token(mut self, token: impl Into<String>) -> Self {
+        self.ui_api_token = token.into();
+        self
+    }
+
     /// Attach the action registry. Idempotent — the first set wins.
     pub fn set_registry(&self, registry: Arc<ActionRegistry>) {
         let _ = self.registry.set(registry);
@@ -72,6 +103,13 @@ impl AppState {
             .expect("ActionRegistry not attached to AppState")
     }
 
+    /// Tell the UI a request is parked waiting for the operator.
+    pub fn emit_approval_required(&self, request: &ApprovalRequest) {
+        if let Some(app_handle) = self.app_handle.as_ref() {
+            let _ = app_handle.emit("approval-required", request);
+        }
+    }
+
     pub async fn emit_conversation_message(
         &self,
         session_id: &str,
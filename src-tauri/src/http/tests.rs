@@ -2,13 +2,14 @@ use crate::coordination::InjectionManager;
 use crate::coordination::{PeerMessageRecord, StateManager};
 use crate::domain::WorkspaceStrategy;
 use crate::events::EventBus;
+use crate::http::handlers::UI_TOKEN_HEADER;
 use crate::http::routes::create_router;
 use crate::http::state::AppState;
 use crate::pty::PtyManager;
 use crate::pty::{AgentConfig, AgentRole, AgentStatus};
 use crate::session::{
     AgentInfo, AuthStrategy, Session, SessionController, SessionState, SessionType,
-    DEFAULT_MAX_QA_ITERATIONS,
+    AGENT_ID_HEADER, DEFAULT_MAX_QA_ITERATIONS, SESSION_TOKEN_HEADER,
 };
 use crate::storage::{ConversationMessage, PersistedSession, SessionStorage, SessionTypeInfo};
 use axum::{
@@ -25,6 +26,9 @@ use tower::ServiceExt;
 
 static KNOWLEDGE_ENV_LOCK: Mutex<()> = Mutex::new(());
 
+/// The UI token of apps built by the `setup_test_app_with_controller*` helpers.
+const TEST_UI_TOKEN: &str = "test-ui-token";
+
 struct ScopedEnvironmentVariable {
     key: &'static str,
     previous: Option<OsString>,
@@ -146,17 +150,20 @@ async fn setup_test_app_with_controller_at(
         queue_repo,
         event_bus.clone(),
     ));
-    let state = Arc::new(AppState::new(
-        config,
-        pty_manager,
-        session_controller.clone(),
-        injection_manager,
-        storage.clone(),
-        event_bus,
-        app_state_db,
-        queue_manager,
-        None,
-    ));
+    let state = Arc::new(
+        AppState::new(
+            config,
+            pty_manager,
+            session_controller.clone(),
+            injection_manager,
+            storage.clone(),
+            event_bus,
+            app_state_db,
+            queue_manager,
+            None,
+        )
+        .with_ui_api_token(TEST_UI_TOKEN),
+    );
     state.set_registry(Arc::new(crate::actions::build_registry()));
 
     (create_router(state), session_controller, storage)
@@ -214,29 +221,41 @@ async fn setup_test_app_with_controller() -> (axum::Router, Arc<RwLock<SessionCo
         queue_repo,
         event_bus.clone(),
     ));
-    let state = Arc::new(AppState::new(
-        config,
-        pty_manager,
-        session_controller.clone(),
-        injection_manager,
-        storage,
-        event_bus,
-        app_state_db,
-        queue_manager,
-        None,
-    ));
+    let state = Arc::new(
+        AppState::new(
+            config,
+            pty_manager,
+            session_controller.clone(),
+            injection_manager,
+            storage,
+            event_bus,
+            app_state_db,
+            queue_manager,
+            None,
+        )
+        .with_ui_api_token(TEST_UI_TOKEN),
+    );
     state.set_registry(Arc::new(crate::actions::build_registry()));
 
     (create_router(state), session_controller)
 }
 
-fn run_git_for_test(repo_path: &Path, args: &[&str]) {
-    let status = Command::new("git")
+/// Run git in `repo_path`, failing the test when it fails. Returns the trimmed stdout.
+pub(crate) fn run_git_for_test(repo_path: &Path, args: &[&str]) -> String {
+    let output = Command::new("git")
         .args(args)
         .current_dir(repo_path)
-        .status()
+        .output()
         .expect("run git command");
-    assert!(status.success(), "git {:?} should succeed", args);
+    assert!(output.status.success(), "git {:?} should succeed", args);
+    String::from_utf8_lossy(&output.stdout).trim().to_string()
+}
+
+/// An empty repository on `main` that can be committed to.
+pub(crate) fn init_git_repo_for_test(repo_path: &Path) {
+    run_git_for_test(repo_path, &["init", "-q", "-b", "main"]);
+    run_git_for_test(repo_path, &["config", "user.email", "test@example.com"]);
+    run_git_for_test(repo_path, &["config", "user.name", "Test"]);
 }
 
 fn init_git_repo_for_launch_fixture(repo_path: &Path) {
@@ -274,6 +293,8 @@ fn make_test_session(id: &str, project_path: &str) -> Session {
         worktree_branch: None,
         no_git: false,
         resume_report: None,
+        pull_request_url: None,
+        spawn_quota: crate::session::SpawnQuota::default(),
     }
 }
 
@@ -292,6 +313,7 @@ fn make_test_session_with_agents(id: &str, project_path: &str, agent_ids: &[&str
             parent_id: None,
             commit_sha: None,
             base_commit_sha: None,
+            exit_info: None,
         })
         .collect();
     let now = chrono::Utc::now();
@@ -319,6 +341,8 @@ fn make_test_session_with_agents(id: &str, project_path: &str, agent_ids: &[&str
         worktree_branch: None,
         no_git: false,
         resume_report: None,
+        pull_request_url: None,
+        spawn_quota: crate::session::SpawnQuota::default(),
     }
 }
 
@@ -342,6 +366,7 @@ fn make_test_session_for_completion(
             parent_id: None,
             commit_sha: None,
             base_commit_sha: None,
+            exit_info: None,
         });
     } else {
         session.session_type = SessionType::Fusion {
@@ -467,6 +492,50 @@ async fn test_cli_health_lists_every_supported_cli_with_stable_schema() {
     }
 }
 
+#[tokio::test]
+async fn test_preflight_reports_every_launch_cli_and_gh() {
+    let body = serde_json::json!({
+        "mode": "debate",
+        "config": {
+            "project_path": "/tmp/project",
+            "debaters": [
+                { "name": "pro", "cli": "codex" },
+                { "name": "con", "cli": "droid" },
+            ],
+            "topic": "Tabs or spaces",
+            "judge_config": { "cli": "codex" },
+            "default_model": null,
+        },
+    });
+    let response = setup_test_app()
+        .await
+        .oneshot(
+            Request::builder()
+                .method("POST")
+                .uri("/api/preflight")
+                .header("content-type", "application/json")
+                .body(Body::from(body.to_string()))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+    let clis: Vec<&str> = json["clis"]
+        .as_array()
+        .expect("clis should be an array")
+        .iter()
+        .map(|health| health["cli"].as_str().expect("cli name"))
+        .collect();
+    assert_eq!(clis, vec!["codex", "droid", "gh"]);
+    assert!(json["blockers"].is_array());
+    assert!(json["warnings"].is_array());
+}
+
 async fn setup_session_files_fixture(
     session_id: &str,
 ) -> (
@@ -708,6 +777,113 @@ async fn test_session_files_rejects_binary_content() {
     assert_eq!(response.status(), StatusCode::UNSUPPORTED_MEDIA_TYPE);
 }
 
+#[tokio::test]
+async fn test_reviews_endpoint_returns_validated_findings() {
+    let session_id = "session-reviews";
+    let (_storage_dir, _project_dir, app, _storage, session_root) =
+        setup_session_files_fixture(session_id).await;
+    std::fs::create_dir_all(session_root.join("reviews")).unwrap();
+    std::fs::write(
+        session_root.join("reviews").join("worker-3.json"),
+        r#"[
+            {"file": "src/lib.rs", "severity": "minor", "comment": "Unused import"},
+            {"file": "src/lib.rs", "line": 7, "severity": "critical", "comment": "Panics"},
+            {"file": "/etc/passwd", "line": 1, "severity": "major", "comment": "Outside"}
+        ]"#,
+    )
+    .unwrap();
+
+    let response = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .uri(format!("/api/sessions/{session_id}/reviews"))
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let json = read_json_body(response).await;
+    let reviews = json.as_array().unwrap();
+    assert_eq!(reviews.len(), 1);
+    assert_eq!(reviews[0]["worker"], 3);
+    let findings = reviews[0]["findings"].as_array().unwrap();
+    assert_eq!(findings.len(), 2);
+    assert_eq!(findings[0]["severity"], "critical");
+    assert_eq!(findings[0]["line"], 7);
+    assert_eq!(findings[1]["line"], serde_json::Value::Null);
+    assert_eq!(reviews[0]["errors"].as_array().unwrap().len(), 1);
+
+    let missing = app
+        .oneshot(
+            Request::builder()
+                .uri("/api/sessions/no-such-session/reviews")
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(missing.status(), StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn test_worker_reports_task_status_through_the_api() {
+    let (app, controller) = setup_test_app_with_controller().await;
+    let temp_dir = tempfile::tempdir().unwrap();
+    let session_id = "task-status";
+    controller
+        .read()
+        .insert_test_session(make_test_session_with_agents(
+            session_id,
+            temp_dir.path().to_str().unwrap(),
+            &["task-status-worker-1"],
+        ));
+    let token = controller.read().session_api_token(session_id);
+    let task_file = temp_dir
+        .path()
+        .join(".hive-manager/worktrees/task-status/worker-1/.hive-manager/tasks")
+        .join("worker-1-task.md");
+    std::fs::create_dir_all(task_file.parent().unwrap()).unwrap();
+    std::fs::write(&task_file, "# Task\n\n## Status: ACTIVE\n").unwrap();
+
+    let report = |worker: u8, body: &'static str| {
+        Request::builder()
+            .method("POST")
+            .uri(format!("/api/sessions/{session_id}/tasks/{worker}/status"))
+            .header("Content-Type", "application/json")
+            .header(SESSION_TOKEN_HEADER, token.as_str())
+            .body(Body::from(body))
+            .unwrap()
+    };
+
+    let response = app
+        .clone()
+        .oneshot(report(1, r#"{"status":"completed","summary":"Added the endpoint"}"#))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let json = read_json_body(response).await;
+    assert_eq!(json["status"], "COMPLETED");
+    assert_eq!(json["worker_id"], "task-status-worker-1");
+    let content = std::fs::read_to_string(&task_file).unwrap();
+    assert!(content.starts_with("# Task\n\n## Status: COMPLETED\n\n## Result ("));
+    assert!(content.trim_end().ends_with("Added the endpoint"));
+
+    let invalid = app
+        .clone()
+        .oneshot(report(1, r#"{"status":"ACTIVE","summary":"Restart"}"#))
+        .await
+        .unwrap();
+    assert_eq!(invalid.status(), StatusCode::BAD_REQUEST);
+
+    let unknown = app
+        .oneshot(report(2, r#"{"status":"BLOCKED","summary":"No access"}"#))
+        .await
+        .unwrap();
+    assert_eq!(unknown.status(), StatusCode::NOT_FOUND);
+}
+
 #[tokio::test]
 async fn test_list_sessions_empty() {
     let app = setup_test_app().await;
@@ -824,6 +1000,8 @@ async fn test_patch_session_omitted_field_preserves_existing_value() {
         worktree_branch: None,
         no_git: false,
         resume_report: None,
+        pull_request_url: None,
+        spawn_quota: crate::session::SpawnQuota::default(),
     });
 
     let body = serde_json::json!({
@@ -882,6 +1060,8 @@ async fn test_patch_session_null_clears_field() {
         worktree_branch: None,
         no_git: false,
         resume_report: None,
+        pull_request_url: None,
+        spawn_quota: crate::session::SpawnQuota::default(),
     });
 
     let body = serde_json::json!({
@@ -1045,6 +1225,8 @@ async fn test_patch_session_updates_persisted_session_not_loaded_in_memory() {
         worktree_path: None,
         worktree_branch: None,
         no_git: false,
+        pull_request_url: None,
+        spawn_quota: crate::session::SpawnQuota::default(),
     };
     storage.save_session(&persisted).unwrap();
 
@@ -1995,6 +2177,99 @@ async fn test_session_scoped_list_learnings_for_valid_session() {
     let _ = std::fs::remove_dir_all(&temp_dir);
 }
 
+#[tokio::test]
+async fn test_session_scoped_learnings_use_the_configured_storage_backend() {
+    let storage_dir = TempDir::new().unwrap();
+    let storage =
+        Arc::new(SessionStorage::new_with_base(storage_dir.path().to_path_buf()).unwrap());
+    let backend = Arc::new(crate::storage::MemoryStorage::new());
+    let persisted: PersistedSession = serde_json::from_str(
+        r#"{
+            "id": "memory-session",
+            "session_type": {"Hive": {"worker_count": 1}},
+            "project_path": "/tmp/memory",
+            "created_at": "2026-01-01T00:00:00Z",
+            "agents": [],
+            "state": "Completed"
+        }"#,
+    )
+    .unwrap();
+    crate::storage::Storage::save_session(backend.as_ref(), &persisted).unwrap();
+
+    let config = Arc::new(tokio::sync::RwLock::new(storage.load_config().unwrap()));
+    let pty_manager = Arc::new(RwLock::new(PtyManager::new()));
+    let session_controller = Arc::new(RwLock::new(SessionController::new(pty_manager.clone())));
+    let injection_manager = Arc::new(RwLock::new(InjectionManager::new(
+        pty_manager.clone(),
+        SessionStorage::new_with_base(storage_dir.path().to_path_buf()).unwrap(),
+    )));
+    let event_bus = EventBus::new(storage.base_dir().clone());
+    let app_state_db = Arc::new(crate::storage::ApplicationStateDb::open_in_memory().unwrap());
+    let queue_repo = Arc::new(crate::storage::QueueRepo::new(app_state_db.clone()));
+    queue_repo.ensure_schema().unwrap();
+    let queue_manager = Arc::new(crate::coordination::QueueManager::new(
+        queue_repo,
+        event_bus.clone(),
+    ));
+    let state = Arc::new(
+        AppState::new(
+            config,
+            pty_manager,
+            session_controller,
+            injection_manager,
+            storage.clone(),
+            event_bus,
+            app_state_db,
+            queue_manager,
+            None,
+        )
+        .with_backend(backend.clone()),
+    );
+    state.set_registry(Arc::new(crate::actions::build_registry()));
+    let app = create_router(state);
+
+    let response = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .method("POST")
+                .uri("/api/sessions/memory-session/learnings")
+                .header("content-type", "application/json")
+                .body(Body::from(
+                    serde_json::json!({
+                        "session": "memory-session",
+                        "task": "Extract storage trait",
+                        "outcome": "success",
+                        "insight": "Handlers only need the Storage contract",
+                    })
+                    .to_string(),
+                ))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::CREATED);
+
+    let response = app
+        .oneshot(
+            Request::builder()
+                .uri("/api/sessions/memory-session/learnings")
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let json = read_json_body(response).await;
+    assert_eq!(json["count"], 1);
+
+    // Nothing reached the filesystem storage.
+    assert!(storage
+        .read_learnings_session("memory-session")
+        .unwrap()
+        .is_empty());
+}
+
 #[tokio::test]
 async fn test_session_scoped_project_dna_for_valid_session() {
     let (app, controller) = setup_test_app_with_controller().await;
@@ -2233,6 +2508,77 @@ async fn test_session_scoped_submit_learning_success() {
     let _ = std::fs::remove_dir_all(&temp_dir);
 }
 
+#[tokio::test]
+async fn test_judge_shaped_learning_is_accepted_and_missing_fields_are_named() {
+    let storage_dir = TempDir::new().unwrap();
+    let project_dir = TempDir::new().unwrap();
+    let session_id = "session-judge-learning";
+    let (app, controller, storage) =
+        setup_test_app_with_controller_at(storage_dir.path().to_path_buf()).await;
+
+    controller.read().insert_test_session(make_test_session(
+        session_id,
+        project_dir.path().to_str().unwrap(),
+    ));
+
+    let submit = |body: serde_json::Value| {
+        Request::builder()
+            .method("POST")
+            .uri(format!("/api/sessions/{}/learnings", session_id))
+            .header("content-type", "application/json")
+            .body(Body::from(body.to_string()))
+            .unwrap()
+    };
+
+    let response = app
+        .clone()
+        .oneshot(submit(serde_json::json!({
+            "content": "Variant B kept the parser free of IO",
+            "category": "Architecture",
+            "confidence": 0.7,
+            "source": "fusion-judge"
+        })))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::CREATED);
+
+    let learnings = storage
+        .read_learnings_session(session_id)
+        .expect("read session learnings");
+    assert_eq!(learnings.len(), 1);
+    assert_eq!(learnings[0].session, session_id);
+    assert_eq!(learnings[0].insight, "Variant B kept the parser free of IO");
+    assert_eq!(learnings[0].category.as_deref(), Some("architecture"));
+    assert_eq!(learnings[0].confidence, Some(0.7));
+    assert_eq!(learnings[0].source_agent.as_deref(), Some("fusion-judge"));
+
+    let response = app
+        .clone()
+        .oneshot(submit(serde_json::json!({ "keywords": ["parser"] })))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    let message = String::from_utf8_lossy(&body);
+    assert!(
+        message.contains("insight (or content), task, outcome"),
+        "{}",
+        message
+    );
+
+    let response = app
+        .oneshot(submit(serde_json::json!({
+            "content": "Overconfident",
+            "category": "architecture",
+            "confidence": 1.5
+        })))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+}
+
 #[tokio::test]
 async fn test_pending_jsonl_learning_round_trips_through_session_submit_endpoint() {
     let storage_dir = TempDir::new().unwrap();
@@ -3707,6 +4053,8 @@ async fn test_add_worker_rejects_path_traversal_session_id() {
         "Error should mention invalid session ID: {}",
         error_msg
     );
+    assert_eq!(response_json["code"], "validation_failed");
+    assert_eq!(response_json["retryable"], false);
 }
 
 #[tokio::test]
@@ -4117,6 +4465,7 @@ async fn test_add_qa_worker_valid_request_reaches_controller() {
         parent_id: None,
         commit_sha: None,
         base_commit_sha: None,
+        exit_info: None,
     });
     controller.read().insert_test_session(session);
 
@@ -4173,6 +4522,8 @@ fn test_persisted_session_serializes_default_cli() {
         worktree_path: None,
         worktree_branch: None,
         no_git: false,
+        pull_request_url: None,
+        spawn_quota: crate::session::SpawnQuota::default(),
     };
 
     let json = serde_json::to_string(&session).unwrap();
@@ -4909,16 +5260,16 @@ async fn test_get_fusion_evaluation_not_found() {
 }
 
 #[tokio::test]
-async fn test_select_fusion_winner_not_found() {
+async fn test_run_fusion_judges_not_found() {
     let app = setup_test_app().await;
 
-    let body = serde_json::json!({ "variant": "variant-a" });
+    let body = serde_json::json!({ "judges": [{ "cli": "codex", "model": "gpt-5.5" }] });
 
     let response = app
         .oneshot(
             Request::builder()
                 .method("POST")
-                .uri("/api/sessions/nonexistent/fusion/select-winner")
+                .uri("/api/sessions/nonexistent/fusion/judges")
                 .header("content-type", "application/json")
                 .body(Body::from(serde_json::to_string(&body).unwrap()))
                 .unwrap(),
@@ -4926,21 +5277,20 @@ async fn test_select_fusion_winner_not_found() {
         .await
         .unwrap();
 
-    // Should be 404 or 500 (session not found), not 200
-    assert_ne!(response.status(), StatusCode::OK);
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
 }
 
 #[tokio::test]
-async fn test_select_fusion_winner_empty_variant() {
+async fn test_run_fusion_judges_rejects_invalid_cli() {
     let app = setup_test_app().await;
 
-    let body = serde_json::json!({ "variant": "  " });
+    let body = serde_json::json!({ "judges": [{ "cli": "gemini" }] });
 
     let response = app
         .oneshot(
             Request::builder()
                 .method("POST")
-                .uri("/api/sessions/nonexistent/fusion/select-winner")
+                .uri("/api/sessions/nonexistent/fusion/judges")
                 .header("content-type", "application/json")
                 .body(Body::from(serde_json::to_string(&body).unwrap()))
                 .unwrap(),
@@ -4951,31 +5301,84 @@ async fn test_select_fusion_winner_empty_variant() {
     assert_eq!(response.status(), StatusCode::BAD_REQUEST);
 }
 
+fn git_output_for_test(repo_path: &Path, args: &[&str]) -> String {
+    let output = Command::new("git")
+        .args(args)
+        .current_dir(repo_path)
+        .output()
+        .expect("run git command");
+    assert!(output.status.success(), "git {:?} should succeed", args);
+    String::from_utf8_lossy(&output.stdout).trim().to_string()
+}
+
 #[tokio::test]
-async fn test_fusion_status_path_traversal() {
-    let app = setup_test_app().await;
+async fn test_get_session_branch_reports_commits_missing_from_branch() {
+    let (app, controller) = setup_test_app_with_controller().await;
+    let repo = TempDir::new().expect("temp repo");
+    init_git_repo_for_launch_fixture(repo.path());
+
+    run_git_for_test(repo.path(), &["branch", "feat/session-branch"]);
+    run_git_for_test(repo.path(), &["checkout", "-q", "-b", "hive/side/worker-2"]);
+    run_git_for_test(
+        repo.path(),
+        &[
+            "commit",
+            "-q",
+            "--allow-empty",
+            "-m",
+            "unmerged worker commit",
+        ],
+    );
+    let landed = git_output_for_test(repo.path(), &["rev-parse", "feat/session-branch"]);
+    let unmerged = git_output_for_test(repo.path(), &["rev-parse", "HEAD"]);
+
+    let mut session = make_test_session_with_agents(
+        "session-branch-check",
+        repo.path().to_string_lossy().as_ref(),
+        &[
+            "session-branch-check-worker-1",
+            "session-branch-check-worker-2",
+        ],
+    );
+    session.worktree_branch = Some("feat/session-branch".to_string());
+    session.agents[0].commit_sha = Some(landed.clone());
+    session.agents[1].commit_sha = Some(unmerged.clone());
+    controller.read().insert_test_session(session);
 
     let response = app
         .oneshot(
             Request::builder()
-                .uri("/api/sessions/..evil/fusion/status")
+                .uri("/api/sessions/session-branch-check/branch")
                 .body(Body::empty())
                 .unwrap(),
         )
         .await
         .unwrap();
 
-    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    assert_eq!(response.status(), StatusCode::OK);
+    let body_bytes = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    let json: serde_json::Value = serde_json::from_slice(&body_bytes).unwrap();
+    assert_eq!(json["branch"], "feat/session-branch");
+    assert_eq!(json["all_commits_on_branch"], false);
+    assert_eq!(json["commits"][0]["commit_sha"], landed.as_str());
+    assert_eq!(json["commits"][0]["on_branch"], true);
+    assert_eq!(json["commits"][1]["commit_sha"], unmerged.as_str());
+    assert_eq!(json["commits"][1]["on_branch"], false);
 }
 
 #[tokio::test]
-async fn test_fusion_evaluation_path_traversal() {
-    let app = setup_test_app().await;
+async fn test_get_session_branch_requires_session_branch() {
+    let (app, controller) = setup_test_app_with_controller().await;
+    controller
+        .read()
+        .insert_test_session(make_test_session("session-no-branch", "/tmp/no-branch"));
 
     let response = app
         .oneshot(
             Request::builder()
-                .uri("/api/sessions/..evil/fusion/evaluation")
+                .uri("/api/sessions/session-no-branch/branch")
                 .body(Body::empty())
                 .unwrap(),
         )
@@ -4986,50 +5389,213 @@ async fn test_fusion_evaluation_path_traversal() {
 }
 
 #[tokio::test]
-async fn test_select_winner_path_traversal() {
+async fn test_create_pull_request_not_found() {
     let app = setup_test_app().await;
 
-    let body = serde_json::json!({ "variant": "v1" });
-
     let response = app
         .oneshot(
             Request::builder()
                 .method("POST")
-                .uri("/api/sessions/..evil/fusion/select-winner")
+                .uri("/api/sessions/nonexistent/pull-request")
                 .header("content-type", "application/json")
-                .body(Body::from(serde_json::to_string(&body).unwrap()))
+                .body(Body::from("{}"))
                 .unwrap(),
         )
         .await
         .unwrap();
 
-    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
 }
 
-// --- Conversations API tests ---
-
 #[tokio::test]
-async fn test_append_conversation_and_verify_file_content() {
+async fn test_create_pull_request_requires_session_branch() {
     let (app, controller) = setup_test_app_with_controller().await;
-    let storage = SessionStorage::new().unwrap();
-    let session_id = format!("conv-append-{}", uuid::Uuid::new_v4());
-
-    let temp_dir = std::env::temp_dir().join(format!("hive-test-{}", session_id));
-    let _ = std::fs::create_dir_all(&temp_dir);
     controller
         .read()
-        .insert_test_session(make_test_session(&session_id, temp_dir.to_str().unwrap()));
+        .insert_test_session(make_test_session("session-no-branch", "/tmp/no-branch"));
 
-    let body = serde_json::json!({
-        "from": "worker-1",
-        "content": "First conversation message"
-    });
     let response = app
         .oneshot(
             Request::builder()
                 .method("POST")
-                .uri(format!(
-                    "/api/sessions/{}/conversations/worker-1/append",
+                .uri("/api/sessions/session-no-branch/pull-request")
+                .header("content-type", "application/json")
+                .body(Body::from(r#"{"title":"Add login"}"#))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+}
+
+#[tokio::test]
+async fn test_retry_fusion_variant_not_found() {
+    let app = setup_test_app().await;
+
+    let response = app
+        .oneshot(
+            Request::builder()
+                .method("POST")
+                .uri("/api/sessions/nonexistent/fusion/variants/1/retry")
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn test_select_fusion_winner_not_found() {
+    let app = setup_test_app().await;
+
+    let body = serde_json::json!({ "variant": "variant-a" });
+
+    let response = app
+        .oneshot(
+            Request::builder()
+                .method("POST")
+                .uri("/api/sessions/nonexistent/fusion/select-winner")
+                .header("content-type", "application/json")
+                .body(Body::from(serde_json::to_string(&body).unwrap()))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+
+    // Should be 404 or 500 (session not found), not 200
+    assert_ne!(response.status(), StatusCode::OK);
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_select_fusion_winner_does_not_wait_on_in_flight_launch() {
+    let (app, controller) = setup_test_app_with_controller().await;
+    // Stands in for a launch that holds the controller while it spawns PTYs.
+    let launch = controller.read();
+
+    let body = serde_json::json!({ "variant": "variant-a" });
+    let request = tokio::spawn(
+        app.oneshot(
+            Request::builder()
+                .method("POST")
+                .uri("/api/sessions/nonexistent/fusion/select-winner")
+                .header("content-type", "application/json")
+                .body(Body::from(serde_json::to_string(&body).unwrap()))
+                .unwrap(),
+        ),
+    );
+    let response = tokio::time::timeout(std::time::Duration::from_secs(5), request)
+        .await
+        .expect("request should not queue behind the launch")
+        .unwrap()
+        .unwrap();
+    drop(launch);
+
+    assert_ne!(response.status(), StatusCode::OK);
+}
+
+#[tokio::test]
+async fn test_select_fusion_winner_empty_variant() {
+    let app = setup_test_app().await;
+
+    let body = serde_json::json!({ "variant": "  " });
+
+    let response = app
+        .oneshot(
+            Request::builder()
+                .method("POST")
+                .uri("/api/sessions/nonexistent/fusion/select-winner")
+                .header("content-type", "application/json")
+                .body(Body::from(serde_json::to_string(&body).unwrap()))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+}
+
+#[tokio::test]
+async fn test_fusion_status_path_traversal() {
+    let app = setup_test_app().await;
+
+    let response = app
+        .oneshot(
+            Request::builder()
+                .uri("/api/sessions/..evil/fusion/status")
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+}
+
+#[tokio::test]
+async fn test_fusion_evaluation_path_traversal() {
+    let app = setup_test_app().await;
+
+    let response = app
+        .oneshot(
+            Request::builder()
+                .uri("/api/sessions/..evil/fusion/evaluation")
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+}
+
+#[tokio::test]
+async fn test_select_winner_path_traversal() {
+    let app = setup_test_app().await;
+
+    let body = serde_json::json!({ "variant": "v1" });
+
+    let response = app
+        .oneshot(
+            Request::builder()
+                .method("POST")
+                .uri("/api/sessions/..evil/fusion/select-winner")
+                .header("content-type", "application/json")
+                .body(Body::from(serde_json::to_string(&body).unwrap()))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+}
+
+// --- Conversations API tests ---
+
+#[tokio::test]
+async fn test_append_conversation_and_verify_file_content() {
+    let (app, controller) = setup_test_app_with_controller().await;
+    let storage = SessionStorage::new().unwrap();
+    let session_id = format!("conv-append-{}", uuid::Uuid::new_v4());
+
+    let temp_dir = std::env::temp_dir().join(format!("hive-test-{}", session_id));
+    let _ = std::fs::create_dir_all(&temp_dir);
+    controller
+        .read()
+        .insert_test_session(make_test_session(&session_id, temp_dir.to_str().unwrap()));
+
+    let body = serde_json::json!({
+        "from": "worker-1",
+        "content": "First conversation message"
+    });
+    let response = app
+        .oneshot(
+            Request::builder()
+                .method("POST")
+                .uri(format!(
+                    "/api/sessions/{}/conversations/worker-1/append",
                     session_id
                 ))
                 .header("content-type", "application/json")
@@ -5350,6 +5916,7 @@ async fn test_post_verdict_persists_commit_sha_and_rationale() {
         parent_id: None,
         commit_sha: None,
         base_commit_sha: None,
+        exit_info: None,
     });
     controller.write().insert_test_session(session);
 
@@ -5465,6 +6032,7 @@ async fn test_post_verdict_fail_persists_commit_sha_and_failure_state() {
         parent_id: None,
         commit_sha: None,
         base_commit_sha: None,
+        exit_info: None,
     });
     controller.write().insert_test_session(session);
 
@@ -5832,6 +6400,8 @@ async fn test_list_artifacts_uses_persisted_session_fallback() {
             worktree_path: None,
             worktree_branch: None,
             no_git: false,
+            pull_request_url: None,
+            spawn_quota: crate::session::SpawnQuota::default(),
         })
         .unwrap();
     storage
@@ -6181,152 +6751,172 @@ async fn test_template_crud_endpoints() {
 }
 
 #[tokio::test]
-async fn test_send_agent_input_rejects_empty_input() {
-    let (app, controller) = setup_test_app_with_controller().await;
-
-    let temp_dir = std::env::temp_dir().join("hive-test-agent-input");
-    let _ = std::fs::create_dir_all(&temp_dir);
-
-    controller
-        .read()
-        .insert_test_session(make_test_session_with_agents(
-            "session-agent-input",
-            temp_dir.to_str().unwrap(),
-            &["worker-1"],
-        ));
+async fn test_template_variables_endpoint_lists_placeholders() {
+    let app = setup_test_app().await;
 
     let response = app
+        .clone()
         .oneshot(
             Request::builder()
-                .method("POST")
-                .uri("/api/sessions/session-agent-input/agents/worker-1/input")
-                .header("content-type", "application/json")
-                .body(Body::from(r#"{"input":"   "}"#))
+                .uri("/api/templates/roles%2Fbackend/variables")
+                .body(Body::empty())
                 .unwrap(),
         )
         .await
         .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let info = read_json_body(response).await;
+    assert_eq!(info["name"], "roles/backend");
+    let task = info["variables"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|variable| variable["name"] == "task")
+        .expect("backend template uses {{task}}");
+    assert_eq!(task["supplied_by_engine"], true);
 
-    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
-
-    let _ = std::fs::remove_dir_all(&temp_dir);
-}
-
-#[tokio::test]
-async fn test_send_agent_input_rejects_invalid_agent_id() {
-    let app = setup_test_app().await;
-
-    let response = app
+    let list_response = app
+        .clone()
         .oneshot(
             Request::builder()
-                .method("POST")
-                .uri("/api/sessions/session-safe/agents/../bad/input")
-                .header("content-type", "application/json")
-                .body(Body::from(r#"{"input":"hello"}"#))
+                .uri("/api/templates")
+                .body(Body::empty())
                 .unwrap(),
         )
         .await
         .unwrap();
+    let catalog = read_json_body(list_response).await;
+    assert!(catalog["prompt_templates"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .any(|template| template["name"] == "queen-hive"));
 
-    // Path traversal is rejected before handler validation by route matching.
-    assert_eq!(response.status(), StatusCode::NOT_FOUND);
-}
-
-#[tokio::test]
-async fn test_create_session_rejects_invalid_mode() {
-    let app = setup_test_app().await;
-    let temp_dir = std::env::temp_dir().join("hive-test-create-session-invalid-mode");
-    let _ = std::fs::create_dir_all(&temp_dir);
-
-    let body = serde_json::json!({
-        "project_path": temp_dir.to_str().unwrap(),
-        "mode": "solo",
-        "objective": "test objective"
-    });
-
-    let response = app
+    let missing = app
+        .clone()
         .oneshot(
             Request::builder()
-                .method("POST")
-                .uri("/api/sessions")
-                .header("content-type", "application/json")
-                .body(Body::from(serde_json::to_string(&body).unwrap()))
+                .uri("/api/templates/no-such-template/variables")
+                .body(Body::empty())
                 .unwrap(),
         )
         .await
         .unwrap();
+    assert_eq!(missing.status(), StatusCode::NOT_FOUND);
 
-    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
-
-    let _ = std::fs::remove_dir_all(&temp_dir);
+    let invalid = app
+        .oneshot(
+            Request::builder()
+                .uri("/api/templates/..%2Fconfig/variables")
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(invalid.status(), StatusCode::BAD_REQUEST);
 }
 
 #[tokio::test]
-async fn test_launch_session_returns_not_implemented() {
+async fn test_prompt_template_crud_endpoints() {
     let app = setup_test_app().await;
+    let name = format!("test-prompt-{}", uuid::Uuid::new_v4().simple());
+    let uri = format!("/api/templates/prompts/{}", name);
 
-    let response = app
+    let save_response = app
+        .clone()
         .oneshot(
             Request::builder()
-                .method("POST")
-                .uri("/api/sessions/session-launch/launch")
+                .method("PUT")
+                .uri(&uri)
                 .header("content-type", "application/json")
-                .body(Body::from(r#"{}"#))
+                .body(Body::from(
+                    serde_json::json!({ "content": "Review {{branch}} for {{task}}" }).to_string(),
+                ))
                 .unwrap(),
         )
         .await
         .unwrap();
+    assert_eq!(save_response.status(), StatusCode::OK);
+    let saved = read_json_body(save_response).await;
+    assert_eq!(saved["on_disk"], true);
+    assert_eq!(saved["is_builtin"], false);
+    assert_eq!(saved["variables"][0]["name"], "branch");
 
-    assert_eq!(response.status(), StatusCode::INTERNAL_SERVER_ERROR);
-}
-
-// --- Heartbeat endpoint tests ---
-
-#[tokio::test]
-async fn test_post_heartbeat_updates_timestamp() {
-    let (app, controller) = setup_test_app_with_controller().await;
-
-    let temp_dir = std::env::temp_dir().join("hive-test-heartbeat");
-    let _ = std::fs::create_dir_all(&temp_dir);
+    let get_response = app
+        .clone()
+        .oneshot(Request::builder().uri(&uri).body(Body::empty()).unwrap())
+        .await
+        .unwrap();
+    assert_eq!(get_response.status(), StatusCode::OK);
+    assert_eq!(
+        read_json_body(get_response).await["content"],
+        "Review {{branch}} for {{task}}"
+    );
 
-    controller
-        .read()
-        .insert_test_session(make_test_session_with_agents(
-            "session-hb",
-            temp_dir.to_str().unwrap(),
-            &["worker-1"],
-        ));
+    let list_response = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .uri("/api/templates/prompts")
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    let listed = read_json_body(list_response).await;
+    assert!(listed
+        .as_array()
+        .unwrap()
+        .iter()
+        .any(|template| template["name"] == name.as_str()));
 
-    let response = app
+    let empty_response = app
+        .clone()
         .oneshot(
             Request::builder()
-                .method("POST")
-                .uri("/api/sessions/session-hb/heartbeat")
-                .header("Content-Type", "application/json")
-                .body(Body::from(
-                    r#"{"agent_id":"worker-1","status":"working","summary":"3/5 files done"}"#,
-                ))
+                .method("PUT")
+                .uri(&uri)
+                .header("content-type", "application/json")
+                .body(Body::from(r#"{"content":"  "}"#))
                 .unwrap(),
         )
         .await
         .unwrap();
+    assert_eq!(empty_response.status(), StatusCode::BAD_REQUEST);
 
-    assert_eq!(response.status(), StatusCode::OK);
+    for expected in [StatusCode::NO_CONTENT, StatusCode::NOT_FOUND] {
+        let delete_response = app
+            .clone()
+            .oneshot(
+                Request::builder()
+                    .method("DELETE")
+                    .uri(&uri)
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(delete_response.status(), expected);
+    }
 
-    let _ = std::fs::remove_dir_all(&temp_dir);
+    let deleted_response = app
+        .oneshot(Request::builder().uri(&uri).body(Body::empty()).unwrap())
+        .await
+        .unwrap();
+    assert_eq!(deleted_response.status(), StatusCode::NOT_FOUND);
 }
 
 #[tokio::test]
-async fn test_post_heartbeat_rejects_invalid_status() {
+async fn test_send_agent_input_rejects_empty_input() {
     let (app, controller) = setup_test_app_with_controller().await;
 
-    let temp_dir = std::env::temp_dir().join("hive-test-heartbeat-invalid");
+    let temp_dir = std::env::temp_dir().join("hive-test-agent-input");
     let _ = std::fs::create_dir_all(&temp_dir);
 
     controller
         .read()
         .insert_test_session(make_test_session_with_agents(
-            "session-hb-inv",
+            "session-agent-input",
             temp_dir.to_str().unwrap(),
             &["worker-1"],
         ));
@@ -6335,11 +6925,9 @@ async fn test_post_heartbeat_rejects_invalid_status() {
         .oneshot(
             Request::builder()
                 .method("POST")
-                .uri("/api/sessions/session-hb-inv/heartbeat")
-                .header("Content-Type", "application/json")
-                .body(Body::from(
-                    r#"{"agent_id":"worker-1","status":"invalid","summary":"x"}"#,
-                ))
+                .uri("/api/sessions/session-agent-input/agents/worker-1/input")
+                .header("content-type", "application/json")
+                .body(Body::from(r#"{"input":"   "}"#))
                 .unwrap(),
         )
         .await
@@ -6351,353 +6939,453 @@ async fn test_post_heartbeat_rejects_invalid_status() {
 }
 
 #[tokio::test]
-async fn test_completed_heartbeat_is_excluded_from_stall_sweep() {
-    let (app, controller) = setup_test_app_with_controller().await;
+async fn test_send_agent_input_rejects_invalid_agent_id() {
+    let app = setup_test_app().await;
 
-    let temp_dir = std::env::temp_dir().join(format!(
-        "hive-test-heartbeat-completed-{}",
-        uuid::Uuid::new_v4()
-    ));
-    let _ = std::fs::create_dir_all(&temp_dir);
+    let response = app
+        .oneshot(
+            Request::builder()
+                .method("POST")
+                .uri("/api/sessions/session-safe/agents/../bad/input")
+                .header("content-type", "application/json")
+                .body(Body::from(r#"{"input":"hello"}"#))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
 
+    // Path traversal is rejected before handler validation by route matching.
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn test_agent_chat_validates_message_and_agent() {
+    let (app, controller) = setup_test_app_with_controller().await;
+    let temp_dir = tempfile::tempdir().expect("project dir");
     controller
         .read()
         .insert_test_session(make_test_session_with_agents(
-            "session-hb-completed",
-            temp_dir.to_str().unwrap(),
-            &["worker-done", "worker-active"],
+            "session-agent-chat",
+            temp_dir.path().to_str().unwrap(),
+            &["worker-1"],
         ));
 
-    for (agent_id, status) in [
-        ("worker-done", "completed"),
-        ("worker-active", "working"),
-    ] {
-        let response = app
-            .clone()
-            .oneshot(
-                Request::builder()
-                    .method("POST")
-                    .uri("/api/sessions/session-hb-completed/heartbeat")
-                    .header("Content-Type", "application/json")
-                    .body(Body::from(
-                        serde_json::json!({ "agent_id": agent_id, "status": status })
-                            .to_string(),
-                    ))
-                    .unwrap(),
-            )
-            .await
-            .unwrap();
-        assert_eq!(response.status(), StatusCode::OK);
-    }
+    let chat = |agent_id: &str, body: &'static str| {
+        Request::builder()
+            .method("POST")
+            .uri(format!(
+                "/api/sessions/session-agent-chat/agents/{agent_id}/chat"
+            ))
+            .header("content-type", "application/json")
+            .body(Body::from(body))
+            .unwrap()
+    };
 
-    // Heartbeat timestamps have second precision in the stall sweep. Let both
-    // entries age past a zero-second threshold so status is the deciding factor.
-    tokio::time::sleep(std::time::Duration::from_millis(1_100)).await;
-    let stalled = controller
-        .read()
-        .get_stalled_agents("session-hb-completed", std::time::Duration::ZERO);
-    let stalled_ids: Vec<_> = stalled.into_iter().map(|(id, _)| id).collect();
+    let response = app
+        .clone()
+        .oneshot(chat("worker-1", r#"{"message":"  "}"#))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
 
-    assert_eq!(stalled_ids, vec!["worker-active"]);
-    assert!(!stalled_ids.iter().any(|id| id == "worker-done"));
+    let response = app
+        .clone()
+        .oneshot(chat("worker-9", r#"{"message":"hello"}"#))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
 
-    let _ = std::fs::remove_dir_all(&temp_dir);
+    let response = app
+        .oneshot(chat(
+            "worker-1",
+            r#"{"message":"hello","submit_key":"shift_enter"}"#,
+        ))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
 }
 
 #[tokio::test]
-async fn test_get_active_sessions_returns_only_running() {
-    let (app, controller) = setup_test_app_with_controller().await;
-
-    let temp_dir = std::env::temp_dir().join("hive-test-active");
+async fn test_create_session_rejects_invalid_mode() {
+    let app = setup_test_app().await;
+    let temp_dir = std::env::temp_dir().join("hive-test-create-session-invalid-mode");
     let _ = std::fs::create_dir_all(&temp_dir);
 
-    controller
-        .read()
-        .insert_test_session(make_test_session_with_agents(
-            "session-running",
-            temp_dir.to_str().unwrap(),
-            &["worker-1"],
-        ));
+    let body = serde_json::json!({
+        "project_path": temp_dir.to_str().unwrap(),
+        "mode": "solo",
+        "objective": "test objective"
+    });
 
     let response = app
         .oneshot(
             Request::builder()
-                .uri("/api/sessions/active")
-                .body(Body::empty())
+                .method("POST")
+                .uri("/api/sessions")
+                .header("content-type", "application/json")
+                .body(Body::from(serde_json::to_string(&body).unwrap()))
                 .unwrap(),
         )
         .await
         .unwrap();
 
-    assert_eq!(response.status(), StatusCode::OK);
-    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+    let _ = std::fs::remove_dir_all(&temp_dir);
+}
+
+#[tokio::test]
+async fn test_launch_session_returns_not_implemented() {
+    let app = setup_test_app().await;
+
+    let response = app
+        .oneshot(
+            Request::builder()
+                .method("POST")
+                .uri("/api/sessions/session-launch/launch")
+                .header("content-type", "application/json")
+                .body(Body::from(r#"{}"#))
+                .unwrap(),
+        )
         .await
         .unwrap();
-    let response_json: serde_json::Value = serde_json::from_slice(&body).unwrap();
-    let sessions = response_json.get("sessions").unwrap().as_array().unwrap();
-    assert_eq!(sessions.len(), 1);
-    assert_eq!(
-        sessions[0].get("id").unwrap().as_str().unwrap(),
-        "session-running"
-    );
-    let agents = sessions[0].get("agents").unwrap().as_array().unwrap();
-    assert_eq!(agents.len(), 1);
 
-    let _ = std::fs::remove_dir_all(&temp_dir);
+    assert_eq!(response.status(), StatusCode::INTERNAL_SERVER_ERROR);
 }
 
+// --- Heartbeat endpoint tests ---
+
 #[tokio::test]
-async fn test_get_active_sessions_includes_heartbeat_after_post() {
+async fn test_post_heartbeat_updates_timestamp() {
     let (app, controller) = setup_test_app_with_controller().await;
 
-    let temp_dir = std::env::temp_dir().join("hive-test-active-hb");
+    let temp_dir = std::env::temp_dir().join("hive-test-heartbeat");
     let _ = std::fs::create_dir_all(&temp_dir);
 
     controller
         .read()
         .insert_test_session(make_test_session_with_agents(
-            "session-active-hb",
+            "session-hb",
             temp_dir.to_str().unwrap(),
             &["worker-1"],
         ));
 
-    // POST heartbeat first
-    let _ = app
-        .clone()
+    let response = app
         .oneshot(
             Request::builder()
                 .method("POST")
-                .uri("/api/sessions/session-active-hb/heartbeat")
+                .uri("/api/sessions/session-hb/heartbeat")
                 .header("Content-Type", "application/json")
                 .body(Body::from(
-                    r#"{"agent_id":"worker-1","status":"working","summary":"2/3 done"}"#,
+                    r#"{"agent_id":"worker-1","status":"working","summary":"3/5 files done"}"#,
                 ))
                 .unwrap(),
         )
         .await
         .unwrap();
 
-    // GET active sessions
-    let response = app
-        .oneshot(
-            Request::builder()
-                .uri("/api/sessions/active")
-                .body(Body::empty())
-                .unwrap(),
-        )
-        .await
-        .unwrap();
-
     assert_eq!(response.status(), StatusCode::OK);
-    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
-        .await
-        .unwrap();
-    let response_json: serde_json::Value = serde_json::from_slice(&body).unwrap();
-    let sessions = response_json.get("sessions").unwrap().as_array().unwrap();
-    assert_eq!(sessions.len(), 1);
-    let agents = sessions[0].get("agents").unwrap().as_array().unwrap();
-    assert_eq!(agents.len(), 1);
-    assert!(agents[0].get("last_activity").unwrap().as_str().is_some());
-    assert_eq!(
-        agents[0].get("status").unwrap().as_str().unwrap(),
-        "working"
-    );
-    assert_eq!(
-        agents[0].get("summary").unwrap().as_str().unwrap(),
-        "2/3 done"
-    );
 
     let _ = std::fs::remove_dir_all(&temp_dir);
 }
 
 #[tokio::test]
-async fn test_list_sessions_reflects_fresh_heartbeat_activity_and_persists_it() {
+async fn test_session_writes_require_the_session_api_token() {
     let (app, controller) = setup_test_app_with_controller().await;
-    let storage = SessionStorage::new().unwrap();
-    let session_id = format!("session-list-hb-{}", uuid::Uuid::new_v4());
-    let temp_dir = std::env::temp_dir().join(&session_id);
-    let _ = std::fs::create_dir_all(&temp_dir);
-    let _ = std::fs::remove_dir_all(storage.session_dir(&session_id));
-
-    let before = chrono::Utc::now() - chrono::Duration::minutes(20);
+    let temp_dir = tempfile::tempdir().unwrap();
     controller
         .read()
         .insert_test_session(make_test_session_with_agents(
-            &session_id,
-            temp_dir.to_str().unwrap(),
+            "session-token",
+            temp_dir.path().to_str().unwrap(),
             &["worker-1"],
         ));
-    {
-        let sessions = controller.write();
-        let session = sessions.get_session(&session_id).expect("session inserted");
-        let mut updated = session.clone();
-        updated.last_activity_at = before;
-        updated.created_at = before - chrono::Duration::minutes(1);
-        sessions.insert_test_session(updated);
-    }
+    let token = controller.read().session_api_token("session-token");
+
+    let heartbeat = |header: Option<(&'static str, String)>| {
+        let mut request = Request::builder()
+            .method("POST")
+            .uri("/api/sessions/session-token/heartbeat")
+            .header("Content-Type", "application/json");
+        if let Some((name, value)) = header {
+            request = request.header(name, value);
+        }
+        request
+            .body(Body::from(r#"{"agent_id":"worker-1","status":"working"}"#))
+            .unwrap()
+    };
 
-    let heartbeat_response = app
+    let missing = app.clone().oneshot(heartbeat(None)).await.unwrap();
+    assert_eq!(missing.status(), StatusCode::UNAUTHORIZED);
+
+    let other_session = app
+        .clone()
+        .oneshot(heartbeat(Some((
+            SESSION_TOKEN_HEADER,
+            "not-the-token".to_string(),
+        ))))
+        .await
+        .unwrap();
+    assert_eq!(other_session.status(), StatusCode::UNAUTHORIZED);
+
+    let valid = app
+        .clone()
+        .oneshot(heartbeat(Some((SESSION_TOKEN_HEADER, token))))
+        .await
+        .unwrap();
+    assert_eq!(valid.status(), StatusCode::OK);
+
+    // The app's own UI is trusted with its UI token, not with its origin.
+    let forged_origin = app
+        .clone()
+        .oneshot(heartbeat(Some(("Origin", "tauri://localhost".to_string()))))
+        .await
+        .unwrap();
+    assert_eq!(forged_origin.status(), StatusCode::UNAUTHORIZED);
+    let from_ui = app
+        .clone()
+        .oneshot(heartbeat(Some((UI_TOKEN_HEADER, TEST_UI_TOKEN.to_string()))))
+        .await
+        .unwrap();
+    assert_eq!(from_ui.status(), StatusCode::OK);
+
+    let learning = |header: Option<(&'static str, String)>| {
+        let mut request = Request::builder()
+            .method("POST")
+            .uri("/api/learnings")
+            .header("Content-Type", "application/json");
+        if let Some((name, value)) = header {
+            request = request.header(name, value);
+        }
+        request
+            .body(Body::from(
+                r#"{"session":"session-token","content":"Use the fixtures","category":"testing"}"#,
+            ))
+            .unwrap()
+    };
+    let untokened_learning = app.clone().oneshot(learning(None)).await.unwrap();
+    assert_eq!(untokened_learning.status(), StatusCode::UNAUTHORIZED);
+    let learning_token = controller.read().session_api_token("session-token");
+    let tokened_learning = app
+        .clone()
+        .oneshot(learning(Some((SESSION_TOKEN_HEADER, learning_token))))
+        .await
+        .unwrap();
+    assert_eq!(tokened_learning.status(), StatusCode::CREATED);
+
+    let action = app
         .clone()
         .oneshot(
             Request::builder()
                 .method("POST")
-                .uri(format!("/api/sessions/{}/heartbeat", session_id))
+                .uri("/api/actions/session.stop")
                 .header("Content-Type", "application/json")
-                .body(Body::from(
-                    r#"{"agent_id":"worker-1","status":"working","summary":"fresh activity"}"#,
-                ))
+                .body(Body::from(r#"{"id":"session-token"}"#))
                 .unwrap(),
         )
         .await
         .unwrap();
-    assert_eq!(heartbeat_response.status(), StatusCode::OK);
+    assert_eq!(action.status(), StatusCode::UNAUTHORIZED);
 
-    let response = app
+    // Reads stay open.
+    let read = app
         .oneshot(
             Request::builder()
-                .uri("/api/sessions")
+                .uri("/api/sessions/session-token")
                 .body(Body::empty())
                 .unwrap(),
         )
         .await
         .unwrap();
-    assert_eq!(response.status(), StatusCode::OK);
-
-    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
-        .await
-        .unwrap();
-    let response_json: serde_json::Value = serde_json::from_slice(&body).unwrap();
-    let listed = response_json
-        .get("sessions")
-        .and_then(|sessions| sessions.as_array())
-        .and_then(|sessions| {
-            sessions.iter().find(|session| {
-                session.get("id").and_then(|id| id.as_str()) == Some(session_id.as_str())
-            })
-        })
-        .expect("session should be listed");
-
-    let listed_last_activity = listed
-        .get("last_activity_at")
-        .and_then(|value| value.as_str())
-        .expect("last_activity_at");
-    let listed_last_activity = chrono::DateTime::parse_from_rfc3339(listed_last_activity)
-        .unwrap()
-        .with_timezone(&chrono::Utc);
-    assert!(listed_last_activity > before);
-
-    let persisted = storage.load_session(&session_id).unwrap();
-    assert_eq!(persisted.last_activity_at, Some(listed_last_activity));
-
-    let _ = std::fs::remove_dir_all(&temp_dir);
-    let _ = std::fs::remove_dir_all(storage.session_dir(&session_id));
+    assert_eq!(read.status(), StatusCode::OK);
 }
 
 #[tokio::test]
-async fn test_complete_session_returns_conflict_when_not_quiescent() {
+async fn test_quarantined_agent_is_cut_off_from_the_session_api() {
     let (app, controller) = setup_test_app_with_controller().await;
-    let temp_dir = std::env::temp_dir().join("hive-test-complete-blocked");
-    let _ = std::fs::create_dir_all(&temp_dir);
-
+    let temp_dir = tempfile::tempdir().unwrap();
     controller
         .read()
-        .insert_test_session(make_test_session_for_completion(
-            "session-complete-blocked",
-            temp_dir.to_str().unwrap(),
-            SessionState::Running,
-            chrono::Utc::now() - chrono::Duration::minutes(11),
-            true,
+        .insert_test_session(make_test_session_with_agents(
+            "session-quarantine",
+            temp_dir.path().to_str().unwrap(),
+            &["worker-1", "worker-2"],
         ));
 
-    let response = app
+    let quarantine = |method: &str| {
+        Request::builder()
+            .method(method)
+            .uri("/api/sessions/session-quarantine/agents/worker-1/quarantine")
+            .header("Origin", "tauri://localhost")
+            .body(Body::empty())
+            .unwrap()
+    };
+    let heartbeat = |agent_id: &'static str| {
+        Request::builder()
+            .method("POST")
+            .uri("/api/sessions/session-quarantine/heartbeat")
+            .header("Content-Type", "application/json")
+            .header(AGENT_ID_HEADER, agent_id)
+            .body(Body::from(format!(
+                r#"{{"agent_id":"{}","status":"working"}}"#,
+                agent_id
+            )))
+            .unwrap()
+    };
+
+    let response = app.clone().oneshot(quarantine("POST")).await.unwrap();
+    assert_eq!(response.status(), StatusCode::NO_CONTENT);
+    assert_eq!(
+        controller.read().quarantined_agents("session-quarantine"),
+        vec!["worker-1".to_string()]
+    );
+
+    let blocked = app.clone().oneshot(heartbeat("worker-1")).await.unwrap();
+    assert_eq!(blocked.status(), StatusCode::FORBIDDEN);
+    let peer = app.clone().oneshot(heartbeat("worker-2")).await.unwrap();
+    assert_eq!(peer.status(), StatusCode::OK);
+
+    // Messages to or from the agent are not routed, whoever sends them.
+    let message = app
+        .clone()
         .oneshot(
             Request::builder()
                 .method("POST")
-                .uri("/api/sessions/session-complete-blocked/complete")
-                .body(Body::empty())
+                .uri("/api/sessions/session-quarantine/conversations/worker-1/append")
+                .header("Content-Type", "application/json")
+                .body(Body::from(r#"{"from":"worker-2","content":"ping"}"#))
                 .unwrap(),
         )
         .await
         .unwrap();
+    assert_eq!(message.status(), StatusCode::FORBIDDEN);
 
-    assert_eq!(response.status(), StatusCode::CONFLICT);
+    let response = app.clone().oneshot(quarantine("DELETE")).await.unwrap();
+    assert_eq!(response.status(), StatusCode::NO_CONTENT);
+    let released = app.clone().oneshot(heartbeat("worker-1")).await.unwrap();
+    assert_eq!(released.status(), StatusCode::OK);
 
-    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+    let unknown = app
+        .oneshot(
+            Request::builder()
+                .method("POST")
+                .uri("/api/sessions/session-quarantine/agents/worker-9/quarantine")
+                .header("Origin", "tauri://localhost")
+                .body(Body::empty())
+                .unwrap(),
+        )
         .await
         .unwrap();
-    let response_json: serde_json::Value = serde_json::from_slice(&body).unwrap();
-    let error = response_json
-        .get("error")
-        .and_then(|value| value.as_str())
-        .unwrap();
-    assert!(error.contains("QaPassed"));
-
-    let _ = std::fs::remove_dir_all(&temp_dir);
+    assert_eq!(unknown.status(), StatusCode::NOT_FOUND);
 }
 
 #[tokio::test]
-async fn test_complete_session_returns_conflict_for_recent_qa_passed_session() {
+async fn test_post_heartbeat_rejects_invalid_status() {
     let (app, controller) = setup_test_app_with_controller().await;
-    let temp_dir = std::env::temp_dir().join("hive-test-complete-recent-pass");
+
+    let temp_dir = std::env::temp_dir().join("hive-test-heartbeat-invalid");
     let _ = std::fs::create_dir_all(&temp_dir);
 
     controller
         .read()
-        .insert_test_session(make_test_session_for_completion(
-            "session-complete-recent-pass",
+        .insert_test_session(make_test_session_with_agents(
+            "session-hb-inv",
             temp_dir.to_str().unwrap(),
-            SessionState::QaPassed,
-            chrono::Utc::now() - chrono::Duration::minutes(5),
-            true,
+            &["worker-1"],
         ));
 
     let response = app
         .oneshot(
             Request::builder()
                 .method("POST")
-                .uri("/api/sessions/session-complete-recent-pass/complete")
-                .body(Body::empty())
+                .uri("/api/sessions/session-hb-inv/heartbeat")
+                .header("Content-Type", "application/json")
+                .body(Body::from(
+                    r#"{"agent_id":"worker-1","status":"invalid","summary":"x"}"#,
+                ))
                 .unwrap(),
         )
         .await
         .unwrap();
 
-    assert_eq!(response.status(), StatusCode::CONFLICT);
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
 
-    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
-        .await
-        .unwrap();
-    let response_json: serde_json::Value = serde_json::from_slice(&body).unwrap();
-    let error = response_json
-        .get("error")
-        .and_then(|value| value.as_str())
-        .unwrap();
-    assert!(error.contains("10 minutes"));
+    let _ = std::fs::remove_dir_all(&temp_dir);
+}
+
+#[tokio::test]
+async fn test_completed_heartbeat_is_excluded_from_stall_sweep() {
+    let (app, controller) = setup_test_app_with_controller().await;
+
+    let temp_dir = std::env::temp_dir().join(format!(
+        "hive-test-heartbeat-completed-{}",
+        uuid::Uuid::new_v4()
+    ));
+    let _ = std::fs::create_dir_all(&temp_dir);
+
+    controller
+        .read()
+        .insert_test_session(make_test_session_with_agents(
+            "session-hb-completed",
+            temp_dir.to_str().unwrap(),
+            &["worker-done", "worker-active"],
+        ));
+
+    for (agent_id, status) in [
+        ("worker-done", "completed"),
+        ("worker-active", "working"),
+    ] {
+        let response = app
+            .clone()
+            .oneshot(
+                Request::builder()
+                    .method("POST")
+                    .uri("/api/sessions/session-hb-completed/heartbeat")
+                    .header("Content-Type", "application/json")
+                    .body(Body::from(
+                        serde_json::json!({ "agent_id": agent_id, "status": status })
+                            .to_string(),
+                    ))
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+    }
+
+    // Heartbeat timestamps have second precision in the stall sweep. Let both
+    // entries age past a zero-second threshold so status is the deciding factor.
+    tokio::time::sleep(std::time::Duration::from_millis(1_100)).await;
+    let stalled = controller
+        .read()
+        .get_stalled_agents("session-hb-completed", std::time::Duration::ZERO);
+    let stalled_ids: Vec<_> = stalled.into_iter().map(|(id, _)| id).collect();
+
+    assert_eq!(stalled_ids, vec!["worker-active"]);
+    assert!(!stalled_ids.iter().any(|id| id == "worker-done"));
 
     let _ = std::fs::remove_dir_all(&temp_dir);
 }
 
 #[tokio::test]
-async fn test_complete_session_allows_quiet_evaluator_backed_session() {
+async fn test_get_active_sessions_returns_only_running() {
     let (app, controller) = setup_test_app_with_controller().await;
-    let temp_dir = std::env::temp_dir().join("hive-test-complete-ok");
+
+    let temp_dir = std::env::temp_dir().join("hive-test-active");
     let _ = std::fs::create_dir_all(&temp_dir);
 
     controller
         .read()
-        .insert_test_session(make_test_session_for_completion(
-            "session-complete-ok",
+        .insert_test_session(make_test_session_with_agents(
+            "session-running",
             temp_dir.to_str().unwrap(),
-            SessionState::QaPassed,
-            chrono::Utc::now() - chrono::Duration::minutes(11),
-            true,
+            &["worker-1"],
         ));
 
     let response = app
         .oneshot(
             Request::builder()
-                .method("POST")
-                .uri("/api/sessions/session-complete-ok/complete")
+                .uri("/api/sessions/active")
                 .body(Body::empty())
                 .unwrap(),
         )
@@ -6705,542 +7393,760 @@ async fn test_complete_session_allows_quiet_evaluator_backed_session() {
         .unwrap();
 
     assert_eq!(response.status(), StatusCode::OK);
-    let session = controller
-        .read()
-        .get_session("session-complete-ok")
-        .expect("session should remain loaded");
-    assert!(matches!(session.state, SessionState::Completed));
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    let response_json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+    let sessions = response_json.get("sessions").unwrap().as_array().unwrap();
+    assert_eq!(sessions.len(), 1);
+    assert_eq!(
+        sessions[0].get("id").unwrap().as_str().unwrap(),
+        "session-running"
+    );
+    let agents = sessions[0].get("agents").unwrap().as_array().unwrap();
+    assert_eq!(agents.len(), 1);
 
     let _ = std::fs::remove_dir_all(&temp_dir);
 }
 
 #[tokio::test]
-async fn test_complete_session_allows_quiet_fusion_session_without_evaluator() {
+async fn test_get_active_sessions_includes_heartbeat_after_post() {
     let (app, controller) = setup_test_app_with_controller().await;
-    let temp_dir = std::env::temp_dir().join("hive-test-complete-fusion");
+
+    let temp_dir = std::env::temp_dir().join("hive-test-active-hb");
     let _ = std::fs::create_dir_all(&temp_dir);
 
     controller
         .read()
-        .insert_test_session(make_test_session_for_completion(
-            "session-complete-fusion",
+        .insert_test_session(make_test_session_with_agents(
+            "session-active-hb",
             temp_dir.to_str().unwrap(),
-            SessionState::Running,
-            chrono::Utc::now() - chrono::Duration::minutes(11),
-            false,
+            &["worker-1"],
         ));
 
-    let response = app
+    // POST heartbeat first
+    let _ = app
+        .clone()
         .oneshot(
             Request::builder()
                 .method("POST")
-                .uri("/api/sessions/session-complete-fusion/complete")
-                .body(Body::empty())
+                .uri("/api/sessions/session-active-hb/heartbeat")
+                .header("Content-Type", "application/json")
+                .body(Body::from(
+                    r#"{"agent_id":"worker-1","status":"working","summary":"2/3 done"}"#,
+                ))
                 .unwrap(),
         )
         .await
         .unwrap();
 
-    assert_eq!(response.status(), StatusCode::OK);
-    let session = controller
-        .read()
-        .get_session("session-complete-fusion")
-        .expect("session should remain loaded");
-    assert!(matches!(session.state, SessionState::Completed));
-
-    let _ = std::fs::remove_dir_all(&temp_dir);
-}
-
-// --- SSE Event Endpoint Tests ---
-
-#[tokio::test]
-async fn test_get_events_returns_empty_for_new_session() {
-    let app = setup_test_app().await;
-
+    // GET active sessions
     let response = app
         .oneshot(
             Request::builder()
-                .uri("/api/sessions/new-session/events")
+                .uri("/api/sessions/active")
                 .body(Body::empty())
                 .unwrap(),
         )
         .await
         .unwrap();
 
-    // Should return OK with empty array for non-existent session
     assert_eq!(response.status(), StatusCode::OK);
-
-    let body_bytes = axum::body::to_bytes(response.into_body(), usize::MAX)
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
         .await
         .unwrap();
-    let response_json: serde_json::Value = serde_json::from_slice(&body_bytes).unwrap();
-    let events = response_json.as_array().unwrap();
-    assert_eq!(events.len(), 0);
+    let response_json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+    let sessions = response_json.get("sessions").unwrap().as_array().unwrap();
+    assert_eq!(sessions.len(), 1);
+    let agents = sessions[0].get("agents").unwrap().as_array().unwrap();
+    assert_eq!(agents.len(), 1);
+    assert!(agents[0].get("last_activity").unwrap().as_str().is_some());
+    assert_eq!(
+        agents[0].get("status").unwrap().as_str().unwrap(),
+        "working"
+    );
+    assert_eq!(
+        agents[0].get("summary").unwrap().as_str().unwrap(),
+        "2/3 done"
+    );
+
+    let _ = std::fs::remove_dir_all(&temp_dir);
 }
 
 #[tokio::test]
-async fn test_get_events_rejects_path_traversal_session_id() {
-    let app = setup_test_app().await;
+async fn test_agent_questions_are_listed_until_answered() {
+    let (app, controller) = setup_test_app_with_controller().await;
+    let temp_dir = tempfile::tempdir().unwrap();
+    controller
+        .read()
+        .insert_test_session(make_test_session_with_agents(
+            "session-questions",
+            temp_dir.path().to_str().unwrap(),
+            &["worker-1"],
+        ));
+
+    let ask = |agent_id: &str| {
+        Request::builder()
+            .method("POST")
+            .uri("/api/sessions/session-questions/questions")
+            .header("Content-Type", "application/json")
+            .body(Body::from(format!(
+                r#"{{"agent_id":"{}","question":"Drop the legacy table?","options":["yes","no"]}}"#,
+                agent_id
+            )))
+            .unwrap()
+    };
+    let unknown = app.clone().oneshot(ask("worker-9")).await.unwrap();
+    assert_eq!(unknown.status(), StatusCode::NOT_FOUND);
+
+    let response = app.clone().oneshot(ask("worker-1")).await.unwrap();
+    assert_eq!(response.status(), StatusCode::CREATED);
+    let question = read_json_body(response).await;
+    let question_id = question["id"].as_str().unwrap().to_string();
+    assert_eq!(question["options"], serde_json::json!(["yes", "no"]));
+    assert!(question["answer"].is_null());
 
     let response = app
+        .clone()
         .oneshot(
             Request::builder()
-                .uri("/api/sessions/../../../etc/passwd/events")
+                .uri("/api/sessions/active")
                 .body(Body::empty())
                 .unwrap(),
         )
         .await
         .unwrap();
+    let active = read_json_body(response).await;
+    assert_eq!(
+        active["sessions"][0]["pending_questions"][0]["id"],
+        question_id.as_str()
+    );
 
-    // Path traversal is rejected before handler validation by route matching.
-    assert_eq!(response.status(), StatusCode::NOT_FOUND);
-}
-
-#[tokio::test]
-async fn test_stream_events_endpoint_exists() {
-    let app = setup_test_app().await;
-
-    // Just verify the endpoint exists and returns SSE content type
-    // Note: Testing actual SSE streaming in unit tests is complex
-    // This test verifies the endpoint is accessible
+    controller
+        .read()
+        .answer_operator_question("session-questions", &question_id, "no")
+        .unwrap();
     let response = app
+        .clone()
         .oneshot(
             Request::builder()
-                .uri("/api/sessions/test-session/stream")
+                .uri("/api/sessions/session-questions/questions?pending=true")
                 .body(Body::empty())
                 .unwrap(),
         )
         .await
         .unwrap();
-
-    // SSE endpoint should return OK (it will keep connection open)
-    // The response should have content-type: text/event-stream
     assert_eq!(response.status(), StatusCode::OK);
-
-    let content_type = response
-        .headers()
-        .get("content-type")
-        .and_then(|v| v.to_str().ok())
-        .unwrap_or("");
-    assert!(content_type.contains("text/event-stream") || content_type.contains("event-stream"));
-}
-
-#[tokio::test]
-async fn test_stream_events_rejects_path_traversal_session_id() {
-    let app = setup_test_app().await;
+    assert_eq!(read_json_body(response).await["questions"], serde_json::json!([]));
 
     let response = app
         .oneshot(
             Request::builder()
-                .uri("/api/sessions/../../etc/stream")
+                .uri(format!(
+                    "/api/sessions/session-questions/questions/{}",
+                    question_id
+                ))
                 .body(Body::empty())
                 .unwrap(),
         )
         .await
         .unwrap();
-
-    // Path traversal is rejected before handler validation by route matching.
-    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    assert_eq!(read_json_body(response).await["answer"], "no");
 }
 
-// ── Resolver launch endpoint tests ──────────────────────────────────────
+#[tokio::test]
+async fn test_gated_push_is_parked_until_the_operator_decides() {
+    let state = setup_test_state().await;
+    state.config.write().await.approvals.protected_branches = vec!["main".to_string()];
+    let temp_dir = tempfile::tempdir().unwrap();
+    state
+        .session_controller
+        .read()
+        .insert_test_session(make_test_session_with_agents(
+            "session-approvals",
+            temp_dir.path().to_str().unwrap(),
+            &["worker-1"],
+        ));
+    let app = create_router(state.clone());
+    let push = |branch: &str| {
+        Request::builder()
+            .method("POST")
+            .uri("/api/sessions/session-approvals/approvals")
+            .header("Content-Type", "application/json")
+            .body(Body::from(format!(
+                r#"{{"agent_id":"worker-1","kind":"push","branch":"{}"}}"#,
+                branch
+            )))
+            .unwrap()
+    };
 
-fn make_fusion_session(id: &str, project_path: &str) -> Session {
-    let now = chrono::Utc::now();
-    Session {
-        id: id.to_string(),
-        name: None,
-        color: None,
-        session_type: SessionType::Fusion {
-            variants: vec!["variant-a".to_string(), "variant-b".to_string()],
-        },
-        project_path: PathBuf::from(project_path),
-        state: SessionState::Running,
-        created_at: now,
-        last_activity_at: now,
-        agents: vec![],
-        default_cli: "claude".to_string(),
-        default_model: Some("opus".to_string()),
-        default_principal_cli: None,
-        default_principal_model: None,
-        default_principal_flags: Vec::new(),
-        execution_policy: crate::domain::HiveExecutionPolicy::default(),
-        qa_workers: Vec::new(),
-        max_qa_iterations: test_default_max_qa_iterations(),
-        qa_timeout_secs: 300,
-        auth_strategy: AuthStrategy::default(),
-        worktree_path: None,
-        worktree_branch: None,
-        no_git: false,
-        resume_report: None,
+    let ungated = app.clone().oneshot(push("feature/x")).await.unwrap();
+    assert_eq!(ungated.status(), StatusCode::OK);
+    assert!(state.approvals.pending(None).is_empty());
+
+    for approved in [true, false] {
+        let parked = tokio::spawn(app.clone().oneshot(push("main")));
+        let request = loop {
+            if let Some(request) = state.approvals.pending(Some("session-approvals")).pop() {
+                break request;
+            }
+            tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+        };
+        assert_eq!(request.agent_id.as_deref(), Some("worker-1"));
+        assert!(!parked.is_finished());
+
+        state.approvals.resolve(&request.id, approved).unwrap();
+        let response = parked.await.unwrap().unwrap();
+        let expected = if approved {
+            StatusCode::OK
+        } else {
+            StatusCode::FORBIDDEN
+        };
+        assert_eq!(response.status(), expected);
     }
 }
 
 #[tokio::test]
-async fn test_resolver_launch_success_with_artifacts() {
-    let (app, controller) = setup_test_app_with_controller().await;
-    let storage = SessionStorage::new().unwrap();
-    let session_id = format!("resolver-launch-{}", uuid::Uuid::new_v4());
+async fn test_git_operations_run_for_the_queen_only() {
+    let state = setup_test_state().await;
+    let repo = tempfile::tempdir().unwrap();
+    init_git_repo_for_launch_fixture(repo.path());
+    std::fs::write(repo.path().join("README.md"), "integrated\n").unwrap();
+    state
+        .session_controller
+        .read()
+        .insert_test_session(make_test_session_with_agents(
+            "session-git",
+            repo.path().to_str().unwrap(),
+            &["session-git-worker-1"],
+        ));
+    let app = create_router(state);
+    let request = |agent_id: &str, operations: &str| {
+        Request::builder()
+            .method("POST")
+            .uri("/api/sessions/session-git/git")
+            .header("Content-Type", "application/json")
+            .body(Body::from(format!(
+                r#"{{"agent_id":"{}","operations":{}}}"#,
+                agent_id, operations
+            )))
+            .unwrap()
+    };
+    let commit = r#"[{"op":"commit","message":"Integrate workers"}]"#;
 
-    // Create session dir and artifacts
-    storage.create_session_dir(&session_id).unwrap();
-    storage
-        .save_artifact(
-            &session_id,
-            "variant-a",
-            &crate::domain::ArtifactBundle {
-                summary: Some("Variant A impl".to_string()),
-                changed_files: vec!["src/a.rs".to_string()],
-                commits: vec!["a1".to_string()],
-                branch: "fusion/a".to_string(),
-                test_results: None,
-                diff_summary: None,
-                unresolved_issues: vec![],
-                confidence: Some(0.9),
-                recommended_next_step: None,
-            },
-        )
+    let worker = app
+        .clone()
+        .oneshot(request("session-git-worker-1", commit))
+        .await
         .unwrap();
-    storage
-        .save_artifact(
-            &session_id,
-            "variant-b",
-            &crate::domain::ArtifactBundle {
-                summary: Some("Variant B impl".to_string()),
-                changed_files: vec!["src/b.rs".to_string()],
-                commits: vec!["b1".to_string()],
-                branch: "fusion/b".to_string(),
-                test_results: None,
-                diff_summary: None,
-                unresolved_issues: vec!["todo".to_string()],
-                confidence: Some(0.5),
-                recommended_next_step: None,
-            },
-        )
+    assert_eq!(worker.status(), StatusCode::FORBIDDEN);
+    let empty = app
+        .clone()
+        .oneshot(request("session-git-queen", "[]"))
+        .await
         .unwrap();
-
-    // Register Fusion session in controller
-    let session = make_fusion_session(&session_id, &std::env::temp_dir().to_string_lossy());
-    let mut session = session;
-    session.last_activity_at = chrono::Utc::now() - chrono::Duration::minutes(11);
-    controller.write().insert_test_session(session);
-
-    let body = serde_json::json!({
-        "candidate_ids": ["variant-a", "variant-b"]
-    });
+    assert_eq!(empty.status(), StatusCode::BAD_REQUEST);
 
     let response = app
-        .oneshot(
-            Request::builder()
-                .method("POST")
-                .uri(format!("/api/sessions/{}/resolver/launch", session_id))
-                .header("content-type", "application/json")
-                .body(Body::from(serde_json::to_string(&body).unwrap()))
-                .unwrap(),
-        )
+        .clone()
+        .oneshot(request("session-git-queen", commit))
         .await
         .unwrap();
-
     assert_eq!(response.status(), StatusCode::OK);
+    let body = read_json_body(response).await;
+    assert_eq!(body["ok"], true);
+    assert_eq!(body["outcomes"][0]["status"], "done");
 
-    let body_bytes = axum::body::to_bytes(response.into_body(), usize::MAX)
+    // Nothing is left to commit, so a second commit fails its preflight check.
+    let again = app
+        .oneshot(request("session-git-queen", commit))
         .await
         .unwrap();
-    let output: crate::domain::ResolverOutput = serde_json::from_slice(&body_bytes).unwrap();
-    assert_eq!(output.selected_candidate, "variant-a");
-    assert!(!output.rationale.is_empty());
-
-    let session = controller.read().get_session(&session_id).unwrap();
-    assert_eq!(session.state, SessionState::Completed);
-
-    // Verify output was persisted
-    let persisted_output = storage.load_resolver_output(&session_id).unwrap();
-    assert!(persisted_output.is_some());
-
-    let _ = std::fs::remove_dir_all(storage.session_dir(&session_id));
+    let body = read_json_body(again).await;
+    assert_eq!(body["ok"], false);
+    assert_eq!(body["outcomes"][0]["error"], "Nothing to commit");
 }
 
 #[tokio::test]
-async fn test_resolver_launch_missing_session_returns_404() {
-    let app = setup_test_app().await;
+async fn test_list_sessions_reflects_fresh_heartbeat_activity_and_persists_it() {
+    let (app, controller) = setup_test_app_with_controller().await;
+    let storage = SessionStorage::new().unwrap();
+    let session_id = format!("session-list-hb-{}", uuid::Uuid::new_v4());
+    let temp_dir = std::env::temp_dir().join(&session_id);
+    let _ = std::fs::create_dir_all(&temp_dir);
+    let _ = std::fs::remove_dir_all(storage.session_dir(&session_id));
 
-    let body = serde_json::json!({
-        "candidate_ids": ["variant-a"]
-    });
+    let before = chrono::Utc::now() - chrono::Duration::minutes(20);
+    controller
+        .read()
+        .insert_test_session(make_test_session_with_agents(
+            &session_id,
+            temp_dir.to_str().unwrap(),
+            &["worker-1"],
+        ));
+    {
+        let sessions = controller.write();
+        let session = sessions.get_session(&session_id).expect("session inserted");
+        let mut updated = session.clone();
+        updated.last_activity_at = before;
+        updated.created_at = before - chrono::Duration::minutes(1);
+        sessions.insert_test_session(updated);
+    }
 
-    let response = app
+    let heartbeat_response = app
+        .clone()
         .oneshot(
             Request::builder()
                 .method("POST")
-                .uri("/api/sessions/nonexistent-session-id/resolver/launch")
-                .header("content-type", "application/json")
-                .body(Body::from(serde_json::to_string(&body).unwrap()))
+                .uri(format!("/api/sessions/{}/heartbeat", session_id))
+                .header("Content-Type", "application/json")
+                .body(Body::from(
+                    r#"{"agent_id":"worker-1","status":"working","summary":"fresh activity"}"#,
+                ))
                 .unwrap(),
         )
         .await
         .unwrap();
+    assert_eq!(heartbeat_response.status(), StatusCode::OK);
 
-    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    let response = app
+        .oneshot(
+            Request::builder()
+                .uri("/api/sessions")
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    let response_json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+    let listed = response_json
+        .get("sessions")
+        .and_then(|sessions| sessions.as_array())
+        .and_then(|sessions| {
+            sessions.iter().find(|session| {
+                session.get("id").and_then(|id| id.as_str()) == Some(session_id.as_str())
+            })
+        })
+        .expect("session should be listed");
+
+    let listed_last_activity = listed
+        .get("last_activity_at")
+        .and_then(|value| value.as_str())
+        .expect("last_activity_at");
+    let listed_last_activity = chrono::DateTime::parse_from_rfc3339(listed_last_activity)
+        .unwrap()
+        .with_timezone(&chrono::Utc);
+    assert!(listed_last_activity > before);
+
+    let persisted = storage.load_session(&session_id).unwrap();
+    assert_eq!(persisted.last_activity_at, Some(listed_last_activity));
+
+    let _ = std::fs::remove_dir_all(&temp_dir);
+    let _ = std::fs::remove_dir_all(storage.session_dir(&session_id));
 }
 
 #[tokio::test]
-async fn test_resolver_launch_empty_candidates_returns_400() {
+async fn test_complete_session_returns_conflict_when_not_quiescent() {
     let (app, controller) = setup_test_app_with_controller().await;
-    let session_id = format!("resolver-empty-{}", uuid::Uuid::new_v4());
-
-    let session = make_fusion_session(&session_id, &std::env::temp_dir().to_string_lossy());
-    controller.write().insert_test_session(session);
+    let temp_dir = std::env::temp_dir().join("hive-test-complete-blocked");
+    let _ = std::fs::create_dir_all(&temp_dir);
 
-    let body = serde_json::json!({
-        "candidate_ids": []
-    });
+    controller
+        .read()
+        .insert_test_session(make_test_session_for_completion(
+            "session-complete-blocked",
+            temp_dir.to_str().unwrap(),
+            SessionState::Running,
+            chrono::Utc::now() - chrono::Duration::minutes(11),
+            true,
+        ));
 
     let response = app
         .oneshot(
             Request::builder()
                 .method("POST")
-                .uri(format!("/api/sessions/{}/resolver/launch", session_id))
-                .header("content-type", "application/json")
-                .body(Body::from(serde_json::to_string(&body).unwrap()))
+                .uri("/api/sessions/session-complete-blocked/complete")
+                .body(Body::empty())
                 .unwrap(),
         )
         .await
         .unwrap();
 
-    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    assert_eq!(response.status(), StatusCode::CONFLICT);
+
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    let response_json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+    let error = response_json
+        .get("error")
+        .and_then(|value| value.as_str())
+        .unwrap();
+    assert!(error.contains("QaPassed"));
+
+    let _ = std::fs::remove_dir_all(&temp_dir);
 }
 
 #[tokio::test]
-async fn test_resolver_launch_rejects_duplicate_candidate_ids() {
+async fn test_complete_session_returns_conflict_for_recent_qa_passed_session() {
     let (app, controller) = setup_test_app_with_controller().await;
-    let session_id = format!("resolver-duplicate-{}", uuid::Uuid::new_v4());
-
-    let session = make_fusion_session(&session_id, &std::env::temp_dir().to_string_lossy());
-    controller.write().insert_test_session(session);
+    let temp_dir = std::env::temp_dir().join("hive-test-complete-recent-pass");
+    let _ = std::fs::create_dir_all(&temp_dir);
 
-    let body = serde_json::json!({
-        "candidate_ids": ["variant-a", "variant-a"]
-    });
+    controller
+        .read()
+        .insert_test_session(make_test_session_for_completion(
+            "session-complete-recent-pass",
+            temp_dir.to_str().unwrap(),
+            SessionState::QaPassed,
+            chrono::Utc::now() - chrono::Duration::minutes(5),
+            true,
+        ));
 
     let response = app
         .oneshot(
             Request::builder()
                 .method("POST")
-                .uri(format!("/api/sessions/{}/resolver/launch", session_id))
-                .header("content-type", "application/json")
-                .body(Body::from(serde_json::to_string(&body).unwrap()))
+                .uri("/api/sessions/session-complete-recent-pass/complete")
+                .body(Body::empty())
                 .unwrap(),
         )
         .await
         .unwrap();
 
-    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    assert_eq!(response.status(), StatusCode::CONFLICT);
+
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    let response_json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+    let error = response_json
+        .get("error")
+        .and_then(|value| value.as_str())
+        .unwrap();
+    assert!(error.contains("10 minutes"));
+
+    let _ = std::fs::remove_dir_all(&temp_dir);
 }
 
 #[tokio::test]
-async fn test_resolver_launch_rejects_unknown_candidate_ids() {
+async fn test_complete_session_allows_quiet_evaluator_backed_session() {
     let (app, controller) = setup_test_app_with_controller().await;
-    let session_id = format!("resolver-unknown-{}", uuid::Uuid::new_v4());
-
-    let session = make_fusion_session(&session_id, &std::env::temp_dir().to_string_lossy());
-    controller.write().insert_test_session(session);
+    let temp_dir = std::env::temp_dir().join("hive-test-complete-ok");
+    let _ = std::fs::create_dir_all(&temp_dir);
 
-    let body = serde_json::json!({
-        "candidate_ids": ["variant-a", "variant-c"]
-    });
+    controller
+        .read()
+        .insert_test_session(make_test_session_for_completion(
+            "session-complete-ok",
+            temp_dir.to_str().unwrap(),
+            SessionState::QaPassed,
+            chrono::Utc::now() - chrono::Duration::minutes(11),
+            true,
+        ));
 
     let response = app
         .oneshot(
             Request::builder()
                 .method("POST")
-                .uri(format!("/api/sessions/{}/resolver/launch", session_id))
-                .header("content-type", "application/json")
-                .body(Body::from(serde_json::to_string(&body).unwrap()))
+                .uri("/api/sessions/session-complete-ok/complete")
+                .body(Body::empty())
                 .unwrap(),
         )
         .await
         .unwrap();
 
-    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
-}
-
-// ---------------------------------------------------------------------------
-// Application-state (SQLite) HTTP endpoint tests (issue #124)
-// ---------------------------------------------------------------------------
+    assert_eq!(response.status(), StatusCode::OK);
+    let session = controller
+        .read()
+        .get_session("session-complete-ok")
+        .expect("session should remain loaded");
+    assert!(matches!(session.state, SessionState::Completed));
 
-async fn read_json_body(response: axum::response::Response) -> serde_json::Value {
-    let bytes = axum::body::to_bytes(response.into_body(), usize::MAX)
-        .await
-        .unwrap();
-    serde_json::from_slice(&bytes).unwrap()
+    let _ = std::fs::remove_dir_all(&temp_dir);
 }
 
 #[tokio::test]
-async fn test_post_application_state_returns_row() {
-    let (app, _controller) = setup_test_app_with_controller().await;
+async fn test_complete_session_allows_quiet_fusion_session_without_evaluator() {
+    let (app, controller) = setup_test_app_with_controller().await;
+    let temp_dir = std::env::temp_dir().join("hive-test-complete-fusion");
+    let _ = std::fs::create_dir_all(&temp_dir);
 
-    let body = serde_json::json!({
-        "key": "route",
-        "value": { "route": "dashboard" }
-    });
+    controller
+        .read()
+        .insert_test_session(make_test_session_for_completion(
+            "session-complete-fusion",
+            temp_dir.to_str().unwrap(),
+            SessionState::Running,
+            chrono::Utc::now() - chrono::Duration::minutes(11),
+            false,
+        ));
 
     let response = app
         .oneshot(
             Request::builder()
                 .method("POST")
-                .uri("/api/sessions/app-state-post/application-state")
-                .header("content-type", "application/json")
-                .body(Body::from(serde_json::to_string(&body).unwrap()))
+                .uri("/api/sessions/session-complete-fusion/complete")
+                .body(Body::empty())
                 .unwrap(),
         )
         .await
         .unwrap();
 
     assert_eq!(response.status(), StatusCode::OK);
-    let json = read_json_body(response).await;
-    assert_eq!(json["session_id"], "app-state-post");
-    assert_eq!(json["key"], "route");
-    assert_eq!(json["value"], serde_json::json!({ "route": "dashboard" }));
-    // Server stamps a positive millisecond timestamp.
-    assert!(json["updated_at"].as_i64().unwrap() > 0);
+    let session = controller
+        .read()
+        .get_session("session-complete-fusion")
+        .expect("session should remain loaded");
+    assert!(matches!(session.state, SessionState::Completed));
+
+    let _ = std::fs::remove_dir_all(&temp_dir);
 }
 
-#[tokio::test]
-async fn test_get_snapshot_returns_all() {
-    let (app, _controller) = setup_test_app_with_controller().await;
-    let session_id = "app-state-snapshot";
+// --- SSE Event Endpoint Tests ---
 
-    for (key, value) in [("a", serde_json::json!(1)), ("b", serde_json::json!("two"))] {
-        let body = serde_json::json!({ "key": key, "value": value });
-        let resp = app
-            .clone()
-            .oneshot(
-                Request::builder()
-                    .method("POST")
-                    .uri(format!("/api/sessions/{session_id}/application-state"))
-                    .header("content-type", "application/json")
-                    .body(Body::from(serde_json::to_string(&body).unwrap()))
-                    .unwrap(),
-            )
-            .await
-            .unwrap();
-        assert_eq!(resp.status(), StatusCode::OK);
-    }
+#[tokio::test]
+async fn test_get_events_returns_empty_for_new_session() {
+    let app = setup_test_app().await;
 
     let response = app
         .oneshot(
             Request::builder()
-                .uri(format!("/api/sessions/{session_id}/application-state"))
+                .uri("/api/sessions/new-session/events")
                 .body(Body::empty())
                 .unwrap(),
         )
         .await
         .unwrap();
 
+    // Should return OK with empty array for non-existent session
     assert_eq!(response.status(), StatusCode::OK);
-    let json = read_json_body(response).await;
-    let rows = json.as_array().unwrap();
-    assert_eq!(rows.len(), 2);
-    // Ordered by key: a, then b. Values are parsed JSON, not strings.
-    assert_eq!(rows[0]["key"], "a");
-    assert_eq!(rows[0]["value"], serde_json::json!(1));
-    assert_eq!(rows[1]["key"], "b");
-    assert_eq!(rows[1]["value"], serde_json::json!("two"));
+
+    let body_bytes = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    let response_json: serde_json::Value = serde_json::from_slice(&body_bytes).unwrap();
+    let events = response_json.as_array().unwrap();
+    assert_eq!(events.len(), 0);
 }
 
 #[tokio::test]
-async fn test_get_poll_returns_changed_only() {
-    let (app, _controller) = setup_test_app_with_controller().await;
-    let session_id = "app-state-poll";
+async fn test_get_events_rejects_path_traversal_session_id() {
+    let app = setup_test_app().await;
 
-    // Write two keys; capture the highest updated_at as the watermark.
-    let mut watermark = 0i64;
-    for key in ["a", "b"] {
-        let body = serde_json::json!({ "key": key, "value": key });
-        let resp = app
-            .clone()
-            .oneshot(
-                Request::builder()
-                    .method("POST")
-                    .uri(format!("/api/sessions/{session_id}/application-state"))
-                    .header("content-type", "application/json")
-                    .body(Body::from(serde_json::to_string(&body).unwrap()))
-                    .unwrap(),
-            )
-            .await
-            .unwrap();
-        let row = read_json_body(resp).await;
-        watermark = watermark.max(row["updated_at"].as_i64().unwrap());
-    }
-
-    // Ensure the next write has a strictly greater millisecond timestamp.
-    tokio::time::sleep(std::time::Duration::from_millis(5)).await;
-
-    // Write a third key AFTER the watermark.
-    let body = serde_json::json!({ "key": "c", "value": "c" });
-    let resp = app
-        .clone()
+    let response = app
         .oneshot(
             Request::builder()
-                .method("POST")
-                .uri(format!("/api/sessions/{session_id}/application-state"))
-                .header("content-type", "application/json")
-                .body(Body::from(serde_json::to_string(&body).unwrap()))
+                .uri("/api/sessions/../../../etc/passwd/events")
+                .body(Body::empty())
                 .unwrap(),
         )
         .await
         .unwrap();
-    assert_eq!(resp.status(), StatusCode::OK);
 
-    // Poll with the watermark: only the third key should return.
+    // Path traversal is rejected before handler validation by route matching.
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn test_stream_events_endpoint_exists() {
+    let app = setup_test_app().await;
+
+    // Just verify the endpoint exists and returns SSE content type
+    // Note: Testing actual SSE streaming in unit tests is complex
+    // This test verifies the endpoint is accessible
     let response = app
         .oneshot(
             Request::builder()
-                .uri(format!(
-                    "/api/sessions/{session_id}/application-state/poll?since={watermark}"
-                ))
+                .uri("/api/sessions/test-session/stream")
                 .body(Body::empty())
                 .unwrap(),
         )
         .await
         .unwrap();
 
+    // SSE endpoint should return OK (it will keep connection open)
+    // The response should have content-type: text/event-stream
     assert_eq!(response.status(), StatusCode::OK);
-    let json = read_json_body(response).await;
-    let rows = json.as_array().unwrap();
-    assert_eq!(rows.len(), 1, "watermark poll returns only newer rows");
-    assert_eq!(rows[0]["key"], "c");
+
+    let content_type = response
+        .headers()
+        .get("content-type")
+        .and_then(|v| v.to_str().ok())
+        .unwrap_or("");
+    assert!(content_type.contains("text/event-stream") || content_type.contains("event-stream"));
 }
 
 #[tokio::test]
-async fn test_application_state_invalid_session_id_rejected() {
-    let (app, _controller) = setup_test_app_with_controller().await;
+async fn test_stream_events_rejects_path_traversal_session_id() {
+    let app = setup_test_app().await;
 
-    // Path-traversal-style session id must be rejected with 400 by validate_session_id.
-    // Use a backslash (encoded) which the handler checks for after axum decodes the path.
     let response = app
         .oneshot(
             Request::builder()
-                .uri("/api/sessions/bad..id/application-state")
+                .uri("/api/sessions/../../etc/stream")
                 .body(Body::empty())
                 .unwrap(),
         )
         .await
         .unwrap();
 
-    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    // Path traversal is rejected before handler validation by route matching.
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
 }
 
 #[tokio::test]
-async fn test_resolver_launch_non_fusion_session_returns_400() {
+async fn test_output_stream_subscriptions_change_while_it_is_open() {
+    use futures::StreamExt;
+
     let (app, controller) = setup_test_app_with_controller().await;
-    let session_id = format!("resolver-hive-{}", uuid::Uuid::new_v4());
+    let temp_dir = TempDir::new().unwrap();
+    controller.read().insert_test_session(make_test_session_with_agents(
+        "session-output",
+        temp_dir.path().to_str().unwrap(),
+        &["session-output-queen", "session-output-worker-1"],
+    ));
+    let open = |agents: &str| {
+        Request::builder()
+            .uri(format!("/api/sessions/session-output/output/stream?agents={agents}"))
+            .body(Body::empty())
+            .unwrap()
+    };
+    let update = |stream_id: &str, body: serde_json::Value| {
+        Request::builder()
+            .method("POST")
+            .uri(format!("/api/output-streams/{stream_id}"))
+            .header("content-type", "application/json")
+            .body(Body::from(body.to_string()))
+            .unwrap()
+    };
 
-    let session = make_test_session(&session_id, &std::env::temp_dir().to_string_lossy());
+    let response = app.clone().oneshot(open("someone-else")).await.unwrap();
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+    let response = app.clone().oneshot(open("session-output-queen")).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let mut body = response.into_body().into_data_stream();
+    let frame = body.next().await.unwrap().unwrap();
+    let frame = String::from_utf8_lossy(&frame).to_string();
+    assert!(frame.starts_with("event: subscribed"), "{frame}");
+    let data = frame
+        .lines()
+        .find_map(|line| line.strip_prefix("data: "))
+        .unwrap();
+    let subscribed: serde_json::Value = serde_json::from_str(data).unwrap();
+    assert_eq!(subscribed["agents"], serde_json::json!(["session-output-queen"]));
+    let stream_id = subscribed["stream_id"].as_str().unwrap().to_string();
+
+    let body_json = serde_json::json!({
+        "subscribe": ["session-output-worker-1"],
+        "unsubscribe": ["session-output-queen"],
+    });
+    let response = app.clone().oneshot(update(&stream_id, body_json)).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let json = read_json_body(response).await;
+    assert_eq!(json["agents"], serde_json::json!(["session-output-worker-1"]));
+
+    let unknown_agent = serde_json::json!({ "subscribe": ["someone-else"] });
+    let response = app.clone().oneshot(update(&stream_id, unknown_agent)).await.unwrap();
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+    drop(body);
+    let response = app.oneshot(update(&stream_id, serde_json::json!({}))).await.unwrap();
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+}
+
+// ── Resolver launch endpoint tests ──────────────────────────────────────
+
+fn make_fusion_session(id: &str, project_path: &str) -> Session {
+    let now = chrono::Utc::now();
+    Session {
+        id: id.to_string(),
+        name: None,
+        color: None,
+        session_type: SessionType::Fusion {
+            variants: vec!["variant-a".to_string(), "variant-b".to_string()],
+        },
+        project_path: PathBuf::from(project_path),
+        state: SessionState::Running,
+        created_at: now,
+        last_activity_at: now,
+        agents: vec![],
+        default_cli: "claude".to_string(),
+        default_model: Some("opus".to_string()),
+        default_principal_cli: None,
+        default_principal_model: None,
+        default_principal_flags: Vec::new(),
+        execution_policy: crate::domain::HiveExecutionPolicy::default(),
+        qa_workers: Vec::new(),
+        max_qa_iterations: test_default_max_qa_iterations(),
+        qa_timeout_secs: 300,
+        auth_strategy: AuthStrategy::default(),
+        worktree_path: None,
+        worktree_branch: None,
+        no_git: false,
+        resume_report: None,
+        pull_request_url: None,
+        spawn_quota: crate::session::SpawnQuota::default(),
+    }
+}
+
+#[tokio::test]
+async fn test_resolver_launch_success_with_artifacts() {
+    let (app, controller) = setup_test_app_with_controller().await;
+    let storage = SessionStorage::new().unwrap();
+    let session_id = format!("resolver-launch-{}", uuid::Uuid::new_v4());
+
+    // Create session dir and artifacts
+    storage.create_session_dir(&session_id).unwrap();
+    storage
+        .save_artifact(
+            &session_id,
+            "variant-a",
+            &crate::domain::ArtifactBundle {
+                summary: Some("Variant A impl".to_string()),
+                changed_files: vec!["src/a.rs".to_string()],
+                commits: vec!["a1".to_string()],
+                branch: "fusion/a".to_string(),
+                test_results: None,
+                diff_summary: None,
+                unresolved_issues: vec![],
+                confidence: Some(0.9),
+                recommended_next_step: None,
+            },
+        )
+        .unwrap();
+    storage
+        .save_artifact(
+            &session_id,
+            "variant-b",
+            &crate::domain::ArtifactBundle {
+                summary: Some("Variant B impl".to_string()),
+                changed_files: vec!["src/b.rs".to_string()],
+                commits: vec!["b1".to_string()],
+                branch: "fusion/b".to_string(),
+                test_results: None,
+                diff_summary: None,
+                unresolved_issues: vec!["todo".to_string()],
+                confidence: Some(0.5),
+                recommended_next_step: None,
+            },
+        )
+        .unwrap();
+
+    // Register Fusion session in controller
+    let session = make_fusion_session(&session_id, &std::env::temp_dir().to_string_lossy());
+    let mut session = session;
+    session.last_activity_at = chrono::Utc::now() - chrono::Duration::minutes(11);
     controller.write().insert_test_session(session);
 
     let body = serde_json::json!({
-        "candidate_ids": ["variant-a"]
+        "candidate_ids": ["variant-a", "variant-b"]
     });
 
     let response = app
@@ -7255,176 +8161,119 @@ async fn test_resolver_launch_non_fusion_session_returns_400() {
         .await
         .unwrap();
 
-    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
-}
+    assert_eq!(response.status(), StatusCode::OK);
 
-// ---------------------------------------------------------------------------
-// #123 — unified Action contract: HTTP surface over the registry
-// ---------------------------------------------------------------------------
+    let body_bytes = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    let output: crate::domain::ResolverOutput = serde_json::from_slice(&body_bytes).unwrap();
+    assert_eq!(output.selected_candidate, "variant-a");
+    assert!(!output.rationale.is_empty());
+
+    let session = controller.read().get_session(&session_id).unwrap();
+    assert_eq!(session.state, SessionState::Completed);
+
+    // Verify output was persisted
+    let persisted_output = storage.load_resolver_output(&session_id).unwrap();
+    assert!(persisted_output.is_some());
+
+    let _ = std::fs::remove_dir_all(storage.session_dir(&session_id));
+}
 
 #[tokio::test]
-async fn test_action_endpoint_lists_schemas() {
+async fn test_resolver_launch_missing_session_returns_404() {
     let app = setup_test_app().await;
 
+    let body = serde_json::json!({
+        "candidate_ids": ["variant-a"]
+    });
+
     let response = app
         .oneshot(
             Request::builder()
-                .uri("/api/actions")
-                .body(Body::empty())
+                .method("POST")
+                .uri("/api/sessions/nonexistent-session-id/resolver/launch")
+                .header("content-type", "application/json")
+                .body(Body::from(serde_json::to_string(&body).unwrap()))
                 .unwrap(),
         )
         .await
         .unwrap();
 
-    assert_eq!(response.status(), StatusCode::OK);
-    let body_bytes = axum::body::to_bytes(response.into_body(), usize::MAX)
-        .await
-        .unwrap();
-    let json: serde_json::Value = serde_json::from_slice(&body_bytes).unwrap();
-    let actions = json.get("actions").unwrap().as_array().unwrap();
-
-    let names: Vec<&str> = actions
-        .iter()
-        .map(|a| a.get("name").unwrap().as_str().unwrap())
-        .collect();
-    assert!(names.contains(&"session.launch_hive_v2"));
-    assert!(names.contains(&"session.list"));
-    assert!(names.contains(&"git.pull"));
-
-    // Every descriptor carries a non-empty input_schema object.
-    for a in actions {
-        assert!(
-            a.get("input_schema").unwrap().is_object(),
-            "input_schema should be an object for {}",
-            a.get("name").unwrap()
-        );
-    }
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
 }
 
 #[tokio::test]
-async fn test_http_dispatch_session_list_via_registry() {
+async fn test_resolver_launch_empty_candidates_returns_400() {
     let (app, controller) = setup_test_app_with_controller().await;
+    let session_id = format!("resolver-empty-{}", uuid::Uuid::new_v4());
 
-    let temp_dir = std::env::temp_dir().join("hive-test-action-session-list");
-    let _ = std::fs::create_dir_all(&temp_dir);
-    controller.read().insert_test_session(make_test_session(
-        "session-actionlist",
-        temp_dir.to_str().unwrap(),
-    ));
+    let session = make_fusion_session(&session_id, &std::env::temp_dir().to_string_lossy());
+    controller.write().insert_test_session(session);
+
+    let body = serde_json::json!({
+        "candidate_ids": []
+    });
 
     let response = app
         .oneshot(
             Request::builder()
                 .method("POST")
-                .uri("/api/actions/session.list")
+                .uri(format!("/api/sessions/{}/resolver/launch", session_id))
                 .header("content-type", "application/json")
-                .body(Body::from("{}"))
+                .body(Body::from(serde_json::to_string(&body).unwrap()))
                 .unwrap(),
         )
         .await
         .unwrap();
 
-    assert_eq!(response.status(), StatusCode::OK);
-    let body_bytes = axum::body::to_bytes(response.into_body(), usize::MAX)
-        .await
-        .unwrap();
-    let json: serde_json::Value = serde_json::from_slice(&body_bytes).unwrap();
-    assert_eq!(json.get("renderer").and_then(|v| v.as_str()), Some("table"));
-    let sessions = json
-        .get("data")
-        .and_then(|v| v.as_array())
-        .expect("session.list data returns an array");
-    assert!(sessions
-        .iter()
-        .any(|s| s.get("id").and_then(|v| v.as_str()) == Some("session-actionlist")));
-
-    let _ = std::fs::remove_dir_all(&temp_dir);
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
 }
 
 #[tokio::test]
-async fn test_same_handler_both_callers() {
-    use crate::actions::{build_registry, ActionContext, Caller};
-    use crate::coordination::InjectionManager;
-    use crate::events::EventBus;
-    use crate::pty::PtyManager;
-
-    // Build a hermetic state + controller and insert one session.
-    let storage = Arc::new(SessionStorage::new().unwrap());
-    let config = Arc::new(tokio::sync::RwLock::new(storage.load_config().unwrap()));
-    let pty_manager = Arc::new(RwLock::new(PtyManager::new()));
-    let session_controller = Arc::new(RwLock::new(SessionController::new(pty_manager.clone())));
-    session_controller.write().set_storage(storage.clone());
-    let injection_manager = Arc::new(RwLock::new(InjectionManager::new(
-        pty_manager.clone(),
-        SessionStorage::new().unwrap(),
-    )));
-    let event_bus = EventBus::new(storage.base_dir().clone());
-    let app_state_db = Arc::new(crate::storage::ApplicationStateDb::open_in_memory().unwrap());
-    let queue_repo = Arc::new(crate::storage::QueueRepo::new(app_state_db.clone()));
-    queue_repo.ensure_schema().unwrap();
-    let queue_manager = Arc::new(crate::coordination::QueueManager::new(
-        queue_repo,
-        event_bus.clone(),
-    ));
-    let state = Arc::new(AppState::new(
-        config,
-        pty_manager,
-        session_controller.clone(),
-        injection_manager,
-        storage,
-        event_bus,
-        app_state_db,
-        queue_manager,
-        None,
-    ));
-    state.set_registry(Arc::new(build_registry()));
-
-    let temp_dir = std::env::temp_dir().join("hive-test-action-both-callers");
-    let _ = std::fs::create_dir_all(&temp_dir);
-    session_controller
-        .read()
-        .insert_test_session(make_test_session(
-            "session-both",
-            temp_dir.to_str().unwrap(),
-        ));
+async fn test_resolver_launch_rejects_duplicate_candidate_ids() {
+    let (app, controller) = setup_test_app_with_controller().await;
+    let session_id = format!("resolver-duplicate-{}", uuid::Uuid::new_v4());
 
-    let registry = state.registry().clone();
+    let session = make_fusion_session(&session_id, &std::env::temp_dir().to_string_lossy());
+    controller.write().insert_test_session(session);
 
-    // Dispatch the SAME action with two different callers.
-    let frontend_ctx = ActionContext::new(Caller::Frontend, state.clone());
-    let http_ctx = ActionContext::new(Caller::Http, state.clone());
+    let body = serde_json::json!({
+        "candidate_ids": ["variant-a", "variant-a"]
+    });
 
-    let frontend_out = registry
-        .dispatch("session.list", &frontend_ctx, serde_json::json!({}))
-        .await
-        .unwrap();
-    let http_out = registry
-        .dispatch("session.list", &http_ctx, serde_json::json!({}))
+    let response = app
+        .oneshot(
+            Request::builder()
+                .method("POST")
+                .uri(format!("/api/sessions/{}/resolver/launch", session_id))
+                .header("content-type", "application/json")
+                .body(Body::from(serde_json::to_string(&body).unwrap()))
+                .unwrap(),
+        )
         .await
         .unwrap();
 
-    // Same handler, same payload regardless of caller (AC2).
-    assert_eq!(frontend_out, http_out);
-    assert!(frontend_out
-        .as_array()
-        .unwrap()
-        .iter()
-        .any(|s| s.get("id").and_then(|v| v.as_str()) == Some("session-both")));
-
-    let _ = std::fs::remove_dir_all(&temp_dir);
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
 }
 
 #[tokio::test]
-async fn test_http_action_validation_rejects_bad_color() {
-    let app = setup_test_app().await;
+async fn test_resolver_launch_rejects_unknown_candidate_ids() {
+    let (app, controller) = setup_test_app_with_controller().await;
+    let session_id = format!("resolver-unknown-{}", uuid::Uuid::new_v4());
+
+    let session = make_fusion_session(&session_id, &std::env::temp_dir().to_string_lossy());
+    controller.write().insert_test_session(session);
+
+    let body = serde_json::json!({
+        "candidate_ids": ["variant-a", "variant-c"]
+    });
 
-    // Invalid color must be rejected with 400 before the controller runs (AC3).
-    let body = serde_json::json!({ "id": "sess-x", "color": "purple" });
     let response = app
         .oneshot(
             Request::builder()
                 .method("POST")
-                .uri("/api/actions/session.update_metadata")
+                .uri(format!("/api/sessions/{}/resolver/launch", session_id))
                 .header("content-type", "application/json")
                 .body(Body::from(serde_json::to_string(&body).unwrap()))
                 .unwrap(),
@@ -7435,161 +8284,1006 @@ async fn test_http_action_validation_rejects_bad_color() {
     assert_eq!(response.status(), StatusCode::BAD_REQUEST);
 }
 
+// ---------------------------------------------------------------------------
+// Application-state (SQLite) HTTP endpoint tests (issue #124)
+// ---------------------------------------------------------------------------
+
+async fn read_json_body(response: axum::response::Response) -> serde_json::Value {
+    let bytes = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    serde_json::from_slice(&bytes).unwrap()
+}
+
 #[tokio::test]
-async fn test_http_action_unknown_returns_404() {
-    let app = setup_test_app().await;
+async fn test_post_application_state_returns_row() {
+    let (app, _controller) = setup_test_app_with_controller().await;
+
+    let body = serde_json::json!({
+        "key": "route",
+        "value": { "route": "dashboard" }
+    });
 
     let response = app
         .oneshot(
             Request::builder()
                 .method("POST")
-                .uri("/api/actions/does.not.exist")
+                .uri("/api/sessions/app-state-post/application-state")
                 .header("content-type", "application/json")
-                .body(Body::from("{}"))
+                .body(Body::from(serde_json::to_string(&body).unwrap()))
                 .unwrap(),
         )
         .await
         .unwrap();
 
-    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    assert_eq!(response.status(), StatusCode::OK);
+    let json = read_json_body(response).await;
+    assert_eq!(json["session_id"], "app-state-post");
+    assert_eq!(json["key"], "route");
+    assert_eq!(json["value"], serde_json::json!({ "route": "dashboard" }));
+    // Server stamps a positive millisecond timestamp.
+    assert!(json["updated_at"].as_i64().unwrap() > 0);
 }
 
 #[tokio::test]
-async fn test_conversation_emit_includes_render_envelope() {
-    let state = setup_test_state().await;
-    let mut events = state
-        .event_bus
-        .subscribe_session("session-render".to_string());
+async fn test_get_snapshot_returns_all() {
+    let (app, _controller) = setup_test_app_with_controller().await;
+    let session_id = "app-state-snapshot";
 
-    let table_message = ConversationMessage {
-        timestamp: chrono::Utc::now(),
-        from: "worker-3".to_string(),
-        content: "| file | status |\n| --- | --- |\n| src/lib.rs | changed |".to_string(),
-    };
-    state
-        .emit_conversation_message("session-render", "worker-3", &table_message)
+    for (key, value) in [("a", serde_json::json!(1)), ("b", serde_json::json!("two"))] {
+        let body = serde_json::json!({ "key": key, "value": value });
+        let resp = app
+            .clone()
+            .oneshot(
+                Request::builder()
+                    .method("POST")
+                    .uri(format!("/api/sessions/{session_id}/application-state"))
+                    .header("content-type", "application/json")
+                    .body(Body::from(serde_json::to_string(&body).unwrap()))
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(resp.status(), StatusCode::OK);
+    }
+
+    let response = app
+        .oneshot(
+            Request::builder()
+                .uri(format!("/api/sessions/{session_id}/application-state"))
+                .body(Body::empty())
+                .unwrap(),
+        )
         .await
         .unwrap();
 
-    let table_event = events.recv().await.unwrap();
-    assert_eq!(table_event.payload["renderer"], "table");
-    assert_eq!(
-        table_event.payload["data"]["content"],
-        table_message.content
-    );
-    assert_eq!(table_event.payload["data"]["agent_id"], "worker-3");
+    assert_eq!(response.status(), StatusCode::OK);
+    let json = read_json_body(response).await;
+    let rows = json.as_array().unwrap();
+    assert_eq!(rows.len(), 2);
+    // Ordered by key: a, then b. Values are parsed JSON, not strings.
+    assert_eq!(rows[0]["key"], "a");
+    assert_eq!(rows[0]["value"], serde_json::json!(1));
+    assert_eq!(rows[1]["key"], "b");
+    assert_eq!(rows[1]["value"], serde_json::json!("two"));
+}
 
-    let diff_message = ConversationMessage {
-        timestamp: chrono::Utc::now(),
-        from: "worker-3".to_string(),
-        content: "diff --git a/src/lib.rs b/src/lib.rs\n@@ -1 +1 @@\n-old\n+new".to_string(),
-    };
-    state
-        .emit_conversation_message("session-render", "worker-3", &diff_message)
+#[tokio::test]
+async fn test_get_poll_returns_changed_only() {
+    let (app, _controller) = setup_test_app_with_controller().await;
+    let session_id = "app-state-poll";
+
+    // Write two keys; capture the highest updated_at as the watermark.
+    let mut watermark = 0i64;
+    for key in ["a", "b"] {
+        let body = serde_json::json!({ "key": key, "value": key });
+        let resp = app
+            .clone()
+            .oneshot(
+                Request::builder()
+                    .method("POST")
+                    .uri(format!("/api/sessions/{session_id}/application-state"))
+                    .header("content-type", "application/json")
+                    .body(Body::from(serde_json::to_string(&body).unwrap()))
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        let row = read_json_body(resp).await;
+        watermark = watermark.max(row["updated_at"].as_i64().unwrap());
+    }
+
+    // Ensure the next write has a strictly greater millisecond timestamp.
+    tokio::time::sleep(std::time::Duration::from_millis(5)).await;
+
+    // Write a third key AFTER the watermark.
+    let body = serde_json::json!({ "key": "c", "value": "c" });
+    let resp = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .method("POST")
+                .uri(format!("/api/sessions/{session_id}/application-state"))
+                .header("content-type", "application/json")
+                .body(Body::from(serde_json::to_string(&body).unwrap()))
+                .unwrap(),
+        )
         .await
         .unwrap();
+    assert_eq!(resp.status(), StatusCode::OK);
 
-    let diff_event = events.recv().await.unwrap();
-    assert_eq!(diff_event.payload["renderer"], "diff");
-    assert_eq!(diff_event.payload["data"]["content"], diff_message.content);
+    // Poll with the watermark: only the third key should return.
+    let response = app
+        .oneshot(
+            Request::builder()
+                .uri(format!(
+                    "/api/sessions/{session_id}/application-state/poll?since={watermark}"
+                ))
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(response.status(), StatusCode::OK);
+    let json = read_json_body(response).await;
+    let rows = json.as_array().unwrap();
+    assert_eq!(rows.len(), 1, "watermark poll returns only newer rows");
+    assert_eq!(rows[0]["key"], "c");
+}
+
+#[tokio::test]
+async fn test_application_state_invalid_session_id_rejected() {
+    let (app, _controller) = setup_test_app_with_controller().await;
+
+    // Path-traversal-style session id must be rejected with 400 by validate_session_id.
+    // Use a backslash (encoded) which the handler checks for after axum decodes the path.
+    let response = app
+        .oneshot(
+            Request::builder()
+                .uri("/api/sessions/bad..id/application-state")
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+}
+
+#[tokio::test]
+async fn test_resolver_launch_non_fusion_session_returns_400() {
+    let (app, controller) = setup_test_app_with_controller().await;
+    let session_id = format!("resolver-hive-{}", uuid::Uuid::new_v4());
+
+    let session = make_test_session(&session_id, &std::env::temp_dir().to_string_lossy());
+    controller.write().insert_test_session(session);
+
+    let body = serde_json::json!({
+        "candidate_ids": ["variant-a"]
+    });
+
+    let response = app
+        .oneshot(
+            Request::builder()
+                .method("POST")
+                .uri(format!("/api/sessions/{}/resolver/launch", session_id))
+                .header("content-type", "application/json")
+                .body(Body::from(serde_json::to_string(&body).unwrap()))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+}
+
+// ---------------------------------------------------------------------------
+// #123 — unified Action contract: HTTP surface over the registry
+// ---------------------------------------------------------------------------
+
+#[tokio::test]
+async fn test_action_endpoint_lists_schemas() {
+    let app = setup_test_app().await;
+
+    let response = app
+        .oneshot(
+            Request::builder()
+                .uri("/api/actions")
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(response.status(), StatusCode::OK);
+    let body_bytes = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    let json: serde_json::Value = serde_json::from_slice(&body_bytes).unwrap();
+    let actions = json.get("actions").unwrap().as_array().unwrap();
+
+    let names: Vec<&str> = actions
+        .iter()
+        .map(|a| a.get("name").unwrap().as_str().unwrap())
+        .collect();
+    assert!(names.contains(&"session.launch_hive_v2"));
+    assert!(names.contains(&"session.list"));
+    assert!(names.contains(&"git.pull"));
+
+    // Every descriptor carries a non-empty input_schema object.
+    for a in actions {
+        assert!(
+            a.get("input_schema").unwrap().is_object(),
+            "input_schema should be an object for {}",
+            a.get("name").unwrap()
+        );
+    }
+}
+
+#[tokio::test]
+async fn test_http_dispatch_session_list_via_registry() {
+    let (app, controller) = setup_test_app_with_controller().await;
+
+    let temp_dir = std::env::temp_dir().join("hive-test-action-session-list");
+    let _ = std::fs::create_dir_all(&temp_dir);
+    controller.read().insert_test_session(make_test_session(
+        "session-actionlist",
+        temp_dir.to_str().unwrap(),
+    ));
+
+    let response = app
+        .oneshot(
+            Request::builder()
+                .method("POST")
+                .uri("/api/actions/session.list")
+                .header("content-type", "application/json")
+                .body(Body::from("{}"))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(response.status(), StatusCode::OK);
+    let body_bytes = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    let json: serde_json::Value = serde_json::from_slice(&body_bytes).unwrap();
+    assert_eq!(json.get("renderer").and_then(|v| v.as_str()), Some("table"));
+    let sessions = json
+        .get("data")
+        .and_then(|v| v.as_array())
+        .expect("session.list data returns an array");
+    assert!(sessions
+        .iter()
+        .any(|s| s.get("id").and_then(|v| v.as_str()) == Some("session-actionlist")));
+
+    let _ = std::fs::remove_dir_all(&temp_dir);
+}
+
+#[tokio::test]
+async fn test_same_handler_both_callers() {
+    use crate::actions::{build_registry, ActionContext, Caller};
+    use crate::coordination::InjectionManager;
+    use crate::events::EventBus;
+    use crate::pty::PtyManager;
+
+    // Build a hermetic state + controller and insert one session.
+    let storage = Arc::new(SessionStorage::new().unwrap());
+    let config = Arc::new(tokio::sync::RwLock::new(storage.load_config().unwrap()));
+    let pty_manager = Arc::new(RwLock::new(PtyManager::new()));
+    let session_controller = Arc::new(RwLock::new(SessionController::new(pty_manager.clone())));
+    session_controller.write().set_storage(storage.clone());
+    let injection_manager = Arc::new(RwLock::new(InjectionManager::new(
+        pty_manager.clone(),
+        SessionStorage::new().unwrap(),
+    )));
+    let event_bus = EventBus::new(storage.base_dir().clone());
+    let app_state_db = Arc::new(crate::storage::ApplicationStateDb::open_in_memory().unwrap());
+    let queue_repo = Arc::new(crate::storage::QueueRepo::new(app_state_db.clone()));
+    queue_repo.ensure_schema().unwrap();
+    let queue_manager = Arc::new(crate::coordination::QueueManager::new(
+        queue_repo,
+        event_bus.clone(),
+    ));
+    let state = Arc::new(
+        AppState::new(
+            config,
+            pty_manager,
+            session_controller.clone(),
+            injection_manager,
+            storage,
+            event_bus,
+            app_state_db,
+            queue_manager,
+            None,
+        )
+        .with_ui_api_token(TEST_UI_TOKEN),
+    );
+    state.set_registry(Arc::new(build_registry()));
+
+    let temp_dir = std::env::temp_dir().join("hive-test-action-both-callers");
+    let _ = std::fs::create_dir_all(&temp_dir);
+    session_controller
+        .read()
+        .insert_test_session(make_test_session(
+            "session-both",
+            temp_dir.to_str().unwrap(),
+        ));
+
+    let registry = state.registry().clone();
+
+    // Dispatch the SAME action with two different callers.
+    let frontend_ctx = ActionContext::new(Caller::Frontend, state.clone());
+    let http_ctx = ActionContext::new(Caller::Http, state.clone());
+
+    let frontend_out = registry
+        .dispatch("session.list", &frontend_ctx, serde_json::json!({}))
+        .await
+        .unwrap();
+    let http_out = registry
+        .dispatch("session.list", &http_ctx, serde_json::json!({}))
+        .await
+        .unwrap();
+
+    // Same handler, same payload regardless of caller (AC2).
+    assert_eq!(frontend_out, http_out);
+    assert!(frontend_out
+        .as_array()
+        .unwrap()
+        .iter()
+        .any(|s| s.get("id").and_then(|v| v.as_str()) == Some("session-both")));
+
+    let _ = std::fs::remove_dir_all(&temp_dir);
+}
+
+#[tokio::test]
+async fn test_http_action_validation_rejects_bad_color() {
+    let app = setup_test_app().await;
+
+    // Invalid color must be rejected with 400 before the controller runs (AC3).
+    let body = serde_json::json!({ "id": "sess-x", "color": "purple" });
+    let response = app
+        .oneshot(
+            Request::builder()
+                .method("POST")
+                .uri("/api/actions/session.update_metadata")
+                .header("content-type", "application/json")
+                .body(Body::from(serde_json::to_string(&body).unwrap()))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+}
+
+#[tokio::test]
+async fn test_http_action_unknown_returns_404() {
+    let app = setup_test_app().await;
+
+    let response = app
+        .oneshot(
+            Request::builder()
+                .method("POST")
+                .uri("/api/actions/does.not.exist")
+                .header("content-type", "application/json")
+                .body(Body::from("{}"))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn test_conversation_emit_includes_render_envelope() {
+    let state = setup_test_state().await;
+    let mut events = state
+        .event_bus
+        .subscribe_session("session-render".to_string());
+
+    let table_message = ConversationMessage {
+        timestamp: chrono::Utc::now(),
+        from: "worker-3".to_string(),
+        content: "| file | status |\n| --- | --- |\n| src/lib.rs | changed |".to_string(),
+    };
+    state
+        .emit_conversation_message("session-render", "worker-3", &table_message)
+        .await
+        .unwrap();
+
+    let table_event = events.recv().await.unwrap();
+    assert_eq!(table_event.payload["renderer"], "table");
+    assert_eq!(
+        table_event.payload["data"]["content"],
+        table_message.content
+    );
+    assert_eq!(table_event.payload["data"]["agent_id"], "worker-3");
+
+    let diff_message = ConversationMessage {
+        timestamp: chrono::Utc::now(),
+        from: "worker-3".to_string(),
+        content: "diff --git a/src/lib.rs b/src/lib.rs\n@@ -1 +1 @@\n-old\n+new".to_string(),
+    };
+    state
+        .emit_conversation_message("session-render", "worker-3", &diff_message)
+        .await
+        .unwrap();
+
+    let diff_event = events.recv().await.unwrap();
+    assert_eq!(diff_event.payload["renderer"], "diff");
+    assert_eq!(diff_event.payload["data"]["content"], diff_message.content);
+}
+
+// ---- #125 run-journal endpoint ----
+
+#[tokio::test]
+async fn test_get_run_journal_empty_returns_ok() {
+    // A session with no journaled steps returns 200 with empty arrays (routing +
+    // serialization smoke).
+    let (app, _controller) = setup_test_app_with_controller().await;
+
+    let response = app
+        .oneshot(
+            Request::builder()
+                .uri("/api/sessions/run-journal-empty/run-journal")
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(response.status(), StatusCode::OK);
+    let json = read_json_body(response).await;
+    assert_eq!(json["journal"].as_array().unwrap().len(), 0);
+    assert_eq!(json["ledger"].as_array().unwrap().len(), 0);
+}
+
+#[tokio::test]
+async fn test_get_run_journal_returns_seeded_entries() {
+    use crate::domain::run_journal::{Confidence, StepKind, StepStatus};
+
+    // Use an on-disk DB so the seeding store and the HTTP app share the same file.
+    let temp = TempDir::new().unwrap();
+    let (app, _controller, storage) =
+        setup_test_app_with_controller_at(temp.path().to_path_buf()).await;
+
+    let db = Arc::new(crate::storage::ApplicationStateDb::open(storage.base_dir()).unwrap());
+    let store = crate::storage::RunJournalStore::new(db);
+    store.ensure_schema().unwrap();
+
+    let run_id = "run-journal-seeded";
+    let step_id = store
+        .record_step_started(run_id, StepKind::GitCommit, 1, Some("worker-1"))
+        .unwrap();
+    store
+        .record_ledger(
+            run_id,
+            &step_id,
+            "git_commit",
+            Some("abc123"),
+            Confidence::High,
+        )
+        .unwrap();
+    store
+        .confirm_ledger(run_id, &step_id, None, Confidence::High)
+        .unwrap();
+    store
+        .record_step_finished(run_id, &step_id, StepStatus::Completed)
+        .unwrap();
+
+    let response = app
+        .oneshot(
+            Request::builder()
+                .uri(format!("/api/sessions/{run_id}/run-journal"))
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(response.status(), StatusCode::OK);
+    let json = read_json_body(response).await;
+    let journal = json["journal"].as_array().unwrap();
+    assert_eq!(journal.len(), 1);
+    assert_eq!(journal[0]["kind"], "git_commit");
+    assert_eq!(journal[0]["status"], "completed");
+    let ledger = json["ledger"].as_array().unwrap();
+    assert_eq!(ledger.len(), 1);
+    assert_eq!(ledger[0]["effect_ref"], "abc123");
+    assert_eq!(ledger[0]["confirmed"], true);
+    assert_eq!(ledger[0]["confidence"], "high");
+}
+
+#[tokio::test]
+async fn test_get_run_journal_rejects_bad_session_id() {
+    let (app, _controller) = setup_test_app_with_controller().await;
+
+    let response = app
+        .oneshot(
+            Request::builder()
+                .uri("/api/sessions/..%2Fevil/run-journal")
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+
+    // Axum decodes %2F; the traversal token is rejected by validate_session_id.
+    assert_ne!(response.status(), StatusCode::OK);
+}
+
+#[tokio::test]
+async fn test_get_coordination_stats_summarizes_appended_messages() {
+    use crate::coordination::CoordinationMessage;
+
+    let temp = TempDir::new().unwrap();
+    let (app, _controller, storage) =
+        setup_test_app_with_controller_at(temp.path().to_path_buf()).await;
+
+    let session_id = "coordination-stats";
+    for message in [
+        CoordinationMessage::task("QUEEN", "WORKER-1", "Add the endpoint"),
+        CoordinationMessage::system("QUEEN", "WORKER-1 started"),
+        CoordinationMessage::new(
+            "WORKER-1",
+            "QUEEN",
+            "COMPLETED: endpoint added",
+            crate::coordination::MessageType::Completion,
+        ),
+        CoordinationMessage::new(
+            "WORKER-2",
+            "QUEEN",
+            "ERROR: build failed",
+            crate::coordination::MessageType::Error,
+        ),
+    ] {
+        storage.append_coordination_log(session_id, &message).unwrap();
+    }
+
+    let response = app
+        .oneshot(
+            Request::builder()
+                .uri(format!("/api/sessions/{session_id}/coordination/stats"))
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(response.status(), StatusCode::OK);
+    let json = read_json_body(response).await;
+    assert_eq!(json["total_messages"], 4);
+    assert_eq!(json["by_type"]["TASK"], 1);
+    assert_eq!(json["by_type"]["COMPLETION"], 1);
+    assert_eq!(json["by_sender"]["QUEEN"], 1);
+    assert_eq!(json["error_rate"], 0.25);
+    assert_eq!(json["workers"]["WORKER-1"]["completions"], 1);
+    assert!(json["workers"]["WORKER-1"]["avg_task_secs"].is_number());
+    assert_eq!(json["workers"]["WORKER-2"]["errors"], 1);
+}
+
+#[tokio::test]
+async fn test_coordination_log_cursor_returns_only_new_messages() {
+    use crate::coordination::CoordinationMessage;
+
+    let temp = TempDir::new().unwrap();
+    let (app, _controller, storage) =
+        setup_test_app_with_controller_at(temp.path().to_path_buf()).await;
+    let session_id = "coordination-cursor";
+    for content in ["one", "two", "three"] {
+        let message = CoordinationMessage::task("QUEEN", "WORKER-1", content);
+        storage.append_coordination_log(session_id, &message).unwrap();
+    }
+
+    let read = |query: String| {
+        let app = app.clone();
+        async move {
+            let response = app
+                .oneshot(
+                    Request::builder()
+                        .uri(format!("/api/sessions/{session_id}/coordination{query}"))
+                        .body(Body::empty())
+                        .unwrap(),
+                )
+                .await
+                .unwrap();
+            assert_eq!(response.status(), StatusCode::OK);
+            read_json_body(response).await
+        }
+    };
+
+    let page = read("?after_seq=0".to_string()).await;
+    assert_eq!(page["messages"].as_array().unwrap().len(), 3);
+    assert_eq!(page["cursor"], 3);
+
+    let page = read("?after_seq=3".to_string()).await;
+    assert!(page["messages"].as_array().unwrap().is_empty());
+    assert_eq!(page["cursor"], 3);
+
+    let message = CoordinationMessage::task("QUEEN", "WORKER-1", "four");
+    storage.append_coordination_log(session_id, &message).unwrap();
+    let page = read("?after_seq=3".to_string()).await;
+    assert_eq!(page["messages"].as_array().unwrap().len(), 1);
+    assert_eq!(page["messages"][0]["content"], "four");
+    assert_eq!(page["cursor"], 4);
+
+    let page = read("?limit=2".to_string()).await;
+    assert_eq!(page["messages"][0]["content"], "three");
+    assert_eq!(page["cursor"], 4);
+}
+
+#[tokio::test]
+async fn test_uploaded_attachment_is_referenced_from_the_log_and_downloadable() {
+    use crate::coordination::CoordinationMessage;
+
+    let temp = TempDir::new().unwrap();
+    let (app, _controller, storage) =
+        setup_test_app_with_controller_at(temp.path().to_path_buf()).await;
+    let session_id = "coordination-attachments";
+    storage.create_session_dir(session_id).unwrap();
+
+    let body = "--hive\r\n\
+        Content-Disposition: form-data; name=\"file\"; filename=\"fix.diff\"\r\n\
+        Content-Type: text/x-diff\r\n\r\n\
+        -old\n+new\n\r\n\
+        --hive--\r\n";
+    let response = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .method("POST")
+                .uri(format!("/api/sessions/{session_id}/coordination/attachments"))
+                .header("Content-Type", "multipart/form-data; boundary=hive")
+                .body(Body::from(body))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let json = read_json_body(response).await;
+    let attachment_id = json["id"].as_str().unwrap().to_string();
+    assert!(attachment_id.ends_with("-fix.diff"));
+    assert_eq!(json["size"], 10);
+
+    let mut message = CoordinationMessage::task("WORKER-1", "QUEEN", "See the attached diff");
+    message.attachments = vec![attachment_id.clone()];
+    storage.append_coordination_log(session_id, &message).unwrap();
+    let logged = storage.read_coordination_log(session_id, None).unwrap();
+    assert_eq!(
+        logged[0].content,
+        format!("See the attached diff [ATTACH {attachment_id}]")
+    );
+    assert_eq!(logged[0].attachments, vec![attachment_id.clone()]);
+
+    let response = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .uri(format!(
+                    "/api/sessions/{session_id}/coordination/attachments/{attachment_id}"
+                ))
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    assert!(response.headers()["content-type"]
+        .to_str()
+        .unwrap()
+        .starts_with("text/plain"));
+    let bytes = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    assert_eq!(&bytes[..], b"-old\n+new\n");
+
+    let response = app
+        .oneshot(
+            Request::builder()
+                .method("POST")
+                .uri("/api/sessions/no-such-session/coordination/attachments")
+                .header("Content-Type", "multipart/form-data; boundary=hive")
+                .body(Body::from(body))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn test_scratchpad_entries_are_shared_by_key() {
+    let temp = TempDir::new().unwrap();
+    let (app, _controller, storage) =
+        setup_test_app_with_controller_at(temp.path().to_path_buf()).await;
+    let session_id = "scratchpad-session";
+    storage.create_session_dir(session_id).unwrap();
+
+    let put = |uri: String, value: &str| {
+        Request::builder()
+            .method("PUT")
+            .uri(uri)
+            .header("Content-Type", "application/json")
+            .header(AGENT_ID_HEADER, "scratchpad-session-worker-1")
+            .body(Body::from(serde_json::json!({ "value": value }).to_string()))
+            .unwrap()
+    };
+    let get = |uri: String| Request::builder().uri(uri).body(Body::empty()).unwrap();
+
+    let response = app
+        .clone()
+        .oneshot(put(
+            format!("/api/sessions/{session_id}/scratchpad/api-contract"),
+            "POST /users {name} -> 201",
+        ))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let json = read_json_body(response).await;
+    assert_eq!(json["updated_by"], "scratchpad-session-worker-1");
+
+    let response = app
+        .clone()
+        .oneshot(get(format!("/api/sessions/{session_id}/scratchpad/api-contract")))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(read_json_body(response).await["value"], "POST /users {name} -> 201");
+
+    let response = app
+        .clone()
+        .oneshot(get(format!("/api/sessions/{session_id}/scratchpad")))
+        .await
+        .unwrap();
+    let json = read_json_body(response).await;
+    assert_eq!(json.as_array().unwrap().len(), 1);
+    assert_eq!(json[0]["key"], "api-contract");
+
+    let response = app
+        .clone()
+        .oneshot(get(format!("/api/sessions/{session_id}/scratchpad/missing")))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+    let response = app
+        .clone()
+        .oneshot(put(format!("/api/sessions/{session_id}/scratchpad/.hidden"), "x"))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+    let response = app
+        .oneshot(put("/api/sessions/no-such-session/scratchpad/api-contract".to_string(), "x"))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn test_view_coordination_log_renders_filtered_escaped_html() {
+    use crate::coordination::CoordinationMessage;
+
+    let temp = TempDir::new().unwrap();
+    let (app, _controller, storage) =
+        setup_test_app_with_controller_at(temp.path().to_path_buf()).await;
+
+    let session_id = "coordination-view";
+    for message in [
+        CoordinationMessage::task("QUEEN", "WORKER-1", "Fix <script>alert(1)</script>"),
+        CoordinationMessage::new(
+            "WORKER-2",
+            "QUEEN",
+            "ERROR: build failed",
+            crate::coordination::MessageType::Error,
+        ),
+    ] {
+        storage
+            .append_coordination_log(session_id, &message)
+            .unwrap();
+    }
+
+    let response = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .uri(format!("/api/sessions/{session_id}/coordination/view"))
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    assert!(response.headers()["content-type"]
+        .to_str()
+        .unwrap()
+        .starts_with("text/html"));
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    let html = String::from_utf8(body.to_vec()).unwrap();
+    assert!(html.contains("Fix &lt;script&gt;alert(1)&lt;/script&gt;"));
+    assert!(!html.contains("<script>"));
+    assert!(html.contains("http-equiv=\"refresh\" content=\"10\""));
+    // Newest first.
+    assert!(html.find("build failed").unwrap() < html.find("Fix &lt;script").unwrap());
+
+    let response = app
+        .oneshot(
+            Request::builder()
+                .uri(format!(
+                    "/api/sessions/{session_id}/coordination/view?kind=error&refresh=0"
+                ))
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    let html = String::from_utf8(body.to_vec()).unwrap();
+    assert!(html.contains("ERROR: build failed"));
+    assert!(!html.contains("alert(1)"));
+    assert!(html.contains("1 of the last 2 messages"));
+    assert!(!html.contains("http-equiv"));
+}
+
+#[tokio::test]
+async fn test_get_session_durations_reports_phase_totals() {
+    let session_id = "session-durations";
+    let (_storage_dir, _project_dir, app, _storage, session_root) =
+        setup_session_files_fixture(session_id).await;
+    crate::session::durations::record(
+        &session_root,
+        Some("Planning".to_string()),
+        [],
+        chrono::Utc::now() - chrono::Duration::minutes(2),
+    )
+    .unwrap();
+
+    let response = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .uri(format!("/api/sessions/{session_id}/durations"))
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let json = read_json_body(response).await;
+    assert_eq!(json["session_type"], "Hive");
+    assert_eq!(json["phases"][0]["phase"], "Planning");
+    assert!(json["phase_totals"]["Planning"].as_i64().unwrap() >= 120);
+    assert!(json["total_secs"].as_i64().unwrap() >= 120);
+
+    let response = app
+        .oneshot(
+            Request::builder()
+                .uri("/api/sessions/no-such-session/durations")
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
 }
 
-// ---- #125 run-journal endpoint ----
-
 #[tokio::test]
-async fn test_get_run_journal_empty_returns_ok() {
-    // A session with no journaled steps returns 200 with empty arrays (routing +
-    // serialization smoke).
-    let (app, _controller) = setup_test_app_with_controller().await;
+async fn test_session_slug_is_accepted_in_place_of_the_id() {
+    let session_id = "5f0c7a52-3c1e-4b8e-9d0a-2f6e1c9b7d44";
+    let slug = crate::session::slug::slug_for(session_id);
+    let (_storage_dir, _project_dir, app, _storage, _session_root) =
+        setup_session_files_fixture(session_id).await;
 
     let response = app
+        .clone()
         .oneshot(
             Request::builder()
-                .uri("/api/sessions/run-journal-empty/run-journal")
-                .body(Body::empty())
+                .method("PATCH")
+                .uri(format!("/api/sessions/{slug}?source=test"))
+                .header("content-type", "application/json")
+                .body(Body::from(r#"{"name":"Renamed"}"#))
                 .unwrap(),
         )
         .await
         .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let json = read_json_body(response).await;
+    assert_eq!(json["id"], session_id);
+    assert_eq!(json["slug"], slug.as_str());
+    assert_eq!(json["name"], "Renamed");
 
+    let body = serde_json::json!({ "id": slug, "name": "Renamed again" });
+    let response = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .method("POST")
+                .uri("/api/actions/session.update_metadata")
+                .header("content-type", "application/json")
+                .body(Body::from(serde_json::to_string(&body).unwrap()))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
     assert_eq!(response.status(), StatusCode::OK);
     let json = read_json_body(response).await;
-    assert_eq!(json["journal"].as_array().unwrap().len(), 0);
-    assert_eq!(json["ledger"].as_array().unwrap().len(), 0);
+    assert_eq!(json["id"], session_id);
+    assert_eq!(json["name"], "Renamed again");
+
+    // A slug no session has is reported as an unknown session.
+    let unknown = crate::session::slug::slug_for("some-other-session");
+    assert_ne!(unknown, slug);
+    let response = app
+        .oneshot(
+            Request::builder()
+                .uri(format!("/api/sessions/{unknown}/durations"))
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
 }
 
 #[tokio::test]
-async fn test_get_run_journal_returns_seeded_entries() {
-    use crate::domain::run_journal::{Confidence, StepKind, StepStatus};
-
-    // Use an on-disk DB so the seeding store and the HTTP app share the same file.
-    let temp = TempDir::new().unwrap();
-    let (app, _controller, storage) =
-        setup_test_app_with_controller_at(temp.path().to_path_buf()).await;
-
-    let db = Arc::new(crate::storage::ApplicationStateDb::open(storage.base_dir()).unwrap());
-    let store = crate::storage::RunJournalStore::new(db);
-    store.ensure_schema().unwrap();
-
-    let run_id = "run-journal-seeded";
-    let step_id = store
-        .record_step_started(run_id, StepKind::GitCommit, 1, Some("worker-1"))
-        .unwrap();
-    store
-        .record_ledger(
-            run_id,
-            &step_id,
-            "git_commit",
-            Some("abc123"),
-            Confidence::High,
+async fn test_compare_sessions_reports_phase_deltas() {
+    let (_storage_dir, app, controller, _storage) = setup_isolated_test_app_with_controller().await;
+    let project_dir = TempDir::new().unwrap();
+    let project_path = project_dir.path().to_string_lossy().to_string();
+    let started = chrono::Utc::now() - chrono::Duration::minutes(10);
+    for (session_id, planning_mins) in [("compare-a", 4), ("compare-b", 1)] {
+        let session_root = project_dir.path().join(".hive-manager").join(session_id);
+        std::fs::create_dir_all(&session_root).unwrap();
+        crate::session::durations::record(&session_root, Some("Planning".to_string()), [], started)
+            .unwrap();
+        crate::session::durations::record(
+            &session_root,
+            Some("Running".to_string()),
+            [],
+            started + chrono::Duration::minutes(planning_mins),
         )
         .unwrap();
-    store
-        .confirm_ledger(run_id, &step_id, None, Confidence::High)
-        .unwrap();
-    store
-        .record_step_finished(run_id, &step_id, StepStatus::Completed)
-        .unwrap();
+        controller
+            .read()
+            .insert_test_session(make_test_session(session_id, &project_path));
+    }
 
     let response = app
+        .clone()
         .oneshot(
             Request::builder()
-                .uri(format!("/api/sessions/{run_id}/run-journal"))
+                .uri("/api/sessions/compare?a=compare-a&b=compare-b")
                 .body(Body::empty())
                 .unwrap(),
         )
         .await
         .unwrap();
-
     assert_eq!(response.status(), StatusCode::OK);
     let json = read_json_body(response).await;
-    let journal = json["journal"].as_array().unwrap();
-    assert_eq!(journal.len(), 1);
-    assert_eq!(journal[0]["kind"], "git_commit");
-    assert_eq!(journal[0]["status"], "completed");
-    let ledger = json["ledger"].as_array().unwrap();
-    assert_eq!(ledger.len(), 1);
-    assert_eq!(ledger[0]["effect_ref"], "abc123");
-    assert_eq!(ledger[0]["confirmed"], true);
-    assert_eq!(ledger[0]["confidence"], "high");
-}
-
-#[tokio::test]
-async fn test_get_run_journal_rejects_bad_session_id() {
-    let (app, _controller) = setup_test_app_with_controller().await;
+    assert_eq!(json["a"]["session_id"], "compare-a");
+    assert_eq!(json["a"]["phase_totals"]["Planning"], 240);
+    assert_eq!(json["phase_delta_secs"]["Planning"], -180);
+    assert_eq!(json["same_project"], true);
+    assert!(json["b"]["cost_usd"].is_null());
 
     let response = app
         .oneshot(
             Request::builder()
-                .uri("/api/sessions/..%2Fevil/run-journal")
+                .uri("/api/sessions/compare?a=compare-a&b=no-such-session")
                 .body(Body::empty())
                 .unwrap(),
         )
         .await
         .unwrap();
-
-    // Axum decodes %2F; the traversal token is rejected by validate_session_id.
-    assert_ne!(response.status(), StatusCode::OK);
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
 }
 
 // ----------------------------------------------------------------------------
@@ -7813,3 +9507,332 @@ async fn test_queue_endpoint_rejects_bad_session_id() {
         .unwrap();
     assert_ne!(response.status(), StatusCode::OK);
 }
+
+#[tokio::test]
+async fn test_agent_api_calls_are_logged_per_session() {
+    let temp = TempDir::new().unwrap();
+    let (app, controller, storage) =
+        setup_test_app_with_controller_at(temp.path().to_path_buf()).await;
+    let session_id = "api-usage";
+    controller
+        .read()
+        .insert_test_session(make_test_session_with_agents(
+            session_id,
+            temp.path().to_str().unwrap(),
+            &["worker-1"],
+        ));
+    storage.create_session_dir(session_id).unwrap();
+    let token = controller.read().session_api_token(session_id);
+
+    let heartbeat = |headers: &[(&'static str, &str)]| {
+        let mut request = Request::builder()
+            .method("POST")
+            .uri(format!("/api/sessions/{session_id}/heartbeat"))
+            .header("Content-Type", "application/json");
+        for (name, value) in headers {
+            request = request.header(*name, *value);
+        }
+        request
+            .body(Body::from(r#"{"agent_id":"worker-1","status":"working"}"#))
+            .unwrap()
+    };
+    let response = app
+        .clone()
+        .oneshot(heartbeat(&[
+            (SESSION_TOKEN_HEADER, &token),
+            (AGENT_ID_HEADER, "worker-1"),
+        ]))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let response = app.clone().oneshot(heartbeat(&[])).await.unwrap();
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+    // The app's own UI is not counted.
+    let response = app
+        .clone()
+        .oneshot(heartbeat(&[
+            ("Origin", "tauri://localhost"),
+            (UI_TOKEN_HEADER, TEST_UI_TOKEN),
+        ]))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let response = app
+        .oneshot(
+            Request::builder()
+                .uri(format!("/api/sessions/{session_id}/api-usage"))
+                .header("Origin", "tauri://localhost")
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    let summary: serde_json::Value = serde_json::from_slice(&body).unwrap();
+    assert_eq!(summary["total_calls"], 2);
+    assert_eq!(
+        summary["by_endpoint"]["POST /api/sessions/{id}/heartbeat"],
+        2
+    );
+    assert_eq!(summary["agents"]["worker-1"]["calls"], 1);
+    assert_eq!(summary["agents"]["worker-1"]["errors"], 0);
+    assert_eq!(summary["agents"]["(unidentified)"]["errors"], 1);
+}
+
+#[tokio::test]
+async fn test_add_planner_refuses_a_domain_waiting_on_another() {
+    let (_storage_dir, app, controller, _storage) = setup_isolated_test_app_with_controller().await;
+    let project_dir = TempDir::new().unwrap();
+    let session_root = project_dir.path().join(".hive-manager").join("swarm-order");
+    std::fs::create_dir_all(&session_root).unwrap();
+    std::fs::write(
+        session_root.join("plan.md"),
+        "# Plan\n\n### Domain 1: Backend\n- [ ] Add the API -> Planner 1\n\n\
+         ### Domain 2: Frontend\n- [ ] Add the page -> Planner 2\n\n\
+         ## Cross-Domain Dependencies\n- Domain 2 depends on Domain 1\n",
+    )
+    .unwrap();
+    let mut session = make_test_session("swarm-order", &project_dir.path().to_string_lossy());
+    session.session_type = SessionType::Swarm { planner_count: 2 };
+    controller.read().insert_test_session(session);
+
+    assert!(controller
+        .read()
+        .domain_blockers("swarm-order", "Backend")
+        .is_empty());
+    let response = app
+        .oneshot(
+            Request::builder()
+                .method("POST")
+                .uri("/api/sessions/swarm-order/planners")
+                .header("content-type", "application/json")
+                .body(Body::from(r#"{"domain": "Frontend"}"#))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::CONFLICT);
+    let json = read_json_body(response).await;
+    assert_eq!(json["waiting_on"], serde_json::json!(["Backend"]));
+}
+
+#[tokio::test]
+async fn test_open_file_refuses_paths_outside_the_session_project() {
+    let session_id = "open-file-session";
+    let (_storage_dir, _project_dir, app, _storage, _session_root) =
+        setup_session_files_fixture(session_id).await;
+    let elsewhere = TempDir::new().unwrap();
+    let outside = elsewhere.path().join("outside.rs");
+    std::fs::write(&outside, "fn main() {}\n").unwrap();
+
+    let cases = [
+        (
+            serde_json::json!({ "path": "../outside.rs", "session_id": session_id }),
+            StatusCode::BAD_REQUEST,
+        ),
+        (
+            serde_json::json!({ "path": outside, "session_id": session_id }),
+            StatusCode::BAD_REQUEST,
+        ),
+        (
+            serde_json::json!({ "path": "src/missing.rs", "session_id": session_id }),
+            StatusCode::NOT_FOUND,
+        ),
+        (serde_json::json!({ "path": "src/main.rs" }), StatusCode::BAD_REQUEST),
+        (
+            serde_json::json!({ "path": "src/main.rs", "session_id": "no-such-session" }),
+            StatusCode::NOT_FOUND,
+        ),
+    ];
+    for (body, status) in cases {
+        let response = app
+            .clone()
+            .oneshot(
+                Request::builder()
+                    .method("POST")
+                    .uri("/api/open-file")
+                    .header("content-type", "application/json")
+                    .body(Body::from(serde_json::to_string(&body).unwrap()))
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(response.status(), status, "{body}");
+    }
+}
+
+#[tokio::test]
+async fn test_add_worker_is_refused_over_the_spawn_quota_until_overridden() {
+    let (app, controller) = setup_test_app_with_controller().await;
+    let temp_dir = TempDir::new().unwrap();
+    controller.read().insert_test_session(make_test_session(
+        "session-quota",
+        temp_dir.path().to_str().unwrap(),
+    ));
+    let quota = crate::session::SpawnQuota {
+        max_workers: None,
+        per_role: [("reviewer".to_string(), 0)].into_iter().collect(),
+    };
+    controller
+        .read()
+        .override_spawn_quota("session-quota", quota)
+        .unwrap();
+
+    let add_reviewer = || {
+        Request::builder()
+            .method("POST")
+            .uri("/api/sessions/session-quota/workers")
+            .header("content-type", "application/json")
+            .body(Body::from(r#"{"role_type":"Reviewer","cli":"claude"}"#))
+            .unwrap()
+    };
+    let response = app.clone().oneshot(add_reviewer()).await.unwrap();
+    assert_eq!(response.status(), StatusCode::CONFLICT);
+    let json = read_json_body(response).await;
+    assert_eq!(json["role_type"], "reviewer");
+    assert_eq!(json["limit"], 0);
+    assert!(json["error"].as_str().unwrap_or_default().contains("quota"), "{json}");
+
+    controller
+        .read()
+        .override_spawn_quota("session-quota", Default::default())
+        .unwrap();
+    let response = app.oneshot(add_reviewer()).await.unwrap();
+    assert_ne!(response.status(), StatusCode::CONFLICT);
+}
+
+#[tokio::test]
+async fn test_full_session_snapshot_returns_the_session_and_plan_progress() {
+    let (_storage_dir, app, controller, _storage) = setup_isolated_test_app_with_controller().await;
+    let project_dir = TempDir::new().unwrap();
+    let session_root = project_dir.path().join(".hive-manager").join("session-full");
+    std::fs::create_dir_all(&session_root).unwrap();
+    std::fs::write(
+        session_root.join("plan.md"),
+        "# Plan\n\n## Tasks\n- [x] Add the API -> worker-1\n- [ ] Add the page -> worker-2\n",
+    )
+    .unwrap();
+    controller.read().insert_test_session(make_test_session(
+        "session-full",
+        &project_dir.path().to_string_lossy(),
+    ));
+
+    let snapshot_request = |id: &str| {
+        Request::builder()
+            .method("POST")
+            .uri("/api/actions/session.get_full_snapshot")
+            .header("content-type", "application/json")
+            .body(Body::from(serde_json::json!({ "id": id }).to_string()))
+            .unwrap()
+    };
+    let response = app.clone().oneshot(snapshot_request("session-full")).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let json = read_json_body(response).await;
+    let snapshot = &json["data"];
+    assert_eq!(snapshot["session"]["id"], "session-full");
+    assert_eq!(snapshot["plan"]["tasks_done"], 1);
+    assert_eq!(snapshot["plan"]["tasks_total"], 2);
+    assert_eq!(snapshot["plan"]["plan"]["tasks"][1]["title"], "Add the page");
+    assert_eq!(snapshot["pending_injections"], serde_json::json!([]));
+    assert_eq!(snapshot["coordination"], serde_json::json!([]));
+
+    let response = app.oneshot(snapshot_request("no-such-session")).await.unwrap();
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn test_changed_sessions_are_persisted_once_the_debounce_passes() {
+    let (_storage_dir, _app, controller, storage) = setup_isolated_test_app_with_controller().await;
+    let project_dir = TempDir::new().unwrap();
+    controller.read().insert_test_session(make_test_session(
+        "session-unsaved",
+        &project_dir.path().to_string_lossy(),
+    ));
+    assert!(storage.load_session("session-unsaved").is_err());
+
+    controller.read().emit_session_update("session-unsaved");
+    assert_eq!(
+        controller
+            .read()
+            .persist_unsaved_sessions(std::time::Duration::from_secs(60)),
+        0
+    );
+    assert_eq!(
+        controller
+            .read()
+            .persist_unsaved_sessions(std::time::Duration::ZERO),
+        1
+    );
+    assert_eq!(storage.load_session("session-unsaved").unwrap().id, "session-unsaved");
+    assert_eq!(
+        controller
+            .read()
+            .persist_unsaved_sessions(std::time::Duration::ZERO),
+        0
+    );
+}
+
+#[tokio::test]
+async fn test_outbox_requests_are_sent_through_the_router_and_answered() {
+    let (_storage_dir, app, controller, storage) = setup_isolated_test_app_with_controller().await;
+    let project_dir = TempDir::new().unwrap();
+    controller.read().insert_test_session(make_test_session_with_agents(
+        "session-outbox",
+        &project_dir.path().to_string_lossy(),
+        &["session-outbox-worker-1"],
+    ));
+    let outbox = storage.outbox_dir("session-outbox");
+    std::fs::create_dir_all(&outbox).unwrap();
+    let heartbeat = serde_json::json!({
+        "path": "heartbeat",
+        "agent_id": "session-outbox-worker-1",
+        "body": { "agent_id": "session-outbox-worker-1", "status": "working" },
+    });
+    std::fs::write(outbox.join("heartbeat-1.json"), heartbeat.to_string()).unwrap();
+    std::fs::write(outbox.join("escape.json"), r#"{"path": "../../health"}"#).unwrap();
+    std::fs::write(outbox.join("half-written.tmp"), "{").unwrap();
+    // Neither a file still being written nor an unreadable one holds up the rest.
+    std::fs::write(outbox.join("fresh.json"), "{").unwrap();
+    let binary = outbox.join("binary.json");
+    std::fs::write(&binary, [0xff, 0xfe, 0x00]).unwrap();
+    std::fs::File::options()
+        .write(true)
+        .open(&binary)
+        .unwrap()
+        .set_modified(std::time::SystemTime::now() - std::time::Duration::from_secs(60))
+        .unwrap();
+
+    let answered = crate::http::outbox::drain(&app, "session-outbox", None, &outbox)
+        .await
+        .unwrap();
+    assert_eq!(answered, 3);
+    assert!(!outbox.join("heartbeat-1.json").exists());
+    assert!(outbox.join("half-written.tmp").exists());
+    assert!(outbox.join("fresh.json").exists());
+    assert!(!binary.exists());
+    assert!(outbox.join(crate::http::outbox::FAILED_DIR).join("binary.json").exists());
+
+    let reply = |name: &str| -> serde_json::Value {
+        let path = outbox.join(crate::http::outbox::REPLIES_DIR).join(name);
+        serde_json::from_str(&std::fs::read_to_string(path).unwrap()).unwrap()
+    };
+    assert_eq!(reply("heartbeat-1.json")["status"], 200);
+    assert_eq!(reply("escape.json")["status"], 400);
+    assert_eq!(reply("binary.json")["status"], 400);
+    let heartbeats = controller.read().get_heartbeat_info("session-outbox");
+    assert_eq!(heartbeats["session-outbox-worker-1"].status, "working");
+}
+
+#[tokio::test]
+async fn test_taken_port_moves_the_api_to_a_free_one() {
+    let holder = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let taken = holder.local_addr().unwrap().port();
+
+    let listener = crate::http::bind_free_port(taken).await.unwrap();
+    let bound = listener.local_addr().unwrap().port();
+    assert!(bound > taken && bound < taken + crate::http::PORT_ATTEMPTS, "{bound}");
+}
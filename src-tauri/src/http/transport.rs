@@ -0,0 +1,157 @@
+//! How agents reach the local API: the TCP port actually bound, or a Unix socket.
+//!
+//! Prompts and tool docs are written against `http://localhost:18800`. When that
+//! port is taken, such as by another Hive Manager instance, `http::serve` binds the
+//! next free one and the prompts and tool docs written afterwards name it instead.
+//! With `api.socket_path` set, the API is also served on that socket, and agents'
+//! curl commands are rewritten to go through it with `--unix-socket`. The TCP port
+//! is still opened for the app's own window when `api.enabled` is set.
+
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, OnceLock};
+
+use parking_lot::RwLock;
+use regex::Regex;
+
+use crate::http::state::AppState;
+
+/// The port agent prompts and tool docs are written against.
+pub const DOCUMENTED_PORT: u16 = 18800;
+
+/// The socket the API is being served on, once it is bound.
+static SERVING_SOCKET: RwLock<Option<PathBuf>> = RwLock::new(None);
+
+/// The TCP port the API is being served on, once it is bound.
+static SERVING_PORT: RwLock<Option<u16>> = RwLock::new(None);
+
+/// The TCP port the API is being served on, if any.
+pub fn serving_port() -> Option<u16> {
+    *SERVING_PORT.read()
+}
+
+pub(crate) fn set_serving_port(port: u16) {
+    *SERVING_PORT.write() = Some(port);
+}
+
+/// The socket the API is being served on, if any.
+pub fn serving_socket() -> Option<PathBuf> {
+    SERVING_SOCKET.read().clone()
+}
+
+/// The socket `api.socket_path` names for this process: `{pid}` is replaced with
+/// the process ID, so instances sharing one config each get their own socket.
+pub fn socket_path(configured: &str) -> PathBuf {
+    PathBuf::from(configured.replace("{pid}", &std::process::id().to_string()))
+}
+
+/// Point every URL of the documented port in `text` at `port`.
+pub fn rewrite_for_port(text: &str, port: u16) -> String {
+    text.replace(
+        &format!("localhost:{}", DOCUMENTED_PORT),
+        &format!("localhost:{}", port),
+    )
+}
+
+/// Point every curl invocation in `text` at `socket`.
+pub fn rewrite_for_socket(text: &str, socket: &Path) -> String {
+    static CURL: OnceLock<Regex> = OnceLock::new();
+    let curl = CURL.get_or_init(|| Regex::new(r#"\bcurl( +)([-"'])"#).expect("valid regex"));
+    let socket = socket.display().to_string().replace('\'', r"'\''");
+    let text = text.replace(&format!("http://localhost:{}", DOCUMENTED_PORT), "http://localhost");
+    curl.replace_all(&text, |caps: &regex::Captures<'_>| {
+        let options = &text[caps.get(2).map_or(0, |m| m.start())..];
+        if options.starts_with("--unix-socket") {
+            return caps[0].to_string();
+        }
+        format!("curl --unix-socket '{}'{}{}", socket, &caps[1], &caps[2])
+    })
+    .into_owned()
+}
+
+/// `text` as agents should see it: when the API is served on a socket, its curl
+/// commands are rewritten and a note naming the socket is appended; otherwise its
+/// URLs name the port that was bound.
+pub fn for_agents(text: &str) -> String {
+    let Some(socket) = serving_socket() else {
+        return match serving_port() {
+            Some(port) if port != DOCUMENTED_PORT => rewrite_for_port(text, port),
+            _ => text.to_string(),
+        };
+    };
+    format!(
+        "{}\n\nThe Hive Manager API is served on the Unix socket `{}`, not on a TCP port. \
+         Call it with `curl --unix-socket '{}' http://localhost/api/...`.\n",
+        rewrite_for_socket(text, &socket).trim_end(),
+        socket.display(),
+        socket.display()
+    )
+}
+
+/// Serve the API on the Unix domain socket at `path`, readable by this user only.
+/// A socket left behind by an instance that is gone is replaced; one that still
+/// answers is not.
+#[cfg(unix)]
+pub async fn serve_unix(state: Arc<AppState>, path: &Path) -> io::Result<()> {
+    use std::os::unix::fs::PermissionsExt;
+
+    if path.exists() {
+        if std::os::unix::net::UnixStream::connect(path).is_ok() {
+            return Err(io::Error::new(
+                io::ErrorKind::AddrInUse,
+                format!("{} is served by another process", path.display()),
+            ));
+        }
+        std::fs::remove_file(path)?;
+    }
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    let listener = tokio::net::UnixListener::bind(path)?;
+    std::fs::set_permissions(path, std::fs::Permissions::from_mode(0o600))?;
+    *SERVING_SOCKET.write() = Some(path.to_path_buf());
+
+    let served = axum::serve(listener, crate::http::routes::create_router(state)).await;
+    *SERVING_SOCKET.write() = None;
+    served
+}
+
+/// Unix domain sockets are not served on this platform; agents keep using the port.
+#[cfg(not(unix))]
+pub async fn serve_unix(_state: Arc<AppState>, path: &Path) -> io::Result<()> {
+    Err(io::Error::new(
+        io::ErrorKind::Unsupported,
+        format!("cannot serve {}: Unix domain sockets need Linux or macOS", path.display()),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_curl_invocations_are_pointed_at_the_socket() {
+        let docs = r#"Spawn one:
+  curl -s -X POST "http://localhost:18800/api/sessions/s/workers" -d '{}'
+Read: `curl "http://localhost:18800/api/sessions/s/learnings"`
+Never curl POST /evaluators."#;
+        let rewritten = rewrite_for_socket(docs, Path::new("/tmp/hive.sock"));
+
+        assert!(rewritten.contains(
+            r#"curl --unix-socket '/tmp/hive.sock' -s -X POST "http://localhost/api/sessions/s/workers""#
+        ));
+        assert!(rewritten.contains(
+            r#"`curl --unix-socket '/tmp/hive.sock' "http://localhost/api/sessions/s/learnings"`"#
+        ));
+        assert!(rewritten.contains("Never curl POST /evaluators."));
+        assert!(!rewritten.contains("18800"));
+        assert_eq!(rewrite_for_socket(&rewritten, Path::new("/tmp/hive.sock")), rewritten);
+
+        let moved = rewrite_for_port(docs, 18801);
+        assert!(moved.contains(r#""http://localhost:18801/api/sessions/s/workers""#));
+        assert!(!moved.contains("18800"));
+
+        let path = socket_path("/tmp/hive-{pid}.sock");
+        assert_eq!(path, PathBuf::from(format!("/tmp/hive-{}.sock", std::process::id())));
+    }
+}
@@ -7,7 +7,10 @@ pub mod artifacts;
 pub mod cli;
 mod coordination;
 pub mod domain;
+pub mod error;
 pub mod events;
+#[cfg(feature = "grpc")]
+mod grpc;
 mod http;
 pub mod orchestrator;
 mod preview;
@@ -43,24 +46,34 @@ use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt};
 
 #[cfg(not(test))]
 use commands::{
-    add_worker_to_session, assign_task, close_session, continue_after_planning, create_pty,
-    get_app_config, get_coordination_log, get_current_branch, get_current_directory,
-    get_pty_status, get_run_journal, get_session, get_session_plan, get_session_storage_path,
-    get_workers_state, git_fetch, git_pull, git_push, git_worktree_add, git_worktree_list,
-    git_worktree_prune, git_worktree_remove, inject_to_pty, kill_pty, launch_debate, launch_fusion,
-    launch_hive, launch_hive_v2, launch_research, launch_solo, launch_swarm, list_branches,
-    list_ptys, list_session_files, list_sessions, list_stored_sessions, log_coordination_message,
-    mark_plan_ready, operator_inject, paste_to_pty, queen_inject, queen_switch_branch, resize_pty,
-    resume_session, stop_agent, stop_session, switch_branch, update_app_config,
-    update_session_metadata, write_to_pty, CoordinationState, PtyManagerState,
-    SessionControllerState, StorageState,
+    add_worker_to_session, adopt_session, answer_operator_question, approve_action, assign_task,
+    broadcast_to_session, cancel_queued_session, cleanup_project_artifacts, close_session,
+    compare_sessions, compose_fusion_result, continue_after_planning, create_pty,
+    create_pull_request, delete_secret, delete_template, ensure_project_gitignore, get_app_config,
+    get_cli_leaderboard, get_coordination_log, get_current_branch, get_current_directory,
+    get_full_session_snapshot, get_instance_info, get_orphaned_ptys, get_plan_diff, get_pty_status,
+    get_run_journal, get_session, get_session_durations, get_session_plan,
+    get_session_plan_structured, get_session_storage_path, get_template, get_workers_state,
+    git_fetch, git_pull, git_push, git_worktree_add, git_worktree_list, git_worktree_prune,
+    git_worktree_remove, import_legacy_learnings, inject_to_pty, kill_pty, launch_debate,
+    launch_fusion, launch_hive, launch_hive_v2, launch_research, launch_solo, launch_swarm,
+    link_plan_issues, list_branches, list_checkpoints, list_operator_questions,
+    list_pending_approvals, list_profiles, list_ptys, list_queued_sessions, list_recordings,
+    list_session_files, list_sessions, list_stored_sessions, list_templates,
+    list_unacknowledged_messages, log_coordination_message, mark_plan_ready, open_file_in_editor,
+    operator_inject, override_spawn_quota, paste_to_pty, preview_agent_command, promote_session,
+    quarantine_agent, queen_inject, queen_switch_branch, queue_solo_task, release_agent, resize_pty,
+    resume_session, run_fusion_judges, save_template, scale_workers, set_secret,
+    set_session_recording, stop_agent, stop_session, suggest_fusion_variants, switch_branch,
+    switch_profile, update_app_config, update_session_metadata, write_to_pty, CoordinationState,
+    PtyManagerState, SessionControllerState, StorageState,
 };
 #[cfg(not(test))]
 use pty::PtyManager;
 #[cfg(not(test))]
-use session::SessionController;
+use session::{SessionController, HEARTBEAT_ROLLUP_INTERVAL, SESSION_PERSIST_DEBOUNCE};
 #[cfg(not(test))]
-use storage::{ApplicationStateDb, SessionStorage};
+use storage::{profile_arg, ApplicationStateDb, SessionStorage};
 #[cfg(not(test))]
 use coordination::InjectionManager;
 #[cfg(not(test))]
@@ -75,8 +88,32 @@ pub fn run() {
         .with(tracing_subscriber::EnvFilter::from_default_env())
         .init();
 
+    let context = tauri::generate_context!();
+
+    // Claim this process's instance number first: it decides which sessions
+    // directory every SessionStorage below opens.
+    let instance = match crate::workspace::paths::app_data_dir()
+        .map_err(|e| format!("Failed to resolve the app data directory: {}", e))
+        .and_then(|app_data_dir| {
+            storage::instance::claim(&app_data_dir)
+                .map_err(|e| format!("Failed to claim an instance: {}", e))
+        }) {
+        Ok(instance) => instance,
+        Err(e) => return exit_with_startup_error(context, e),
+    };
+    if instance > 0 {
+        tracing::info!("Another Hive Manager is running; this is instance {}", instance);
+    }
+
     // Initialize session storage
     let storage = Arc::new(SessionStorage::new().expect("Failed to initialize session storage"));
+    // `--profile <name>` picks the config profile for this run before anything reads
+    // config, without replacing the one saved for the next launch.
+    if let Some(profile) = profile_arg(std::env::args()) {
+        storage.use_launch_profile(&profile);
+    }
+    // CLIs defined in `clis/*.toml` under the app data dir.
+    cli::CliRegistry::load_custom_clis(&storage.base_dir().join("clis"));
 
     // Initialize the SQLite application_state DB alongside file storage (runs migrations
     // idempotently). Shared via Arc onto AppState for HTTP + downstream subsystems.
@@ -128,6 +165,7 @@ pub fn run() {
         controller.set_event_bus(Arc::clone(&event_bus));
         controller.set_run_journal(run_journal_store.clone());
     }
+    pty_manager.write().set_storage(Arc::clone(&storage));
 
     // Unified action registry — the single registration point shared by the
     // Tauri #[command] wrappers (caller=Frontend) and the HTTP layer (caller=Http).
@@ -224,6 +262,286 @@ pub fn run() {
                 }
             });
 
+            // Solo task queue - every 5s, hand the next queued prompt to Solo agents whose
+            // output has gone quiet (idle heartbeats dispatch immediately).
+            let solo_queue_controller = session_controller.clone();
+            tauri::async_runtime::spawn(async move {
+                let mut interval = tokio::time::interval(Duration::from_secs(5));
+                loop {
+                    interval.tick().await;
+                    solo_queue_controller.read().dispatch_idle_solo_tasks();
+                }
+            });
+
+            // Worker checkpoints - every minute, when enabled in config, snapshot the
+            // worktrees of running workers that are due (see `checkpoints.interval_minutes`)
+            // to `refs/checkpoints/<session-id>/<worktree>`.
+            let checkpoint_controller = session_controller.clone();
+            let checkpoint_config = shared_config.clone();
+            tauri::async_runtime::spawn(async move {
+                let mut interval = tokio::time::interval(Duration::from_secs(60));
+                loop {
+                    interval.tick().await;
+                    let (enabled, minutes) = {
+                        let cfg = checkpoint_config.read().await;
+                        (cfg.checkpoints.enabled, cfg.checkpoints.interval_minutes)
+                    };
+                    if !enabled {
+                        continue;
+                    }
+                    let controller = checkpoint_controller.clone();
+                    let every = Duration::from_secs(minutes.max(1) * 60);
+                    let _ = tauri::async_runtime::spawn_blocking(move || {
+                        controller.read().checkpoint_workers(every)
+                    })
+                    .await;
+                }
+            });
+
+            // Diff budgets - every minute, when configured, warn and then stop workers whose
+            // diff against their starting commit outgrows `diff_budget`.
+            let diff_budget_controller = session_controller.clone();
+            let diff_budget_config = shared_config.clone();
+            tauri::async_runtime::spawn(async move {
+                let mut interval = tokio::time::interval(Duration::from_secs(60));
+                loop {
+                    interval.tick().await;
+                    let budget = diff_budget_config.read().await.diff_budget;
+                    if !budget.is_enabled() {
+                        continue;
+                    }
+                    let controller = diff_budget_controller.clone();
+                    let _ = tauri::async_runtime::spawn_blocking(move || {
+                        controller.read().enforce_diff_budgets(&budget)
+                    })
+                    .await;
+                }
+            });
+
+            // Judge read-only guard - every 10s, revert tracked-file edits a running judge
+            // made in the main checkout and log the intervention (see `workspace::read_only`).
+            let read_only_controller = session_controller.clone();
+            tauri::async_runtime::spawn(async move {
+                let mut interval = tokio::time::interval(Duration::from_secs(10));
+                loop {
+                    interval.tick().await;
+                    let controller = read_only_controller.clone();
+                    let _ = tauri::async_runtime::spawn_blocking(move || {
+                        SessionController::enforce_read_only_agents(&controller)
+                    })
+                    .await;
+                }
+            });
+
+            // File permissions - every 30s, report files workers changed outside their
+            // `allowed_paths` / `denied_paths` globs (see `session::path_policy`).
+            let path_policy_controller = session_controller.clone();
+            tauri::async_runtime::spawn(async move {
+                let mut interval = tokio::time::interval(Duration::from_secs(30));
+                loop {
+                    interval.tick().await;
+                    let controller = path_policy_controller.clone();
+                    let _ = tauri::async_runtime::spawn_blocking(move || {
+                        controller.read().enforce_path_policies()
+                    })
+                    .await;
+                }
+            });
+
+            // Phase time boxes - every 30s, warn, wrap up or end phases that run past the
+            // budgets in their session's launch config (`phase_timeouts`).
+            let phase_timeout_controller = session_controller.clone();
+            tauri::async_runtime::spawn(async move {
+                let mut interval = tokio::time::interval(Duration::from_secs(30));
+                loop {
+                    interval.tick().await;
+                    let controller = phase_timeout_controller.clone();
+                    let _ = tauri::async_runtime::spawn_blocking(move || {
+                        controller.read().enforce_phase_timeouts()
+                    })
+                    .await;
+                }
+            });
+
+            // Auto-complete - every 30s, complete sessions whose agents have all been
+            // idle or completed, with every task COMPLETED, for `auto_complete` long.
+            let auto_complete_controller = session_controller.clone();
+            let auto_complete_config = shared_config.clone();
+            tauri::async_runtime::spawn(async move {
+                let mut interval = tokio::time::interval(Duration::from_secs(30));
+                loop {
+                    interval.tick().await;
+                    let config = auto_complete_config.read().await.auto_complete;
+                    let controller = auto_complete_controller.clone();
+                    let _ = tauri::async_runtime::spawn_blocking(move || {
+                        controller.read().auto_complete_settled_sessions(&config)
+                    })
+                    .await;
+                }
+            });
+
+            // Inbox digests - every `inbox_digest.interval_secs`, type a summary of each
+            // running agent's unread conversation messages into its terminal.
+            let digest_controller = session_controller.clone();
+            let digest_injection = Arc::clone(&injection_manager);
+            let digest_config = shared_config.clone();
+            tauri::async_runtime::spawn(async move {
+                loop {
+                    let interval_secs = digest_config.read().await.inbox_digest.interval_secs;
+                    tokio::time::sleep(Duration::from_secs(interval_secs.max(30))).await;
+                    let config = digest_config.read().await.inbox_digest;
+                    if !config.enabled {
+                        continue;
+                    }
+                    let controller = digest_controller.clone();
+                    let injection = Arc::clone(&digest_injection);
+                    let _ = tauri::async_runtime::spawn_blocking(move || {
+                        let sessions = controller.read().inbox_digest_targets();
+                        for (session_id, targets) in sessions {
+                            if let Err(e) = injection.read().inject_inbox_digests(
+                                &session_id,
+                                &targets,
+                                config.max_items,
+                            ) {
+                                tracing::warn!("Inbox digest for {} failed: {}", session_id, e);
+                            }
+                        }
+                    })
+                    .await;
+                }
+            });
+
+            // Session snapshots - every `snapshots.interval_secs`, record what the
+            // controller believes about each running session (see `session::snapshots`).
+            let snapshot_controller = session_controller.clone();
+            let snapshot_config = shared_config.clone();
+            tauri::async_runtime::spawn(async move {
+                loop {
+                    let interval_secs = snapshot_config.read().await.snapshots.interval_secs;
+                    tokio::time::sleep(Duration::from_secs(interval_secs.max(10))).await;
+                    let config = snapshot_config.read().await.snapshots;
+                    if !config.enabled {
+                        continue;
+                    }
+                    let controller = snapshot_controller.clone();
+                    let _ = tauri::async_runtime::spawn_blocking(move || {
+                        controller.read().capture_snapshots(config.keep)
+                    })
+                    .await;
+                }
+            });
+
+            // Warm pool - every 15s, keep idle CLI processes started for the workers of
+            // sessions that share a directory, or stop them once disabled
+            // (see `session::warm_pool`).
+            let warm_pool_controller = session_controller.clone();
+            let warm_pool_config = shared_config.clone();
+            tauri::async_runtime::spawn(async move {
+                let mut interval = tokio::time::interval(Duration::from_secs(15));
+                loop {
+                    interval.tick().await;
+                    let config = warm_pool_config.read().await.warm_pool.clone();
+                    let controller = warm_pool_controller.clone();
+                    let _ = tauri::async_runtime::spawn_blocking(move || {
+                        controller.read().refill_warm_pool(&config)
+                    })
+                    .await;
+                }
+            });
+
+            // Session persistence - every 250ms, write each session changed at least
+            // SESSION_PERSIST_DEBOUNCE ago, so agent additions and state changes
+            // survive a crash.
+            let persist_controller = session_controller.clone();
+            tauri::async_runtime::spawn(async move {
+                let mut interval = tokio::time::interval(Duration::from_millis(250));
+                loop {
+                    interval.tick().await;
+                    let controller = persist_controller.clone();
+                    let _ = tauri::async_runtime::spawn_blocking(move || {
+                        controller
+                            .read()
+                            .persist_unsaved_sessions(SESSION_PERSIST_DEBOUNCE)
+                    })
+                    .await;
+                }
+            });
+
+            // Plan issue sync - every 60s, close the GitHub sub-issues (or tick the
+            // checklist) of plan tasks that are done (see `workspace::github_issues`).
+            let plan_issue_controller = session_controller.clone();
+            tauri::async_runtime::spawn(async move {
+                let mut interval = tokio::time::interval(Duration::from_secs(60));
+                loop {
+                    interval.tick().await;
+                    let controller = plan_issue_controller.clone();
+                    let _ = tauri::async_runtime::spawn_blocking(move || {
+                        controller.read().sync_plan_issues()
+                    })
+                    .await;
+                }
+            });
+
+            // PTY watchdog - every 30s, adopt or kill PTYs that no session lists as an
+            // agent (see `session::orphans`).
+            let orphan_controller = session_controller.clone();
+            tauri::async_runtime::spawn(async move {
+                let mut interval = tokio::time::interval(Duration::from_secs(30));
+                loop {
+                    interval.tick().await;
+                    let controller = orphan_controller.clone();
+                    let _ = tauri::async_runtime::spawn_blocking(move || {
+                        controller.read().reconcile_orphaned_ptys()
+                    })
+                    .await;
+                }
+            });
+
+            // Context monitor - read every PTY chunk for the CLI's context-window hints,
+            // and send `/compact` to agents near the limit when `context_monitor` allows.
+            let context_controller = session_controller.clone();
+            let context_config = shared_config.clone();
+            let mut context_output = pty_manager.read().subscribe_output();
+            tauri::async_runtime::spawn(async move {
+                loop {
+                    let output = match context_output.recv().await {
+                        Ok(output) => output,
+                        Err(tokio::sync::broadcast::error::RecvError::Closed) => break,
+                        Err(tokio::sync::broadcast::error::RecvError::Lagged(_)) => continue,
+                    };
+                    let config = context_config.read().await.context_monitor;
+                    context_controller
+                        .read()
+                        .observe_agent_output(&output.id, &output.data, &config);
+                }
+            });
+
+            // Heartbeat rollups - every few minutes, summarize each running session's
+            // heartbeats ("worker-2 idle 12m") into its coordination log.
+            let rollup_controller = session_controller.clone();
+            tauri::async_runtime::spawn(async move {
+                let mut interval = tokio::time::interval(HEARTBEAT_ROLLUP_INTERVAL);
+                loop {
+                    interval.tick().await;
+                    let controller = rollup_controller.clone();
+                    let _ = tauri::async_runtime::spawn_blocking(move || {
+                        controller.read().roll_up_heartbeats()
+                    })
+                    .await;
+                }
+            });
+
+            // Launch queue - every 5s, start queued sessions that now fit under the
+            // concurrency limits, oldest first.
+            let launch_queue_state = Arc::clone(&app_state);
+            tauri::async_runtime::spawn(async move {
+                let mut interval = tokio::time::interval(Duration::from_secs(5));
+                loop {
+                    interval.tick().await;
+                    actions::session::start_queued_launches(&launch_queue_state).await;
+                }
+            });
+
             // #126: durable run-queue maintenance — every 30s, reclaim stuck running rows
             // (heartbeat older than STUCK_CUTOFF flips back to 'queued', emits
             // WorkerReclaimed) and finalize no-progress / continuation-exceeded runs (emits
@@ -370,6 +688,60 @@ pub fn run() {
                 }
             });
 
+            // The same API on a Unix domain socket, so a second instance that cannot
+            // bind the port still serves its agents (see `http::transport`).
+            let socket_state = Arc::clone(&app_state);
+            tauri::async_runtime::spawn(async move {
+                let socket_path = socket_state.config.read().await.api.socket_path.clone();
+
+                if let Some(socket_path) = socket_path {
+                    let path = http::transport::socket_path(&socket_path);
+                    tracing::info!("Starting HTTP API on socket {}", path.display());
+                    if let Err(e) = http::transport::serve_unix(socket_state, &path).await {
+                        tracing::error!("HTTP socket server error: {}", e);
+                    }
+                }
+            });
+
+            // Offline mode - every second, answer the API calls agents wrote to their
+            // session's outbox instead of sending them to the port (see `http::outbox`).
+            let outbox_state = Arc::clone(&app_state);
+            tauri::async_runtime::spawn(async move {
+                let router = http::routes::create_router(Arc::clone(&outbox_state));
+                let mut interval = tokio::time::interval(Duration::from_secs(1));
+                loop {
+                    interval.tick().await;
+                    if outbox_state.config.read().await.api.offline {
+                        http::outbox::drain_all(&router, &outbox_state).await;
+                    }
+                }
+            });
+
+            // The gRPC mirror of the HTTP API shares the same AppState.
+            let grpc_state = Arc::clone(&app_state);
+            tauri::async_runtime::spawn(async move {
+                let (enabled, port) = {
+                    let cfg = grpc_state.config.read().await;
+                    (cfg.grpc.enabled, cfg.grpc.port)
+                };
+
+                if enabled {
+                    #[cfg(feature = "grpc")]
+                    {
+                        tracing::info!("Starting gRPC API on port {}", port);
+                        if let Err(e) = grpc::serve(grpc_state, port).await {
+                            tracing::error!("gRPC server error: {}", e);
+                        }
+                    }
+                    #[cfg(not(feature = "grpc"))]
+                    tracing::warn!(
+                        "grpc.enabled is set but this build lacks the grpc feature; \
+                         not serving on port {}",
+                        port
+                    );
+                }
+            });
+
             // Set up worker-completed event listener for sequential spawning
             let session_controller_clone = session_controller.clone();
             use tauri::Listener;
@@ -453,6 +825,82 @@ pub fn run() {
                 }
             });
 
+            // Fusion variants that report BLOCKED/FAILED are retried or marked failed
+            let fusion_failed_controller_clone = session_controller.clone();
+            app.listen("fusion-variant-failed", move |event: tauri::Event| {
+                let payload = event.payload();
+
+                if let Ok(json) = serde_json::from_str::<serde_json::Value>(payload) {
+                    let session_id = json.get("session_id")
+                        .and_then(|v| v.as_str())
+                        .unwrap_or("");
+                    let variant_index = json.get("variant_index")
+                        .and_then(|v| v.as_u64())
+                        .unwrap_or(0) as u8;
+                    let status = json.get("status")
+                        .and_then(|v| v.as_str())
+                        .unwrap_or("FAILED");
+
+                    if session_id.is_empty() || variant_index == 0 {
+                        tracing::warn!("Invalid fusion-variant-failed payload: {}", payload);
+                        return;
+                    }
+
+                    let controller = fusion_failed_controller_clone.clone();
+                    let session_id_clone = session_id.to_string();
+                    let reason = format!("Task reported {}", status);
+                    tauri::async_runtime::spawn_blocking(move || {
+                        let result = tauri::async_runtime::block_on(
+                            SessionController::on_fusion_variant_failed(
+                                &controller,
+                                &session_id_clone,
+                                variant_index,
+                                &reason,
+                            ),
+                        );
+
+                        if let Err(e) = result {
+                            tracing::error!("Failed to handle fusion variant failure: {}", e);
+                        }
+                    });
+                } else {
+                    tracing::warn!("Failed to parse fusion-variant-failed payload: {}", payload);
+                }
+            });
+
+            // Settle exited agents as Completed/Crashed; Fusion variant CLIs that exit
+            // before completing count as failures
+            let agent_exit_controller_clone = session_controller.clone();
+            app.listen("agent-exited", move |event: tauri::Event| {
+                let payload = event.payload();
+
+                let Ok(json) = serde_json::from_str::<serde_json::Value>(payload) else {
+                    tracing::warn!("Failed to parse agent-exited payload: {}", payload);
+                    return;
+                };
+                let agent_id = json.get("id").and_then(|v| v.as_str()).unwrap_or("");
+                let exit_info = json
+                    .get("exit_info")
+                    .cloned()
+                    .and_then(|v| serde_json::from_value::<pty::AgentExitInfo>(v).ok());
+                let Some(exit_info) = exit_info.filter(|_| !agent_id.is_empty()) else {
+                    tracing::warn!("Invalid agent-exited payload: {}", payload);
+                    return;
+                };
+
+                let controller = agent_exit_controller_clone.clone();
+                let agent_id = agent_id.to_string();
+                tauri::async_runtime::spawn_blocking(move || {
+                    let result = tauri::async_runtime::block_on(
+                        SessionController::on_agent_exited(&controller, &agent_id, exit_info),
+                    );
+
+                    if let Err(e) = result {
+                        tracing::error!("Failed to handle agent exit: {}", e);
+                    }
+                });
+            });
+
             let debate_controller_clone = session_controller.clone();
             app.listen("debate-round-completed", move |event: tauri::Event| {
                 let payload = event.payload();
@@ -580,6 +1028,8 @@ pub fn run() {
             kill_pty,
             get_pty_status,
             list_ptys,
+            set_session_recording,
+            list_recordings,
             // Session commands
             launch_hive,
             launch_hive_v2,
@@ -593,11 +1043,38 @@ pub fn run() {
             stop_session,
             close_session,
             stop_agent,
+            quarantine_agent,
+            release_agent,
+            list_queued_sessions,
+            cancel_queued_session,
+            get_orphaned_ptys,
             update_session_metadata,
+            queue_solo_task,
+            list_checkpoints,
+            get_session_durations,
+            compare_sessions,
+            get_cli_leaderboard,
+            suggest_fusion_variants,
+            preview_agent_command,
+            cleanup_project_artifacts,
+            ensure_project_gitignore,
+            promote_session,
+            scale_workers,
+            link_plan_issues,
+            open_file_in_editor,
+            override_spawn_quota,
+            run_fusion_judges,
+            get_full_session_snapshot,
             // Coordination commands
             queen_inject,
             queen_switch_branch,
             operator_inject,
+            broadcast_to_session,
+            list_operator_questions,
+            answer_operator_question,
+            list_pending_approvals,
+            approve_action,
+            list_unacknowledged_messages,
             add_worker_to_session,
             get_coordination_log,
             log_coordination_message,
@@ -605,11 +1082,24 @@ pub fn run() {
             assign_task,
             get_session_storage_path,
             list_stored_sessions,
+            import_legacy_learnings,
             get_current_directory,
+            get_instance_info,
             get_app_config,
             update_app_config,
+            list_profiles,
+            switch_profile,
+            set_secret,
+            delete_secret,
+            list_templates,
+            get_template,
+            save_template,
+            delete_template,
             cli::health::get_cli_health,
+            cli::health::preflight_check,
             get_session_plan,
+            get_session_plan_structured,
+            get_plan_diff,
             // Preview commands
             preview::open_preview_window,
             preview::close_preview_window,
@@ -632,12 +1122,43 @@ pub fn run() {
             continue_after_planning,
             mark_plan_ready,
             resume_session,
+            adopt_session,
             get_run_journal,
             list_session_files,
+            create_pull_request,
+            compose_fusion_result,
         ])
-        .run(tauri::generate_context!())
+        .run(context)
         .expect("error while running tauri application");
 }
 
+/// Show why Hive Manager cannot start and exit once the dialog is dismissed. Runs
+/// before any state exists, so the app starts with nothing but the dialog plugin.
+#[cfg(not(test))]
+fn exit_with_startup_error(context: tauri::Context, message: String) -> ! {
+    use tauri_plugin_dialog::{DialogExt, MessageDialogKind};
+
+    tracing::error!("{}", message);
+    let result = tauri::Builder::default()
+        .plugin(tauri_plugin_dialog::init())
+        .setup(move |app| {
+            if let Some(window) = app.get_webview_window("main") {
+                let _ = window.hide();
+            }
+            let handle = app.handle().clone();
+            app.dialog()
+                .message(message)
+                .title("Hive Manager cannot start")
+                .kind(MessageDialogKind::Error)
+                .show(move |_| handle.exit(1));
+            Ok(())
+        })
+        .run(context);
+    if let Err(e) = result {
+        tracing::error!("Failed to show the startup error: {}", e);
+    }
+    std::process::exit(1);
+}
+
 #[cfg(test)]
 pub fn run() {}
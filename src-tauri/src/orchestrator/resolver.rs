@@ -48,15 +48,16 @@ impl Resolver {
         event_emitter: Option<EventEmitter>,
     ) -> Self {
         let templates_dir = storage.templates_dir();
-        let artifacts_collector = ArtifactCollector::new(SessionStorage::new_with_base(
-            storage.base_dir().clone(),
-        )
-        .expect("resolver artifact storage initialization failed"));
+        let locale = storage.load_config().ok().and_then(|config| config.locale);
+        let artifacts_collector = ArtifactCollector::new(
+            SessionStorage::new_with_base(storage.base_dir().clone())
+                .expect("resolver artifact storage initialization failed"),
+        );
 
         Self {
             storage,
             artifacts_collector,
-            template_engine: TemplateEngine::new(templates_dir),
+            template_engine: TemplateEngine::new(templates_dir).with_locale(locale.as_deref()),
             event_emitter,
         }
     }
@@ -1,5 +1,7 @@
 use tauri::Url;
 
+#[cfg(not(test))]
+use crate::error::HiveError;
 #[cfg(not(test))]
 use crate::http::state::AppState;
 #[cfg(not(test))]
@@ -1596,10 +1598,14 @@ pub async fn open_preview_window(
     app_state: State<'_, Arc<AppState>>,
     url: String,
     session_id: Option<String>,
-) -> Result<PreviewStatus, String> {
-    let configured_api_port = app_state.config.read().await.api.port;
+) -> Result<PreviewStatus, HiveError> {
+    let configured_api_port = match crate::http::transport::serving_port() {
+        Some(port) => port,
+        None => app_state.config.read().await.api.port,
+    };
     // The one and only normalizing entry point: this URL was typed by a human.
-    let url = validate_operator_preview_input(&url, configured_api_port)?;
+    let url = validate_operator_preview_input(&url, configured_api_port)
+        .map_err(HiveError::validation)?;
     let state = Arc::clone(app_state.inner());
 
     {
@@ -1685,7 +1691,7 @@ pub async fn get_preview_status(
     app: AppHandle,
     app_state: State<'_, Arc<AppState>>,
     session_id: Option<String>,
-) -> Result<PreviewStatus, String> {
+) -> Result<PreviewStatus, HiveError> {
     let state = Arc::clone(app_state.inner());
     let guard = runtime(&state).lock();
     Ok(status_for(&app, &guard, session_id.as_deref()))
@@ -1698,7 +1704,7 @@ pub async fn dock_preview_window(
     app: AppHandle,
     app_state: State<'_, Arc<AppState>>,
     session_id: Option<String>,
-) -> Result<PreviewStatus, String> {
+) -> Result<PreviewStatus, HiveError> {
     let state = Arc::clone(app_state.inner());
     enter_docked_mode(&app, &state)?;
     Ok(publish_status(&app, &state, session_id.as_deref()))
@@ -1712,7 +1718,7 @@ pub async fn undock_preview_window(
     app: AppHandle,
     app_state: State<'_, Arc<AppState>>,
     session_id: Option<String>,
-) -> Result<PreviewStatus, String> {
+) -> Result<PreviewStatus, HiveError> {
     let state = Arc::clone(app_state.inner());
     leave_docked_mode(&app, &state)?;
     Ok(publish_status(&app, &state, session_id.as_deref()))
@@ -1721,10 +1727,10 @@ pub async fn undock_preview_window(
 /// Reload whatever the preview is currently showing.
 #[cfg(not(test))]
 #[tauri::command]
-pub async fn reload_preview_window(app: AppHandle) -> Result<(), String> {
-    require_window(&app, PREVIEW_WINDOW_LABEL)?
+pub async fn reload_preview_window(app: AppHandle) -> Result<(), HiveError> {
+    Ok(require_window(&app, PREVIEW_WINDOW_LABEL)?
         .reload()
-        .map_err(|error| format!("Failed to reload the preview: {error}"))
+        .map_err(|error| format!("Failed to reload the preview: {error}"))?)
 }
 
 /// Close the preview window, persisting its geometry first.
@@ -1734,7 +1740,7 @@ pub async fn close_preview_window(
     app: AppHandle,
     app_state: State<'_, Arc<AppState>>,
     session_id: Option<String>,
-) -> Result<PreviewStatus, String> {
+) -> Result<PreviewStatus, HiveError> {
     let state = Arc::clone(app_state.inner());
 
     if let Some(window) = app.get_webview_window(PREVIEW_WINDOW_LABEL) {
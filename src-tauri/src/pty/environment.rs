@@ -0,0 +1,101 @@
+//! The process environment a CLI is spawned with.
+//!
+//! By default an agent inherits the app's environment, its login shell's profile
+//! runs first (see `pty::shell`), and the CLI's configured `env` is layered on top.
+//! A CLI can also pin its toolchain: `path_prepend` directories go in front of
+//! PATH, re-applied after the profile so nvm or pyenv hooks cannot push them back,
+//! and an `env_allowlist` starts it from only the named host variables, skipping
+//! the login shell altogether.
+
+use std::collections::HashMap;
+use std::ffi::{OsStr, OsString};
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CliEnvironment {
+    /// `CliConfig.env`, with secrets resolved.
+    pub vars: HashMap<String, String>,
+    pub path_prepend: Vec<String>,
+    /// Host variables to keep; `None` keeps them all.
+    pub allowlist: Option<Vec<String>>,
+}
+
+impl CliEnvironment {
+    pub fn is_isolated(&self) -> bool {
+        self.allowlist.is_some()
+    }
+
+    /// The host variables the CLI keeps out of `host`, or `None` when it keeps
+    /// them all. Names match case-insensitively on Windows, as its variables do.
+    pub fn kept_host_vars(
+        &self,
+        host: impl IntoIterator<Item = (OsString, OsString)>,
+    ) -> Option<Vec<(OsString, OsString)>> {
+        let allowlist = self.allowlist.as_ref()?;
+        let allowed = |name: &OsStr| {
+            let name = name.to_string_lossy();
+            allowlist.iter().any(|allowed| {
+                if cfg!(windows) {
+                    allowed.eq_ignore_ascii_case(&name)
+                } else {
+                    *allowed == name
+                }
+            })
+        };
+        Some(host.into_iter().filter(|(name, _)| allowed(name.as_os_str())).collect())
+    }
+
+    /// The `path_prepend` directories joined as a PATH fragment, or `None` when
+    /// there are none.
+    pub fn path_prefix(&self) -> Option<OsString> {
+        if self.path_prepend.is_empty() {
+            return None;
+        }
+        std::env::join_paths(&self.path_prepend).ok()
+    }
+
+    /// `base` with the `path_prepend` directories in front, or `None` when there
+    /// is nothing to prepend.
+    pub fn path(&self, base: Option<&OsStr>) -> Option<OsString> {
+        let prefix = self.path_prefix()?;
+        let mut dirs: Vec<_> = std::env::split_paths(&prefix).collect();
+        dirs.extend(base.map(std::env::split_paths).into_iter().flatten());
+        std::env::join_paths(dirs).ok()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_allowlist_keeps_named_host_vars_and_path_is_prepended() {
+        let host = || {
+            [("HOME", "/home/me"), ("PATH", "/usr/bin"), ("NVM_DIR", "/home/me/.nvm")]
+                .map(|(name, value)| (OsString::from(name), OsString::from(value)))
+        };
+        let inherit = CliEnvironment::default();
+        assert!(!inherit.is_isolated());
+        assert_eq!(inherit.kept_host_vars(host()), None);
+        assert_eq!(inherit.path(Some(OsStr::new("/usr/bin"))), None);
+
+        let pinned = CliEnvironment {
+            vars: HashMap::new(),
+            path_prepend: vec!["/opt/node-20/bin".to_string(), "/opt/py312/bin".to_string()],
+            allowlist: Some(vec!["HOME".to_string(), "PATH".to_string()]),
+        };
+        let kept: Vec<_> = pinned.kept_host_vars(host()).unwrap();
+        assert_eq!(
+            kept.iter().map(|(name, _)| name.to_str().unwrap()).collect::<Vec<_>>(),
+            ["HOME", "PATH"]
+        );
+        let separator = if cfg!(windows) { ";" } else { ":" };
+        assert_eq!(
+            pinned.path(Some(OsStr::new("/usr/bin"))).unwrap(),
+            OsString::from(["/opt/node-20/bin", "/opt/py312/bin", "/usr/bin"].join(separator))
+        );
+        assert_eq!(
+            pinned.path(None).unwrap(),
+            OsString::from(["/opt/node-20/bin", "/opt/py312/bin"].join(separator))
+        );
+    }
+}
@@ -0,0 +1,227 @@
+//! Structured exit reporting for PTY agents: how the process ended and the last lines it
+//! printed, so an agent that crashed can be told apart from one that finished.
+
+use std::collections::VecDeque;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use super::session::AgentStatus;
+
+/// Trailing output lines kept for an exited agent.
+pub const EXIT_OUTPUT_LINES: usize = 20;
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct AgentExitInfo {
+    /// Process exit code; `None` when the exit status could not be collected.
+    pub exit_code: Option<u32>,
+    /// Signal that terminated the process, as reported by the OS.
+    pub signal: Option<String>,
+    /// The agent was stopped by Hive Manager rather than exiting on its own.
+    #[serde(default)]
+    pub stopped: bool,
+    /// Last non-empty output lines, ANSI escapes stripped, oldest first.
+    #[serde(default)]
+    pub last_output: Vec<String>,
+    pub exited_at: DateTime<Utc>,
+}
+
+impl AgentExitInfo {
+    pub fn new(
+        exit_code: Option<u32>,
+        signal: Option<String>,
+        stopped: bool,
+        last_output: Vec<String>,
+    ) -> Self {
+        Self {
+            exit_code,
+            signal,
+            stopped,
+            last_output,
+            exited_at: Utc::now(),
+        }
+    }
+
+    /// A non-zero exit or a signal the agent did not get from us.
+    pub fn crashed(&self) -> bool {
+        !self.stopped && (self.signal.is_some() || self.exit_code.is_some_and(|code| code != 0))
+    }
+
+    /// Human-readable exit reason.
+    pub fn reason(&self) -> String {
+        if self.stopped {
+            return "Stopped".to_string();
+        }
+        match (&self.signal, self.exit_code) {
+            (Some(signal), _) => format!("Terminated by {}", signal),
+            (None, Some(0)) => "Exited normally".to_string(),
+            (None, Some(code)) => format!("Exited with code {}", code),
+            (None, None) => "Exited".to_string(),
+        }
+    }
+
+    /// Status the agent settles in after this exit.
+    pub fn status(&self) -> AgentStatus {
+        if self.crashed() {
+            AgentStatus::Crashed(self.reason())
+        } else {
+            AgentStatus::Completed
+        }
+    }
+}
+
+/// Rolling tail of a PTY's output, split into lines with terminal escapes removed.
+#[derive(Debug)]
+pub struct OutputTail {
+    lines: VecDeque<String>,
+    current: String,
+    pending: Vec<u8>,
+    limit: usize,
+}
+
+impl Default for OutputTail {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl OutputTail {
+    pub fn new() -> Self {
+        Self::with_limit(EXIT_OUTPUT_LINES)
+    }
+
+    /// A tail keeping the last `limit` lines.
+    pub fn with_limit(limit: usize) -> Self {
+        Self {
+            lines: VecDeque::new(),
+            current: String::new(),
+            pending: Vec::new(),
+            limit,
+        }
+    }
+
+    pub fn push(&mut self, data: &[u8]) {
+        self.pending.extend_from_slice(data);
+        // Hold back an incomplete UTF-8 sequence until the next chunk completes it.
+        let valid = match std::str::from_utf8(&self.pending) {
+            Ok(_) => self.pending.len(),
+            Err(e) if e.error_len().is_none() => e.valid_up_to(),
+            Err(_) => self.pending.len(),
+        };
+        let text = String::from_utf8_lossy(&self.pending[..valid]).into_owned();
+        self.pending.drain(..valid);
+
+        let mut chars = text.chars().peekable();
+        while let Some(c) = chars.next() {
+            match c {
+                '\x1b' => skip_escape(&mut chars),
+                '\n' => self.end_line(),
+                // A bare carriage return redraws the line (spinners, progress bars).
+                '\r' if chars.peek() != Some(&'\n') => self.current.clear(),
+                c if c.is_control() && c != '\t' => {}
+                c => self.current.push(c),
+            }
+        }
+    }
+
+    fn end_line(&mut self) {
+        let line = std::mem::take(&mut self.current);
+        let line = line.trim_end();
+        if line.is_empty() {
+            return;
+        }
+        if self.lines.len() == self.limit {
+            self.lines.pop_front();
+        }
+        self.lines.push_back(line.to_string());
+    }
+
+    /// Remove and return the completed lines kept so far.
+    pub fn take_lines(&mut self) -> Vec<String> {
+        self.lines.drain(..).collect()
+    }
+
+    /// The line still being written, escapes stripped.
+    pub fn current_line(&self) -> &str {
+        &self.current
+    }
+
+    /// The kept lines, including an unterminated final line.
+    pub fn into_lines(mut self) -> Vec<String> {
+        self.end_line();
+        self.lines.into()
+    }
+}
+
+/// Consume the rest of an escape sequence whose `ESC` was already read.
+fn skip_escape(chars: &mut std::iter::Peekable<std::str::Chars<'_>>) {
+    match chars.next() {
+        // CSI: parameters and intermediates, then a final byte in `@`..=`~`.
+        Some('[') => {
+            for c in chars.by_ref() {
+                if ('@'..='~').contains(&c) {
+                    break;
+                }
+            }
+        }
+        // OSC: terminated by BEL or ST (`ESC \`).
+        Some(']') => {
+            while let Some(c) = chars.next() {
+                if c == '\x07' {
+                    break;
+                }
+                if c == '\x1b' {
+                    chars.next_if_eq(&'\\');
+                    break;
+                }
+            }
+        }
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_output_tail_keeps_last_lines_without_escapes() {
+        let mut tail = OutputTail::new();
+        for i in 0..(EXIT_OUTPUT_LINES + 5) {
+            tail.push(format!("line {}\r\n", i).as_bytes());
+        }
+        tail.push(b"\x1b[31mError:\x1b[0m boom\r\n\x1b]0;title\x07Spinning\rpanic: ");
+        // A multi-byte character split across reads survives intact.
+        let arrow = "→".as_bytes();
+        tail.push(&arrow[..1]);
+        tail.push(&arrow[1..]);
+
+        let lines = tail.into_lines();
+        assert_eq!(lines.len(), EXIT_OUTPUT_LINES);
+        assert_eq!(lines[lines.len() - 2], "Error: boom");
+        assert_eq!(lines[lines.len() - 1], "panic: →");
+        assert_eq!(lines[0], "line 7");
+    }
+
+    #[test]
+    fn test_exit_info_distinguishes_completed_from_crashed() {
+        let clean = AgentExitInfo::new(Some(0), None, false, Vec::new());
+        assert_eq!(clean.status(), AgentStatus::Completed);
+
+        let failed = AgentExitInfo::new(Some(101), None, false, Vec::new());
+        assert_eq!(
+            failed.status(),
+            AgentStatus::Crashed("Exited with code 101".to_string())
+        );
+
+        let signalled = AgentExitInfo::new(Some(1), Some("Killed".to_string()), false, Vec::new());
+        assert_eq!(
+            signalled.status(),
+            AgentStatus::Crashed("Terminated by Killed".to_string())
+        );
+
+        let stopped = AgentExitInfo::new(Some(1), Some("Killed".to_string()), true, Vec::new());
+        assert_eq!(stopped.status(), AgentStatus::Completed);
+        assert_eq!(stopped.reason(), "Stopped");
+    }
+}
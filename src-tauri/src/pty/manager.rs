@@ -1,14 +1,29 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
 use std::thread;
 use std::time::Duration;
 use parking_lot::{Mutex, RwLock};
 use serde::Serialize;
+use tokio::sync::broadcast;
 
+use super::environment::CliEnvironment;
+use super::exit::{AgentExitInfo, OutputTail};
+use super::priority;
+use super::recording::{self, CastRecorder, RecordingInfo};
 use super::session::{AgentRole, AgentStatus, PtyError, PtySession, read_from_reader};
+use crate::adapters::SubmitKey;
+use crate::cli::CliRegistry;
+use crate::storage::secrets::{self, KeyringSecrets};
+use crate::storage::SessionStorage;
 use crate::tauri_shim::{AppHandle, Emitter};
 
-#[derive(Clone, Serialize)]
+const OUTPUT_CHANNEL_CAPACITY: usize = 256;
+
+/// IDs of the idle processes the warm pool keeps for workers (see
+/// `session::warm_pool`). They belong to no session until handed over.
+pub const WARM_PTY_PREFIX: &str = "warm-";
+
+#[derive(Debug, Clone, Serialize)]
 pub struct PtyOutput {
     pub id: String,
     pub data: Vec<u8>,
@@ -20,12 +35,30 @@ pub struct PtyStatusChange {
     pub status: AgentStatus,
 }
 
+/// Payload of `agent-exited`, emitted once per PTY process exit.
+#[derive(Clone, Serialize)]
+pub struct AgentExited {
+    pub id: String,
+    pub reason: String,
+    pub exit_info: AgentExitInfo,
+}
+
 pub struct PtyManager {
     sessions: Arc<RwLock<HashMap<String, Arc<PtySession>>>>,
     /// Serialize create/kill so a same-id kill cannot pass between process spawn and
     /// insertion, and a duplicate create cannot replace a still-live process handle.
     lifecycle: Mutex<()>,
     app_handle: Option<AppHandle>,
+    /// Source of `CliConfig.env` for spawned CLIs. `None` spawns with the inherited env only.
+    storage: Option<Arc<SessionStorage>>,
+    /// Every PTY's output as it is read, for callers waiting on an agent's reply.
+    output_tx: broadcast::Sender<PtyOutput>,
+    /// Sessions whose agent PTYs are recorded to `.cast` files when spawned.
+    recording_sessions: RwLock<HashSet<String>>,
+    /// Sessions whose agent PTYs run at background priority when spawned.
+    background_sessions: RwLock<HashSet<String>>,
+    /// PTY ID -> the key its CLI submits typed text with, resolved at spawn.
+    submit_keys: RwLock<HashMap<String, SubmitKey>>,
 }
 
 // Explicitly implement Send + Sync
@@ -38,13 +71,161 @@ impl PtyManager {
             sessions: Arc::new(RwLock::new(HashMap::new())),
             lifecycle: Mutex::new(()),
             app_handle: None,
+            storage: None,
+            output_tx: broadcast::channel(OUTPUT_CHANNEL_CAPACITY).0,
+            recording_sessions: RwLock::new(HashSet::new()),
+            background_sessions: RwLock::new(HashSet::new()),
+            submit_keys: RwLock::new(HashMap::new()),
         }
     }
 
+    /// Subscribe to the output of every PTY from now on. Receivers that fall more
+    /// than `OUTPUT_CHANNEL_CAPACITY` chunks behind lose the oldest.
+    pub fn subscribe_output(&self) -> broadcast::Receiver<PtyOutput> {
+        self.output_tx.subscribe()
+    }
+
     pub fn set_app_handle(&mut self, handle: AppHandle) {
         self.app_handle = Some(handle);
     }
 
+    pub fn set_storage(&mut self, storage: Arc<SessionStorage>) {
+        self.storage = Some(storage);
+    }
+
+    /// Record the agent PTYs spawned for `session_id` from now on, or stop doing so.
+    /// PTYs already running keep their current setting.
+    pub fn set_recording(&self, session_id: &str, enabled: bool) -> Result<(), PtyError> {
+        if self.storage.is_none() {
+            return Err(PtyError::CreateError(
+                "Recording needs session storage".to_string(),
+            ));
+        }
+        let mut sessions = self.recording_sessions.write();
+        if enabled {
+            sessions.insert(session_id.to_string());
+        } else {
+            sessions.remove(session_id);
+        }
+        Ok(())
+    }
+
+    pub fn is_recording(&self, session_id: &str) -> bool {
+        self.recording_sessions.read().contains(session_id)
+    }
+
+    /// Spawn the agent PTYs of `session_id` at background priority from now on, or
+    /// stop doing so. PTYs already running keep the priority they started with.
+    pub fn set_background(&self, session_id: &str, background: bool) {
+        let mut sessions = self.background_sessions.write();
+        if background {
+            sessions.insert(session_id.to_string());
+        } else {
+            sessions.remove(session_id);
+        }
+    }
+
+    pub fn is_background(&self, session_id: &str) -> bool {
+        self.background_sessions.read().contains(session_id)
+    }
+
+    /// Whether PTY `id` belongs to a background session, by its agent ID prefix.
+    fn is_background_pty(&self, id: &str) -> bool {
+        self.background_sessions
+            .read()
+            .iter()
+            .any(|session_id| id.starts_with(&format!("{}-", session_id)))
+    }
+
+    /// The session's `.cast` recordings, oldest first.
+    pub fn list_recordings(&self, session_id: &str) -> Result<Vec<RecordingInfo>, PtyError> {
+        match self.storage.as_ref() {
+            Some(storage) => Ok(recording::list(
+                &storage.session_dir(session_id).join(recording::RECORDINGS_DIR),
+            )?),
+            None => Ok(Vec::new()),
+        }
+    }
+
+    /// A recorder for PTY `id` when its session (the `{session_id}-` prefix of agent
+    /// IDs) is being recorded. Failing to start one never fails the spawn.
+    fn recorder_for(&self, id: &str, command: &str, cols: u16, rows: u16) -> Option<CastRecorder> {
+        let storage = self.storage.as_ref()?;
+        let session_id = self
+            .recording_sessions
+            .read()
+            .iter()
+            .find(|session_id| id.starts_with(&format!("{}-", session_id)))
+            .cloned()?;
+        let dir = storage.session_dir(&session_id).join(recording::RECORDINGS_DIR);
+        match CastRecorder::create(&dir, id, command, cols, rows) {
+            Ok((recorder, path)) => {
+                tracing::info!("Recording PTY {} to {}", id, path.display());
+                Some(recorder)
+            }
+            Err(e) => {
+                tracing::warn!("Failed to start recording PTY {}: {}", id, e);
+                None
+            }
+        }
+    }
+
+    /// How `command`'s CLI submits typed text (see [`CliRegistry::submit_key`]),
+    /// read from the config fresh like its environment.
+    fn cli_submit_key(&self, command: &str) -> SubmitKey {
+        let config = self
+            .storage
+            .as_ref()
+            .and_then(|storage| storage.load_config().ok());
+        CliRegistry::submit_key(config.as_ref(), command)
+    }
+
+    /// Environment configured for `command`: its `CliConfig.env`, and its PATH and
+    /// host-environment settings. `${secret:NAME}` references are left as they are,
+    /// so this is also what a preview of the spawn shows.
+    pub fn configured_cli_env(&self, command: &str) -> Result<CliEnvironment, PtyError> {
+        let Some(storage) = self.storage.as_ref() else {
+            return Ok(CliEnvironment::default());
+        };
+        let config = storage
+            .load_config()
+            .map_err(|e| PtyError::CreateError(format!("Failed to load config: {}", e)))?;
+        let configured = config.clis.values().find(|cli| cli.command == command);
+        let custom = crate::cli::custom::by_command(command);
+        let mut environment = configured
+            .map(|cli| (cli.path_prepend.clone(), cli.env_allowlist.clone()))
+            .or(custom.as_ref().map(|cli| (cli.path_prepend.clone(), cli.env_allowlist.clone())))
+            .map(|(path_prepend, allowlist)| CliEnvironment {
+                vars: HashMap::new(),
+                path_prepend,
+                allowlist,
+            })
+            .unwrap_or_default();
+        if let Some(env) = configured
+            .and_then(|cli| cli.env.as_ref())
+            .or(custom.as_ref().map(|cli| &cli.env).filter(|env| !env.is_empty()))
+        {
+            environment.vars = env.clone();
+        }
+        Ok(environment)
+    }
+
+    /// [`Self::configured_cli_env`] with the secret references resolved from the OS
+    /// keychain. Config is read fresh on every spawn so secrets are only ever resolved
+    /// at spawn time and never cached.
+    fn cli_env(&self, command: &str) -> Result<CliEnvironment, PtyError> {
+        let mut environment = self.configured_cli_env(command)?;
+        if environment.vars.is_empty() {
+            return Ok(environment);
+        }
+
+        tracing::debug!("CLI env for {}: {:?}", command, secrets::env_keys(&environment.vars));
+        environment.vars = secrets::resolve_env(&environment.vars, &KeyringSecrets).map_err(|e| {
+            PtyError::CreateError(format!("Failed to resolve env for {}: {}", command, e))
+        })?;
+        Ok(environment)
+    }
+
     pub fn create_session(
         &self,
         id: String,
@@ -76,30 +257,50 @@ impl PtyManager {
             }
         }
 
-        let session = Arc::new(PtySession::new(id.clone(), role, command, args, cwd, cols, rows)?);
+        let env = self.cli_env(command)?;
+        let submit_key = self.cli_submit_key(command);
+        let background = self.is_background_pty(&id);
+        let session = Arc::new(PtySession::new(
+            id.clone(),
+            role,
+            command,
+            args,
+            cwd,
+            &env,
+            cols,
+            rows,
+            background,
+        )?);
 
         // Insert session BEFORE spawning reader thread (fixes race condition)
         {
             let mut sessions = self.sessions.write();
             sessions.insert(id.clone(), Arc::clone(&session));
         }
+        self.submit_keys.write().insert(id.clone(), submit_key);
 
         // Start the output reader thread
         if let Some(ref app_handle) = self.app_handle {
             let session_clone = Arc::clone(&session);
             let app_handle_clone = app_handle.clone();
-            let id_clone = id.clone();
             let sessions_ref = Arc::clone(&self.sessions);
+            let output_tx = self.output_tx.clone();
+            let mut recorder = self.recorder_for(&id, command, cols, rows);
 
             thread::spawn(move || {
                 let reader = session_clone.get_reader();
-                let mut buf = [0u8; 4096];
+                let mut buf = if background {
+                    vec![0u8; priority::BACKGROUND_READ_BYTES]
+                } else {
+                    vec![0u8; 4096]
+                };
+                let mut tail = OutputTail::new();
 
                 loop {
-                    // Check if session still exists
+                    // Check if session still exists, under the ID it was last handed to
                     {
                         let sessions_read = sessions_ref.read();
-                        if !sessions_read.contains_key(&id_clone) {
+                        if !sessions_read.contains_key(&session_clone.id()) {
                             break;
                         }
                     }
@@ -118,21 +319,46 @@ impl PtyManager {
                     };
 
                     if bytes_read > 0 {
+                        let id_clone = session_clone.id();
                         tracing::debug!("PTY {} read {} bytes", id_clone, bytes_read);
+                        tail.push(&buf[..bytes_read]);
+                        session_clone.mark_output();
+                        if let Some(cast) = recorder.as_mut() {
+                            if let Err(e) = cast.output(&buf[..bytes_read]) {
+                                tracing::warn!("Stopped recording PTY {}: {}", id_clone, e);
+                                recorder = None;
+                            }
+                        }
                         let output = PtyOutput {
                             id: id_clone.clone(),
                             data: buf[..bytes_read].to_vec(),
                         };
+                        // Fails only when nobody is listening.
+                        let _ = output_tx.send(output.clone());
                         if let Err(e) = app_handle_clone.emit("pty-output", output) {
                             tracing::error!("Failed to emit pty-output: {}", e);
                         }
+                        if background {
+                            thread::sleep(priority::BACKGROUND_OUTPUT_INTERVAL);
+                        }
                     }
                 }
 
-                // Session ended - emit status change
+                // Session ended - record how it ended and emit the status change
+                let id_clone = session_clone.id();
+                let exit_info = session_clone.exit_info(tail.into_lines());
+                let status = exit_info.status();
+                *session_clone.status.write() = status.clone();
+                tracing::info!("PTY {} exited: {}", id_clone, exit_info.reason());
+
                 let _ = app_handle_clone.emit("pty-status", PtyStatusChange {
+                    id: id_clone.clone(),
+                    status,
+                });
+                let _ = app_handle_clone.emit("agent-exited", AgentExited {
                     id: id_clone,
-                    status: AgentStatus::Completed,
+                    reason: exit_info.reason(),
+                    exit_info,
                 });
             });
         }
@@ -149,6 +375,64 @@ impl PtyManager {
         Ok(id)
     }
 
+    /// Hand the running PTY `from` over to agent `id`: its output, status and exit
+    /// are reported under `id` from now on.
+    pub fn adopt(&self, from: &str, id: String, role: AgentRole) -> Result<(), PtyError> {
+        let _lifecycle_guard = self.lifecycle.lock();
+        {
+            let mut sessions = self.sessions.write();
+            if sessions.contains_key(&id) {
+                return Err(PtyError::CreateError(format!(
+                    "PTY session already exists: {id}"
+                )));
+            }
+            let session = sessions
+                .get(from)
+                .filter(|session| session.is_alive())
+                .cloned()
+                .ok_or_else(|| PtyError::NotFound(from.to_string()))?;
+            sessions.remove(from);
+            session.reassign(id.clone(), role);
+            sessions.insert(id.clone(), session);
+        }
+        {
+            let mut submit_keys = self.submit_keys.write();
+            if let Some(submit_key) = submit_keys.remove(from) {
+                submit_keys.insert(id.clone(), submit_key);
+            }
+        }
+
+        if let Some(ref app_handle) = self.app_handle {
+            let _ = app_handle.emit("pty-status", PtyStatusChange {
+                id,
+                status: AgentStatus::Running,
+            });
+        }
+        Ok(())
+    }
+
+    /// The key that submits text typed into PTY `id`, chosen for its CLI at spawn.
+    pub fn submit_key(&self, id: &str) -> SubmitKey {
+        self.submit_keys
+            .read()
+            .get(id)
+            .copied()
+            .unwrap_or(SubmitKey::Enter)
+    }
+
+    /// Paste `text` into PTY `id` as one block, so its newlines stay in the prompt,
+    /// then press the key its CLI submits with.
+    pub fn submit(&self, id: &str, text: &str) -> Result<(), PtyError> {
+        let session = self
+            .sessions
+            .read()
+            .get(id)
+            .cloned()
+            .ok_or_else(|| PtyError::NotFound(id.to_string()))?;
+        session.write_bracketed(text.trim_end_matches(['\r', '\n']).as_bytes())?;
+        session.write(self.submit_key(id).bytes())
+    }
+
     pub fn write(&self, id: &str, data: &[u8]) -> Result<(), PtyError> {
         tracing::debug!("PtyManager::write called for session: {}", id);
         let sessions = self.sessions.read();
@@ -197,6 +481,7 @@ impl PtyManager {
                 .is_some_and(|current| Arc::ptr_eq(current, &session))
             {
                 sessions.remove(id);
+                self.submit_keys.write().remove(id);
             }
         }
         Ok(())
@@ -215,12 +500,21 @@ impl PtyManager {
             .unwrap_or(false)
     }
 
+    /// How long the agent's PTY has gone without output.
+    pub fn idle_for(&self, id: &str) -> Option<Duration> {
+        let sessions = self.sessions.read();
+        sessions.get(id).map(|session| session.idle_for())
+    }
+
     pub fn list_sessions(&self) -> Vec<(String, AgentRole, AgentStatus)> {
         let sessions = self.sessions.read();
         sessions
             .iter()
-            .filter(|(_, session)| !matches!(&session.role, AgentRole::ScratchShell))
-            .map(|(id, session)| (id.clone(), session.role.clone(), session.status.read().clone()))
+            .filter(|(id, session)| {
+                !matches!(session.role(), AgentRole::ScratchShell)
+                    && !id.starts_with(WARM_PTY_PREFIX)
+            })
+            .map(|(id, session)| (id.clone(), session.role(), session.status.read().clone()))
             .collect()
     }
 }
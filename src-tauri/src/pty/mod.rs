@@ -1,9 +1,16 @@
+pub(crate) mod environment;
+mod exit;
 mod manager;
+pub(crate) mod priority;
+pub(crate) mod recording;
 #[cfg(not(all(test, windows)))]
 mod session;
 #[cfg(all(test, windows))]
 #[path = "session_stub.rs"]
 mod session;
+pub(crate) mod shell;
 
-pub use manager::PtyManager;
-pub use session::{AgentConfig, AgentRole, AgentStatus, WorkerRole};
+pub use exit::{AgentExitInfo, OutputTail};
+pub use manager::{PtyManager, PtyOutput, WARM_PTY_PREFIX};
+pub use recording::{RecordingInfo, SessionRecordings};
+pub use session::{AgentConfig, AgentRole, AgentStatus, PtyError, WorkerRole};
@@ -0,0 +1,55 @@
+//! Running a background session's agents below normal OS priority.
+//!
+//! A session marked as background (an experimental Fusion run next to an
+//! interactive Solo session, say) starts its CLIs through `nice` on Unix and with
+//! `start /BELOWNORMAL` on Windows; their child processes inherit the lower
+//! priority. Its terminals' output is also read at a slower pace (see
+//! [`BACKGROUND_OUTPUT_INTERVAL`]), so it sends the UI fewer, larger events.
+
+use std::time::Duration;
+
+/// Niceness background CLIs run at on Unix.
+pub const BACKGROUND_NICENESS: i32 = 10;
+
+/// Pause after each chunk of a background PTY's output is passed on, which caps it
+/// at ten `pty-output` events a second.
+pub const BACKGROUND_OUTPUT_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Read buffer for background PTYs, large enough to take what piles up during a pause.
+pub const BACKGROUND_READ_BYTES: usize = 64 * 1024;
+
+/// `command args...` run through `nice`.
+#[cfg_attr(windows, allow(dead_code))]
+pub fn niced(command: &str, args: &[&str]) -> (String, Vec<String>) {
+    let mut niced_args = vec![
+        "-n".to_string(),
+        BACKGROUND_NICENESS.to_string(),
+        command.to_string(),
+    ];
+    niced_args.extend(args.iter().map(|arg| arg.to_string()));
+    ("nice".to_string(), niced_args)
+}
+
+/// A batch file command line started at below-normal priority in the same console,
+/// with the batch file waiting for it to exit.
+#[cfg_attr(not(windows), allow(dead_code))]
+pub fn below_normal_batch_line(cmd_line: &str) -> String {
+    format!("start \"\" /BELOWNORMAL /B /WAIT {}", cmd_line)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_background_commands_are_wrapped_for_lower_priority() {
+        let (program, args) = niced("claude", &["--model", "opus"]);
+        assert_eq!(program, "nice");
+        assert_eq!(args, ["-n", "10", "claude", "--model", "opus"]);
+
+        assert_eq!(
+            below_normal_batch_line("codex --full-auto"),
+            "start \"\" /BELOWNORMAL /B /WAIT codex --full-auto"
+        );
+    }
+}
@@ -0,0 +1,233 @@
+//! Terminal recordings in asciinema's v2 `.cast` format.
+//!
+//! When recording is switched on for a session, every agent PTY spawned for it
+//! writes `sessions/{id}/recordings/{pty_id}-{timestamp}.cast`: a JSON header
+//! line followed by one `[seconds, "o", text]` line per chunk of output. The files
+//! play back with `asciinema play` or the asciinema web player.
+
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+use std::time::Instant;
+
+use chrono::{DateTime, TimeZone, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+
+pub const RECORDINGS_DIR: &str = "recordings";
+
+const CAST_EXTENSION: &str = "cast";
+
+/// The header line of a v2 `.cast` file, as far as we read it back.
+#[derive(Debug, Deserialize)]
+struct CastHeader {
+    version: u8,
+    width: u16,
+    height: u16,
+    timestamp: Option<i64>,
+    title: Option<String>,
+}
+
+/// One recording in a session's `recordings/` directory.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RecordingInfo {
+    /// The PTY that was recorded, from the header's `title`.
+    pub agent_id: String,
+    pub path: PathBuf,
+    pub started_at: Option<DateTime<Utc>>,
+    /// Time of the last output event.
+    pub duration_secs: f64,
+    pub width: u16,
+    pub height: u16,
+    pub size_bytes: u64,
+}
+
+/// Answer to `list_recordings`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SessionRecordings {
+    /// Whether agents spawned for the session from now on are recorded.
+    pub enabled: bool,
+    pub recordings: Vec<RecordingInfo>,
+}
+
+/// Writes one PTY's output to a `.cast` file as it is read.
+pub struct CastRecorder {
+    file: File,
+    started: Instant,
+    /// Trailing bytes of a UTF-8 sequence split across reads.
+    pending: Vec<u8>,
+}
+
+impl CastRecorder {
+    /// Start a recording of `pty_id` in `dir`, named after the PTY and the start time.
+    pub fn create(
+        dir: &Path,
+        pty_id: &str,
+        command: &str,
+        cols: u16,
+        rows: u16,
+    ) -> io::Result<(Self, PathBuf)> {
+        fs::create_dir_all(dir)?;
+        let now = Utc::now();
+        let path = dir.join(format!(
+            "{}-{}.{}",
+            pty_id,
+            now.format("%Y%m%dT%H%M%S%.3fZ"),
+            CAST_EXTENSION
+        ));
+        let mut file = OpenOptions::new().write(true).create_new(true).open(&path)?;
+        let header = json!({
+            "version": 2,
+            "width": cols,
+            "height": rows,
+            "timestamp": now.timestamp(),
+            "title": pty_id,
+            "command": command,
+            "env": { "TERM": "xterm-256color" },
+        });
+        writeln!(file, "{}", header)?;
+        Ok((
+            Self {
+                file,
+                started: Instant::now(),
+                pending: Vec::new(),
+            },
+            path,
+        ))
+    }
+
+    /// Append an output event for `data`. Bytes of an incomplete UTF-8 sequence at
+    /// the end are held back until the next read completes them.
+    pub fn output(&mut self, data: &[u8]) -> io::Result<()> {
+        self.pending.extend_from_slice(data);
+        let complete = match std::str::from_utf8(&self.pending) {
+            Err(e) if e.error_len().is_none() => e.valid_up_to(),
+            _ => self.pending.len(),
+        };
+        if complete == 0 {
+            return Ok(());
+        }
+        let text = String::from_utf8_lossy(&self.pending[..complete]).into_owned();
+        self.pending.drain(..complete);
+        self.event("o", &text)
+    }
+
+    fn event(&mut self, kind: &str, data: &str) -> io::Result<()> {
+        let elapsed = (self.started.elapsed().as_secs_f64() * 1_000_000.0).round() / 1_000_000.0;
+        // One write per line so a crash never leaves half an event behind.
+        let line = format!("{}\n", json!([elapsed, kind, data]));
+        self.file.write_all(line.as_bytes())
+    }
+}
+
+/// Recordings in `dir`, oldest first; empty when nothing was recorded yet. Files
+/// without a v2 header are skipped.
+pub fn list(dir: &Path) -> io::Result<Vec<RecordingInfo>> {
+    let entries = match fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(e),
+    };
+
+    let mut recordings = Vec::new();
+    for entry in entries {
+        let path = entry?.path();
+        if path.extension().and_then(|ext| ext.to_str()) != Some(CAST_EXTENSION) {
+            continue;
+        }
+        match read_info(&path) {
+            Ok(Some(info)) => recordings.push(info),
+            Ok(None) => {}
+            Err(e) => tracing::warn!("Skipping unreadable recording {}: {}", path.display(), e),
+        }
+    }
+    recordings.sort_by(|a, b| {
+        a.started_at
+            .cmp(&b.started_at)
+            .then_with(|| a.path.cmp(&b.path))
+    });
+    Ok(recordings)
+}
+
+fn read_info(path: &Path) -> io::Result<Option<RecordingInfo>> {
+    let size_bytes = fs::metadata(path)?.len();
+    let mut lines = BufReader::new(File::open(path)?).lines();
+    let Some(header) = lines
+        .next()
+        .transpose()?
+        .and_then(|line| serde_json::from_str::<CastHeader>(&line).ok())
+        .filter(|header| header.version == 2)
+    else {
+        return Ok(None);
+    };
+
+    let mut duration_secs = 0.0;
+    for line in lines {
+        if let Some(time) = serde_json::from_str::<(f64, String, String)>(&line?)
+            .ok()
+            .map(|(time, _, _)| time)
+        {
+            duration_secs = time;
+        }
+    }
+
+    let agent_id = header.title.unwrap_or_else(|| {
+        path.file_stem()
+            .map(|stem| stem.to_string_lossy().into_owned())
+            .unwrap_or_default()
+    });
+    Ok(Some(RecordingInfo {
+        agent_id,
+        path: path.to_path_buf(),
+        started_at: header
+            .timestamp
+            .and_then(|secs| Utc.timestamp_opt(secs, 0).single()),
+        duration_secs,
+        width: header.width,
+        height: header.height,
+        size_bytes,
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_recording_writes_a_v2_cast_and_lists_it() {
+        let dir = tempfile::tempdir().unwrap();
+        let recordings = dir.path().join(RECORDINGS_DIR);
+        assert!(list(&recordings).unwrap().is_empty());
+
+        let (mut recorder, path) =
+            CastRecorder::create(&recordings, "s1-worker-1", "claude", 120, 30).unwrap();
+        recorder.output(b"hello\r\n").unwrap();
+        // "é" split across two reads is written once, whole.
+        recorder.output(&[b'a', 0xC3]).unwrap();
+        recorder.output(&[0xA9]).unwrap();
+        drop(recorder);
+        fs::write(recordings.join("notes.txt"), "not a recording").unwrap();
+
+        let content = fs::read_to_string(&path).unwrap();
+        let lines: Vec<serde_json::Value> = content
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        assert_eq!(lines[0]["version"], 2);
+        assert_eq!(lines[0]["width"], 120);
+        assert_eq!(lines[0]["title"], "s1-worker-1");
+        assert_eq!(lines[1][1], "o");
+        assert_eq!(lines[1][2], "hello\r\n");
+        assert_eq!(lines[2][2], "a");
+        assert_eq!(lines[3][2], "é");
+        assert_eq!(lines.len(), 4);
+
+        let listed = list(&recordings).unwrap();
+        assert_eq!(listed.len(), 1);
+        assert_eq!(listed[0].agent_id, "s1-worker-1");
+        assert_eq!(listed[0].path, path);
+        assert_eq!((listed[0].width, listed[0].height), (120, 30));
+        assert!(listed[0].started_at.is_some());
+        assert_eq!(listed[0].size_bytes, content.len() as u64);
+    }
+}
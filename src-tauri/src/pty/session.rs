@@ -1,10 +1,18 @@
 use serde::{Deserialize, Serialize};
 use std::io::{Read, Write};
 use std::borrow::Cow;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
+use std::time::{Duration, Instant};
 use parking_lot::Mutex;
 use thiserror::Error;
 
+use super::environment::CliEnvironment;
+use super::exit::AgentExitInfo;
+
+/// How long to wait for an exit status after the PTY output closes.
+const EXIT_STATUS_WAIT: Duration = Duration::from_secs(2);
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum AgentRole {
     MasterPlanner,  // Initial planning agent that generates plan.md
@@ -30,6 +38,8 @@ pub enum AgentStatus {
     Idle,
     WaitingForInput(String),
     Completed,
+    /// The process exited on its own with a failure; carries the exit reason.
+    Crashed(String),
     Error(String),
 }
 
@@ -73,6 +83,10 @@ pub struct AgentConfig {
     pub description: Option<String>, // One-line task summary
     pub role: Option<WorkerRole>, // Worker role assignment
     pub initial_prompt: Option<String>, // Prompt to inject on spawn
+    #[serde(default)]
+    pub allowed_paths: Vec<String>, // Globs the agent may change; empty allows all
+    #[serde(default)]
+    pub denied_paths: Vec<String>,  // Globs the agent must not change
 }
 
 fn default_cli() -> String {
@@ -90,6 +104,8 @@ impl Default for AgentConfig {
             description: None,
             role: None,
             initial_prompt: None,
+            allowed_paths: vec![],
+            denied_paths: vec![],
         }
     }
 }
@@ -180,12 +196,18 @@ fn sanitize_bracketed_paste(data: &[u8]) -> Cow<'_, [u8]> {
 }
 
 pub struct PtySession {
-    pub role: AgentRole,
+    /// The ID and role the manager holds this process under; a warm-pool process
+    /// takes on its worker's when handed over.
+    identity: parking_lot::RwLock<(String, AgentRole)>,
     pub status: Arc<parking_lot::RwLock<AgentStatus>>,
     writer: Arc<Mutex<SendWriter>>,
     reader: Arc<Mutex<SendReader>>,
     child: Arc<Mutex<Option<Box<dyn portable_pty::Child + Send + Sync>>>>,
     master: Arc<Mutex<MasterPtyHandle>>,
+    /// Set once we kill the process, so its exit is not reported as a crash.
+    stopped: AtomicBool,
+    /// When the process last produced output.
+    last_output: Mutex<Instant>,
 }
 
 // Make PtySession Send + Sync
@@ -193,14 +215,19 @@ unsafe impl Send for PtySession {}
 unsafe impl Sync for PtySession {}
 
 impl PtySession {
+    /// Spawn `command` in a new PTY; `background` runs it below normal OS priority
+    /// (see `pty::priority`).
+    #[allow(clippy::too_many_arguments)]
     pub fn new(
-        _id: String,
+        id: String,
         role: AgentRole,
         command: &str,
         args: &[&str],
         cwd: Option<&str>,
+        env: &CliEnvironment,
         cols: u16,
         rows: u16,
+        background: bool,
     ) -> Result<Self, PtyError> {
         use portable_pty::{CommandBuilder, NativePtySystem, PtySize, PtySystem};
 
@@ -219,9 +246,10 @@ impl PtySession {
 
         // On Windows, create a batch file to avoid shell quoting issues
         // This is the same pattern used by /hive command
-        let mut cmd = if cfg!(windows) {
+        #[cfg(windows)]
+        let mut cmd = {
             // Create temp batch file with the full command
-            let batch_content = Self::create_batch_content(command, args);
+            let batch_content = Self::create_batch_content(command, args, background);
             let batch_path = Self::write_temp_batch(&batch_content)?;
 
             tracing::info!("Created batch file: {} with content:\n{}", batch_path.display(), batch_content);
@@ -229,12 +257,69 @@ impl PtySession {
             let mut cmd = CommandBuilder::new("cmd.exe");
             cmd.args(&["/c", &batch_path.to_string_lossy()]);
             cmd
-        } else {
-            let mut cmd = CommandBuilder::new(command);
-            cmd.args(args);
-            cmd
         };
 
+        // Elsewhere, start the command through the user's login shell so their
+        // profile's PATH applies; shells without POSIX syntax run it directly, and
+        // so do CLIs isolated from the host environment, profile included.
+        #[cfg(not(windows))]
+        let mut cmd = {
+            let (program, program_args) = if background {
+                super::priority::niced(command, args)
+            } else {
+                (
+                    command.to_string(),
+                    args.iter().map(|arg| arg.to_string()).collect(),
+                )
+            };
+            let program_args: Vec<&str> = program_args.iter().map(String::as_str).collect();
+            let shell = super::shell::user_shell();
+            let wrapped = if env.is_isolated() {
+                None
+            } else if let Some(prefix) = env.path_prefix() {
+                super::shell::through_login_shell_with_path(
+                    &shell,
+                    &prefix.to_string_lossy(),
+                    &program,
+                    &program_args,
+                )
+            } else {
+                super::shell::through_login_shell(&shell, &program, &program_args)
+            };
+            match wrapped {
+                Some(wrapped) => {
+                    let mut cmd = CommandBuilder::new(&wrapped.program);
+                    cmd.args(&wrapped.args);
+                    cmd
+                }
+                None => {
+                    let mut cmd = CommandBuilder::new(&program);
+                    cmd.args(&program_args);
+                    cmd
+                }
+            }
+        };
+
+        // Set on the builder (not in the Windows batch file) so resolved secrets never
+        // touch disk; cmd.exe passes the environment through to the CLI.
+        if let Some(kept) = env.kept_host_vars(std::env::vars_os()) {
+            cmd.env_clear();
+            for (key, value) in kept {
+                cmd.env(key, value);
+            }
+        }
+        // The Windows batch file sets this too.
+        #[cfg(not(windows))]
+        if command == "opencode" {
+            cmd.env("OPENCODE_YOLO", "true");
+        }
+        for (key, value) in &env.vars {
+            cmd.env(key, value);
+        }
+        if let Some(path) = env.path(cmd.get_env("PATH")) {
+            cmd.env("PATH", path);
+        }
+
         if let Some(dir) = cwd {
             cmd.cwd(dir);
         }
@@ -258,15 +343,30 @@ impl PtySession {
         let master = pty_pair.master;
 
         Ok(Self {
-            role,
+            identity: parking_lot::RwLock::new((id, role)),
             status: Arc::new(parking_lot::RwLock::new(AgentStatus::Starting)),
             writer: Arc::new(Mutex::new(SendWriter(writer))),
             reader: Arc::new(Mutex::new(SendReader(reader))),
             child: Arc::new(Mutex::new(Some(child))),
             master: Arc::new(Mutex::new(MasterPtyHandle(master))),
+            stopped: AtomicBool::new(false),
+            last_output: Mutex::new(Instant::now()),
         })
     }
 
+    pub fn id(&self) -> String {
+        self.identity.read().0.clone()
+    }
+
+    pub fn role(&self) -> AgentRole {
+        self.identity.read().1.clone()
+    }
+
+    /// Hand the process to another agent, which it reports and is listed as from now on.
+    pub fn reassign(&self, id: String, role: AgentRole) {
+        *self.identity.write() = (id, role);
+    }
+
     pub fn write(&self, data: &[u8]) -> Result<(), PtyError> {
         tracing::debug!("PTY write: {} bytes: {:?}", data.len(), String::from_utf8_lossy(data));
         let mut writer = self.writer.lock();
@@ -319,7 +419,18 @@ impl PtySession {
         Ok(())
     }
 
+    /// Record that the process just produced output.
+    pub fn mark_output(&self) {
+        *self.last_output.lock() = Instant::now();
+    }
+
+    /// How long the process has been silent.
+    pub fn idle_for(&self) -> Duration {
+        self.last_output.lock().elapsed()
+    }
+
     pub fn kill(&self) -> Result<(), PtyError> {
+        self.stopped.store(true, Ordering::SeqCst);
         let mut child = self.child.lock();
         if let Some(ref mut c) = *child {
             c.kill().map_err(into_io_error)?;
@@ -339,6 +450,40 @@ impl PtySession {
         }
     }
 
+    /// Collect how the process ended once its output has closed. The exit status can lag
+    /// EOF slightly, so poll briefly before reporting it as unknown.
+    pub fn exit_info(&self, last_output: Vec<String>) -> AgentExitInfo {
+        let deadline = Instant::now() + EXIT_STATUS_WAIT;
+        let status = loop {
+            let polled = match self.child.lock().as_mut() {
+                Some(child) => child.try_wait().ok().flatten(),
+                None => break None,
+            };
+            if polled.is_some() || Instant::now() >= deadline {
+                break polled;
+            }
+            std::thread::sleep(Duration::from_millis(20));
+        };
+
+        let (exit_code, signal) = match status {
+            // portable-pty only exposes the signal through its `Display` impl.
+            Some(status) => (
+                Some(status.exit_code()),
+                status
+                    .to_string()
+                    .strip_prefix("Terminated by ")
+                    .map(str::to_string),
+            ),
+            None => (None, None),
+        };
+        AgentExitInfo::new(
+            exit_code,
+            signal,
+            self.stopped.load(Ordering::SeqCst),
+            last_output,
+        )
+    }
+
     /// Gracefully terminate the process by sending Ctrl+C, waiting, then killing if needed
     #[allow(dead_code)]
     pub async fn graceful_terminate(&self) -> Result<(), PtyError> {
@@ -367,7 +512,7 @@ impl PtySession {
 
     /// Create batch file content for Windows command execution
     #[cfg(windows)]
-    fn create_batch_content(command: &str, args: &[&str]) -> String {
+    fn create_batch_content(command: &str, args: &[&str], background: bool) -> String {
         let mut lines = vec!["@echo off".to_string()];
 
         // Add CLI-specific environment variables
@@ -388,15 +533,13 @@ impl PtySession {
             }
         }
 
+        if background {
+            cmd_line = super::priority::below_normal_batch_line(&cmd_line);
+        }
         lines.push(cmd_line);
         lines.join("\r\n")
     }
 
-    #[cfg(not(windows))]
-    fn create_batch_content(_command: &str, _args: &[&str]) -> String {
-        String::new()
-    }
-
     /// Write a temporary batch file and return its path
     #[cfg(windows)]
     fn write_temp_batch(content: &str) -> Result<std::path::PathBuf, PtyError> {
@@ -418,11 +561,6 @@ impl PtySession {
 
         Ok(path)
     }
-
-    #[cfg(not(windows))]
-    fn write_temp_batch(_content: &str) -> Result<std::path::PathBuf, PtyError> {
-        Err(PtyError::CreateError("Batch files only supported on Windows".to_string()))
-    }
 }
 
 impl Drop for PtySession {
@@ -4,9 +4,14 @@ use parking_lot::Mutex;
 use serde::{Deserialize, Serialize};
 use std::borrow::Cow;
 use std::io::{Read, Write};
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
+use std::time::{Duration, Instant};
 use thiserror::Error;
 
+use super::environment::CliEnvironment;
+use super::exit::AgentExitInfo;
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum AgentRole {
     MasterPlanner,
@@ -28,6 +33,8 @@ pub enum AgentStatus {
     Idle,
     WaitingForInput(String),
     Completed,
+    /// The process exited on its own with a failure; carries the exit reason.
+    Crashed(String),
     Error(String),
 }
 
@@ -70,6 +77,10 @@ pub struct AgentConfig {
     pub description: Option<String>,
     pub role: Option<WorkerRole>,
     pub initial_prompt: Option<String>,
+    #[serde(default)]
+    pub allowed_paths: Vec<String>,
+    #[serde(default)]
+    pub denied_paths: Vec<String>,
 }
 
 fn default_cli() -> String {
@@ -87,6 +98,8 @@ impl Default for AgentConfig {
             description: None,
             role: None,
             initial_prompt: None,
+            allowed_paths: vec![],
+            denied_paths: vec![],
         }
     }
 }
@@ -150,35 +163,57 @@ fn sanitize_bracketed_paste(data: &[u8]) -> Cow<'_, [u8]> {
 }
 
 pub struct PtySession {
-    pub role: AgentRole,
+    /// The ID and role the manager holds this process under; a warm-pool process
+    /// takes on its worker's when handed over.
+    identity: parking_lot::RwLock<(String, AgentRole)>,
     pub status: Arc<parking_lot::RwLock<AgentStatus>>,
     writer: Arc<Mutex<SendWriter>>,
     reader: Arc<Mutex<SendReader>>,
+    stopped: AtomicBool,
+    last_output: Mutex<Instant>,
 }
 
 unsafe impl Send for PtySession {}
 unsafe impl Sync for PtySession {}
 
 impl PtySession {
+    #[allow(clippy::too_many_arguments)]
     pub fn new(
-        _id: String,
+        id: String,
         role: AgentRole,
         _command: &str,
         _args: &[&str],
         _cwd: Option<&str>,
+        _env: &CliEnvironment,
         _cols: u16,
         _rows: u16,
+        _background: bool,
     ) -> Result<Self, PtyError> {
         Ok(Self {
-            role,
+            identity: parking_lot::RwLock::new((id, role)),
             status: Arc::new(parking_lot::RwLock::new(AgentStatus::Starting)),
             writer: Arc::new(Mutex::new(SendWriter(Box::new(std::io::sink())))),
             reader: Arc::new(Mutex::new(SendReader(Box::new(std::io::Cursor::new(
                 Vec::new(),
             ))))),
+            stopped: AtomicBool::new(false),
+            last_output: Mutex::new(Instant::now()),
         })
     }
 
+    pub fn id(&self) -> String {
+        self.identity.read().0.clone()
+    }
+
+    pub fn role(&self) -> AgentRole {
+        self.identity.read().1.clone()
+    }
+
+    /// Hand the process to another agent, which it reports and is listed as from now on.
+    pub fn reassign(&self, id: String, role: AgentRole) {
+        *self.identity.write() = (id, role);
+    }
+
     pub fn write(&self, data: &[u8]) -> Result<(), PtyError> {
         let mut writer = self.writer.lock();
 
@@ -208,7 +243,16 @@ impl PtySession {
         Ok(())
     }
 
+    pub fn mark_output(&self) {
+        *self.last_output.lock() = Instant::now();
+    }
+
+    pub fn idle_for(&self) -> Duration {
+        self.last_output.lock().elapsed()
+    }
+
     pub fn kill(&self) -> Result<(), PtyError> {
+        self.stopped.store(true, Ordering::SeqCst);
         Ok(())
     }
 
@@ -217,6 +261,10 @@ impl PtySession {
         false
     }
 
+    pub fn exit_info(&self, last_output: Vec<String>) -> AgentExitInfo {
+        AgentExitInfo::new(None, None, self.stopped.load(Ordering::SeqCst), last_output)
+    }
+
     #[allow(dead_code)]
     pub async fn graceful_terminate(&self) -> Result<(), PtyError> {
         Ok(())
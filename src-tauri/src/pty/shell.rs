@@ -0,0 +1,243 @@
+//! Platform shells for PTYs.
+//!
+//! On Windows agents run from a `cmd.exe` batch file (see `PtySession::new`). On
+//! macOS and Linux they are started through the user's login shell instead, so the
+//! PATH and environment from their shell profile (nvm, Homebrew, `~/.local/bin`)
+//! apply even when the app was opened from a desktop launcher with a bare
+//! environment.
+
+/// A program and the arguments to start it with.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ShellCommand {
+    pub program: String,
+    pub args: Vec<String>,
+}
+
+/// Shells that understand `-l` and `-c 'exec "$0" "$@"'`.
+const POSIX_SHELLS: [&str; 7] = ["sh", "bash", "zsh", "ksh", "mksh", "dash", "ash"];
+
+/// The user's shell: `%COMSPEC%` (else `cmd.exe`) on Windows; elsewhere `$SHELL`,
+/// or the first of the platform's usual shells that exists.
+pub fn user_shell() -> String {
+    #[cfg(windows)]
+    {
+        std::env::var("COMSPEC")
+            .ok()
+            .filter(|comspec| !comspec.trim().is_empty())
+            .unwrap_or_else(|| "cmd.exe".to_string())
+    }
+    #[cfg(not(windows))]
+    {
+        unix_shell(std::env::var("SHELL").ok().as_deref(), |path| {
+            std::path::Path::new(path).is_file()
+        })
+    }
+}
+
+#[cfg_attr(windows, allow(dead_code))]
+fn unix_shell(shell_env: Option<&str>, exists: impl Fn(&str) -> bool) -> String {
+    let fallbacks = if cfg!(target_os = "macos") {
+        ["/bin/zsh", "/bin/bash", "/bin/sh"]
+    } else {
+        ["/bin/bash", "/bin/zsh", "/bin/sh"]
+    };
+    shell_env
+        .map(str::trim)
+        .filter(|shell| shell.starts_with('/') && exists(shell))
+        .or_else(|| fallbacks.into_iter().find(|shell| exists(shell)))
+        .unwrap_or("/bin/sh")
+        .to_string()
+}
+
+fn shell_name(shell: &str) -> &str {
+    shell.rsplit(['/', '\\']).next().unwrap_or(shell)
+}
+
+pub fn is_posix_shell(shell: &str) -> bool {
+    POSIX_SHELLS.contains(&shell_name(shell))
+}
+
+/// The user's shell as an interactive login shell, for scratch terminals.
+pub fn login_shell() -> ShellCommand {
+    let program = user_shell();
+    let login = !cfg!(windows) && (is_posix_shell(&program) || shell_name(&program) == "fish");
+    ShellCommand {
+        args: if login { vec!["-l".to_string()] } else { Vec::new() },
+        program,
+    }
+}
+
+/// `command args...` run by `shell` as a login shell, which then `exec`s it. The
+/// command and its arguments are passed as positional parameters, never spliced
+/// into the script, so nothing needs quoting. `None` for shells that do not take
+/// POSIX syntax; those callers start the command directly.
+#[cfg_attr(windows, allow(dead_code))]
+pub fn through_login_shell(shell: &str, command: &str, args: &[&str]) -> Option<ShellCommand> {
+    login_shell_script(shell, r#"exec "$0" "$@""#, command, args)
+}
+
+/// [`through_login_shell`], with `path_prefix` put in front of PATH once the
+/// profile has run, so the profile cannot move other versions of a tool ahead of
+/// the pinned ones. The prefix is passed as a parameter too.
+#[cfg_attr(windows, allow(dead_code))]
+pub fn through_login_shell_with_path(
+    shell: &str,
+    path_prefix: &str,
+    command: &str,
+    args: &[&str],
+) -> Option<ShellCommand> {
+    let mut positional = vec![command];
+    positional.extend_from_slice(args);
+    login_shell_script(
+        shell,
+        r#"PATH="$0:$PATH"; export PATH; exec "$@""#,
+        path_prefix,
+        &positional,
+    )
+}
+
+/// `command args...` as one line to paste into a terminal: arguments quoted for a
+/// POSIX shell, or in double quotes for `cmd.exe` on Windows, when they need it.
+pub fn command_line(command: &str, args: &[String]) -> String {
+    std::iter::once(command)
+        .chain(args.iter().map(String::as_str))
+        .map(quote_argument)
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+fn quote_argument(arg: &str) -> String {
+    // A backslash separates Windows paths but escapes the next character in sh.
+    let plain_chars = if cfg!(windows) { "-_./:=@+,\\" } else { "-_./:=@+," };
+    let plain = !arg.is_empty()
+        && arg.chars().all(|c| c.is_ascii_alphanumeric() || plain_chars.contains(c));
+    if plain {
+        arg.to_string()
+    } else if cfg!(windows) {
+        format!("\"{}\"", arg.replace('"', "\\\""))
+    } else {
+        format!("'{}'", arg.replace('\'', r"'\''"))
+    }
+}
+
+/// `shell -l -c script arg0 args...`.
+#[cfg_attr(windows, allow(dead_code))]
+fn login_shell_script(
+    shell: &str,
+    script: &str,
+    arg0: &str,
+    args: &[&str],
+) -> Option<ShellCommand> {
+    if !is_posix_shell(shell) {
+        return None;
+    }
+    let mut shell_args = vec![
+        "-l".to_string(),
+        "-c".to_string(),
+        script.to_string(),
+        arg0.to_string(),
+    ];
+    shell_args.extend(args.iter().map(|arg| arg.to_string()));
+    Some(ShellCommand {
+        program: shell.to_string(),
+        args: shell_args,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_unix_shell_prefers_shell_env_then_platform_fallbacks() {
+        let installed = |path: &str| ["/bin/bash", "/bin/zsh", "/usr/bin/fish"].contains(&path);
+        assert_eq!(unix_shell(Some("/usr/bin/fish"), installed), "/usr/bin/fish");
+
+        let fallback = if cfg!(target_os = "macos") {
+            "/bin/zsh"
+        } else {
+            "/bin/bash"
+        };
+        assert_eq!(unix_shell(Some("/opt/missing/zsh"), installed), fallback);
+        assert_eq!(unix_shell(Some("zsh"), installed), fallback);
+        assert_eq!(unix_shell(None, installed), fallback);
+        assert_eq!(unix_shell(None, |_| false), "/bin/sh");
+    }
+
+    #[test]
+    fn test_commands_run_through_posix_login_shells_only() {
+        let wrapped = through_login_shell("/bin/zsh", "claude", &["--model", "opus 4"]).unwrap();
+        assert_eq!(wrapped.program, "/bin/zsh");
+        assert_eq!(
+            wrapped.args,
+            vec!["-l", "-c", r#"exec "$0" "$@""#, "claude", "--model", "opus 4"]
+        );
+        assert!(through_login_shell("/usr/bin/fish", "claude", &[]).is_none());
+        assert!(through_login_shell("C:\\Windows\\System32\\cmd.exe", "claude", &[]).is_none());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_login_shell_is_an_absolute_unix_shell() {
+        let shell = login_shell();
+        assert!(shell.program.starts_with('/'));
+        if is_posix_shell(&shell.program) {
+            assert_eq!(shell.args, vec!["-l"]);
+        }
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_login_shell_runs_the_command_with_arguments_intact() {
+        let wrapped =
+            through_login_shell("/bin/sh", "printf", &["%s|", "two words", "$HOME"]).unwrap();
+        let output = std::process::Command::new(&wrapped.program)
+            .args(&wrapped.args)
+            .output()
+            .expect("run /bin/sh");
+        assert_eq!(String::from_utf8_lossy(&output.stdout), "two words|$HOME|");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_command_line_quotes_arguments_for_the_shell() {
+        let args = ["--model", "opus 4", "Read /tmp/it's prompt.md and execute.", ""]
+            .map(str::to_string);
+        let line = command_line("claude", &args);
+        assert_eq!(
+            line,
+            r#"claude --model 'opus 4' 'Read /tmp/it'\''s prompt.md and execute.' ''"#
+        );
+
+        let script = format!("printf '%s|' {}", line.trim_start_matches("claude "));
+        let output = std::process::Command::new("/bin/sh")
+            .args(["-c", script.as_str()])
+            .output()
+            .expect("run /bin/sh");
+        assert_eq!(
+            String::from_utf8_lossy(&output.stdout),
+            "--model|opus 4|Read /tmp/it's prompt.md and execute.||"
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_login_shell_puts_the_path_prefix_first() {
+        let wrapped =
+            through_login_shell_with_path("/bin/sh", "/opt/pinned/bin", "printenv", &["PATH"])
+                .unwrap();
+        let output = std::process::Command::new(&wrapped.program)
+            .args(&wrapped.args)
+            .output()
+            .expect("run /bin/sh");
+        assert!(String::from_utf8_lossy(&output.stdout).starts_with("/opt/pinned/bin:"));
+    }
+
+    #[cfg(windows)]
+    #[test]
+    fn test_windows_shell_is_cmd_without_login_flag() {
+        let shell = login_shell();
+        assert!(shell.program.to_ascii_lowercase().ends_with("cmd.exe"));
+        assert!(shell.args.is_empty());
+    }
+}
@@ -0,0 +1,104 @@
+//! Completing a session on its own once its work is done.
+//!
+//! A session counts as settled when every agent whose process is still running
+//! last reported `idle` or `completed` in its heartbeat and every worker's task
+//! file is COMPLETED. Once it has stayed settled for `idle_secs` (see
+//! [`AutoCompleteConfig`]) the controller announces that it will complete the
+//! session, and does so `grace_secs` later unless an agent becomes active again
+//! in the meantime.
+
+use chrono::{DateTime, Duration, Utc};
+
+use crate::storage::AutoCompleteConfig;
+
+/// Heartbeat statuses of an agent with nothing left to do.
+pub const SETTLED_STATUSES: [&str; 2] = ["idle", "completed"];
+
+/// What the controller should do for a session after one check.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AutoCompleteStep {
+    Wait,
+    /// Settled for the idle period; the session completes at `deadline`.
+    Announce { deadline: DateTime<Utc> },
+    /// Active again after an announcement.
+    Cancel,
+    Complete,
+}
+
+/// Per-session progress towards auto-completion.
+#[derive(Debug, Clone, Default)]
+pub struct AutoCompleteClock {
+    settled_since: Option<DateTime<Utc>>,
+    deadline: Option<DateTime<Utc>>,
+}
+
+impl AutoCompleteClock {
+    pub fn advance(
+        &mut self,
+        settled: bool,
+        now: DateTime<Utc>,
+        config: &AutoCompleteConfig,
+    ) -> AutoCompleteStep {
+        if !settled {
+            let announced = self.deadline.is_some();
+            *self = Self::default();
+            return if announced {
+                AutoCompleteStep::Cancel
+            } else {
+                AutoCompleteStep::Wait
+            };
+        }
+
+        let settled_since = *self.settled_since.get_or_insert(now);
+        match self.deadline {
+            Some(deadline) if now >= deadline => AutoCompleteStep::Complete,
+            Some(_) => AutoCompleteStep::Wait,
+            None if now - settled_since >= secs(config.idle_secs) => {
+                let deadline = now + secs(config.grace_secs);
+                self.deadline = Some(deadline);
+                AutoCompleteStep::Announce { deadline }
+            }
+            None => AutoCompleteStep::Wait,
+        }
+    }
+}
+
+fn secs(secs: u64) -> Duration {
+    Duration::seconds(secs.min(u64::from(u32::MAX)) as i64)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_clock_announces_after_idle_period_and_completes_after_grace() {
+        let config = AutoCompleteConfig {
+            enabled: true,
+            idle_secs: 300,
+            grace_secs: 60,
+        };
+        let start = Utc::now();
+        let at = |secs: i64| start + Duration::seconds(secs);
+        let mut clock = AutoCompleteClock::default();
+
+        assert_eq!(clock.advance(true, at(0), &config), AutoCompleteStep::Wait);
+        assert_eq!(clock.advance(true, at(299), &config), AutoCompleteStep::Wait);
+        assert_eq!(
+            clock.advance(true, at(300), &config),
+            AutoCompleteStep::Announce { deadline: at(360) }
+        );
+        assert_eq!(clock.advance(true, at(330), &config), AutoCompleteStep::Wait);
+
+        // An agent picking up work again cancels the announcement and restarts
+        // the idle period.
+        assert_eq!(clock.advance(false, at(340), &config), AutoCompleteStep::Cancel);
+        assert_eq!(clock.advance(false, at(350), &config), AutoCompleteStep::Wait);
+        assert_eq!(clock.advance(true, at(400), &config), AutoCompleteStep::Wait);
+        assert_eq!(
+            clock.advance(true, at(700), &config),
+            AutoCompleteStep::Announce { deadline: at(760) }
+        );
+        assert_eq!(clock.advance(true, at(760), &config), AutoCompleteStep::Complete);
+    }
+}
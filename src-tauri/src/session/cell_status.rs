@@ -49,6 +49,7 @@ pub(crate) fn agent_in_cell(session: &Session, cell_id: &str, agent: &AgentInfo)
 
 pub(crate) fn session_state_to_cell_status(state: &SessionState) -> CellStatus {
     match state {
+        SessionState::Queued => CellStatus::Queued,
         SessionState::Planning | SessionState::PlanReady => CellStatus::Preparing,
         SessionState::Starting
         | SessionState::SpawningWorker(_)
@@ -61,10 +62,11 @@ pub(crate) fn session_state_to_cell_status(state: &SessionState) -> CellStatus {
         | SessionState::WaitingForPlanner(_)
         | SessionState::WaitingForFusionVariants
         | SessionState::WaitingForDebateRound(_)
-        | SessionState::Judging
+        | SessionState::Judging { .. }
         | SessionState::MergingWinner
         | SessionState::QaInProgress { .. }
         | SessionState::PrinceRemediation
+        | SessionState::Reviewing { .. }
         | SessionState::Running => CellStatus::Running,
         SessionState::AwaitingVerdictSelection
         | SessionState::Paused
@@ -239,7 +241,7 @@ fn agent_status_to_cell_status(status: &AgentStatus) -> CellStatus {
         AgentStatus::Running | AgentStatus::Idle => CellStatus::Running,
         AgentStatus::WaitingForInput(_) => CellStatus::WaitingInput,
         AgentStatus::Completed => CellStatus::Completed,
-        AgentStatus::Error(_) => CellStatus::Failed,
+        AgentStatus::Crashed(_) | AgentStatus::Error(_) => CellStatus::Failed,
     }
 }
 
@@ -281,6 +283,7 @@ mod tests {
                     parent_id: None,
                     commit_sha: None,
                     base_commit_sha: None,
+                    exit_info: None,
                 })
                 .collect(),
             default_cli: "claude".to_string(),
@@ -297,6 +300,8 @@ mod tests {
             worktree_branch: None,
             no_git: false,
             resume_report: None,
+            pull_request_url: None,
+            spawn_quota: crate::session::SpawnQuota::default(),
         }
     }
 
@@ -434,6 +439,7 @@ mod tests {
             parent_id: None,
             commit_sha: None,
             base_commit_sha: None,
+            exit_info: None,
         };
         let judge_agent = AgentInfo {
             id: "judge-agent".to_string(),
@@ -445,6 +451,7 @@ mod tests {
             parent_id: None,
             commit_sha: None,
             base_commit_sha: None,
+            exit_info: None,
         };
 
         let session = Session {
@@ -471,6 +478,7 @@ mod tests {
             parent_id: None,
             commit_sha: None,
             base_commit_sha: None,
+            exit_info: None,
         };
         let judge_agent = AgentInfo {
             id: "judge-agent".to_string(),
@@ -482,6 +490,7 @@ mod tests {
             parent_id: None,
             commit_sha: None,
             base_commit_sha: None,
+            exit_info: None,
         };
 
         let session = Session {
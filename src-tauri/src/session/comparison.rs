@@ -0,0 +1,103 @@
+//! Side-by-side numbers for two sessions, so runs of different session types on
+//! the same repository (a Swarm against a Hive, say) can be judged on time spent,
+//! work finished and how much they changed.
+
+use std::collections::BTreeMap;
+
+use serde::Serialize;
+
+use super::SessionState;
+
+/// What one session took and produced.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct SessionRunStats {
+    pub session_id: String,
+    pub session_type: String,
+    pub state: SessionState,
+    pub project_path: String,
+    pub total_secs: i64,
+    pub phase_totals: BTreeMap<String, i64>,
+    pub agents: usize,
+    pub tasks_total: usize,
+    pub tasks_completed: usize,
+    /// Lines workers changed against their starting commits; `None` when no
+    /// worktree could be measured (for example once they were cleaned up).
+    pub diff_lines: Option<u64>,
+    pub learnings: usize,
+    /// Not tracked yet.
+    pub cost_usd: Option<f64>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct SessionComparison {
+    pub a: SessionRunStats,
+    pub b: SessionRunStats,
+    /// `b` minus `a` for every phase either session went through.
+    pub phase_delta_secs: BTreeMap<String, i64>,
+    /// `b` minus `a`.
+    pub total_delta_secs: i64,
+    pub same_project: bool,
+}
+
+pub fn compare(a: SessionRunStats, b: SessionRunStats) -> SessionComparison {
+    let phase_delta_secs = a
+        .phase_totals
+        .keys()
+        .chain(b.phase_totals.keys())
+        .map(|phase| {
+            let secs = |stats: &SessionRunStats| stats.phase_totals.get(phase).copied();
+            (phase.clone(), secs(&b).unwrap_or(0) - secs(&a).unwrap_or(0))
+        })
+        .collect();
+    SessionComparison {
+        phase_delta_secs,
+        total_delta_secs: b.total_secs - a.total_secs,
+        same_project: a.project_path == b.project_path,
+        a,
+        b,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn stats(session_id: &str, phases: &[(&str, i64)]) -> SessionRunStats {
+        SessionRunStats {
+            session_id: session_id.to_string(),
+            session_type: "Hive".to_string(),
+            state: SessionState::Completed,
+            project_path: "/repo".to_string(),
+            total_secs: phases.iter().map(|(_, secs)| secs).sum(),
+            phase_totals: phases
+                .iter()
+                .map(|(phase, secs)| (phase.to_string(), *secs))
+                .collect(),
+            agents: 2,
+            tasks_total: 1,
+            tasks_completed: 1,
+            diff_lines: None,
+            learnings: 0,
+            cost_usd: None,
+        }
+    }
+
+    #[test]
+    fn test_compare_covers_phases_of_both_sessions() {
+        let comparison = compare(
+            stats("hive", &[("Planning", 120), ("Worker 1 active", 600)]),
+            stats("swarm", &[("Planning", 60), ("Planner 1 active", 300)]),
+        );
+
+        assert_eq!(comparison.total_delta_secs, -360);
+        assert!(comparison.same_project);
+        assert_eq!(
+            comparison.phase_delta_secs,
+            BTreeMap::from([
+                ("Planner 1 active".to_string(), 300),
+                ("Planning".to_string(), -60),
+                ("Worker 1 active".to_string(), -600),
+            ])
+        );
+    }
+}
@@ -0,0 +1,148 @@
+//! Context-window pressure per agent, read from what its CLI prints.
+//!
+//! Claude Code and Codex show how much context is left ("Context left until
+//! auto-compact: 12%", "37% context left") and say when they compact. The
+//! monitor splits each agent's PTY output into lines, hands them to the CLI's
+//! adapter, and keeps the latest reading so heartbeats can show which agents
+//! are close to the limit. An agent at or below the configured threshold can
+//! be sent the CLI's compact command; it is sent once until the agent
+//! compacts or its context frees up again.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+use crate::adapters::ContextSignal;
+use crate::pty::OutputTail;
+
+/// Completed lines held between two chunks of one agent's output.
+const MONITOR_TAIL_LINES: usize = 64;
+
+/// What an agent's CLI last said about its context window.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ContextPressure {
+    /// Context left, in percent, as last reported.
+    pub percent_left: Option<u8>,
+    /// The CLI warned that context is low without giving a figure.
+    #[serde(default)]
+    pub low: bool,
+    pub compacted_at: Option<DateTime<Utc>>,
+    /// When the compact command was last sent to the agent.
+    pub nudged_at: Option<DateTime<Utc>>,
+    pub updated_at: Option<DateTime<Utc>>,
+}
+
+impl ContextPressure {
+    pub fn apply(&mut self, signal: ContextSignal, at: DateTime<Utc>) {
+        match signal {
+            ContextSignal::Remaining { percent } => {
+                // More room than before means the CLI compacted on its own.
+                if self.percent_left.is_some_and(|previous| percent > previous) {
+                    self.nudged_at = None;
+                }
+                self.percent_left = Some(percent);
+                self.low = false;
+            }
+            ContextSignal::Low => self.low = true,
+            ContextSignal::Compacted => {
+                self.percent_left = None;
+                self.low = false;
+                self.compacted_at = Some(at);
+                self.nudged_at = None;
+            }
+        }
+        self.updated_at = Some(at);
+    }
+
+    /// At or below `threshold` percent left, or warned low.
+    pub fn is_critical(&self, threshold: u8) -> bool {
+        self.low || self.percent_left.is_some_and(|percent| percent <= threshold)
+    }
+
+    /// Critical and not yet sent the compact command.
+    pub fn wants_nudge(&self, threshold: u8) -> bool {
+        self.is_critical(threshold) && self.nudged_at.is_none()
+    }
+}
+
+/// Per-PTY line buffers feeding an adapter's context detection.
+#[derive(Debug, Default)]
+pub struct ContextMonitor {
+    tails: HashMap<String, OutputTail>,
+}
+
+impl ContextMonitor {
+    /// Add a chunk of `pty_id`'s output and return the signals found in it. The
+    /// line still being drawn is checked too, since TUIs redraw their status
+    /// line in place rather than ending it.
+    pub fn signals(
+        &mut self,
+        pty_id: &str,
+        data: &[u8],
+        detect: impl Fn(&str) -> Option<ContextSignal>,
+    ) -> Vec<ContextSignal> {
+        let tail = self
+            .tails
+            .entry(pty_id.to_string())
+            .or_insert_with(|| OutputTail::with_limit(MONITOR_TAIL_LINES));
+        tail.push(data);
+        let mut signals: Vec<ContextSignal> =
+            tail.take_lines().iter().filter_map(|line| detect(line)).collect();
+        signals.extend(detect(tail.current_line()));
+        signals
+    }
+
+    pub fn forget(&mut self, pty_id: &str) {
+        self.tails.remove(pty_id);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::adapters::{ClaudeCodeAdapter, CliAdapter};
+
+    #[test]
+    fn test_nudge_is_sent_once_until_context_frees_up() {
+        let now = Utc::now();
+        let mut pressure = ContextPressure::default();
+
+        pressure.apply(ContextSignal::Remaining { percent: 40 }, now);
+        assert!(!pressure.wants_nudge(15));
+
+        pressure.apply(ContextSignal::Remaining { percent: 12 }, now);
+        assert!(pressure.wants_nudge(15));
+        pressure.nudged_at = Some(now);
+        pressure.apply(ContextSignal::Remaining { percent: 9 }, now);
+        assert!(pressure.is_critical(15));
+        assert!(!pressure.wants_nudge(15));
+
+        pressure.apply(ContextSignal::Compacted, now);
+        assert_eq!(pressure.compacted_at, Some(now));
+        assert!(!pressure.is_critical(15));
+        pressure.apply(ContextSignal::Low, now);
+        assert!(pressure.wants_nudge(15));
+    }
+
+    #[test]
+    fn test_monitor_reads_signals_across_chunks() {
+        let adapter = ClaudeCodeAdapter;
+        let mut monitor = ContextMonitor::default();
+        let detect = |line: &str| adapter.detect_context_signal(line);
+
+        assert!(monitor
+            .signals("s-worker-1", b"\x1b[2mContext left until auto-", detect)
+            .is_empty());
+        assert_eq!(
+            monitor.signals("s-worker-1", b"compact: 7%\x1b[0m", detect),
+            vec![ContextSignal::Remaining { percent: 7 }]
+        );
+        assert_eq!(
+            monitor.signals("s-worker-1", b"\r\n\xe2\x9c\xbb Conversation compacted\r\n", detect),
+            vec![
+                ContextSignal::Remaining { percent: 7 },
+                ContextSignal::Compacted
+            ]
+        );
+    }
+}
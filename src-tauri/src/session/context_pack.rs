@@ -0,0 +1,163 @@
+//! The slice of `plan.md` a worker needs, embedded in its prompt.
+//!
+//! A worker added mid-session would otherwise have to read the whole plan and
+//! work out which parts are its own. At spawn time the controller takes the
+//! parsed [`Plan`] and pulls out the tasks assigned to it (`- [ ] ... -> Worker
+//! 2`) and the files from the plan's tables, putting rows that name it first.
+
+use super::plan_parser::{Plan, PlanFile, PlanTask};
+
+/// Files listed in a context pack at most.
+pub const MAX_PACK_FILES: usize = 12;
+
+/// `text` lowercased with `-` and `_` read as spaces and runs of spaces
+/// collapsed, so "Worker-2" and "worker 2" compare equal.
+fn normalize(text: &str) -> String {
+    text.to_lowercase()
+        .replace(['-', '_'], " ")
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Whether `text` mentions any of `names` as whole words, so "worker 1" is not
+/// found in "Worker 10".
+pub fn mentions(text: &str, names: &[String]) -> bool {
+    let text = normalize(text);
+    names.iter().map(|name| normalize(name)).any(|name| {
+        !name.is_empty()
+            && text.match_indices(&name).any(|(start, _)| {
+                let before = text[..start].chars().next_back();
+                let after = text[start + name.len()..].chars().next();
+                !before.is_some_and(char::is_alphanumeric)
+                    && !after.is_some_and(char::is_alphanumeric)
+            })
+    })
+}
+
+/// The plan's tasks whose assignee is one of `names`.
+pub fn assigned_tasks<'a>(plan: &'a Plan, names: &[String]) -> Vec<&'a PlanTask> {
+    plan.tasks
+        .iter()
+        .filter(|task| {
+            task.assignee
+                .as_deref()
+                .is_some_and(|assignee| mentions(assignee, names))
+        })
+        .collect()
+}
+
+/// Paths from the plan's files tables, those whose row mentions one of `names`
+/// first, without duplicates and at most `limit` of them.
+pub fn relevant_files(plan: &Plan, names: &[String], limit: usize) -> Vec<String> {
+    let row_mentions = |file: &&PlanFile| {
+        [&file.owner, &file.notes]
+            .into_iter()
+            .flatten()
+            .any(|cell| mentions(cell, names))
+    };
+    let (mentioned, others): (Vec<&PlanFile>, Vec<&PlanFile>) =
+        plan.files.iter().partition(row_mentions);
+
+    let mut files: Vec<String> = Vec::new();
+    for file in mentioned.into_iter().chain(others) {
+        if files.len() == limit {
+            break;
+        }
+        if !files.contains(&file.path) {
+            files.push(file.path.clone());
+        }
+    }
+    files
+}
+
+fn task_line(task: &PlanTask) -> String {
+    let mut line = String::from(if task.done { "- [x] " } else { "- [ ] " });
+    if let Some(priority) = task.priority {
+        line.push_str(&format!("[{}] ", priority.as_str().to_uppercase()));
+    }
+    line.push_str(&task.title);
+    line
+}
+
+/// The prompt section for a worker known by `names`, or `None` when the plan
+/// neither assigns it anything nor lists any files.
+pub fn render(plan: &Plan, names: &[String], plan_path: &str) -> Option<String> {
+    let tasks = assigned_tasks(plan, names);
+    let files = relevant_files(plan, names, MAX_PACK_FILES);
+    if tasks.is_empty() && files.is_empty() {
+        return None;
+    }
+
+    let mut pack = format!(
+        "## Context Pack\n\nFrom the approved plan at {plan_path}, so you need not read all of it. \
+         Your task file stays authoritative for what is ACTIVE.\n"
+    );
+    if !tasks.is_empty() {
+        pack.push_str("\n### Plan Tasks Assigned to You\n\n");
+        for task in tasks {
+            pack.push_str(&task_line(task));
+            pack.push('\n');
+        }
+    }
+    if !files.is_empty() {
+        pack.push_str("\n### Relevant Files\n\n");
+        for file in &files {
+            pack.push_str(&format!("- `{file}`\n"));
+        }
+    }
+    Some(pack.trim_end().to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::session::plan_parser;
+
+    const PLAN: &str = "# Implementation Plan
+
+## Tasks
+- [ ] [HIGH] Add the export endpoint -> Worker 2
+- [ ] [LOW] Polish the settings page -> Worker 10
+- [x] Wire the export button \u{2192} worker-2 (Frontend)
+- [ ] Write release notes -> Queen
+
+## Files to Modify
+| File | Owner | Changes Needed |
+|------|-------|----------------|
+| `src/settings.ts` | Worker 10 | Layout |
+| `src/export.rs`, `src/api.rs` | Worker 2 | New endpoint |
+| `src/settings.ts` | Worker 2 | Export toggle |
+
+Notes that are not a table.
+";
+
+    fn names() -> Vec<String> {
+        vec!["worker-2".to_string(), "Principal 2".to_string()]
+    }
+
+    #[test]
+    fn test_pack_holds_only_the_workers_tasks_and_its_files_first() {
+        let plan = plan_parser::parse(PLAN);
+        let titles: Vec<&str> = assigned_tasks(&plan, &names())
+            .iter()
+            .map(|task| task.title.as_str())
+            .collect();
+        assert_eq!(titles, vec!["Add the export endpoint", "Wire the export button"]);
+        assert_eq!(
+            relevant_files(&plan, &names(), MAX_PACK_FILES),
+            vec!["src/export.rs", "src/api.rs", "src/settings.ts"]
+        );
+        assert_eq!(relevant_files(&plan, &names(), 1), vec!["src/export.rs"]);
+
+        let pack = render(&plan, &names(), ".hive-manager/s1/plan.md").unwrap();
+        assert!(pack.starts_with("## Context Pack\n\nFrom the approved plan at .hive-manager/s1"));
+        assert!(pack.contains("### Plan Tasks Assigned to You\n\n- [ ] [HIGH] Add the export"));
+        assert!(pack.contains("- [x] Wire the export button\n"));
+        assert!(pack.ends_with("- `src/export.rs`\n- `src/api.rs`\n- `src/settings.ts`"));
+        assert!(!pack.contains("Polish"));
+
+        let empty = plan_parser::parse("# Plan\n\nNothing yet.\n");
+        assert_eq!(render(&empty, &names(), "plan.md"), None);
+    }
+}
@@ -1,42 +1,90 @@
 use crate::tauri_shim::{AppHandle, Emitter};
 use chrono::{DateTime, Utc};
+use futures::stream::StreamExt;
 use parking_lot::{Mutex, RwLock, RwLockReadGuard};
 use serde::{Deserialize, Serialize};
-use std::collections::{HashMap, HashSet};
+use std::collections::{BTreeMap, HashMap, HashSet, VecDeque};
 use std::path::{Path, PathBuf};
 use std::process::Command;
 use std::sync::Arc;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 use uuid::Uuid;
 
 use crate::artifacts::collector::ArtifactCollector;
-use crate::cli::{CliBehavior, CliRegistry};
+use crate::cli::{custom, CliBehavior, CliRegistry};
 use crate::coordination::queue_manager::{heartbeat_cadence_label, STUCK_CUTOFF_SECS};
-use crate::coordination::{HierarchyNode, StateManager, WorkerStateInfo};
+use crate::coordination::{
+    CoordinationMessage, DigestTarget, HierarchyNode, StateManager, WorkerStateInfo,
+};
 use crate::domain::{ArtifactBundle, HiveExecutionPolicy, HiveLaunchKind, WorkspaceStrategy};
 use crate::events::{EventBus, EventEmitter};
 use crate::orchestrator::session_orchestrator::SessionOrchestrator;
-use crate::pty::{AgentConfig, AgentRole, AgentStatus, PtyManager, WorkerRole};
+use crate::pty::{AgentConfig, AgentExitInfo, AgentRole, AgentStatus, PtyManager, WorkerRole};
 use crate::session::cell_status::{
     agent_in_cell, derive_cell_status_name, derive_cell_status_name_for_state, session_cell_ids,
     variant_to_cell_id, PRIMARY_CELL_ID, RESOLVER_CELL_ID,
 };
+use crate::session::auto_complete::{AutoCompleteClock, AutoCompleteStep, SETTLED_STATUSES};
+use crate::session::comparison::{self, SessionComparison, SessionRunStats};
+use crate::session::context_monitor::{ContextMonitor, ContextPressure};
+use crate::session::context_pack;
+use crate::session::diff_budget::DiffBudgetStage;
+use crate::session::durations::{self, SessionDurations};
+use crate::session::fusion_compose::{self, FusionComposeReport, FusionComposeRequest};
+use crate::session::hooks::{self, HookPayload};
+use crate::session::judge_context;
+use crate::session::launch_queue::{self, QueuedLaunch, QueuedLaunchConfig};
+use crate::session::orphans::{self, OrphanedPtys};
+use crate::session::path_policy::{self, PathViolation};
+use crate::session::phase_timeouts::{
+    grace_secs, PhaseClock, PhaseStage, PhaseTimeouts, SessionPhaseClocks, JUDGING_PHASE,
+    PLANNING_PHASE, WORKER_PHASE,
+};
+use crate::session::plan_history;
+use crate::session::plan_parser;
+use crate::session::policy_script::{PolicyScript, SpawnDecision};
 use crate::session::polling_intervals::{
     format_poll_label, ACTIVATION_POLL_INTERVAL, SMOKE_ACTIVE_POLL_INTERVAL,
     SMOKE_EVALUATOR_FIRST_POLL_INTERVAL, SMOKE_IDLE_POLL_INTERVAL, STANDARD_ACTIVE_POLL_INTERVAL,
     STANDARD_EVALUATOR_FIRST_POLL_INTERVAL, STANDARD_IDLE_POLL_INTERVAL,
 };
+use crate::session::project_dna;
 use crate::session::prompt_contract::{
     render_assignment_contract, render_capability_card, render_delegation_guidance,
     render_role_kernel, render_workspace_contract, AssignmentSpec, ContractRole,
 };
-use crate::storage::{SessionStorage, StorageError};
-use crate::templates::{heartbeat_snippet, PromptContext, TemplateEngine};
+use crate::session::review_findings;
+use crate::session::review_stage::{
+    self, ReviewContext, ReviewStage, ReviewStageConfig, ReviewStep,
+};
+use crate::session::slug;
+use crate::session::smoke_probe;
+use crate::session::snapshots::{self, SessionSnapshot};
+use crate::session::spawn_quota::{
+    QuotaExceeded, SpawnQuota, SpawnReservation, SpawnReservations,
+};
+use crate::session::swarm_domains::{DomainSchedule, DomainUnblocked};
+use crate::session::warm_pool::{WarmKey, WarmPool, WarmProcess};
+use crate::storage::fusion_history::{self, FusionOutcome, FusionVariantOutcome};
+use crate::storage::{
+    AutoCompleteConfig, ConcurrencyConfig, ContextMonitorConfig, DiffBudgetConfig,
+    FusionWorktreeLocation, SessionStorage, StorageError, WarmPoolConfig,
+};
+use crate::templates::{heartbeat_snippet, PromptContext, TemplateEngine, WorkerInfo};
 use crate::watcher::TaskFileWatcher;
+use crate::workspace::checkpoint;
 use crate::workspace::git::{
-    cleanup_session_worktrees, create_session_worktree, current_head, remove_session_worktree_cell,
-    resolve_fresh_base,
+    cleanup_session_worktrees, create_session_worktree, current_head, diff_stat_since,
+    files_changed_since, is_commit_on_branch, lines_changed_since, output_with_timeout,
+    remove_session_worktree_cell, resolve_commit, resolve_fresh_base, run_git_async,
+    validate_branch_name, GIT_COMMAND_TIMEOUT,
 };
+use crate::workspace::git_ops::{self, GitOperation, GitOperationOutcome};
+use crate::workspace::github_issues::{self, PlanIssueLink, PlanIssueMode};
+use crate::workspace::paths::{self, expand_tilde, PathStyle, WslTarget};
+use crate::workspace::project_artifacts::{self, ArtifactCleanupReport};
+use crate::workspace::pull_request::{self, CompletedTask, PullRequestReport};
+use crate::workspace::read_only;
 
 /// Example `coordination.log` lines for Queen quality-reconciliation (quiescence-based; no iteration cap).
 const QUEEN_QUALITY_RECONCILIATION_LOG_LINES: &str = r#"[TIMESTAMP] QUEEN: Entering reconciliation loop for latest push
@@ -109,6 +157,8 @@ const DEFAULT_QA_TIMEOUT_SECS: u64 = 300;
 const MAX_PRIMARY_CELL_BRANCHES: usize = 4;
 const MAX_PRIMARY_CELL_DIFF_SUMMARY_LEN: usize = 4_096;
 const MAX_DEBATE_ROUNDS: u8 = 20;
+/// Fusion variants whose worktree + CLI are brought up at the same time.
+const FUSION_SPAWN_CONCURRENCY: usize = 4;
 
 /// Authentication strategy for QA workers accessing the session
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
@@ -261,6 +311,8 @@ fn default_session_qa_settings() -> (u8, u64, AuthStrategy) {
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub enum SessionState {
+    /// Waiting in the launch queue for running sessions to free capacity.
+    Queued,
     Planning,
     PlanReady,
     Starting,
@@ -273,7 +325,11 @@ pub enum SessionState {
     SpawningDebateRound(u8),
     WaitingForDebateRound(u8),
     SpawningJudge,
-    Judging,
+    /// One or more judges are evaluating. `outstanding` counts the judges that
+    /// have not yet written their report (1 for a single judge).
+    Judging {
+        outstanding: u8,
+    },
     AwaitingVerdictSelection,
     MergingWinner,
     SpawningEvaluator,
@@ -295,6 +351,12 @@ pub enum SessionState {
     /// for the automated loop: never auto-ships. Operator unblocks via
     /// force-pass / force-fail.
     QaInconclusive,
+    /// Every worker finished and the review chain is running. `step` counts from 1;
+    /// `role` is the role of the worker running it.
+    Reviewing {
+        step: u8,
+        role: String,
+    },
     Running,
     Paused,
     Completed,
@@ -316,6 +378,7 @@ impl SessionState {
                 | SessionState::QaFailed { .. }
                 | SessionState::PrinceRemediation
                 | SessionState::QaInconclusive
+                | SessionState::Reviewing { .. }
         )
     }
 }
@@ -331,6 +394,38 @@ pub struct AgentInfo {
     pub commit_sha: Option<String>,
     #[serde(default)]
     pub base_commit_sha: Option<String>,
+    /// How the agent's process ended, once it has exited.
+    #[serde(default)]
+    pub exit_info: Option<AgentExitInfo>,
+}
+
+/// What spawning an agent with a given config would run (see
+/// `SessionController::preview_agent_command`).
+#[derive(Debug, Clone, Serialize)]
+pub struct AgentCommandPreview {
+    pub command: String,
+    pub args: Vec<String>,
+    /// Directory the agent would start in; the app's own when `None`.
+    pub cwd: Option<String>,
+    /// Names of the variables in the CLI's configured `env`; the values may hold
+    /// credentials, so they are left out.
+    pub env_keys: Vec<String>,
+    pub path_prepend: Vec<String>,
+    pub env_allowlist: Option<Vec<String>>,
+    /// `command` and `args`, quoted to paste into a terminal.
+    pub command_line: String,
+}
+
+impl AgentInfo {
+    /// The operator-facing name: the agent's label when it has one, else its ID.
+    pub fn display_name(&self) -> &str {
+        self.config
+            .label
+            .as_deref()
+            .map(str::trim)
+            .filter(|label| !label.is_empty())
+            .unwrap_or(&self.id)
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, schemars::JsonSchema)]
@@ -353,8 +448,29 @@ pub struct HiveLaunchConfig {
     pub qa_workers: Option<Vec<QaWorkerConfig>>,
     #[serde(default)]
     pub smoke_test: bool, // If true, create a minimal test plan without real investigation
+    /// With `smoke_test`: start no CLI at all and let the backend play the workers to
+    /// check the task-file chain (see `smoke_probe`).
+    #[serde(default)]
+    pub smoke_probe: bool,
     #[serde(default)]
     pub execution_policy: HiveExecutionPolicy,
+    /// Session branch for the Queen/primary worktree (e.g. `feat/login-flow`). Created
+    /// before any agent spawns; defaults to the managed `hive/<session-id>/...` name.
+    #[serde(default)]
+    pub branch_name: Option<String>,
+    /// Time budgets for planning, each worker and judging.
+    #[serde(default)]
+    pub phase_timeouts: PhaseTimeouts,
+    /// Review chain to run once every worker has finished.
+    #[serde(default)]
+    pub review_stage: Option<ReviewStageConfig>,
+    /// Caps on the workers the Queen may spawn, in total and per role.
+    #[serde(default)]
+    pub spawn_quota: SpawnQuota,
+    /// Run the session's CLIs below normal OS priority and pace their terminal
+    /// output, so the session does not starve interactive ones.
+    #[serde(default)]
+    pub background: bool,
 }
 
 /// Launch config for **Research** mode.
@@ -384,38 +500,6 @@ pub struct ResearchLaunchConfig {
     pub smoke_test: bool,
 }
 
-/// Expand a leading `~` in a path to the user's home directory so the value can
-/// be safely embedded in a shell command (a tilde inside quotes is NOT expanded
-/// by the shell). Returns the input unchanged if it doesn't start with `~` or
-/// the home directory cannot be determined.
-fn expand_tilde(path: &str) -> String {
-    let Some(rest) = path.strip_prefix('~') else {
-        return path.to_string();
-    };
-    // Only expand the current user's home (`~`, `~/...`, `~\...`). A bare `~user`
-    // form refers to another user's home and is not something we resolve — leave
-    // it untouched rather than mangling it into `<home>/user`.
-    if !rest.is_empty() && !rest.starts_with('/') && !rest.starts_with('\\') {
-        return path.to_string();
-    }
-    let home = if cfg!(windows) {
-        std::env::var("USERPROFILE").ok()
-    } else {
-        std::env::var("HOME").ok()
-    };
-    match home {
-        Some(home) => {
-            let rest = rest.trim_start_matches(['/', '\\']);
-            if rest.is_empty() {
-                home
-            } else {
-                format!("{}/{}", home.trim_end_matches(['/', '\\']), rest)
-            }
-        }
-        None => path.to_string(),
-    }
-}
-
 #[derive(Debug, Clone, Serialize, Deserialize, schemars::JsonSchema)]
 pub struct SwarmLaunchConfig {
     pub project_path: String,
@@ -446,6 +530,12 @@ pub struct SwarmLaunchConfig {
     // Legacy support - if planners vec is provided, use it instead
     #[serde(default)]
     pub planners: Vec<PlannerConfig>,
+    /// Time budgets for planning, each worker and judging.
+    #[serde(default)]
+    pub phase_timeouts: PhaseTimeouts,
+    /// Background priority, as for [`HiveLaunchConfig::background`].
+    #[serde(default)]
+    pub background: bool,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, Hash, schemars::JsonSchema)]
@@ -484,6 +574,36 @@ pub struct FusionLaunchConfig {
     #[serde(default = "default_fusion_cli")]
     pub default_cli: String,
     pub default_model: Option<String>,
+    /// Completed variants required before judging may proceed without the failed
+    /// ones. `None` waits for every variant.
+    #[serde(default)]
+    pub quorum: Option<u8>,
+    /// Automatic respawns per variant after it reports BLOCKED or its CLI exits early.
+    #[serde(default)]
+    pub max_variant_retries: u8,
+    /// Time budgets for planning, each worker and judging.
+    #[serde(default)]
+    pub phase_timeouts: PhaseTimeouts,
+    /// Background priority, as for [`HiveLaunchConfig::background`].
+    #[serde(default)]
+    pub background: bool,
+    /// What to do when the project has uncommitted changes the variants would miss.
+    #[serde(default)]
+    pub dirty_tree: DirtyTreeAction,
+}
+
+/// How a Fusion launch treats uncommitted changes in the project, which the variants'
+/// worktrees would not contain.
+#[derive(
+    Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, schemars::JsonSchema,
+)]
+#[serde(rename_all = "snake_case")]
+pub enum DirtyTreeAction {
+    /// Refuse to launch until the changes are committed or stashed.
+    #[default]
+    Abort,
+    /// Stash the changes (untracked files included) and launch.
+    Stash,
 }
 
 fn default_fusion_cli() -> String {
@@ -514,6 +634,45 @@ struct FusionSessionMetadata {
     judge_config: AgentConfig,
     task_description: String,
     decision_file: String,
+    /// Judges spawned after the original one (re-runs and second opinions). Each
+    /// writes its own report next to `decision_file`.
+    #[serde(default)]
+    extra_judges: Vec<FusionJudgeMetadata>,
+    #[serde(default)]
+    quorum: Option<u8>,
+    #[serde(default)]
+    max_variant_retries: u8,
+    /// Directory holding this session's variant worktrees; `None` for sessions
+    /// launched before the location was configurable, which used
+    /// `<project>/.hive-fusion/<session-id>`.
+    #[serde(default)]
+    worktree_root: Option<String>,
+    /// Commit `base_branch` pointed at when the variants branched off, so judging and
+    /// composition diff against the same base even if the branch moves.
+    #[serde(default)]
+    base_commit: Option<String>,
+}
+
+impl FusionSessionMetadata {
+    /// The variants' base: the pinned commit, else the base branch for sessions
+    /// launched before it was recorded.
+    fn base_ref(&self) -> &str {
+        self.base_commit.as_deref().unwrap_or(&self.base_branch)
+    }
+
+    fn worktree_root(&self, project_path: &Path, session_id: &str) -> PathBuf {
+        self.worktree_root
+            .as_deref()
+            .map(PathBuf::from)
+            .unwrap_or_else(|| project_path.join(".hive-fusion").join(session_id))
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct FusionJudgeMetadata {
+    agent_id: String,
+    config: AgentConfig,
+    decision_file: String,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -525,6 +684,71 @@ struct FusionVariantMetadata {
     worktree_path: String,
     task_file: String,
     agent_id: String,
+    /// Respawns performed after a failure (0 for the original run).
+    #[serde(default)]
+    attempts: u8,
+    /// Why the variant failed; cleared when it is retried or completes.
+    #[serde(default)]
+    failure: Option<String>,
+}
+
+/// A Fusion variant whose worktree and CLI `spawn_fusion_variants` still has to bring up.
+#[derive(Debug, Clone)]
+struct FusionVariantSpawn {
+    variant: FusionVariantMetadata,
+    agent_config: AgentConfig,
+}
+
+/// Variant spawn work left over by `launch_fusion` so the launch can return as soon as
+/// the session exists.
+#[derive(Debug, Clone)]
+pub struct FusionSpawnPlan {
+    pub session_id: String,
+    project_path: PathBuf,
+    base_branch: String,
+    task_description: String,
+    variants: Vec<FusionVariantSpawn>,
+    /// Per-variant worktree outcome once `prepare_fusion_worktrees` has run the git work
+    /// off the controller lock; `None` means `spawn_fusion_variants` creates them itself.
+    worktrees: Option<Vec<Result<(), String>>>,
+}
+
+/// Where a Fusion variant is in being brought up.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FusionVariantStage {
+    CreatingWorktree,
+    Spawning,
+    Running,
+    Failed,
+}
+
+/// Payload of the `fusion-variant-progress` event.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FusionVariantProgress {
+    pub session_id: String,
+    pub variant_index: u8,
+    pub variant_name: String,
+    pub stage: FusionVariantStage,
+    pub error: Option<String>,
+}
+
+/// A Fusion variant between the two halves of a retry, while its worktree is reset.
+struct FusionVariantRetry {
+    project_path: PathBuf,
+    metadata: FusionSessionMetadata,
+    position: usize,
+    variant: FusionVariantMetadata,
+    agent_config: AgentConfig,
+}
+
+/// Whether a Fusion session's variants are ready to be judged.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum FusionSettlement {
+    /// Some variants are still running.
+    Pending,
+    Judge,
+    QuorumMissed(String),
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -534,6 +758,33 @@ pub struct FusionVariantStatus {
     pub branch: String,
     pub worktree_path: String,
     pub status: String,
+    pub attempts: u8,
+    pub failure: Option<String>,
+}
+
+/// One Fusion judge's verdict as read from its report.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FusionJudgeVerdict {
+    pub judge_id: String,
+    pub cli: String,
+    pub model: Option<String>,
+    pub report_path: String,
+    pub reported: bool,
+    /// Variant named on the report's `Winner:` line, if it matches a known variant.
+    pub winner: Option<String>,
+    /// Rubric score (0-10) per variant, from the report's `Score:` lines.
+    #[serde(default)]
+    pub scores: BTreeMap<String, f64>,
+}
+
+/// Aggregated Fusion judge verdicts, recorded as `evaluation/verdicts.json`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FusionVerdictTally {
+    pub verdicts: Vec<FusionJudgeVerdict>,
+    pub votes: BTreeMap<String, u8>,
+    /// Variant backed by a strict majority of the judges that have reported.
+    pub majority_winner: Option<String>,
+    pub outstanding: u8,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, schemars::JsonSchema)]
@@ -555,6 +806,12 @@ pub struct DebateLaunchConfig {
     #[serde(default = "default_fusion_cli")]
     pub default_cli: String,
     pub default_model: Option<String>,
+    /// Time budgets for planning, each worker and judging.
+    #[serde(default)]
+    pub phase_timeouts: PhaseTimeouts,
+    /// Background priority, as for [`HiveLaunchConfig::background`].
+    #[serde(default)]
+    pub background: bool,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, schemars::JsonSchema)]
@@ -632,6 +889,8 @@ pub struct Session {
     /// Primary git worktree path for this session (e.g. Queen or first Fusion variant), for UI.
     #[serde(default)]
     pub worktree_path: Option<String>,
+    /// Session branch checked out in `worktree_path`: the launch config's `branch_name`
+    /// when one was given. Shown in the UI and used as the PR head.
     #[serde(default)]
     pub worktree_branch: Option<String>,
     /// No-git sessions (Research) never create worktrees or branches: every agent,
@@ -640,6 +899,12 @@ pub struct Session {
     /// non-repo folders and honors the research "no git" contract.
     #[serde(default)]
     pub no_git: bool,
+    /// URL of the pull request opened for the session branch by `create_pull_request`.
+    #[serde(default)]
+    pub pull_request_url: Option<String>,
+    /// Caps on live workers, from the launch config or `override_spawn_quota`.
+    #[serde(default)]
+    pub spawn_quota: SpawnQuota,
     /// Populated by `resume_session` (#125): per-step classification of a resumed run so
     /// the frontend can show a confirmation modal. `None` for freshly launched sessions.
     #[serde(default, skip_serializing_if = "Option::is_none")]
@@ -651,14 +916,112 @@ pub struct SessionUpdate {
     pub session: Session,
 }
 
+/// A worker commit checked against the session branch.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SessionBranchCommit {
+    pub agent_id: String,
+    pub commit_sha: String,
+    pub on_branch: bool,
+}
+
+/// Whether every recorded worker commit has landed on the session branch.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SessionBranchStatus {
+    pub branch: String,
+    pub worktree_path: Option<String>,
+    pub commits: Vec<SessionBranchCommit>,
+    pub all_commits_on_branch: bool,
+}
+
 /// Per-agent heartbeat data for stall detection
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct AgentHeartbeatInfo {
     pub last_activity: DateTime<Utc>,
     pub status: String,
     pub summary: Option<String>,
 }
 
+/// A question an agent is blocked on until the operator answers it.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OperatorQuestion {
+    pub id: String,
+    pub session_id: String,
+    pub agent_id: String,
+    pub question: String,
+    /// Suggested answers the UI offers next to a free-text reply.
+    #[serde(default)]
+    pub options: Vec<String>,
+    pub asked_at: DateTime<Utc>,
+    #[serde(default)]
+    pub answer: Option<String>,
+    #[serde(default)]
+    pub answered_at: Option<DateTime<Utc>>,
+}
+
+const MAX_OPERATOR_QUESTION_LEN: usize = 8_000;
+
+/// Outcome of [`SessionController::scale_workers`].
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WorkerScaleReport {
+    pub session_id: String,
+    pub target_count: u8,
+    /// Live workers once scaling finished.
+    pub worker_count: u8,
+    pub added: Vec<String>,
+    pub retired: Vec<String>,
+    /// Surplus workers left running because they are mid-task. Scale again once
+    /// they finish.
+    pub pending_retirement: usize,
+}
+
+/// How long a Solo agent's PTY must stay silent before it counts as idle when it
+/// has not posted an idle heartbeat.
+pub const SOLO_IDLE_OUTPUT_THRESHOLD: Duration = Duration::from_secs(30);
+
+/// How often each running session's heartbeat state is summarized into its
+/// coordination log.
+pub const HEARTBEAT_ROLLUP_INTERVAL: Duration = Duration::from_secs(5 * 60);
+
+/// How long a warm-pool process must stay silent, done starting up, before it
+/// is handed a worker's prompt.
+const WARM_PROCESS_SETTLED: Duration = Duration::from_secs(3);
+
+/// How long a changed session may go unwritten, so a burst of updates (a launch
+/// adding its workers one by one) is written to storage once.
+pub const SESSION_PERSIST_DEBOUNCE: Duration = Duration::from_millis(500);
+
+/// Longest heartbeat summary quoted in a coordination log line.
+const MAX_LOGGED_HEARTBEAT_SUMMARY_CHARS: usize = 120;
+
+/// A time-boxed phase running in a session.
+#[derive(Debug, Clone)]
+struct RunningPhase {
+    /// `planning`, `judging` or the worker's agent ID.
+    key: String,
+    /// One of the `*_PHASE` kinds.
+    kind: &'static str,
+    limit_secs: u64,
+    /// The agents doing the phase's work.
+    agent_ids: Vec<String>,
+}
+
+/// Follow-up prompts waiting for a Solo session's agent to go idle.
+#[derive(Debug, Default)]
+struct SoloTaskQueue {
+    pending: VecDeque<String>,
+    /// When the last queued prompt was written to the agent.
+    last_dispatch: Option<DateTime<Utc>>,
+}
+
+/// A judge held to a read-only main checkout (see [`read_only`]).
+#[derive(Debug, Clone)]
+struct ReadOnlyGuard {
+    session_id: String,
+    repo: PathBuf,
+    /// Commit holding the tracked files as they were before the judge started.
+    snapshot: String,
+}
+
 pub struct SessionController {
     sessions: Arc<RwLock<HashMap<String, Session>>>,
     pty_manager: Arc<RwLock<PtyManager>>,
@@ -682,12 +1045,251 @@ pub struct SessionController {
     /// Durable run journal + side-effect ledger (#125). Optional so tests/legacy
     /// construction paths can run without a SQLite DB; write-step seams no-op when unset.
     run_journal: Option<crate::storage::RunJournalStore>,
+    /// session_id -> API token rendered into that session's agent prompts
+    api_tokens: RwLock<HashMap<String, String>>,
+    /// session_id -> follow-up prompts queued for a Solo session's agent
+    solo_task_queues: Mutex<HashMap<String, SoloTaskQueue>>,
+    /// worktree path -> when it was last considered for a checkpoint
+    checkpoint_times: Mutex<HashMap<PathBuf, Instant>>,
+    /// worker agent_id -> how far past its diff budget it has been acted on
+    diff_budget_stages: Mutex<HashMap<String, DiffBudgetStage>>,
+    /// worker agent_id -> paths outside its file permissions already reported
+    path_violations: Mutex<HashMap<String, HashSet<String>>>,
+    /// session_id -> questions agents asked the operator, oldest first
+    operator_questions: RwLock<HashMap<String, Vec<OperatorQuestion>>>,
+    /// session_id -> phase budgets from the launch config and the running phases' clocks
+    phase_clocks: Mutex<HashMap<String, SessionPhaseClocks>>,
+    /// session_id -> agents cut off from the session API and coordination messages
+    quarantined_agents: RwLock<HashMap<String, HashSet<String>>>,
+    /// Launches waiting for capacity under the concurrency limits, oldest first
+    launch_queue: Mutex<VecDeque<QueuedLaunch>>,
+    /// session_id -> the review chain a Hive session runs once its workers finish
+    review_stages: Mutex<HashMap<String, ReviewStage>>,
+    /// session_id -> role types of workers that passed the spawn quota and are
+    /// still being spawned
+    spawn_reservations: SpawnReservations,
+    /// Idle CLI processes started for the workers of sessions that share a directory
+    warm_pool: Mutex<WarmPool>,
+    /// session_id -> when its oldest change not yet written to storage was made
+    unsaved_sessions: Mutex<HashMap<String, Instant>>,
+    /// PTYs awaiting confirmation as orphans, and what the watchdog has done so far
+    orphaned_ptys: Mutex<OrphanedPtys>,
+    /// Line buffers reading agents' context-window hints out of their output
+    context_monitor: Mutex<ContextMonitor>,
+    /// session_id -> agent_id -> what the agent's CLI last said about its context
+    context_pressure: RwLock<HashMap<String, HashMap<String, ContextPressure>>>,
+    /// judge agent_id -> the main-checkout snapshot its edits are reverted to
+    read_only_guards: Mutex<HashMap<String, ReadOnlyGuard>>,
+    /// session_id -> the order a Swarm's plan puts its domains in
+    domain_schedules: Mutex<HashMap<String, DomainSchedule>>,
+    /// session_id -> how long the session has been settled, for auto-completion
+    auto_complete_clocks: Mutex<HashMap<String, AutoCompleteClock>>,
+    /// The operator's `scripts/policy.rhai`, once storage is known
+    policy_script: Option<PolicyScript>,
 }
 
 // Explicitly implement Send + Sync
 unsafe impl Send for SessionController {}
 unsafe impl Sync for SessionController {}
 
+/// Header agents send their session's API token in.
+pub const SESSION_TOKEN_HEADER: &str = "X-Hive-Session-Token";
+
+/// Header agents name themselves in on session API writes, so a quarantined
+/// agent's requests can be turned away.
+pub const AGENT_ID_HEADER: &str = "X-Hive-Agent-Id";
+
+/// Prompt section telling an agent how to authenticate writes to its session's API.
+fn session_api_token_block(session_id: &str, token: &str) -> String {
+    format!(
+        r#"
+
+## Session API Token
+Writes (`POST`, `PATCH`, `PUT`, `DELETE`) under `/api/sessions/{session_id}/` are rejected
+without this session's token. Add this header to every such request:
+
+  -H "{header}: {token}"
+  -H "{agent_header}: <your agent id>"
+
+The token is only valid for session {session_id}; never send it to another session.
+"#,
+        header = SESSION_TOKEN_HEADER,
+        agent_header = AGENT_ID_HEADER,
+    )
+}
+
+/// Prompt section telling an agent how to ask the operator a blocking question.
+fn ask_operator_block(session_id: &str) -> String {
+    format!(
+        r#"
+
+## Asking the Operator
+When you cannot continue without a human decision, ask instead of guessing:
+
+  curl -fsS -X POST "http://localhost:18800/api/sessions/{session_id}/questions" \
+    -H "Content-Type: application/json" \
+    -H "{header}: <session API token>" \
+    -d '{{"agent_id": "<your agent id>", "question": "...", "options": ["...", "..."]}}'
+
+Then stop and wait. The answer is typed into this terminal as `[OPERATOR ANSWER] ...`.
+"#,
+        header = SESSION_TOKEN_HEADER,
+    )
+}
+
+/// Prompt section telling an agent to clear pushes and deletions with the operator.
+fn approval_gate_block(session_id: &str) -> String {
+    format!(
+        r#"
+
+## Approval Gates
+Before `git push` or deleting tracked files, ask first. The request returns once the
+operator decides, which can take minutes:
+
+  curl -fsS -X POST "http://localhost:18800/api/sessions/{session_id}/approvals" \
+    -H "Content-Type: application/json" \
+    -H "{header}: <session API token>" \
+    -d '{{"agent_id": "<your agent id>", "kind": "push", "branch": "<branch>"}}'
+
+For deletions send `"kind": "delete_files", "paths": ["..."]`. HTTP 200 means go ahead;
+403 means the operator declined, so do not carry the action out.
+"#,
+        header = SESSION_TOKEN_HEADER,
+    )
+}
+
+/// Prompt section telling a Queen to run git through the backend's checked operations.
+fn git_operations_block(session_id: &str) -> String {
+    format!(
+        r#"## Git Operations
+
+Where your topology lets you commit, branch or push, ask the backend to do it instead of
+running git yourself. It checks each operation first and records it in the coordination log:
+
+  curl -fsS -X POST "http://localhost:18800/api/sessions/{session_id}/git" \
+    -H "Content-Type: application/json" \
+    -H "{header}: <session API token>" \
+    -d '{{"agent_id": "{session_id}-queen", "operations": [
+          {{"op": "commit", "message": "...", "paths": ["optional/path"]}},
+          {{"op": "create_branch", "name": "...", "checkout": false}},
+          {{"op": "push", "branch": "..."}}]}}'
+
+Operations run in order and stop at the first failure; check `ok` and each outcome's
+`error`. A push waits for operator approval when the branch is protected."#,
+        header = SESSION_TOKEN_HEADER,
+    )
+}
+
+/// Prompt section telling an agent to acknowledge the Queen's `[MSG <id>]` tasks.
+fn progress_ack_block(session_id: &str) -> String {
+    format!(
+        r#"
+
+## Acknowledging Instructions
+Instructions typed into this terminal start with `[MSG <id>]`. Once you act on one,
+report progress with its ID so the Queen knows it arrived:
+
+  curl -fsS -X POST "http://localhost:18800/api/sessions/{session_id}/coordination/progress" \
+    -H "Content-Type: application/json" \
+    -H "{header}: <session API token>" \
+    -d '{{"agent_id": "<your agent id>", "message_id": "<id>", "content": "..."}}'
+
+The Queen can list instructions nobody acknowledged at
+`GET http://localhost:18800/api/sessions/{session_id}/coordination/unacknowledged`.
+
+To follow the coordination log without re-reading all of it, poll
+`GET http://localhost:18800/api/sessions/{session_id}/coordination?after_seq=<cursor>`,
+starting from 0 and passing back the `cursor` each response returns.
+"#,
+        header = SESSION_TOKEN_HEADER,
+    )
+}
+
+/// Prompt section telling an agent how to attach files to coordination messages.
+fn attachments_block(session_id: &str) -> String {
+    format!(
+        r#"
+## Attachments
+To hand another agent a diff, screenshot or log excerpt, upload it and put the returned
+`reference` (`[ATTACH <id>]`) in your message instead of pasting the file:
+
+  curl -fsS -X POST "http://localhost:18800/api/sessions/{session_id}/coordination/attachments" \
+    -H "{header}: <session API token>" \
+    -F "file=@changes.diff"
+
+When a message you receive names `[ATTACH <id>]`, fetch the file with
+`GET http://localhost:18800/api/sessions/{session_id}/coordination/attachments/<id>`.
+"#,
+        header = SESSION_TOKEN_HEADER,
+    )
+}
+
+/// Prompt section telling an agent how to share long values through the scratchpad.
+fn scratchpad_block(session_id: &str) -> String {
+    format!(
+        r#"
+## Scratchpad
+Keep API contracts, interface sketches and JSON schemas other agents need in the session
+scratchpad rather than in coordination messages. Keys are letters, digits, `.`, `_` or `-`:
+
+  curl -fsS -X PUT "http://localhost:18800/api/sessions/{session_id}/scratchpad/api-contract" \
+    -H "{header}: <session API token>" -H "Content-Type: application/json" \
+    -d '{{"value": "POST /users {{name, email}} -> 201 User"}}'
+
+Read a key with `GET http://localhost:18800/api/sessions/{session_id}/scratchpad/<key>`,
+or list every entry with `GET http://localhost:18800/api/sessions/{session_id}/scratchpad`.
+"#,
+        header = SESSION_TOKEN_HEADER,
+    )
+}
+
+/// Prompt section telling an agent in offline mode to make its API calls as files in
+/// `outbox` (see `http::outbox`) instead of with curl.
+fn offline_api_block(session_id: &str, outbox: &Path) -> String {
+    format!(
+        r#"
+
+## Session API (offline mode)
+The Hive Manager HTTP API cannot be reached from this machine, so `curl` against
+`http://localhost:18800` fails. Make every call these instructions show under
+`/api/sessions/{session_id}/` by writing a JSON file to this directory instead:
+
+  {outbox}
+
+Write each file under a temporary name and then rename it to end in `.json`, so it is
+never read half-written. Use a new name for every call:
+
+  cat > "{outbox}/heartbeat-1.tmp" <<'EOF'
+  {{"method": "POST", "path": "heartbeat", "agent_id": "<your agent id>",
+   "body": {{"agent_id": "<your agent id>", "status": "working", "summary": "..."}}}}
+  EOF
+  mv "{outbox}/heartbeat-1.tmp" "{outbox}/heartbeat-1.json"
+
+`path` is what follows `/api/sessions/{session_id}/` in the URL, query string included;
+`method` defaults to POST; `body` is the JSON curl would send with `-d`. No token
+header is needed. Within a few seconds the file is consumed and the response is written
+to `{outbox}/replies/<same name>` as `{{"status": 200, "body": ...}}`. For example:
+
+- Heartbeat: `{{"path": "heartbeat", "body": {{"agent_id": "...", "status": "working"}}}}`
+- Record a learning: `{{"path": "learnings", "body": {{"task": "...", "outcome": "success", "insight": "..."}}}}`
+- Read learnings: `{{"method": "GET", "path": "learnings"}}`
+- Spawn a worker: `{{"path": "workers", "body": {{"role_type": "backend", "cli": "claude"}}}}`;
+  the reply names the new worker.
+"#,
+        outbox = outbox.display(),
+    )
+}
+
+/// `45s`, `12m`, `2h05m`.
+fn compact_age(secs: i64) -> String {
+    let secs = secs.max(0);
+    match secs {
+        0..=59 => format!("{}s", secs),
+        60..=3599 => format!("{}m", secs / 60),
+        _ => format!("{}h{:02}m", secs / 3600, secs % 3600 / 60),
+    }
+}
+
 fn is_terminal_session_state(state: &SessionState) -> bool {
     matches!(
         state,
@@ -868,6 +1470,26 @@ impl SessionController {
             qa_timeout_handles: Mutex::new(HashMap::new()),
             evaluator_respawns_inflight: Mutex::new(HashSet::new()),
             run_journal: None,
+            api_tokens: RwLock::new(HashMap::new()),
+            solo_task_queues: Mutex::new(HashMap::new()),
+            checkpoint_times: Mutex::new(HashMap::new()),
+            diff_budget_stages: Mutex::new(HashMap::new()),
+            path_violations: Mutex::new(HashMap::new()),
+            operator_questions: RwLock::new(HashMap::new()),
+            phase_clocks: Mutex::new(HashMap::new()),
+            quarantined_agents: RwLock::new(HashMap::new()),
+            launch_queue: Mutex::new(VecDeque::new()),
+            review_stages: Mutex::new(HashMap::new()),
+            spawn_reservations: SpawnReservations::default(),
+            warm_pool: Mutex::new(WarmPool::default()),
+            unsaved_sessions: Mutex::new(HashMap::new()),
+            orphaned_ptys: Mutex::new(OrphanedPtys::default()),
+            context_monitor: Mutex::new(ContextMonitor::default()),
+            context_pressure: RwLock::new(HashMap::new()),
+            read_only_guards: Mutex::new(HashMap::new()),
+            domain_schedules: Mutex::new(HashMap::new()),
+            auto_complete_clocks: Mutex::new(HashMap::new()),
+            policy_script: None,
         }
     }
 
@@ -950,6 +1572,7 @@ impl SessionController {
     }
 
     pub fn set_storage(&mut self, storage: Arc<SessionStorage>) {
+        self.policy_script = Some(PolicyScript::new(&storage.base_dir().join("scripts")));
         self.storage = Some(storage);
     }
 
@@ -966,31 +1589,48 @@ impl SessionController {
         name: Option<String>,
         color: Option<String>,
     ) -> Result<Session, String> {
-        let session_id = Uuid::new_v4().to_string();
+        let session_id = launch_queue::new_session_id();
         let mut agents = Vec::new();
         let prompt_str = prompt.unwrap_or_default();
         let cwd = project_path.to_str().unwrap_or(".");
 
-        // Parse command - support "command arg1 arg2" format
+        // Parse command - support "command arg1 arg2" format; no command opens a shell
         let parts: Vec<&str> = command.split_whitespace().collect();
+        let default_shell = crate::pty::shell::user_shell();
         let (cmd, base_args) = if parts.is_empty() {
-            ("cmd.exe", vec![])
+            (default_shell.as_str(), vec![])
         } else {
             (parts[0], parts[1..].to_vec())
         };
         let launch_model = extract_model_arg(&base_args)
             .or_else(|| CliRegistry::default_model(cmd).map(ToString::to_string));
 
+        let queen_id = format!("{}-queen", session_id);
+        let queen_prompt = if cmd == "claude" && !prompt_str.is_empty() {
+            match self.spill_oversized_prompt(
+                &project_path,
+                &session_id,
+                &queen_id,
+                cmd,
+                "queen-task.md",
+                &prompt_str,
+            )? {
+                Some(prompt_file) => Self::prompt_file_instruction(&prompt_file.to_string_lossy()),
+                None => prompt_str,
+            }
+        } else {
+            String::new()
+        };
+
         {
             let pty_manager = self.pty_manager.read();
 
             // Create Queen agent
-            let queen_id = format!("{}-queen", session_id);
             let mut queen_args = base_args.clone();
 
             // Add prompt as positional argument if provided and command is claude
-            if cmd == "claude" && !prompt_str.is_empty() {
-                queen_args.push(&prompt_str);
+            if !queen_prompt.is_empty() {
+                queen_args.push(&queen_prompt);
             }
 
             tracing::info!(
@@ -1025,6 +1665,8 @@ impl SessionController {
                 description: None,
                 role: None,
                 initial_prompt: None,
+                allowed_paths: Vec::new(),
+                denied_paths: Vec::new(),
             };
 
             agents.push(AgentInfo {
@@ -1035,6 +1677,7 @@ impl SessionController {
                 parent_id: None,
                 commit_sha: None,
                 base_commit_sha: None,
+                exit_info: None,
             });
 
             // Create Worker agents
@@ -1078,6 +1721,8 @@ impl SessionController {
                     description: None,
                     role: None,
                     initial_prompt: None,
+                    allowed_paths: Vec::new(),
+                    denied_paths: Vec::new(),
                 };
 
                 agents.push(AgentInfo {
@@ -1091,6 +1736,7 @@ impl SessionController {
                     parent_id: Some(format!("{}-queen", session_id)),
                     commit_sha: None,
                     base_commit_sha: None,
+                    exit_info: None,
                 });
             }
         }
@@ -1120,6 +1766,8 @@ impl SessionController {
             worktree_branch: None,
             no_git: false,
             resume_report: None,
+            pull_request_url: None,
+            spawn_quota: SpawnQuota::default(),
         };
 
         {
@@ -1148,6 +1796,44 @@ impl SessionController {
         sessions.get(id).cloned()
     }
 
+    /// Directories a session's files live in: its project first, then the worktrees
+    /// the session and its agents work in. `None` for an unknown session.
+    pub fn session_file_roots(&self, id: &str) -> Option<Vec<PathBuf>> {
+        let session = self.get_session(id)?;
+        let mut roots = vec![session.project_path.clone()];
+        roots.extend(session.worktree_path.as_ref().map(PathBuf::from));
+        roots.extend(Self::recorded_fusion_worktree_root(&session));
+        for agent in &session.agents {
+            roots.extend(Self::agent_git_worktree_path_for_artifacts(&session, agent));
+        }
+        roots.dedup();
+        Some(roots)
+    }
+
+    /// The session ID `reference` names: the reference itself unless it is a session slug
+    /// (see [`slug`]). A slug no session has is returned unchanged so callers report it as
+    /// an unknown session; one several sessions share is an error.
+    pub fn resolve_session_ref(&self, reference: &str) -> Result<String, String> {
+        if !slug::is_slug(reference) {
+            return Ok(reference.to_string());
+        }
+        let mut ids: Vec<String> = self.sessions.read().keys().cloned().collect();
+        if let Some(storage) = self.storage.as_ref() {
+            ids.extend(storage.session_ids());
+        }
+        ids.sort();
+        ids.dedup();
+        let mut matches = ids.into_iter().filter(|id| slug::slug_for(id) == reference);
+        match (matches.next(), matches.next()) {
+            (Some(id), None) => Ok(id),
+            (None, _) => Ok(reference.to_string()),
+            (Some(_), Some(_)) => Err(format!(
+                "Session slug {} matches more than one session; use the session ID",
+                reference
+            )),
+        }
+    }
+
     fn refresh_session_from_storage_if_clean(&self, session_id: &str) {
         let Some(storage) = self.storage.as_ref() else {
             return;
@@ -1377,6 +2063,8 @@ impl SessionController {
                 description: None,
                 role: None,
                 initial_prompt: None,
+                allowed_paths: Vec::new(),
+                denied_paths: Vec::new(),
             }
         })
     }
@@ -1512,6 +2200,26 @@ impl SessionController {
                     }),
                 );
             }
+            let label = Self::heartbeat_agent_name(session_snapshot.as_ref(), session_id, agent_id);
+            let mut line = match prev_status {
+                Some(prev) => format!("{} {} (was {})", label, status, prev),
+                None => format!("{} {}", label, status),
+            };
+            // One log line per message: fold any newlines in the summary.
+            let summary = summary
+                .map(|s| s.split_whitespace().collect::<Vec<_>>().join(" "))
+                .filter(|s| !s.is_empty());
+            if let Some(summary) = summary {
+                line.push_str(": ");
+                line.extend(summary.chars().take(MAX_LOGGED_HEARTBEAT_SUMMARY_CHARS));
+            }
+            self.log_coordination_system(session_id, &line);
+        }
+        if status == "idle" || status == "completed" {
+            self.dispatch_solo_task_if_idle(session_id);
+        }
+        if status == "completed" && status_changed {
+            self.complete_swarm_domain(session_id, agent_id);
         }
         Ok(())
     }
@@ -1523,7 +2231,6 @@ impl SessionController {
         threshold: Duration,
     ) -> Vec<(String, DateTime<Utc>)> {
         let now = Utc::now();
-        let threshold_secs = threshold.as_secs() as i64;
         let heartbeats = self.agent_heartbeats.read();
         let Some(agents) = heartbeats.get(session_id) else {
             return vec![];
@@ -1532,6 +2239,20 @@ impl SessionController {
             .iter()
             .filter_map(|(agent_id, info)| {
                 let elapsed = (now - info.last_activity).num_seconds();
+                let threshold_secs = self
+                    .policy_script
+                    .as_ref()
+                    .and_then(|policy| {
+                        policy.stall_threshold_secs(&self.policy_context(
+                            session_id,
+                            serde_json::json!({
+                                "agent_id": agent_id,
+                                "status": info.status,
+                                "default_secs": threshold.as_secs(),
+                            }),
+                        ))
+                    })
+                    .unwrap_or(threshold.as_secs()) as i64;
                 if elapsed > threshold_secs && info.status != "completed" {
                     Some((agent_id.clone(), info.last_activity))
                 } else {
@@ -1547,44 +2268,2348 @@ impl SessionController {
         heartbeats.get(session_id).cloned().unwrap_or_default()
     }
 
-    pub(crate) fn emit_session_update(&self, session_id: &str) {
-        let session = {
-            let sessions = self.sessions.read();
-            sessions.get(session_id).cloned()
-        };
+    /// Worker agent ID to the status line of its task file, `UNKNOWN` when unreadable.
+    pub fn worker_task_statuses(session: &Session) -> BTreeMap<String, String> {
+        session
+            .agents
+            .iter()
+            .filter_map(|agent| {
+                let AgentRole::Worker { index, .. } = &agent.role else {
+                    return None;
+                };
+                let status = Self::task_file_path_for_session_worker(session, usize::from(*index))
+                    .map(|path| Self::read_task_status(&path.to_string_lossy()))
+                    .unwrap_or_else(|_| "UNKNOWN".to_string());
+                Some((agent.id.clone(), status))
+            })
+            .collect()
+    }
 
-        if let (Some(app_handle), Some(session)) = (self.app_handle.as_ref(), session) {
-            let _ = app_handle.emit("session-update", SessionUpdate { session });
-        }
+    /// Note that `session_id` changed in memory, for [`Self::persist_unsaved_sessions`].
+    /// Every state transition and every update sent to the UI marks its session.
+    fn mark_session_unsaved(&self, session_id: &str) {
+        self.unsaved_sessions
+            .lock()
+            .entry(session_id.to_string())
+            .or_insert_with(Instant::now);
     }
 
-    fn emit_cell_created(&self, session_id: &str, cell_id: &str) {
-        let Some(emitter) = self.event_emitter.clone() else {
-            return;
+    /// Write to storage each session changed at least `debounce` ago and not
+    /// written since. Returns the number written.
+    pub fn persist_unsaved_sessions(&self, debounce: Duration) -> usize {
+        let due: Vec<String> = {
+            let mut unsaved = self.unsaved_sessions.lock();
+            let due = unsaved
+                .iter()
+                .filter(|(_, changed_at)| changed_at.elapsed() >= debounce)
+                .map(|(session_id, _)| session_id.clone())
+                .collect::<Vec<_>>();
+            for session_id in &due {
+                unsaved.remove(session_id);
+            }
+            due
         };
-        let session_id = session_id.to_string();
-        let cell_id = cell_id.to_string();
-        let cell_type = cell_type_for_id(&cell_id).to_string();
-        tokio::spawn(async move {
-            if let Err(error) = emitter
-                .emit_cell_created(&session_id, &cell_id, &cell_type)
-                .await
-            {
-                tracing::debug!("Failed to emit cell created event: {}", error);
+        let mut written = 0;
+        for session_id in due {
+            if !self.sessions.read().contains_key(&session_id) {
+                continue;
             }
-        });
+            match self.update_session_storage_checked(&session_id) {
+                Ok(()) => written += 1,
+                Err(e) => tracing::warn!("Failed to persist session {}: {}", session_id, e),
+            }
+        }
+        written
     }
 
-    fn emit_agent_launched(&self, session: &Session, agent: &AgentInfo) {
-        let Some(emitter) = self.event_emitter.clone() else {
-            return;
+    /// Snapshot every monitorable session whose state changed since its last
+    /// snapshot (see `session::snapshots`), keeping the newest `keep`. Returns the
+    /// number written.
+    pub fn capture_snapshots(&self, keep: usize) -> usize {
+        let Some(storage) = self.storage.as_ref() else {
+            return 0;
         };
-        let session_id = session.id.clone();
-        let cell_id = agent_cell_id(session, agent);
-        let agent_id = agent.id.clone();
-        let cli = agent.config.cli.clone();
-        tokio::spawn(async move {
-            if let Err(error) = emitter
+        let sessions: Vec<Session> = self
+            .sessions
+            .read()
+            .values()
+            .filter(|session| session.state.is_monitorable())
+            .cloned()
+            .collect();
+        let now = Utc::now();
+
+        let mut written = 0;
+        for session in sessions {
+            let task_statuses = Self::worker_task_statuses(&session);
+            let heartbeats = self.get_heartbeat_info(&session.id).into_iter().collect();
+            let session_value = match serde_json::to_value(&session) {
+                Ok(value) => value,
+                Err(e) => {
+                    tracing::warn!(
+                        "Failed to serialize session {} for a snapshot: {}",
+                        session.id,
+                        e
+                    );
+                    continue;
+                }
+            };
+            let snapshot =
+                SessionSnapshot::new(&session.id, now, session_value, task_statuses, heartbeats);
+            let dir = storage.session_dir(&session.id).join(snapshots::SNAPSHOTS_DIR);
+            match snapshots::record(&dir, &snapshot, keep) {
+                Ok(true) => written += 1,
+                Ok(false) => {}
+                Err(e) => tracing::warn!("Failed to snapshot session {}: {}", session.id, e),
+            }
+        }
+        written
+    }
+
+    /// Summarize heartbeat state into the coordination log of every monitorable
+    /// session that has heartbeats, so the log alone shows who is idle and for how
+    /// long. Returns the number of sessions logged.
+    pub fn roll_up_heartbeats(&self) -> usize {
+        let now = Utc::now();
+        let session_ids: Vec<String> = self
+            .sessions
+            .read()
+            .values()
+            .filter(|session| session.state.is_monitorable())
+            .map(|session| session.id.clone())
+            .collect();
+
+        session_ids
+            .iter()
+            .filter(|session_id| {
+                self.heartbeat_rollup(session_id, now)
+                    .is_some_and(|rollup| {
+                        self.log_coordination_system(session_id, &format!("Heartbeats: {}", rollup))
+                    })
+            })
+            .count()
+    }
+
+    /// "worker-1 working 2m, Payments Backend idle 12m": each agent's last status and
+    /// the time since it reported, in agent order. Agents with a label go by it.
+    fn heartbeat_rollup(&self, session_id: &str, now: DateTime<Utc>) -> Option<String> {
+        let session = self.sessions.read().get(session_id).cloned();
+        let heartbeats = self.agent_heartbeats.read();
+        let agents: BTreeMap<&String, &AgentHeartbeatInfo> =
+            heartbeats.get(session_id)?.iter().collect();
+        if agents.is_empty() {
+            return None;
+        }
+        Some(
+            agents
+                .into_iter()
+                .map(|(agent_id, info)| {
+                    format!(
+                        "{} {} {}",
+                        Self::heartbeat_agent_name(session.as_ref(), session_id, agent_id),
+                        info.status,
+                        compact_age((now - info.last_activity).num_seconds())
+                    )
+                })
+                .collect::<Vec<_>>()
+                .join(", "),
+        )
+    }
+
+    /// Check every session that could be completed for whether its agents have all
+    /// settled (see [`crate::session::auto_complete`]): announce the completion once
+    /// they have been settled for `idle_secs`, cancel it if one becomes active, and
+    /// complete the session when the grace period runs out. Returns the sessions
+    /// completed.
+    pub fn auto_complete_settled_sessions(&self, config: &AutoCompleteConfig) -> Vec<String> {
+        if !config.enabled {
+            self.auto_complete_clocks.lock().clear();
+            return Vec::new();
+        }
+
+        let now = Utc::now();
+        let mut steps: Vec<(String, AutoCompleteStep)> = Vec::new();
+        {
+            let sessions = self.sessions.read();
+            let heartbeats = self.agent_heartbeats.read();
+            let mut clocks = self.auto_complete_clocks.lock();
+            clocks.retain(|session_id, _| {
+                sessions
+                    .get(session_id)
+                    .is_some_and(Self::state_allows_completion)
+            });
+            for session in sessions.values() {
+                if !Self::state_allows_completion(session) {
+                    continue;
+                }
+                let settled = Self::session_settled(session, heartbeats.get(&session.id));
+                let step = clocks
+                    .entry(session.id.clone())
+                    .or_default()
+                    .advance(settled, now, config);
+                if step != AutoCompleteStep::Wait {
+                    steps.push((session.id.clone(), step));
+                }
+            }
+        }
+
+        let mut completed = Vec::new();
+        for (session_id, step) in steps {
+            match step {
+                AutoCompleteStep::Wait => {}
+                AutoCompleteStep::Announce { deadline } => {
+                    self.log_coordination_system(
+                        &session_id,
+                        &format!(
+                            "[AUTO_COMPLETE] Every agent is idle and every task is COMPLETED; \
+                             the session completes at {} unless an agent becomes active",
+                            deadline.format("%H:%M:%S UTC")
+                        ),
+                    );
+                    if let Some(ref app_handle) = self.app_handle {
+                        let _ = app_handle.emit(
+                            "session-auto-complete-pending",
+                            serde_json::json!({
+                                "session_id": session_id,
+                                "complete_at": deadline,
+                            }),
+                        );
+                    }
+                }
+                AutoCompleteStep::Cancel => {
+                    self.log_coordination_system(
+                        &session_id,
+                        "[AUTO_COMPLETE] Cancelled: an agent became active again",
+                    );
+                    if let Some(ref app_handle) = self.app_handle {
+                        let _ = app_handle.emit(
+                            "session-auto-complete-cancelled",
+                            serde_json::json!({ "session_id": session_id }),
+                        );
+                    }
+                }
+                AutoCompleteStep::Complete => {
+                    self.auto_complete_clocks.lock().remove(&session_id);
+                    if let Err(err) = self.set_session_completed(&session_id) {
+                        tracing::warn!("Failed to auto-complete session {}: {:?}", session_id, err);
+                        continue;
+                    }
+                    self.log_coordination_system(
+                        &session_id,
+                        "[AUTO_COMPLETE] Session completed: all agents settled",
+                    );
+                    if let Some(ref app_handle) = self.app_handle {
+                        let _ = app_handle.emit(
+                            "session-auto-completed",
+                            serde_json::json!({ "session_id": session_id }),
+                        );
+                    }
+                    completed.push(session_id);
+                }
+            }
+        }
+        completed
+    }
+
+    /// Every agent still running last reported a settled status and every worker's
+    /// task file is COMPLETED. A session without workers has nothing to finish and
+    /// never settles.
+    fn session_settled(
+        session: &Session,
+        heartbeats: Option<&HashMap<String, AgentHeartbeatInfo>>,
+    ) -> bool {
+        let agents_settled = session.agents.iter().all(|agent| {
+            matches!(agent.status, AgentStatus::Completed)
+                || heartbeats
+                    .and_then(|heartbeats| heartbeats.get(&agent.id))
+                    .is_some_and(|info| SETTLED_STATUSES.contains(&info.status.as_str()))
+        });
+        let workers: Vec<usize> = session
+            .agents
+            .iter()
+            .filter_map(|agent| match agent.role {
+                AgentRole::Worker { index, .. } => Some(usize::from(index)),
+                _ => None,
+            })
+            .collect();
+        agents_settled
+            && !workers.is_empty()
+            && workers.into_iter().all(|index| {
+                Self::task_file_path_for_session_worker(session, index)
+                    .is_ok_and(|path| Self::is_task_completed(&path.to_string_lossy()))
+            })
+    }
+
+    /// How heartbeat log lines name an agent: its label when it has one, else its ID
+    /// without the session prefix (`worker-2` for `<session>-worker-2`).
+    fn heartbeat_agent_name(session: Option<&Session>, session_id: &str, agent_id: &str) -> String {
+        let labelled = session
+            .and_then(|session| session.agents.iter().find(|agent| agent.id == agent_id))
+            .map(AgentInfo::display_name)
+            .filter(|name| *name != agent_id);
+        if let Some(name) = labelled {
+            return name.to_string();
+        }
+        agent_id
+            .strip_prefix(session_id)
+            .and_then(|rest| rest.strip_prefix('-'))
+            .unwrap_or(agent_id)
+            .to_string()
+    }
+
+    /// Append a SYSTEM line to the session's coordination log and show it in the
+    /// coordination view. Returns false when there is no storage or the write failed.
+    fn log_coordination_system(&self, session_id: &str, content: &str) -> bool {
+        let Some(storage) = self.storage.as_ref() else {
+            return false;
+        };
+        let message = CoordinationMessage::system("LOG", content);
+        if let Err(e) = storage.append_coordination_log(session_id, &message) {
+            tracing::warn!(
+                "Failed to write coordination log for session {}: {}",
+                session_id,
+                e
+            );
+            return false;
+        }
+        if let Some(ref app_handle) = self.app_handle {
+            let _ = app_handle.emit("coordination-message", &message);
+        }
+        true
+    }
+
+    // --- Solo Task Queue ---
+
+    /// Queue a follow-up prompt for a Solo session. It is written to the agent as
+    /// soon as the agent goes idle. Returns the number of prompts now pending.
+    pub fn queue_solo_task(&self, session_id: &str, prompt: &str) -> Result<usize, String> {
+        let prompt = prompt.trim();
+        if prompt.is_empty() {
+            return Err("Prompt must not be empty".to_string());
+        }
+        {
+            let sessions = self.sessions.read();
+            let session = sessions
+                .get(session_id)
+                .ok_or_else(|| format!("Session not found: {}", session_id))?;
+            if !matches!(session.session_type, SessionType::Solo { .. }) {
+                return Err(format!("Session {} is not a Solo session", session_id));
+            }
+            if is_terminal_session_state(&session.state) {
+                return Err(format!(
+                    "Session {} has already finished ({:?})",
+                    session_id, session.state
+                ));
+            }
+        }
+
+        let pending = {
+            let mut queues = self.solo_task_queues.lock();
+            let queue = queues.entry(session_id.to_string()).or_default();
+            queue.pending.push_back(prompt.to_string());
+            queue.pending.len()
+        };
+        self.emit_solo_queue_update(session_id);
+        self.dispatch_solo_task_if_idle(session_id);
+        Ok(pending)
+    }
+
+    /// Prompts still waiting for the session's Solo agent, oldest first.
+    pub fn solo_task_queue(&self, session_id: &str) -> Vec<String> {
+        self.solo_task_queues
+            .lock()
+            .get(session_id)
+            .map(|queue| queue.pending.iter().cloned().collect())
+            .unwrap_or_default()
+    }
+
+    /// Hand the next queued prompt to every Solo agent that has gone idle. Called
+    /// periodically so agents that never post heartbeats are still picked up by
+    /// their output going quiet.
+    pub fn dispatch_idle_solo_tasks(&self) {
+        let session_ids: Vec<String> = self.solo_task_queues.lock().keys().cloned().collect();
+        for session_id in session_ids {
+            self.dispatch_solo_task_if_idle(&session_id);
+        }
+    }
+
+    fn solo_agent_id(session_id: &str) -> String {
+        format!("{}-worker-1", session_id)
+    }
+
+    /// The agent is idle once it has reported an idle/completed heartbeat or gone
+    /// quiet for [`SOLO_IDLE_OUTPUT_THRESHOLD`], in both cases since the last prompt
+    /// we gave it.
+    fn solo_agent_is_idle(&self, session_id: &str, last_dispatch: Option<DateTime<Utc>>) -> bool {
+        let agent_id = Self::solo_agent_id(session_id);
+        let output_idle_for = {
+            let pty_manager = self.pty_manager.read();
+            if !pty_manager.is_alive(&agent_id) {
+                return false;
+            }
+            pty_manager.idle_for(&agent_id)
+        };
+
+        let heartbeat_idle = self
+            .agent_heartbeats
+            .read()
+            .get(session_id)
+            .and_then(|agents| agents.get(&agent_id))
+            .is_some_and(|heartbeat| {
+                (heartbeat.status == "idle" || heartbeat.status == "completed")
+                    && last_dispatch.is_none_or(|at| heartbeat.last_activity > at)
+            });
+        if heartbeat_idle {
+            return true;
+        }
+
+        output_idle_for.is_some_and(|idle| idle >= SOLO_IDLE_OUTPUT_THRESHOLD)
+            && last_dispatch.is_none_or(|at| {
+                (Utc::now() - at)
+                    .to_std()
+                    .is_ok_and(|elapsed| elapsed >= SOLO_IDLE_OUTPUT_THRESHOLD)
+            })
+    }
+
+    fn dispatch_solo_task_if_idle(&self, session_id: &str) {
+        let finished = self
+            .sessions
+            .read()
+            .get(session_id)
+            .is_none_or(|session| is_terminal_session_state(&session.state));
+        if finished {
+            self.solo_task_queues.lock().remove(session_id);
+            return;
+        }
+
+        let mut queues = self.solo_task_queues.lock();
+        let Some(queue) = queues.get_mut(session_id) else {
+            return;
+        };
+        if queue.pending.is_empty() || !self.solo_agent_is_idle(session_id, queue.last_dispatch) {
+            return;
+        }
+        let Some(prompt) = queue.pending.pop_front() else {
+            return;
+        };
+
+        let agent_id = Self::solo_agent_id(session_id);
+        let written = self
+            .pty_manager
+            .read()
+            .write_bracketed(&agent_id, format!("{}\r", prompt).as_bytes());
+        match written {
+            Ok(()) => {
+                queue.last_dispatch = Some(Utc::now());
+                drop(queues);
+                tracing::info!("Dispatched queued task to Solo agent {}", agent_id);
+                self.emit_solo_queue_update(session_id);
+            }
+            Err(e) => {
+                tracing::warn!(
+                    "Failed to dispatch queued task to Solo agent {}: {}",
+                    agent_id,
+                    e
+                );
+                queue.pending.push_front(prompt);
+            }
+        }
+    }
+
+    fn emit_solo_queue_update(&self, session_id: &str) {
+        if let Some(ref app_handle) = self.app_handle {
+            let _ = app_handle.emit(
+                "solo-queue-updated",
+                serde_json::json!({
+                    "session_id": session_id,
+                    "pending": self.solo_task_queue(session_id),
+                }),
+            );
+        }
+    }
+
+    // --- Operator Questions ---
+
+    /// Record a question `agent_id` is blocked on and surface it to the operator.
+    /// The answer reaches the agent through its PTY once the operator replies.
+    pub fn ask_operator(
+        &self,
+        session_id: &str,
+        agent_id: &str,
+        question: &str,
+        options: Vec<String>,
+    ) -> Result<OperatorQuestion, String> {
+        let question = question.trim();
+        if question.is_empty() {
+            return Err("Question must not be empty".to_string());
+        }
+        if question.chars().count() > MAX_OPERATOR_QUESTION_LEN {
+            return Err(format!(
+                "Question exceeds {} characters",
+                MAX_OPERATOR_QUESTION_LEN
+            ));
+        }
+        let cell_id = {
+            let sessions = self.sessions.read();
+            let session = sessions
+                .get(session_id)
+                .ok_or_else(|| format!("Session not found: {}", session_id))?;
+            if is_terminal_session_state(&session.state) {
+                return Err(format!("Session {} has already finished", session_id));
+            }
+            let agent = session
+                .agents
+                .iter()
+                .find(|agent| agent.id == agent_id)
+                .ok_or_else(|| format!("Agent not found: {}", agent_id))?;
+            agent_cell_id(session, agent)
+        };
+
+        let entry = OperatorQuestion {
+            id: Uuid::new_v4().to_string(),
+            session_id: session_id.to_string(),
+            agent_id: agent_id.to_string(),
+            question: question.to_string(),
+            options: options
+                .into_iter()
+                .map(|option| option.trim().to_string())
+                .filter(|option| !option.is_empty())
+                .collect(),
+            asked_at: Utc::now(),
+            answer: None,
+            answered_at: None,
+        };
+        self.operator_questions
+            .write()
+            .entry(session_id.to_string())
+            .or_default()
+            .push(entry.clone());
+        self.emit_operator_question(&entry);
+
+        if let Some(emitter) = self.event_emitter.clone() {
+            let session_id = session_id.to_string();
+            let agent_id = agent_id.to_string();
+            let prompt = entry.question.clone();
+            tokio::spawn(async move {
+                if let Err(error) = emitter
+                    .emit_agent_waiting_input(&session_id, &cell_id, &agent_id, &prompt)
+                    .await
+                {
+                    tracing::debug!("Failed to emit agent waiting input event: {}", error);
+                }
+            });
+        }
+        Ok(entry)
+    }
+
+    /// Every question asked in a session, oldest first.
+    pub fn operator_questions(&self, session_id: &str) -> Vec<OperatorQuestion> {
+        self.operator_questions
+            .read()
+            .get(session_id)
+            .cloned()
+            .unwrap_or_default()
+    }
+
+    /// Questions in a session still waiting for an answer.
+    pub fn pending_operator_questions(&self, session_id: &str) -> Vec<OperatorQuestion> {
+        self.operator_questions(session_id)
+            .into_iter()
+            .filter(|question| question.answer.is_none())
+            .collect()
+    }
+
+    /// A question that has not been answered yet.
+    pub fn pending_operator_question(
+        &self,
+        session_id: &str,
+        question_id: &str,
+    ) -> Result<OperatorQuestion, String> {
+        let question = self
+            .operator_questions(session_id)
+            .into_iter()
+            .find(|question| question.id == question_id)
+            .ok_or_else(|| format!("Question not found: {}", question_id))?;
+        if question.answer.is_some() {
+            return Err(format!("Question {} was already answered", question_id));
+        }
+        Ok(question)
+    }
+
+    /// Record the operator's answer. Delivering it to the agent is the caller's job
+    /// (see `coordination.answer_question`), so this runs once that succeeded.
+    pub fn answer_operator_question(
+        &self,
+        session_id: &str,
+        question_id: &str,
+        answer: &str,
+    ) -> Result<OperatorQuestion, String> {
+        let answered = {
+            let mut questions = self.operator_questions.write();
+            let question = questions
+                .get_mut(session_id)
+                .and_then(|questions| {
+                    questions
+                        .iter_mut()
+                        .find(|question| question.id == question_id)
+                })
+                .ok_or_else(|| format!("Question not found: {}", question_id))?;
+            if question.answer.is_some() {
+                return Err(format!("Question {} was already answered", question_id));
+            }
+            question.answer = Some(answer.to_string());
+            question.answered_at = Some(Utc::now());
+            question.clone()
+        };
+        self.emit_operator_question(&answered);
+        Ok(answered)
+    }
+
+    fn emit_operator_question(&self, question: &OperatorQuestion) {
+        if let Some(ref app_handle) = self.app_handle {
+            let _ = app_handle.emit("operator-question", question);
+        }
+    }
+
+    // --- Launch Queue ---
+
+    /// Running sessions and their live agents. Queued, closing and finished
+    /// sessions do not count against the concurrency limits.
+    fn launch_load(&self) -> (usize, usize) {
+        let sessions = self.sessions.read();
+        let active: Vec<&Session> = sessions
+            .values()
+            .filter(|session| {
+                !matches!(session.state, SessionState::Queued | SessionState::Closing)
+                    && !is_terminal_session_state(&session.state)
+            })
+            .collect();
+        let live_agents = active
+            .iter()
+            .flat_map(|session| &session.agents)
+            .filter(|agent| {
+                matches!(
+                    agent.status,
+                    AgentStatus::Starting
+                        | AgentStatus::Running
+                        | AgentStatus::Idle
+                        | AgentStatus::WaitingForInput(_)
+                )
+            })
+            .count();
+        (active.len(), live_agents)
+    }
+
+    /// Whether a launch of `agents` agents may start now: no launch is queued
+    /// ahead of it and it fits under `limits`.
+    pub fn can_launch_now(&self, agents: usize, limits: &ConcurrencyConfig) -> bool {
+        let queue = self.launch_queue.lock();
+        if !queue.is_empty() {
+            return false;
+        }
+        let (sessions, live_agents) = self.launch_load();
+        launch_queue::fits(limits, sessions, live_agents, agents)
+    }
+
+    /// Hold a launch that does not fit under the concurrency limits. Returns the
+    /// `Queued` placeholder session that stands in for it until it starts.
+    pub fn enqueue_launch(&self, config: QueuedLaunchConfig) -> Session {
+        let session_id = Uuid::new_v4().to_string();
+        let placeholder = Self::queued_placeholder(&session_id, &config);
+
+        let position = {
+            let mut queue = self.launch_queue.lock();
+            queue.push_back(QueuedLaunch {
+                session_id: session_id.clone(),
+                config,
+            });
+            self.sessions
+                .write()
+                .insert(session_id.clone(), placeholder.clone());
+            queue.len()
+        };
+        tracing::info!(
+            "Session {} queued at position {} until capacity frees",
+            session_id,
+            position
+        );
+        self.emit_session_update(&session_id);
+        placeholder
+    }
+
+    /// Queued placeholder sessions, next to start first.
+    pub fn list_queued_sessions(&self) -> Vec<Session> {
+        let queue = self.launch_queue.lock();
+        let sessions = self.sessions.read();
+        queue
+            .iter()
+            .filter_map(|launch| sessions.get(&launch.session_id).cloned())
+            .collect()
+    }
+
+    pub fn is_launch_queued(&self, session_id: &str) -> bool {
+        self.launch_queue
+            .lock()
+            .iter()
+            .any(|launch| launch.session_id == session_id)
+    }
+
+    /// Drop a queued launch before it starts. Its placeholder session is closed.
+    pub fn cancel_queued_session(&self, session_id: &str) -> Result<(), String> {
+        {
+            let mut queue = self.launch_queue.lock();
+            let index = queue
+                .iter()
+                .position(|launch| launch.session_id == session_id)
+                .ok_or_else(|| format!("No queued launch for session {}", session_id))?;
+            queue.remove(index);
+            if let Some(session) = self.sessions.write().get_mut(session_id) {
+                session.state = SessionState::Closed;
+            }
+        }
+        tracing::info!("Queued launch for session {} cancelled", session_id);
+        self.emit_session_update(session_id);
+        Ok(())
+    }
+
+    /// Take the oldest queued launch once it fits under `limits`. Its placeholder
+    /// is dropped so the launch can register the session under the same ID.
+    pub fn next_startable_launch(&self, limits: &ConcurrencyConfig) -> Option<QueuedLaunch> {
+        let mut queue = self.launch_queue.lock();
+        let agents = queue.front()?.config.agent_count();
+        let (sessions, live_agents) = self.launch_load();
+        if !launch_queue::fits(limits, sessions, live_agents, agents) {
+            return None;
+        }
+        let launch = queue.pop_front()?;
+        self.sessions.write().remove(&launch.session_id);
+        Some(launch)
+    }
+
+    /// Launch a session taken from the queue under its placeholder's ID. A launch
+    /// that fails leaves the placeholder behind as `Failed` so the operator sees why.
+    pub fn start_queued_launch(
+        &self,
+        launch: QueuedLaunch,
+    ) -> Result<(Session, Option<FusionSpawnPlan>), String> {
+        let QueuedLaunch { session_id, config } = launch;
+        let placeholder = Self::queued_placeholder(&session_id, &config);
+        let result = launch_queue::with_reserved_session_id(&session_id, || match config {
+            QueuedLaunchConfig::LegacyHive {
+                project_path,
+                worker_count,
+                command,
+                prompt,
+                name,
+                color,
+            } => self
+                .launch_hive(project_path, worker_count, &command, prompt, name, color)
+                .map(|session| (session, None)),
+            QueuedLaunchConfig::Hive(config) => {
+                self.launch_hive_v2(config).map(|session| (session, None))
+            }
+            QueuedLaunchConfig::Solo(config) => {
+                self.launch_solo(config).map(|session| (session, None))
+            }
+            QueuedLaunchConfig::Research(config) => {
+                self.launch_research(config).map(|session| (session, None))
+            }
+            QueuedLaunchConfig::Swarm(config) => {
+                self.launch_swarm(config).map(|session| (session, None))
+            }
+            QueuedLaunchConfig::Fusion(config) => self.launch_fusion(config),
+            QueuedLaunchConfig::Debate(config) => {
+                self.launch_debate(config).map(|session| (session, None))
+            }
+        });
+
+        match &result {
+            Ok((session, _)) => {
+                tracing::info!("Queued session {} started", session.id);
+            }
+            Err(error) => {
+                tracing::error!("Queued session {} failed to launch: {}", session_id, error);
+                let mut failed = placeholder;
+                failed.state = SessionState::Failed(error.clone());
+                self.sessions
+                    .write()
+                    .entry(session_id.clone())
+                    .or_insert(failed);
+                self.emit_session_update(&session_id);
+            }
+        }
+        result
+    }
+
+    /// The session a queued launch shows as until it starts.
+    fn queued_placeholder(session_id: &str, config: &QueuedLaunchConfig) -> Session {
+        let (max_qa_iterations, qa_timeout_secs, auth_strategy) = default_session_qa_settings();
+        let (name, color) = config.name_and_color();
+        let now = Utc::now();
+        Session {
+            id: session_id.to_string(),
+            name,
+            color,
+            session_type: config.session_type(),
+            project_path: config.project_path(),
+            state: SessionState::Queued,
+            created_at: now,
+            last_activity_at: now,
+            agents: Vec::new(),
+            default_cli: config.default_cli(),
+            default_model: None,
+            default_principal_cli: None,
+            default_principal_model: None,
+            default_principal_flags: Vec::new(),
+            execution_policy: HiveExecutionPolicy::default(),
+            qa_workers: Vec::new(),
+            max_qa_iterations,
+            qa_timeout_secs,
+            auth_strategy,
+            worktree_path: None,
+            worktree_branch: None,
+            no_git: false,
+            resume_report: None,
+            pull_request_url: None,
+            spawn_quota: SpawnQuota::default(),
+        }
+    }
+
+    // --- Agent Quarantine ---
+
+    /// Cut an agent off from the session's HTTP API and from coordination messages
+    /// while its PTY keeps running, so a misbehaving agent can be inspected before
+    /// it is killed.
+    pub fn quarantine_agent(&self, session_id: &str, agent_id: &str) -> Result<(), String> {
+        self.set_agent_quarantined(session_id, agent_id, true)
+    }
+
+    pub fn release_agent(&self, session_id: &str, agent_id: &str) -> Result<(), String> {
+        self.set_agent_quarantined(session_id, agent_id, false)
+    }
+
+    fn set_agent_quarantined(
+        &self,
+        session_id: &str,
+        agent_id: &str,
+        quarantined: bool,
+    ) -> Result<(), String> {
+        {
+            let sessions = self.sessions.read();
+            let session = sessions
+                .get(session_id)
+                .ok_or_else(|| format!("Session not found: {}", session_id))?;
+            if !session.agents.iter().any(|agent| agent.id == agent_id) {
+                return Err(format!("Agent not found: {}", agent_id));
+            }
+        }
+
+        let changed = {
+            let mut quarantined_agents = self.quarantined_agents.write();
+            if quarantined {
+                quarantined_agents
+                    .entry(session_id.to_string())
+                    .or_default()
+                    .insert(agent_id.to_string())
+            } else {
+                quarantined_agents
+                    .get_mut(session_id)
+                    .is_some_and(|agents| agents.remove(agent_id))
+            }
+        };
+        if changed {
+            tracing::info!(
+                "Agent {} in session {} {}",
+                agent_id,
+                session_id,
+                if quarantined {
+                    "quarantined"
+                } else {
+                    "released from quarantine"
+                }
+            );
+            if let Some(ref app_handle) = self.app_handle {
+                let _ = app_handle.emit(
+                    "agent-quarantine",
+                    serde_json::json!({
+                        "session_id": session_id,
+                        "agent_id": agent_id,
+                        "quarantined": quarantined,
+                    }),
+                );
+            }
+        }
+        Ok(())
+    }
+
+    pub fn is_agent_quarantined(&self, session_id: &str, agent_id: &str) -> bool {
+        self.quarantined_agents
+            .read()
+            .get(session_id)
+            .is_some_and(|agents| agents.contains(agent_id))
+    }
+
+    /// Quarantined agents in a session, sorted by ID.
+    pub fn quarantined_agents(&self, session_id: &str) -> Vec<String> {
+        let mut agents: Vec<String> = self
+            .quarantined_agents
+            .read()
+            .get(session_id)
+            .map(|agents| agents.iter().cloned().collect())
+            .unwrap_or_default();
+        agents.sort();
+        agents
+    }
+
+    // --- Inbox Digests ---
+
+    /// Agents of running sessions that can be sent an inbox digest, per session: each
+    /// one whose CLI is up and that is not quarantined.
+    pub fn inbox_digest_targets(&self) -> Vec<(String, Vec<DigestTarget>)> {
+        let sessions = self.sessions.read();
+        sessions
+            .values()
+            .filter(|session| session.state.is_monitorable())
+            .filter_map(|session| {
+                let prefix = format!("{}-", session.id);
+                let targets: Vec<DigestTarget> = session
+                    .agents
+                    .iter()
+                    .filter(|agent| {
+                        matches!(
+                            agent.status,
+                            AgentStatus::Running
+                                | AgentStatus::Idle
+                                | AgentStatus::WaitingForInput(_)
+                        )
+                    })
+                    .filter(|agent| !self.is_agent_quarantined(&session.id, &agent.id))
+                    .map(|agent| DigestTarget {
+                        agent_id: agent.id.clone(),
+                        inbox: agent
+                            .id
+                            .strip_prefix(&prefix)
+                            .unwrap_or(&agent.id)
+                            .to_string(),
+                        submit: self.pty_manager.read().submit_key(&agent.id),
+                    })
+                    .collect();
+                (!targets.is_empty()).then(|| (session.id.clone(), targets))
+            })
+            .collect()
+    }
+
+    // --- Phase Time Boxes ---
+
+    /// Spawn the agents of a session launched with `background` below normal OS
+    /// priority (see `pty::priority`).
+    fn track_background(&self, session_id: &str, background: bool) {
+        if background {
+            self.pty_manager.read().set_background(session_id, true);
+        }
+    }
+
+    fn track_phase_timeouts(&self, session_id: &str, timeouts: PhaseTimeouts) {
+        if !timeouts.is_empty() {
+            self.phase_clocks
+                .lock()
+                .insert(session_id.to_string(), SessionPhaseClocks::new(timeouts));
+        }
+    }
+
+    /// The phases of `session` that are running and have a budget.
+    fn running_phases(
+        session: &Session,
+        limits: &PhaseTimeouts,
+        pty_manager: &PtyManager,
+    ) -> Vec<RunningPhase> {
+        let live_agents = |matches_role: fn(&AgentRole) -> bool| -> Vec<String> {
+            session
+                .agents
+                .iter()
+                .filter(|agent| matches_role(&agent.role) && pty_manager.is_alive(&agent.id))
+                .map(|agent| agent.id.clone())
+                .collect()
+        };
+
+        let mut phases = Vec::new();
+        match (&session.state, limits.planning_secs, limits.judging_secs) {
+            (SessionState::Planning, Some(limit_secs), _) => phases.push(RunningPhase {
+                key: PLANNING_PHASE.to_string(),
+                kind: PLANNING_PHASE,
+                limit_secs,
+                agent_ids: live_agents(|role| matches!(role, AgentRole::MasterPlanner)),
+            }),
+            (SessionState::SpawningJudge | SessionState::Judging { .. }, _, Some(limit_secs)) => {
+                phases.push(RunningPhase {
+                    key: JUDGING_PHASE.to_string(),
+                    kind: JUDGING_PHASE,
+                    limit_secs,
+                    agent_ids: live_agents(|role| matches!(role, AgentRole::Judge { .. })),
+                })
+            }
+            _ => {}
+        }
+
+        if let Some(limit_secs) = limits.worker_secs {
+            for agent in &session.agents {
+                let finished = match agent.role {
+                    AgentRole::Worker { index, .. } => {
+                        Self::task_file_path_for_session_worker(session, index as usize)
+                            .is_ok_and(|path| Self::is_task_completed(&path.to_string_lossy()))
+                    }
+                    AgentRole::Fusion { .. } => false,
+                    _ => continue,
+                };
+                if finished
+                    || agent.status == AgentStatus::Completed
+                    || !pty_manager.is_alive(&agent.id)
+                {
+                    continue;
+                }
+                phases.push(RunningPhase {
+                    key: agent.id.clone(),
+                    kind: WORKER_PHASE,
+                    limit_secs,
+                    agent_ids: vec![agent.id.clone()],
+                });
+            }
+        }
+        phases
+    }
+
+    /// Move every time-boxed phase along its clock: a heads-up at 80% of the
+    /// budget, a wrap-up instruction at 100%, and once the grace period after that
+    /// is over the phase is ended. Returns how many phases were ended.
+    pub fn enforce_phase_timeouts(&self) -> usize {
+        let now = Utc::now();
+        let mut due: Vec<(String, RunningPhase, PhaseStage)> = Vec::new();
+        {
+            let mut tracked = self.phase_clocks.lock();
+            let sessions = self.sessions.read();
+            let pty_manager = self.pty_manager.read();
+            // A session still launching has no entry yet; keep its budgets for later.
+            tracked.retain(|session_id, _| {
+                sessions
+                    .get(session_id)
+                    .is_none_or(|session| !is_terminal_session_state(&session.state))
+            });
+            for (session_id, phase_clocks) in tracked.iter_mut() {
+                let Some(session) = sessions.get(session_id) else {
+                    continue;
+                };
+                let running = Self::running_phases(session, &phase_clocks.limits, &pty_manager);
+                phase_clocks
+                    .clocks
+                    .retain(|key, _| running.iter().any(|phase| &phase.key == key));
+                for phase in running {
+                    let stage = phase_clocks
+                        .clocks
+                        .entry(phase.key.clone())
+                        .or_insert_with(|| PhaseClock::new(now))
+                        .advance(now, phase.limit_secs);
+                    if let Some(stage) = stage {
+                        due.push((session_id.clone(), phase, stage));
+                    }
+                }
+            }
+        }
+
+        let mut ended = 0;
+        for (session_id, phase, stage) in due {
+            let label = match phase.kind {
+                PLANNING_PHASE => "Planning".to_string(),
+                JUDGING_PHASE => "Judging".to_string(),
+                _ => format!("Worker {}", phase.key),
+            };
+            let minutes = phase.limit_secs.div_ceil(60);
+            match stage {
+                PhaseStage::Warning => {
+                    tracing::info!(
+                        "{} in session {} has used 80% of its budget",
+                        label,
+                        session_id
+                    );
+                    self.send_phase_notice(
+                        &phase.agent_ids,
+                        &format!(
+                            "[TIME] {} has used 80% of its {}-minute budget. Start wrapping up.",
+                            label, minutes
+                        ),
+                    );
+                }
+                PhaseStage::WrapUp => {
+                    tracing::info!("{} in session {} is out of time", label, session_id);
+                    self.send_phase_notice(
+                        &phase.agent_ids,
+                        &format!(
+                            "[TIME] {} has used its {}-minute budget. Stop starting new work: \
+                             save what you have, update your status and report back within {} \
+                             minutes, or the phase will be ended for you.",
+                            label,
+                            minutes,
+                            grace_secs(phase.limit_secs).div_ceil(60)
+                        ),
+                    );
+                }
+                PhaseStage::TimedOut => match self.end_timed_out_phase(&session_id, &phase, &label)
+                {
+                    Ok(()) => ended += 1,
+                    Err(e) => tracing::warn!(
+                        "Failed to end timed-out {} in session {}: {}",
+                        label,
+                        session_id,
+                        e
+                    ),
+                },
+            }
+            self.emit_phase_time_budget(&session_id, &phase, stage);
+        }
+        ended
+    }
+
+    fn send_phase_notice(&self, agent_ids: &[String], message: &str) {
+        let pty_manager = self.pty_manager.read();
+        for agent_id in agent_ids {
+            if let Err(e) =
+                pty_manager.write_bracketed(agent_id, format!("{}\r", message).as_bytes())
+            {
+                tracing::warn!("Failed to send time notice to {}: {}", agent_id, e);
+            }
+        }
+    }
+
+    /// Force a phase that ran past its budget and grace period to end: planning is
+    /// marked ready with whatever plan exists, a worker is stopped and the Queen
+    /// told, and judging moves on to the operator's verdict selection.
+    fn end_timed_out_phase(
+        &self,
+        session_id: &str,
+        phase: &RunningPhase,
+        label: &str,
+    ) -> Result<(), String> {
+        tracing::warn!(
+            "{} in session {} ran out of time; ending it",
+            label,
+            session_id
+        );
+        match phase.kind {
+            PLANNING_PHASE => self.mark_plan_ready(session_id)?,
+            JUDGING_PHASE => {
+                let judging = self.get_session(session_id).is_some_and(|session| {
+                    matches!(
+                        session.state,
+                        SessionState::SpawningJudge | SessionState::Judging { .. }
+                    )
+                });
+                if !judging {
+                    return Err("Session is no longer judging".to_string());
+                }
+                for agent_id in &phase.agent_ids {
+                    self.stop_agent(session_id, agent_id)?;
+                }
+                let changes = {
+                    let mut sessions = self.sessions.write();
+                    let session = sessions
+                        .get_mut(session_id)
+                        .ok_or_else(|| format!("Session not found: {}", session_id))?;
+                    self.set_session_state_with_events(
+                        session,
+                        SessionState::AwaitingVerdictSelection,
+                    )
+                };
+                self.emit_session_update(session_id);
+                self.update_session_storage(session_id);
+                self.emit_cell_status_changes(session_id, changes);
+            }
+            _ => {
+                self.stop_agent(session_id, &phase.key)?;
+                let queen_id = self.get_session(session_id).and_then(|session| {
+                    session
+                        .agents
+                        .iter()
+                        .find(|agent| matches!(agent.role, AgentRole::Queen))
+                        .map(|agent| agent.id.clone())
+                });
+                if let Some(queen_id) = queen_id {
+                    self.send_phase_notice(
+                        &[queen_id],
+                        &format!(
+                            "[TIME] {} ran out of time and was stopped before finishing its \
+                             task. Review its worktree, then reassign or continue without it.",
+                            label
+                        ),
+                    );
+                }
+            }
+        }
+
+        if let Some(emitter) = self.event_emitter.clone() {
+            let session_id = session_id.to_string();
+            let agent_id = (phase.kind == WORKER_PHASE).then(|| phase.key.clone());
+            let kind = phase.kind;
+            let limit_secs = phase.limit_secs;
+            tokio::spawn(async move {
+                if let Err(error) = emitter
+                    .emit_phase_timeout(&session_id, agent_id.as_deref(), kind, limit_secs)
+                    .await
+                {
+                    tracing::debug!("Failed to emit phase timeout event: {}", error);
+                }
+            });
+        }
+        Ok(())
+    }
+
+    /// `phase-timeout` once a phase is ended; `phase-time-warning` for the
+    /// heads-up and the wrap-up instruction before that.
+    fn emit_phase_time_budget(&self, session_id: &str, phase: &RunningPhase, stage: PhaseStage) {
+        if let Some(ref app_handle) = self.app_handle {
+            let event = if stage == PhaseStage::TimedOut {
+                "phase-timeout"
+            } else {
+                "phase-time-warning"
+            };
+            let _ = app_handle.emit(
+                event,
+                serde_json::json!({
+                    "session_id": session_id,
+                    "phase": phase.kind,
+                    "agent_ids": phase.agent_ids,
+                    "stage": stage,
+                    "limit_secs": phase.limit_secs,
+                }),
+            );
+        }
+    }
+
+    // --- Review Stage ---
+
+    /// Move a Hive session's review chain on. The chain starts once every worker
+    /// has completed its task; each step starts when the one before it completes,
+    /// and the session goes back to `Running` after the last. Returns whether the
+    /// call was the review stage's to handle.
+    pub fn advance_review_stage(&self, session_id: &str) -> Result<bool, String> {
+        // Held while spawning so simultaneous worker completions start a step once.
+        let mut stages = self.review_stages.lock();
+        let Some(stage) = stages.get_mut(session_id) else {
+            return Ok(false);
+        };
+        let session = self
+            .get_session(session_id)
+            .ok_or_else(|| format!("Session not found: {}", session_id))?;
+        let task_completed = |index: u8| {
+            Self::task_file_path_for_session_worker(&session, index as usize)
+                .map(|path| Self::is_task_completed(&path.to_string_lossy()))
+                .unwrap_or(false)
+        };
+
+        match (&session.state, stage.current) {
+            (SessionState::Running, None) if !stage.finished => {
+                let workers: Vec<u8> = session
+                    .agents
+                    .iter()
+                    .filter_map(|agent| match agent.role {
+                        AgentRole::Worker { index, .. } => Some(index),
+                        _ => None,
+                    })
+                    .collect();
+                if workers.is_empty() || !workers.iter().all(|index| task_completed(*index)) {
+                    return Ok(false);
+                }
+            }
+            (SessionState::Reviewing { .. }, Some(current)) => {
+                if !task_completed(current.worker_index) {
+                    return Ok(true);
+                }
+            }
+            _ => return Ok(false),
+        }
+
+        let Some(step) = stage.next_step() else {
+            stage.finished = true;
+            let changes = {
+                let mut sessions = self.sessions.write();
+                sessions
+                    .get_mut(session_id)
+                    .map(|s| self.set_session_state_with_events(s, SessionState::Running))
+            };
+            if let Some(changes) = changes {
+                self.persist_then_emit_session_update(session_id, changes)?;
+            }
+            tracing::info!(session_id = %session_id, "Review stage finished");
+            return Ok(true);
+        };
+
+        let task = Self::review_stage_task(&session, &stage.chain, step);
+        let (config, role) = stage.worker_config(step, task);
+        let role_type = role.role_type.clone();
+        let worker = self.add_worker(session_id, config, role, None)?;
+        let AgentRole::Worker {
+            index: worker_index,
+            ..
+        } = worker.role
+        else {
+            return Err(format!("Review worker {} is not a worker", worker.id));
+        };
+        stage.current = Some(ReviewStep { step, worker_index });
+        tracing::info!(
+            session_id = %session_id,
+            agent_id = %worker.id,
+            "Review stage step {} of {}: {}",
+            step + 1,
+            stage.chain.len(),
+            role_type
+        );
+
+        let changes = {
+            let mut sessions = self.sessions.write();
+            sessions.get_mut(session_id).map(|s| {
+                self.set_session_state_with_events(
+                    s,
+                    SessionState::Reviewing {
+                        step: (step + 1) as u8,
+                        role: role_type,
+                    },
+                )
+            })
+        };
+        if let Some(changes) = changes {
+            self.persist_then_emit_session_update(session_id, changes)?;
+        }
+        Ok(true)
+    }
+
+    /// The task for `step` of the review chain: the diff of the session's primary
+    /// workspace since the first worker branched, and the commits workers finished on.
+    fn review_stage_task(session: &Session, chain: &[String], step: usize) -> String {
+        let workers = session
+            .agents
+            .iter()
+            .filter(|agent| matches!(agent.role, AgentRole::Worker { .. }));
+        let base_commit = workers
+            .clone()
+            .find_map(|agent| agent.base_commit_sha.as_deref());
+        let workspace = session
+            .worktree_path
+            .as_deref()
+            .map(PathBuf::from)
+            .unwrap_or_else(|| session.project_path.clone());
+        let diff_stat = match base_commit {
+            Some(base) if !session.no_git => diff_stat_since(&workspace, base)
+                .map_err(|err| {
+                    tracing::warn!(session_id = %session.id, "Review diff unavailable: {}", err)
+                })
+                .ok(),
+            _ => None,
+        };
+        let context = ReviewContext {
+            base_commit,
+            diff_stat: diff_stat.as_deref(),
+            worker_commits: workers
+                .filter_map(|agent| Some((agent.id.as_str(), agent.commit_sha.as_deref()?)))
+                .collect(),
+        };
+        let session_root = session.project_path.join(".hive-manager").join(&session.id);
+        review_stage::review_task(chain, step, &session_root, &context)
+    }
+
+    // --- PTY Watchdog ---
+
+    /// Compare the PTY manager's processes with every session's agents and deal
+    /// with the PTYs no session lists (see [`orphans`]): once seen on two passes
+    /// in a row, an orphan whose ID names a live session is adopted back into it
+    /// and any other is killed. Returns how many orphans were acted on.
+    pub fn reconcile_orphaned_ptys(&self) -> usize {
+        type Orphaned = HashMap<String, (AgentRole, AgentStatus)>;
+        let (orphaned, live_sessions): (Orphaned, Vec<String>) = {
+            let sessions = self.sessions.read();
+            let known: HashSet<&str> = sessions
+                .values()
+                .flat_map(|session| session.agents.iter().map(|agent| agent.id.as_str()))
+                .collect();
+            let orphaned = self
+                .pty_manager
+                .read()
+                .list_sessions()
+                .into_iter()
+                .filter(|(id, _, _)| !known.contains(id.as_str()))
+                .map(|(id, role, status)| (id, (role, status)))
+                .collect();
+            let live_sessions = sessions
+                .values()
+                .filter(|session| {
+                    !is_terminal_session_state(&session.state)
+                        && !matches!(session.state, SessionState::Closing)
+                })
+                .map(|session| session.id.clone())
+                .collect();
+            (orphaned, live_sessions)
+        };
+
+        let confirmed = {
+            let mut watchdog = self.orphaned_ptys.lock();
+            let previous: HashSet<String> = watchdog.pending.drain(..).collect();
+            let (confirmed, pending) =
+                orphans::confirm_orphans(orphaned.keys().cloned().collect(), &previous);
+            watchdog.pending = pending;
+            watchdog.pending.sort();
+            watchdog.last_checked = Some(Utc::now());
+            confirmed
+        };
+
+        let mut acted = 0;
+        for pty_id in confirmed {
+            let Some((role, status)) = orphaned.get(&pty_id) else {
+                continue;
+            };
+            let owner =
+                orphans::owning_session(&pty_id, live_sessions.iter().map(String::as_str));
+            if let Some(session_id) = owner {
+                if self.adopt_orphaned_pty(session_id, &pty_id, role.clone(), status.clone()) {
+                    self.orphaned_ptys.lock().adopted += 1;
+                    acted += 1;
+                }
+                continue;
+            }
+            match self.pty_manager.read().kill(&pty_id) {
+                Ok(()) => {
+                    tracing::warn!("Killed orphaned PTY {} (no session owns it)", pty_id);
+                    self.orphaned_ptys.lock().killed += 1;
+                    acted += 1;
+                }
+                Err(e) => tracing::warn!("Failed to kill orphaned PTY {}: {}", pty_id, e),
+            }
+        }
+        acted
+    }
+
+    /// The PTY watchdog's pending orphans and running totals.
+    pub fn orphaned_ptys(&self) -> OrphanedPtys {
+        self.orphaned_ptys.lock().clone()
+    }
+
+    /// Record a running PTY as an agent of `session_id` again. Returns false when
+    /// the session is gone or already lists the agent.
+    fn adopt_orphaned_pty(
+        &self,
+        session_id: &str,
+        pty_id: &str,
+        role: AgentRole,
+        status: AgentStatus,
+    ) -> bool {
+        {
+            let mut sessions = self.sessions.write();
+            let Some(session) = sessions.get_mut(session_id) else {
+                return false;
+            };
+            if session.agents.iter().any(|agent| agent.id == pty_id) {
+                return false;
+            }
+            let parent_id = match &role {
+                AgentRole::Worker { parent, .. } | AgentRole::QaWorker { parent, .. } => {
+                    parent.clone()
+                }
+                _ => None,
+            };
+            let config = AgentConfig {
+                cli: session.default_cli.clone(),
+                ..AgentConfig::default()
+            };
+            session.agents.push(AgentInfo {
+                id: pty_id.to_string(),
+                role,
+                status,
+                config,
+                parent_id,
+                commit_sha: None,
+                base_commit_sha: None,
+                exit_info: None,
+            });
+        }
+        tracing::warn!("Adopted orphaned PTY {} back into session {}", pty_id, session_id);
+        self.update_session_storage(session_id);
+        self.emit_session_update(session_id);
+        true
+    }
+
+    // --- Context Monitoring ---
+
+    /// Read a chunk of PTY output for context-window hints (see
+    /// [`crate::session::context_monitor`]) and record the agent's context
+    /// pressure. With `auto_compact` on, an agent that reaches the threshold is
+    /// sent its CLI's compact command once.
+    pub fn observe_agent_output(&self, pty_id: &str, data: &[u8], config: &ContextMonitorConfig) {
+        let owner = self.sessions.read().values().find_map(|session| {
+            let agent = session.agents.iter().find(|agent| agent.id == pty_id)?;
+            Some((session.id.clone(), agent.config.cli.clone()))
+        });
+        let Some((session_id, cli)) = owner else {
+            self.context_monitor.lock().forget(pty_id);
+            return;
+        };
+        let Ok(adapter) = crate::adapters::get_adapter(&cli) else {
+            return;
+        };
+
+        let signals = self.context_monitor.lock().signals(pty_id, data, |line| {
+            adapter.detect_context_signal(line)
+        });
+        if signals.is_empty() {
+            return;
+        }
+
+        let now = Utc::now();
+        let nudge = {
+            let mut pressure = self.context_pressure.write();
+            let agent = pressure
+                .entry(session_id.clone())
+                .or_default()
+                .entry(pty_id.to_string())
+                .or_default();
+            for signal in signals {
+                agent.apply(signal, now);
+            }
+            let command = adapter
+                .compact_command()
+                .filter(|_| config.auto_compact && agent.wants_nudge(config.compact_below_percent));
+            if command.is_some() {
+                agent.nudged_at = Some(now);
+            }
+            command.map(|command| (command, agent.percent_left))
+        };
+
+        if let Some((command, percent_left)) = nudge {
+            let pty_manager = self.pty_manager.read();
+            let sent = pty_manager
+                .write_bracketed(pty_id, command.as_bytes())
+                .and_then(|()| pty_manager.write(pty_id, pty_manager.submit_key(pty_id).bytes()));
+            match sent {
+                Ok(()) => {
+                    let left = percent_left
+                        .map(|percent| format!("{}% context left", percent))
+                        .unwrap_or_else(|| "context low".to_string());
+                    self.log_coordination_system(
+                        &session_id,
+                        &format!("Sent {} to {} ({})", command, pty_id, left),
+                    );
+                }
+                Err(e) => tracing::warn!("Failed to send {} to {}: {}", command, pty_id, e),
+            }
+        }
+    }
+
+    /// Context pressure of every agent in `session_id` that has reported any.
+    pub fn context_pressure(&self, session_id: &str) -> HashMap<String, ContextPressure> {
+        self.context_pressure
+            .read()
+            .get(session_id)
+            .cloned()
+            .unwrap_or_default()
+    }
+
+    // --- Judge Read-Only Guard ---
+
+    /// Snapshot the main checkout's tracked files before a judge starts, so its
+    /// edits can be undone (see [`read_only`]). A judge spawned while another is
+    /// guarded reuses that snapshot, which predates both.
+    fn read_only_snapshot(&self, session: &Session) -> Option<String> {
+        if session.no_git {
+            return None;
+        }
+        let shared = self
+            .read_only_guards
+            .lock()
+            .values()
+            .find(|guard| guard.session_id == session.id)
+            .map(|guard| guard.snapshot.clone());
+        shared.or_else(|| {
+            read_only::snapshot(&session.project_path)
+                .map_err(|e| {
+                    tracing::warn!(
+                        "Judge edits in session {} cannot be reverted: {}",
+                        session.id,
+                        e
+                    )
+                })
+                .ok()
+        })
+    }
+
+    fn guard_read_only(&self, session: &Session, agent_id: &str, snapshot: Option<String>) {
+        if let Some(snapshot) = snapshot {
+            self.read_only_guards.lock().insert(
+                agent_id.to_string(),
+                ReadOnlyGuard {
+                    session_id: session.id.clone(),
+                    repo: session.project_path.clone(),
+                    snapshot,
+                },
+            );
+        }
+    }
+
+    /// Revert tracked-file edits judges made in the main checkout since they
+    /// started, log each intervention and tell the judge. Guards end once the
+    /// judge stops or the session leaves judging. Returns how many judges had
+    /// edits reverted.
+    ///
+    /// Git runs without the controller lock, which is only taken to collect the
+    /// guards and to report each revert.
+    pub fn enforce_read_only_agents(controller: &RwLock<SessionController>) -> usize {
+        let active = controller.read().active_read_only_guards();
+
+        let mut reverted = 0;
+        for (agent_id, label, guard) in active {
+            let edits = match read_only::modified_since(&guard.repo, &guard.snapshot) {
+                Ok(edits) if !edits.is_empty() => edits,
+                Ok(_) => continue,
+                Err(e) => {
+                    tracing::debug!("Failed to check {} for edits: {}", agent_id, e);
+                    continue;
+                }
+            };
+            if let Err(e) = read_only::restore(&guard.repo, &guard.snapshot, &edits) {
+                tracing::warn!("Failed to revert edits made by {}: {}", agent_id, e);
+                continue;
+            }
+            reverted += 1;
+            let files = edits.paths().collect::<Vec<_>>().join(", ");
+            controller.read().report_read_only_revert(
+                &agent_id,
+                &label,
+                &guard.session_id,
+                &files,
+            );
+        }
+        reverted
+    }
+
+    /// Guarded judges that are still running, with their display labels. Guards
+    /// of judges that stopped or whose session left judging are dropped.
+    fn active_read_only_guards(&self) -> Vec<(String, String, ReadOnlyGuard)> {
+        let sessions = self.sessions.read();
+        let pty_manager = self.pty_manager.read();
+        let mut guards = self.read_only_guards.lock();
+        guards.retain(|agent_id, guard| {
+            sessions.get(&guard.session_id).is_some_and(|session| {
+                matches!(
+                    session.state,
+                    SessionState::SpawningJudge | SessionState::Judging { .. }
+                ) && session.agents.iter().any(|agent| {
+                    &agent.id == agent_id
+                        && agent.status != AgentStatus::Completed
+                        && pty_manager.is_alive(agent_id)
+                })
+            })
+        });
+        guards
+            .iter()
+            .map(|(agent_id, guard)| {
+                let label = sessions
+                    .get(&guard.session_id)
+                    .and_then(|session| session.agents.iter().find(|a| &a.id == agent_id))
+                    .map_or(agent_id.as_str(), AgentInfo::display_name)
+                    .to_string();
+                (agent_id.clone(), label, guard.clone())
+            })
+            .collect()
+    }
+
+    fn report_read_only_revert(
+        &self,
+        agent_id: &str,
+        label: &str,
+        session_id: &str,
+        files: &str,
+    ) {
+        tracing::warn!("Reverted {}'s edits to {}", agent_id, files);
+        self.log_coordination_system(
+            session_id,
+            &format!(
+                "Reverted {}'s edits to tracked files in the main checkout (judges are \
+                 read-only): {}",
+                label, files
+            ),
+        );
+        let notice = format!(
+            "Hive Manager reverted your edits to {}. Judges must not modify code; put \
+             your findings in your report instead.",
+            files
+        );
+        if let Err(e) = self
+            .pty_manager
+            .read()
+            .write_bracketed(agent_id, format!("{}\r", notice).as_bytes())
+        {
+            tracing::warn!("Failed to tell {} its edits were reverted: {}", agent_id, e);
+        }
+    }
+
+    // --- Worker Checkpoints ---
+
+    /// Snapshot the worktree of every running worker that has not been
+    /// checkpointed within `interval`, so a crashed agent's partial work survives
+    /// under `refs/checkpoints/<session-id>/<worktree>`. Returns how many new
+    /// checkpoints were written.
+    pub fn checkpoint_workers(&self, interval: Duration) -> usize {
+        let targets: Vec<(String, PathBuf)> = {
+            let sessions = self.sessions.read();
+            let pty_manager = self.pty_manager.read();
+            let mut targets: Vec<(String, PathBuf)> = Vec::new();
+            for session in sessions.values() {
+                if is_terminal_session_state(&session.state) {
+                    continue;
+                }
+                for agent in &session.agents {
+                    if !matches!(agent.role, AgentRole::Worker { .. })
+                        || !pty_manager.is_alive(&agent.id)
+                    {
+                        continue;
+                    }
+                    let Some(worktree) =
+                        Self::agent_git_worktree_path_for_artifacts(session, agent)
+                    else {
+                        continue;
+                    };
+                    if !targets.iter().any(|(_, path)| path == &worktree) {
+                        targets.push((session.id.clone(), worktree));
+                    }
+                }
+            }
+            targets
+        };
+
+        self.checkpoint_times
+            .lock()
+            .retain(|path, _| targets.iter().any(|(_, worktree)| worktree == path));
+
+        let mut created = 0;
+        for (session_id, worktree) in targets {
+            {
+                let mut times = self.checkpoint_times.lock();
+                if times
+                    .get(&worktree)
+                    .is_some_and(|last| last.elapsed() < interval)
+                {
+                    continue;
+                }
+                times.insert(worktree.clone(), Instant::now());
+            }
+            if !worktree.exists() {
+                continue;
+            }
+            let worker = worktree
+                .file_name()
+                .map(|name| name.to_string_lossy().to_string())
+                .unwrap_or_else(|| "worktree".to_string());
+            let ref_name = checkpoint::checkpoint_ref(&session_id, &worker);
+            match checkpoint::create_checkpoint(&worktree, &ref_name, &worker) {
+                Ok(Some(commit)) => {
+                    created += 1;
+                    tracing::info!("Checkpointed {} to {} ({})", worker, ref_name, commit);
+                }
+                Ok(None) => {}
+                Err(e) => {
+                    tracing::warn!("Failed to checkpoint {}: {}", worktree.display(), e);
+                }
+            }
+        }
+        created
+    }
+
+    /// Checkpoints recorded for a session's worktrees, newest first per worktree.
+    pub fn list_checkpoints(
+        &self,
+        session_id: &str,
+    ) -> Result<Vec<checkpoint::Checkpoint>, String> {
+        let project_path = self
+            .sessions
+            .read()
+            .get(session_id)
+            .map(|session| session.project_path.clone())
+            .ok_or_else(|| format!("Session not found: {}", session_id))?;
+        checkpoint::list_checkpoints(&project_path, session_id)
+    }
+
+    // --- Diff Budgets ---
+
+    /// Measure each running worker's diff against the commit it started from and
+    /// act on `budget`: past `warn_lines` the worker is told to split its work up,
+    /// past `stop_lines` it is stopped and the Queen told. Each happens once per
+    /// worker. Workers sharing a cell's worktree are skipped, as their changes
+    /// cannot be told apart. Returns how many workers were stopped.
+    pub fn enforce_diff_budgets(&self, budget: &DiffBudgetConfig) -> usize {
+        struct Target {
+            session_id: String,
+            agent_id: String,
+            worktree: PathBuf,
+            base: String,
+            queen_id: Option<String>,
+        }
+
+        let targets: Vec<Target> = {
+            let sessions = self.sessions.read();
+            let pty_manager = self.pty_manager.read();
+            let mut targets = Vec::new();
+            for session in sessions.values() {
+                if is_terminal_session_state(&session.state)
+                    || session.execution_policy.workspace_strategy == WorkspaceStrategy::SharedCell
+                {
+                    continue;
+                }
+                let queen_id = session
+                    .agents
+                    .iter()
+                    .find(|agent| matches!(agent.role, AgentRole::Queen))
+                    .map(|agent| agent.id.clone());
+                for agent in &session.agents {
+                    if !matches!(agent.role, AgentRole::Worker { .. })
+                        || agent.status == AgentStatus::Completed
+                        || !pty_manager.is_alive(&agent.id)
+                    {
+                        continue;
+                    }
+                    let (Some(worktree), Some(base)) = (
+                        Self::agent_git_worktree_path_for_artifacts(session, agent),
+                        agent.base_commit_sha.clone(),
+                    ) else {
+                        continue;
+                    };
+                    targets.push(Target {
+                        session_id: session.id.clone(),
+                        agent_id: agent.id.clone(),
+                        worktree,
+                        base,
+                        queen_id: queen_id.clone(),
+                    });
+                }
+            }
+            targets
+        };
+
+        self.diff_budget_stages
+            .lock()
+            .retain(|agent_id, _| targets.iter().any(|target| &target.agent_id == agent_id));
+
+        let mut stopped = 0;
+        for target in targets {
+            let lines = match lines_changed_since(&target.worktree, &target.base) {
+                Ok(lines) => lines,
+                Err(e) => {
+                    tracing::debug!("Failed to measure the diff of {}: {}", target.agent_id, e);
+                    continue;
+                }
+            };
+            let Some(stage) = DiffBudgetStage::reached(lines, budget) else {
+                continue;
+            };
+            {
+                let mut stages = self.diff_budget_stages.lock();
+                if stages
+                    .get(&target.agent_id)
+                    .is_some_and(|previous| *previous >= stage)
+                {
+                    continue;
+                }
+                stages.insert(target.agent_id.clone(), stage);
+            }
+
+            match stage {
+                DiffBudgetStage::Warned => {
+                    tracing::info!(
+                        "Worker {} in session {} has changed {} lines",
+                        target.agent_id,
+                        target.session_id,
+                        lines
+                    );
+                    self.send_phase_notice(
+                        &[target.agent_id.clone()],
+                        &format!(
+                            "[DIFF] You have changed {} lines since you started, over the \
+                             {}-line budget. Stop widening this change: commit the part that \
+                             stands on its own and ask the Queen to split the rest into \
+                             smaller tasks.",
+                            lines,
+                            budget.warn_lines.unwrap_or(lines)
+                        ),
+                    );
+                }
+                DiffBudgetStage::Stopped => {
+                    tracing::warn!(
+                        "Worker {} in session {} changed {} lines; stopping it",
+                        target.agent_id,
+                        target.session_id,
+                        lines
+                    );
+                    match self.stop_agent(&target.session_id, &target.agent_id) {
+                        Ok(()) => stopped += 1,
+                        Err(e) => {
+                            tracing::warn!("Failed to stop {}: {}", target.agent_id, e);
+                            continue;
+                        }
+                    }
+                    if let Some(queen_id) = &target.queen_id {
+                        self.send_phase_notice(
+                            &[queen_id.clone()],
+                            &format!(
+                                "[DIFF] {} changed {} lines, over the {}-line budget, and was \
+                                 stopped. Review its worktree and reassign the work as smaller \
+                                 tasks.",
+                                target.agent_id,
+                                lines,
+                                budget.stop_lines.unwrap_or(lines)
+                            ),
+                        );
+                    }
+                }
+            }
+
+            if let Some(ref app_handle) = self.app_handle {
+                let _ = app_handle.emit(
+                    "diff-budget-exceeded",
+                    serde_json::json!({
+                        "session_id": target.session_id,
+                        "agent_id": target.agent_id,
+                        "lines": lines,
+                        "stage": stage,
+                    }),
+                );
+            }
+        }
+        stopped
+    }
+
+    // --- File Permissions ---
+
+    /// Check the files each running worker with `allowed_paths` or `denied_paths` has
+    /// changed since it started against those globs (see `path_policy`). Each newly
+    /// detected violation is logged, emitted as `path-violation`, and put to the worker
+    /// and its Queen; the changes themselves are left in place. Workers sharing a cell's
+    /// worktree are skipped, as their changes cannot be told apart. Returns how many
+    /// workers had new violations.
+    pub fn enforce_path_policies(&self) -> usize {
+        struct Target {
+            session_id: String,
+            agent_id: String,
+            label: String,
+            config: AgentConfig,
+            worktree: PathBuf,
+            base: String,
+            queen_id: Option<String>,
+        }
+
+        let targets: Vec<Target> = {
+            let sessions = self.sessions.read();
+            let pty_manager = self.pty_manager.read();
+            let mut targets = Vec::new();
+            for session in sessions.values() {
+                if is_terminal_session_state(&session.state)
+                    || session.execution_policy.workspace_strategy == WorkspaceStrategy::SharedCell
+                {
+                    continue;
+                }
+                let queen_id = session
+                    .agents
+                    .iter()
+                    .find(|agent| matches!(agent.role, AgentRole::Queen))
+                    .map(|agent| agent.id.clone());
+                for agent in &session.agents {
+                    if !matches!(agent.role, AgentRole::Worker { .. })
+                        || !path_policy::is_restricted(&agent.config)
+                        || agent.status == AgentStatus::Completed
+                        || !pty_manager.is_alive(&agent.id)
+                    {
+                        continue;
+                    }
+                    let (Some(worktree), Some(base)) = (
+                        Self::agent_git_worktree_path_for_artifacts(session, agent),
+                        agent.base_commit_sha.clone(),
+                    ) else {
+                        continue;
+                    };
+                    targets.push(Target {
+                        session_id: session.id.clone(),
+                        agent_id: agent.id.clone(),
+                        label: agent.display_name().to_string(),
+                        config: agent.config.clone(),
+                        worktree,
+                        base,
+                        queen_id: queen_id.clone(),
+                    });
+                }
+            }
+            targets
+        };
+
+        self.path_violations
+            .lock()
+            .retain(|agent_id, _| targets.iter().any(|target| &target.agent_id == agent_id));
+
+        let mut violators = 0;
+        for target in targets {
+            let changed = match files_changed_since(&target.worktree, &target.base) {
+                Ok(changed) => changed,
+                Err(e) => {
+                    tracing::debug!("Failed to list the changes of {}: {}", target.agent_id, e);
+                    continue;
+                }
+            };
+            let paths: Vec<String> = {
+                let mut reported = self.path_violations.lock();
+                let reported = reported.entry(target.agent_id.clone()).or_default();
+                path_policy::violations(&target.config, &changed)
+                    .into_iter()
+                    .filter(|path| reported.insert(path.clone()))
+                    .collect()
+            };
+            if paths.is_empty() {
+                continue;
+            }
+            violators += 1;
+
+            let files = paths.join(", ");
+            tracing::warn!(
+                "Worker {} in session {} changed files outside its permissions: {}",
+                target.agent_id,
+                target.session_id,
+                files
+            );
+            self.log_coordination_system(
+                &target.session_id,
+                &format!(
+                    "[PATHS] {} changed files outside its file permissions: {}",
+                    target.label, files
+                ),
+            );
+            self.send_phase_notice(
+                &[target.agent_id.clone()],
+                &format!(
+                    "[PATHS] You changed {}, outside your File Permissions. Undo those changes \
+                     unless the Queen explicitly widens your scope.",
+                    files
+                ),
+            );
+            if let Some(queen_id) = &target.queen_id {
+                self.send_phase_notice(
+                    &[queen_id.clone()],
+                    &format!(
+                        "[PATHS] {} changed {}, outside its file permissions. Confirm the \
+                         change belongs in its scope or have it reverted.",
+                        target.agent_id, files
+                    ),
+                );
+            }
+
+            if let Some(ref app_handle) = self.app_handle {
+                let _ = app_handle.emit(
+                    "path-violation",
+                    PathViolation {
+                        session_id: target.session_id,
+                        agent_id: target.agent_id,
+                        paths,
+                    },
+                );
+            }
+        }
+        violators
+    }
+
+    // --- Project Artifacts ---
+
+    /// List (`dry_run`) or delete the per-session directories under `project_path`'s
+    /// `.hive-manager/`, `.hive-fusion/` and `.hive-debate/` for sessions that have
+    /// finished. Active sessions are never touched; directories no known session owns
+    /// are only removed with `include_unknown`.
+    pub fn cleanup_project_artifacts(
+        &self,
+        project_path: &Path,
+        dry_run: bool,
+        include_unknown: bool,
+    ) -> ArtifactCleanupReport {
+        let mut report = ArtifactCleanupReport {
+            project_path: project_path.to_string_lossy().to_string(),
+            dry_run,
+            missing_gitignore_entries: project_artifacts::missing_gitignore_entries(project_path),
+            ..ArtifactCleanupReport::default()
+        };
+
+        for artifact in project_artifacts::session_artifacts(project_path) {
+            let finished = match self.get_session(&artifact.session_id) {
+                Some(session) => Some(is_terminal_session_state(&session.state)),
+                None => self
+                    .storage
+                    .as_ref()
+                    .and_then(|storage| storage.load_session(&artifact.session_id).ok())
+                    .map(|persisted| {
+                        is_terminal_session_state(&parse_persisted_session_state(&persisted.state))
+                    }),
+            };
+            let skipped = match finished {
+                Some(false) => Some(&mut report.skipped_active),
+                None if !include_unknown => Some(&mut report.skipped_unknown),
+                _ => None,
+            };
+            if let Some(skipped) = skipped {
+                if !skipped.contains(&artifact.session_id) {
+                    skipped.push(artifact.session_id);
+                }
+                continue;
+            }
+
+            if !dry_run {
+                if let Err(e) = project_artifacts::remove_artifact(project_path, &artifact) {
+                    report.errors.push(e);
+                    continue;
+                }
+            }
+            report.freed_bytes += artifact.bytes;
+            report.artifacts.push(artifact);
+        }
+        report
+    }
+
+    // --- Injection Templates ---
+
+    /// Render a named injection template for `target_agent_id` with the session's
+    /// context: `workers_list`, `plan_path`, `inbox_path`, `workspace_path`,
+    /// `agent_id`, `session_id`, `project_path` and the agent's `task`. Caller
+    /// `variables` fill any other placeholders; session values take precedence.
+    pub fn render_injection(
+        &self,
+        session_id: &str,
+        target_agent_id: &str,
+        template: &str,
+        mut variables: HashMap<String, String>,
+    ) -> Result<String, String> {
+        let session = self
+            .get_session(session_id)
+            .ok_or_else(|| format!("Session not found: {}", session_id))?;
+        let target = session
+            .agents
+            .iter()
+            .find(|agent| agent.id == target_agent_id);
+        let session_root = Self::session_root_path(&session.project_path, session_id);
+        let inbox_name = target_agent_id
+            .strip_prefix(&format!("{}-", session_id))
+            .unwrap_or(target_agent_id);
+        let workspace = target
+            .and_then(|agent| Self::agent_git_worktree_path_for_artifacts(&session, agent))
+            .unwrap_or_else(|| session.project_path.clone());
+
+        variables.insert("agent_id".to_string(), target_agent_id.to_string());
+        variables.insert(
+            "inbox_path".to_string(),
+            paths::prompt_path(
+                &session_root
+                    .join("conversations")
+                    .join(format!("{}.md", inbox_name)),
+            ),
+        );
+        variables.insert(
+            "plan_path".to_string(),
+            paths::prompt_path(&session_root.join("plan.md")),
+        );
+        variables.insert("workspace_path".to_string(), paths::prompt_path(&workspace));
+
+        let workers: Vec<WorkerInfo> = session
+            .agents
+            .iter()
+            .filter(|agent| matches!(agent.role, AgentRole::Worker { .. }))
+            .map(|agent| {
+                let role = agent.config.role.as_ref();
+                WorkerInfo {
+                    id: agent.id.clone(),
+                    role_label: role
+                        .map(|role| role.label.clone())
+                        .unwrap_or_else(|| "Worker".to_string()),
+                    role_type: role
+                        .map(|role| role.role_type.clone())
+                        .unwrap_or_else(|| "general".to_string()),
+                    cli: agent.config.cli.clone(),
+                    status: format!("{:?}", agent.status),
+                    current_task: None,
+                }
+            })
+            .collect();
+        let context = PromptContext {
+            session_id: session_id.to_string(),
+            project_path: paths::prompt_path(&session.project_path),
+            task: target.and_then(|agent| agent.config.initial_prompt.clone()),
+            variables,
+        };
+        self.template_engine()
+            .render_injection(template, &workers, &context)
+            .map_err(|e| e.to_string())
+    }
+
+    /// The engine prompts are rendered with: the user's templates directory, in
+    /// the configured `locale`.
+    fn template_engine(&self) -> TemplateEngine {
+        let Some(storage) = self.storage.as_ref() else {
+            return TemplateEngine::default();
+        };
+        let locale = storage.load_config().ok().and_then(|config| config.locale);
+        TemplateEngine::new(storage.templates_dir()).with_locale(locale.as_deref())
+    }
+
+    // --- Durations ---
+
+    /// Fold the session's current phase and agent liveness into its duration ledger.
+    fn record_session_durations(session: &Session) {
+        let session_root = Self::session_root_path(&session.project_path, &session.id);
+        let finished = is_terminal_session_state(&session.state);
+        let agents = session
+            .agents
+            .iter()
+            .filter(|agent| !matches!(agent.role, AgentRole::ScratchShell))
+            .map(|agent| durations::AgentObservation {
+                agent_id: &agent.id,
+                role: format_agent_display(&agent.role),
+                running: !finished
+                    && agent.exit_info.is_none()
+                    && matches!(
+                        agent.status,
+                        AgentStatus::Starting
+                            | AgentStatus::Running
+                            | AgentStatus::Idle
+                            | AgentStatus::WaitingForInput(_)
+                    ),
+            });
+        if let Err(e) = durations::record(
+            &session_root,
+            durations::phase_label(&session.state),
+            agents,
+            Utc::now(),
+        ) {
+            tracing::warn!(
+                "Failed to record durations for session {}: {}",
+                session.id,
+                e
+            );
+        }
+    }
+
+    /// Wall-clock time per phase and per agent, for comparing session types.
+    pub fn get_session_durations(&self, session_id: &str) -> Result<SessionDurations, String> {
+        let session = self
+            .get_session(session_id)
+            .ok_or_else(|| format!("Session not found: {}", session_id))?;
+        Ok(Self::session_durations(&session))
+    }
+
+    /// Time per phase, tasks finished, diff size and learnings of two sessions side
+    /// by side (see [`comparison`]).
+    pub fn compare_sessions(&self, a: &str, b: &str) -> Result<SessionComparison, String> {
+        let stats = |session_id: &str| {
+            self.get_session(session_id)
+                .map(|session| self.session_run_stats(&session))
+                .ok_or_else(|| format!("Session not found: {}", session_id))
+        };
+        Ok(comparison::compare(stats(a)?, stats(b)?))
+    }
+
+    fn session_run_stats(&self, session: &Session) -> SessionRunStats {
+        let durations = Self::session_durations(session);
+        let workers: Vec<&AgentInfo> = session
+            .agents
+            .iter()
+            .filter(|agent| matches!(agent.role, AgentRole::Worker { .. }))
+            .collect();
+
+        // Workers sharing a worktree are measured once.
+        let mut measured = HashSet::new();
+        let diff_lines = workers
+            .iter()
+            .filter_map(|agent| {
+                let worktree = Self::agent_git_worktree_path_for_artifacts(session, agent)?;
+                let base = agent.base_commit_sha.as_deref()?;
+                if !measured.insert((worktree.clone(), base.to_string())) {
+                    return None;
+                }
+                lines_changed_since(&worktree, base).ok()
+            })
+            .reduce(|total, lines| total + lines);
+
+        let learnings = self
+            .storage
+            .as_ref()
+            .and_then(|storage| storage.read_learnings_session(&session.id).ok())
+            .map_or(0, |learnings| learnings.len());
+
+        SessionRunStats {
+            session_id: session.id.clone(),
+            session_type: durations.session_type,
+            state: session.state.clone(),
+            project_path: session.project_path.to_string_lossy().to_string(),
+            total_secs: durations.total_secs,
+            phase_totals: durations.phase_totals,
+            agents: session.agents.len(),
+            tasks_total: workers.len(),
+            tasks_completed: Self::completed_tasks(session).len(),
+            diff_lines,
+            learnings,
+            cost_usd: None,
+        }
+    }
+
+    fn session_type_name(session_type: &SessionType) -> &'static str {
+        match session_type {
+            SessionType::Hive { .. } => "Hive",
+            SessionType::Swarm { .. } => "Swarm",
+            SessionType::Fusion { .. } => "Fusion",
+            SessionType::Debate { .. } => "Debate",
+            SessionType::Solo { .. } => "Solo",
+        }
+    }
+
+    fn session_durations(session: &Session) -> SessionDurations {
+        durations::summarize(
+            &Self::session_root_path(&session.project_path, &session.id),
+            &session.id,
+            Self::session_type_name(&session.session_type),
+            Utc::now(),
+        )
+    }
+
+    pub(crate) fn emit_session_update(&self, session_id: &str) {
+        self.mark_session_unsaved(session_id);
+        let session = {
+            let sessions = self.sessions.read();
+            sessions.get(session_id).cloned()
+        };
+
+        if let (Some(app_handle), Some(session)) = (self.app_handle.as_ref(), session) {
+            let _ = app_handle.emit("session-update", SessionUpdate { session });
+        }
+    }
+
+    fn emit_cell_created(&self, session_id: &str, cell_id: &str) {
+        let Some(emitter) = self.event_emitter.clone() else {
+            return;
+        };
+        let session_id = session_id.to_string();
+        let cell_id = cell_id.to_string();
+        let cell_type = cell_type_for_id(&cell_id).to_string();
+        tokio::spawn(async move {
+            if let Err(error) = emitter
+                .emit_cell_created(&session_id, &cell_id, &cell_type)
+                .await
+            {
+                tracing::debug!("Failed to emit cell created event: {}", error);
+            }
+        });
+    }
+
+    fn emit_agent_launched(&self, session: &Session, agent: &AgentInfo) {
+        let Some(emitter) = self.event_emitter.clone() else {
+            return;
+        };
+        let session_id = session.id.clone();
+        let cell_id = agent_cell_id(session, agent);
+        let agent_id = agent.id.clone();
+        let cli = agent.config.cli.clone();
+        tokio::spawn(async move {
+            if let Err(error) = emitter
                 .emit_agent_launched(&session_id, &cell_id, &agent_id, &cli)
                 .await
             {
@@ -1958,10 +4983,67 @@ impl SessionController {
         new_state: SessionState,
     ) -> Vec<(String, String, String)> {
         let changes = cell_status_changes_for_transition(session, &new_state);
+        let hook_event = hooks::event_for_transition(&session.state, &new_state);
         session.state = new_state;
+        self.mark_session_unsaved(&session.id);
+        if let Some(event) = hook_event {
+            self.run_session_hooks(event, session);
+        }
         changes
     }
 
+    /// Start the hooks configured for `event` (see [`crate::session::hooks`]) on a
+    /// background thread, noting how each one went in the coordination log. Callers
+    /// hold the sessions map, so the config is read on that thread as well.
+    fn run_session_hooks(&self, event: &'static str, session: &Session) {
+        let Some(storage) = self.storage.clone() else {
+            return;
+        };
+        let payload = HookPayload {
+            event: event.to_string(),
+            session_id: session.id.clone(),
+            session_type: Self::session_type_name(&session.session_type).to_string(),
+            state: session.state.clone(),
+            project_path: session.project_path.to_string_lossy().into_owned(),
+            session_dir: Self::session_root_path(&session.project_path, &session.id)
+                .to_string_lossy()
+                .into_owned(),
+            timestamp: Utc::now(),
+        };
+        let app_handle = self.app_handle.clone();
+        std::thread::spawn(move || {
+            let configured = match storage.load_config() {
+                Ok(config) => hooks::matching(&config.hooks, event),
+                Err(e) => {
+                    tracing::warn!("Failed to load hooks for {}: {}", event, e);
+                    return;
+                }
+            };
+            for hook in configured {
+                let line = match hooks::run(&hook, &payload) {
+                    Ok(()) => format!("[HOOK] {} ran {}", event, hook.command),
+                    Err(e) => {
+                        tracing::warn!("Hook {} for {} failed: {}", hook.command, event, e);
+                        format!("[HOOK] {} {} failed: {}", event, hook.command, e)
+                    }
+                };
+                let message = CoordinationMessage::system("LOG", &line);
+                match storage.append_coordination_log(&payload.session_id, &message) {
+                    Ok(()) => {
+                        if let Some(ref app_handle) = app_handle {
+                            let _ = app_handle.emit("coordination-message", &message);
+                        }
+                    }
+                    Err(e) => tracing::warn!(
+                        "Failed to write coordination log for session {}: {}",
+                        payload.session_id,
+                        e
+                    ),
+                }
+            }
+        });
+    }
+
     fn persist_then_emit_session_update(
         &self,
         session_id: &str,
@@ -2112,6 +5194,9 @@ impl SessionController {
     }
 
     pub fn stop_session(&self, id: &str) -> Result<(), String> {
+        if self.is_launch_queued(id) {
+            return self.cancel_queued_session(id);
+        }
         let lifecycle_lock = self.session_lifecycle_lock(id);
         let _lifecycle_guard = lifecycle_lock.lock();
         let session = {
@@ -2164,7 +5249,10 @@ impl SessionController {
 
     pub fn mark_session_completed(&self, session_id: &str) -> Result<(), CompletionError> {
         self.can_complete_session(session_id)?;
+        self.set_session_completed(session_id)
+    }
 
+    fn set_session_completed(&self, session_id: &str) -> Result<(), CompletionError> {
         let previous_state = {
             let mut sessions = self.sessions.write();
             sessions.get_mut(session_id).map(|session| {
@@ -2208,6 +5296,9 @@ impl SessionController {
     }
 
     pub fn close_session(&self, id: &str) -> Result<(), String> {
+        if self.is_launch_queued(id) {
+            return self.cancel_queued_session(id);
+        }
         let lifecycle_lock = self.session_lifecycle_lock(id);
         let _lifecycle_guard = lifecycle_lock.lock();
         let (agent_ids, cleanup_session): (Vec<String>, Session) = {
@@ -2253,7 +5344,18 @@ impl SessionController {
             heartbeats.remove(id);
         }
 
-        if let Err(err) = cleanup_session_worktrees(&cleanup_session) {
+        self.solo_task_queues.lock().remove(id);
+        self.phase_clocks.lock().remove(id);
+        self.review_stages.lock().remove(id);
+        let warm = self.warm_pool.lock().release_session(id);
+        self.kill_warm_processes(warm);
+        self.quarantined_agents.write().remove(id);
+        self.context_pressure.write().remove(id);
+        self.domain_schedules.lock().remove(id);
+        self.auto_complete_clocks.lock().remove(id);
+
+        let fusion_root = Self::recorded_fusion_worktree_root(&cleanup_session);
+        if let Err(err) = cleanup_session_worktrees(&cleanup_session, fusion_root.as_deref()) {
             tracing::warn!("Session {} cleanup had issues: {}", id, err);
         }
 
@@ -2604,6 +5706,13 @@ impl SessionController {
             &config.flags,
         );
 
+        // CLIs defined in `clis/*.toml` carry their own command and flags.
+        if let Some(custom) = custom::get(&config.cli) {
+            let mut args = custom.launch_args(custom.model(effective_model.as_deref()));
+            args.extend(extra_flags);
+            return (custom.command, args);
+        }
+
         // Add CLI-specific flags
         match config.cli.as_str() {
             "claude" => {
@@ -2631,9 +5740,7 @@ impl SessionController {
             }
             "cursor" => {
                 // Cursor Agent via WSL - interactive TUI mode
-                args.push("-d".to_string());
-                args.push("Ubuntu".to_string());
-                args.push("/root/.local/bin/agent".to_string());
+                args.extend(WslTarget::from_env().launch_args());
                 args.push("--force".to_string()); // Auto-approve commands
                                                   // Cursor uses global model setting, no --model flag
             }
@@ -2674,12 +5781,7 @@ impl SessionController {
     /// Add prompt argument to args based on CLI type
     /// Each CLI has different syntax for accepting initial prompts
     fn add_prompt_to_args(cli: &str, args: &mut Vec<String>, prompt_path: &str) {
-        let prompt_path = if Self::cli_runs_under_wsl(cli) {
-            Self::to_wsl_path(prompt_path)
-        } else {
-            prompt_path.to_string()
-        };
-        let prompt_arg = format!("Read {} and execute.", prompt_path);
+        let prompt_arg = Self::prompt_file_instruction_for_cli(cli, prompt_path);
         match cli {
             "claude" | "codex" | "cursor" | "droid" => {
                 // Claude, Codex, Cursor, Droid accept prompt as positional argument
@@ -2695,11 +5797,103 @@ impl SessionController {
                 args.push("--prompt".to_string());
                 args.push(prompt_arg);
             }
-            _ => {
+            _ => match custom::by_command(cli) {
+                Some(custom) => custom.push_prompt(args, prompt_arg),
                 // Default: try positional argument
-                args.push(prompt_arg);
-            }
+                None => args.push(prompt_arg),
+            },
+        }
+    }
+
+    /// The instruction to read `prompt_path`, as `cli` sees the path.
+    fn prompt_file_instruction_for_cli(cli: &str, prompt_path: &str) -> String {
+        // `cli` is the command `build_command` mapped it to, so Cursor arrives as `wsl`.
+        let prompt_path = match PathStyle::for_cli(cli) {
+            PathStyle::Wsl => paths::to_wsl_path(prompt_path),
+            PathStyle::Native => prompt_path.to_string(),
+        };
+        Self::prompt_file_instruction(&prompt_path)
+    }
+
+    fn prompt_file_instruction(prompt_path: &str) -> String {
+        format!("Read {} and execute.", prompt_path)
+    }
+
+    /// The command, arguments, directory and environment `create_session` would get
+    /// for an agent with `config`, worked out the way launches do but without
+    /// spawning anything. With `prompt_path`, the arguments end in the instruction to
+    /// read that prompt file.
+    pub fn preview_agent_command(
+        &self,
+        config: &AgentConfig,
+        cwd: Option<&str>,
+        prompt_path: Option<&str>,
+    ) -> Result<AgentCommandPreview, String> {
+        let (command, mut args) = Self::build_command(config);
+        if let Some(prompt_path) = prompt_path {
+            Self::add_prompt_to_args(&command, &mut args, prompt_path);
+        }
+        let environment = self
+            .pty_manager
+            .read()
+            .configured_cli_env(&command)
+            .map_err(|e| e.to_string())?;
+        Ok(AgentCommandPreview {
+            command_line: crate::pty::shell::command_line(&command, &args),
+            env_keys: crate::storage::secrets::env_keys(&environment.vars)
+                .into_iter()
+                .map(str::to_string)
+                .collect(),
+            path_prepend: environment.path_prepend,
+            env_allowlist: environment.allowlist,
+            cwd: cwd.map(str::to_string),
+            command,
+            args,
+        })
+    }
+
+    /// Inline prompts over a CLI's limit get truncated by the OS or the CLI
+    /// without any error. Write an oversized `prompt` to `filename` in the
+    /// session's prompts directory instead and warn with the measured size.
+    /// Returns the prompt file when the fallback was taken.
+    fn spill_oversized_prompt(
+        &self,
+        project_path: &PathBuf,
+        session_id: &str,
+        agent_id: &str,
+        cli: &str,
+        filename: &str,
+        prompt: &str,
+    ) -> Result<Option<PathBuf>, String> {
+        let size_bytes = prompt.len();
+        let limit_bytes = CliRegistry::max_inline_prompt_bytes(cli);
+        if size_bytes <= limit_bytes {
+            return Ok(None);
         }
+
+        let prompt_file = Self::write_prompt_file(project_path, session_id, filename, prompt)?;
+        tracing::warn!(
+            "Prompt for {} is {} bytes, over the {} byte inline limit for {}; passing {} instead",
+            agent_id,
+            size_bytes,
+            limit_bytes,
+            cli,
+            prompt_file.display()
+        );
+        if let Some(ref app_handle) = self.app_handle {
+            let _ = app_handle.emit(
+                "prompt-size-exceeded",
+                serde_json::json!({
+                    "session_id": session_id,
+                    "agent_id": agent_id,
+                    "cli": cli,
+                    "size_bytes": size_bytes,
+                    "limit_bytes": limit_bytes,
+                    "prompt_file": prompt_file.to_string_lossy(),
+                }),
+            );
+        }
+        Ok(Some(prompt_file))
     }
 
     /// Add an inline task prompt to args based on CLI type (solo mode).
@@ -2718,9 +5912,10 @@ impl SessionController {
             "cursor" | "droid" => {
                 args.push(task.to_string());
             }
-            _ => {
-                args.push(task.to_string());
-            }
+            _ => match custom::get(cli) {
+                Some(custom) => custom.push_prompt(args, task.to_string()),
+                None => args.push(task.to_string()),
+            },
         }
     }
 
@@ -2736,6 +5931,15 @@ impl SessionController {
             &config.flags,
         );
 
+        if let Some(custom) = custom::get(&config.cli) {
+            let mut args = custom.launch_args(custom.model(effective_model.as_deref()));
+            if let Some(task) = task {
+                custom.push_prompt(&mut args, task.to_string());
+            }
+            args.extend(extra_flags);
+            return (custom.command, args);
+        }
+
         // Add CLI-specific auto-approve flags (matching build_command for hive/swarm modes)
         match config.cli.as_str() {
             "claude" => {
@@ -2766,9 +5970,7 @@ impl SessionController {
                 }
             }
             "cursor" => {
-                args.push("-d".to_string());
-                args.push("Ubuntu".to_string());
-                args.push("/root/.local/bin/agent".to_string());
+                args.extend(WslTarget::from_env().launch_args());
                 args.push("--force".to_string());
             }
             "droid" => {
@@ -2812,9 +6014,9 @@ impl SessionController {
             &session_root,
             "the Solo implementation and its focused validation",
         );
-        let qa_verdict = Self::prompt_path(&session_root.join("peer").join("qa-verdict.json"));
+        let qa_verdict = paths::prompt_path(&session_root.join("peer").join("qa-verdict.json"));
         let prince_verdict =
-            Self::prompt_path(&session_root.join("peer").join("prince-verdict.json"));
+            paths::prompt_path(&session_root.join("peer").join("prince-verdict.json"));
         let qa_blocked_pattern = Self::qa_blocked_verdict_grep_pattern();
         let objective = task.unwrap_or("Complete the operator's bounded Solo assignment.");
 
@@ -2881,7 +6083,9 @@ cat "{prince_verdict}"
         }
 
         let mut cmd = Command::new("git");
-        cmd.args(args).current_dir(project_path);
+        cmd.args(args)
+            .current_dir(project_path)
+            .env("GIT_TERMINAL_PROMPT", "0");
 
         #[cfg(windows)]
         {
@@ -2890,8 +6094,7 @@ cat "{prince_verdict}"
             cmd.creation_flags(CREATE_NO_WINDOW);
         }
 
-        let output = cmd
-            .output()
+        let output = output_with_timeout(&mut cmd, GIT_COMMAND_TIMEOUT)
             .map_err(|e| format!("Failed to run git {:?}: {}", args, e))?;
 
         if !output.status.success() {
@@ -2961,6 +6164,44 @@ cat "{prince_verdict}"
         format!("{}-debate-{}-r{}", session_id, debater_index, round)
     }
 
+    /// Where a new Fusion session's variant worktrees go, per the
+    /// `fusion_worktrees` config. Without storage (tests) they stay inside the project.
+    fn fusion_worktree_root(&self, project_path: &Path, session_id: &str) -> PathBuf {
+        let Some(storage) = self.storage.as_ref() else {
+            return project_path.join(".hive-fusion").join(session_id);
+        };
+        let config = storage
+            .load_config()
+            .map(|config| config.fusion_worktrees)
+            .unwrap_or_default();
+        let base = match (config.dir.as_deref().map(str::trim), config.location) {
+            (Some(dir), _) if !dir.is_empty() => PathBuf::from(expand_tilde(dir)),
+            (_, FusionWorktreeLocation::Project) => project_path.join(".hive-fusion"),
+            (_, FusionWorktreeLocation::Sibling) => {
+                let name = project_path
+                    .file_name()
+                    .map(|name| name.to_string_lossy().to_string())
+                    .unwrap_or_else(|| "project".to_string());
+                project_path
+                    .parent()
+                    .unwrap_or(project_path)
+                    .join(format!("{}.hive-fusion", name))
+            }
+            (_, FusionWorktreeLocation::AppData) => storage.base_dir().join("fusion-worktrees"),
+        };
+        base.join(session_id)
+    }
+
+    /// The variant worktree directory recorded for a Fusion session.
+    fn recorded_fusion_worktree_root(session: &Session) -> Option<PathBuf> {
+        if !matches!(session.session_type, SessionType::Fusion { .. }) {
+            return None;
+        }
+        Self::read_fusion_metadata(&session.project_path, &session.id)
+            .ok()
+            .map(|metadata| metadata.worktree_root(&session.project_path, &session.id))
+    }
+
     fn fusion_metadata_path(project_path: &PathBuf, session_id: &str) -> PathBuf {
         project_path
             .join(".hive-manager")
@@ -3187,6 +6428,143 @@ Last updated: {timestamp}
         Self::task_file_path_for_worker(&worktree_path, worker_index)
     }
 
+    /// Hand the Queen's TASK message for `worker_id` to the worker through its task
+    /// file: the status becomes ACTIVE and the message is appended as the
+    /// assignment. Returns the task file, or `None` when `worker_id` is not one of the
+    /// session's numbered workers.
+    pub fn activate_worker_task(
+        &self,
+        session_id: &str,
+        worker_id: &str,
+        task: &str,
+    ) -> Result<Option<PathBuf>, String> {
+        let session = self
+            .get_session(session_id)
+            .ok_or_else(|| format!("Session {} not found", session_id))?;
+        let is_worker = session.agents.iter().any(|agent| {
+            agent.id == worker_id && matches!(agent.role, AgentRole::Worker { .. })
+        });
+        let index = worker_id
+            .strip_prefix(&format!("{}-worker-", session_id))
+            .and_then(|index| index.parse::<usize>().ok());
+        let (true, Some(index)) = (is_worker, index) else {
+            return Ok(None);
+        };
+
+        let task_path = Self::task_file_path_for_session_worker(&session, index)?;
+        let existing = std::fs::read_to_string(&task_path).unwrap_or_default();
+        if let Some(parent) = task_path.parent() {
+            std::fs::create_dir_all(parent)
+                .map_err(|e| format!("Failed to create tasks directory: {}", e))?;
+        }
+        std::fs::write(
+            &task_path,
+            Self::activated_task_content(&existing, task, Utc::now()),
+        )
+        .map_err(|e| format!("Failed to write task file {}: {}", task_path.display(), e))?;
+        Ok(Some(task_path))
+    }
+
+    /// `existing` with its status line set to ACTIVE (or one added at the top) and
+    /// `task` appended under a timestamped assignment heading.
+    fn activated_task_content(existing: &str, task: &str, at: DateTime<Utc>) -> String {
+        let mut content = Self::with_task_status(existing, "ACTIVE");
+        content.push_str(&format!(
+            "\n\n## Queen Assignment ({})\n\n{}\n",
+            at.format("%Y-%m-%dT%H:%M:%SZ"),
+            task.trim()
+        ));
+        content
+    }
+
+    /// `existing` with its first status line set to `status`, or one added at the top.
+    fn with_task_status(existing: &str, status: &str) -> String {
+        let mut status_set = false;
+        let mut lines: Vec<String> = existing
+            .lines()
+            .map(|line| {
+                if !status_set {
+                    let trimmed = line.trim();
+                    if trimmed.starts_with("## Status:") {
+                        status_set = true;
+                        return format!("## Status: {}", status);
+                    }
+                    if trimmed.starts_with("**Status**:") {
+                        status_set = true;
+                        return format!("**Status**: {}", status);
+                    }
+                }
+                line.to_string()
+            })
+            .collect();
+        if !status_set {
+            lines.splice(0..0, [format!("## Status: {}", status), String::new()]);
+        }
+        lines.join("\n").trim_end().to_string()
+    }
+
+    /// A worker's own report that its task is COMPLETED or BLOCKED, for CLIs that
+    /// edit files unreliably. The task file gets the status and `summary` (as its
+    /// result or its blocker) in a single rename, so the file watcher only ever sees
+    /// the finished file and raises its usual events; the report is also logged for
+    /// the Queen. Returns the task file, or `None` when `worker_index` is not one of
+    /// the session's workers.
+    pub fn report_worker_task_status(
+        &self,
+        session_id: &str,
+        worker_index: u8,
+        status: &str,
+        summary: &str,
+    ) -> Result<Option<PathBuf>, String> {
+        let session = self
+            .get_session(session_id)
+            .ok_or_else(|| format!("Session {} not found", session_id))?;
+        let agent_id = format!("{}-worker-{}", session_id, worker_index);
+        let is_worker = session.agents.iter().any(|agent| {
+            agent.id == agent_id && matches!(agent.role, AgentRole::Worker { .. })
+        });
+        if !is_worker {
+            return Ok(None);
+        }
+
+        let task_path = Self::task_file_path_for_session_worker(&session, worker_index.into())?;
+        let existing = std::fs::read_to_string(&task_path)
+            .map_err(|e| format!("Failed to read task file {}: {}", task_path.display(), e))?;
+        let staged = task_path.with_extension("md.tmp");
+        std::fs::write(
+            &staged,
+            Self::reported_task_content(&existing, status, summary, Utc::now()),
+        )
+        .and_then(|()| std::fs::rename(&staged, &task_path))
+        .map_err(|e| format!("Failed to write task file {}: {}", task_path.display(), e))?;
+
+        let name = Self::heartbeat_agent_name(Some(&session), session_id, &agent_id);
+        self.log_coordination_system(
+            session_id,
+            &format!("{} reported its task {}: {}", name, status, summary.trim()),
+        );
+        Ok(Some(task_path))
+    }
+
+    /// `existing` with its status set to `status` and `summary` appended under a
+    /// timestamped `Result` heading, or `Blocker` for a BLOCKED task.
+    fn reported_task_content(
+        existing: &str,
+        status: &str,
+        summary: &str,
+        at: DateTime<Utc>,
+    ) -> String {
+        let heading = if status == "BLOCKED" { "Blocker" } else { "Result" };
+        let mut content = Self::with_task_status(existing, status);
+        content.push_str(&format!(
+            "\n\n## {} ({})\n\n{}\n",
+            heading,
+            at.format("%Y-%m-%dT%H:%M:%SZ"),
+            summary.trim()
+        ));
+        content
+    }
+
     pub(crate) fn task_file_path_for_session_worker(
         session: &Session,
         worker_index: usize,
@@ -3312,9 +6690,12 @@ Read {task_file}. Begin work only when Status is ACTIVE.{polling_instructions}
         )
     }
 
+    /// `contexts` holds each variant's context pack, in the order of `variants`; a
+    /// variant without one is judged from `git diff` instead.
     fn build_fusion_judge_prompt(
         session_id: &str,
         variants: &[FusionVariantMetadata],
+        contexts: &[Option<judge_context::VariantContext>],
         decision_file: &str,
     ) -> String {
         let variant_list = variants
@@ -3325,7 +6706,20 @@ Read {task_file}. Begin work only when Status is ACTIVE.{polling_instructions}
 
         let diff_commands = variants
             .iter()
-            .map(|v| format!("git diff fusion/{session_id}/base..{}", v.branch))
+            .zip(contexts)
+            .map(|(v, context)| match context {
+                Some(context) => format!(
+                    "- {}: read the diff `{}` and the summary (commits, changed files, test \
+                     results) `{}`",
+                    v.name,
+                    paths::prompt_path(&context.diff_file),
+                    paths::prompt_path(&context.summary_file)
+                ),
+                None => format!(
+                    "- {}: run `git diff fusion/{session_id}/base..{}`",
+                    v.name, v.branch
+                ),
+            })
             .collect::<Vec<_>>()
             .join("\n");
 
@@ -3336,7 +6730,8 @@ Read {task_file}. Begin work only when Status is ACTIVE.{polling_instructions}
 {variant_list}
 
 ## Evaluation Process
-1. For each variant, run:
+1. For each variant, review its changes. Context packs were prepared from each branch, so
+   there is no need to run git for variants that have one:
 {diff_commands}
 2. Review code quality, correctness, test coverage, and pattern adherence
 3. Write comparison report to: {decision_file}
@@ -3349,6 +6744,9 @@ Read {task_file}. Begin work only when Status is ACTIVE.{polling_instructions}
 # Evaluation Report
 ## Variant Comparison
 | Criterion | Variant A | Variant B | Notes |
+## Scores
+Score: [variant name]: [0-10]
+(one line per variant, for the overall rubric score)
 ## Recommendation
 Winner: [variant name]
 Rationale: [explanation]
@@ -3366,9 +6764,11 @@ curl -s "http://localhost:18800/api/sessions/{session_id}/learnings"
 ```bash
 curl -s -X POST "http://localhost:18800/api/sessions/{session_id}/learnings" \
   -H "Content-Type: application/json" \
-  -d '{{"content": "YOUR LEARNING HERE", "category": "CATEGORY", "source": "fusion-judge"}}'
+  -d '{{"content": "YOUR LEARNING HERE", "category": "CATEGORY", "confidence": 0.8, "source": "fusion-judge"}}'
 ```
 
+`confidence` (0.0-1.0) is optional. A 400 response names any missing fields.
+
 ### What to capture:
 - **Which variant won and why** (category: "architecture")
 - **Code quality patterns** observed — good and bad (category: "code-quality")
@@ -3402,7 +6802,7 @@ curl -s -X POST "http://localhost:18800/api/sessions/{session_id}/learnings" \
             .stance
             .as_deref()
             .unwrap_or("No explicit stance provided");
-        let argument_file = Self::prompt_path(argument_file);
+        let argument_file = paths::prompt_path(argument_file);
         let content = format!(
             r#"# Task Assignment - Debate Debater {debater_index} ({debater_name}) Round {round}
 
@@ -3492,6 +6892,7 @@ Last updated: {timestamp}
 
     #[allow(clippy::too_many_arguments)]
     fn build_debate_debater_prompt(
+        engine: &TemplateEngine,
         session_id: &str,
         debater: &DebateDebaterMetadata,
         topic: &str,
@@ -3529,20 +6930,19 @@ Last updated: {timestamp}
         variables.insert("branch".to_string(), debater.branch.clone());
         variables.insert(
             "argument_file".to_string(),
-            Self::prompt_path(argument_file),
+            paths::prompt_path(argument_file),
         );
         variables.insert(
             "previous_round_dir".to_string(),
             previous_round_dir
-                .map(Self::prompt_path)
+                .map(paths::prompt_path)
                 .unwrap_or_else(|| "(none; this is the opening round)".to_string()),
         );
         variables.insert("opponent_files".to_string(), opponent_files.to_string());
-        variables.insert("task_file".to_string(), Self::prompt_path(task_file));
+        variables.insert("task_file".to_string(), paths::prompt_path(task_file));
         // The debater's own CLI executes this prompt, so it decides the wiki path form.
         Self::insert_wiki_path_variables(&mut variables, global_wiki_path, &debater.config.cli);
 
-        let engine = TemplateEngine::default();
         let context = PromptContext {
             session_id: session_id.to_string(),
             project_path: debater.worktree_path.clone(),
@@ -3563,6 +6963,7 @@ Last updated: {timestamp}
     /// session-default fallback for a blank `metadata.judge_config.cli`), because it
     /// decides how the wiki path must be spelled in the prompt's shell blocks.
     fn build_debate_judge_prompt(
+        engine: &TemplateEngine,
         session_id: &str,
         metadata: &DebateSessionMetadata,
         global_wiki_path: &str,
@@ -3612,7 +7013,6 @@ Last updated: {timestamp}
             .join("\n");
         variables.insert("round_files".to_string(), round_files);
 
-        let engine = TemplateEngine::default();
         let context = PromptContext {
             session_id: session_id.to_string(),
             task: Some(metadata.topic.clone()),
@@ -3630,21 +7030,6 @@ Last updated: {timestamp}
             })
     }
 
-    fn prompt_path(path: &Path) -> String {
-        path.to_string_lossy().replace('\\', "/")
-    }
-
-    /// Does `cli` execute its prompt inside WSL rather than on the Windows host?
-    ///
-    /// `build_command` maps `cli == "cursor"` to the `wsl` executable, and call sites
-    /// pass the *remapped* command name (`&cmd`) to `add_prompt_to_args`, so both
-    /// spellings must answer yes. Centralized so the "runs under WSL" set is defined
-    /// once instead of being re-`matches!`-ed at every site that needs to translate a
-    /// host path (the divergence class behind #159 and #169).
-    fn cli_runs_under_wsl(cli: &str) -> bool {
-        matches!(cli.trim(), "cursor" | "wsl")
-    }
-
     /// Normalize a configured global wiki path for embedding in the **quoted shell
     /// commands** of a rendered prompt, for the CLI that will actually execute it.
     ///
@@ -3657,7 +7042,7 @@ Last updated: {timestamp}
     /// It genuinely breaks under WSL: neither `C:\Users\...` **nor** `C:/Users/...`
     /// resolves there, only `/mnt/c/Users/...`. A separator swap alone would therefore
     /// look fixed while leaving the one adapter that needs real translation still broken,
-    /// so WSL-backed CLIs are routed through [`Self::to_wsl_path`] — the same translation
+    /// so WSL-backed CLIs are routed through [`PathStyle::Wsl`] — the same translation
     /// `add_prompt_to_args` already applies to the prompt file path for cursor.
     ///
     /// A blank path is returned unchanged so the `{{#if has_global_wiki}}` gates and the
@@ -3666,28 +7051,7 @@ Last updated: {timestamp}
         if global_wiki_path.trim().is_empty() {
             return global_wiki_path.to_string();
         }
-        if Self::cli_runs_under_wsl(cli) {
-            Self::to_wsl_path(global_wiki_path)
-        } else {
-            global_wiki_path.replace('\\', "/")
-        }
-    }
-
-    fn to_wsl_path(path: &str) -> String {
-        let forward_slash_path = path.replace('\\', "/");
-        let bytes = forward_slash_path.as_bytes();
-
-        if bytes.len() >= 2 && bytes[0].is_ascii_alphabetic() && bytes[1] == b':' {
-            let drive = bytes[0].to_ascii_lowercase() as char;
-            let rest = forward_slash_path[2..].trim_start_matches('/');
-            if rest.is_empty() {
-                format!("/mnt/{drive}")
-            } else {
-                format!("/mnt/{drive}/{rest}")
-            }
-        } else {
-            forward_slash_path
-        }
+        PathStyle::for_cli(cli).render_str(global_wiki_path)
     }
 
     fn worktree_boundary_rules(worktree_path: &str) -> String {
@@ -3722,7 +7086,7 @@ Last updated: {timestamp}
 
     fn queen_required_protocol(session_root: &Path, has_evaluator: bool) -> String {
         let mark_worker_status_path =
-            Self::prompt_path(&session_root.join("tools").join("mark-worker-status.md"));
+            paths::prompt_path(&session_root.join("tools").join("mark-worker-status.md"));
         if !has_evaluator {
             return format!(
                 r#"## Required Protocol
@@ -3736,8 +7100,9 @@ Last updated: {timestamp}
         }
 
         let milestone_ready_path =
-            Self::prompt_path(&session_root.join("peer").join("milestone-ready.json"));
-        let qa_verdict_path = Self::prompt_path(&session_root.join("peer").join("qa-verdict.json"));
+            paths::prompt_path(&session_root.join("peer").join("milestone-ready.json"));
+        let qa_verdict_path =
+            paths::prompt_path(&session_root.join("peer").join("qa-verdict.json"));
 
         format!(
             r#"## Required Protocol
@@ -3790,10 +7155,11 @@ Last updated: {timestamp}
         has_evaluator: bool,
     ) -> String {
         let milestone_ready_path =
-            Self::prompt_path(&session_root.join("peer").join("milestone-ready.json"));
-        let qa_verdict_path = Self::prompt_path(&session_root.join("peer").join("qa-verdict.json"));
+            paths::prompt_path(&session_root.join("peer").join("milestone-ready.json"));
+        let qa_verdict_path =
+            paths::prompt_path(&session_root.join("peer").join("qa-verdict.json"));
         let prince_verdict_path =
-            Self::prompt_path(&session_root.join("peer").join("prince-verdict.json"));
+            paths::prompt_path(&session_root.join("peer").join("prince-verdict.json"));
 
         if !has_evaluator {
             return format!(
@@ -3990,6 +7356,7 @@ Hard rule: The Evaluator AND the Prince are created PROGRAMMATICALLY by the back
 
     #[allow(dead_code)]
     fn build_evaluator_prompt(
+        engine: &TemplateEngine,
         session_id: &str,
         config: &AgentConfig,
         qa_workers: &[QaWorkerConfig],
@@ -4089,11 +7456,12 @@ Hard rule: The Evaluator AND the Prince are created PROGRAMMATICALLY by the back
             );
         }
 
-        Self::render_named_prompt("roles/evaluator", session_id, None, variables)
+        Self::render_named_prompt(engine, "roles/evaluator", session_id, None, variables)
     }
 
     #[allow(dead_code)]
     fn build_prince_prompt(
+        engine: &TemplateEngine,
         session_id: &str,
         config: &AgentConfig,
         principal_defaults: &AgentConfig,
@@ -4184,11 +7552,12 @@ Hard rule: The Evaluator AND the Prince are created PROGRAMMATICALLY by the back
             active_secs.as_secs().to_string(),
         );
 
-        Self::render_named_prompt("roles/prince", session_id, None, variables)
+        Self::render_named_prompt(engine, "roles/prince", session_id, None, variables)
     }
 
     #[allow(dead_code)]
     fn build_qa_worker_prompt(
+        engine: &TemplateEngine,
         session_id: &str,
         index: u8,
         specialization: &str,
@@ -4253,7 +7622,7 @@ Hard rule: The Evaluator AND the Prince are created PROGRAMMATICALLY by the back
 
         auth.apply_prompt_variables(session_id, &mut variables);
 
-        Self::render_named_prompt(template_name, session_id, None, variables)
+        Self::render_named_prompt(engine, template_name, session_id, None, variables)
     }
 
     fn qa_worker_label(specialization: &str) -> &'static str {
@@ -4267,12 +7636,12 @@ Hard rule: The Evaluator AND the Prince are created PROGRAMMATICALLY by the back
     }
 
     fn render_named_prompt(
+        engine: &TemplateEngine,
         template_name: &str,
         session_id: &str,
         task: Option<String>,
         variables: HashMap<String, String>,
     ) -> String {
-        let engine = TemplateEngine::default();
         let context = PromptContext {
             session_id: session_id.to_string(),
             task,
@@ -4574,7 +7943,7 @@ Do not run the debate. Stop after writing the plan.
         };
         let task_file_glob = variants
             .iter()
-            .map(|variant| format!("\"{}\"", Self::prompt_path(Path::new(&variant.task_file))))
+            .map(|variant| format!("\"{}\"", paths::prompt_path(Path::new(&variant.task_file))))
             .collect::<Vec<_>>()
             .join(" ");
 
@@ -4693,13 +8062,14 @@ Read tool docs in `.hive-manager/{session_id}/tools/` for:
         session_root: &Path,
         completion_scope: &str,
     ) -> String {
-        let peer_dir = Self::prompt_path(&session_root.join("peer"));
+        let peer_dir = paths::prompt_path(&session_root.join("peer"));
         let milestone_ready_path =
-            Self::prompt_path(&session_root.join("peer").join("milestone-ready.json"));
-        let qa_verdict_path = Self::prompt_path(&session_root.join("peer").join("qa-verdict.json"));
-        let contracts_dir = Self::prompt_path(&session_root.join("contracts"));
+            paths::prompt_path(&session_root.join("peer").join("milestone-ready.json"));
+        let qa_verdict_path =
+            paths::prompt_path(&session_root.join("peer").join("qa-verdict.json"));
+        let contracts_dir = paths::prompt_path(&session_root.join("contracts"));
         let contract_path =
-            Self::prompt_path(&session_root.join("contracts").join("milestone-1.md"));
+            paths::prompt_path(&session_root.join("contracts").join("milestone-1.md"));
 
         format!(
             r#"## QA Milestone Handoff (CRITICAL — Evaluator waits for this)
@@ -4771,8 +8141,8 @@ When ALL {completion_scope} have completed, you MUST signal the existing Evaluat
             user_prompt.trim()
         };
         let plan_path =
-            Self::prompt_path(&Self::session_root_path(project_path, session_id).join("plan.md"));
-        let planner_workspace_path = Self::prompt_path(planner_workspace_path);
+            paths::prompt_path(&Self::session_root_path(project_path, session_id).join("plan.md"));
+        let planner_workspace_path = paths::prompt_path(planner_workspace_path);
         let deliverables = [
             plan_path.as_str(),
             "One build-ready execution contract organized by coherent workstreams",
@@ -4805,11 +8175,14 @@ When ALL {completion_scope} have completed, you MUST signal the existing Evaluat
         };
         let mut principal_roster = String::new();
         for (index, principal) in workers.iter().enumerate() {
-            let label = principal
-                .role
-                .as_ref()
-                .map(|role| role.label.as_str())
-                .unwrap_or("Coding Principal");
+            let label = Self::principal_title(
+                principal,
+                principal
+                    .role
+                    .as_ref()
+                    .map(|role| role.label.as_str())
+                    .unwrap_or("Coding Principal"),
+            );
             let model = principal.model.as_deref().unwrap_or("harness default");
             let flags =
                 serde_json::to_string(&principal.flags).unwrap_or_else(|_| "[]".to_string());
@@ -5125,7 +8498,8 @@ Write to `.hive-manager/{session_id}/plan.md`:
 |------|--------|----------|----------------|
 
 ## Cross-Domain Dependencies
-[Note any dependencies between domains]
+- Domain N depends on Domain M
+[One line per dependency, or "None" when the domains are independent. Independent domains run in parallel; Hive Manager holds a dependent domain back until its prerequisites are complete.]
 
 ## Risks
 [List potential risks and mitigation strategies]
@@ -5620,6 +8994,7 @@ This tests that:
     /// `session_id`, `api_base_url`, `workers_list`, `queen_heartbeat_snippet`,
     /// and `task`. Caller extras win on key collision.
     fn build_templated_queen_prompt(
+        engine: &TemplateEngine,
         template_name: &str,
         session_id: &str,
         workers: &[AgentConfig],
@@ -5676,6 +9051,7 @@ This tests that:
         }
 
         Self::render_named_prompt(
+            engine,
             template_name,
             session_id,
             user_prompt.map(|s| s.to_string()),
@@ -5688,6 +9064,7 @@ This tests that:
         project_path: &Path,
         queen_workspace_path: &Path,
         session_id: &str,
+        session_branch: &str,
         workers: &[AgentConfig],
         user_prompt: Option<&str>,
         has_plan: bool,
@@ -5712,14 +9089,14 @@ This tests that:
             render_workspace_contract(role, &execution_policy.workspace_strategy);
 
         let session_root = Self::session_root_path(project_path, session_id);
-        let plan_path = Self::prompt_path(&session_root.join("plan.md"));
-        let tools_dir = Self::prompt_path(&session_root.join("tools"));
-        let coordination_log_path = Self::prompt_path(&session_root.join("coordination.log"));
-        let queen_workspace = Self::prompt_path(queen_workspace_path);
+        let plan_path = paths::prompt_path(&session_root.join("plan.md"));
+        let tools_dir = paths::prompt_path(&session_root.join("tools"));
+        let coordination_log_path = paths::prompt_path(&session_root.join("coordination.log"));
+        let queen_workspace = paths::prompt_path(queen_workspace_path);
         let queen_conversation =
-            Self::prompt_path(&session_root.join("conversations").join("queen.md"));
+            paths::prompt_path(&session_root.join("conversations").join("queen.md"));
         let shared_conversation =
-            Self::prompt_path(&session_root.join("conversations").join("shared.md"));
+            paths::prompt_path(&session_root.join("conversations").join("shared.md"));
 
         let objective = user_prompt
             .map(str::trim)
@@ -5772,11 +9149,14 @@ This tests that:
         for (offset, principal) in workers.iter().enumerate() {
             let index = offset + 1;
             let principal_id = format!("{session_id}-worker-{index}");
-            let label = principal
-                .role
-                .as_ref()
-                .map(|worker_role| worker_role.label.as_str())
-                .unwrap_or("Coding Principal");
+            let label = Self::principal_title(
+                principal,
+                principal
+                    .role
+                    .as_ref()
+                    .map(|worker_role| worker_role.label.as_str())
+                    .unwrap_or("Coding Principal"),
+            );
             let model = principal.model.as_deref().unwrap_or("harness default");
             let flags =
                 serde_json::to_string(&principal.flags).unwrap_or_else(|_| "[]".to_string());
@@ -5799,8 +9179,8 @@ This tests that:
                     .join(format!("worker-{index}")),
                 WorkspaceStrategy::None => project_path.to_path_buf(),
             };
-            let principal_workspace = Self::prompt_path(&principal_workspace);
-            let task_file = Self::prompt_path(
+            let principal_workspace = paths::prompt_path(&principal_workspace);
+            let task_file = paths::prompt_path(
                 &PathBuf::from(&principal_workspace)
                     .join(".hive-manager")
                     .join("tasks")
@@ -5819,10 +9199,10 @@ This tests that:
 
         let topology_instructions = match execution_policy.workspace_strategy {
             WorkspaceStrategy::SharedCell => format!(
-                "## Shared Cell Integration\n\nThe Queen and managed principals run in the same backend-created worktree at {queen_workspace}. Assign explicit, non-overlapping paths and serialize shared files. Principal edits are immediately visible. Principals do not commit. Review the combined diff, run integration validation, then commit from the current backend-created hive/{session_id}/primary branch. Do not create, rename, or switch branches."
+                "## Shared Cell Integration\n\nThe Queen and managed principals run in the same backend-created worktree at {queen_workspace}. Assign explicit, non-overlapping paths and serialize shared files. Principal edits are immediately visible. Principals do not commit. Review the combined diff, run integration validation, then commit from the current backend-created {session_branch} branch. Do not create, rename, or switch branches."
             ),
             WorkspaceStrategy::IsolatedCell => format!(
-                "## Isolated Cell Integration\n\nThe Queen runs at {queen_workspace}. Each principal owns the workspace and task path in the roster and commits only its completed assignment on its backend-created hive/{session_id}/worker-N branch. Inspect and validate each commit, then integrate it into the current backend-created Queen branch in dependency order. Resolve conflicts centrally. Do not create, rename, or switch managed branches."
+                "## Isolated Cell Integration\n\nThe Queen runs at {queen_workspace}. Each principal owns the workspace and task path in the roster and commits only its completed assignment on its backend-created hive/{session_id}/worker-N branch. Inspect and validate each commit, then integrate it into the current backend-created Queen branch {session_branch} in dependency order. Resolve conflicts centrally. Do not create, rename, or switch managed branches."
             ),
             WorkspaceStrategy::None => format!(
                 "## Current Checkout Coordination\n\nAgents run in the operator checkout rooted at {queen_workspace}. Preserve operator changes. Do not create, switch, commit, or push branches without explicit operator authorization."
@@ -5885,7 +9265,7 @@ Managed principals are visible Hive agents with their own lifecycle and task con
 1. Read the plan, project DNA, learnings, and current repository state.
 2. Partition work by coherent ownership and dependencies, not by roster size.
 3. Use the existing roster or POST /api/sessions/{session_id}/workers when a new visible principal is genuinely needed. Preserve that principal's exact harness, model, and flags array from the roster; do not drop effort or reasoning settings. Never launch unmanaged external CLI subprocesses.
-4. Activate a principal by writing a precise objective, owned paths, authoritative inputs, deliverables, validation, and stop conditions to its task file, then set Status to ACTIVE.
+4. Activate a principal by writing a precise objective, owned paths, authoritative inputs, deliverables, validation, and stop conditions to its task file, then set Status to ACTIVE. Sending the same assignment as a TASK through POST /api/sessions/{session_id}/inject/queen (`queen_id`, `target_worker_id`, `message`) does both for you: the backend appends the message to the task file and sets it ACTIVE.
 5. Monitor heartbeats and the Queen/shared conversations. Review every principal result and evidence before integration.
 6. Keep native Queen children read-only for planning, scouting, and review. Delegate implementation to managed principals.
 7. The Queen coordinates and integrates; do not become a coding principal.
@@ -5895,6 +9275,8 @@ Heartbeat while coordinating:
 
 {topology_instructions}
 
+{git_operations}
+
 ## Learning Curation
 
 Workers submit durable learnings through POST /api/sessions/{session_id}/learnings. Review GET /api/sessions/{session_id}/learnings and GET /api/sessions/{session_id}/project-dna after major phases and before the final PR. Curate durable conventions, decisions, failures, and architectural facts; remove duplicates and stale records.
@@ -5928,6 +9310,7 @@ When the objective and every configured gate are complete, send an idle heartbea
             principal_roster = principal_roster.trim_end(),
             queen_heartbeat = queen_heartbeat,
             topology_instructions = topology_instructions,
+            git_operations = git_operations_block(session_id),
             qa_milestone_handoff = qa_milestone_handoff,
             post_workers_protocol = post_workers_protocol,
             coordination_log_path = coordination_log_path,
@@ -5935,7 +9318,57 @@ When the objective and every configured gate are complete, send an idle heartbea
             objective = objective,
         )
     }
-    /// Build a worker's role prompt
+    /// The plan tasks and files for worker `index` of a running session, from its
+    /// `plan.md`. `None` when there is no plan or nothing in it for the worker.
+    fn worker_context_pack(session: &Session, index: u8, config: &AgentConfig) -> Option<String> {
+        let plan_path = Self::session_root_path(&session.project_path, &session.id).join("plan.md");
+        let plan = plan_parser::parse(&std::fs::read_to_string(&plan_path).ok()?);
+        let mut names = vec![format!("worker-{index}"), format!("principal {index}")];
+        names.extend(Self::custom_label(config).map(str::to_string));
+        context_pack::render(&plan, &names, &paths::prompt_path(&plan_path))
+    }
+
+    /// The project DNA to embed in a worker's prompt: the session's curated DNA,
+    /// else the project's `.ai-docs/project-dna.md`, cut to the configured size.
+    /// `None` when embedding is turned off or there is no DNA yet.
+    fn worker_project_dna(&self, session_id: &str, project_path: &Path) -> Option<String> {
+        let storage = self.storage.as_ref()?;
+        let config = storage
+            .load_config()
+            .map(|config| config.project_dna)
+            .unwrap_or_default();
+        if !config.embed_in_worker_prompts {
+            return None;
+        }
+        let dna = storage
+            .read_project_dna_session(session_id)
+            .ok()
+            .filter(|dna| !dna.trim().is_empty())
+            .or_else(|| storage.read_project_dna(project_path).ok())?;
+        project_dna::excerpt(&dna, config.max_chars)
+    }
+
+    /// Build a worker's role prompt. `project_dna` is embedded in place of the
+    /// pointer to `.ai-docs/project-dna.md` when given.
+    #[allow(clippy::too_many_arguments)]
+    /// The operator's label for an agent, if it set a non-blank one.
+    fn custom_label(config: &AgentConfig) -> Option<&str> {
+        config
+            .label
+            .as_deref()
+            .map(str::trim)
+            .filter(|label| !label.is_empty())
+    }
+
+    /// "Payments Backend (Backend)" for a principal with a custom label, else just
+    /// the role name.
+    fn principal_title(config: &AgentConfig, role_name: &str) -> String {
+        match Self::custom_label(config) {
+            Some(label) if label != role_name => format!("{label} ({role_name})"),
+            _ => role_name.to_string(),
+        }
+    }
+
     fn build_worker_prompt(
         index: u8,
         config: &AgentConfig,
@@ -5944,12 +9377,17 @@ When the objective and every configured gate are complete, send an idle heartbea
         project_path: &Path,
         workspace_path: &Path,
         execution_policy: &HiveExecutionPolicy,
+        project_dna: Option<&str>,
     ) -> String {
         let role_name = config
             .role
             .as_ref()
             .map(|worker_role| worker_role.label.clone())
             .unwrap_or_else(|| format!("Coding Principal {index}"));
+        let title = Self::principal_title(config, &role_name);
+        let display_name = Self::custom_label(config)
+            .map(str::to_string)
+            .unwrap_or_else(|| format!("worker-{index}"));
         let role_type = config
             .role
             .as_ref()
@@ -5977,26 +9415,28 @@ When the objective and every configured gate are complete, send an idle heartbea
         let workspace_contract =
             render_workspace_contract(contract_role, &execution_policy.workspace_strategy);
 
+        // The worker's own shell reads these, so spell them the way its CLI sees them.
+        let path_style = PathStyle::for_cli(&config.cli);
         let session_root = Self::session_root_path(project_path, session_id);
-        let workspace_path = Self::prompt_path(workspace_path);
         let task_file_path = if execution_policy.workspace_strategy == WorkspaceStrategy::None {
             Self::session_task_file_path(project_path, session_id, index as usize)
         } else {
-            PathBuf::from(&workspace_path)
+            workspace_path
                 .join(".hive-manager")
                 .join("tasks")
                 .join(format!("worker-{index}-task.md"))
         };
-        let task_file = Self::prompt_path(&task_file_path);
-        let worker_conversation = Self::prompt_path(
+        let workspace_path = path_style.render(workspace_path);
+        let task_file = path_style.render(&task_file_path);
+        let worker_conversation = path_style.render(
             &session_root
                 .join("conversations")
                 .join(format!("worker-{index}.md")),
         );
         let queen_conversation =
-            Self::prompt_path(&session_root.join("conversations").join("queen.md"));
+            path_style.render(&session_root.join("conversations").join("queen.md"));
         let shared_conversation =
-            Self::prompt_path(&session_root.join("conversations").join("shared.md"));
+            path_style.render(&session_root.join("conversations").join("shared.md"));
 
         let role_description = match role_type.as_str() {
             "backend" => "Server-side logic, APIs, databases, and backend infrastructure.",
@@ -6018,6 +9458,16 @@ When the objective and every configured gate are complete, send an idle heartbea
         } else {
             Self::scope_block(".")
         };
+        let file_permissions = path_policy::prompt_block(config)
+            .map(|block| format!("{block}\n\n"))
+            .unwrap_or_default();
+        let review_findings = review_findings::prompt_block(
+            &role_type,
+            &path_style.render(&review_findings::findings_path(&session_root, index)),
+            session_id,
+        )
+        .map(|block| format!("{block}\n\n"))
+        .unwrap_or_default();
         let objective = config
             .initial_prompt
             .as_deref()
@@ -6134,12 +9584,15 @@ When the objective and every configured gate are complete, send an idle heartbea
             }
         };
 
+        let status_api = format!(
+            r#"POST {{"status": "COMPLETED", "summary": "<result>"}} to /api/sessions/{session_id}/tasks/{index}/status"#
+        );
         let completion_protocol = if is_research {
             format!(
                 r#"## Completion Protocol (MANDATORY)
 
 1. {validation_and_handoff_rule}
-2. Update the authoritative task file at {task_file} to `Status: COMPLETED` and add the evidence summary.
+2. Update the authoritative task file at {task_file} to `Status: COMPLETED` and add the evidence summary. If file edits are unreliable in your harness, {status_api} instead; it does both.
 3. Send this completed heartbeat exactly as shown:
    ```bash
    {completed_heartbeat}
@@ -6149,6 +9602,7 @@ When the objective and every configured gate are complete, send an idle heartbea
                 validation_and_handoff_rule = validation_and_handoff_rule,
                 task_file = task_file,
                 completed_heartbeat = completed_heartbeat,
+                status_api = status_api,
             )
         } else {
             format!(
@@ -6156,7 +9610,7 @@ When the objective and every configured gate are complete, send an idle heartbea
 
 1. {validation_and_handoff_rule}
 2. Complete the Learnings Protocol below before changing the task status.
-3. Update the authoritative task file at {task_file} to `Status: COMPLETED` and add the result summary.
+3. Update the authoritative task file at {task_file} to `Status: COMPLETED` and add the result summary. If file edits are unreliable in your harness, {status_api} instead; it does both.
 4. Send this completed heartbeat exactly as shown:
    ```bash
    {completed_heartbeat}
@@ -6166,6 +9620,7 @@ When the objective and every configured gate are complete, send an idle heartbea
                 validation_and_handoff_rule = validation_and_handoff_rule,
                 task_file = task_file,
                 completed_heartbeat = completed_heartbeat,
+                status_api = status_api,
             )
         };
 
@@ -6175,19 +9630,21 @@ When the objective and every configured gate are complete, send an idle heartbea
             format!(
                 r#"## Learnings Protocol (MANDATORY)
 
-Before marking the task COMPLETED, POST one durable learning record to /api/sessions/{session_id}/learnings with session, task, outcome, keywords, insight, and files_touched. If the API is unavailable, append the same valid JSON object as one line to .hive-manager/{session_id}/learnings.pending.jsonl in this workspace. Do not write .ai-docs/learnings.jsonl directly. The session API is the topology-neutral durable path.
+Before marking the task COMPLETED, POST one durable learning record to /api/sessions/{session_id}/learnings with session, task, outcome, keywords, insight, and files_touched (optionally category and a 0.0-1.0 confidence). If the API is unavailable, append the same valid JSON object as one line to .hive-manager/{session_id}/learnings.pending.jsonl in this workspace. Do not write .ai-docs/learnings.jsonl directly. The session API is the topology-neutral durable path.
 
 "#
             )
         };
-        let project_context = if is_research {
-            String::new()
-        } else {
-            "## Project Context\n\nRead .ai-docs/project-dna.md before implementation and follow its current conventions.\n\n".to_string()
+        let project_context = match project_dna {
+            _ if is_research => String::new(),
+            Some(dna) => format!(
+                "## Project Context\n\nFollow the project's current conventions, from its project DNA:\n\n{dna}\n\n"
+            ),
+            None => "## Project Context\n\nRead .ai-docs/project-dna.md before implementation and follow its current conventions.\n\n".to_string(),
         };
 
         format!(
-            r#"# Managed Principal {index} - {role_name}
+            r#"# Managed Principal {index} - {title}
 
 {role_kernel}
 
@@ -6205,6 +9662,7 @@ Before marking the task COMPLETED, POST one durable learning record to /api/sess
 
 - Session ID: {session_id}
 - Principal ID: {session_id}-worker-{index}
+- Display name: {display_name}
 - Queen: {queen_id}
 - Harness: {cli}
 - Model: {model}
@@ -6215,13 +9673,13 @@ Use only the native tools exposed by the configured harness. The Capability Card
 
 {scope_block}
 
-## Task Lifecycle
+{file_permissions}{review_findings}## Task Lifecycle
 
 1. Read {task_file}.
 2. If Status is STANDBY, wait and re-check. Do not infer an assignment from this prompt.
 3. Begin only when Status is ACTIVE.
 4. Stay inside the objective and owned paths. Ask the Queen when ownership or acceptance criteria are unclear.
-5. If blocked, set Status to BLOCKED and report the exact blocker.
+5. If blocked, set Status to BLOCKED and report the exact blocker, or POST {{"status": "BLOCKED", "summary": "<blocker>"}} to /api/sessions/{session_id}/tasks/{index}/status.
 6. When work is complete, follow the mandatory Completion Protocol below exactly.
 
 {polling_instructions}
@@ -6244,7 +9702,8 @@ treated as stuck and requeued.
 
 {learnings_section}{project_context}After reporting completion, stop and continue monitoring the inbox without sending another heartbeat. Do not take a new task until its task file status is ACTIVE; once reactivated, send a working heartbeat."#,
             index = index,
-            role_name = role_name,
+            title = title,
+            display_name = display_name,
             role_kernel = role_kernel,
             capability_card = capability_card,
             delegation = delegation,
@@ -6258,6 +9717,8 @@ treated as stuck and requeued.
             workspace_path = workspace_path,
             task_file = task_file,
             scope_block = scope_block,
+            file_permissions = file_permissions,
+            review_findings = review_findings,
             polling_instructions = polling_instructions,
             completion_protocol = completion_protocol,
             worker_conversation = worker_conversation,
@@ -6484,7 +9945,7 @@ You are the **Queen** orchestrating a multi-agent Swarm session. You spawn and c
 ## Session Info
 
 - **Session ID**: {session_id}
-- **Mode**: Swarm (hierarchical with sequential spawning)
+- **Mode**: Swarm (hierarchical, domains ordered by the plan's dependencies)
 - **Prompts Directory**: `.hive-manager/{session_id}/prompts/`
 - **Tools Directory**: `.hive-manager/{session_id}/tools/`
 
@@ -6496,7 +9957,7 @@ Before assigning work, read:
 
 ## Planners to Spawn
 
-You will spawn {planner_count} planners SEQUENTIALLY. Each planner spawns their own workers.
+You will spawn {planner_count} planners. Each planner spawns their own workers. Planners for independent domains may run at the same time; a domain listed under `Cross-Domain Dependencies` in plan.md waits until every domain it depends on is complete.
 
 | # | Domain | Workers |
 |---|--------|---------|
@@ -6583,20 +10044,20 @@ Workers and planners record learnings during task completion. Your curation resp
 
 {qa_milestone_handoff}
 
-## SEQUENTIAL SPAWNING PROTOCOL WITH COMMITS (CRITICAL)
+## DOMAIN ORDERING PROTOCOL WITH COMMITS (CRITICAL)
 
-You MUST spawn planners ONE AT A TIME and COMMIT between each:
+Hive Manager enforces the plan's cross-domain dependencies. Spawn planners as their domains become ready and COMMIT after each domain:
 
 ### Protocol:
 
-1. **Spawn Planner 1** via HTTP API with domain task
-2. **Wait for Planner 1** to signal `[DOMAIN_COMPLETE]`
+1. **Spawn a planner for every domain with no unfinished dependencies** via HTTP API with its domain task. Use the domain's name from plan.md.
+2. **Wait for a planner** to signal `[DOMAIN_COMPLETE]`, verify its work, then mark its exact planner ID `completed` with `mark-worker-status.md`
 3. **COMMIT** changes with message: "feat(DOMAIN): [description of domain work]"
-4. **Spawn Planner 2** via HTTP API with domain task
-5. **Wait for Planner 2** to signal `[DOMAIN_COMPLETE]`
-6. **COMMIT** changes with message: "feat(DOMAIN): [description of domain work]"
-7. Continue for all {planner_count} planners
-8. **Final integration commit** and push
+4. **Spawn planners for unblocked domains**: each domain that could not start before gets a `[DOMAIN_UNBLOCKED]` line in the coordination log once all of its dependencies are complete
+5. Continue until all {planner_count} domains are complete
+6. **Final integration commit** and push
+
+Spawning a planner for a domain that still waits returns `409` with the domains it is `waiting_on`; wait for its `[DOMAIN_UNBLOCKED]` line instead of retrying.
 
 ### Monitoring Planner Completion
 
@@ -6605,8 +10066,8 @@ Check planner status via HTTP API or look for signals:
 # List planners
 curl "http://localhost:18800/api/sessions/{session_id}/planners"
 
-# Check coordination log for [DOMAIN_COMPLETE] signals
-cat .hive-manager/{session_id}/coordination/coordination.log | grep "DOMAIN_COMPLETE"
+# Check coordination log for [DOMAIN_COMPLETE] and [DOMAIN_UNBLOCKED] signals
+cat .hive-manager/{session_id}/coordination/coordination.log | grep -E "DOMAIN_(COMPLETE|UNBLOCKED)"
 ```
 
 ### Git Commit Pattern
@@ -6620,9 +10081,9 @@ git commit -m "feat(DOMAIN): Brief description of what this domain accomplished"
 ## Protocol Summary
 
 1. Analyze task → identify domains
-2. For each planner (sequentially):
+2. For each domain, once its dependencies are complete:
    a. Spawn planner with domain task
-   b. Wait for `[DOMAIN_COMPLETE]` signal
+   b. Wait for `[DOMAIN_COMPLETE]` signal and mark the planner `completed`
    c. **COMMIT** domain changes
 3. Run integration tests
 4. Final commit and push
@@ -6650,6 +10111,114 @@ Log each iteration to `.hive-manager/{session_id}/coordination.log`:
         )
     }
 
+    /// The token this session's agents must send as `SESSION_TOKEN_HEADER` on writes to
+    /// the session's HTTP endpoints. Issued the first time a prompt is rendered for the
+    /// session and persisted so a restored session keeps accepting its agents.
+    pub fn session_api_token(&self, session_id: &str) -> String {
+        if let Some(token) = self.issued_session_api_token(session_id) {
+            return token;
+        }
+        self.api_tokens
+            .write()
+            .entry(session_id.to_string())
+            .or_insert_with(|| {
+                let token = Uuid::new_v4().simple().to_string();
+                if let Some(storage) = &self.storage {
+                    if let Err(e) = storage.save_session_api_token(session_id, &token) {
+                        tracing::warn!(
+                            "Failed to persist API token for session {}: {}",
+                            session_id,
+                            e
+                        );
+                    }
+                }
+                token
+            })
+            .clone()
+    }
+
+    /// The session's token if one was ever issued. Sessions launched before tokens existed
+    /// have none: their agents were never told one, so their endpoints stay open.
+    pub fn issued_session_api_token(&self, session_id: &str) -> Option<String> {
+        if let Some(token) = self.api_tokens.read().get(session_id) {
+            return Some(token.clone());
+        }
+        let token = self
+            .storage
+            .as_ref()?
+            .load_session_api_token(session_id)
+            .ok()
+            .flatten()?;
+        self.api_tokens
+            .write()
+            .insert(session_id.to_string(), token.clone());
+        Some(token)
+    }
+
+    /// What a policy script sees of a session (see
+    /// [`crate::session::policy_script`]), with the fields of `extra` added. Only
+    /// `session_id` is filled in while the session table is being written.
+    fn policy_context(&self, session_id: &str, extra: serde_json::Value) -> serde_json::Value {
+        let mut context = serde_json::json!({ "session_id": session_id });
+        if let Some(sessions) = self.sessions.try_read() {
+            if let Some(session) = sessions.get(session_id) {
+                context["session_type"] = Self::session_type_name(&session.session_type).into();
+                context["state"] = serialize_session_state(&session.state).into();
+                context["project_path"] = session.project_path.to_string_lossy().into();
+                context["agents"] = session
+                    .agents
+                    .iter()
+                    .map(|agent| {
+                        serde_json::json!({
+                            "id": agent.id,
+                            "role": agent.role,
+                            "status": agent.status,
+                            "cli": agent.config.cli,
+                            "label": agent.config.label,
+                        })
+                    })
+                    .collect();
+            }
+        }
+        if let (serde_json::Value::Object(context), serde_json::Value::Object(extra)) =
+            (&mut context, extra)
+        {
+            context.extend(extra);
+        }
+        context
+    }
+
+    /// The outbox `session_id`'s agents write API calls to, when `api.offline` is set.
+    fn offline_outbox_dir(&self, session_id: &str) -> Option<PathBuf> {
+        let storage = self.storage.as_ref()?;
+        let offline = storage.load_config().is_ok_and(|config| config.api.offline);
+        offline.then(|| storage.outbox_dir(session_id))
+    }
+
+    /// Append the session's API token, ask-the-operator, approval-gate and
+    /// acknowledgement instructions to an agent prompt.
+    fn with_session_api_token(&self, session_id: &str, prompt: &str) -> String {
+        let scripted = self.policy_script.as_ref().and_then(|policy| {
+            let context = self.policy_context(session_id, serde_json::Value::Null);
+            policy.transform_prompt(&context, prompt)
+        });
+        let token = self.session_api_token(session_id);
+        let api_block = match self.offline_outbox_dir(session_id) {
+            Some(outbox) => offline_api_block(session_id, &outbox),
+            None => session_api_token_block(session_id, &token),
+        };
+        crate::http::transport::for_agents(&format!(
+            "{}{}{}{}{}{}{}",
+            scripted.as_deref().unwrap_or(prompt),
+            api_block,
+            ask_operator_block(session_id),
+            approval_gate_block(session_id),
+            progress_ack_block(session_id),
+            attachments_block(session_id),
+            scratchpad_block(session_id)
+        ))
+    }
+
     /// Write a prompt file to the session's prompts directory
     fn write_prompt_file(
         project_path: &PathBuf,
@@ -6712,7 +10281,7 @@ Log each iteration to `.hive-manager/{session_id}/coordination.log`:
             .map_err(|e| format!("Failed to create tools directory: {}", e))?;
 
         let file_path = tools_dir.join(filename);
-        std::fs::write(&file_path, content)
+        std::fs::write(&file_path, crate::http::transport::for_agents(content))
             .map_err(|e| format!("Failed to write tool file: {}", e))?;
 
         Ok(file_path)
@@ -6767,6 +10336,8 @@ Content-Type: application/json
 | description | string | No | One-line task summary used for deterministic labels |
 | label | string | No | Legacy label field; kept as a fallback input |
 | initial_task | string | No | Initial task/prompt for the worker |
+| allowed_paths | string[] | No | Globs the worker may change, e.g. `["src/**"]`; omit to allow everything not denied |
+| denied_paths | string[] | No | Globs the worker must not change, e.g. `["src-tauri/**"]` |
 | parent_id | string | No | Parent agent ID (defaults to Queen) |
 
 ## Example Usage
@@ -7021,16 +10592,22 @@ Content-Type: application/json
 }
 ```
 
-## Required Fields
+## Fields
+
+Send either `task` + `outcome` + `insight`, or `content` + `category`. A 400
+response lists any required fields that are missing.
 
 | Field | Type | Description |
 |-------|------|-------------|
-| session | string | Current session ID |
+| session | string | Current session ID (defaults to the session in the URL) |
 | task | string | What task was being performed |
-| insight | string | The learning or discovery |
-| outcome | string | Category: success, partial, failed |
+| insight | string | The learning or discovery (`content` is accepted too) |
+| outcome | string | success, partial, failed |
 | keywords | string[] | Relevant keywords for filtering |
 | files_touched | string[] | Files involved in this learning |
+| category | string | Optional: architecture, code-quality, anti-pattern, ... |
+| confidence | number | Optional: 0.0-1.0, how sure you are |
+| source | string | Optional: your agent ID or role |
 
 ## Example
 
@@ -7061,7 +10638,7 @@ List all learnings recorded for this session.
 
 | Parameter | Type | Description |
 |-----------|------|-------------|
-| category | string | Filter by outcome category (e.g., "success", "partial") |
+| category | string | Filter by category or outcome (e.g., "architecture", "success") |
 | keywords | string | Comma-separated keyword filter (e.g., "api,rust") |
 
 ## Example
@@ -7071,7 +10648,7 @@ List all learnings recorded for this session.
 curl "http://localhost:18800/api/sessions/{{session_id}}/learnings"
 
 # Filter by category
-curl "http://localhost:18800/api/sessions/{{session_id}}/learnings?category=success"
+curl "http://localhost:18800/api/sessions/{{session_id}}/learnings?category=architecture"
 
 # Filter by keywords
 curl "http://localhost:18800/api/sessions/{{session_id}}/learnings?keywords=api,rust"
@@ -7203,21 +10780,23 @@ curl -X POST "http://localhost:18800/api/sessions/{session_id}/planners" \
 }}
 ```
 
-## Sequential Spawning Protocol
+## Spawning Order
 
-1. Spawn Planner 1 → Wait for completion signal
-2. **COMMIT changes** with message describing Planner 1's domain work
-3. Spawn Planner 2 → Wait for completion signal
-4. **COMMIT changes** with message describing Planner 2's domain work
+1. Spawn a planner for each domain whose dependencies in plan.md are complete
+2. Wait for a planner's completion signal, then mark it `completed`
+3. **COMMIT changes** with message describing that planner's domain work
+4. Spawn planners for domains announced by `[DOMAIN_UNBLOCKED]`
 5. Continue for all {planner_count} planners
 6. Final integration commit and push
 
+A domain that still waits on another is refused with `409 Conflict`; the response's `waiting_on` names the domains it needs.
+
 ## Notes
 
 - Planners spawn in a new Windows Terminal tab (visible window)
 - Each planner knows how to spawn its own workers sequentially
-- Wait for `[DOMAIN_COMPLETE]` signal from planner before committing and spawning next
-- Commit between each planner to create clean git history
+- Wait for `[DOMAIN_COMPLETE]` signal from planner before committing its domain
+- Commit after each domain to create clean git history
 "#,
             session_id = session_id,
             planner_count = planner_count,
@@ -7448,11 +11027,388 @@ Last updated: {timestamp}
             timestamp = timestamp
         );
 
-        std::fs::write(&file_path, content)
-            .map_err(|e| format!("Failed to write QA task file: {}", e))?;
+        std::fs::write(&file_path, content)
+            .map_err(|e| format!("Failed to write QA task file: {}", e))?;
+
+        Ok(file_path)
+    }
+    /// Pick the session branch: the operator's `branch_name` when set (validated
+    /// against git's ref rules), otherwise the managed default.
+    fn resolve_session_branch(
+        branch_name: Option<&str>,
+        default_branch: String,
+    ) -> Result<String, String> {
+        match branch_name
+            .map(str::trim)
+            .filter(|branch| !branch.is_empty())
+        {
+            Some(branch) => {
+                validate_branch_name(branch)?;
+                Ok(branch.to_string())
+            }
+            None => Ok(default_branch),
+        }
+    }
+
+    /// Check that each worker's recorded commit is reachable from the session branch.
+    /// The controller is locked only to read the session; one `git merge-base` runs
+    /// per commit, so the checks run without it.
+    pub fn get_session_branch_status(
+        controller: &RwLock<SessionController>,
+        session_id: &str,
+    ) -> Result<SessionBranchStatus, String> {
+        let session = controller
+            .read()
+            .get_session(session_id)
+            .ok_or_else(|| format!("Session not found: {}", session_id))?;
+        let branch = session
+            .worktree_branch
+            .clone()
+            .ok_or_else(|| format!("Session {} has no session branch", session_id))?;
+
+        let mut commits = Vec::new();
+        for agent in &session.agents {
+            let Some(commit_sha) = agent.commit_sha.clone() else {
+                continue;
+            };
+            let on_branch = is_commit_on_branch(&session.project_path, &commit_sha, &branch)?;
+            commits.push(SessionBranchCommit {
+                agent_id: agent.id.clone(),
+                commit_sha,
+                on_branch,
+            });
+        }
+
+        Ok(SessionBranchStatus {
+            all_commits_on_branch: commits.iter().all(|commit| commit.on_branch),
+            branch,
+            worktree_path: session.worktree_path.clone(),
+            commits,
+        })
+    }
+
+    /// Push the session branch and open a pull request whose body is composed from the
+    /// session report (plan summary, completed tasks, learnings). The URL is recorded on
+    /// the session; a session that already has a PR returns its URL unchanged. `git push`
+    /// and `gh pr create` can take a long time, so the controller is locked only to read
+    /// the session and to record the URL, never while they run.
+    pub fn create_pull_request(
+        controller: &RwLock<SessionController>,
+        session_id: &str,
+        title: Option<&str>,
+        base: Option<&str>,
+        draft: bool,
+    ) -> Result<String, String> {
+        let (worktree_path, branch, title, body_file) = {
+            let controller = controller.read();
+            let session = controller
+                .get_session(session_id)
+                .ok_or_else(|| format!("Session not found: {}", session_id))?;
+            if let Some(url) = session.pull_request_url.clone() {
+                return Ok(url);
+            }
+            let branch = session
+                .worktree_branch
+                .clone()
+                .ok_or_else(|| format!("Session {} has no session branch", session_id))?;
+            let worktree_path = session
+                .worktree_path
+                .clone()
+                .map(PathBuf::from)
+                .unwrap_or_else(|| session.project_path.clone());
+
+            let body =
+                pull_request::compose_pull_request_body(&controller.pull_request_report(&session));
+            let body_file =
+                Self::session_root_path(&session.project_path, session_id).join("pull-request.md");
+            if let Some(parent) = body_file.parent() {
+                std::fs::create_dir_all(parent)
+                    .map_err(|e| format!("Failed to create session directory: {}", e))?;
+            }
+            std::fs::write(&body_file, body)
+                .map_err(|e| format!("Failed to write pull request body: {}", e))?;
+
+            let title = title
+                .map(str::trim)
+                .filter(|title| !title.is_empty())
+                .map(str::to_string)
+                .or_else(|| session.name.clone())
+                .unwrap_or_else(|| format!("Hive session {}", session_id));
+            (worktree_path, branch, title, body_file)
+        };
+
+        pull_request::push_branch(&worktree_path, &branch)?;
+        let url = pull_request::open_pull_request(
+            &worktree_path,
+            &branch,
+            base,
+            &title,
+            &body_file,
+            draft,
+        )?;
+        tracing::info!("Opened pull request {} for session {}", url, session_id);
+
+        let controller = controller.read();
+        {
+            let mut sessions = controller.sessions.write();
+            if let Some(s) = sessions.get_mut(session_id) {
+                s.pull_request_url = Some(url.clone());
+            }
+        }
+        controller.update_session_storage(session_id);
+        controller.emit_session_update(session_id);
+
+        Ok(url)
+    }
+
+    /// Run a batch of git operations requested by the session's Queen in the Queen's
+    /// workspace (see `workspace::git_ops`), recording each outcome in the coordination
+    /// log and emitting them as `git-operations`.
+    pub fn run_queen_git_operations(
+        &self,
+        session_id: &str,
+        operations: &[GitOperation],
+    ) -> Result<Vec<GitOperationOutcome>, String> {
+        let session = self
+            .get_session(session_id)
+            .ok_or_else(|| format!("Session not found: {}", session_id))?;
+        if session.no_git {
+            return Err(format!("Session {} does not use git", session_id));
+        }
+        let workspace = session
+            .agents
+            .iter()
+            .find(|agent| matches!(agent.role, AgentRole::Queen))
+            .and_then(|queen| Self::agent_git_worktree_path_for_artifacts(&session, queen))
+            .filter(|path| path.exists())
+            .unwrap_or_else(|| session.project_path.clone());
+
+        let outcomes = git_ops::run_batch(&workspace, operations);
+        for outcome in &outcomes {
+            let detail = match (&outcome.commit, &outcome.error) {
+                (Some(commit), _) => format!(" ({})", commit),
+                (None, Some(error)) => format!(": {}", error),
+                (None, None) => String::new(),
+            };
+            tracing::info!(
+                "Queen git operation in session {}: {} {}{}",
+                session_id,
+                outcome.operation,
+                outcome.status.as_str(),
+                detail
+            );
+            self.log_coordination_system(
+                session_id,
+                &format!(
+                    "[GIT] Queen {} in {}: {}{}",
+                    outcome.operation,
+                    workspace.display(),
+                    outcome.status.as_str(),
+                    detail
+                ),
+            );
+        }
+        if let Some(ref app_handle) = self.app_handle {
+            let _ = app_handle.emit(
+                "git-operations",
+                serde_json::json!({
+                    "session_id": session_id,
+                    "outcomes": outcomes,
+                }),
+            );
+        }
+        Ok(outcomes)
+    }
+
+    fn pull_request_report(&self, session: &Session) -> PullRequestReport {
+        let plan_summary =
+            plan_parser::load(&Self::session_root_path(&session.project_path, &session.id))
+                .ok()
+                .flatten()
+                .and_then(|plan| plan.summary);
+
+        let completed_tasks = Self::completed_tasks(session);
+
+        let learnings = self
+            .storage
+            .as_ref()
+            .and_then(|storage| storage.read_learnings_session(&session.id).ok())
+            .unwrap_or_default()
+            .into_iter()
+            .map(|learning| learning.insight)
+            .collect();
+
+        PullRequestReport {
+            session_id: session.id.clone(),
+            plan_summary,
+            completed_tasks,
+            learnings,
+            durations: Some(Self::session_durations(session)),
+        }
+    }
+
+    /// Workers whose task file is marked COMPLETED, with their result summaries.
+    fn completed_tasks(session: &Session) -> Vec<CompletedTask> {
+        session
+            .agents
+            .iter()
+            .filter_map(|agent| {
+                let AgentRole::Worker { index, .. } = &agent.role else {
+                    return None;
+                };
+                let task_path =
+                    Self::task_file_path_for_session_worker(session, usize::from(*index)).ok()?;
+                let task = std::fs::read_to_string(task_path).ok()?;
+                if Self::parse_task_status(&task).as_deref() != Some("COMPLETED") {
+                    return None;
+                }
+                Some(CompletedTask {
+                    label: agent
+                        .config
+                        .label
+                        .clone()
+                        .unwrap_or_else(|| agent.id.clone()),
+                    summary: pull_request::task_result_summary(&task),
+                })
+            })
+            .collect()
+    }
+
+    /// Mirror the ready plan of `session_id` onto GitHub issue `parent` (see
+    /// `workspace::github_issues`), then close whatever is already done.
+    pub fn link_plan_issues(
+        &self,
+        session_id: &str,
+        parent: &str,
+        mode: PlanIssueMode,
+    ) -> Result<PlanIssueLink, String> {
+        let session = self
+            .get_session(session_id)
+            .ok_or_else(|| format!("Session not found: {}", session_id))?;
+        if session.state == SessionState::Planning {
+            return Err("The plan is not ready yet; mark it ready first".to_string());
+        }
+        let parent = github_issues::parse_issue_number(parent)
+            .ok_or_else(|| format!("Not a GitHub issue: {}", parent))?;
+        let session_root = Self::session_root_path(&session.project_path, session_id);
+        if let Some(link) = github_issues::load(&session_root)? {
+            return Err(format!(
+                "The plan is already linked to GitHub issue #{}",
+                link.parent
+            ));
+        }
+        let plan = plan_parser::load(&session_root)
+            .map_err(|e| format!("Failed to read the plan: {}", e))?
+            .filter(|plan| !plan.tasks.is_empty())
+            .ok_or_else(|| format!("Session {} has no plan tasks", session_id))?;
+
+        let mut link = github_issues::link_plan(
+            &session.project_path,
+            session_id,
+            parent,
+            mode,
+            &plan.tasks,
+        )?;
+        github_issues::store(&session_root, &link)?;
+        self.log_coordination_system(
+            session_id,
+            &format!(
+                "[GITHUB] Plan linked to issue #{}: {} task(s) as {}",
+                parent,
+                link.tasks.len(),
+                match mode {
+                    PlanIssueMode::SubIssues => "sub-issues",
+                    PlanIssueMode::Checklist => "a checklist comment",
+                }
+            ),
+        );
+        self.close_done_plan_issues(&session, &mut link);
+        Ok(link)
+    }
+
+    /// Close the GitHub sub-issues, or tick the checklist boxes, of plan tasks
+    /// finished since the last pass, for every loaded session with a linked plan.
+    pub fn sync_plan_issues(&self) {
+        let linked: Vec<(Session, PlanIssueLink)> = self
+            .sessions
+            .read()
+            .values()
+            .filter_map(|session| {
+                let session_root = Self::session_root_path(&session.project_path, &session.id);
+                let link = github_issues::load(&session_root).ok().flatten()?;
+                (!link.is_settled()).then(|| (session.clone(), link))
+            })
+            .collect();
+        for (session, mut link) in linked {
+            self.close_done_plan_issues(&session, &mut link);
+        }
+    }
 
-        Ok(file_path)
+    fn close_done_plan_issues(&self, session: &Session, link: &mut PlanIssueLink) {
+        let done = Self::done_plan_task_numbers(session);
+        if done.is_empty() {
+            return;
+        }
+        let session_root = Self::session_root_path(&session.project_path, &session.id);
+        match github_issues::close_tasks(&session.project_path, &session.id, link, &done) {
+            Ok(closed) if closed.is_empty() => {}
+            Ok(closed) => {
+                if let Err(e) = github_issues::store(&session_root, link) {
+                    tracing::warn!("Failed to save the plan's GitHub link: {}", e);
+                }
+                let numbers: Vec<String> = closed.iter().map(usize::to_string).collect();
+                self.log_coordination_system(
+                    &session.id,
+                    &format!(
+                        "[GITHUB] Closed plan task(s) {} on issue #{}",
+                        numbers.join(", "),
+                        link.parent
+                    ),
+                );
+            }
+            Err(e) => {
+                tracing::warn!("Failed to sync plan issues for {}: {}", session.id, e);
+            }
+        }
+    }
+
+    /// Plan tasks that are ticked in `plan.md`, or assigned to a worker whose task
+    /// file is marked COMPLETED.
+    fn done_plan_task_numbers(session: &Session) -> Vec<usize> {
+        let session_root = Self::session_root_path(&session.project_path, &session.id);
+        let Some(plan) = plan_parser::load(&session_root).ok().flatten() else {
+            return Vec::new();
+        };
+        let completed_workers: Vec<String> = session
+            .agents
+            .iter()
+            .filter_map(|agent| {
+                let AgentRole::Worker { index, .. } = &agent.role else {
+                    return None;
+                };
+                let task_path =
+                    Self::task_file_path_for_session_worker(session, usize::from(*index)).ok()?;
+                let task = std::fs::read_to_string(task_path).ok()?;
+                (Self::parse_task_status(&task).as_deref() == Some("COMPLETED")).then(|| {
+                    let mut names = vec![format!("worker-{index}"), format!("principal {index}")];
+                    names.extend(Self::custom_label(&agent.config).map(str::to_string));
+                    names
+                })
+            })
+            .flatten()
+            .collect();
+        plan.tasks
+            .iter()
+            .filter(|task| {
+                task.done
+                    || task.assignee.as_deref().is_some_and(|assignee| {
+                        context_pack::mentions(assignee, &completed_workers)
+                    })
+            })
+            .map(|task| task.number)
+            .collect()
     }
+
     fn launch_solo_internal(
         &self,
         project_path: PathBuf,
@@ -7467,10 +11423,14 @@ Last updated: {timestamp}
         qa_workers: Option<Vec<QaWorkerConfig>>,
         smoke_test: bool,
         execution_policy: HiveExecutionPolicy,
+        branch_name: Option<&str>,
+        background: bool,
     ) -> Result<Session, String> {
-        let session_id = Uuid::new_v4().to_string();
+        let session_id = launch_queue::new_session_id();
+        self.track_background(&session_id, background);
+        let solo_branch =
+            Self::resolve_session_branch(branch_name, format!("solo/{}/worker-1", session_id))?;
         let base_ref = resolve_fresh_base(&project_path);
-        let solo_branch = format!("solo/{}/worker-1", session_id);
         let mut created_cells = Vec::new();
         let mut spawned_agent_ids = Vec::new();
         let (_, solo_cwd) = create_session_worktree(
@@ -7494,15 +11454,48 @@ Last updated: {timestamp}
             description: Some(solo_description),
             role: None,
             initial_prompt: task_description.clone(),
+            allowed_paths: Vec::new(),
+            denied_paths: Vec::new(),
+        };
+        let solo_id = format!("{}-worker-1", session_id);
+        let inline_task = if with_evaluator {
+            None
+        } else {
+            task_description.as_deref()
+        };
+        let task_file = match inline_task {
+            Some(task) => match self.spill_oversized_prompt(
+                &project_path,
+                &session_id,
+                &solo_id,
+                &cli,
+                "solo-task.md",
+                task,
+            ) {
+                Ok(task_file) => task_file,
+                Err(err) => {
+                    self.rollback_launch_allocations(
+                        &project_path,
+                        &session_id,
+                        &created_cells,
+                        &spawned_agent_ids,
+                    );
+                    return Err(err);
+                }
+            },
+            None => None,
         };
         let (cmd, mut args) = Self::build_solo_command(
             &solo_config,
-            if with_evaluator {
+            if task_file.is_some() {
                 None
             } else {
-                task_description.as_deref()
+                inline_task
             },
         );
+        if let Some(task_file) = task_file {
+            Self::add_prompt_to_args(&cmd, &mut args, &task_file.to_string_lossy());
+        }
         if with_evaluator {
             let solo_prompt = Self::build_solo_evaluator_prompt(
                 &session_id,
@@ -7514,7 +11507,7 @@ Last updated: {timestamp}
                 &project_path,
                 &session_id,
                 "solo-prompt.md",
-                &solo_prompt,
+                &self.with_session_api_token(&session_id, &solo_prompt),
             ) {
                 Ok(path) => path,
                 Err(err) => {
@@ -7529,7 +11522,6 @@ Last updated: {timestamp}
             };
             Self::add_prompt_to_args(&cmd, &mut args, &prompt_file.to_string_lossy());
         }
-        let solo_id = format!("{}-worker-1", session_id);
 
         {
             let pty_manager = self.pty_manager.read();
@@ -7580,6 +11572,7 @@ Last updated: {timestamp}
                 parent_id: None,
                 commit_sha: None,
                 base_commit_sha: None,
+                exit_info: None,
             }],
             default_cli: cli,
             default_model: model,
@@ -7595,6 +11588,8 @@ Last updated: {timestamp}
             worktree_branch: Some(solo_branch.clone()),
             no_git: false,
             resume_report: None,
+            pull_request_url: None,
+            spawn_quota: SpawnQuota::default(),
         };
 
         if let Err(err) = Self::write_tool_files(
@@ -7690,10 +11685,15 @@ Last updated: {timestamp}
             config.qa_workers.clone(),
             config.smoke_test,
             execution_policy,
+            config.branch_name.as_deref(),
+            config.background,
         )
     }
 
     pub fn launch_hive_v2(&self, config: HiveLaunchConfig) -> Result<Session, String> {
+        if config.smoke_test && config.smoke_probe {
+            return self.launch_smoke_probe(config);
+        }
         self.launch_hive_internal(config, None, HashMap::new(), true, true)
     }
 
@@ -7719,7 +11719,6 @@ Last updated: {timestamp}
         use_worktrees: bool,
         pre_spawn_workers: bool,
     ) -> Result<Session, String> {
-        let session_id = Uuid::new_v4().to_string();
         let mut agents = Vec::new();
         let project_path = PathBuf::from(&config.project_path);
         let mut created_cells = Vec::new();
@@ -7738,6 +11737,17 @@ Last updated: {timestamp}
             return self.launch_solo(config);
         }
 
+        let session_id = launch_queue::new_session_id();
+
+        self.track_phase_timeouts(&session_id, config.phase_timeouts);
+        self.track_background(&session_id, config.background);
+        if let Some(review) = config.review_stage.clone() {
+            self.review_stages.lock().insert(
+                session_id.clone(),
+                ReviewStage::new(review, &config.queen_config),
+            );
+        }
+
         // If with_planning is true, spawn Master Planner first
         if config.with_planning {
             return self.launch_planning_phase(session_id, config);
@@ -7757,11 +11767,14 @@ Last updated: {timestamp}
         // Create Queen agent
         let queen_id = format!("{}-queen", session_id);
         let (cmd, mut args) = Self::build_command(&config.queen_config);
-        let queen_branch = if shared_cell {
-            format!("hive/{}/primary", session_id)
-        } else {
-            format!("hive/{}/queen", session_id)
-        };
+        let queen_branch = Self::resolve_session_branch(
+            config.branch_name.as_deref(),
+            if shared_cell {
+                format!("hive/{}/primary", session_id)
+            } else {
+                format!("hive/{}/queen", session_id)
+            },
+        )?;
         let queen_cwd = if use_worktrees {
             let queen_cell_id = if shared_cell { "primary" } else { "queen" };
             let (_, cwd) = create_session_worktree(
@@ -7799,6 +11812,7 @@ Last updated: {timestamp}
         // template; the default Hive path uses the hand-built master prompt.
         let master_prompt = if let Some(template_name) = queen_template_override {
             Self::build_templated_queen_prompt(
+                &self.template_engine(),
                 template_name,
                 &session_id,
                 &config.workers,
@@ -7811,6 +11825,7 @@ Last updated: {timestamp}
                 &project_path,
                 Path::new(&queen_cwd),
                 &session_id,
+                &queen_branch,
                 &config.workers,
                 config.prompt.as_deref(),
                 has_plan,
@@ -7822,7 +11837,7 @@ Last updated: {timestamp}
             &project_path,
             &session_id,
             "queen-prompt.md",
-            &master_prompt,
+            &self.with_session_api_token(&session_id, &master_prompt),
         ) {
             Ok(prompt_file) => prompt_file,
             Err(err) => {
@@ -7891,6 +11906,7 @@ Last updated: {timestamp}
             parent_id: None,
             commit_sha: None,
             base_commit_sha: None,
+            exit_info: None,
         });
 
         // Create Worker agents.
@@ -7904,6 +11920,11 @@ Last updated: {timestamp}
         } else {
             &[]
         };
+        let project_dna = if workers_to_spawn.is_empty() {
+            None
+        } else {
+            self.worker_project_dna(&session_id, &project_path)
+        };
         for (i, worker_config) in workers_to_spawn.iter().enumerate() {
             let index = (i + 1) as u8;
             let worker_id = format!("{}-worker-{}", session_id, index);
@@ -7994,13 +12015,14 @@ Last updated: {timestamp}
                 &project_path,
                 Path::new(&worker_cwd),
                 &config.execution_policy,
+                project_dna.as_deref(),
             );
             let filename = format!("worker-{}-prompt.md", index);
             let prompt_file = match Self::write_worker_prompt_file(
                 Path::new(&worker_cwd),
                 index,
                 &filename,
-                &worker_prompt,
+                &self.with_session_api_token(&session_id, &worker_prompt),
             ) {
                 Ok(prompt_file) => prompt_file,
                 Err(err) => {
@@ -8060,6 +12082,7 @@ Last updated: {timestamp}
                 parent_id: Some(queen_id.clone()),
                 commit_sha: None,
                 base_commit_sha: worker_base_commit_sha,
+                exit_info: None,
             });
         }
 
@@ -8102,6 +12125,8 @@ Last updated: {timestamp}
             },
             no_git: !use_worktrees,
             resume_report: None,
+            pull_request_url: None,
+            spawn_quota: config.spawn_quota.clone(),
         };
 
         {
@@ -8196,11 +12221,17 @@ Last updated: {timestamp}
             // Research smoke is driven entirely by the Queen prompt (see `smoke_directive`
             // below); it must NOT trigger the evaluator-based smoke path.
             smoke_test: false,
+            smoke_probe: false,
             execution_policy: HiveExecutionPolicy {
                 launch_kind: HiveLaunchKind::Hive,
                 workspace_strategy: WorkspaceStrategy::None,
                 ..HiveExecutionPolicy::default()
             },
+            branch_name: None,
+            phase_timeouts: PhaseTimeouts::default(),
+            review_stage: None,
+            spawn_quota: SpawnQuota::default(),
+            background: false,
         };
 
         // Resolve the global wiki path from AppConfig (falls back to the documented
@@ -8290,7 +12321,15 @@ phases and do EXACTLY this, then stop:
         .to_string()
     }
 
-    pub fn launch_fusion(&self, config: FusionLaunchConfig) -> Result<Session, String> {
+    /// Create a Fusion session and its metadata, then return without waiting for git or
+    /// the variants. The returned plan goes through `prepare_fusion_worktrees` (base branch
+    /// and worktrees, async git) and then `spawn_fusion_variants`, which brings the variants
+    /// up in parallel and reports `fusion-variant-progress`. Planning launches return no
+    /// plan: their variants are spawned after the plan is approved.
+    pub fn launch_fusion(
+        &self,
+        config: FusionLaunchConfig,
+    ) -> Result<(Session, Option<FusionSpawnPlan>), String> {
         tracing::info!(
             "launch_fusion called: with_planning={}, variants={}, task={}",
             config.with_planning,
@@ -8303,11 +12342,17 @@ phases and do EXACTLY this, then stop:
         }
 
         if config.with_planning {
-            let session_id = Uuid::new_v4().to_string();
-            return self.launch_fusion_planning_phase(session_id, config);
+            let session_id = launch_queue::new_session_id();
+            self.track_phase_timeouts(&session_id, config.phase_timeouts);
+            self.track_background(&session_id, config.background);
+            return self
+                .launch_fusion_planning_phase(session_id, config)
+                .map(|session| (session, None));
         }
 
-        let session_id = Uuid::new_v4().to_string();
+        let session_id = launch_queue::new_session_id();
+        self.track_phase_timeouts(&session_id, config.phase_timeouts);
+        self.track_background(&session_id, config.background);
         let project_path = PathBuf::from(&config.project_path);
         let default_cli = if config.default_cli.trim().is_empty() {
             "claude".to_string()
@@ -8315,6 +12360,7 @@ phases and do EXACTLY this, then stop:
             config.default_cli.trim().to_string()
         };
 
+        let worktree_root = self.fusion_worktree_root(&project_path, &session_id);
         let mut seen_slugs: HashMap<String, u16> = HashMap::new();
         let mut variants = Vec::new();
 
@@ -8327,9 +12373,7 @@ phases and do EXACTLY this, then stop:
             };
             let slug = Self::unique_variant_slug(&name, &mut seen_slugs);
             let branch = format!("fusion/{}/{}", session_id, slug);
-            let worktree_path = project_path
-                .join(".hive-fusion")
-                .join(&session_id)
+            let worktree_path = worktree_root
                 .join(format!("variant-{}", slug))
                 .to_string_lossy()
                 .to_string();
@@ -8346,6 +12390,8 @@ phases and do EXACTLY this, then stop:
                 worktree_path,
                 task_file,
                 agent_id: format!("{}-fusion-{}", session_id, index),
+                attempts: 0,
+                failure: None,
             });
         }
 
@@ -8376,6 +12422,8 @@ phases and do EXACTLY this, then stop:
             worktree_branch: variants.first().map(|v| v.branch.clone()),
             no_git: false,
             resume_report: None,
+            pull_request_url: None,
+            spawn_quota: SpawnQuota::default(),
         };
 
         {
@@ -8384,190 +12432,451 @@ phases and do EXACTLY this, then stop:
         }
         self.emit_session_update(&session_id);
 
-        let fresh_base = resolve_fresh_base(&project_path);
+        // The base branch is created by `prepare_fusion_worktrees` along with the variant
+        // worktrees, so no git process runs while the caller holds the controller.
         let base_branch = format!("fusion/{}/base", session_id);
-        Self::run_git_in_dir(&project_path, &["branch", &base_branch, &fresh_base])?;
 
-        for (variant_idx, variant) in variants.iter().enumerate() {
-            let spawning_changes = {
-                let mut sessions = self.sessions.write();
-                if let Some(s) = sessions.get_mut(&session_id) {
-                    Some(self.set_session_state_with_events(
-                        s,
-                        SessionState::SpawningFusionVariant(variant.index),
-                    ))
-                } else {
-                    None
-                }
-            };
-            if let Some(changes) = spawning_changes {
-                self.emit_cell_status_changes(&session_id, changes);
-            }
-            self.emit_session_update(&session_id);
+        let spawn_variants = variants
+            .iter()
+            .zip(&config.variants)
+            .map(|(variant, source)| FusionVariantSpawn {
+                variant: variant.clone(),
+                agent_config: Self::fusion_variant_agent_config(
+                    source,
+                    &variant.name,
+                    &default_cli,
+                    config.default_model.as_ref(),
+                    &config.task_description,
+                ),
+            })
+            .collect();
+        let plan = FusionSpawnPlan {
+            session_id: session_id.clone(),
+            project_path: project_path.clone(),
+            base_branch: base_branch.clone(),
+            task_description: config.task_description.clone(),
+            variants: spawn_variants,
+            worktrees: None,
+        };
 
-            let worktree_path = PathBuf::from(&variant.worktree_path);
-            if let Some(parent) = worktree_path.parent() {
-                std::fs::create_dir_all(parent)
-                    .map_err(|e| format!("Failed to create worktree parent dir: {}", e))?;
-            }
+        let evaluation_dir = project_path
+            .join(".hive-manager")
+            .join(&session_id)
+            .join("evaluation");
+        std::fs::create_dir_all(&evaluation_dir)
+            .map_err(|e| format!("Failed to create fusion evaluation directory: {}", e))?;
 
-            Self::run_git_in_dir(
-                &project_path,
-                &[
-                    "worktree",
-                    "add",
-                    &variant.worktree_path,
-                    "-b",
-                    &variant.branch,
-                    &base_branch,
-                ],
-            )?;
-            self.emit_workspace_created(
-                &session_id,
-                &variant_to_cell_id(&variant.name),
-                &variant.branch,
-                Some(&variant.worktree_path),
-            );
+        let decision_file = project_path
+            .join(".hive-manager")
+            .join(&session_id)
+            .join("evaluation")
+            .join("decision.md")
+            .to_string_lossy()
+            .to_string();
 
-            Self::write_fusion_variant_task_file(
-                Path::new(&variant.worktree_path),
-                variant.index,
-                &variant.name,
-                &config.task_description,
-            )?;
+        let metadata = FusionSessionMetadata {
+            base_branch,
+            variants,
+            judge_config: config.judge_config,
+            task_description: config.task_description,
+            decision_file,
+            extra_judges: Vec::new(),
+            quorum: config.quorum,
+            max_variant_retries: config.max_variant_retries,
+            worktree_root: Some(worktree_root.to_string_lossy().to_string()),
+            base_commit: None,
+        };
+        Self::write_fusion_metadata(&project_path, &session_id, &metadata)?;
 
-            let source_variant = &config.variants[variant_idx];
-            let cli = if source_variant.cli.trim().is_empty() {
-                default_cli.clone()
-            } else {
-                source_variant.cli.trim().to_string()
-            };
-            let variant_agent_config = AgentConfig {
-                cli: cli.clone(),
-                model: source_variant
-                    .model
-                    .clone()
-                    .or(config.default_model.clone()),
-                flags: source_variant.flags.clone(),
-                label: Some(format!("Fusion {}", variant.name)),
-                name: None,
-                description: None,
-                role: None,
-                initial_prompt: Some(config.task_description.clone()),
-            };
+        let session = self
+            .get_session(&session_id)
+            .ok_or_else(|| "Failed to read fusion session after launch".to_string())?;
+        self.init_session_storage(&session);
+        self.update_session_storage(&session_id);
+        self.ensure_task_watcher(&session_id, &project_path);
 
-            let worker_prompt = Self::build_fusion_worker_prompt(
-                &session_id,
-                variant.index,
-                &variant.name,
-                &variant.branch,
-                &variant.worktree_path,
-                &config.task_description,
-                &cli,
-            );
-            let prompt_filename = format!("fusion-worker-{}-prompt.md", variant.index);
-            let prompt_file = Self::write_worker_prompt_file(
-                Path::new(&variant.worktree_path),
-                variant.index,
-                &prompt_filename,
-                &worker_prompt,
-            )?;
-            let prompt_path = prompt_file.to_string_lossy().to_string();
+        Ok((session, Some(plan)))
+    }
 
-            let (cmd, mut args) = Self::build_command(&variant_agent_config);
-            Self::add_prompt_to_args(&cmd, &mut args, &prompt_path);
+    fn fusion_variant_agent_config(
+        source: &FusionVariantConfig,
+        variant_name: &str,
+        default_cli: &str,
+        default_model: Option<&String>,
+        task_description: &str,
+    ) -> AgentConfig {
+        let cli = if source.cli.trim().is_empty() {
+            default_cli.to_string()
+        } else {
+            source.cli.trim().to_string()
+        };
+        AgentConfig {
+            cli,
+            model: source.model.clone().or(default_model.cloned()),
+            flags: source.flags.clone(),
+            label: Some(format!("Fusion {}", variant_name)),
+            name: None,
+            description: None,
+            role: None,
+            initial_prompt: Some(task_description.to_string()),
+            allowed_paths: Vec::new(),
+            denied_paths: Vec::new(),
+        }
+    }
 
-            tracing::info!(
-                "Launching Fusion variant {} ({}) on branch {} in {}",
-                variant.index,
-                variant.name,
-                variant.branch,
-                variant.worktree_path
+    /// Note in the coordination log that the project's uncommitted changes were stashed
+    /// before the launch, so the operator knows to restore them.
+    pub fn record_fusion_stash(&self, session_id: &str, stash_commit: &str) {
+        tracing::info!(
+            "Stashed uncommitted changes before Fusion session {} as {}",
+            session_id,
+            stash_commit
+        );
+        self.log_coordination_system(
+            session_id,
+            &format!(
+                "[FUSION] Uncommitted changes in the project were stashed before launch ({}); \
+                 restore them with `git stash pop` once the session is done",
+                stash_commit
+            ),
+        );
+    }
+
+    /// Create the Fusion base branch and every variant worktree with `tokio::process` git,
+    /// taking the controller lock only to emit events. A hung git command is killed after
+    /// `GIT_COMMAND_TIMEOUT` and fails its variant instead of freezing the controller.
+    pub async fn prepare_fusion_worktrees(
+        controller: &RwLock<SessionController>,
+        plan: &mut FusionSpawnPlan,
+    ) {
+        for job in &plan.variants {
+            controller.read().emit_fusion_variant_progress(
+                &plan.session_id,
+                &job.variant,
+                FusionVariantStage::CreatingWorktree,
+                None,
             );
+        }
 
-            {
-                let pty_manager = self.pty_manager.read();
-                pty_manager
-                    .create_session(
-                        variant.agent_id.clone(),
-                        AgentRole::Fusion {
-                            variant: variant.name.clone(),
-                        },
-                        &cmd,
-                        &args.iter().map(|s| s.as_str()).collect::<Vec<_>>(),
-                        Some(&variant.worktree_path),
-                        120,
-                        30,
+        // Branch from the exact commit the fresh base resolves to and record it, so the
+        // judges diff every variant against the same base.
+        let project_path = plan.project_path.clone();
+        let resolved = tokio::task::spawn_blocking(move || {
+            let fresh_base = resolve_fresh_base(&project_path);
+            resolve_commit(&project_path, &fresh_base)
+                .map_err(|e| format!("Failed to resolve Fusion base {}: {}", fresh_base, e))
+        })
+        .await;
+        let base = match resolved {
+            Ok(Ok(base_commit)) => run_git_async(
+                &plan.project_path,
+                &["branch", &plan.base_branch, &base_commit],
+                GIT_COMMAND_TIMEOUT,
+            )
+            .await
+            .map(|_| base_commit)
+            .map_err(|e| format!("Failed to create Fusion base branch: {}", e)),
+            Ok(Err(e)) => Err(e),
+            Err(e) => Err(format!("Failed to resolve Fusion base: {}", e)),
+        };
+        if let Ok(base_commit) = &base {
+            let pinned = Self::read_fusion_metadata(&plan.project_path, &plan.session_id)
+                .and_then(|mut metadata| {
+                    metadata.base_commit = Some(base_commit.clone());
+                    Self::write_fusion_metadata(&plan.project_path, &plan.session_id, &metadata)
+                });
+            if let Err(e) = pinned {
+                tracing::warn!(
+                    "Failed to record the Fusion base commit for {}: {}",
+                    plan.session_id,
+                    e
+                );
+            }
+        }
+
+        let worktrees: Vec<Result<(), String>> = match base {
+            Err(error) => vec![Err(error); plan.variants.len()],
+            Ok(_) => {
+                let plan = &*plan;
+                futures::stream::iter(plan.variants.iter().map(|job| async move {
+                    let variant = &job.variant;
+                    if let Some(parent) = Path::new(&variant.worktree_path).parent() {
+                        tokio::fs::create_dir_all(parent)
+                            .await
+                            .map_err(|e| format!("Failed to create worktree parent dir: {}", e))?;
+                    }
+                    run_git_async(
+                        &plan.project_path,
+                        &[
+                            "worktree",
+                            "add",
+                            &variant.worktree_path,
+                            "-b",
+                            &variant.branch,
+                            &plan.base_branch,
+                        ],
+                        GIT_COMMAND_TIMEOUT,
                     )
-                    .map_err(|e| {
-                        format!("Failed to spawn Fusion variant {}: {}", variant.name, e)
-                    })?;
+                    .await
+                    .map(|_| ())
+                }))
+                .buffered(FUSION_SPAWN_CONCURRENCY)
+                .collect()
+                .await
+            }
+        };
+
+        {
+            let controller = controller.read();
+            for (job, result) in plan.variants.iter().zip(&worktrees) {
+                if result.is_ok() {
+                    controller.emit_workspace_created(
+                        &plan.session_id,
+                        &variant_to_cell_id(&job.variant.name),
+                        &job.variant.branch,
+                        Some(&job.variant.worktree_path),
+                    );
+                }
+            }
+        }
+        plan.worktrees = Some(worktrees);
+    }
+
+    /// Bring up every variant in `plan` (worktree, task file, prompt, CLI) with at most
+    /// `FUSION_SPAWN_CONCURRENCY` in flight. A variant that fails to come up is recorded
+    /// as failed rather than aborting its siblings; the session then waits on the rest,
+    /// or fails straight away when too few variants are left to meet the quorum.
+    pub fn spawn_fusion_variants(&self, plan: &FusionSpawnPlan) -> Result<Session, String> {
+        // Scoped threads have no tokio context of their own; the controller's event
+        // helpers spawn onto the runtime, so carry the caller's handle across.
+        let runtime = tokio::runtime::Handle::try_current().ok();
+        let next = std::sync::atomic::AtomicUsize::new(0);
+        let results: Mutex<Vec<Option<Result<AgentInfo, String>>>> =
+            Mutex::new(vec![None; plan.variants.len()]);
+        let workers = plan.variants.len().min(FUSION_SPAWN_CONCURRENCY);
+
+        std::thread::scope(|scope| {
+            for _ in 0..workers {
+                scope.spawn(|| {
+                    let _runtime = runtime.as_ref().map(|handle| handle.enter());
+                    loop {
+                        let slot = next.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+                        let Some(job) = plan.variants.get(slot) else {
+                            break;
+                        };
+                        let result = self.spawn_fusion_variant(plan, slot, job);
+                        results.lock()[slot] = Some(result);
+                    }
+                });
             }
+        });
+
+        let mut metadata = Self::read_fusion_metadata(&plan.project_path, &plan.session_id)?;
+        let mut launched = Vec::new();
+        let mut agents = Vec::new();
+        for (job, result) in plan.variants.iter().zip(results.into_inner()) {
+            let variant = &job.variant;
+            let result = result.unwrap_or_else(|| Err("Variant spawn did not run".to_string()));
+            let error = match result {
+                Ok(agent) => {
+                    self.emit_fusion_variant_progress(
+                        &plan.session_id,
+                        variant,
+                        FusionVariantStage::Running,
+                        None,
+                    );
+                    launched.push(agent.clone());
+                    agents.push(agent);
+                    continue;
+                }
+                Err(error) => error,
+            };
 
-            let agent_info = AgentInfo {
+            tracing::warn!("Fusion variant {} failed to start: {}", variant.name, error);
+            self.emit_fusion_variant_progress(
+                &plan.session_id,
+                variant,
+                FusionVariantStage::Failed,
+                Some(&error),
+            );
+            if let Some(entry) = metadata
+                .variants
+                .iter_mut()
+                .find(|v| v.index == variant.index)
+            {
+                entry.failure = Some(error.clone());
+            }
+            // Keep the agent (in Error) so the variant can be retried with its config.
+            agents.push(AgentInfo {
                 id: variant.agent_id.clone(),
                 role: AgentRole::Fusion {
                     variant: variant.name.clone(),
                 },
-                status: AgentStatus::Running,
-                config: variant_agent_config,
+                status: AgentStatus::Error(error),
+                config: job.agent_config.clone(),
                 parent_id: None,
                 commit_sha: None,
                 base_commit_sha: None,
-            };
+                exit_info: None,
+            });
+        }
+        Self::write_fusion_metadata(&plan.project_path, &plan.session_id, &metadata)?;
 
-            let waiting_changes =
-                {
-                    let mut sessions = self.sessions.write();
-                    if let Some(s) = sessions.get_mut(&session_id) {
-                        s.agents.push(agent_info.clone());
-                        self.emit_agent_launched(s, &agent_info);
-                        Some(self.set_session_state_with_events(
-                            s,
-                            SessionState::WaitingForFusionVariants,
-                        ))
-                    } else {
-                        None
-                    }
-                };
-            if let Some(changes) = waiting_changes {
-                self.emit_cell_status_changes(&session_id, changes);
+        let waiting_changes = {
+            let mut sessions = self.sessions.write();
+            let s = sessions
+                .get_mut(&plan.session_id)
+                .ok_or_else(|| "Session disappeared".to_string())?;
+            s.agents.extend(agents);
+            self.emit_agent_batch_launched(s, &launched);
+            self.set_session_state_with_events(s, SessionState::WaitingForFusionVariants)
+        };
+        self.emit_cell_status_changes(&plan.session_id, waiting_changes);
+        self.emit_session_update(&plan.session_id);
+        self.update_session_storage(&plan.session_id);
+
+        self.maybe_start_fusion_judging(&plan.session_id, &metadata)?;
+
+        self.get_session(&plan.session_id)
+            .ok_or_else(|| "Failed to read fusion session after spawning variants".to_string())
+    }
+
+    fn spawn_fusion_variant(
+        &self,
+        plan: &FusionSpawnPlan,
+        slot: usize,
+        job: &FusionVariantSpawn,
+    ) -> Result<AgentInfo, String> {
+        let variant = &job.variant;
+        let worktree_path = PathBuf::from(&variant.worktree_path);
+        match plan
+            .worktrees
+            .as_ref()
+            .and_then(|worktrees| worktrees.get(slot))
+        {
+            Some(prepared) => prepared.clone()?,
+            None => {
+                self.emit_fusion_variant_progress(
+                    &plan.session_id,
+                    variant,
+                    FusionVariantStage::CreatingWorktree,
+                    None,
+                );
+                if let Some(parent) = worktree_path.parent() {
+                    std::fs::create_dir_all(parent)
+                        .map_err(|e| format!("Failed to create worktree parent dir: {}", e))?;
+                }
+                Self::run_git_in_dir(
+                    &plan.project_path,
+                    &[
+                        "worktree",
+                        "add",
+                        &variant.worktree_path,
+                        "-b",
+                        &variant.branch,
+                        &plan.base_branch,
+                    ],
+                )?;
+                self.emit_workspace_created(
+                    &plan.session_id,
+                    &variant_to_cell_id(&variant.name),
+                    &variant.branch,
+                    Some(&variant.worktree_path),
+                );
             }
-            self.emit_session_update(&session_id);
         }
 
-        let evaluation_dir = project_path
-            .join(".hive-manager")
-            .join(&session_id)
-            .join("evaluation");
-        std::fs::create_dir_all(&evaluation_dir)
-            .map_err(|e| format!("Failed to create fusion evaluation directory: {}", e))?;
+        Self::write_fusion_variant_task_file(
+            &worktree_path,
+            variant.index,
+            &variant.name,
+            &plan.task_description,
+        )?;
 
-        let decision_file = project_path
-            .join(".hive-manager")
-            .join(&session_id)
-            .join("evaluation")
-            .join("decision.md")
-            .to_string_lossy()
-            .to_string();
+        self.emit_fusion_variant_progress(
+            &plan.session_id,
+            variant,
+            FusionVariantStage::Spawning,
+            None,
+        );
+        let worker_prompt = Self::build_fusion_worker_prompt(
+            &plan.session_id,
+            variant.index,
+            &variant.name,
+            &variant.branch,
+            &variant.worktree_path,
+            &plan.task_description,
+            &job.agent_config.cli,
+        );
+        let prompt_file = Self::write_worker_prompt_file(
+            &worktree_path,
+            variant.index,
+            &format!("fusion-worker-{}-prompt.md", variant.index),
+            &self.with_session_api_token(&plan.session_id, &worker_prompt),
+        )?;
+        let prompt_path = prompt_file.to_string_lossy().to_string();
 
-        let metadata = FusionSessionMetadata {
-            base_branch,
-            variants: variants.clone(),
-            judge_config: config.judge_config,
-            task_description: config.task_description,
-            decision_file,
-        };
-        Self::write_fusion_metadata(&project_path, &session_id, &metadata)?;
+        let (cmd, mut args) = Self::build_command(&job.agent_config);
+        Self::add_prompt_to_args(&cmd, &mut args, &prompt_path);
 
-        let session = self
-            .get_session(&session_id)
-            .ok_or_else(|| "Failed to read fusion session after launch".to_string())?;
-        self.init_session_storage(&session);
-        self.update_session_storage(&session_id);
-        self.ensure_task_watcher(&session_id, &project_path);
+        tracing::info!(
+            "Launching Fusion variant {} ({}) on branch {} in {}",
+            variant.index,
+            variant.name,
+            variant.branch,
+            variant.worktree_path
+        );
 
-        Ok(session)
+        {
+            let pty_manager = self.pty_manager.read();
+            pty_manager
+                .create_session(
+                    variant.agent_id.clone(),
+                    AgentRole::Fusion {
+                        variant: variant.name.clone(),
+                    },
+                    &cmd,
+                    &args.iter().map(|s| s.as_str()).collect::<Vec<_>>(),
+                    Some(&variant.worktree_path),
+                    120,
+                    30,
+                )
+                .map_err(|e| format!("Failed to spawn Fusion variant {}: {}", variant.name, e))?;
+        }
+
+        Ok(AgentInfo {
+            id: variant.agent_id.clone(),
+            role: AgentRole::Fusion {
+                variant: variant.name.clone(),
+            },
+            status: AgentStatus::Running,
+            config: job.agent_config.clone(),
+            parent_id: None,
+            commit_sha: None,
+            base_commit_sha: None,
+            exit_info: None,
+        })
+    }
+
+    fn emit_fusion_variant_progress(
+        &self,
+        session_id: &str,
+        variant: &FusionVariantMetadata,
+        stage: FusionVariantStage,
+        error: Option<&str>,
+    ) {
+        if let Some(ref app_handle) = self.app_handle {
+            let _ = app_handle.emit(
+                "fusion-variant-progress",
+                FusionVariantProgress {
+                    session_id: session_id.to_string(),
+                    variant_index: variant.index,
+                    variant_name: variant.name.clone(),
+                    stage,
+                    error: error.map(str::to_string),
+                },
+            );
+        }
     }
 
     pub fn launch_debate(&self, mut config: DebateLaunchConfig) -> Result<Session, String> {
@@ -8588,11 +12897,15 @@ phases and do EXACTLY this, then stop:
         }
 
         if config.with_planning {
-            let session_id = Uuid::new_v4().to_string();
+            let session_id = launch_queue::new_session_id();
+            self.track_phase_timeouts(&session_id, config.phase_timeouts);
+            self.track_background(&session_id, config.background);
             return self.launch_debate_planning_phase(session_id, config);
         }
 
-        let session_id = Uuid::new_v4().to_string();
+        let session_id = launch_queue::new_session_id();
+        self.track_phase_timeouts(&session_id, config.phase_timeouts);
+        self.track_background(&session_id, config.background);
         let project_path = PathBuf::from(&config.project_path);
         let default_cli = if config.default_cli.trim().is_empty() {
             "claude".to_string()
@@ -8629,6 +12942,8 @@ phases and do EXACTLY this, then stop:
             worktree_branch: debaters.first().map(|d| d.branch.clone()),
             no_git: false,
             resume_report: None,
+            pull_request_url: None,
+            spawn_quota: SpawnQuota::default(),
         };
 
         {
@@ -8725,6 +13040,8 @@ phases and do EXACTLY this, then stop:
                     description: debater.stance.clone(),
                     role: None,
                     initial_prompt: Some(config.topic.clone()),
+                    allowed_paths: Vec::new(),
+                    denied_paths: Vec::new(),
                 };
 
                 DebateDebaterMetadata {
@@ -8798,7 +13115,7 @@ phases and do EXACTLY this, then stop:
                     round - 1,
                     &debater.slug,
                 );
-                format!("- {}: `{}`", debater.name, Self::prompt_path(&path))
+                format!("- {}: `{}`", debater.name, paths::prompt_path(&path))
             })
             .collect::<Vec<_>>()
             .join("\n")
@@ -8842,6 +13159,7 @@ phases and do EXACTLY this, then stop:
             .and_then(|cfg| cfg.global_wiki_path)
             .unwrap_or_default();
         let global_wiki_path = expand_tilde(&global_wiki_path);
+        let engine = self.template_engine();
 
         let mut new_agents = Vec::new();
         for debater in &metadata.debaters {
@@ -8884,6 +13202,7 @@ phases and do EXACTLY this, then stop:
                 &opponent_files,
             )?;
             let prompt = Self::build_debate_debater_prompt(
+                &engine,
                 session_id,
                 debater,
                 &metadata.topic,
@@ -8901,7 +13220,7 @@ phases and do EXACTLY this, then stop:
                 &worktree_path,
                 debater.index,
                 &prompt_filename,
-                &prompt,
+                &self.with_session_api_token(session_id, &prompt),
             )?;
             let prompt_path = prompt_file.to_string_lossy().to_string();
 
@@ -8942,6 +13261,7 @@ phases and do EXACTLY this, then stop:
                 parent_id: None,
                 commit_sha: None,
                 base_commit_sha: None,
+                exit_info: None,
             });
         }
 
@@ -8987,11 +13307,12 @@ phases and do EXACTLY this, then stop:
         )
         .map_err(|error| error.to_string())?;
         let mut created_cells = Vec::new();
-        let (workspace_cell, branch) = if topology.uses_shared_cell() {
+        let (workspace_cell, default_branch) = if topology.uses_shared_cell() {
             ("primary", format!("hive/{}/primary", session_id))
         } else {
             ("queen", format!("hive/{}/queen", session_id))
         };
+        let branch = Self::resolve_session_branch(config.branch_name.as_deref(), default_branch)?;
         let base_ref = resolve_fresh_base(&project_path);
         let (_, cwd) = create_session_worktree(
             &session_id,
@@ -9058,7 +13379,7 @@ phases and do EXACTLY this, then stop:
                 &project_path,
                 &session_id,
                 "master-planner-prompt.md",
-                &planner_prompt,
+                &self.with_session_api_token(&session_id, &planner_prompt),
             ) {
                 Ok(prompt_file) => prompt_file,
                 Err(error) => {
@@ -9106,6 +13427,7 @@ phases and do EXACTLY this, then stop:
                 parent_id: None,
                 commit_sha: None,
                 base_commit_sha: None,
+                exit_info: None,
             });
         }
 
@@ -9138,6 +13460,8 @@ phases and do EXACTLY this, then stop:
             worktree_branch,
             no_git: false,
             resume_report: None,
+            pull_request_url: None,
+            spawn_quota: config.spawn_quota.clone(),
         };
 
         {
@@ -9189,7 +13513,7 @@ phases and do EXACTLY this, then stop:
                 &project_path,
                 &session_id,
                 "master-planner-prompt.md",
-                &planner_prompt,
+                &self.with_session_api_token(&session_id, &planner_prompt),
             )?;
             let prompt_path = prompt_file.to_string_lossy().to_string();
             Self::add_prompt_to_args(&cmd, &mut args, &prompt_path);
@@ -9221,6 +13545,7 @@ phases and do EXACTLY this, then stop:
                 parent_id: None,
                 commit_sha: None,
                 base_commit_sha: None,
+                exit_info: None,
             });
         }
 
@@ -9268,6 +13593,8 @@ phases and do EXACTLY this, then stop:
             worktree_branch: None,
             no_git: false,
             resume_report: None,
+            pull_request_url: None,
+            spawn_quota: SpawnQuota::default(),
         };
 
         {
@@ -9319,7 +13646,7 @@ phases and do EXACTLY this, then stop:
                 &project_path,
                 &session_id,
                 "master-planner-prompt.md",
-                &planner_prompt,
+                &self.with_session_api_token(&session_id, &planner_prompt),
             )?;
             let prompt_path = prompt_file.to_string_lossy().to_string();
             Self::add_prompt_to_args(&cmd, &mut args, &prompt_path);
@@ -9351,6 +13678,7 @@ phases and do EXACTLY this, then stop:
                 parent_id: None,
                 commit_sha: None,
                 base_commit_sha: None,
+                exit_info: None,
             });
         }
 
@@ -9397,6 +13725,8 @@ phases and do EXACTLY this, then stop:
             worktree_branch: None,
             no_git: false,
             resume_report: None,
+            pull_request_url: None,
+            spawn_quota: SpawnQuota::default(),
         };
 
         {
@@ -9422,11 +13752,14 @@ phases and do EXACTLY this, then stop:
     }
 
     /// Continue a Fusion session after planning phase - spawns Queen + Variants
+    /// Spawn the Fusion Queen once the plan is approved and return the variant spawn
+    /// work, which goes through `prepare_fusion_worktrees` and `spawn_fusion_variants`
+    /// like a direct launch's, so no git runs while the caller holds the controller.
     fn continue_fusion_after_planning(
         &self,
         session_id: &str,
         session: &Session,
-    ) -> Result<Session, String> {
+    ) -> Result<(Session, FusionSpawnPlan), String> {
         let cwd = session.project_path.to_str().unwrap_or(".");
 
         // Load the pending Fusion config
@@ -9462,6 +13795,7 @@ phases and do EXACTLY this, then stop:
         };
 
         // Build variant metadata (same logic as launch_fusion)
+        let worktree_root = self.fusion_worktree_root(&session.project_path, session_id);
         let mut seen_slugs: HashMap<String, u16> = HashMap::new();
         let mut variants = Vec::new();
 
@@ -9474,10 +13808,7 @@ phases and do EXACTLY this, then stop:
             };
             let slug = Self::unique_variant_slug(&name, &mut seen_slugs);
             let branch = format!("fusion/{}/{}", session_id, slug);
-            let worktree_path = session
-                .project_path
-                .join(".hive-fusion")
-                .join(session_id)
+            let worktree_path = worktree_root
                 .join(format!("variant-{}", slug))
                 .to_string_lossy()
                 .to_string();
@@ -9494,16 +13825,13 @@ phases and do EXACTLY this, then stop:
                 worktree_path,
                 task_file,
                 agent_id: format!("{}-fusion-{}", session_id, index),
+                attempts: 0,
+                failure: None,
             });
         }
 
-        // Create git base branch and worktrees
-        let fresh_base = resolve_fresh_base(&session.project_path);
+        // `prepare_fusion_worktrees` creates and pins the base branch with the worktrees.
         let base_branch = format!("fusion/{}/base", session_id);
-        Self::run_git_in_dir(
-            &session.project_path,
-            &["branch", &base_branch, &fresh_base],
-        )?;
 
         let mut new_agents = Vec::new();
 
@@ -9531,7 +13859,7 @@ phases and do EXACTLY this, then stop:
                 &session.project_path,
                 session_id,
                 "fusion-queen-prompt.md",
-                &queen_prompt,
+                &self.with_session_api_token(session_id, &queen_prompt),
             )?;
             let prompt_path = prompt_file.to_string_lossy().to_string();
             Self::add_prompt_to_args(&cmd, &mut args, &prompt_path);
@@ -9565,120 +13893,7 @@ phases and do EXACTLY this, then stop:
                 parent_id: None,
                 commit_sha: None,
                 base_commit_sha: None,
-            });
-        }
-
-        // Spawn variants (same logic as launch_fusion)
-        for (variant_idx, variant) in variants.iter().enumerate() {
-            let worktree_path = PathBuf::from(&variant.worktree_path);
-            if let Some(parent) = worktree_path.parent() {
-                std::fs::create_dir_all(parent)
-                    .map_err(|e| format!("Failed to create worktree parent dir: {}", e))?;
-            }
-
-            Self::run_git_in_dir(
-                &session.project_path,
-                &[
-                    "worktree",
-                    "add",
-                    &variant.worktree_path,
-                    "-b",
-                    &variant.branch,
-                    &base_branch,
-                ],
-            )?;
-            self.emit_workspace_created(
-                session_id,
-                &variant_to_cell_id(&variant.name),
-                &variant.branch,
-                Some(&variant.worktree_path),
-            );
-
-            Self::write_fusion_variant_task_file(
-                Path::new(&variant.worktree_path),
-                variant.index,
-                &variant.name,
-                &config.task_description,
-            )?;
-
-            let source_variant = &config.variants[variant_idx];
-            let cli = if source_variant.cli.trim().is_empty() {
-                default_cli.clone()
-            } else {
-                source_variant.cli.trim().to_string()
-            };
-            let variant_agent_config = AgentConfig {
-                cli: cli.clone(),
-                model: source_variant
-                    .model
-                    .clone()
-                    .or(config.default_model.clone()),
-                flags: source_variant.flags.clone(),
-                label: Some(format!("Fusion {}", variant.name)),
-                name: None,
-                description: None,
-                role: None,
-                initial_prompt: Some(config.task_description.clone()),
-            };
-
-            let worker_prompt = Self::build_fusion_worker_prompt(
-                session_id,
-                variant.index,
-                &variant.name,
-                &variant.branch,
-                &variant.worktree_path,
-                &config.task_description,
-                &cli,
-            );
-            let prompt_filename = format!("fusion-worker-{}-prompt.md", variant.index);
-            let prompt_file = Self::write_worker_prompt_file(
-                Path::new(&variant.worktree_path),
-                variant.index,
-                &prompt_filename,
-                &worker_prompt,
-            )?;
-            let prompt_path = prompt_file.to_string_lossy().to_string();
-
-            let (cmd, mut args) = Self::build_command(&variant_agent_config);
-            Self::add_prompt_to_args(&cmd, &mut args, &prompt_path);
-
-            tracing::info!(
-                "Launching Fusion variant {} ({}) on branch {} in {}",
-                variant.index,
-                variant.name,
-                variant.branch,
-                variant.worktree_path
-            );
-
-            {
-                let pty_manager = self.pty_manager.read();
-                pty_manager
-                    .create_session(
-                        variant.agent_id.clone(),
-                        AgentRole::Fusion {
-                            variant: variant.name.clone(),
-                        },
-                        &cmd,
-                        &args.iter().map(|s| s.as_str()).collect::<Vec<_>>(),
-                        Some(&variant.worktree_path),
-                        120,
-                        30,
-                    )
-                    .map_err(|e| {
-                        format!("Failed to spawn Fusion variant {}: {}", variant.name, e)
-                    })?;
-            }
-
-            new_agents.push(AgentInfo {
-                id: variant.agent_id.clone(),
-                role: AgentRole::Fusion {
-                    variant: variant.name.clone(),
-                },
-                status: AgentStatus::Running,
-                config: variant_agent_config,
-                parent_id: None,
-                commit_sha: None,
-                base_commit_sha: None,
+                exit_info: None,
             });
         }
 
@@ -9700,50 +13915,72 @@ phases and do EXACTLY this, then stop:
             .to_string_lossy()
             .to_string();
 
+        let plan = FusionSpawnPlan {
+            session_id: session_id.to_string(),
+            project_path: session.project_path.clone(),
+            base_branch: base_branch.clone(),
+            task_description: config.task_description.clone(),
+            variants: variants
+                .iter()
+                .zip(&config.variants)
+                .map(|(variant, source)| FusionVariantSpawn {
+                    variant: variant.clone(),
+                    agent_config: Self::fusion_variant_agent_config(
+                        source,
+                        &variant.name,
+                        &default_cli,
+                        config.default_model.as_ref(),
+                        &config.task_description,
+                    ),
+                })
+                .collect(),
+            worktrees: None,
+        };
+
         let metadata = FusionSessionMetadata {
             base_branch,
             variants: variants.clone(),
             judge_config: config.judge_config.clone(),
             task_description: config.task_description,
             decision_file,
+            extra_judges: Vec::new(),
+            quorum: config.quorum,
+            max_variant_retries: config.max_variant_retries,
+            worktree_root: Some(worktree_root.to_string_lossy().to_string()),
+            base_commit: None,
         };
         Self::write_fusion_metadata(&session.project_path, session_id, &metadata)?;
 
-        // Update session with new agents and Running state
-        let (updated_session, changes) = {
+        // Register the Queen and leave planning, so the plan cannot be continued twice
+        // while the variants come up; spawning them moves the session on to
+        // WaitingForFusionVariants.
+        let starting_changes = {
             let mut sessions = self.sessions.write();
-            if let Some(s) = sessions.get_mut(session_id) {
-                s.agents.extend(new_agents.clone());
-                if let Some(v) = variants.first() {
-                    s.worktree_path = Some(v.worktree_path.clone());
-                    s.worktree_branch = Some(v.branch.clone());
-                }
-                self.emit_agent_batch_launched(s, &new_agents);
-                let changes =
-                    self.set_session_state_with_events(s, SessionState::WaitingForFusionVariants);
-                (s.clone(), changes)
-            } else {
-                return Err("Session disappeared".to_string());
+            let s = sessions
+                .get_mut(session_id)
+                .ok_or_else(|| "Session disappeared".to_string())?;
+            s.agents.extend(new_agents.clone());
+            if let Some(v) = variants.first() {
+                s.worktree_path = Some(v.worktree_path.clone());
+                s.worktree_branch = Some(v.branch.clone());
             }
+            self.emit_agent_batch_launched(s, &new_agents);
+            self.set_session_state_with_events(s, SessionState::Starting)
         };
-
-        if let Some(ref app_handle) = self.app_handle {
-            let _ = app_handle.emit(
-                "session-update",
-                SessionUpdate {
-                    session: updated_session.clone(),
-                },
-            );
-        }
-
+        self.emit_cell_status_changes(session_id, starting_changes);
+        self.emit_session_update(session_id);
         self.update_session_storage(session_id);
-        self.emit_cell_status_changes(session_id, changes);
-        self.ensure_task_watcher(session_id, &updated_session.project_path);
+        // The watcher started during planning predates the recorded worktree root.
+        self.task_watchers.lock().remove(session_id);
+        self.ensure_task_watcher(session_id, &session.project_path);
 
         // Clean up pending config
         let _ = std::fs::remove_file(&pending_config_path);
 
-        Ok(updated_session)
+        let updated_session = self
+            .get_session(session_id)
+            .ok_or_else(|| "Session disappeared".to_string())?;
+        Ok((updated_session, plan))
     }
 
     fn continue_debate_after_planning(
@@ -9909,7 +14146,7 @@ phases and do EXACTLY this, then stop:
                 &project_path,
                 &session_id,
                 "master-planner-prompt.md",
-                &planner_prompt,
+                &self.with_session_api_token(&session_id, &planner_prompt),
             )?;
             let prompt_path = prompt_file.to_string_lossy().to_string();
             Self::add_prompt_to_args(&cmd, &mut args, &prompt_path);
@@ -9941,6 +14178,7 @@ phases and do EXACTLY this, then stop:
                 parent_id: None,
                 commit_sha: None,
                 base_commit_sha: None,
+                exit_info: None,
             });
         }
 
@@ -9961,56 +14199,181 @@ phases and do EXACTLY this, then stop:
             id: session_id.clone(),
             name: config.name.clone(),
             color: config.color.clone(),
-            session_type: SessionType::Swarm {
-                planner_count: if config.planners.is_empty() {
-                    config.planner_count
-                } else {
-                    config.planners.len() as u8
-                },
-            },
-            project_path,
-            state: SessionState::Planning,
+            session_type: SessionType::Swarm {
+                planner_count: if config.planners.is_empty() {
+                    config.planner_count
+                } else {
+                    config.planners.len() as u8
+                },
+            },
+            project_path,
+            state: SessionState::Planning,
+            created_at: Utc::now(),
+            last_activity_at: Utc::now(),
+            agents,
+            default_cli,
+            default_model,
+            default_principal_cli: None,
+            default_principal_model: None,
+            default_principal_flags: Vec::new(),
+            execution_policy: HiveExecutionPolicy::default(),
+            qa_workers: config.qa_workers.clone().unwrap_or_default(),
+            max_qa_iterations,
+            qa_timeout_secs,
+            auth_strategy,
+            worktree_path: None,
+            worktree_branch: None,
+            no_git: false,
+            resume_report: None,
+            pull_request_url: None,
+            spawn_quota: SpawnQuota::default(),
+        };
+
+        {
+            let mut sessions = self.sessions.write();
+            sessions.insert(session_id.clone(), session.clone());
+        }
+
+        if let Some(ref app_handle) = self.app_handle {
+            let _ = app_handle.emit(
+                "session-update",
+                SessionUpdate {
+                    session: session.clone(),
+                },
+            );
+        }
+
+        self.init_session_storage(&session);
+        self.ensure_task_watcher(&session.id, &session.project_path);
+
+        Ok(session)
+    }
+
+    /// Launch a Hive without a Queen whose workers idle instead of running a CLI,
+    /// and play them from the backend to check the watcher -> `worker-completed` ->
+    /// sequential spawn chain (see `smoke_probe`).
+    fn launch_smoke_probe(&self, config: HiveLaunchConfig) -> Result<Session, String> {
+        let worker_count = match u8::try_from(config.workers.len()) {
+            Ok(0) => return Err("A smoke probe needs at least one worker".to_string()),
+            Ok(count) => count,
+            Err(_) => return Err("Too many workers for a smoke probe".to_string()),
+        };
+        let session_id = launch_queue::new_session_id();
+        let project_path = PathBuf::from(&config.project_path);
+
+        // `on_worker_completed` reads the workers to spawn from here, as after planning.
+        let pending_config_path =
+            Self::session_root_path(&project_path, &session_id).join("pending-config.json");
+        std::fs::create_dir_all(Self::session_root_path(&project_path, &session_id))
+            .map_err(|e| format!("Failed to create session directory: {}", e))?;
+        let config_json = serde_json::to_string_pretty(&config)
+            .map_err(|e| format!("Failed to serialize config: {}", e))?;
+        std::fs::write(&pending_config_path, config_json)
+            .map_err(|e| format!("Failed to write pending config: {}", e))?;
+
+        let (max_qa_iterations, qa_timeout_secs, auth_strategy) = default_session_qa_settings();
+        let session = Session {
+            id: session_id.clone(),
+            name: config.name.clone(),
+            color: config.color.clone(),
+            session_type: SessionType::Hive { worker_count },
+            project_path: project_path.clone(),
+            state: SessionState::Starting,
             created_at: Utc::now(),
             last_activity_at: Utc::now(),
-            agents,
-            default_cli,
-            default_model,
+            agents: Vec::new(),
+            default_cli: config.queen_config.cli.clone(),
+            default_model: config.queen_config.model.clone(),
             default_principal_cli: None,
             default_principal_model: None,
             default_principal_flags: Vec::new(),
-            execution_policy: HiveExecutionPolicy::default(),
-            qa_workers: config.qa_workers.clone().unwrap_or_default(),
+            execution_policy: config.execution_policy.clone(),
+            qa_workers: Vec::new(),
             max_qa_iterations,
             qa_timeout_secs,
             auth_strategy,
             worktree_path: None,
             worktree_branch: None,
             no_git: false,
+            pull_request_url: None,
+            spawn_quota: SpawnQuota::default(),
             resume_report: None,
         };
+        self.sessions
+            .write()
+            .insert(session_id.clone(), session.clone());
+        self.init_session_storage(&session);
+        self.ensure_task_watcher(&session_id, &project_path);
 
-        {
-            let mut sessions = self.sessions.write();
-            sessions.insert(session_id.clone(), session.clone());
+        let queen_id = format!("{}-queen", session_id);
+        if let Err(e) = self.spawn_next_worker(&session_id, 0, &config, &queen_id) {
+            self.sessions.write().remove(&session_id);
+            self.task_watchers.lock().remove(&session_id);
+            if let Some(storage) = self.storage.as_ref() {
+                let _ = storage.delete_session(&session_id);
+            }
+            let _ = std::fs::remove_file(&pending_config_path);
+            return Err(e.to_string());
         }
+        self.log_coordination_system(
+            &session_id,
+            &format!("Smoke probe started with {} synthetic workers", worker_count),
+        );
+        self.start_smoke_probe(&session_id, &project_path, worker_count);
 
-        if let Some(ref app_handle) = self.app_handle {
-            let _ = app_handle.emit(
-                "session-update",
-                SessionUpdate {
-                    session: session.clone(),
+        self.get_session(&session_id)
+            .ok_or_else(|| format!("Session disappeared after launch: {}", session_id))
+    }
+
+    /// Play a smoke probe's workers on a background thread, then write the report
+    /// next to the session's other files and its summary to the coordination log.
+    fn start_smoke_probe(&self, session_id: &str, project_path: &Path, workers: u8) {
+        let sessions = Arc::clone(&self.sessions);
+        let storage = self.storage.clone();
+        let app_handle = self.app_handle.clone();
+        let session_id = session_id.to_string();
+        let project_path = project_path.to_path_buf();
+
+        std::thread::spawn(move || {
+            let report = smoke_probe::run(
+                workers,
+                |index| {
+                    Self::absolute_task_file_path_for_worker(
+                        &project_path,
+                        &session_id,
+                        usize::from(index),
+                    )
                 },
+                || sessions.read().get(&session_id).map(|session| session.state.clone()),
+                smoke_probe::STEP_DELAY,
+                smoke_probe::STEP_TIMEOUT,
             );
-        }
-
-        self.init_session_storage(&session);
-        self.ensure_task_watcher(&session.id, &session.project_path);
+            let summary = report.summary();
+            tracing::info!("{} for session {}", summary, session_id);
+
+            let report_path =
+                Self::session_root_path(&project_path, &session_id).join(smoke_probe::REPORT_FILE);
+            let written = serde_json::to_string_pretty(&report)
+                .map_err(|e| e.to_string())
+                .and_then(|json| std::fs::write(&report_path, json).map_err(|e| e.to_string()));
+            if let Err(e) = written {
+                tracing::warn!("Failed to write the smoke probe report: {}", e);
+            }
 
-        Ok(session)
+            let Some(storage) = storage else {
+                return;
+            };
+            let message = CoordinationMessage::system("LOG", &summary);
+            if let Err(e) = storage.append_coordination_log(&session_id, &message) {
+                tracing::warn!("Failed to log the smoke probe summary: {}", e);
+            } else if let Some(app_handle) = app_handle {
+                let _ = app_handle.emit("coordination-message", &message);
+            }
+        });
     }
 
     /// Spawn the next worker sequentially
-    async fn spawn_next_worker(
+    fn spawn_next_worker(
         &self,
         session_id: &str,
         worker_index: usize,
@@ -10159,12 +14522,14 @@ phases and do EXACTLY this, then stop:
             &session.project_path,
             Path::new(&worker_cwd),
             &session.execution_policy,
+            self.worker_project_dna(session_id, &session.project_path)
+                .as_deref(),
         );
         let prompt_file = Self::write_worker_prompt_file(
             Path::new(&worker_cwd),
             index,
             &filename,
-            &worker_prompt,
+            &self.with_session_api_token(session_id, &worker_prompt),
         )
         .map_err(|err| {
             Self::rollback_worker_launch_artifacts(
@@ -10180,9 +14545,14 @@ phases and do EXACTLY this, then stop:
         })?;
         let prompt_path = prompt_file.to_string_lossy().to_string();
 
-        // 4. Build command with prompt
-        let (cmd, mut args) = Self::build_command(worker_config);
-        Self::add_prompt_to_args(&cmd, &mut args, &prompt_path);
+        // 4. Build command with prompt; a smoke probe's workers only idle
+        let (cmd, args) = if config.smoke_probe {
+            smoke_probe::idle_command()
+        } else {
+            let (cmd, mut args) = Self::build_command(worker_config);
+            Self::add_prompt_to_args(&cmd, &mut args, &prompt_path);
+            (cmd, args)
+        };
 
         // 5. Spawn the worker (use worker_cwd as PTY cwd)
         let pty_manager = self.pty_manager.read();
@@ -10227,6 +14597,7 @@ phases and do EXACTLY this, then stop:
                     parent_id: Some(queen_id.to_string()),
                     commit_sha: None,
                     base_commit_sha: Some(worker_base_commit_sha.clone()),
+                    exit_info: None,
                 };
                 s.agents.push(agent.clone());
                 self.emit_agent_launched(s, &agent);
@@ -10535,6 +14906,8 @@ phases and do EXACTLY this, then stop:
                     description: None,
                     role: None,
                     initial_prompt: None,
+                    allowed_paths: Vec::new(),
+                    denied_paths: Vec::new(),
                 };
                 if let Err(err) = self.launch_prince(session_id, prince_config, false) {
                     tracing::warn!(
@@ -10711,6 +15084,12 @@ phases and do EXACTLY this, then stop:
 
         // Verify we're in sequential mode and this is the expected worker
         if session.state != SessionState::WaitingForWorker(worker_id) {
+            if self
+                .advance_review_stage(session_id)
+                .map_err(SessionError::ConfigError)?
+            {
+                return Ok(());
+            }
             tracing::warn!(
                 "Worker {} completed but session in state {:?}",
                 worker_id,
@@ -10805,8 +15184,11 @@ phases and do EXACTLY this, then stop:
 
         // 2. Spawn next worker
         let next_worker_index = worker_id as usize;
-        self.spawn_next_worker(session_id, next_worker_index, &config, &queen_id)
-            .await?;
+        self.spawn_next_worker(session_id, next_worker_index, &config, &queen_id)?;
+
+        // 3. After the last worker, hand over to the review stage if there is one
+        self.advance_review_stage(session_id)
+            .map_err(SessionError::ConfigError)?;
 
         Ok(())
     }
@@ -10852,6 +15234,8 @@ phases and do EXACTLY this, then stop:
                     description: None,
                     role: None,
                     initial_prompt: None,
+                    allowed_paths: Vec::new(),
+                    denied_paths: Vec::new(),
                 });
 
             (maybe_evaluator, config)
@@ -11077,40 +15461,359 @@ phases and do EXACTLY this, then stop:
         handles.insert(session_id.to_string(), abort_handle);
     }
 
-    /// Cancel a pending QA timeout timer
-    pub fn cancel_qa_timeout(&self, session_id: &str) {
-        let mut handles = self.qa_timeout_handles.lock();
-        if let Some(handle) = handles.remove(session_id) {
-            handle.abort();
-        }
+    /// Cancel a pending QA timeout timer
+    pub fn cancel_qa_timeout(&self, session_id: &str) {
+        let mut handles = self.qa_timeout_handles.lock();
+        if let Some(handle) = handles.remove(session_id) {
+            handle.abort();
+        }
+    }
+
+    pub async fn on_fusion_variant_completed(
+        &self,
+        session_id: &str,
+        variant_index: u8,
+    ) -> Result<(), SessionError> {
+        let session = self
+            .get_session(session_id)
+            .ok_or_else(|| SessionError::NotFound(format!("Session not found: {}", session_id)))?;
+
+        if !matches!(session.session_type, SessionType::Fusion { .. }) {
+            return Ok(());
+        }
+
+        let metadata = Self::read_fusion_metadata(&session.project_path, session_id)
+            .map_err(SessionError::ConfigError)?;
+        let variant = metadata
+            .variants
+            .iter()
+            .find(|v| v.index == variant_index)
+            .ok_or_else(|| {
+                SessionError::ConfigError(format!(
+                    "Unknown fusion variant index: {}",
+                    variant_index
+                ))
+            })?;
+
+        {
+            let pty_manager = self.pty_manager.read();
+            if let Err(e) = pty_manager.kill(&variant.agent_id) {
+                tracing::warn!(
+                    "Failed to stop fusion variant PTY {}: {}",
+                    variant.agent_id,
+                    e
+                );
+            }
+        }
+
+        let completed_agent = {
+            let mut sessions = self.sessions.write();
+            if let Some(s) = sessions.get_mut(session_id) {
+                if let Some(index) = s
+                    .agents
+                    .iter()
+                    .position(|agent| agent.id == variant.agent_id)
+                {
+                    s.agents[index].status = AgentStatus::Completed;
+                    Some((s.clone(), s.agents[index].clone()))
+                } else {
+                    None
+                }
+            } else {
+                None
+            }
+        };
+        self.update_session_storage(session_id);
+        if let Some((session, agent)) = completed_agent {
+            self.emit_agent_completed(&session, &agent);
+        }
+
+        let already_judging = {
+            let sessions = self.sessions.read();
+            sessions
+                .get(session_id)
+                .map(|s| {
+                    matches!(
+                        s.state,
+                        SessionState::SpawningJudge
+                            | SessionState::Judging { .. }
+                            | SessionState::AwaitingVerdictSelection
+                            | SessionState::MergingWinner
+                            | SessionState::Completed
+                    )
+                })
+                .unwrap_or(false)
+        };
+        if already_judging {
+            return Ok(());
+        }
+
+        let mut metadata = metadata;
+        if let Some(variant) = metadata
+            .variants
+            .iter_mut()
+            .find(|v| v.index == variant_index && v.failure.is_some())
+        {
+            // A variant that was marked failed can still finish on its own.
+            variant.failure = None;
+            Self::write_fusion_metadata(&session.project_path, session_id, &metadata)
+                .map_err(SessionError::ConfigError)?;
+        }
+
+        self.maybe_start_fusion_judging(session_id, &metadata)
+            .map_err(SessionError::SpawnError)
+    }
+
+    /// Handle a Fusion variant that reported BLOCKED/FAILED or whose CLI exited before
+    /// completing its task. The variant is respawned while retries remain; otherwise it
+    /// is marked failed and judging proceeds once the remaining variants settle.
+    pub async fn on_fusion_variant_failed(
+        controller: &RwLock<SessionController>,
+        session_id: &str,
+        variant_index: u8,
+        reason: &str,
+    ) -> Result<(), SessionError> {
+        let retry = controller
+            .read()
+            .settle_failed_fusion_variant(session_id, variant_index, reason)?;
+        if retry {
+            Self::retry_fusion_variant(controller, session_id, variant_index)
+                .map_err(SessionError::SpawnError)?;
+        }
+        Ok(())
+    }
+
+    /// Mark a failed Fusion variant as failed for good, or return `true` when it still
+    /// has retries left and should be respawned instead.
+    fn settle_failed_fusion_variant(
+        &self,
+        session_id: &str,
+        variant_index: u8,
+        reason: &str,
+    ) -> Result<bool, SessionError> {
+        let session = self
+            .get_session(session_id)
+            .ok_or_else(|| SessionError::NotFound(format!("Session not found: {}", session_id)))?;
+
+        if !matches!(session.session_type, SessionType::Fusion { .. })
+            || !matches!(session.state, SessionState::WaitingForFusionVariants)
+        {
+            return Ok(false);
+        }
+
+        let mut metadata = Self::read_fusion_metadata(&session.project_path, session_id)
+            .map_err(SessionError::ConfigError)?;
+        let position = metadata
+            .variants
+            .iter()
+            .position(|v| v.index == variant_index)
+            .ok_or_else(|| {
+                SessionError::ConfigError(format!(
+                    "Unknown fusion variant index: {}",
+                    variant_index
+                ))
+            })?;
+        let variant = metadata.variants[position].clone();
+        if variant.failure.is_some() || Self::is_task_completed(&variant.task_file) {
+            return Ok(false);
+        }
+
+        if variant.attempts < metadata.max_variant_retries {
+            tracing::warn!(
+                "Fusion variant {} failed ({}), retrying (attempt {} of {})",
+                variant.name,
+                reason,
+                variant.attempts + 1,
+                metadata.max_variant_retries
+            );
+            return Ok(true);
+        }
+
+        tracing::warn!("Fusion variant {} failed: {}", variant.name, reason);
+        metadata.variants[position].failure = Some(reason.to_string());
+        Self::write_fusion_metadata(&session.project_path, session_id, &metadata)
+            .map_err(SessionError::ConfigError)?;
+
+        {
+            let mut sessions = self.sessions.write();
+            if let Some(agent) = sessions
+                .get_mut(session_id)
+                .and_then(|s| s.agents.iter_mut().find(|a| a.id == variant.agent_id))
+            {
+                agent.status = AgentStatus::Error(reason.to_string());
+            }
+        }
+        self.update_session_storage(session_id);
+        self.emit_session_update(session_id);
+
+        // Stopped only once the failure is on disk, so its exit is seen as settled and a
+        // crash in between cannot leave the variant looking like it is still running.
+        {
+            let pty_manager = self.pty_manager.read();
+            if let Err(e) = pty_manager.kill(&variant.agent_id) {
+                tracing::warn!(
+                    "Failed to stop fusion variant PTY {}: {}",
+                    variant.agent_id,
+                    e
+                );
+            }
+        }
+
+        self.maybe_start_fusion_judging(session_id, &metadata)
+            .map_err(SessionError::SpawnError)?;
+        Ok(false)
+    }
+
+    /// Record how an agent's PTY process ended. Agents still marked live settle in
+    /// `Completed` or `Crashed`; a status the controller already decided (a finished task,
+    /// a failed Fusion variant) is kept. Exits reported after a same-ID respawn are ignored.
+    pub async fn on_agent_exited(
+        controller: &RwLock<SessionController>,
+        agent_id: &str,
+        exit_info: AgentExitInfo,
+    ) -> Result<(), SessionError> {
+        if controller.read().pty_manager.read().is_alive(agent_id) {
+            return Ok(());
+        }
+        // Judged against the pre-exit status, so it runs before the status is settled.
+        if agent_id.contains("-fusion-") {
+            Self::on_fusion_agent_exited(controller, agent_id).await?;
+        }
+
+        let controller = controller.read();
+        let session_id = {
+            let mut sessions = controller.sessions.write();
+            sessions.iter_mut().find_map(|(session_id, session)| {
+                let agent = session.agents.iter_mut().find(|a| a.id == agent_id)?;
+                if matches!(
+                    agent.status,
+                    AgentStatus::Starting
+                        | AgentStatus::Running
+                        | AgentStatus::Idle
+                        | AgentStatus::WaitingForInput(_)
+                ) {
+                    agent.status = exit_info.status();
+                }
+                agent.exit_info = Some(exit_info.clone());
+                Some(session_id.clone())
+            })
+        };
+        if let Some(session_id) = session_id {
+            controller.emit_session_update(&session_id);
+        }
+        Ok(())
+    }
+
+    /// Treat a Fusion variant PTY that exited without completing its task as a failed
+    /// variant. Exits caused by our own kills/respawns are ignored.
+    pub async fn on_fusion_agent_exited(
+        controller: &RwLock<SessionController>,
+        agent_id: &str,
+    ) -> Result<(), SessionError> {
+        let Some((session_id, variant_index)) = agent_id
+            .rsplit_once("-fusion-")
+            .and_then(|(session_id, index)| Some((session_id, index.parse::<u8>().ok()?)))
+        else {
+            return Ok(());
+        };
+
+        let exited_unexpectedly = {
+            let controller = controller.read();
+            let sessions = controller.sessions.read();
+            let unexpected = sessions
+                .get(session_id)
+                .and_then(|s| s.agents.iter().find(|a| a.id == agent_id))
+                .map(|agent| {
+                    !matches!(
+                        agent.status,
+                        AgentStatus::Starting | AgentStatus::Completed | AgentStatus::Error(_)
+                    )
+                })
+                .unwrap_or(false);
+            unexpected && !controller.pty_manager.read().is_alive(agent_id)
+        };
+        if !exited_unexpectedly {
+            return Ok(());
+        }
+
+        Self::on_fusion_variant_failed(
+            controller,
+            session_id,
+            variant_index,
+            "CLI exited before completing the task",
+        )
+        .await
+    }
+
+    /// Respawn a Fusion variant from scratch: stop its CLI, reset its worktree to the
+    /// Fusion base branch, rewrite its task file and relaunch the same agent config.
+    ///
+    /// The worktree reset runs without the controller lock; only stopping the old CLI
+    /// and launching the new one hold it.
+    pub fn retry_fusion_variant(
+        controller: &RwLock<SessionController>,
+        session_id: &str,
+        variant_index: u8,
+    ) -> Result<(), String> {
+        let retry = controller
+            .read()
+            .stop_fusion_variant_for_retry(session_id, variant_index)?;
+
+        let worktree_path = PathBuf::from(&retry.variant.worktree_path);
+        Self::run_git_in_dir(&worktree_path, &["reset", "--hard", retry.metadata.base_ref()])?;
+        Self::run_git_in_dir(&worktree_path, &["clean", "-fd"])?;
+
+        controller.read().respawn_fusion_variant(session_id, retry)
     }
 
-    pub async fn on_fusion_variant_completed(
+    /// First half of a variant retry: mark the agent as restarting and stop its CLI.
+    fn stop_fusion_variant_for_retry(
         &self,
         session_id: &str,
         variant_index: u8,
-    ) -> Result<(), SessionError> {
+    ) -> Result<FusionVariantRetry, String> {
         let session = self
             .get_session(session_id)
-            .ok_or_else(|| SessionError::NotFound(format!("Session not found: {}", session_id)))?;
+            .ok_or_else(|| format!("Session not found: {}", session_id))?;
 
         if !matches!(session.session_type, SessionType::Fusion { .. }) {
-            return Ok(());
+            return Err(format!("Session {} is not a Fusion session", session_id));
+        }
+        if !matches!(
+            session.state,
+            SessionState::WaitingForFusionVariants | SessionState::Failed(_)
+        ) {
+            return Err(format!(
+                "Session {} cannot retry fusion variants in state {:?}",
+                session_id, session.state
+            ));
         }
 
-        let metadata = Self::read_fusion_metadata(&session.project_path, session_id)
-            .map_err(SessionError::ConfigError)?;
-        let variant = metadata
+        let metadata = Self::read_fusion_metadata(&session.project_path, session_id)?;
+        let position = metadata
             .variants
             .iter()
-            .find(|v| v.index == variant_index)
-            .ok_or_else(|| {
-                SessionError::ConfigError(format!(
-                    "Unknown fusion variant index: {}",
-                    variant_index
-                ))
-            })?;
+            .position(|v| v.index == variant_index)
+            .ok_or_else(|| format!("Unknown fusion variant index: {}", variant_index))?;
+        let variant = metadata.variants[position].clone();
+        let agent_config = session
+            .agents
+            .iter()
+            .find(|a| a.id == variant.agent_id)
+            .map(|a| a.config.clone())
+            .ok_or_else(|| format!("Fusion variant agent not found: {}", variant.agent_id))?;
 
+        // Mark the agent as restarting first so the exit of the old PTY is not
+        // reported as another failure.
+        {
+            let mut sessions = self.sessions.write();
+            if let Some(agent) = sessions
+                .get_mut(session_id)
+                .and_then(|s| s.agents.iter_mut().find(|a| a.id == variant.agent_id))
+            {
+                agent.status = AgentStatus::Starting;
+            }
+        }
         {
             let pty_manager = self.pty_manager.read();
             if let Err(e) = pty_manager.kill(&variant.agent_id) {
@@ -11122,58 +15825,167 @@ phases and do EXACTLY this, then stop:
             }
         }
 
-        let completed_agent = {
+        Ok(FusionVariantRetry {
+            project_path: session.project_path,
+            metadata,
+            position,
+            variant,
+            agent_config,
+        })
+    }
+
+    /// Second half of a variant retry, once its worktree is reset: rewrite the task
+    /// file and relaunch the agent.
+    fn respawn_fusion_variant(
+        &self,
+        session_id: &str,
+        retry: FusionVariantRetry,
+    ) -> Result<(), String> {
+        let FusionVariantRetry {
+            project_path,
+            mut metadata,
+            position,
+            variant,
+            agent_config,
+        } = retry;
+        if self.get_session(session_id).is_none() {
+            return Err(format!("Session not found: {}", session_id));
+        }
+
+        let worktree_path = PathBuf::from(&variant.worktree_path);
+        Self::write_fusion_variant_task_file(
+            &worktree_path,
+            variant.index,
+            &variant.name,
+            &metadata.task_description,
+        )?;
+        let worker_prompt = Self::build_fusion_worker_prompt(
+            session_id,
+            variant.index,
+            &variant.name,
+            &variant.branch,
+            &variant.worktree_path,
+            &metadata.task_description,
+            &agent_config.cli,
+        );
+        let prompt_file = Self::write_worker_prompt_file(
+            &worktree_path,
+            variant.index,
+            &format!("fusion-worker-{}-prompt.md", variant.index),
+            &self.with_session_api_token(session_id, &worker_prompt),
+        )?;
+        let prompt_path = prompt_file.to_string_lossy().to_string();
+
+        let (cmd, mut args) = Self::build_command(&agent_config);
+        Self::add_prompt_to_args(&cmd, &mut args, &prompt_path);
+
+        tracing::info!(
+            "Retrying Fusion variant {} ({}) on branch {} (attempt {})",
+            variant.index,
+            variant.name,
+            variant.branch,
+            variant.attempts + 1
+        );
+
+        {
+            let pty_manager = self.pty_manager.read();
+            pty_manager
+                .create_session(
+                    variant.agent_id.clone(),
+                    AgentRole::Fusion {
+                        variant: variant.name.clone(),
+                    },
+                    &cmd,
+                    &args.iter().map(|s| s.as_str()).collect::<Vec<_>>(),
+                    Some(&variant.worktree_path),
+                    120,
+                    30,
+                )
+                .map_err(|e| format!("Failed to respawn Fusion variant {}: {}", variant.name, e))?;
+        }
+
+        metadata.variants[position].attempts = variant.attempts.saturating_add(1);
+        metadata.variants[position].failure = None;
+        Self::write_fusion_metadata(&project_path, session_id, &metadata)?;
+
+        let waiting_changes = {
             let mut sessions = self.sessions.write();
             if let Some(s) = sessions.get_mut(session_id) {
-                if let Some(index) = s
-                    .agents
-                    .iter()
-                    .position(|agent| agent.id == variant.agent_id)
-                {
-                    s.agents[index].status = AgentStatus::Completed;
-                    Some((s.clone(), s.agents[index].clone()))
-                } else {
-                    None
+                if let Some(index) = s.agents.iter().position(|a| a.id == variant.agent_id) {
+                    s.agents[index].status = AgentStatus::Running;
+                    let agent = s.agents[index].clone();
+                    self.emit_agent_launched(s, &agent);
                 }
+                Some(self.set_session_state_with_events(s, SessionState::WaitingForFusionVariants))
             } else {
                 None
             }
         };
-        self.update_session_storage(session_id);
-        if let Some((session, agent)) = completed_agent {
-            self.emit_agent_completed(&session, &agent);
+        if let Some(changes) = waiting_changes {
+            self.emit_cell_status_changes(session_id, changes);
         }
+        self.emit_session_update(session_id);
+        self.update_session_storage(session_id);
 
-        let already_judging = {
-            let sessions = self.sessions.read();
-            sessions
-                .get(session_id)
-                .map(|s| {
-                    matches!(
-                        s.state,
-                        SessionState::SpawningJudge
-                            | SessionState::Judging
-                            | SessionState::AwaitingVerdictSelection
-                            | SessionState::MergingWinner
-                            | SessionState::Completed
-                    )
-                })
-                .unwrap_or(false)
-        };
-        if already_judging {
-            return Ok(());
-        }
+        Ok(())
+    }
 
-        if metadata
+    fn fusion_settlement(metadata: &FusionSessionMetadata) -> FusionSettlement {
+        let total = metadata.variants.len();
+        let completed = metadata
             .variants
             .iter()
-            .all(|v| Self::is_task_completed(&v.task_file))
-        {
-            self.spawn_fusion_judge(session_id)
-                .map_err(SessionError::SpawnError)?;
+            .filter(|v| Self::is_task_completed(&v.task_file))
+            .count();
+        let failed = metadata
+            .variants
+            .iter()
+            .filter(|v| v.failure.is_some() && !Self::is_task_completed(&v.task_file))
+            .count();
+        if completed + failed < total {
+            return FusionSettlement::Pending;
         }
 
-        Ok(())
+        let quorum = metadata
+            .quorum
+            .map(|quorum| usize::from(quorum).clamp(1, total.max(1)))
+            .unwrap_or(total);
+        if completed >= quorum {
+            FusionSettlement::Judge
+        } else {
+            FusionSettlement::QuorumMissed(format!(
+                "Fusion quorum not met: {} of {} variants completed, {} required",
+                completed, total, quorum
+            ))
+        }
+    }
+
+    /// Spawn the judge once every variant has completed or failed and enough of them
+    /// completed to meet the quorum; fail the session when the survivors fall short.
+    fn maybe_start_fusion_judging(
+        &self,
+        session_id: &str,
+        metadata: &FusionSessionMetadata,
+    ) -> Result<(), String> {
+        match Self::fusion_settlement(metadata) {
+            FusionSettlement::Pending => Ok(()),
+            FusionSettlement::Judge => self.spawn_fusion_judge(session_id),
+            FusionSettlement::QuorumMissed(reason) => {
+                tracing::warn!("Fusion session {}: {}", session_id, reason);
+                let failed_changes = {
+                    let mut sessions = self.sessions.write();
+                    sessions.get_mut(session_id).map(|s| {
+                        self.set_session_state_with_events(s, SessionState::Failed(reason))
+                    })
+                };
+                if let Some(changes) = failed_changes {
+                    self.emit_cell_status_changes(session_id, changes);
+                }
+                self.emit_session_update(session_id);
+                self.update_session_storage(session_id);
+                Ok(())
+            }
+        }
     }
 
     fn spawn_fusion_judge(&self, session_id: &str) -> Result<(), String> {
@@ -11212,20 +16024,94 @@ phases and do EXACTLY this, then stop:
         }
         self.emit_session_update(session_id);
 
+        self.spawn_fusion_judge_agent(
+            &session,
+            &metadata,
+            &judge_id,
+            metadata.judge_config.clone(),
+            &metadata.decision_file,
+            "fusion-judge-prompt.md",
+        )?;
+
+        let judging_changes = {
+            let mut sessions = self.sessions.write();
+            if let Some(s) = sessions.get_mut(session_id) {
+                Some(
+                    self.set_session_state_with_events(s, SessionState::Judging { outstanding: 1 }),
+                )
+            } else {
+                None
+            }
+        };
+        self.emit_session_update(session_id);
+        self.update_session_storage(session_id);
+        if let Some(changes) = judging_changes {
+            self.emit_cell_status_changes(session_id, changes);
+        }
+
+        Ok(())
+    }
+
+    /// Spawn one Fusion judge PTY writing its report to `decision_file` and register it
+    /// on the session. Returns the effective config (session defaults filled in).
+    fn spawn_fusion_judge_agent(
+        &self,
+        session: &Session,
+        metadata: &FusionSessionMetadata,
+        judge_id: &str,
+        mut judge_config: AgentConfig,
+        decision_file: &str,
+        prompt_filename: &str,
+    ) -> Result<AgentConfig, String> {
+        let session_id = session.id.as_str();
+        let surviving_variants = metadata
+            .variants
+            .iter()
+            .filter(|v| v.failure.is_none())
+            .cloned()
+            .collect::<Vec<_>>();
+        let evaluation_dir = Path::new(decision_file)
+            .parent()
+            .map(Path::to_path_buf)
+            .unwrap_or_else(|| {
+                Self::session_root_path(&session.project_path, session_id).join("evaluation")
+            });
+        let contexts = surviving_variants
+            .iter()
+            .map(|variant| {
+                judge_context::write_variant_context(
+                    &session.project_path,
+                    &evaluation_dir,
+                    metadata.base_ref(),
+                    &variant.name,
+                    &variant.slug,
+                    &variant.branch,
+                    Path::new(&variant.worktree_path),
+                )
+                .map_err(|e| {
+                    tracing::warn!(
+                        "No judge context pack for variant {}: {}",
+                        variant.name,
+                        e
+                    )
+                })
+                .ok()
+            })
+            .collect::<Vec<_>>();
         let judge_prompt = Self::build_fusion_judge_prompt(
             session_id,
-            &metadata.variants,
-            &metadata.decision_file,
+            &surviving_variants,
+            &contexts,
+            decision_file,
         );
         let prompt_file = Self::write_prompt_file(
             &session.project_path,
             session_id,
-            "fusion-judge-prompt.md",
-            &judge_prompt,
+            prompt_filename,
+            &self.with_session_api_token(session_id, &judge_prompt),
         )?;
         let prompt_path = prompt_file.to_string_lossy().to_string();
 
-        let mut judge_config = metadata.judge_config.clone();
         if judge_config.cli.trim().is_empty() {
             judge_config.cli = session.default_cli.clone();
         }
@@ -11236,12 +16122,13 @@ phases and do EXACTLY this, then stop:
         let (cmd, mut args) = Self::build_command(&judge_config);
         Self::add_prompt_to_args(&cmd, &mut args, &prompt_path);
 
+        let snapshot = self.read_only_snapshot(session);
         let cwd = session.project_path.to_string_lossy().to_string();
         {
             let pty_manager = self.pty_manager.read();
             pty_manager
                 .create_session(
-                    judge_id.clone(),
+                    judge_id.to_string(),
                     AgentRole::Judge {
                         session_id: session_id.to_string(),
                     },
@@ -11253,35 +16140,252 @@ phases and do EXACTLY this, then stop:
                 )
                 .map_err(|e| format!("Failed to spawn fusion judge: {}", e))?;
         }
+        self.guard_read_only(session, judge_id, snapshot);
 
-        let judging_changes = {
+        {
             let mut sessions = self.sessions.write();
             if let Some(s) = sessions.get_mut(session_id) {
                 let agent = AgentInfo {
-                    id: judge_id,
+                    id: judge_id.to_string(),
                     role: AgentRole::Judge {
                         session_id: session_id.to_string(),
                     },
                     status: AgentStatus::Running,
-                    config: judge_config,
+                    config: judge_config.clone(),
                     parent_id: None,
                     commit_sha: None,
                     base_commit_sha: None,
+                    exit_info: None,
                 };
                 s.agents.push(agent.clone());
                 self.emit_agent_launched(s, &agent);
-                Some(self.set_session_state_with_events(s, SessionState::Judging))
+            }
+        }
+
+        Ok(judge_config)
+    }
+
+    /// Re-run the Fusion judge or add second-opinion judges. Each config spawns one
+    /// additional judge (in parallel) writing its own report under `evaluation/`; an
+    /// empty list re-runs the original judge config. Returns the spawned agent ids.
+    pub fn spawn_fusion_judge_panel(
+        &self,
+        session_id: &str,
+        judge_configs: Vec<AgentConfig>,
+    ) -> Result<Vec<String>, String> {
+        let session = self
+            .get_session(session_id)
+            .ok_or_else(|| format!("Session not found: {}", session_id))?;
+
+        if !matches!(session.session_type, SessionType::Fusion { .. }) {
+            return Err(format!("Session {} is not a Fusion session", session_id));
+        }
+        if !matches!(
+            session.state,
+            SessionState::Judging { .. } | SessionState::AwaitingVerdictSelection
+        ) {
+            return Err(format!(
+                "Session {} cannot run additional judges in state {:?}",
+                session_id, session.state
+            ));
+        }
+
+        let mut metadata = Self::read_fusion_metadata(&session.project_path, session_id)?;
+        let judge_configs = if judge_configs.is_empty() {
+            vec![metadata.judge_config.clone()]
+        } else {
+            judge_configs
+        };
+        let evaluation_dir = Path::new(&metadata.decision_file)
+            .parent()
+            .map(Path::to_path_buf)
+            .unwrap_or_else(|| {
+                Self::session_root_path(&session.project_path, session_id).join("evaluation")
+            });
+
+        let mut spawned = Vec::new();
+        for config in judge_configs {
+            let number = metadata.extra_judges.len() + 2;
+            let judge_id = format!("{}-judge-{}", session_id, number);
+            let decision_file = evaluation_dir
+                .join(format!("decision-{}.md", number))
+                .to_string_lossy()
+                .to_string();
+            let config = self.spawn_fusion_judge_agent(
+                &session,
+                &metadata,
+                &judge_id,
+                config,
+                &decision_file,
+                &format!("fusion-judge-{}-prompt.md", number),
+            )?;
+            metadata.extra_judges.push(FusionJudgeMetadata {
+                agent_id: judge_id.clone(),
+                config,
+                decision_file,
+            });
+            Self::write_fusion_metadata(&session.project_path, session_id, &metadata)?;
+            spawned.push(judge_id);
+        }
+
+        let outstanding = Self::tally_fusion_verdicts(session_id, &metadata).outstanding;
+        let judging_changes = {
+            let mut sessions = self.sessions.write();
+            if let Some(s) = sessions.get_mut(session_id) {
+                Some(self.set_session_state_with_events(
+                    s,
+                    SessionState::Judging {
+                        outstanding: outstanding.max(1),
+                    },
+                ))
+            } else {
+                None
+            }
+        };
+        self.emit_session_update(session_id);
+        self.update_session_storage(session_id);
+        if let Some(changes) = judging_changes {
+            self.emit_cell_status_changes(session_id, changes);
+        }
+
+        Ok(spawned)
+    }
+
+    /// Every judge of a Fusion session: the original judge first, then re-runs and
+    /// second opinions in spawn order.
+    fn fusion_judges(
+        session_id: &str,
+        metadata: &FusionSessionMetadata,
+    ) -> Vec<FusionJudgeMetadata> {
+        let mut judges = vec![FusionJudgeMetadata {
+            agent_id: format!("{}-judge", session_id),
+            config: metadata.judge_config.clone(),
+            decision_file: metadata.decision_file.clone(),
+        }];
+        judges.extend(metadata.extra_judges.iter().cloned());
+        judges
+    }
+
+    /// Read the `Winner: <variant>` line of a judge report and resolve it to a known
+    /// variant name (by exact name, case-insensitive name, or slug).
+    fn parse_fusion_report_winner(
+        report: &str,
+        variants: &[FusionVariantMetadata],
+    ) -> Option<String> {
+        let named = report.lines().find_map(|line| {
+            let rest = line
+                .trim()
+                .trim_start_matches(['*', '-', '#', ' '])
+                .strip_prefix("Winner")?;
+            let value = rest
+                .trim_start_matches('*')
+                .trim_start()
+                .strip_prefix(':')?;
+            let value = value.trim().trim_matches(['*', '`', '[', ']']).trim();
+            (!value.is_empty()).then(|| value.to_string())
+        })?;
+        Self::resolve_fusion_variant_name(&named, variants)
+    }
+
+    /// Read the `Score: <variant>: <0-10>` lines of a judge report, keeping those
+    /// that name a known variant and give a score in range.
+    fn parse_fusion_report_scores(
+        report: &str,
+        variants: &[FusionVariantMetadata],
+    ) -> BTreeMap<String, f64> {
+        report
+            .lines()
+            .filter_map(|line| {
+                let rest = line
+                    .trim()
+                    .trim_start_matches(['*', '-', '#', ' '])
+                    .strip_prefix("Score")?
+                    .trim_start_matches('*')
+                    .trim_start()
+                    .strip_prefix(':')?;
+                let (name, score) = rest.rsplit_once(':')?;
+                let score = score.trim().trim_matches(['*', '`']).trim_end_matches("/10");
+                let score: f64 = score.trim().parse().ok()?;
+                let name = name.trim().trim_matches(['*', '`', '[', ']']).trim();
+                let variant = Self::resolve_fusion_variant_name(name, variants)?;
+                (0.0..=10.0).contains(&score).then_some((variant, score))
+            })
+            .collect()
+    }
+
+    /// A variant name as a judge wrote it, resolved by exact name, case-insensitive
+    /// name, or slug.
+    fn resolve_fusion_variant_name(
+        named: &str,
+        variants: &[FusionVariantMetadata],
+    ) -> Option<String> {
+        let slug = Self::slugify_variant_name(named);
+        variants
+            .iter()
+            .find(|v| v.name.eq_ignore_ascii_case(named) || v.slug == slug)
+            .map(|v| v.name.clone())
+    }
+
+    fn tally_fusion_verdicts(
+        session_id: &str,
+        metadata: &FusionSessionMetadata,
+    ) -> FusionVerdictTally {
+        let mut verdicts = Vec::new();
+        let mut votes: BTreeMap<String, u8> = BTreeMap::new();
+        let mut reported_count: u16 = 0;
+        let mut outstanding: u8 = 0;
+
+        for judge in Self::fusion_judges(session_id, metadata) {
+            let report = std::fs::read_to_string(&judge.decision_file).ok();
+            let winner = report
+                .as_deref()
+                .and_then(|report| Self::parse_fusion_report_winner(report, &metadata.variants));
+            let scores = report
+                .as_deref()
+                .map(|report| Self::parse_fusion_report_scores(report, &metadata.variants))
+                .unwrap_or_default();
+            if report.is_some() {
+                reported_count += 1;
             } else {
-                None
+                outstanding = outstanding.saturating_add(1);
             }
-        };
-        self.emit_session_update(session_id);
-        self.update_session_storage(session_id);
-        if let Some(changes) = judging_changes {
-            self.emit_cell_status_changes(session_id, changes);
+            if let Some(winner) = &winner {
+                let count = votes.entry(winner.clone()).or_insert(0);
+                *count = count.saturating_add(1);
+            }
+            verdicts.push(FusionJudgeVerdict {
+                judge_id: judge.agent_id,
+                cli: judge.config.cli,
+                model: judge.config.model,
+                report_path: judge.decision_file,
+                reported: report.is_some(),
+                winner,
+                scores,
+            });
         }
 
-        Ok(())
+        let majority_winner = votes
+            .iter()
+            .find(|(_, count)| u16::from(**count) * 2 > reported_count)
+            .map(|(name, _)| name.clone());
+
+        FusionVerdictTally {
+            verdicts,
+            votes,
+            majority_winner,
+            outstanding,
+        }
+    }
+
+    fn write_fusion_verdict_tally(
+        metadata: &FusionSessionMetadata,
+        tally: &FusionVerdictTally,
+    ) -> Result<(), String> {
+        let tally_path = Path::new(&metadata.decision_file).with_file_name("verdicts.json");
+        let json = serde_json::to_string_pretty(tally)
+            .map_err(|e| format!("Failed to serialize fusion verdicts: {}", e))?;
+        std::fs::write(&tally_path, json)
+            .map_err(|e| format!("Failed to write fusion verdicts: {}", e))
     }
 
     pub fn get_fusion_variant_statuses(
@@ -11305,7 +16409,13 @@ phases and do EXACTLY this, then stop:
                 name: v.name.clone(),
                 branch: v.branch.clone(),
                 worktree_path: v.worktree_path.clone(),
-                status: Self::read_task_status(&v.task_file),
+                status: if v.failure.is_some() {
+                    "FAILED".to_string()
+                } else {
+                    Self::read_task_status(&v.task_file)
+                },
+                attempts: v.attempts,
+                failure: v.failure.clone(),
             })
             .collect())
     }
@@ -11313,7 +16423,7 @@ phases and do EXACTLY this, then stop:
     pub fn get_fusion_evaluation(
         &self,
         session_id: &str,
-    ) -> Result<(String, Option<String>), String> {
+    ) -> Result<(String, Option<String>, FusionVerdictTally), String> {
         let session = self
             .get_session(session_id)
             .ok_or_else(|| format!("Session not found: {}", session_id))?;
@@ -11329,15 +16439,24 @@ phases and do EXACTLY this, then stop:
             Err(err) => return Err(format!("Failed to read evaluation report: {}", err)),
         };
 
-        if report.is_some() {
-            let awaiting_changes = {
+        let tally = Self::tally_fusion_verdicts(session_id, &metadata);
+        if tally.verdicts.iter().any(|verdict| verdict.reported) {
+            Self::write_fusion_verdict_tally(&metadata, &tally)?;
+        }
+
+        if tally.outstanding < tally.verdicts.len() as u8 {
+            let next_state = if tally.outstanding == 0 {
+                SessionState::AwaitingVerdictSelection
+            } else {
+                SessionState::Judging {
+                    outstanding: tally.outstanding,
+                }
+            };
+            let judging_changes = {
                 let mut sessions = self.sessions.write();
                 if let Some(s) = sessions.get_mut(session_id) {
-                    if s.state == SessionState::Judging {
-                        Some(self.set_session_state_with_events(
-                            s,
-                            SessionState::AwaitingVerdictSelection,
-                        ))
+                    if matches!(s.state, SessionState::Judging { .. }) && s.state != next_state {
+                        Some(self.set_session_state_with_events(s, next_state))
                     } else {
                         None
                     }
@@ -11345,14 +16464,14 @@ phases and do EXACTLY this, then stop:
                     None
                 }
             };
-            if let Some(changes) = awaiting_changes {
+            if let Some(changes) = judging_changes {
                 self.emit_session_update(session_id);
                 self.update_session_storage(session_id);
                 self.emit_cell_status_changes(session_id, changes);
             }
         }
 
-        Ok((metadata.decision_file, report))
+        Ok((metadata.decision_file, report, tally))
     }
 
     pub async fn on_debate_round_completed(
@@ -11416,7 +16535,7 @@ phases and do EXACTLY this, then stop:
                     matches!(
                         s.state,
                         SessionState::SpawningJudge
-                            | SessionState::Judging
+                            | SessionState::Judging { .. }
                             | SessionState::AwaitingVerdictSelection
                             | SessionState::Completed
                     )
@@ -11518,6 +16637,7 @@ phases and do EXACTLY this, then stop:
         }
 
         let judge_prompt = Self::build_debate_judge_prompt(
+            &self.template_engine(),
             session_id,
             &metadata,
             &global_wiki_path,
@@ -11527,13 +16647,14 @@ phases and do EXACTLY this, then stop:
             &session.project_path,
             session_id,
             "debate-judge-prompt.md",
-            &judge_prompt,
+            &self.with_session_api_token(session_id, &judge_prompt),
         )?;
         let prompt_path = prompt_file.to_string_lossy().to_string();
 
         let (cmd, mut args) = Self::build_command(&judge_config);
         Self::add_prompt_to_args(&cmd, &mut args, &prompt_path);
 
+        let snapshot = self.read_only_snapshot(&session);
         let cwd = session.project_path.to_string_lossy().to_string();
         {
             let pty_manager = self.pty_manager.read();
@@ -11551,6 +16672,7 @@ phases and do EXACTLY this, then stop:
                 )
                 .map_err(|e| format!("Failed to spawn debate judge: {}", e))?;
         }
+        self.guard_read_only(&session, &judge_id, snapshot);
 
         let judging_changes = {
             let mut sessions = self.sessions.write();
@@ -11565,10 +16687,13 @@ phases and do EXACTLY this, then stop:
                     parent_id: None,
                     commit_sha: None,
                     base_commit_sha: None,
+                    exit_info: None,
                 };
                 s.agents.push(agent.clone());
                 self.emit_agent_launched(s, &agent);
-                Some(self.set_session_state_with_events(s, SessionState::Judging))
+                Some(
+                    self.set_session_state_with_events(s, SessionState::Judging { outstanding: 1 }),
+                )
             } else {
                 None
             }
@@ -11647,7 +16772,7 @@ phases and do EXACTLY this, then stop:
             let awaiting_changes = {
                 let mut sessions = self.sessions.write();
                 if let Some(s) = sessions.get_mut(session_id) {
-                    if s.state == SessionState::Judging {
+                    if matches!(s.state, SessionState::Judging { .. }) {
                         Some(self.set_session_state_with_events(
                             s,
                             SessionState::AwaitingVerdictSelection,
@@ -11724,6 +16849,7 @@ phases and do EXACTLY this, then stop:
                 &format!("Merge fusion winner: {}", winner.name),
             ],
         )?;
+        self.record_fusion_outcome(&session, &metadata, &winner.name);
 
         for variant in &metadata.variants {
             let pty_manager = self.pty_manager.read();
@@ -11738,7 +16864,8 @@ phases and do EXACTLY this, then stop:
             let _ = pty_manager.kill(&judge_id);
         }
 
-        let cleanup_result = cleanup_session_worktrees(&session);
+        let worktree_root = metadata.worktree_root(&session.project_path, session_id);
+        let cleanup_result = cleanup_session_worktrees(&session, Some(&worktree_root));
 
         let completed_state = {
             let mut sessions = self.sessions.write();
@@ -11780,6 +16907,122 @@ phases and do EXACTLY this, then stop:
         }
     }
 
+    /// Add the merged session to the app-wide Fusion history: each variant's CLI and
+    /// model, whether it won, and what the judges made of it. Failures are logged,
+    /// since the merge itself has already happened.
+    fn record_fusion_outcome(
+        &self,
+        session: &Session,
+        metadata: &FusionSessionMetadata,
+        winner: &str,
+    ) {
+        let Some(storage) = self.storage.as_ref() else {
+            return;
+        };
+        let tally = Self::tally_fusion_verdicts(&session.id, metadata);
+        let variants = metadata
+            .variants
+            .iter()
+            .filter_map(|variant| {
+                let agent = session.agents.iter().find(|a| a.id == variant.agent_id)?;
+                let scores: Vec<f64> = tally
+                    .verdicts
+                    .iter()
+                    .filter_map(|verdict| verdict.scores.get(&variant.name).copied())
+                    .collect();
+                Some(FusionVariantOutcome {
+                    name: variant.name.clone(),
+                    cli: agent.config.cli.clone(),
+                    model: agent.config.model.clone(),
+                    won: variant.name == winner,
+                    votes: tally.votes.get(&variant.name).copied().unwrap_or(0),
+                    score: (!scores.is_empty())
+                        .then(|| scores.iter().sum::<f64>() / scores.len() as f64),
+                })
+            })
+            .collect();
+        let outcome = FusionOutcome {
+            session_id: session.id.clone(),
+            project_path: session.project_path.to_string_lossy().to_string(),
+            recorded_at: Utc::now(),
+            winner: winner.to_string(),
+            variants,
+            task_keywords: fusion_history::keywords(&metadata.task_description),
+        };
+        if let Err(e) = storage.record_fusion_outcome(&outcome) {
+            tracing::warn!(session_id = %session.id, "Failed to record fusion outcome: {}", e);
+        }
+    }
+
+    /// Build an integration branch from chosen commits and files of several Fusion
+    /// variants, for when the best result mixes them. The session itself is left
+    /// as it is; the branch can then be reviewed or merged like any other.
+    pub fn compose_fusion_result(
+        &self,
+        session_id: &str,
+        request: &FusionComposeRequest,
+    ) -> Result<FusionComposeReport, String> {
+        let session = self
+            .get_session(session_id)
+            .ok_or_else(|| format!("Session not found: {}", session_id))?;
+        if !matches!(session.session_type, SessionType::Fusion { .. }) {
+            return Err(format!("Session {} is not a Fusion session", session_id));
+        }
+        if request.picks.is_empty() {
+            return Err("Nothing to compose: no commits or files were picked".to_string());
+        }
+
+        let metadata = Self::read_fusion_metadata(&session.project_path, session_id)?;
+        let picks = request
+            .picks
+            .iter()
+            .map(|pick| {
+                let requested = pick.variant().trim();
+                let slug = Self::slugify_variant_name(requested);
+                metadata
+                    .variants
+                    .iter()
+                    .find(|v| v.name == requested || v.slug == slug)
+                    .map(|variant| (pick.clone(), variant.branch.clone()))
+                    .ok_or_else(|| {
+                        format!(
+                            "Variant '{}' not found for session {}",
+                            requested, session_id
+                        )
+                    })
+            })
+            .collect::<Result<Vec<_>, String>>()?;
+
+        let branch = request
+            .branch
+            .as_deref()
+            .map(str::trim)
+            .filter(|branch| !branch.is_empty())
+            .map_or_else(|| fusion_compose::default_branch(session_id), String::from);
+        let worktree = metadata
+            .worktree_root(&session.project_path, session_id)
+            .join(".compose");
+        let report = fusion_compose::compose(
+            &session.project_path,
+            metadata.base_ref(),
+            &branch,
+            &worktree,
+            &picks,
+        )?;
+        tracing::info!(
+            "Composed {} for Fusion session {} from {} picks{}",
+            report.branch,
+            session_id,
+            report.picks.len(),
+            if report.has_conflicts() {
+                " (some conflicted)"
+            } else {
+                ""
+            }
+        );
+        Ok(report)
+    }
+
     /// Terminate a worker
     fn terminate_worker(&self, session_id: &str, worker_id: u8) -> Result<(), SessionError> {
         let worker_agent_id = format!("{}-worker-{}", session_id, worker_id);
@@ -11817,8 +17060,12 @@ phases and do EXACTLY this, then stop:
         Ok(())
     }
 
-    /// Continue a session after planning phase - spawns Queen + Workers/Planners
-    pub fn continue_after_planning(&self, session_id: &str) -> Result<Session, String> {
+    /// Continue a session after planning phase - spawns Queen + Workers/Planners. A Fusion
+    /// session also returns its variant spawn plan, to be run like `launch_fusion`'s.
+    pub fn continue_after_planning(
+        &self,
+        session_id: &str,
+    ) -> Result<(Session, Option<FusionSpawnPlan>), String> {
         // Get the session
         let session = {
             let sessions = self.sessions.read();
@@ -11837,13 +17084,19 @@ phases and do EXACTLY this, then stop:
         // Dispatch based on session type
         match &session.session_type {
             SessionType::Swarm { .. } => {
-                return self.continue_swarm_after_planning(session_id, &session);
+                return self
+                    .continue_swarm_after_planning(session_id, &session)
+                    .map(|session| (session, None));
             }
             SessionType::Fusion { .. } => {
-                return self.continue_fusion_after_planning(session_id, &session);
+                return self
+                    .continue_fusion_after_planning(session_id, &session)
+                    .map(|(session, plan)| (session, Some(plan)));
             }
             SessionType::Debate { .. } => {
-                return self.continue_debate_after_planning(session_id, &session);
+                return self
+                    .continue_debate_after_planning(session_id, &session)
+                    .map(|session| (session, None));
             }
             SessionType::Solo { .. } => {
                 return Err("Solo sessions do not support planning continuation".to_string());
@@ -11939,6 +17192,7 @@ phases and do EXACTLY this, then stop:
             &session.project_path,
             Path::new(&cwd),
             session_id,
+            &worktree_branch,
             &config.workers,
             config.prompt.as_deref(),
             has_plan,
@@ -11949,7 +17203,7 @@ phases and do EXACTLY this, then stop:
             &session.project_path,
             session_id,
             "queen-prompt.md",
-            &master_prompt,
+            &self.with_session_api_token(session_id, &master_prompt),
         ) {
             Ok(path) => path,
             Err(error) => {
@@ -12015,6 +17269,7 @@ phases and do EXACTLY this, then stop:
             parent_id: None,
             commit_sha: None,
             base_commit_sha: None,
+            exit_info: None,
         });
 
         // Queen will spawn workers via HTTP API after reading the plan
@@ -12066,7 +17321,7 @@ phases and do EXACTLY this, then stop:
         // Clean up pending config file
         let _ = std::fs::remove_file(&pending_config_path);
 
-        Ok(updated_session)
+        Ok((updated_session, None))
     }
 
     /// Mark a planning session as ready (plan generated)
@@ -12076,6 +17331,14 @@ phases and do EXACTLY this, then stop:
             if session.state == SessionState::Planning {
                 let changes = self.set_session_state_with_events(session, SessionState::PlanReady);
 
+                // Baseline revision for diffing later refinement rounds
+                let session_root = Self::session_root_path(&session.project_path, session_id);
+                if let Ok(plan) = std::fs::read_to_string(session_root.join("plan.md")) {
+                    if let Err(e) = plan_history::record_revision(&session_root, &plan) {
+                        tracing::warn!("Failed to record plan revision for {}: {}", session_id, e);
+                    }
+                }
+
                 if let Some(ref app_handle) = self.app_handle {
                     let _ = app_handle.emit(
                         "session-update",
@@ -12132,8 +17395,24 @@ phases and do EXACTLY this, then stop:
 
         // #125: classify the run journal for this resumed session — mark completed
         // write-steps Skipped (the spawn/commit guards keep them from re-running) and
-        // verify unconfirmed ledger effects. Attach the report for the frontend modal.
-        let report = self.build_resume_report(session_id, &session.project_path);
+        // verify unconfirmed ledger effects. Then set each agent's status from its PTY
+        // and task file rather than trusting the snapshot. Attach the report for the
+        // frontend modal.
+        let mut report = self.build_resume_report(session_id, &session.project_path);
+        report.agents = self.reconcile_resumed_agents(&mut session);
+        let missing = report
+            .agents
+            .iter()
+            .filter(|agent| agent.state == crate::domain::run_journal::AgentResumeState::MissingPty)
+            .count();
+        if missing > 0 {
+            tracing::warn!(
+                "Resumed session {}: {} of {} agents have no running process",
+                session_id,
+                missing,
+                report.agents.len()
+            );
+        }
         if !report.is_empty() {
             session.resume_report = Some(report);
         }
@@ -12159,6 +17438,263 @@ phases and do EXACTLY this, then stop:
         Ok(session)
     }
 
+    /// Register a session that was created by another install (or whose app-data
+    /// entry was lost) from its `.hive-manager/<session_id>/` directory: the plan,
+    /// task files and Fusion/Debate metadata left in the project. The session is
+    /// persisted as if this app had launched it and then loaded like a resumed one.
+    pub fn adopt_session(&self, project_path: &Path, session_id: &str) -> Result<Session, String> {
+        if session_id.contains("..") || session_id.contains("/") || session_id.contains("\\") {
+            return Err("Invalid session ID format".to_string());
+        }
+        if self.sessions.read().contains_key(session_id) {
+            return Err("Session is already loaded".to_string());
+        }
+        let storage = self
+            .storage
+            .clone()
+            .ok_or_else(|| "Session storage is not initialized".to_string())?;
+        if storage.load_session(session_id).is_ok() {
+            return Err(format!(
+                "Session {} is already known; resume it instead",
+                session_id
+            ));
+        }
+
+        let session_root = Self::session_root_path(project_path, session_id);
+        if !session_root.is_dir() {
+            return Err(format!(
+                "No session directory at {}",
+                session_root.display()
+            ));
+        }
+
+        let session = Self::reconstruct_session(project_path, session_id)?;
+        Self::persist_session_snapshot(&storage, &session, session_id)?;
+        tracing::info!(
+            "Adopted session {} from {}",
+            session_id,
+            session_root.display()
+        );
+
+        self.resume_session(session_id)
+    }
+
+    /// Rebuild a stopped session from what it left under `.hive-manager`. Agents come
+    /// back as Completed; the session is Completed when its tasks (or the Fusion/Debate
+    /// decision) say so and Paused otherwise.
+    fn reconstruct_session(project_path: &Path, session_id: &str) -> Result<Session, String> {
+        let project = project_path.to_path_buf();
+        let session_root = Self::session_root_path(project_path, session_id);
+        let adopted_agent = |id: String, role: AgentRole, config: AgentConfig| AgentInfo {
+            parent_id: match &role {
+                AgentRole::Worker { parent, .. } => parent.clone(),
+                _ => None,
+            },
+            id,
+            role,
+            status: AgentStatus::Completed,
+            config,
+            commit_sha: None,
+            base_commit_sha: None,
+            exit_info: None,
+        };
+
+        let mut no_git = false;
+        let is_fusion = Self::fusion_metadata_path(&project, session_id).exists();
+        let (session_type, agents, finished) = if is_fusion {
+            let metadata = Self::read_fusion_metadata(&project, session_id)?;
+            let agents: Vec<AgentInfo> = metadata
+                .variants
+                .iter()
+                .map(|variant| {
+                    adopted_agent(
+                        variant.agent_id.clone(),
+                        AgentRole::Fusion {
+                            variant: variant.name.clone(),
+                        },
+                        AgentConfig::default(),
+                    )
+                })
+                .collect();
+            (
+                SessionType::Fusion {
+                    variants: metadata.variants.into_iter().map(|v| v.name).collect(),
+                },
+                agents,
+                project_path.join(&metadata.decision_file).exists(),
+            )
+        } else if Self::debate_metadata_path(&project, session_id).exists() {
+            let metadata = Self::read_debate_metadata(&project, session_id)?;
+            let agents: Vec<AgentInfo> = metadata
+                .debaters
+                .iter()
+                .map(|debater| {
+                    adopted_agent(
+                        Self::debate_round_agent_id(session_id, debater.index, metadata.rounds),
+                        AgentRole::Fusion {
+                            variant: debater.name.clone(),
+                        },
+                        debater.config.clone(),
+                    )
+                })
+                .collect();
+            (
+                SessionType::Debate {
+                    variants: metadata.debaters.into_iter().map(|d| d.name).collect(),
+                },
+                agents,
+                project_path.join(&metadata.verdict_file).exists(),
+            )
+        } else {
+            let worktrees = project_path
+                .join(".hive-manager")
+                .join("worktrees")
+                .join(session_id);
+            let task_files: Vec<(u8, PathBuf)> = (1..=u8::MAX)
+                .map_while(|index| {
+                    let worker = usize::from(index);
+                    let isolated =
+                        Self::absolute_task_file_path_for_worker(project_path, session_id, worker);
+                    let shared = Self::session_task_file_path(project_path, session_id, worker);
+                    if isolated.exists() || worktrees.join(format!("worker-{}", index)).is_dir() {
+                        Some((index, isolated))
+                    } else if shared.exists() {
+                        Some((index, shared))
+                    } else {
+                        None
+                    }
+                })
+                .collect();
+            no_git = !worktrees.exists() && !task_files.is_empty();
+
+            let queen_id = format!("{}-queen", session_id);
+            let mut agents = vec![adopted_agent(
+                queen_id.clone(),
+                AgentRole::Queen,
+                AgentConfig::default(),
+            )];
+            agents.extend(task_files.iter().map(|(index, _)| {
+                adopted_agent(
+                    format!("{}-worker-{}", session_id, index),
+                    AgentRole::Worker {
+                        index: *index,
+                        parent: Some(queen_id.clone()),
+                    },
+                    AgentConfig::default(),
+                )
+            }));
+            let finished = !task_files.is_empty()
+                && task_files
+                    .iter()
+                    .all(|(_, path)| Self::is_task_completed(&path.to_string_lossy()));
+            (
+                SessionType::Hive {
+                    worker_count: task_files.len() as u8,
+                },
+                agents,
+                finished,
+            )
+        };
+
+        let created_at = std::fs::metadata(&session_root)
+            .and_then(|metadata| metadata.created().or_else(|_| metadata.modified()))
+            .map(DateTime::<Utc>::from)
+            .unwrap_or_else(|_| Utc::now());
+        let mut execution_policy = HiveExecutionPolicy::default();
+        if no_git {
+            execution_policy.workspace_strategy = WorkspaceStrategy::None;
+        }
+        let (max_qa_iterations, qa_timeout_secs, auth_strategy) = default_session_qa_settings();
+
+        Ok(Session {
+            id: session_id.to_string(),
+            name: None,
+            color: None,
+            session_type,
+            project_path: project,
+            state: if finished {
+                SessionState::Completed
+            } else {
+                SessionState::Paused
+            },
+            created_at,
+            last_activity_at: Utc::now(),
+            agents,
+            default_cli: AgentConfig::default().cli,
+            default_model: None,
+            default_principal_cli: None,
+            default_principal_model: None,
+            default_principal_flags: Vec::new(),
+            execution_policy,
+            qa_workers: Vec::new(),
+            max_qa_iterations,
+            qa_timeout_secs,
+            auth_strategy,
+            worktree_path: None,
+            worktree_branch: None,
+            no_git,
+            resume_report: None,
+            pull_request_url: None,
+            spawn_quota: SpawnQuota::default(),
+        })
+    }
+
+    /// Set the status of each agent of a resumed, unfinished session from what is
+    /// actually there: Running when its PTY is alive, Completed when its task file
+    /// says so, and an error when it has neither. Finished sessions are left as
+    /// loaded. Returns one entry per agent for the resume report.
+    fn reconcile_resumed_agents(
+        &self,
+        session: &mut Session,
+    ) -> Vec<crate::domain::run_journal::AgentReconciliation> {
+        use crate::domain::run_journal::{AgentReconciliation, AgentResumeState};
+
+        if is_terminal_session_state(&session.state) {
+            return Vec::new();
+        }
+        let task_statuses: Vec<Option<String>> = session
+            .agents
+            .iter()
+            .map(|agent| match agent.role {
+                AgentRole::Worker { index, .. } => {
+                    Self::task_file_path_for_session_worker(session, index as usize)
+                        .ok()
+                        .and_then(|path| std::fs::read_to_string(path).ok())
+                        .and_then(|content| Self::parse_task_status(&content))
+                }
+                _ => None,
+            })
+            .collect();
+
+        let pty_manager = self.pty_manager.read();
+        session
+            .agents
+            .iter_mut()
+            .zip(task_statuses)
+            .map(|(agent, task_status)| {
+                let state = if pty_manager.is_alive(&agent.id) {
+                    AgentResumeState::Running
+                } else if task_status.as_deref() == Some("COMPLETED") {
+                    AgentResumeState::Completed
+                } else {
+                    AgentResumeState::MissingPty
+                };
+                agent.status = match state {
+                    AgentResumeState::Running => AgentStatus::Running,
+                    AgentResumeState::Completed => AgentStatus::Completed,
+                    AgentResumeState::MissingPty => {
+                        AgentStatus::Error("No running process after resume".to_string())
+                    }
+                };
+                AgentReconciliation {
+                    agent_id: agent.id.clone(),
+                    state,
+                    task_status,
+                }
+            })
+            .collect()
+    }
+
     /// #125: read the run journal, classify each step, mark completed write-steps as
     /// Skipped, and verify unconfirmed ledger effects against the repo. Returns a
     /// [`ResumeReport`](crate::domain::run_journal::ResumeReport). Empty (and cheap) when
@@ -12310,6 +17846,8 @@ phases and do EXACTLY this, then stop:
                         prompt_template: pa.config.initial_prompt.clone(),
                     }),
                     initial_prompt: pa.config.initial_prompt.clone(),
+                    allowed_paths: Vec::new(),
+                    denied_paths: Vec::new(),
                 };
 
                 Some(AgentInfo {
@@ -12320,6 +17858,7 @@ phases and do EXACTLY this, then stop:
                     parent_id: pa.parent_id.clone(),
                     commit_sha: pa.commit_sha.clone(),
                     base_commit_sha: pa.base_commit_sha.clone(),
+                    exit_info: None,
                 })
             })
             .collect();
@@ -12363,6 +17902,8 @@ phases and do EXACTLY this, then stop:
             worktree_branch: persisted.worktree_branch.clone(),
             no_git: persisted.no_git,
             resume_report: None,
+            pull_request_url: persisted.pull_request_url.clone(),
+            spawn_quota: persisted.spawn_quota.clone(),
         })
     }
 
@@ -12440,7 +17981,7 @@ phases and do EXACTLY this, then stop:
                 &session.project_path,
                 session_id,
                 "queen-prompt.md",
-                &master_prompt,
+                &self.with_session_api_token(session_id, &master_prompt),
             )?;
             let prompt_path = prompt_file.to_string_lossy().to_string();
             Self::add_prompt_to_args(&cmd, &mut args, &prompt_path);
@@ -12475,6 +18016,7 @@ phases and do EXACTLY this, then stop:
                 parent_id: None,
                 commit_sha: None,
                 base_commit_sha: None,
+                exit_info: None,
             });
 
             // NOTE: Planners and Workers are NOT spawned here anymore
@@ -12533,7 +18075,9 @@ phases and do EXACTLY this, then stop:
     }
 
     pub fn launch_swarm(&self, config: SwarmLaunchConfig) -> Result<Session, String> {
-        let session_id = Uuid::new_v4().to_string();
+        let session_id = launch_queue::new_session_id();
+        self.track_phase_timeouts(&session_id, config.phase_timeouts);
+        self.track_background(&session_id, config.background);
         let default_cli = config.default_cli.trim().to_string();
         let default_model = config.default_model.clone();
 
@@ -12579,7 +18123,7 @@ phases and do EXACTLY this, then stop:
                 &project_path,
                 &session_id,
                 "queen-prompt.md",
-                &master_prompt,
+                &self.with_session_api_token(&session_id, &master_prompt),
             )?;
             let prompt_path = prompt_file.to_string_lossy().to_string();
             Self::add_prompt_to_args(&cmd, &mut args, &prompt_path);
@@ -12619,6 +18163,7 @@ phases and do EXACTLY this, then stop:
                 parent_id: None,
                 commit_sha: None,
                 base_commit_sha: None,
+                exit_info: None,
             });
 
             // NOTE: Planners and Workers are NOT spawned here anymore
@@ -12664,6 +18209,8 @@ phases and do EXACTLY this, then stop:
             worktree_branch: None,
             no_git: false,
             resume_report: None,
+            pull_request_url: None,
+            spawn_quota: SpawnQuota::default(),
         };
 
         {
@@ -12715,6 +18262,8 @@ phases and do EXACTLY this, then stop:
             description: None,
             role: None,
             initial_prompt: None,
+            allowed_paths: Vec::new(),
+            denied_paths: Vec::new(),
         });
 
         if let Some(configured_qa_workers) = qa_workers {
@@ -12741,6 +18290,8 @@ phases and do EXACTLY this, then stop:
             description: None,
             role: None,
             initial_prompt: None,
+            allowed_paths: Vec::new(),
+            denied_paths: Vec::new(),
         };
         let _prince = self.launch_prince(session_id, prince_config, smoke_test)?;
 
@@ -12804,6 +18355,7 @@ phases and do EXACTLY this, then stop:
                 | SessionState::QaFailed { .. }
                 | SessionState::QaMaxRetriesExceeded
                 | SessionState::PrinceRemediation
+                | SessionState::Reviewing { .. }
         );
         if !can_add_worker {
             return Err(format!(
@@ -12820,6 +18372,25 @@ phases and do EXACTLY this, then stop:
             .count();
         let worker_index = (existing_workers + 1) as u8;
 
+        if let Some(policy) = self.policy_script.as_ref() {
+            let context = self.policy_context(
+                session_id,
+                serde_json::json!({
+                    "role_type": role.role_type,
+                    "label": role.label,
+                    "cli": config.cli,
+                    "workers": existing_workers,
+                }),
+            );
+            if let SpawnDecision::Veto(reason) = policy.allow_spawn(&context) {
+                self.log_coordination_system(
+                    session_id,
+                    &format!("[POLICY] Refused to add a {} worker: {}", role.label, reason),
+                );
+                return Err(format!("Worker spawn refused by policy script: {}", reason));
+            }
+        }
+
         // Determine parent (default to Queen)
         let actual_parent_id = parent_id.unwrap_or_else(|| format!("{}-queen", session_id));
 
@@ -12832,6 +18403,12 @@ phases and do EXACTLY this, then stop:
             && matches!(&session.session_type, SessionType::Hive { .. })
             && session.execution_policy.workspace_strategy == WorkspaceStrategy::SharedCell;
         let creates_worker_worktree = !session.no_git && !uses_shared_workspace;
+        // Only workers that share the session's directory can take a warm process.
+        let warm_key = (!creates_worker_worktree).then(|| WarmKey {
+            session_id: session_id.to_string(),
+            command: cmd.clone(),
+            args: args.clone(),
+        });
         let worker_branch = if uses_shared_workspace {
             session
                 .worktree_branch
@@ -12911,7 +18488,7 @@ phases and do EXACTLY this, then stop:
         };
 
         // Write worker prompt to file and add to args
-        let worker_prompt = Self::build_worker_prompt(
+        let mut worker_prompt = Self::build_worker_prompt(
             worker_index,
             &config_with_role,
             &actual_parent_id,
@@ -12919,13 +18496,19 @@ phases and do EXACTLY this, then stop:
             &session.project_path,
             Path::new(&worker_cwd),
             &session.execution_policy,
+            self.worker_project_dna(session_id, &session.project_path)
+                .as_deref(),
         );
+        if let Some(pack) = Self::worker_context_pack(&session, worker_index, &config_with_role) {
+            worker_prompt.push_str("\n\n");
+            worker_prompt.push_str(&pack);
+        }
         let filename = format!("worker-{}-prompt.md", worker_index);
         let prompt_file = match Self::write_worker_prompt_file(
             Path::new(&worker_cwd),
             worker_index,
             &filename,
-            &worker_prompt,
+            &self.with_session_api_token(session_id, &worker_prompt),
         ) {
             Ok(prompt_file) => prompt_file,
             Err(err) => {
@@ -12956,9 +18539,15 @@ phases and do EXACTLY this, then stop:
             index: worker_index,
             parent: Some(actual_parent_id.clone()),
         };
-
-        // Spawn PTY
-        {
+
+        // Spawn PTY, unless a warm process is handed the prompt instead
+        let handed_over = warm_key
+            .as_ref()
+            .and_then(|key| self.take_warm_process(key))
+            .is_some_and(|warm| {
+                self.hand_over_warm_process(&warm, &worker_id, worker_role.clone(), &prompt_path)
+            });
+        if !handed_over {
             let pty_manager = self.pty_manager.read();
             if let Err(e) = pty_manager.create_session(
                 worker_id.clone(),
@@ -12992,6 +18581,7 @@ phases and do EXACTLY this, then stop:
             parent_id: Some(actual_parent_id),
             commit_sha: None,
             base_commit_sha: worker_base_commit_sha,
+            exit_info: None,
         };
 
         // Update session
@@ -13023,6 +18613,452 @@ phases and do EXACTLY this, then stop:
         Ok(agent_info)
     }
 
+    /// Keep `config.pools` idle CLI processes started in the worker directory of
+    /// every running session whose workers share one (see [`warm_pool`]), and kill
+    /// those no longer wanted. Returns how many were started.
+    ///
+    /// [`warm_pool`]: crate::session::warm_pool
+    pub fn refill_warm_pool(&self, config: &WarmPoolConfig) -> usize {
+        let mut wanted: Vec<(WarmKey, usize, String)> = Vec::new();
+        if config.enabled {
+            for session in self.sessions.read().values() {
+                if !session.state.is_monitorable() {
+                    continue;
+                }
+                let Some(cwd) = Self::shared_worker_dir(session) else {
+                    continue;
+                };
+                for entry in &config.pools {
+                    let agent_config = AgentConfig {
+                        cli: entry.cli.clone(),
+                        model: entry.model.clone(),
+                        ..AgentConfig::default()
+                    };
+                    let (command, args) = Self::build_command(&agent_config);
+                    let key = WarmKey {
+                        session_id: session.id.clone(),
+                        command,
+                        args,
+                    };
+                    wanted.push((key, usize::from(entry.size), cwd.clone()));
+                }
+            }
+        }
+        let sizes: Vec<(WarmKey, usize)> = wanted
+            .iter()
+            .map(|(key, size, _)| (key.clone(), *size))
+            .collect();
+
+        let (dropped, shortfall) = {
+            let pty_manager = self.pty_manager.read();
+            let mut pool = self.warm_pool.lock();
+            let dropped = pool.trim(&sizes, |pty_id| pty_manager.is_alive(pty_id));
+            (dropped, pool.shortfall(&sizes))
+        };
+        self.kill_warm_processes(dropped);
+
+        let mut started = 0;
+        for (key, missing) in shortfall {
+            let Some((_, _, cwd)) = wanted.iter().find(|(wanted, _, _)| *wanted == key) else {
+                continue;
+            };
+            for _ in 0..missing {
+                let pty_id = self.warm_pool.lock().next_pty_id();
+                let spawned = self.pty_manager.read().create_session(
+                    pty_id.clone(),
+                    AgentRole::Worker {
+                        index: 0,
+                        parent: None,
+                    },
+                    &key.command,
+                    &key.args.iter().map(String::as_str).collect::<Vec<_>>(),
+                    Some(cwd),
+                    120,
+                    30,
+                );
+                match spawned {
+                    Ok(_) => {
+                        tracing::info!(
+                            "Warmed {} for session {} as {}",
+                            key.command,
+                            key.session_id,
+                            pty_id
+                        );
+                        self.warm_pool.lock().add(WarmProcess {
+                            pty_id,
+                            key: key.clone(),
+                        });
+                        started += 1;
+                    }
+                    Err(e) => {
+                        tracing::warn!(
+                            "Failed to warm {} for session {}: {}",
+                            key.command,
+                            key.session_id,
+                            e
+                        );
+                        break;
+                    }
+                }
+            }
+        }
+        started
+    }
+
+    /// The directory every worker of `session` runs in, when they share one.
+    fn shared_worker_dir(session: &Session) -> Option<String> {
+        if session.no_git {
+            return Some(session.project_path.to_string_lossy().to_string());
+        }
+        let shared = matches!(&session.session_type, SessionType::Hive { .. })
+            && session.execution_policy.workspace_strategy == WorkspaceStrategy::SharedCell;
+        if shared {
+            session.worktree_path.clone()
+        } else {
+            None
+        }
+    }
+
+    /// The oldest warm process started as `key` that is alive and has gone quiet
+    /// after starting up.
+    fn take_warm_process(&self, key: &WarmKey) -> Option<WarmProcess> {
+        let pty_manager = self.pty_manager.read();
+        self.warm_pool.lock().take(key, |pty_id| {
+            pty_manager.is_alive(pty_id)
+                && pty_manager
+                    .idle_for(pty_id)
+                    .is_some_and(|idle| idle >= WARM_PROCESS_SETTLED)
+        })
+    }
+
+    /// Give `warm` to `worker_id` and paste the instruction to read its prompt
+    /// file. Returns false, having killed the process, when that fails, so the
+    /// caller starts a fresh one.
+    fn hand_over_warm_process(
+        &self,
+        warm: &WarmProcess,
+        worker_id: &str,
+        role: AgentRole,
+        prompt_path: &str,
+    ) -> bool {
+        let instruction = Self::prompt_file_instruction_for_cli(&warm.key.command, prompt_path);
+        let pty_manager = self.pty_manager.read();
+        let handed_over = pty_manager
+            .adopt(&warm.pty_id, worker_id.to_string(), role)
+            .and_then(|()| pty_manager.submit(worker_id, &instruction));
+        match handed_over {
+            Ok(()) => {
+                tracing::info!("Handed warm process {} to {}", warm.pty_id, worker_id);
+                true
+            }
+            Err(e) => {
+                tracing::warn!(
+                    "Warm process {} could not take over {}: {}",
+                    warm.pty_id,
+                    worker_id,
+                    e
+                );
+                let _ = pty_manager.kill(&warm.pty_id);
+                let _ = pty_manager.kill(worker_id);
+                false
+            }
+        }
+    }
+
+    fn kill_warm_processes(&self, processes: Vec<WarmProcess>) {
+        let pty_manager = self.pty_manager.read();
+        for process in processes {
+            if let Err(e) = pty_manager.kill(&process.pty_id) {
+                tracing::warn!("Failed to stop warm process {}: {}", process.pty_id, e);
+            }
+        }
+    }
+
+    /// Reserve room in the session's spawn quota for one more `role_type` worker.
+    /// Workers still being spawned under an earlier reservation count as live, and
+    /// the check and the reservation happen under one lock, so two concurrent spawns
+    /// cannot both take the last slot. Sessions without a quota always have room.
+    pub fn reserve_spawn_quota(
+        &self,
+        session_id: &str,
+        role_type: &str,
+    ) -> Result<SpawnReservation, QuotaExceeded> {
+        let mut reservations = self.spawn_reservations.lock();
+        if let Some(session) = self.get_session(session_id) {
+            if !session.spawn_quota.is_empty() {
+                let mut live_roles: Vec<String> = session
+                    .agents
+                    .into_iter()
+                    .filter(|agent| matches!(agent.role, AgentRole::Worker { .. }))
+                    .filter(|agent| {
+                        !matches!(
+                            agent.status,
+                            AgentStatus::Completed | AgentStatus::Crashed(_) | AgentStatus::Error(_)
+                        )
+                    })
+                    .map(|agent| {
+                        agent
+                            .config
+                            .role
+                            .map(|role| role.role_type)
+                            .unwrap_or_default()
+                    })
+                    .collect();
+                live_roles.extend(reservations.get(session_id).into_iter().flatten().cloned());
+                session.spawn_quota.check(role_type, &live_roles)?;
+            }
+        }
+        reservations
+            .entry(session_id.to_string())
+            .or_default()
+            .push(role_type.to_string());
+        Ok(SpawnReservation::new(
+            Arc::clone(&self.spawn_reservations),
+            session_id,
+            role_type,
+        ))
+    }
+
+    /// Replace the spawn quota of a running session; an empty quota lifts it.
+    pub fn override_spawn_quota(&self, session_id: &str, quota: SpawnQuota) -> Result<(), String> {
+        if self.get_session(session_id).is_none() {
+            return Err(format!("Session not found: {}", session_id));
+        }
+        let summary = if quota.is_empty() {
+            "lifted".to_string()
+        } else {
+            let mut limits: Vec<String> = quota
+                .per_role
+                .iter()
+                .map(|(role, limit)| format!("{} {}", limit, role))
+                .collect();
+            if let Some(max_workers) = quota.max_workers {
+                limits.insert(0, format!("{} in total", max_workers));
+            }
+            format!("set to {}", limits.join(", "))
+        };
+        if let Some(session) = self.sessions.write().get_mut(session_id) {
+            session.spawn_quota = quota;
+        }
+        self.update_session_storage(session_id);
+        self.emit_session_update(session_id);
+        self.log_coordination_system(
+            session_id,
+            &format!("[QUOTA] Worker spawn quota {} by the operator", summary),
+        );
+        Ok(())
+    }
+
+    /// Spawn or retire workers until `target_count` are live. New workers start on
+    /// STANDBY with the session's principal defaults. Surplus workers are retired
+    /// newest first, and only while their task file is STANDBY or COMPLETED; busy
+    /// ones keep running and are counted in `pending_retirement`.
+    pub fn scale_workers(
+        &self,
+        session_id: &str,
+        target_count: u8,
+    ) -> Result<WorkerScaleReport, String> {
+        let session = self
+            .get_session(session_id)
+            .ok_or_else(|| format!("Session not found: {}", session_id))?;
+        if !matches!(session.session_type, SessionType::Hive { .. }) {
+            return Err(format!("Session {} is not a Hive session", session_id));
+        }
+        if is_terminal_session_state(&session.state) {
+            return Err(format!(
+                "Session {} has already finished ({:?})",
+                session_id, session.state
+            ));
+        }
+
+        let mut live_workers: Vec<(u8, String)> = session
+            .agents
+            .iter()
+            .filter(|agent| {
+                !matches!(
+                    agent.status,
+                    AgentStatus::Completed | AgentStatus::Crashed(_) | AgentStatus::Error(_)
+                )
+            })
+            .filter_map(|agent| match agent.role {
+                AgentRole::Worker { index, .. } => Some((index, agent.id.clone())),
+                _ => None,
+            })
+            .collect();
+        live_workers.sort_by_key(|(index, _)| *index);
+
+        let mut report = WorkerScaleReport {
+            session_id: session_id.to_string(),
+            target_count,
+            ..WorkerScaleReport::default()
+        };
+        let target = target_count as usize;
+        if live_workers.len() < target {
+            let defaults = self
+                .get_session_principal_defaults(session_id)
+                .ok_or_else(|| format!("Session not found: {}", session_id))?;
+            // Research sessions only accept read-only workers.
+            let role = if session.no_git {
+                WorkerRole::new("researcher", "Researcher", &defaults.cli)
+            } else {
+                WorkerRole::new("general", "Worker", &defaults.cli)
+            };
+            for _ in live_workers.len()..target {
+                let agent = self.add_worker(session_id, defaults.clone(), role.clone(), None)?;
+                report.added.push(agent.id);
+            }
+        } else {
+            let mut surplus = live_workers.len() - target;
+            for (index, agent_id) in live_workers.iter().rev() {
+                if surplus == 0 {
+                    break;
+                }
+                let idle = Self::task_file_path_for_session_worker(&session, *index as usize)
+                    .map(|path| Self::read_task_status(&path.to_string_lossy()))
+                    .is_ok_and(|status| status == "STANDBY" || status == "COMPLETED");
+                if !idle {
+                    continue;
+                }
+                self.stop_agent(session_id, agent_id)?;
+                report.retired.push(agent_id.clone());
+                surplus -= 1;
+            }
+            report.pending_retirement = surplus;
+        }
+
+        let live_count =
+            (live_workers.len() + report.added.len() - report.retired.len()).min(u8::MAX as usize);
+        report.worker_count = live_count as u8;
+        {
+            let mut sessions = self.sessions.write();
+            if let Some(session) = sessions.get_mut(session_id) {
+                if let SessionType::Hive { worker_count } = &mut session.session_type {
+                    *worker_count = report.worker_count;
+                }
+            }
+        }
+        self.update_session_storage(session_id);
+        self.emit_session_update(session_id);
+        tracing::info!(
+            "Scaled session {} to {} workers (+{} / -{}, {} pending retirement)",
+            session_id,
+            report.worker_count,
+            report.added.len(),
+            report.retired.len(),
+            report.pending_retirement
+        );
+
+        Ok(report)
+    }
+
+    /// Escalate a running Solo session into a Hive without relaunching it. The Solo
+    /// agent stays on as Worker 1 in its existing worktree and is handed the
+    /// standard worker prompt and task file; each entry in `workers` is then spawned
+    /// through [`Self::add_worker`]. The session ID, agent PTY and coordination
+    /// history are all kept.
+    pub fn promote_session(
+        &self,
+        session_id: &str,
+        workers: Vec<AgentConfig>,
+    ) -> Result<Session, String> {
+        let solo_id = Self::solo_agent_id(session_id);
+        let (session, solo_config) = {
+            let mut sessions = self.sessions.write();
+            let session = sessions
+                .get_mut(session_id)
+                .ok_or_else(|| format!("Session not found: {}", session_id))?;
+            if !matches!(session.session_type, SessionType::Solo { .. }) {
+                return Err(format!("Session {} is not a Solo session", session_id));
+            }
+            if is_terminal_session_state(&session.state) {
+                return Err(format!(
+                    "Session {} has already finished ({:?})",
+                    session_id, session.state
+                ));
+            }
+            if session.worktree_path.is_none() {
+                return Err(format!("Solo session {} has no worktree", session_id));
+            }
+            let solo_agent = session
+                .agents
+                .iter_mut()
+                .find(|agent| agent.id == solo_id)
+                .ok_or_else(|| format!("Solo agent not found: {}", solo_id))?;
+            let role =
+                solo_agent.config.role.clone().unwrap_or_else(|| {
+                    WorkerRole::new("general", "Worker", &solo_agent.config.cli)
+                });
+            solo_agent.config = Self::apply_worker_identity(1, &role, solo_agent.config.clone());
+            let solo_config = solo_agent.config.clone();
+
+            // Switch the type first so the prompts, tool docs and `add_worker` all see a Hive.
+            session.session_type = SessionType::Hive { worker_count: 1 };
+            session.execution_policy.launch_kind = HiveLaunchKind::Hive;
+            (session.clone(), solo_config)
+        };
+        self.solo_task_queues.lock().remove(session_id);
+        tracing::info!("Promoting Solo session {} to a Hive", session_id);
+
+        Self::write_tool_files(
+            &session.project_path,
+            session_id,
+            Self::session_principal_cli(&session),
+        )?;
+        let task_file_path = Self::task_file_path_for_session_worker(&session, 1)?;
+        Self::write_task_file_at_path(
+            &task_file_path,
+            1,
+            solo_config.initial_prompt.as_deref(),
+            solo_config.initial_prompt.as_deref().map(|_| "ACTIVE"),
+            false,
+        )?;
+        let solo_cwd = PathBuf::from(session.worktree_path.as_deref().unwrap_or_default());
+        let worker_prompt = Self::build_worker_prompt(
+            1,
+            &solo_config,
+            &format!("{}-queen", session_id),
+            session_id,
+            &session.project_path,
+            &solo_cwd,
+            &session.execution_policy,
+            self.worker_project_dna(session_id, &session.project_path)
+                .as_deref(),
+        );
+        let prompt_file = Self::write_worker_prompt_file(
+            &solo_cwd,
+            1,
+            "worker-1-prompt.md",
+            &self.with_session_api_token(session_id, &worker_prompt),
+        )?;
+        let instruction = Self::prompt_file_instruction(&prompt_file.to_string_lossy());
+        if let Err(e) = self
+            .pty_manager
+            .read()
+            .write_bracketed(&solo_id, format!("{}\r", instruction).as_bytes())
+        {
+            tracing::warn!(
+                "Failed to hand the worker prompt to promoted agent {}: {}",
+                solo_id,
+                e
+            );
+        }
+
+        self.emit_session_update(session_id);
+        self.update_session_storage(session_id);
+        self.ensure_task_watcher(session_id, &session.project_path);
+
+        for config in workers {
+            let role = config
+                .role
+                .clone()
+                .unwrap_or_else(|| WorkerRole::new("general", "Worker", &config.cli));
+            self.add_worker(session_id, config, role, None)?;
+        }
+
+        self.get_session(session_id)
+            .ok_or_else(|| format!("Session disappeared after promotion: {}", session_id))
+    }
+
     #[allow(dead_code)]
     pub fn launch_evaluator(
         &self,
@@ -13092,6 +19128,7 @@ phases and do EXACTLY this, then stop:
         };
         let execution_workspace = Self::execution_workspace(&session);
         let evaluator_prompt = Self::build_evaluator_prompt(
+            &self.template_engine(),
             session_id,
             &config,
             &session.qa_workers,
@@ -13103,7 +19140,7 @@ phases and do EXACTLY this, then stop:
             &session.project_path,
             session_id,
             "evaluator-prompt.md",
-            &evaluator_prompt,
+            &self.with_session_api_token(session_id, &evaluator_prompt),
         )?;
 
         let (cmd, mut args) = Self::build_command(&config);
@@ -13150,6 +19187,7 @@ phases and do EXACTLY this, then stop:
             parent_id: None,
             commit_sha: None,
             base_commit_sha: None,
+            exit_info: None,
         };
 
         let (timeout_secs, qa_changes) = {
@@ -13233,6 +19271,7 @@ phases and do EXACTLY this, then stop:
         Self::write_tool_files(&session.project_path, session_id, &principal_defaults.cli)?;
         let execution_workspace = Self::execution_workspace(&session);
         let prince_prompt = Self::build_prince_prompt(
+            &self.template_engine(),
             session_id,
             &config,
             &principal_defaults,
@@ -13244,7 +19283,7 @@ phases and do EXACTLY this, then stop:
             &session.project_path,
             session_id,
             "prince-prompt.md",
-            &prince_prompt,
+            &self.with_session_api_token(session_id, &prince_prompt),
         )?;
 
         let (cmd, mut args) = Self::build_command(&config);
@@ -13281,6 +19320,7 @@ phases and do EXACTLY this, then stop:
             parent_id: None,
             commit_sha: None,
             base_commit_sha: None,
+            exit_info: None,
         };
 
         {
@@ -13355,6 +19395,7 @@ phases and do EXACTLY this, then stop:
             config.initial_prompt.as_deref(),
         )?;
         let qa_worker_prompt = Self::build_qa_worker_prompt(
+            &self.template_engine(),
             session_id,
             next_index,
             &specialization,
@@ -13368,7 +19409,7 @@ phases and do EXACTLY this, then stop:
             &session.project_path,
             session_id,
             &format!("qa-worker-{}-prompt.md", next_index),
-            &qa_worker_prompt,
+            &self.with_session_api_token(session_id, &qa_worker_prompt),
         )?;
 
         let (cmd, mut args) = Self::build_command(&config);
@@ -13402,6 +19443,7 @@ phases and do EXACTLY this, then stop:
             parent_id: Some(evaluator_id),
             commit_sha: None,
             base_commit_sha: None,
+            exit_info: None,
         };
 
         let qa_changes = {
@@ -13426,6 +19468,127 @@ phases and do EXACTLY this, then stop:
         Ok(agent_info)
     }
 
+    /// Run `f` on a Swarm session's domain schedule, built from its plan the first
+    /// time the plan names domains. Planners already in the session are assigned
+    /// and those marked completed counted, so a restored session keeps its order.
+    fn with_domain_schedule<T>(
+        &self,
+        session: &Session,
+        f: impl FnOnce(&mut DomainSchedule) -> T,
+    ) -> Option<T> {
+        if !matches!(session.session_type, SessionType::Swarm { .. }) {
+            return None;
+        }
+        let mut schedules = self.domain_schedules.lock();
+        if !schedules.contains_key(&session.id) {
+            let session_root = Self::session_root_path(&session.project_path, &session.id);
+            let plan = plan_parser::load(&session_root).ok().flatten()?;
+            let mut schedule = DomainSchedule::new(plan.domains);
+            if schedule.is_empty() {
+                return None;
+            }
+            let heartbeats = self.agent_heartbeats.read();
+            for agent in &session.agents {
+                let AgentRole::Planner { index } = agent.role else {
+                    continue;
+                };
+                let label = agent.config.label.as_deref().unwrap_or_default();
+                let Some(domain) = schedule.resolve(label.trim_end_matches(" Planner"), index)
+                else {
+                    continue;
+                };
+                schedule.assign(index, domain);
+                let completed = matches!(agent.status, AgentStatus::Completed)
+                    || heartbeats
+                        .get(&session.id)
+                        .and_then(|agents| agents.get(&agent.id))
+                        .is_some_and(|heartbeat| heartbeat.status == "completed");
+                if completed {
+                    schedule.complete_planner(index);
+                }
+            }
+            schedules.insert(session.id.clone(), schedule);
+        }
+        schedules.get_mut(&session.id).map(f)
+    }
+
+    /// Names of the domains a new planner for `domain` would still wait on; empty
+    /// when it may start now or the plan sets no order.
+    pub fn domain_blockers(&self, session_id: &str, domain: &str) -> Vec<String> {
+        let Some(session) = self.get_session(session_id) else {
+            return Vec::new();
+        };
+        let planner_index = Self::next_planner_index(&session);
+        self.with_domain_schedule(&session, |schedule| {
+            let Some(number) = schedule.resolve(domain, planner_index) else {
+                return Vec::new();
+            };
+            schedule
+                .blockers(number)
+                .into_iter()
+                .map(|blocker| schedule.name(blocker))
+                .collect()
+        })
+        .unwrap_or_default()
+    }
+
+    /// Count the domain of a planner marked completed as done, and announce each
+    /// domain that can start because of it.
+    fn complete_swarm_domain(&self, session_id: &str, agent_id: &str) {
+        let Some(planner_index) = agent_id
+            .strip_prefix(&format!("{session_id}-planner-"))
+            .and_then(|index| index.parse::<u8>().ok())
+        else {
+            return;
+        };
+        let Some(session) = self.get_session(session_id) else {
+            return;
+        };
+        let Some(Some((completed, unblocked))) = self.with_domain_schedule(&session, |schedule| {
+            schedule
+                .complete_planner(planner_index)
+                .map(|(domain, unblocked)| {
+                    let unblocked: Vec<(usize, String)> = unblocked
+                        .into_iter()
+                        .map(|number| (number, schedule.name(number)))
+                        .collect();
+                    (schedule.name(domain), unblocked)
+                })
+        }) else {
+            return;
+        };
+
+        for (domain, name) in unblocked {
+            self.log_coordination_system(
+                session_id,
+                &format!(
+                    "[DOMAIN_UNBLOCKED] Domain {} ({}) can start now that {} is complete",
+                    domain, name, completed
+                ),
+            );
+            if let Some(ref app_handle) = self.app_handle {
+                let _ = app_handle.emit(
+                    "domain-unblocked",
+                    DomainUnblocked {
+                        session_id: session_id.to_string(),
+                        domain,
+                        name,
+                        completed: completed.clone(),
+                    },
+                );
+            }
+        }
+    }
+
+    fn next_planner_index(session: &Session) -> u8 {
+        let existing_planners = session
+            .agents
+            .iter()
+            .filter(|a| matches!(a.role, AgentRole::Planner { .. }))
+            .count();
+        (existing_planners + 1) as u8
+    }
+
     /// Add a planner to a Swarm session (called by Queen via HTTP API)
     pub fn add_planner(
         &self,
@@ -13453,13 +19616,17 @@ phases and do EXACTLY this, then stop:
             ));
         }
 
-        // Determine planner index
-        let existing_planners = session
-            .agents
-            .iter()
-            .filter(|a| matches!(a.role, AgentRole::Planner { .. }))
-            .count();
-        let planner_index = (existing_planners + 1) as u8;
+        let planner_index = Self::next_planner_index(&session);
+
+        // Domains the plan orders after unfinished ones wait for a domain-unblocked notice
+        let waiting_on = self.domain_blockers(session_id, &domain);
+        if !waiting_on.is_empty() {
+            return Err(format!(
+                "Domain {} waits on {}; spawn its planner once they are complete",
+                domain,
+                waiting_on.join(", ")
+            ));
+        }
 
         // Get queen ID as parent
         let queen_id = format!("{}-queen", session_id);
@@ -13494,7 +19661,7 @@ phases and do EXACTLY this, then stop:
             &session.project_path,
             session_id,
             &filename,
-            &planner_prompt,
+            &self.with_session_api_token(session_id, &planner_prompt),
         )?;
         let prompt_path = prompt_file.to_string_lossy().to_string();
         Self::add_prompt_to_args(&cmd, &mut args, &prompt_path);
@@ -13547,8 +19714,15 @@ phases and do EXACTLY this, then stop:
             parent_id: Some(queen_id),
             commit_sha: None,
             base_commit_sha: None,
+            exit_info: None,
         };
 
+        self.with_domain_schedule(&session, |schedule| {
+            if let Some(number) = schedule.resolve(&domain, planner_index) {
+                schedule.assign(planner_index, number);
+            }
+        });
+
         // Update session state to WaitingForPlanner
         let waiting_changes = {
             let mut sessions = self.sessions.write();
@@ -13686,6 +19860,8 @@ phases and do EXACTLY this, then stop:
             worktree_path: session.worktree_path.clone(),
             worktree_branch: session.worktree_branch.clone(),
             no_git: session.no_git,
+            pull_request_url: session.pull_request_url.clone(),
+            spawn_quota: session.spawn_quota.clone(),
         }
     }
 
@@ -13703,6 +19879,13 @@ phases and do EXACTLY this, then stop:
                 tracing::warn!("Failed to save session metadata: {}", e);
             }
 
+            let exclude = storage
+                .load_config()
+                .map_or(true, |config| config.exclude_session_artifacts);
+            if exclude && !session.no_git {
+                self.exclude_session_artifacts(session);
+            }
+
             // Build hierarchy nodes
             let hierarchy: Vec<HierarchyNode> = session
                 .agents
@@ -13720,6 +19903,7 @@ phases and do EXACTLY this, then stop:
                     HierarchyNode {
                         id: agent.id.clone(),
                         role: role_str,
+                        label: agent.config.label.clone(),
                         parent_id: agent.parent_id.clone(),
                         children,
                     }
@@ -13733,6 +19917,7 @@ phases and do EXACTLY this, then stop:
                 .filter(|a| include_in_worker_roster(&a.role))
                 .map(|a| WorkerStateInfo {
                     id: a.id.clone(),
+                    label: a.config.label.clone(),
                     role: a.config.role.clone().unwrap_or_default(),
                     cli: a.config.cli.clone(),
                     status: format!("{:?}", a.status),
@@ -13753,6 +19938,28 @@ phases and do EXACTLY this, then stop:
         }
     }
 
+    /// Keep the session artifact dirs out of agent commits through the project's
+    /// `.git/info/exclude`, noting any entries added in the coordination log.
+    fn exclude_session_artifacts(&self, session: &Session) {
+        match project_artifacts::ensure_excluded(&session.project_path) {
+            Ok(Some(update)) if !update.added.is_empty() => {
+                let message = format!(
+                    "Added {} to {}",
+                    update.added.join(", "),
+                    update.path.display()
+                );
+                tracing::info!("{}", message);
+                self.log_coordination_system(&session.id, &message);
+            }
+            Ok(_) => {}
+            Err(e) => tracing::warn!(
+                "Failed to exclude session artifacts in {}: {}",
+                session.project_path.display(),
+                e
+            ),
+        }
+    }
+
     fn ensure_task_watcher(&self, session_id: &str, project_path: &PathBuf) {
         let app_handle = match self.app_handle.clone() {
             Some(handle) => handle,
@@ -13769,8 +19976,16 @@ phases and do EXACTLY this, then stop:
             .join(".hive-manager")
             .join("worktrees")
             .join(session_id);
-        let fusion_worktrees_path = project_path.join(".hive-fusion").join(session_id);
+        let fusion_worktrees_path = Self::read_fusion_metadata(project_path, session_id)
+            .map(|metadata| metadata.worktree_root(project_path, session_id))
+            .unwrap_or_else(|_| project_path.join(".hive-fusion").join(session_id));
         let debate_worktrees_path = project_path.join(".hive-debate").join(session_id);
+        let file_watch = self
+            .storage
+            .as_ref()
+            .and_then(|storage| storage.load_config().ok())
+            .map(|config| config.file_watch)
+            .unwrap_or_default();
 
         match TaskFileWatcher::new(
             &session_path,
@@ -13779,6 +19994,7 @@ phases and do EXACTLY this, then stop:
             &debate_worktrees_path,
             session_id,
             app_handle,
+            file_watch,
         ) {
             Ok(watcher) => {
                 watchers.insert(session_id.to_string(), watcher);
@@ -13797,6 +20013,8 @@ phases and do EXACTLY this, then stop:
     }
 
     fn update_session_storage_checked(&self, session_id: &str) -> Result<(), String> {
+        // Changes made from here on are after the copy written below.
+        self.unsaved_sessions.lock().remove(session_id);
         if let Some(ref storage) = self.storage {
             let session = {
                 let mut sessions = self.sessions.write();
@@ -13810,6 +20028,7 @@ phases and do EXACTLY this, then stop:
                 session.clone()
             };
 
+            Self::record_session_durations(&session);
             Self::persist_session_snapshot(storage, &session, session_id)?;
         }
 
@@ -13842,6 +20061,7 @@ phases and do EXACTLY this, then stop:
                 HierarchyNode {
                     id: agent.id.clone(),
                     role: role_str,
+                    label: agent.config.label.clone(),
                     parent_id: agent.parent_id.clone(),
                     children,
                 }
@@ -13854,6 +20074,7 @@ phases and do EXACTLY this, then stop:
             .filter(|a| include_in_worker_roster(&a.role))
             .map(|a| WorkerStateInfo {
                 id: a.id.clone(),
+                label: a.config.label.clone(),
                 role: a.config.role.clone().unwrap_or_default(),
                 cli: a.config.cli.clone(),
                 status: format!("{:?}", a.status),
@@ -13947,6 +20168,18 @@ fn parse_persisted_session_state(state: &str) -> SessionState {
                 .filter(|iteration| *iteration > 0),
         };
     }
+    if let Some(outstanding) = state.strip_prefix("Judging:") {
+        return SessionState::Judging {
+            outstanding: outstanding.parse::<u8>().unwrap_or(1),
+        };
+    }
+    if let Some(review) = state.strip_prefix("Reviewing:") {
+        let (step, role) = review.split_once(':').unwrap_or((review, ""));
+        return SessionState::Reviewing {
+            step: step.parse::<u8>().unwrap_or(1),
+            role: role.to_string(),
+        };
+    }
     if let Some(iteration) = state.strip_prefix("QaFailed:") {
         let iteration = iteration
             .parse::<u8>()
@@ -13957,6 +20190,7 @@ fn parse_persisted_session_state(state: &str) -> SessionState {
     }
 
     match state {
+        "Queued" => SessionState::Queued,
         "Planning" => SessionState::Planning,
         "PlanReady" => SessionState::PlanReady,
         "Starting" => SessionState::Starting,
@@ -13969,7 +20203,7 @@ fn parse_persisted_session_state(state: &str) -> SessionState {
         "SpawningDebateRound" => SessionState::SpawningDebateRound(0),
         "WaitingForDebateRound" => SessionState::WaitingForDebateRound(0),
         "SpawningJudge" => SessionState::SpawningJudge,
-        "Judging" => SessionState::Judging,
+        "Judging" => SessionState::Judging { outstanding: 1 },
         "AwaitingVerdictSelection" => SessionState::AwaitingVerdictSelection,
         "MergingWinner" => SessionState::MergingWinner,
         "SpawningEvaluator" => SessionState::SpawningEvaluator,
@@ -13990,6 +20224,7 @@ fn parse_persisted_session_state(state: &str) -> SessionState {
 
 fn serialize_session_state(state: &SessionState) -> String {
     match state {
+        SessionState::Queued => "Queued".to_string(),
         SessionState::Planning => "Planning".to_string(),
         SessionState::PlanReady => "PlanReady".to_string(),
         SessionState::Starting => "Starting".to_string(),
@@ -14002,7 +20237,7 @@ fn serialize_session_state(state: &SessionState) -> String {
         SessionState::SpawningDebateRound(_) => "SpawningDebateRound".to_string(),
         SessionState::WaitingForDebateRound(_) => "WaitingForDebateRound".to_string(),
         SessionState::SpawningJudge => "SpawningJudge".to_string(),
-        SessionState::Judging => "Judging".to_string(),
+        SessionState::Judging { outstanding } => format!("Judging:{}", outstanding),
         SessionState::AwaitingVerdictSelection => "AwaitingVerdictSelection".to_string(),
         SessionState::MergingWinner => "MergingWinner".to_string(),
         SessionState::SpawningEvaluator => "SpawningEvaluator".to_string(),
@@ -14015,6 +20250,7 @@ fn serialize_session_state(state: &SessionState) -> String {
         SessionState::QaMaxRetriesExceeded => "QaMaxRetriesExceeded".to_string(),
         SessionState::PrinceRemediation => "PrinceRemediation".to_string(),
         SessionState::QaInconclusive => "QaInconclusive".to_string(),
+        SessionState::Reviewing { step, role } => format!("Reviewing:{}:{}", step, role),
         SessionState::Running => "Running".to_string(),
         SessionState::Paused => "Paused".to_string(),
         SessionState::Completed => "Completed".to_string(),
@@ -14089,16 +20325,26 @@ mod tests {
     use super::{
         extract_model_arg, parse_persisted_session_state, serialize_session_state, AgentConfig,
         AgentInfo, AuthStrategy, CompletionError, DebateDebaterMetadata, DebateSessionMetadata,
-        FusionVariantMetadata, QaWorkerConfig, Session, SessionController, SessionError,
-        SessionState, SessionType,
+        FusionJudgeMetadata, FusionSessionMetadata, FusionSettlement, FusionSpawnPlan,
+        FusionVariantMetadata, FusionVariantSpawn, QaWorkerConfig, QueuedLaunchConfig, Session,
+        SessionController, SessionError, SessionState, SessionType,
+    };
+    use super::{
+        heartbeat_cadence_label, CliBehavior, CliRegistry, SpawnQuota, ACTIVATION_POLL_INTERVAL,
+        SESSION_TOKEN_HEADER,
     };
-    use super::{heartbeat_cadence_label, CliBehavior, CliRegistry, ACTIVATION_POLL_INTERVAL};
     use crate::coordination::queue_manager::{
         HEARTBEAT_MAX_INTERVAL_SECS, HEARTBEAT_MIN_INTERVAL_SECS,
     };
-    use crate::domain::{ArtifactBundle, HiveExecutionPolicy, WorkspaceStrategy};
-    use crate::pty::{AgentRole, AgentStatus, PtyManager, WorkerRole};
+    use crate::domain::{ArtifactBundle, HiveExecutionPolicy, HiveLaunchKind, WorkspaceStrategy};
+    use crate::pty::{AgentExitInfo, AgentRole, AgentStatus, PtyManager, WorkerRole};
+    use crate::storage::{
+        AutoCompleteConfig, ConcurrencyConfig, FusionWorktreeConfig, FusionWorktreeLocation,
+        SessionStorage,
+    };
+    use crate::templates::TemplateEngine;
     use crate::workspace::git::current_head;
+    use crate::workspace::paths;
     use chrono::{Duration, Utc};
     use parking_lot::RwLock;
     use std::path::{Path, PathBuf};
@@ -14135,7 +20381,7 @@ mod tests {
         let _spawning_fusion = SessionState::SpawningFusionVariant(1);
         let _waiting_fusion = SessionState::WaitingForFusionVariants;
         let _spawning_judge = SessionState::SpawningJudge;
-        let _judging = SessionState::Judging;
+        let _judging = SessionState::Judging { outstanding: 2 };
         let _awaiting_verdict = SessionState::AwaitingVerdictSelection;
         let _merging_winner = SessionState::MergingWinner;
         let _spawning_evaluator = SessionState::SpawningEvaluator;
@@ -14150,6 +20396,234 @@ mod tests {
         let _failed = SessionState::Failed("error".to_string());
     }
 
+    #[test]
+    fn fusion_worktree_root_follows_the_configured_location() {
+        let app_data = tempfile::tempdir().expect("app data dir");
+        let project = tempfile::tempdir().expect("project dir");
+        let project_path = project.path().join("repo");
+        let storage = Arc::new(
+            SessionStorage::new_with_base(app_data.path().to_path_buf()).expect("storage"),
+        );
+        let mut controller = test_controller();
+        assert_eq!(
+            controller.fusion_worktree_root(&project_path, "s1"),
+            project_path.join(".hive-fusion").join("s1")
+        );
+        controller.set_storage(Arc::clone(&storage));
+
+        let root = |location, dir: Option<&str>| {
+            let mut config = storage.load_config().expect("config");
+            config.fusion_worktrees = FusionWorktreeConfig {
+                location,
+                dir: dir.map(str::to_string),
+            };
+            storage.save_config(&config).expect("save config");
+            controller.fusion_worktree_root(&project_path, "s1")
+        };
+        assert_eq!(
+            root(FusionWorktreeLocation::AppData, None),
+            app_data.path().join("fusion-worktrees").join("s1")
+        );
+        assert_eq!(
+            root(FusionWorktreeLocation::Sibling, None),
+            project.path().join("repo.hive-fusion").join("s1")
+        );
+        assert_eq!(
+            root(FusionWorktreeLocation::Project, Some("/tmp/fusion")),
+            Path::new("/tmp/fusion").join("s1")
+        );
+
+        // Metadata written before the setting existed keeps the in-project path.
+        let legacy: FusionSessionMetadata = serde_json::from_value(serde_json::json!({
+            "base_branch": "main",
+            "variants": [],
+            "judge_config": AgentConfig::default(),
+            "task_description": "Test task",
+            "decision_file": "decision.md",
+        }))
+        .expect("legacy metadata");
+        assert_eq!(
+            legacy.worktree_root(&project_path, "s1"),
+            project_path.join(".hive-fusion").join("s1")
+        );
+    }
+
+    #[test]
+    fn settled_session_completes_after_the_idle_and_grace_periods() {
+        let app_data = tempfile::tempdir().expect("app data dir");
+        let project = tempfile::tempdir().expect("project dir");
+        let storage = Arc::new(
+            SessionStorage::new_with_base(app_data.path().to_path_buf()).expect("storage"),
+        );
+        let mut controller = test_controller();
+        controller.set_storage(Arc::clone(&storage));
+
+        let session_id = "session-settled";
+        let mut session = waiting_worker_session(session_id, project.path(), 1);
+        session.state = SessionState::Running;
+        session.no_git = true;
+        let task_path = SessionController::task_file_path_for_session_worker(&session, 1)
+            .expect("task file path");
+        std::fs::create_dir_all(task_path.parent().unwrap()).expect("tasks dir");
+        std::fs::write(&task_path, "# Task\n\n## Status: ACTIVE\n").expect("task file");
+        controller.insert_test_session(session);
+
+        let config = AutoCompleteConfig {
+            enabled: true,
+            idle_secs: 0,
+            grace_secs: 0,
+        };
+        let worker = "session-settled-worker-1";
+        controller
+            .update_heartbeat(session_id, worker, "idle", None)
+            .expect("idle heartbeat");
+        // The task is still ACTIVE, so an idle worker alone does not settle it.
+        assert!(controller.auto_complete_settled_sessions(&config).is_empty());
+        assert!(controller.auto_complete_settled_sessions(&config).is_empty());
+
+        std::fs::write(&task_path, "# Task\n\n## Status: COMPLETED\n").expect("task file");
+        assert!(controller
+            .auto_complete_settled_sessions(&AutoCompleteConfig {
+                enabled: false,
+                ..config
+            })
+            .is_empty());
+        // The first settled check announces, the next one completes.
+        assert!(controller.auto_complete_settled_sessions(&config).is_empty());
+        assert_eq!(
+            controller.auto_complete_settled_sessions(&config),
+            vec![session_id.to_string()]
+        );
+        assert!(matches!(
+            controller.get_session(session_id).expect("session").state,
+            SessionState::Completed
+        ));
+
+        let lines: Vec<String> = storage
+            .read_coordination_log(session_id, None)
+            .expect("coordination log")
+            .into_iter()
+            .map(|message| message.content)
+            .filter(|content| content.starts_with("[AUTO_COMPLETE]"))
+            .collect();
+        assert_eq!(lines.len(), 2);
+        assert!(lines[0].contains("the session completes at"));
+        assert_eq!(lines[1], "[AUTO_COMPLETE] Session completed: all agents settled");
+    }
+
+    #[test]
+    fn heartbeats_roll_up_into_the_coordination_log() {
+        let app_data = tempfile::tempdir().expect("app data dir");
+        let project = tempfile::tempdir().expect("project dir");
+        let storage = Arc::new(
+            SessionStorage::new_with_base(app_data.path().to_path_buf()).expect("storage"),
+        );
+        let mut controller = test_controller();
+        controller.set_storage(Arc::clone(&storage));
+
+        let session_id = "session-rollup";
+        let mut session = waiting_worker_session(session_id, project.path(), 1);
+        session.state = SessionState::Running;
+        controller.insert_test_session(session);
+
+        let worker = "session-rollup-worker-1";
+        controller
+            .update_heartbeat(session_id, worker, "working", Some("Parsing"))
+            .expect("working heartbeat");
+        controller
+            .update_heartbeat(session_id, worker, "working", None)
+            .expect("repeat heartbeat");
+        controller
+            .update_heartbeat(session_id, worker, "idle", None)
+            .expect("idle heartbeat");
+
+        controller
+            .agent_heartbeats
+            .write()
+            .get_mut(session_id)
+            .and_then(|agents| agents.get_mut(worker))
+            .expect("worker heartbeat")
+            .last_activity = Utc::now() - Duration::minutes(12);
+        assert_eq!(controller.roll_up_heartbeats(), 1);
+
+        let lines: Vec<String> = storage
+            .read_coordination_log(session_id, None)
+            .expect("coordination log")
+            .into_iter()
+            .map(|message| {
+                assert_eq!(message.from, "SYSTEM");
+                message.content
+            })
+            .collect();
+        assert_eq!(
+            lines,
+            vec![
+                "worker-1 working: Parsing",
+                "worker-1 idle (was working)",
+                "Heartbeats: worker-1 idle 12m",
+            ]
+        );
+    }
+
+    #[test]
+    fn launches_over_the_concurrency_limit_wait_in_the_queue() {
+        let project = tempfile::tempdir().expect("project dir");
+        let controller = test_controller();
+        let limits = ConcurrencyConfig {
+            max_concurrent_sessions: Some(1),
+            max_total_agents: None,
+        };
+        let mut running = waiting_worker_session("session-running", project.path(), 1);
+        running.state = SessionState::Running;
+        controller.insert_test_session(running);
+        assert!(!controller.can_launch_now(2, &limits));
+
+        let legacy_hive = |name: &str| QueuedLaunchConfig::LegacyHive {
+            project_path: project.path().to_path_buf(),
+            worker_count: 1,
+            command: "claude".to_string(),
+            prompt: None,
+            name: Some(name.to_string()),
+            color: None,
+        };
+        let first = controller.enqueue_launch(legacy_hive("first"));
+        let second = controller.enqueue_launch(legacy_hive("second"));
+        assert_eq!(first.state, SessionState::Queued);
+        let queued: Vec<String> = controller
+            .list_queued_sessions()
+            .into_iter()
+            .map(|session| session.id)
+            .collect();
+        assert_eq!(queued, vec![first.id.clone(), second.id.clone()]);
+        // Nothing jumps the queue, even when it would fit.
+        assert!(!controller.can_launch_now(2, &ConcurrencyConfig::default()));
+
+        controller
+            .close_session(&second.id)
+            .expect("cancel queued launch");
+        assert!(!controller.is_launch_queued(&second.id));
+        assert_eq!(
+            controller
+                .get_session(&second.id)
+                .map(|session| session.state),
+            Some(SessionState::Closed)
+        );
+
+        assert!(controller.next_startable_launch(&limits).is_none());
+        controller
+            .sessions
+            .write()
+            .get_mut("session-running")
+            .expect("running session")
+            .state = SessionState::Completed;
+        let launch = controller
+            .next_startable_launch(&limits)
+            .expect("queued launch fits once the running session completes");
+        assert_eq!(launch.session_id, first.id);
+        assert!(controller.get_session(&first.id).is_none());
+        assert!(controller.list_queued_sessions().is_empty());
+    }
+
     #[test]
     fn stall_sweep_excludes_completed_heartbeats() {
         let controller = test_controller();
@@ -14220,24 +20694,356 @@ mod tests {
         let fixer = WorkerRole::new("prince-fixer", "Prince Fixer", "codex");
         let prince_id = "solo-fixer-prince";
 
-        session.state = SessionState::Running;
-        assert!(!SessionController::session_allows_dynamic_principal(
-            &session,
-            &fixer,
-            Some(prince_id),
+        session.state = SessionState::Running;
+        assert!(!SessionController::session_allows_dynamic_principal(
+            &session,
+            &fixer,
+            Some(prince_id),
+        ));
+
+        session.state = SessionState::PrinceRemediation;
+        assert!(SessionController::session_allows_dynamic_principal(
+            &session,
+            &fixer,
+            Some(prince_id),
+        ));
+        assert!(!SessionController::session_allows_dynamic_principal(
+            &session,
+            &fixer,
+            Some("another-session-prince"),
+        ));
+    }
+
+    #[tokio::test]
+    async fn agent_exit_settles_live_agents_as_completed_or_crashed() {
+        let controller = RwLock::new(test_controller());
+        let mut session = waiting_worker_session("exit-report", Path::new("/repo"), 1);
+        let mut finished = session.agents[0].clone();
+        finished.id = "exit-report-worker-2".to_string();
+        finished.status = AgentStatus::Completed;
+        session.agents.push(finished);
+        controller.read().insert_test_session(session);
+
+        let crash = AgentExitInfo::new(
+            Some(101),
+            None,
+            false,
+            vec!["thread 'main' panicked".to_string()],
+        );
+        SessionController::on_agent_exited(&controller, "exit-report-worker-1", crash.clone())
+            .await
+            .unwrap();
+        SessionController::on_agent_exited(&controller, "exit-report-worker-2", crash.clone())
+            .await
+            .unwrap();
+
+        let session = controller.read().get_session("exit-report").unwrap();
+        assert_eq!(
+            session.agents[0].status,
+            AgentStatus::Crashed("Exited with code 101".to_string())
+        );
+        assert_eq!(session.agents[0].exit_info.as_ref(), Some(&crash));
+        // The task already finished; a later non-zero exit doesn't turn it into a crash.
+        assert_eq!(session.agents[1].status, AgentStatus::Completed);
+        assert_eq!(session.agents[1].exit_info.as_ref(), Some(&crash));
+    }
+
+    #[test]
+    fn spawn_quota_reservations_hold_room_until_dropped_and_persist_with_the_session() {
+        let controller = test_controller();
+        let mut session = waiting_worker_session("quota-reserve", Path::new("/repo"), 1);
+        session.spawn_quota = SpawnQuota {
+            max_workers: Some(2),
+            per_role: Default::default(),
+        };
+        controller.insert_test_session(session);
+
+        // One worker is live, so only one more fits, even before it is spawned.
+        let reservation = controller
+            .reserve_spawn_quota("quota-reserve", "backend")
+            .unwrap();
+        let exceeded = controller
+            .reserve_spawn_quota("quota-reserve", "backend")
+            .unwrap_err();
+        assert_eq!((exceeded.limit, exceeded.live), (2, 2));
+        drop(reservation);
+        assert!(controller
+            .reserve_spawn_quota("quota-reserve", "backend")
+            .is_ok());
+
+        let session = controller.get_session("quota-reserve").unwrap();
+        let persisted = SessionController::session_to_persisted_snapshot(&session);
+        assert_eq!(persisted.spawn_quota.max_workers, Some(2));
+        let restored = controller.session_from_persisted(&persisted).unwrap();
+        assert_eq!(restored.spawn_quota, session.spawn_quota);
+    }
+
+    #[test]
+    fn solo_task_queue_holds_prompts_until_the_agent_is_idle() {
+        let temp = tempfile::tempdir().expect("temp project");
+        let controller = test_controller();
+        let mut solo = waiting_worker_session("solo-queue", temp.path(), 1);
+        solo.session_type = SessionType::Solo {
+            cli: "claude".to_string(),
+            model: None,
+        };
+        solo.state = SessionState::Running;
+        controller.insert_test_session(solo);
+        controller.insert_test_session(waiting_worker_session("hive-queue", temp.path(), 1));
+
+        assert_eq!(controller.queue_solo_task("solo-queue", "Add tests"), Ok(1));
+        assert_eq!(
+            controller.queue_solo_task("solo-queue", "  Update docs "),
+            Ok(2)
+        );
+        assert!(controller.queue_solo_task("solo-queue", "   ").is_err());
+        assert!(controller
+            .queue_solo_task("hive-queue", "Add tests")
+            .is_err());
+        assert!(controller.queue_solo_task("missing", "Add tests").is_err());
+
+        // An idle heartbeat without a live PTY leaves the queue untouched.
+        controller
+            .update_heartbeat("solo-queue", "solo-queue-worker-1", "idle", None)
+            .unwrap();
+        assert_eq!(
+            controller.solo_task_queue("solo-queue"),
+            vec!["Add tests".to_string(), "Update docs".to_string()]
+        );
+
+        controller.close_session("solo-queue").unwrap();
+        assert!(controller.solo_task_queue("solo-queue").is_empty());
+    }
+
+    #[test]
+    fn promote_session_turns_a_solo_session_into_a_hive_in_place() {
+        let temp = tempfile::tempdir().expect("temp project");
+        let controller = test_controller();
+        let worktree = temp
+            .path()
+            .join(".hive-manager")
+            .join("worktrees")
+            .join("solo-promote")
+            .join("worker-1");
+        std::fs::create_dir_all(&worktree).unwrap();
+        let mut solo = waiting_worker_session("solo-promote", temp.path(), 1);
+        solo.session_type = SessionType::Solo {
+            cli: "claude".to_string(),
+            model: None,
+        };
+        solo.state = SessionState::Running;
+        solo.execution_policy.launch_kind = HiveLaunchKind::Solo;
+        solo.execution_policy.workspace_strategy = WorkspaceStrategy::IsolatedCell;
+        solo.worktree_path = Some(worktree.to_string_lossy().to_string());
+        solo.agents[0].config.initial_prompt = Some("Fix the login flow".to_string());
+        controller.insert_test_session(solo);
+        controller
+            .queue_solo_task("solo-promote", "Add tests")
+            .unwrap();
+
+        let promoted = controller
+            .promote_session("solo-promote", Vec::new())
+            .unwrap();
+        assert_eq!(promoted.id, "solo-promote");
+        assert!(matches!(
+            promoted.session_type,
+            SessionType::Hive { worker_count: 1 }
+        ));
+        assert_eq!(promoted.execution_policy.launch_kind, HiveLaunchKind::Hive);
+        assert_eq!(promoted.agents.len(), 1);
+        assert!(promoted.agents[0].config.role.is_some());
+        assert!(controller.solo_task_queue("solo-promote").is_empty());
+
+        let prompt = worktree
+            .join(".hive-manager")
+            .join("prompts")
+            .join("worker-1-prompt.md");
+        assert!(prompt.exists(), "worker prompt should be written");
+        let task_file = SessionController::task_file_path_for_session_worker(&promoted, 1).unwrap();
+        assert!(std::fs::read_to_string(task_file)
+            .unwrap()
+            .contains("Fix the login flow"));
+
+        assert!(controller
+            .promote_session("solo-promote", Vec::new())
+            .unwrap_err()
+            .contains("not a Solo session"));
+        assert!(controller.promote_session("missing", Vec::new()).is_err());
+    }
+
+    #[test]
+    fn queen_task_messages_activate_the_worker_task_file() {
+        let temp = tempfile::tempdir().expect("temp project");
+        let controller = test_controller();
+        let mut session = waiting_worker_session("activate", temp.path(), 1);
+        session.execution_policy.workspace_strategy = WorkspaceStrategy::IsolatedCell;
+        let path = SessionController::task_file_path_for_session_worker(&session, 1).unwrap();
+        SessionController::write_task_file_at_path(&path, 1, None, Some("STANDBY"), false)
+            .unwrap();
+        controller.insert_test_session(session);
+
+        let activated = controller
+            .activate_worker_task("activate", "activate-worker-1", "Add the /health endpoint")
+            .unwrap();
+        assert_eq!(activated, Some(path.clone()));
+        let content = std::fs::read_to_string(&path).unwrap();
+        assert_eq!(
+            SessionController::parse_task_status(&content).as_deref(),
+            Some("ACTIVE")
+        );
+        assert!(content.contains("## Queen Assignment ("));
+        assert!(content.trim_end().ends_with("Add the /health endpoint"));
+
+        // Only numbered workers of the session have task files to activate.
+        assert_eq!(
+            controller
+                .activate_worker_task("activate", "activate-evaluator", "Review")
+                .unwrap(),
+            None
+        );
+        assert!(controller
+            .activate_worker_task("missing", "missing-worker-1", "Review")
+            .is_err());
+    }
+
+    #[test]
+    fn activated_task_content_adds_a_status_when_there_is_none() {
+        let at = Utc::now();
+        let content = SessionController::activated_task_content("", "Do it", at);
+        assert!(content.starts_with("## Status: ACTIVE\n"));
+        let content =
+            SessionController::activated_task_content("**Status**: COMPLETED\nold\n", "Again", at);
+        assert!(content.starts_with("**Status**: ACTIVE\nold\n\n## Queen Assignment"));
+    }
+
+    #[test]
+    fn worker_task_status_reports_rewrite_the_task_file_in_place() {
+        let temp = tempfile::tempdir().expect("temp project");
+        let controller = test_controller();
+        let mut session = waiting_worker_session("report", temp.path(), 1);
+        session.execution_policy.workspace_strategy = WorkspaceStrategy::IsolatedCell;
+        let path = SessionController::task_file_path_for_session_worker(&session, 1).unwrap();
+        SessionController::write_task_file_at_path(&path, 1, None, Some("ACTIVE"), false)
+            .unwrap();
+        controller.insert_test_session(session);
+
+        let reported = controller
+            .report_worker_task_status("report", 1, "COMPLETED", "Added the endpoint")
+            .unwrap();
+        assert_eq!(reported, Some(path.clone()));
+        let content = std::fs::read_to_string(&path).unwrap();
+        assert_eq!(
+            SessionController::parse_task_status(&content).as_deref(),
+            Some("COMPLETED")
+        );
+        assert_eq!(
+            crate::workspace::pull_request::task_result_summary(&content).as_deref(),
+            Some("Added the endpoint")
+        );
+        assert!(!path.with_extension("md.tmp").exists());
+
+        let blocked = SessionController::reported_task_content(
+            "## Status: ACTIVE\n",
+            "BLOCKED",
+            "Needs a DB URL",
+            Utc::now(),
+        );
+        assert!(blocked.starts_with("## Status: BLOCKED\n\n## Blocker ("));
+        assert_eq!(
+            controller
+                .report_worker_task_status("report", 2, "COMPLETED", "Done")
+                .unwrap(),
+            None
+        );
+    }
+
+    #[test]
+    fn agent_command_preview_shows_what_a_launch_would_run() {
+        let controller = test_controller();
+        let config = AgentConfig {
+            cli: "qwen".to_string(),
+            model: Some("qwen3-coder".to_string()),
+            flags: vec!["--debug".to_string()],
+            ..AgentConfig::default()
+        };
+        let prompt_path = "/repo/.hive-manager/s1/prompts/worker-1-prompt.md";
+
+        let preview = controller
+            .preview_agent_command(&config, Some("/repo"), Some(prompt_path))
+            .unwrap();
+        assert_eq!(preview.command, "qwen");
+        assert_eq!(
+            preview.args,
+            [
+                "-y",
+                "-m",
+                "qwen3-coder",
+                "--debug",
+                "-i",
+                "Read /repo/.hive-manager/s1/prompts/worker-1-prompt.md and execute.",
+            ]
+        );
+        assert_eq!(preview.cwd.as_deref(), Some("/repo"));
+        assert!(preview.env_keys.is_empty());
+        assert!(preview.command_line.starts_with("qwen -y -m qwen3-coder --debug -i "));
+
+        let preview = controller.preview_agent_command(&config, None, None).unwrap();
+        assert_eq!(preview.args.last().map(String::as_str), Some("--debug"));
+    }
+
+    #[test]
+    fn scale_workers_retires_only_idle_surplus_workers() {
+        let temp = tempfile::tempdir().expect("temp project");
+        let controller = test_controller();
+        let mut session = waiting_worker_session("scale-down", temp.path(), 1);
+        session.state = SessionState::Running;
+        session.session_type = SessionType::Hive { worker_count: 3 };
+        session.execution_policy.workspace_strategy = WorkspaceStrategy::IsolatedCell;
+        for index in 2..=3u8 {
+            let mut worker = session.agents[0].clone();
+            worker.id = format!("scale-down-worker-{index}");
+            worker.role = AgentRole::Worker {
+                index,
+                parent: Some("scale-down-queen".to_string()),
+            };
+            session.agents.push(worker);
+        }
+        for (index, status) in [(1, "ACTIVE"), (2, "STANDBY"), (3, "COMPLETED")] {
+            let path =
+                SessionController::task_file_path_for_session_worker(&session, index).unwrap();
+            SessionController::write_task_file_at_path(
+                &path,
+                index as u8,
+                None,
+                Some(status),
+                false,
+            )
+            .unwrap();
+        }
+        controller.insert_test_session(session);
+
+        let report = controller.scale_workers("scale-down", 1).unwrap();
+        assert_eq!(
+            report.retired,
+            vec!["scale-down-worker-3".to_string(), "scale-down-worker-2".to_string()]
+        );
+        assert!(report.added.is_empty());
+        assert_eq!(report.worker_count, 1);
+        assert_eq!(report.pending_retirement, 0);
+        let session = controller.get_session("scale-down").unwrap();
+        assert!(matches!(
+            session.session_type,
+            SessionType::Hive { worker_count: 1 }
         ));
+        assert_eq!(session.agents[2].status, AgentStatus::Completed);
 
-        session.state = SessionState::PrinceRemediation;
-        assert!(SessionController::session_allows_dynamic_principal(
-            &session,
-            &fixer,
-            Some(prince_id),
-        ));
-        assert!(!SessionController::session_allows_dynamic_principal(
-            &session,
-            &fixer,
-            Some("another-session-prince"),
-        ));
+        // Worker 1 is mid-task, so it is left running.
+        let report = controller.scale_workers("scale-down", 0).unwrap();
+        assert!(report.retired.is_empty());
+        assert_eq!(report.pending_retirement, 1);
+        assert_eq!(report.worker_count, 1);
+
+        assert!(controller.scale_workers("missing", 2).is_err());
     }
 
     #[test]
@@ -14429,9 +21235,25 @@ mod tests {
         assert_eq!(serialize_session_state(&SessionState::QaPassed), "QaPassed");
     }
 
+    #[test]
+    fn persisted_review_state_keeps_step_and_role() {
+        let state = SessionState::Reviewing {
+            step: 2,
+            role: "resolver".to_string(),
+        };
+        assert_eq!(serialize_session_state(&state), "Reviewing:2:resolver");
+        assert_eq!(parse_persisted_session_state("Reviewing:2:resolver"), state);
+        assert!(state.is_monitorable());
+
+        // Sessions launched without a review stage never enter one.
+        let controller = SessionController::new(Arc::new(RwLock::new(PtyManager::new())));
+        assert_eq!(controller.advance_review_stage("no-review"), Ok(false));
+    }
+
     #[test]
     fn qa_worker_prompt_uses_requested_specialization() {
         let prompt = SessionController::build_qa_worker_prompt(
+            &TemplateEngine::default(),
             "session-123",
             1,
             "a11y",
@@ -14457,6 +21279,7 @@ mod tests {
     fn every_qa_worker_prompt_has_a_ready_completed_heartbeat() {
         for specialization in ["ui", "api", "a11y", "adversarial"] {
             let prompt = SessionController::build_qa_worker_prompt(
+                &TemplateEngine::default(),
                 "session-qa",
                 3,
                 specialization,
@@ -14613,8 +21436,9 @@ mod tests {
             &restored.project_path,
             &restored.project_path,
             &restored.execution_policy,
+            None,
         );
-        assert!(prompt.contains(&SessionController::prompt_path(&task_path)));
+        assert!(prompt.contains(&paths::prompt_path(&task_path)));
     }
 
     /// Every `CliBehavior` must name a CLI here. Adding a variant breaks this match at
@@ -14666,6 +21490,7 @@ mod tests {
             temp.path(),
             temp.path(),
             &HiveExecutionPolicy::default(),
+            None,
         )
     }
 
@@ -14866,20 +21691,55 @@ mod tests {
     }
 
     #[test]
-    fn to_wsl_path_converts_windows_drive_paths() {
-        assert_eq!(
-            SessionController::to_wsl_path(r"D:\foo\bar"),
-            "/mnt/d/foo/bar"
-        );
+    fn oversized_inline_prompts_spill_to_a_prompt_file() {
+        let temp = tempfile::tempdir().expect("temp project");
+        let project_path = temp.path().to_path_buf();
+        let controller = test_controller();
+
+        let small = "Fix the login bug.";
         assert_eq!(
-            SessionController::to_wsl_path("D:/foo/bar"),
-            "/mnt/d/foo/bar"
+            controller
+                .spill_oversized_prompt(
+                    &project_path,
+                    "spill",
+                    "spill-worker-1",
+                    "cursor",
+                    "solo-task.md",
+                    small
+                )
+                .unwrap(),
+            None
         );
+
+        let large = "x".repeat(CliRegistry::max_inline_prompt_bytes("cursor") + 1);
+        let prompt_file = controller
+            .spill_oversized_prompt(
+                &project_path,
+                "spill",
+                "spill-worker-1",
+                "cursor",
+                "solo-task.md",
+                &large,
+            )
+            .unwrap()
+            .expect("oversized prompt should spill to a file");
+        assert_eq!(std::fs::read_to_string(&prompt_file).unwrap(), large);
+        assert!(prompt_file.ends_with(".hive-manager/spill/prompts/solo-task.md"));
+
+        // The same prompt still fits inline for a CLI with a larger budget.
         assert_eq!(
-            SessionController::to_wsl_path(r"C:\Users\x"),
-            "/mnt/c/Users/x"
+            controller
+                .spill_oversized_prompt(
+                    &project_path,
+                    "spill",
+                    "spill-worker-1",
+                    "claude",
+                    "solo-task.md",
+                    &large
+                )
+                .unwrap(),
+            None
         );
-        assert_eq!(SessionController::to_wsl_path("/tmp/x"), "/tmp/x");
     }
 
     #[test]
@@ -14967,6 +21827,41 @@ mod tests {
         }
     }
 
+    #[test]
+    fn worker_prompt_leads_with_the_custom_label() {
+        let temp = tempfile::tempdir().expect("temp project");
+        let prompt = SessionController::build_worker_prompt(
+            3,
+            &AgentConfig {
+                label: Some("Payments Backend".to_string()),
+                ..codex_principal()
+            },
+            "session-label-queen",
+            "session-label",
+            temp.path(),
+            temp.path(),
+            &HiveExecutionPolicy::default(),
+            None,
+        );
+        assert!(
+            prompt.starts_with("# Managed Principal 3 - Payments Backend (Backend Principal)")
+        );
+        assert!(prompt.contains("- Display name: Payments Backend"));
+
+        let unlabelled = SessionController::build_worker_prompt(
+            3,
+            &codex_principal(),
+            "session-label-queen",
+            "session-label",
+            temp.path(),
+            temp.path(),
+            &HiveExecutionPolicy::default(),
+            None,
+        );
+        assert!(unlabelled.starts_with("# Managed Principal 3 - Backend Principal\n"));
+        assert!(unlabelled.contains("- Display name: worker-3"));
+    }
+
     #[test]
     fn live_master_planner_uses_capability_policy_and_coherent_workstreams() {
         let policy = shared_meta_harness_policy();
@@ -15015,6 +21910,7 @@ mod tests {
             Path::new("/repo"),
             Path::new("/repo/.hive-manager/worktrees/session-modern/primary"),
             "session-modern",
+            "hive/session-modern/primary",
             &[codex_principal()],
             Some("Implement the operator objective"),
             true,
@@ -15054,6 +21950,7 @@ mod tests {
             Path::new("/repo"),
             Path::new("/repo/.hive-manager/worktrees/session-modern/primary"),
             &shared_policy,
+            None,
         );
 
         assert!(shared_prompt.contains("Harness: `codex`"));
@@ -15087,6 +21984,7 @@ mod tests {
             Path::new("/repo"),
             Path::new("/repo/.hive-manager/worktrees/session-modern/worker-1"),
             &isolated_policy,
+            None,
         );
         assert!(isolated_prompt.contains("Commit the completed assignment"));
         assert!(isolated_prompt
@@ -15105,6 +22003,7 @@ mod tests {
             Path::new("/repo"),
             Path::new("/repo"),
             &no_workspace_policy,
+            None,
         );
         assert!(no_workspace_prompt
             .contains("/repo/.hive-manager/session-modern/tasks/worker-1-task.md"));
@@ -15116,6 +22015,7 @@ mod tests {
     #[test]
     fn evaluator_prompt_uses_session_default_cli_and_model() {
         let prompt = SessionController::build_evaluator_prompt(
+            &TemplateEngine::default(),
             "session-123",
             &AgentConfig {
                 cli: "codex".to_string(),
@@ -15146,6 +22046,7 @@ mod tests {
     #[test]
     fn evaluator_prompt_uses_configured_qa_workers() {
         let prompt = SessionController::build_evaluator_prompt(
+            &TemplateEngine::default(),
             "session-123",
             &AgentConfig {
                 cli: "claude".to_string(),
@@ -15182,6 +22083,64 @@ mod tests {
         assert!(!prompt.contains(r#""specialization": "api", "cli": "claude""#));
     }
 
+    #[test]
+    fn worker_prompts_embed_the_project_dna_unless_disabled() {
+        let app_data = tempfile::tempdir().expect("app data dir");
+        let project = tempfile::tempdir().expect("project dir");
+        let storage = Arc::new(
+            SessionStorage::new_with_base(app_data.path().to_path_buf()).expect("storage"),
+        );
+        let mut controller = test_controller();
+        controller.set_storage(Arc::clone(&storage));
+        let session_id = "session-dna-prompt";
+
+        assert_eq!(
+            controller.worker_project_dna(session_id, project.path()),
+            None
+        );
+        std::fs::create_dir_all(project.path().join(".ai-docs")).unwrap();
+        std::fs::write(
+            project.path().join(".ai-docs").join("project-dna.md"),
+            "# DNA\n- Prefer Result over panics\n",
+        )
+        .unwrap();
+        assert_eq!(
+            controller
+                .worker_project_dna(session_id, project.path())
+                .as_deref(),
+            Some("# DNA\n- Prefer Result over panics")
+        );
+        storage
+            .save_project_dna_session(session_id, "# Session DNA\n- Curated by the Queen\n")
+            .unwrap();
+        let dna = controller.worker_project_dna(session_id, project.path());
+        assert_eq!(
+            dna.as_deref(),
+            Some("# Session DNA\n- Curated by the Queen")
+        );
+
+        let prompt = SessionController::build_worker_prompt(
+            1,
+            &AgentConfig::default(),
+            "queen",
+            session_id,
+            project.path(),
+            project.path(),
+            &HiveExecutionPolicy::default(),
+            dna.as_deref(),
+        );
+        assert!(prompt.contains("- Curated by the Queen"));
+        assert!(!prompt.contains("Read .ai-docs/project-dna.md before implementation"));
+
+        let mut config = storage.load_config().unwrap();
+        config.project_dna.embed_in_worker_prompts = false;
+        storage.save_config(&config).unwrap();
+        assert_eq!(
+            controller.worker_project_dna(session_id, project.path()),
+            None
+        );
+    }
+
     #[test]
     fn research_worker_surfaces_are_read_only() {
         let session_id = "session-research-readonly";
@@ -15207,12 +22166,15 @@ mod tests {
             temp.path(),
             &worktree_path,
             &research_policy,
+            None,
         );
         assert!(prompt.contains("RESEARCHER"));
         assert!(prompt.contains("Read-Only"));
-        let expected_task_path = SessionController::prompt_path(
-            &SessionController::session_task_file_path(temp.path(), session_id, 1),
-        );
+        let expected_task_path = paths::prompt_path(&SessionController::session_task_file_path(
+            temp.path(),
+            session_id,
+            1,
+        ));
         assert!(prompt.contains(&expected_task_path));
         assert!(!prompt.contains("## Your Role: EXECUTOR"));
         assert!(!prompt.contains("Learnings Protocol (MANDATORY)"));
@@ -15265,6 +22227,7 @@ mod tests {
             Path::new("."),
             &worktree_path,
             &HiveExecutionPolicy::default(),
+            None,
         );
         let task_file_path = SessionController::write_task_file_with_status(
             &worktree_path,
@@ -15305,6 +22268,7 @@ mod tests {
             Path::new("/repo"),
             Path::new("/repo/.hive-manager/worktrees/session-123/queen"),
             "session-123",
+            "hive/session-123/queen",
             &[],
             None,
             false,
@@ -15355,6 +22319,8 @@ mod tests {
             worktree_path: "/repo/.hive-manager/worktrees/session-123/fusion-1".to_string(),
             task_file: "/repo/.hive-manager/session-123/tasks/fusion-1.md".to_string(),
             agent_id: "session-123-fusion-1".to_string(),
+            attempts: 0,
+            failure: None,
         }];
         let prompt = SessionController::build_fusion_queen_prompt(
             "claude",
@@ -15369,9 +22335,432 @@ mod tests {
         assert!(prompt.contains("| 1 | Safe Variant | `session-123-fusion-1` |"));
     }
 
+    fn fusion_test_variant(index: u8, name: &str, slug: &str) -> FusionVariantMetadata {
+        FusionVariantMetadata {
+            index,
+            name: name.to_string(),
+            slug: slug.to_string(),
+            branch: format!("fusion/session-judges/{slug}"),
+            worktree_path: format!("/repo/.hive-fusion/session-judges/variant-{slug}"),
+            task_file: format!("/repo/.hive-fusion/session-judges/variant-{slug}/task.md"),
+            agent_id: format!("session-judges-fusion-{index}"),
+            attempts: 0,
+            failure: None,
+        }
+    }
+
+    #[test]
+    fn fusion_judge_prompt_points_at_context_packs() {
+        let variants = vec![
+            fusion_test_variant(1, "Safe Variant", "safe-variant"),
+            fusion_test_variant(2, "fast", "fast"),
+        ];
+        let context_dir = Path::new("/repo/.hive-manager/session-judges/evaluation/context");
+        let contexts = vec![
+            Some(crate::session::judge_context::VariantContext {
+                diff_file: context_dir.join("safe-variant.diff"),
+                summary_file: context_dir.join("safe-variant-summary.md"),
+            }),
+            None,
+        ];
+
+        let prompt = SessionController::build_fusion_judge_prompt(
+            "session-judges",
+            &variants,
+            &contexts,
+            "/repo/.hive-manager/session-judges/evaluation/decision.md",
+        );
+        assert!(prompt.contains(
+            "- Safe Variant: read the diff \
+             `/repo/.hive-manager/session-judges/evaluation/context/safe-variant.diff`"
+        ));
+        assert!(prompt.contains("evaluation/context/safe-variant-summary.md`"));
+        assert!(!prompt.contains("base..fusion/session-judges/safe-variant"));
+        assert!(prompt.contains(
+            "- fast: run `git diff fusion/session-judges/base..fusion/session-judges/fast`"
+        ));
+    }
+
+    #[test]
+    fn fusion_report_winner_resolves_markdown_and_slug_forms() {
+        let variants = vec![
+            fusion_test_variant(1, "Safe Variant", "safe-variant"),
+            fusion_test_variant(2, "fast", "fast"),
+        ];
+
+        assert_eq!(
+            SessionController::parse_fusion_report_winner(
+                "## Recommendation\n**Winner**: safe variant\nRationale: tests",
+                &variants
+            )
+            .as_deref(),
+            Some("Safe Variant")
+        );
+        assert_eq!(
+            SessionController::parse_fusion_report_winner("Winner: [FAST]", &variants).as_deref(),
+            Some("fast")
+        );
+        assert_eq!(
+            SessionController::parse_fusion_report_winner("Winner: unknown", &variants),
+            None
+        );
+    }
+
+    #[test]
+    fn fusion_report_scores_keep_known_variants_in_range() {
+        let variants = vec![
+            fusion_test_variant(1, "Safe Variant", "safe-variant"),
+            fusion_test_variant(2, "fast", "fast"),
+        ];
+        let report = "## Scores\n\
+                      Score: safe variant: 8.5\n\
+                      - **Score**: [fast]: 6/10\n\
+                      Score: unknown: 9\n\
+                      Score: fast: 14\n";
+
+        let scores = SessionController::parse_fusion_report_scores(report, &variants);
+        assert_eq!(scores.len(), 2);
+        assert_eq!(scores.get("Safe Variant"), Some(&8.5));
+        assert_eq!(scores.get("fast"), Some(&6.0));
+    }
+
+    #[test]
+    fn fusion_verdict_tally_counts_majority_and_outstanding_judges() {
+        let temp_dir = tempfile::tempdir().expect("temp dir");
+        let report = |name: &str| temp_dir.path().join(name).to_string_lossy().to_string();
+        let extra_judge = |number: u8| FusionJudgeMetadata {
+            agent_id: format!("session-judges-judge-{number}"),
+            config: AgentConfig::default(),
+            decision_file: report(&format!("decision-{number}.md")),
+        };
+        let metadata = FusionSessionMetadata {
+            base_branch: "fusion/session-judges/base".to_string(),
+            variants: vec![
+                fusion_test_variant(1, "alpha", "alpha"),
+                fusion_test_variant(2, "beta", "beta"),
+            ],
+            judge_config: AgentConfig::default(),
+            task_description: "Test task".to_string(),
+            decision_file: report("decision.md"),
+            extra_judges: vec![extra_judge(2), extra_judge(3), extra_judge(4)],
+            quorum: None,
+            max_variant_retries: 0,
+            worktree_root: None,
+            base_commit: None,
+        };
+        std::fs::write(report("decision.md"), "Winner: alpha\n").unwrap();
+        std::fs::write(report("decision-2.md"), "Winner: beta\n").unwrap();
+        std::fs::write(report("decision-3.md"), "Winner: alpha\n").unwrap();
+
+        let tally = SessionController::tally_fusion_verdicts("session-judges", &metadata);
+
+        assert_eq!(tally.outstanding, 1);
+        assert_eq!(tally.votes.get("alpha"), Some(&2));
+        assert_eq!(tally.votes.get("beta"), Some(&1));
+        assert_eq!(tally.majority_winner.as_deref(), Some("alpha"));
+        assert_eq!(tally.verdicts[0].judge_id, "session-judges-judge");
+        assert!(!tally.verdicts[3].reported);
+
+        std::fs::write(report("decision-4.md"), "Winner: beta\n").unwrap();
+        let tied = SessionController::tally_fusion_verdicts("session-judges", &metadata);
+        assert_eq!(tied.outstanding, 0);
+        assert_eq!(tied.majority_winner, None);
+    }
+
+    #[test]
+    fn session_branch_prefers_valid_operator_branch_name() {
+        assert_eq!(
+            SessionController::resolve_session_branch(
+                Some(" feat/login-flow "),
+                "hive/session-1/queen".to_string()
+            )
+            .as_deref(),
+            Ok("feat/login-flow")
+        );
+        assert_eq!(
+            SessionController::resolve_session_branch(Some(""), "hive/session-1/queen".to_string())
+                .as_deref(),
+            Ok("hive/session-1/queen")
+        );
+        assert!(SessionController::resolve_session_branch(
+            Some("feat/login flow"),
+            "hive/session-1/queen".to_string()
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn fusion_settlement_waits_for_variants_and_applies_quorum() {
+        let temp_dir = tempfile::tempdir().expect("temp dir");
+        let mut variants = vec![
+            fusion_test_variant(1, "alpha", "alpha"),
+            fusion_test_variant(2, "beta", "beta"),
+            fusion_test_variant(3, "gamma", "gamma"),
+        ];
+        for variant in &mut variants {
+            variant.task_file = temp_dir
+                .path()
+                .join(format!("{}-task.md", variant.slug))
+                .to_string_lossy()
+                .to_string();
+            std::fs::write(&variant.task_file, "## Status: ACTIVE\n").expect("task file");
+        }
+        std::fs::write(&variants[0].task_file, "## Status: COMPLETED\n").expect("task file");
+        std::fs::write(&variants[1].task_file, "## Status: COMPLETED\n").expect("task file");
+        let mut metadata = FusionSessionMetadata {
+            base_branch: "fusion/session-judges/base".to_string(),
+            variants,
+            judge_config: AgentConfig::default(),
+            task_description: "Test task".to_string(),
+            decision_file: "decision.md".to_string(),
+            extra_judges: Vec::new(),
+            quorum: Some(2),
+            max_variant_retries: 0,
+            worktree_root: None,
+            base_commit: None,
+        };
+
+        assert_eq!(
+            SessionController::fusion_settlement(&metadata),
+            FusionSettlement::Pending
+        );
+
+        metadata.variants[2].failure = Some("Task reported BLOCKED".to_string());
+        assert_eq!(
+            SessionController::fusion_settlement(&metadata),
+            FusionSettlement::Judge
+        );
+
+        metadata.quorum = None;
+        assert_eq!(
+            SessionController::fusion_settlement(&metadata),
+            FusionSettlement::QuorumMissed(
+                "Fusion quorum not met: 2 of 3 variants completed, 3 required".to_string()
+            )
+        );
+    }
+
+    #[test]
+    fn spawn_fusion_variants_records_variants_that_fail_to_start() {
+        // Not a git repository, so every `git worktree add` fails.
+        let temp_dir = tempfile::tempdir().expect("temp dir");
+        let project_path = temp_dir.path().to_path_buf();
+        let session_id = "session-spawn";
+        let controller = test_controller();
+
+        let mut session = waiting_worker_session(session_id, &project_path, 1);
+        session.session_type = SessionType::Fusion {
+            variants: vec!["alpha".to_string(), "beta".to_string()],
+        };
+        session.state = SessionState::Starting;
+        session.agents.clear();
+        controller.insert_test_session(session);
+
+        let variants: Vec<FusionVariantMetadata> = [(1, "alpha"), (2, "beta")]
+            .into_iter()
+            .map(|(index, name)| {
+                let mut variant = fusion_test_variant(index, name, name);
+                variant.worktree_path = project_path
+                    .join(".hive-fusion")
+                    .join(session_id)
+                    .join(format!("variant-{name}"))
+                    .to_string_lossy()
+                    .to_string();
+                variant
+            })
+            .collect();
+        let metadata = FusionSessionMetadata {
+            base_branch: format!("fusion/{session_id}/base"),
+            variants: variants.clone(),
+            judge_config: AgentConfig::default(),
+            task_description: "Test task".to_string(),
+            decision_file: "decision.md".to_string(),
+            extra_judges: Vec::new(),
+            quorum: None,
+            max_variant_retries: 0,
+            worktree_root: None,
+            base_commit: None,
+        };
+        SessionController::write_fusion_metadata(&project_path, session_id, &metadata)
+            .expect("write metadata");
+
+        let plan = FusionSpawnPlan {
+            session_id: session_id.to_string(),
+            project_path: project_path.clone(),
+            base_branch: metadata.base_branch.clone(),
+            task_description: metadata.task_description.clone(),
+            variants: variants
+                .into_iter()
+                .map(|variant| FusionVariantSpawn {
+                    variant,
+                    agent_config: AgentConfig::default(),
+                })
+                .collect(),
+            worktrees: None,
+        };
+
+        let session = controller
+            .spawn_fusion_variants(&plan)
+            .expect("spawn settles even when variants fail");
+
+        assert!(matches!(session.state, SessionState::Failed(_)));
+        assert_eq!(session.agents.len(), 2);
+        assert!(session
+            .agents
+            .iter()
+            .all(|agent| matches!(agent.status, AgentStatus::Error(_))));
+        let metadata = SessionController::read_fusion_metadata(&project_path, session_id)
+            .expect("read metadata");
+        assert!(metadata.variants.iter().all(|v| v.failure.is_some()));
+    }
+
+    #[test]
+    fn session_api_token_is_stable_persisted_and_rendered_into_prompts() {
+        let temp_dir = tempfile::tempdir().expect("temp dir");
+        let storage = Arc::new(
+            SessionStorage::new_with_base(temp_dir.path().to_path_buf()).expect("storage"),
+        );
+        let mut controller = test_controller();
+        controller.set_storage(Arc::clone(&storage));
+
+        assert_eq!(controller.issued_session_api_token("session-a"), None);
+        let token = controller.session_api_token("session-a");
+        assert_eq!(controller.session_api_token("session-a"), token);
+        assert_ne!(controller.session_api_token("session-b"), token);
+
+        let prompt = controller.with_session_api_token("session-a", "Prompt body");
+        assert!(prompt.starts_with("Prompt body"));
+        assert!(prompt.contains(&format!("-H \"{}: {}\"", SESSION_TOKEN_HEADER, token)));
+
+        // A restarted app keeps accepting the agents it already briefed.
+        let mut restarted = test_controller();
+        restarted.set_storage(storage);
+        assert_eq!(restarted.issued_session_api_token("session-a"), Some(token));
+    }
+
+    #[test]
+    fn offline_mode_prompts_point_agents_at_the_outbox() {
+        let temp_dir = tempfile::tempdir().expect("temp dir");
+        let storage = Arc::new(
+            SessionStorage::new_with_base(temp_dir.path().to_path_buf()).expect("storage"),
+        );
+        let mut config = storage.load_config().expect("config");
+        config.api.offline = true;
+        storage.save_config(&config).expect("save config");
+        let mut controller = test_controller();
+        controller.set_storage(Arc::clone(&storage));
+
+        let prompt = controller.with_session_api_token("session-a", "Prompt body");
+        let outbox = storage.outbox_dir("session-a");
+        assert!(prompt.contains(&format!("{}/heartbeat-1.json", outbox.display())));
+        assert!(!prompt.contains(&controller.session_api_token("session-a")));
+    }
+
+    #[test]
+    fn adopt_session_rebuilds_a_hive_from_its_project_directory() {
+        let app_data = tempfile::tempdir().expect("app data dir");
+        let project = tempfile::tempdir().expect("project dir");
+        let mut controller = test_controller();
+        controller.set_storage(Arc::new(
+            SessionStorage::new_with_base(app_data.path().to_path_buf()).expect("storage"),
+        ));
+
+        let session_id = "adopted-hive";
+        let session_root = project.path().join(".hive-manager").join(session_id);
+        let tasks_dir = session_root.join("tasks");
+        std::fs::create_dir_all(&tasks_dir).expect("tasks dir");
+        std::fs::write(session_root.join("plan.md"), "# Plan\n").expect("plan");
+        std::fs::write(tasks_dir.join("worker-1-task.md"), "## Status: COMPLETED\n")
+            .expect("task 1");
+        std::fs::write(tasks_dir.join("worker-2-task.md"), "## Status: ACTIVE\n").expect("task 2");
+
+        let session = controller
+            .adopt_session(project.path(), session_id)
+            .expect("adopt session");
+        assert!(matches!(
+            session.session_type,
+            SessionType::Hive { worker_count: 2 }
+        ));
+        assert_eq!(session.state, SessionState::Paused);
+        assert!(session.no_git);
+        assert_eq!(session.agents.len(), 3);
+        assert!(controller.get_session(session_id).is_some());
+
+        let err = controller
+            .adopt_session(project.path(), session_id)
+            .expect_err("already loaded");
+        assert!(err.contains("already loaded"));
+        assert!(controller
+            .adopt_session(project.path(), "missing-session")
+            .is_err());
+    }
+
+    #[test]
+    fn resume_reconciles_agents_with_task_files_and_ptys() {
+        use crate::domain::run_journal::AgentResumeState;
+
+        let app_data = tempfile::tempdir().expect("app data dir");
+        let project = tempfile::tempdir().expect("project dir");
+        let mut controller = test_controller();
+        controller.set_storage(Arc::new(
+            SessionStorage::new_with_base(app_data.path().to_path_buf()).expect("storage"),
+        ));
+
+        let session_id = "reconciled-hive";
+        let tasks_dir = project
+            .path()
+            .join(".hive-manager")
+            .join(session_id)
+            .join("tasks");
+        std::fs::create_dir_all(&tasks_dir).expect("tasks dir");
+        std::fs::write(tasks_dir.join("worker-1-task.md"), "## Status: COMPLETED\n")
+            .expect("task 1");
+        std::fs::write(tasks_dir.join("worker-2-task.md"), "## Status: ACTIVE\n").expect("task 2");
+
+        let session = controller
+            .adopt_session(project.path(), session_id)
+            .expect("adopt session");
+        let report = session.resume_report.expect("resume report");
+        assert_eq!(report.agents.len(), 3);
+        for agent in &session.agents {
+            let line = report
+                .agents
+                .iter()
+                .find(|line| line.agent_id == agent.id)
+                .expect("agent in report");
+            match agent.role {
+                AgentRole::Worker { index: 1, .. } => {
+                    assert_eq!(line.state, AgentResumeState::Completed);
+                    assert_eq!(agent.status, AgentStatus::Completed);
+                }
+                AgentRole::Worker { index: 2, .. } => {
+                    assert_eq!(line.state, AgentResumeState::MissingPty);
+                    assert_eq!(line.task_status.as_deref(), Some("ACTIVE"));
+                    assert!(matches!(agent.status, AgentStatus::Error(_)));
+                }
+                _ => {
+                    assert_eq!(line.state, AgentResumeState::MissingPty);
+                    assert_eq!(line.task_status, None);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn persisted_judging_state_keeps_outstanding_judge_count() {
+        let state = SessionState::Judging { outstanding: 3 };
+        assert_eq!(serialize_session_state(&state), "Judging:3");
+        assert_eq!(parse_persisted_session_state("Judging:3"), state);
+        assert_eq!(
+            parse_persisted_session_state("Judging"),
+            SessionState::Judging { outstanding: 1 }
+        );
+    }
+
     #[test]
     fn evaluator_required_protocol_omits_queen_only_handoff_and_wait_text() {
         let evaluator_prompt = SessionController::build_evaluator_prompt(
+            &TemplateEngine::default(),
             "session-123",
             &AgentConfig {
                 cli: "claude".to_string(),
@@ -15485,6 +22874,7 @@ mod tests {
                 parent_id: Some(format!("{session_id}-queen")),
                 commit_sha: None,
                 base_commit_sha: current_head(&worker_worktree).ok(),
+                exit_info: None,
             }],
             default_cli: "claude".to_string(),
             default_model: None,
@@ -15500,6 +22890,8 @@ mod tests {
             worktree_branch: None,
             no_git: false,
             resume_report: None,
+            pull_request_url: None,
+            spawn_quota: SpawnQuota::default(),
         }
     }
 
@@ -15519,6 +22911,7 @@ mod tests {
                 parent_id: None,
                 commit_sha: None,
                 base_commit_sha: None,
+                exit_info: None,
             });
         }
 
@@ -15552,6 +22945,8 @@ mod tests {
             worktree_branch: None,
             no_git: false,
             resume_report: None,
+            pull_request_url: None,
+            spawn_quota: SpawnQuota::default(),
         }
     }
 
@@ -15747,6 +23142,7 @@ mod tests {
             parent_id: None,
             commit_sha: None,
             base_commit_sha: None,
+            exit_info: None,
         }];
         if with_prince {
             agents.push(AgentInfo {
@@ -15757,6 +23153,7 @@ mod tests {
                 parent_id: None,
                 commit_sha: None,
                 base_commit_sha: None,
+                exit_info: None,
             });
         }
         Session {
@@ -15783,6 +23180,8 @@ mod tests {
             worktree_branch: None,
             no_git: false,
             resume_report: None,
+            pull_request_url: None,
+            spawn_quota: SpawnQuota::default(),
         }
     }
 
@@ -15930,6 +23329,7 @@ mod tests {
         // A manually configured adversarial lane counts toward the target but must
         // not suppress the remaining automatic coverage.
         let prompt = SessionController::build_evaluator_prompt(
+            &TemplateEngine::default(),
             "adv-config",
             &AgentConfig {
                 cli: "claude".to_string(),
@@ -16179,6 +23579,8 @@ mod tests {
             worktree_branch: None,
             no_git: false,
             resume_report: None,
+            pull_request_url: None,
+            spawn_quota: SpawnQuota::default(),
         };
 
         assert!(session.worktree_path.is_none());
@@ -16329,6 +23731,7 @@ mod tests {
         let workspace = "/repo/.hive-manager/worktrees/session/primary";
 
         let shared = SessionController::build_prince_prompt(
+            &TemplateEngine::default(),
             "session",
             &prince,
             &principal,
@@ -16344,6 +23747,7 @@ mod tests {
         assert!(shared.contains(workspace));
 
         let isolated = SessionController::build_prince_prompt(
+            &TemplateEngine::default(),
             "session",
             &prince,
             &principal,
@@ -16398,6 +23802,7 @@ mod tests {
 
     fn render_debate_test_debater_prompt_for_cli(global_wiki_path: &str, cli: &str) -> String {
         SessionController::build_debate_debater_prompt(
+            &TemplateEngine::default(),
             "session-wiki",
             &debate_test_debater_with_cli(cli),
             "Monolith versus microservices",
@@ -16534,6 +23939,7 @@ mod tests {
     #[test]
     fn debate_judge_prompt_loads_prior_wiki_context_when_path_configured() {
         let prompt = SessionController::build_debate_judge_prompt(
+            &TemplateEngine::default(),
             "session-wiki",
             &debate_test_metadata(),
             DEBATE_TEST_WIKI_PATH,
@@ -16568,6 +23974,7 @@ mod tests {
     fn debate_judge_prompt_skips_wiki_load_gracefully_when_path_unset() {
         for unset in ["", "   "] {
             let prompt = SessionController::build_debate_judge_prompt(
+                &TemplateEngine::default(),
                 "session-wiki",
                 &debate_test_metadata(),
                 unset,
@@ -16610,6 +24017,7 @@ mod tests {
         let extra_vars =
             SessionController::research_queen_extra_vars(global_wiki_path, queen_cli, false);
         SessionController::build_templated_queen_prompt(
+            &TemplateEngine::default(),
             "queen-research",
             "session-wiki",
             &[AgentConfig::default()],
@@ -16687,6 +24095,7 @@ mod tests {
         assert_no_unrendered_template_syntax(&debater, "debater");
 
         let judge = SessionController::build_debate_judge_prompt(
+            &TemplateEngine::default(),
             "session-wiki",
             &debate_test_metadata(),
             MIXED_SEPARATOR_WIKI_PATH,
@@ -16722,6 +24131,7 @@ mod tests {
         );
 
         let judge = SessionController::build_debate_judge_prompt(
+            &TemplateEngine::default(),
             "session-wiki",
             &debate_test_metadata(),
             MIXED_SEPARATOR_WIKI_PATH,
@@ -16760,6 +24170,7 @@ mod tests {
             (
                 "judge",
                 SessionController::build_debate_judge_prompt(
+                    &TemplateEngine::default(),
                     "session-wiki",
                     &debate_test_metadata(),
                     DEBATE_TEST_WIKI_PATH,
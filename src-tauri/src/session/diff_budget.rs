@@ -0,0 +1,85 @@
+//! Line budgets for worker diffs.
+//!
+//! [`DiffBudgetConfig`] caps how many lines one worker may change against the
+//! commit it started from. Past the warning threshold the worker is told to split
+//! the work up; past the stop threshold it is stopped and the Queen told, so one
+//! runaway agent cannot rewrite half the repository.
+
+use serde::Serialize;
+
+use crate::storage::DiffBudgetConfig;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DiffBudgetStage {
+    /// Over the warning threshold; the worker was told to decompose its work.
+    Warned,
+    /// Over the stop threshold; the worker was stopped.
+    Stopped,
+}
+
+impl DiffBudgetStage {
+    /// The furthest stage a worker with `lines` changed has reached.
+    pub fn reached(lines: u64, budget: &DiffBudgetConfig) -> Option<Self> {
+        if budget.stop_lines.is_some_and(|stop| lines >= stop) {
+            Some(Self::Stopped)
+        } else if budget.warn_lines.is_some_and(|warn| lines >= warn) {
+            Some(Self::Warned)
+        } else {
+            None
+        }
+    }
+}
+
+/// Sum the added and deleted lines of `git diff --numstat` output. Binary files,
+/// listed as `-`, count as no lines.
+pub fn numstat_lines(numstat: &str) -> u64 {
+    numstat
+        .lines()
+        .map(|line| {
+            line.split('\t')
+                .take(2)
+                .filter_map(|count| count.parse::<u64>().ok())
+                .sum::<u64>()
+        })
+        .sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_numstat_lines_adds_insertions_and_deletions() {
+        let numstat = "120\t30\tsrc/lib.rs\n-\t-\tassets/logo.png\n5\t0\tREADME.md\n";
+        assert_eq!(numstat_lines(numstat), 155);
+        assert_eq!(numstat_lines(""), 0);
+    }
+
+    #[test]
+    fn test_diff_budget_stage_follows_the_thresholds() {
+        let budget = DiffBudgetConfig {
+            warn_lines: Some(2_000),
+            stop_lines: Some(4_000),
+        };
+        assert_eq!(DiffBudgetStage::reached(1_999, &budget), None);
+        assert_eq!(
+            DiffBudgetStage::reached(2_000, &budget),
+            Some(DiffBudgetStage::Warned)
+        );
+        assert_eq!(
+            DiffBudgetStage::reached(4_500, &budget),
+            Some(DiffBudgetStage::Stopped)
+        );
+
+        let stop_only = DiffBudgetConfig {
+            warn_lines: None,
+            stop_lines: Some(100),
+        };
+        assert_eq!(DiffBudgetStage::reached(99, &stop_only), None);
+        assert_eq!(
+            DiffBudgetStage::reached(5_000, &DiffBudgetConfig::default()),
+            None
+        );
+    }
+}
@@ -0,0 +1,361 @@
+//! Wall-clock time spent per session phase and per agent.
+//!
+//! Every persisted state change is folded into `durations.json` under the session
+//! root: a span per phase the session passes through (Planning, Worker N active,
+//! Judging, ...) and, per agent, the time its process was live. The totals are what
+//! [`summarize`] reports, so Hive, Swarm and Fusion runs of similar tasks can be
+//! compared after the fact.
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use super::SessionState;
+
+const DURATIONS_FILE: &str = "durations.json";
+
+/// An agent as seen at one state change.
+pub struct AgentObservation<'a> {
+    pub agent_id: &'a str,
+    pub role: String,
+    /// The agent's process is live (starting, working or idle).
+    pub running: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PhaseDuration {
+    pub phase: String,
+    pub started_at: DateTime<Utc>,
+    /// `None` while the session is still in this phase.
+    pub ended_at: Option<DateTime<Utc>>,
+    pub secs: i64,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AgentDuration {
+    pub agent_id: String,
+    pub role: String,
+    pub started_at: DateTime<Utc>,
+    /// When the agent last stopped; `None` while it is still running.
+    pub ended_at: Option<DateTime<Utc>>,
+    /// Seconds the agent's process was live, summed over restarts.
+    pub secs: i64,
+    pub running: bool,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct SessionDurations {
+    pub session_id: String,
+    pub session_type: String,
+    /// From the first recorded phase to the end of the last one (or now).
+    pub total_secs: i64,
+    /// Phases in the order the session entered them; a phase entered twice
+    /// appears twice.
+    pub phases: Vec<PhaseDuration>,
+    pub phase_totals: BTreeMap<String, i64>,
+    pub agents: Vec<AgentDuration>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct AgentSpan {
+    role: String,
+    started_at: DateTime<Utc>,
+    #[serde(default)]
+    ended_at: Option<DateTime<Utc>>,
+    #[serde(default)]
+    running_since: Option<DateTime<Utc>>,
+    #[serde(default)]
+    completed_secs: i64,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct DurationLedger {
+    phases: Vec<PhaseDuration>,
+    agents: BTreeMap<String, AgentSpan>,
+}
+
+/// Phase a session state counts toward; `None` before the session starts and once it
+/// has finished.
+pub fn phase_label(state: &SessionState) -> Option<String> {
+    let label = match state {
+        SessionState::Planning | SessionState::PlanReady => "Planning".to_string(),
+        SessionState::Starting => "Starting".to_string(),
+        SessionState::SpawningWorker(index) | SessionState::WaitingForWorker(index) => {
+            format!("Worker {} active", index)
+        }
+        SessionState::SpawningPlanner(index) | SessionState::WaitingForPlanner(index) => {
+            format!("Planner {} active", index)
+        }
+        SessionState::SpawningFusionVariant(_) | SessionState::WaitingForFusionVariants => {
+            "Variants running".to_string()
+        }
+        SessionState::SpawningDebateRound(round) | SessionState::WaitingForDebateRound(round) => {
+            format!("Debate round {}", round)
+        }
+        SessionState::SpawningJudge
+        | SessionState::Judging { .. }
+        | SessionState::AwaitingVerdictSelection => "Judging".to_string(),
+        SessionState::MergingWinner => "Merging".to_string(),
+        SessionState::SpawningEvaluator
+        | SessionState::QaInProgress { .. }
+        | SessionState::QaPassed
+        | SessionState::QaFailed { .. }
+        | SessionState::QaInconclusive => "QA".to_string(),
+        SessionState::PrinceRemediation => "Remediation".to_string(),
+        SessionState::Reviewing { role, .. } => format!("Review ({})", role),
+        SessionState::Running => "Running".to_string(),
+        SessionState::Paused => "Paused".to_string(),
+        SessionState::Queued
+        | SessionState::QaMaxRetriesExceeded
+        | SessionState::Completed
+        | SessionState::Closing
+        | SessionState::Closed
+        | SessionState::Failed(_) => return None,
+    };
+    Some(label)
+}
+
+fn durations_path(session_root: &Path) -> PathBuf {
+    session_root.join(DURATIONS_FILE)
+}
+
+fn load(session_root: &Path) -> DurationLedger {
+    fs::read_to_string(durations_path(session_root))
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+fn save(session_root: &Path, ledger: &DurationLedger) -> io::Result<()> {
+    let content = serde_json::to_string_pretty(ledger).map_err(io::Error::other)?;
+    let path = durations_path(session_root);
+    let tmp = path.with_extension("json.tmp");
+    fs::write(&tmp, content)?;
+    fs::rename(&tmp, path)
+}
+
+/// Close or open phase and agent spans for the session as it is `now`. Writes
+/// only when something changed; a session root that does not exist yet is left
+/// alone.
+pub fn record<'a>(
+    session_root: &Path,
+    phase: Option<String>,
+    agents: impl IntoIterator<Item = AgentObservation<'a>>,
+    now: DateTime<Utc>,
+) -> io::Result<()> {
+    if !session_root.is_dir() {
+        return Ok(());
+    }
+    let mut ledger = load(session_root);
+    let mut changed = false;
+
+    let current = ledger
+        .phases
+        .last()
+        .filter(|span| span.ended_at.is_none())
+        .map(|span| span.phase.clone());
+    if current != phase {
+        if let Some(span) = ledger
+            .phases
+            .last_mut()
+            .filter(|span| span.ended_at.is_none())
+        {
+            span.ended_at = Some(now);
+            span.secs = (now - span.started_at).num_seconds().max(0);
+        }
+        if let Some(phase) = phase {
+            ledger.phases.push(PhaseDuration {
+                phase,
+                started_at: now,
+                ended_at: None,
+                secs: 0,
+            });
+        }
+        changed = true;
+    }
+
+    for agent in agents {
+        match ledger.agents.get_mut(agent.agent_id) {
+            None if agent.running => {
+                ledger.agents.insert(
+                    agent.agent_id.to_string(),
+                    AgentSpan {
+                        role: agent.role,
+                        started_at: now,
+                        ended_at: None,
+                        running_since: Some(now),
+                        completed_secs: 0,
+                    },
+                );
+                changed = true;
+            }
+            None => {}
+            Some(span) => match (span.running_since, agent.running) {
+                (Some(since), false) => {
+                    span.completed_secs += (now - since).num_seconds().max(0);
+                    span.running_since = None;
+                    span.ended_at = Some(now);
+                    changed = true;
+                }
+                (None, true) => {
+                    span.running_since = Some(now);
+                    span.ended_at = None;
+                    changed = true;
+                }
+                _ => {}
+            },
+        }
+    }
+
+    if changed {
+        save(session_root, &ledger)?;
+    }
+    Ok(())
+}
+
+/// Recorded durations, with phases and agents still running measured up to `now`.
+pub fn summarize(
+    session_root: &Path,
+    session_id: &str,
+    session_type: &str,
+    now: DateTime<Utc>,
+) -> SessionDurations {
+    let ledger = load(session_root);
+
+    let phases: Vec<PhaseDuration> = ledger
+        .phases
+        .into_iter()
+        .map(|mut span| {
+            if span.ended_at.is_none() {
+                span.secs = (now - span.started_at).num_seconds().max(0);
+            }
+            span
+        })
+        .collect();
+    let mut phase_totals = BTreeMap::new();
+    for span in &phases {
+        *phase_totals.entry(span.phase.clone()).or_insert(0) += span.secs;
+    }
+    let total_secs = match (phases.first(), phases.last()) {
+        (Some(first), Some(last)) => (last.ended_at.unwrap_or(now) - first.started_at)
+            .num_seconds()
+            .max(0),
+        _ => 0,
+    };
+
+    let mut agents: Vec<AgentDuration> = ledger
+        .agents
+        .into_iter()
+        .map(|(agent_id, span)| AgentDuration {
+            agent_id,
+            role: span.role,
+            started_at: span.started_at,
+            ended_at: span.ended_at,
+            secs: span.completed_secs
+                + span
+                    .running_since
+                    .map_or(0, |since| (now - since).num_seconds().max(0)),
+            running: span.running_since.is_some(),
+        })
+        .collect();
+    agents.sort_by_key(|agent| agent.started_at);
+
+    SessionDurations {
+        session_id: session_id.to_string(),
+        session_type: session_type.to_string(),
+        total_secs,
+        phases,
+        phase_totals,
+        agents,
+    }
+}
+
+/// `1h 02m 03s`, `4m 05s` or `6s`.
+pub fn format_secs(secs: i64) -> String {
+    let (hours, minutes, seconds) = (secs / 3600, secs % 3600 / 60, secs % 60);
+    if hours > 0 {
+        format!("{}h {:02}m {:02}s", hours, minutes, seconds)
+    } else if minutes > 0 {
+        format!("{}m {:02}s", minutes, seconds)
+    } else {
+        format!("{}s", seconds)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration;
+
+    fn agent(agent_id: &str, running: bool) -> AgentObservation<'_> {
+        AgentObservation {
+            agent_id,
+            role: "Worker 1".to_string(),
+            running,
+        }
+    }
+
+    #[test]
+    fn test_record_tracks_phase_spans_and_agent_run_time() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        let start = Utc::now() - Duration::minutes(30);
+        let at = |minutes: i64| start + Duration::minutes(minutes);
+
+        record(root, phase_label(&SessionState::Planning), [], at(0)).unwrap();
+        record(
+            root,
+            phase_label(&SessionState::WaitingForWorker(1)),
+            [agent("w1", true)],
+            at(5),
+        )
+        .unwrap();
+        record(
+            root,
+            phase_label(&SessionState::WaitingForWorker(1)),
+            [agent("w1", false)],
+            at(15),
+        )
+        .unwrap();
+        record(
+            root,
+            phase_label(&SessionState::Judging { outstanding: 1 }),
+            [agent("w1", true)],
+            at(20),
+        )
+        .unwrap();
+        record(
+            root,
+            phase_label(&SessionState::Completed),
+            [agent("w1", false)],
+            at(22),
+        )
+        .unwrap();
+
+        let durations = summarize(root, "s1", "Fusion", Utc::now());
+        let phases: Vec<(&str, i64)> = durations
+            .phases
+            .iter()
+            .map(|span| (span.phase.as_str(), span.secs))
+            .collect();
+        assert_eq!(
+            phases,
+            vec![
+                ("Planning", 300),
+                ("Worker 1 active", 900),
+                ("Judging", 120)
+            ]
+        );
+        assert_eq!(durations.total_secs, 22 * 60);
+        assert_eq!(durations.phase_totals["Judging"], 120);
+        assert_eq!(durations.agents.len(), 1);
+        assert_eq!(durations.agents[0].secs, 12 * 60);
+        assert!(!durations.agents[0].running);
+        assert_eq!(format_secs(3723), "1h 02m 03s");
+        assert_eq!(format_secs(245), "4m 05s");
+    }
+}
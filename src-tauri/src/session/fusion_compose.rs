@@ -0,0 +1,266 @@
+//! Composing a Fusion result from several variants.
+//!
+//! When no single variant is best, the operator picks commits (cherry-picked) or
+//! whole files (taken as the variant has them) from any variants. [`compose`]
+//! applies the picks in order onto a new integration branch cut from the Fusion
+//! base, in a scratch worktree so the project checkout is left alone. A pick that
+//! conflicts is backed out and reported, and the rest still go on.
+
+use std::path::{Component, Path};
+
+use serde::{Deserialize, Serialize};
+
+use crate::workspace::git::{branch_exists, current_head, is_commit_on_branch, run_git, validate_branch_name};
+
+/// One piece of a variant to carry onto the integration branch.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum FusionPick {
+    /// Cherry-pick a commit from the variant's branch.
+    Commit { variant: String, commit: String },
+    /// Take a file as it is at the tip of the variant's branch.
+    File { variant: String, path: String },
+}
+
+impl FusionPick {
+    pub fn variant(&self) -> &str {
+        match self {
+            Self::Commit { variant, .. } | Self::File { variant, .. } => variant,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct FusionComposeRequest {
+    /// Integration branch to create; `fusion/<session>/composed` when unset.
+    #[serde(default)]
+    pub branch: Option<String>,
+    /// Applied in order.
+    pub picks: Vec<FusionPick>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PickStatus {
+    Applied,
+    /// The file is already as the variant has it.
+    Unchanged,
+    /// Backed out; `conflicts` lists the files it clashed on.
+    Conflicted,
+    /// Not applied for another reason, given in `message`.
+    Failed,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct PickOutcome {
+    pub pick: FusionPick,
+    pub status: PickStatus,
+    pub conflicts: Vec<String>,
+    pub message: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct FusionComposeReport {
+    pub branch: String,
+    pub base: String,
+    /// The integration branch's tip once every pick has been tried.
+    pub head: String,
+    pub picks: Vec<PickOutcome>,
+}
+
+impl FusionComposeReport {
+    pub fn has_conflicts(&self) -> bool {
+        self.picks
+            .iter()
+            .any(|pick| pick.status == PickStatus::Conflicted)
+    }
+}
+
+pub fn default_branch(session_id: &str) -> String {
+    format!("fusion/{}/composed", session_id)
+}
+
+/// Create `branch` from `base` and apply `picks`, each paired with its variant's
+/// branch, in a worktree at `worktree_path` that is removed again afterwards.
+pub fn compose(
+    project_path: &Path,
+    base: &str,
+    branch: &str,
+    worktree_path: &Path,
+    picks: &[(FusionPick, String)],
+) -> Result<FusionComposeReport, String> {
+    validate_branch_name(branch)?;
+    if branch_exists(project_path, branch)? {
+        return Err(format!("Branch {} already exists", branch));
+    }
+    if let Some(parent) = worktree_path.parent() {
+        std::fs::create_dir_all(parent)
+            .map_err(|e| format!("Failed to create worktree parent dir: {}", e))?;
+    }
+    let worktree = worktree_path.to_string_lossy().to_string();
+    run_git(
+        project_path,
+        &["worktree", "add", &worktree, "-b", branch, base],
+    )?;
+
+    let outcomes = picks
+        .iter()
+        .map(|(pick, variant_branch)| apply_pick(project_path, worktree_path, pick, variant_branch))
+        .collect();
+    let head = current_head(worktree_path);
+
+    if let Err(e) = run_git(project_path, &["worktree", "remove", "--force", &worktree]) {
+        tracing::warn!("Failed to remove compose worktree {}: {}", worktree, e);
+    }
+    Ok(FusionComposeReport {
+        branch: branch.to_string(),
+        base: base.to_string(),
+        head: head?,
+        picks: outcomes,
+    })
+}
+
+fn apply_pick(
+    project_path: &Path,
+    worktree: &Path,
+    pick: &FusionPick,
+    variant_branch: &str,
+) -> PickOutcome {
+    let outcome = |status, conflicts, message: Option<String>| PickOutcome {
+        pick: pick.clone(),
+        status,
+        conflicts,
+        message,
+    };
+    match pick {
+        FusionPick::Commit { commit, .. } => {
+            match is_commit_on_branch(project_path, commit, variant_branch) {
+                Ok(true) => {}
+                Ok(false) => {
+                    return outcome(
+                        PickStatus::Failed,
+                        Vec::new(),
+                        Some(format!("{} is not on {}", commit, variant_branch)),
+                    )
+                }
+                Err(e) => return outcome(PickStatus::Failed, Vec::new(), Some(e)),
+            }
+            match run_git(worktree, &["cherry-pick", "-x", commit]) {
+                Ok(_) => outcome(PickStatus::Applied, Vec::new(), None),
+                Err(e) => {
+                    let conflicts = conflicted_files(worktree);
+                    let _ = run_git(worktree, &["cherry-pick", "--abort"]);
+                    let status = if conflicts.is_empty() {
+                        PickStatus::Failed
+                    } else {
+                        PickStatus::Conflicted
+                    };
+                    outcome(status, conflicts, Some(e))
+                }
+            }
+        }
+        FusionPick::File { variant, path } => {
+            if !is_relative_inside(path) {
+                return outcome(
+                    PickStatus::Failed,
+                    Vec::new(),
+                    Some(format!("{} is not a path inside the project", path)),
+                );
+            }
+            if let Err(e) = run_git(worktree, &["checkout", variant_branch, "--", path]) {
+                return outcome(PickStatus::Failed, Vec::new(), Some(e));
+            }
+            let staged = run_git(worktree, &["diff", "--cached", "--name-only"]).unwrap_or_default();
+            if staged.trim().is_empty() {
+                return outcome(PickStatus::Unchanged, Vec::new(), None);
+            }
+            let message = format!("Take {} from fusion variant {}", path, variant);
+            match run_git(worktree, &["commit", "-m", &message]) {
+                Ok(_) => outcome(PickStatus::Applied, Vec::new(), None),
+                Err(e) => {
+                    let _ = run_git(worktree, &["reset", "--hard", "HEAD"]);
+                    outcome(PickStatus::Failed, Vec::new(), Some(e))
+                }
+            }
+        }
+    }
+}
+
+fn conflicted_files(worktree: &Path) -> Vec<String> {
+    run_git(worktree, &["diff", "--name-only", "--diff-filter=U"])
+        .map(|output| output.lines().map(str::to_string).collect())
+        .unwrap_or_default()
+}
+
+fn is_relative_inside(path: &str) -> bool {
+    !path.trim().is_empty()
+        && Path::new(path)
+            .components()
+            .all(|component| matches!(component, Component::Normal(_) | Component::CurDir))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::http::tests::{init_git_repo_for_test, run_git_for_test as git};
+
+    fn commit_file(dir: &Path, file: &str, content: &str) -> String {
+        std::fs::write(dir.join(file), content).unwrap();
+        git(dir, &["add", file]);
+        git(dir, &["commit", "-qm", &format!("Write {}", file)]);
+        git(dir, &["rev-parse", "HEAD"])
+    }
+
+    #[test]
+    fn test_compose_applies_picks_and_reports_conflicts() {
+        let repo = tempfile::tempdir().unwrap();
+        let dir = repo.path();
+        init_git_repo_for_test(dir);
+        commit_file(dir, "shared.txt", "base\n");
+        git(dir, &["branch", "fusion/s1/base"]);
+
+        git(dir, &["checkout", "-qb", "fusion/s1/a", "fusion/s1/base"]);
+        let a_feature = commit_file(dir, "a.txt", "from a\n");
+        let a_shared = commit_file(dir, "shared.txt", "a's version\n");
+        git(dir, &["checkout", "-qb", "fusion/s1/b", "fusion/s1/base"]);
+        commit_file(dir, "b.txt", "from b\n");
+        commit_file(dir, "shared.txt", "b's version\n");
+        git(dir, &["checkout", "-q", "main"]);
+
+        let a = "fusion/s1/a".to_string();
+        let b = "fusion/s1/b".to_string();
+        let picks = vec![
+            (FusionPick::Commit { variant: "a".into(), commit: a_feature.clone() }, a.clone()),
+            (FusionPick::File { variant: "b".into(), path: "b.txt".into() }, b.clone()),
+            (FusionPick::File { variant: "b".into(), path: "shared.txt".into() }, b.clone()),
+            (FusionPick::Commit { variant: "a".into(), commit: a_shared }, a.clone()),
+            (FusionPick::Commit { variant: "b".into(), commit: a_feature }, b),
+            (FusionPick::File { variant: "a".into(), path: "../escape".into() }, a),
+        ];
+        let worktree = dir.join(".hive-fusion").join("s1").join("composed");
+        let report = compose(dir, "fusion/s1/base", &default_branch("s1"), &worktree, &picks)
+            .expect("compose");
+
+        let statuses: Vec<PickStatus> = report.picks.iter().map(|pick| pick.status).collect();
+        assert_eq!(
+            statuses,
+            vec![
+                PickStatus::Applied,
+                PickStatus::Applied,
+                PickStatus::Applied,
+                PickStatus::Conflicted,
+                PickStatus::Failed,
+                PickStatus::Failed,
+            ]
+        );
+        assert!(report.has_conflicts());
+        assert_eq!(report.picks[3].conflicts, vec!["shared.txt".to_string()]);
+        assert!(!worktree.exists());
+
+        let composed = default_branch("s1");
+        assert_eq!(report.head, git(dir, &["rev-parse", &composed]));
+        assert_eq!(git(dir, &["show", &format!("{composed}:a.txt")]), "from a");
+        assert_eq!(git(dir, &["show", &format!("{composed}:shared.txt")]), "b's version");
+        assert!(compose(dir, "fusion/s1/base", &composed, &worktree, &[]).is_err());
+    }
+}
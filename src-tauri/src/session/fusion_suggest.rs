@@ -0,0 +1,138 @@
+//! Proposing the variants of a new Fusion run from how earlier runs went.
+//!
+//! [`suggest`] prefers the CLI and model pairs that won past runs whose task shared
+//! a keyword with the new one, then those that won any run. When the history has
+//! too few winners it makes up the numbers with the other pairs it has seen, and
+//! then with the configured CLIs at their default models.
+
+use crate::storage::fusion_history::{self, FusionOutcome};
+
+use super::FusionVariantConfig;
+
+/// Fewest variants worth comparing.
+pub const MIN_VARIANTS: usize = 2;
+
+/// Most variants suggested; each runs a full CLI in its own worktree.
+pub const MAX_VARIANTS: usize = 4;
+
+type Pair = (String, Option<String>);
+
+/// Up to four variants for `task_description`, and at least two unless history
+/// and `fallback` together know fewer pairs. `fallback` lists CLI and model pairs
+/// to use when the history falls short, in order of preference.
+pub fn suggest(
+    history: &[FusionOutcome],
+    task_description: &str,
+    fallback: &[Pair],
+) -> Vec<FusionVariantConfig> {
+    let task_keywords = fusion_history::keywords(task_description);
+    let similar: Vec<FusionOutcome> = history
+        .iter()
+        .filter(|outcome| {
+            outcome
+                .task_keywords
+                .iter()
+                .any(|keyword| task_keywords.contains(keyword))
+        })
+        .cloned()
+        .collect();
+    let overall = fusion_history::rank(history);
+
+    let mut picks: Vec<Pair> = Vec::new();
+    let mut pick = |pair: Pair, limit: usize| {
+        if picks.len() < limit && !picks.contains(&pair) {
+            picks.push(pair);
+        }
+    };
+    for entry in fusion_history::rank(&similar) {
+        if entry.wins > 0 {
+            pick((entry.cli, entry.model), MAX_VARIANTS);
+        }
+    }
+    for entry in &overall {
+        if entry.wins > 0 {
+            pick((entry.cli.clone(), entry.model.clone()), MAX_VARIANTS);
+        }
+    }
+    for entry in overall {
+        pick((entry.cli, entry.model), MIN_VARIANTS);
+    }
+    for pair in fallback {
+        pick(pair.clone(), MIN_VARIANTS);
+    }
+
+    picks
+        .into_iter()
+        .map(|(cli, model)| FusionVariantConfig {
+            name: match &model {
+                Some(model) => format!("{}-{}", cli, model),
+                None => cli.clone(),
+            },
+            cli,
+            model,
+            flags: Vec::new(),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::fusion_history::FusionVariantOutcome;
+
+    fn outcome(task: &str, results: &[(&str, &str, bool)]) -> FusionOutcome {
+        FusionOutcome {
+            session_id: "f".to_string(),
+            project_path: "/repo".to_string(),
+            recorded_at: chrono::Utc::now(),
+            winner: String::new(),
+            variants: results
+                .iter()
+                .map(|(cli, model, won)| FusionVariantOutcome {
+                    name: cli.to_string(),
+                    cli: cli.to_string(),
+                    model: Some(model.to_string()),
+                    won: *won,
+                    votes: u8::from(*won),
+                    score: None,
+                })
+                .collect(),
+            task_keywords: fusion_history::keywords(task),
+        }
+    }
+
+    fn names(variants: &[FusionVariantConfig]) -> Vec<&str> {
+        variants.iter().map(|variant| variant.name.as_str()).collect()
+    }
+
+    #[test]
+    fn test_winners_on_similar_tasks_are_suggested_first() {
+        let history = [
+            outcome(
+                "Refactor the parser",
+                &[("codex", "gpt-5", true), ("claude", "opus", false)],
+            ),
+            outcome(
+                "Build the settings page UI",
+                &[("claude", "sonnet", true), ("codex", "gpt-5", false)],
+            ),
+            outcome(
+                "Tidy the logging errors",
+                &[("claude", "sonnet", true), ("qwen", "coder", false)],
+            ),
+        ];
+        let fallback = [("droid".to_string(), None)];
+
+        let ui = suggest(&history, "Redesign the settings UI", &fallback);
+        assert_eq!(names(&ui), ["claude-sonnet", "codex-gpt-5"]);
+        assert_eq!(ui[0].cli, "claude");
+        assert_eq!(ui[0].model.as_deref(), Some("sonnet"));
+
+        let parser = suggest(&history, "Speed up the parser", &fallback);
+        assert_eq!(names(&parser), ["codex-gpt-5", "claude-sonnet"]);
+
+        assert_eq!(names(&suggest(&[], "Anything", &fallback)), ["droid"]);
+        let fallback = [("claude".to_string(), None), ("codex".to_string(), None)];
+        assert_eq!(names(&suggest(&[], "Anything", &fallback)), ["claude", "codex"]);
+    }
+}
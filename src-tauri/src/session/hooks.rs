@@ -0,0 +1,175 @@
+//! Operator commands run when a session reaches certain points.
+//!
+//! Each [`HookConfig`] in `config.json` names an event and a program to start in
+//! the session's project directory. The session is described both in
+//! `HIVE_*` environment variables and as a JSON [`HookPayload`] on stdin, so a
+//! hook can kick off CI, back up the session directory or send a notification
+//! without changes to Hive Manager itself.
+
+use std::path::Path;
+use std::process::Command;
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+
+use super::SessionState;
+use crate::storage::HookConfig;
+use crate::workspace::git::output_with_input_and_timeout;
+
+pub const SESSION_COMPLETED: &str = "session-completed";
+pub const PLAN_READY: &str = "plan-ready";
+pub const JUDGE_VERDICT: &str = "judge-verdict";
+
+/// Characters of a failed hook's stderr kept for the coordination log.
+const MAX_HOOK_ERROR_CHARS: usize = 300;
+
+/// What a hook receives on stdin.
+#[derive(Debug, Clone, Serialize)]
+pub struct HookPayload {
+    pub event: String,
+    pub session_id: String,
+    pub session_type: String,
+    pub state: SessionState,
+    pub project_path: String,
+    /// The session's `.hive-manager/<id>` directory.
+    pub session_dir: String,
+    pub timestamp: DateTime<Utc>,
+}
+
+/// The hook event a session entering `to` from `from` fires, if any.
+pub fn event_for_transition(from: &SessionState, to: &SessionState) -> Option<&'static str> {
+    if from == to {
+        return None;
+    }
+    match to {
+        SessionState::Completed => Some(SESSION_COMPLETED),
+        SessionState::PlanReady => Some(PLAN_READY),
+        SessionState::AwaitingVerdictSelection => Some(JUDGE_VERDICT),
+        _ => None,
+    }
+}
+
+/// The hooks configured for `event`, in config order.
+pub fn matching(hooks: &[HookConfig], event: &str) -> Vec<HookConfig> {
+    hooks
+        .iter()
+        .filter(|hook| hook.event == event && !hook.command.trim().is_empty())
+        .cloned()
+        .collect()
+}
+
+/// Run `hook` to completion, or until its timeout kills it.
+pub fn run(hook: &HookConfig, payload: &HookPayload) -> Result<(), String> {
+    let input = serde_json::to_vec(payload)
+        .map_err(|e| format!("Failed to serialize hook payload: {}", e))?;
+    let mut command = Command::new(&hook.command);
+    command
+        .args(&hook.args)
+        .env("HIVE_HOOK_EVENT", &payload.event)
+        .env("HIVE_SESSION_ID", &payload.session_id)
+        .env("HIVE_SESSION_TYPE", &payload.session_type)
+        .env("HIVE_PROJECT_PATH", &payload.project_path)
+        .env("HIVE_SESSION_DIR", &payload.session_dir);
+    if Path::new(&payload.project_path).is_dir() {
+        command.current_dir(&payload.project_path);
+    }
+
+    let output = output_with_input_and_timeout(
+        &mut command,
+        Some(input),
+        Duration::from_secs(hook.timeout_secs.max(1)),
+    )
+    .map_err(|e| e.to_string())?;
+    if output.status.success() {
+        return Ok(());
+    }
+    let stderr: String = String::from_utf8_lossy(&output.stderr)
+        .trim()
+        .chars()
+        .take(MAX_HOOK_ERROR_CHARS)
+        .collect();
+    Err(if stderr.is_empty() {
+        format!("exited with {}", output.status)
+    } else {
+        format!("exited with {}: {}", output.status, stderr)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn hook(event: &str, script: &str) -> HookConfig {
+        HookConfig {
+            event: event.to_string(),
+            command: "sh".to_string(),
+            args: vec!["-c".to_string(), script.to_string()],
+            timeout_secs: 5,
+        }
+    }
+
+    #[test]
+    fn test_transitions_fire_events_once() {
+        let running = SessionState::Running;
+        assert_eq!(
+            event_for_transition(&running, &SessionState::Completed),
+            Some(SESSION_COMPLETED)
+        );
+        assert_eq!(
+            event_for_transition(&SessionState::Planning, &SessionState::PlanReady),
+            Some(PLAN_READY)
+        );
+        assert_eq!(
+            event_for_transition(
+                &SessionState::Judging { outstanding: 1 },
+                &SessionState::AwaitingVerdictSelection
+            ),
+            Some(JUDGE_VERDICT)
+        );
+        assert_eq!(
+            event_for_transition(&SessionState::Completed, &SessionState::Completed),
+            None
+        );
+        assert_eq!(event_for_transition(&running, &SessionState::QaPassed), None);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_hook_gets_session_context_in_env_and_stdin() {
+        let project = tempfile::tempdir().unwrap();
+        let hooks = vec![
+            hook(PLAN_READY, "exit 0"),
+            hook(
+                SESSION_COMPLETED,
+                "cat > payload.json && printf '%s' \"$HIVE_SESSION_ID\" > session_id",
+            ),
+        ];
+        let matched = matching(&hooks, SESSION_COMPLETED);
+        assert_eq!(matched.len(), 1);
+
+        let payload = HookPayload {
+            event: SESSION_COMPLETED.to_string(),
+            session_id: "s1".to_string(),
+            session_type: "Hive".to_string(),
+            state: SessionState::Completed,
+            project_path: project.path().to_string_lossy().into_owned(),
+            session_dir: project.path().join(".hive-manager/s1").to_string_lossy().into_owned(),
+            timestamp: Utc::now(),
+        };
+        run(&matched[0], &payload).unwrap();
+
+        let written = std::fs::read_to_string(project.path().join("payload.json")).unwrap();
+        let written: serde_json::Value = serde_json::from_str(&written).unwrap();
+        assert_eq!(written["event"], "session-completed");
+        assert_eq!(written["session_type"], "Hive");
+        assert_eq!(
+            std::fs::read_to_string(project.path().join("session_id")).unwrap(),
+            "s1"
+        );
+
+        let failing = hook(SESSION_COMPLETED, "echo 'no CI token' >&2; exit 3");
+        let error = run(&failing, &payload).unwrap_err();
+        assert!(error.contains("no CI token"), "{error}");
+    }
+}
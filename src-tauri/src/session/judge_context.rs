@@ -0,0 +1,136 @@
+//! Context packs for the Fusion judge.
+//!
+//! Before a judge is spawned, each surviving variant's changes are written to
+//! `evaluation/context/`: the full diff against the Fusion base and a summary of
+//! commits, changed files and any test results the variant left behind. The judge
+//! prompt points at these files, so the judge reads them instead of running git
+//! itself, which is slower and often goes wrong in Windows shells.
+
+use std::fmt::Write as _;
+use std::path::{Path, PathBuf};
+
+use crate::artifacts::collector::detect_test_results;
+use crate::workspace::git::run_git;
+
+pub const CONTEXT_DIR: &str = "context";
+
+/// The files written for one variant.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VariantContext {
+    pub diff_file: PathBuf,
+    pub summary_file: PathBuf,
+}
+
+/// Write `<slug>.diff` and `<slug>-summary.md` under `evaluation_dir/context/` for
+/// a variant whose work is on `branch` (checked out at `worktree`).
+pub fn write_variant_context(
+    project_path: &Path,
+    evaluation_dir: &Path,
+    base_branch: &str,
+    variant_name: &str,
+    slug: &str,
+    branch: &str,
+    worktree: &Path,
+) -> Result<VariantContext, String> {
+    let context_dir = evaluation_dir.join(CONTEXT_DIR);
+    std::fs::create_dir_all(&context_dir)
+        .map_err(|e| format!("Failed to create judge context dir: {}", e))?;
+
+    let range = format!("{}..{}", base_branch, branch);
+    let diff = run_git(project_path, &["diff", &range, "--"])?;
+    let stat = run_git(project_path, &["diff", "--stat", &range, "--"])?;
+    let commits = run_git(project_path, &["log", "--oneline", &range])?;
+    // Work the variant never committed is not on its branch; flag it for the judge.
+    let uncommitted = if worktree.is_dir() {
+        run_git(worktree, &["status", "--porcelain"]).unwrap_or_default()
+    } else {
+        String::new()
+    };
+    let test_results = detect_test_results(worktree).ok().flatten();
+
+    let mut summary = format!("# Variant {} ({})\n\n", variant_name, branch);
+    section(&mut summary, "Commits", &commits, "No commits since the base.");
+    section(&mut summary, "Changed files", &stat, "No changes against the base.");
+    if !uncommitted.trim().is_empty() {
+        section(
+            &mut summary,
+            "Uncommitted changes (not in the diff)",
+            &uncommitted,
+            "",
+        );
+    }
+    match test_results {
+        Some(results) => {
+            let source = results["source"].as_str().unwrap_or("test results");
+            let snippet = results["snippet"].as_str().unwrap_or_default();
+            section(&mut summary, &format!("Test results ({})", source), snippet, "");
+        }
+        None => section(&mut summary, "Test results", "", "The variant left no test results file."),
+    }
+
+    let context = VariantContext {
+        diff_file: context_dir.join(format!("{}.diff", slug)),
+        summary_file: context_dir.join(format!("{}-summary.md", slug)),
+    };
+    std::fs::write(&context.diff_file, diff)
+        .map_err(|e| format!("Failed to write {}: {}", context.diff_file.display(), e))?;
+    std::fs::write(&context.summary_file, summary)
+        .map_err(|e| format!("Failed to write {}: {}", context.summary_file.display(), e))?;
+    Ok(context)
+}
+
+fn section(out: &mut String, title: &str, body: &str, when_empty: &str) {
+    let _ = writeln!(out, "## {}\n", title);
+    if body.trim().is_empty() {
+        let _ = writeln!(out, "{}\n", when_empty);
+    } else {
+        let _ = writeln!(out, "```\n{}\n```\n", body.trim_end());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::http::tests::{init_git_repo_for_test, run_git_for_test as git};
+
+    #[test]
+    fn test_variant_context_holds_diff_commits_and_test_results() {
+        let repo = tempfile::tempdir().unwrap();
+        let dir = repo.path();
+        init_git_repo_for_test(dir);
+        std::fs::write(dir.join("lib.rs"), "fn old() {}\n").unwrap();
+        git(dir, &["add", "."]);
+        git(dir, &["commit", "-qm", "Base"]);
+        git(dir, &["branch", "fusion/s1/base"]);
+        git(dir, &["checkout", "-qb", "fusion/s1/a"]);
+        std::fs::write(dir.join("lib.rs"), "fn new() {}\n").unwrap();
+        git(dir, &["commit", "-qam", "Rename old to new"]);
+        std::fs::write(dir.join("test-output.txt"), "test result: ok. 3 passed\n").unwrap();
+
+        let evaluation = dir.join(".hive-manager").join("s1").join("evaluation");
+        let context = write_variant_context(
+            dir,
+            &evaluation,
+            "fusion/s1/base",
+            "Variant A",
+            "variant-a",
+            "fusion/s1/a",
+            dir,
+        )
+        .expect("context pack");
+
+        assert_eq!(
+            context.diff_file,
+            evaluation.join("context").join("variant-a.diff")
+        );
+        let diff = std::fs::read_to_string(&context.diff_file).unwrap();
+        assert!(diff.contains("+fn new() {}"));
+        let summary = std::fs::read_to_string(&context.summary_file).unwrap();
+        assert!(summary.starts_with("# Variant Variant A (fusion/s1/a)"));
+        assert!(summary.contains("Rename old to new"));
+        assert!(summary.contains("lib.rs | 2 +-"));
+        assert!(summary.contains("## Uncommitted changes (not in the diff)"));
+        assert!(summary.contains("## Test results (test-output.txt)"));
+        assert!(summary.contains("3 passed"));
+    }
+}
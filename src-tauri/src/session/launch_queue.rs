@@ -0,0 +1,309 @@
+//! Launch admission under the global concurrency limits.
+//!
+//! [`ConcurrencyConfig`] caps how many sessions run at once and how many agents
+//! they run between them. A launch that would exceed either waits in the
+//! controller's launch queue as a `Queued` placeholder session and starts, under
+//! the same session ID, once running sessions free enough capacity.
+
+use std::cell::RefCell;
+use std::collections::BTreeSet;
+use std::path::PathBuf;
+
+use serde::Deserialize;
+use uuid::Uuid;
+
+use super::controller::{
+    DebateLaunchConfig, FusionLaunchConfig, HiveLaunchConfig, QaWorkerConfig, ResearchLaunchConfig,
+    SessionType, SwarmLaunchConfig,
+};
+use crate::pty::AgentConfig;
+use crate::storage::ConcurrencyConfig;
+
+/// A launch request held until there is capacity for it. Preflight checks take the
+/// same shape, as `{ "mode": "hive", "config": { ... } }`.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(tag = "mode", content = "config", rename_all = "snake_case")]
+pub enum QueuedLaunchConfig {
+    /// The legacy `launch_hive` arguments.
+    LegacyHive {
+        project_path: PathBuf,
+        worker_count: u8,
+        command: String,
+        prompt: Option<String>,
+        name: Option<String>,
+        color: Option<String>,
+    },
+    Hive(HiveLaunchConfig),
+    Solo(HiveLaunchConfig),
+    Research(ResearchLaunchConfig),
+    Swarm(SwarmLaunchConfig),
+    Fusion(FusionLaunchConfig),
+    Debate(DebateLaunchConfig),
+}
+
+impl QueuedLaunchConfig {
+    /// Agents the launch brings up, counting the QA evaluator and its workers when
+    /// requested. Judges are counted too: they start while variants still run.
+    pub fn agent_count(&self) -> usize {
+        let qa = |with_evaluator: bool, qa_workers: &Option<Vec<_>>| {
+            if with_evaluator {
+                1 + qa_workers.as_ref().map_or(0, Vec::len)
+            } else {
+                0
+            }
+        };
+        match self {
+            Self::LegacyHive { worker_count, .. } => 1 + *worker_count as usize,
+            Self::Hive(config) => {
+                1 + config.workers.len() + qa(config.with_evaluator, &config.qa_workers)
+            }
+            Self::Solo(config) => 1 + qa(config.with_evaluator, &config.qa_workers),
+            Self::Research(config) => 1 + config.workers.len(),
+            Self::Swarm(config) => {
+                let planners = if config.planners.is_empty() {
+                    config.planner_count as usize * (1 + config.workers_per_planner.len())
+                } else {
+                    config
+                        .planners
+                        .iter()
+                        .map(|planner| 1 + planner.workers.len())
+                        .sum()
+                };
+                1 + planners + qa(config.with_evaluator, &config.qa_workers)
+            }
+            Self::Fusion(config) => config.variants.len() + 1,
+            Self::Debate(config) => config.debaters.len() + 1,
+        }
+    }
+
+    /// Every CLI the launch spawns agents with, each once.
+    pub fn clis(&self) -> Vec<String> {
+        let qa = |evaluator: &Option<AgentConfig>, qa_workers: &Option<Vec<QaWorkerConfig>>| {
+            evaluator
+                .iter()
+                .map(|config| config.cli.clone())
+                .chain(qa_workers.iter().flatten().map(|worker| worker.cli.clone()))
+                .collect::<Vec<_>>()
+        };
+        let clis: BTreeSet<String> = match self {
+            Self::LegacyHive { command, .. } => [command.clone()].into(),
+            Self::Hive(config) => std::iter::once(&config.queen_config)
+                .chain(&config.workers)
+                .chain(
+                    config
+                        .review_stage
+                        .iter()
+                        .flat_map(|review| &review.agent_config),
+                )
+                .map(|agent| agent.cli.clone())
+                .chain(qa(&config.evaluator_config, &config.qa_workers))
+                .collect(),
+            Self::Solo(config) => std::iter::once(config.queen_config.cli.clone())
+                .chain(qa(&config.evaluator_config, &config.qa_workers))
+                .collect(),
+            Self::Research(config) => std::iter::once(&config.queen_config)
+                .chain(&config.workers)
+                .map(|agent| agent.cli.clone())
+                .collect(),
+            Self::Swarm(config) => {
+                let planners: Vec<&AgentConfig> = if config.planners.is_empty() {
+                    std::iter::once(&config.planner_config)
+                        .chain(&config.workers_per_planner)
+                        .collect()
+                } else {
+                    config
+                        .planners
+                        .iter()
+                        .flat_map(|planner| {
+                            std::iter::once(&planner.config).chain(&planner.workers)
+                        })
+                        .collect()
+                };
+                std::iter::once(&config.queen_config)
+                    .chain(planners)
+                    .map(|agent| agent.cli.clone())
+                    .chain(qa(&config.evaluator_config, &config.qa_workers))
+                    .collect()
+            }
+            Self::Fusion(config) => config
+                .variants
+                .iter()
+                .map(|variant| variant.cli.clone())
+                .chain([config.judge_config.cli.clone()])
+                .chain(config.queen_config.iter().map(|queen| queen.cli.clone()))
+                .collect(),
+            Self::Debate(config) => config
+                .debaters
+                .iter()
+                .map(|debater| debater.cli.clone())
+                .chain([config.judge_config.cli.clone()])
+                .chain(config.queen_config.iter().map(|queen| queen.cli.clone()))
+                .collect(),
+        };
+        clis.into_iter().collect()
+    }
+
+    pub fn session_type(&self) -> SessionType {
+        match self {
+            Self::LegacyHive { worker_count, .. } => SessionType::Hive {
+                worker_count: *worker_count,
+            },
+            Self::Hive(config) => SessionType::Hive {
+                worker_count: config.workers.len() as u8,
+            },
+            Self::Solo(config) => SessionType::Solo {
+                cli: config.queen_config.cli.clone(),
+                model: config.queen_config.model.clone(),
+            },
+            Self::Research(config) => SessionType::Hive {
+                worker_count: config.workers.len() as u8,
+            },
+            Self::Swarm(config) => SessionType::Swarm {
+                planner_count: if config.planners.is_empty() {
+                    config.planner_count
+                } else {
+                    config.planners.len() as u8
+                },
+            },
+            Self::Fusion(config) => SessionType::Fusion {
+                variants: config.variants.iter().map(|v| v.name.clone()).collect(),
+            },
+            Self::Debate(config) => SessionType::Debate {
+                variants: config.debaters.iter().map(|d| d.name.clone()).collect(),
+            },
+        }
+    }
+
+    pub fn project_path(&self) -> PathBuf {
+        match self {
+            Self::LegacyHive { project_path, .. } => project_path.clone(),
+            Self::Hive(config) | Self::Solo(config) => PathBuf::from(&config.project_path),
+            Self::Research(config) => PathBuf::from(&config.project_path),
+            Self::Swarm(config) => PathBuf::from(&config.project_path),
+            Self::Fusion(config) => PathBuf::from(&config.project_path),
+            Self::Debate(config) => PathBuf::from(&config.project_path),
+        }
+    }
+
+    pub fn name_and_color(&self) -> (Option<String>, Option<String>) {
+        let (name, color) = match self {
+            Self::LegacyHive { name, color, .. } => (name, color),
+            Self::Hive(config) | Self::Solo(config) => (&config.name, &config.color),
+            Self::Research(config) => (&config.name, &config.color),
+            Self::Swarm(config) => (&config.name, &config.color),
+            Self::Fusion(config) => (&config.name, &config.color),
+            Self::Debate(config) => (&config.name, &config.color),
+        };
+        (name.clone(), color.clone())
+    }
+
+    /// The CLI the session's lead agent runs, shown on the placeholder session.
+    pub fn default_cli(&self) -> String {
+        match self {
+            Self::LegacyHive { command, .. } => command.clone(),
+            Self::Hive(config) | Self::Solo(config) => config.queen_config.cli.clone(),
+            Self::Research(config) => config.queen_config.cli.clone(),
+            Self::Swarm(config) => config.queen_config.cli.clone(),
+            Self::Fusion(config) => config.default_cli.clone(),
+            Self::Debate(config) => config.default_cli.clone(),
+        }
+    }
+}
+
+/// A launch waiting in the queue. `session_id` is the placeholder session's ID,
+/// which the launched session keeps.
+#[derive(Debug, Clone)]
+pub struct QueuedLaunch {
+    pub session_id: String,
+    pub config: QueuedLaunchConfig,
+}
+
+/// Whether a launch of `agents` agents fits beside `sessions` running sessions
+/// with `running_agents` live agents. A launch larger than the agent cap still
+/// starts once nothing else is running, so it cannot wait forever.
+pub fn fits(
+    limits: &ConcurrencyConfig,
+    sessions: usize,
+    running_agents: usize,
+    agents: usize,
+) -> bool {
+    limits
+        .max_concurrent_sessions
+        .is_none_or(|max| sessions < max)
+        && limits
+            .max_total_agents
+            .is_none_or(|max| running_agents == 0 || running_agents + agents <= max)
+}
+
+thread_local! {
+    /// Session ID a queued launch reserved for the launch about to run on this thread.
+    static RESERVED_SESSION_ID: RefCell<Option<String>> = const { RefCell::new(None) };
+}
+
+/// The ID for a session being launched: the one reserved by
+/// [`with_reserved_session_id`] when starting a queued launch, otherwise a new one.
+pub(crate) fn new_session_id() -> String {
+    RESERVED_SESSION_ID
+        .with(|reserved| reserved.borrow_mut().take())
+        .unwrap_or_else(|| Uuid::new_v4().to_string())
+}
+
+/// Run `launch` so that the session it creates takes `session_id`. Launches are
+/// synchronous, so the reservation cannot leak to another launch on this thread.
+pub(crate) fn with_reserved_session_id<T>(session_id: &str, launch: impl FnOnce() -> T) -> T {
+    RESERVED_SESSION_ID.with(|reserved| *reserved.borrow_mut() = Some(session_id.to_string()));
+    let result = launch();
+    // A launch that failed before taking the ID must not hand it to the next one.
+    RESERVED_SESSION_ID.with(|reserved| reserved.borrow_mut().take());
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_launches_fit_within_session_and_agent_limits() {
+        let limits = ConcurrencyConfig {
+            max_concurrent_sessions: Some(2),
+            max_total_agents: Some(8),
+        };
+        assert!(fits(&limits, 1, 4, 4));
+        assert!(!fits(&limits, 2, 0, 1));
+        assert!(!fits(&limits, 1, 5, 4));
+        // An oversized launch is not stuck behind the agent cap forever.
+        assert!(fits(&limits, 0, 0, 12));
+        assert!(fits(&ConcurrencyConfig::default(), 50, 500, 10));
+    }
+
+    #[test]
+    fn test_launch_clis_are_listed_once_each() {
+        let launch: QueuedLaunchConfig = serde_json::from_value(serde_json::json!({
+            "mode": "fusion",
+            "config": {
+                "project_path": "/tmp/project",
+                "variants": [
+                    { "name": "a", "cli": "codex" },
+                    { "name": "b", "cli": "claude" },
+                    { "name": "c", "cli": "codex" },
+                ],
+                "task_description": "Fix the build",
+                "judge_config": { "cli": "claude" },
+                "default_model": null,
+            },
+        }))
+        .expect("fusion preflight config");
+        assert_eq!(launch.clis(), vec!["claude", "codex"]);
+        assert_eq!(launch.agent_count(), 4);
+    }
+
+    #[test]
+    fn test_reserved_session_id_is_used_once() {
+        let id = with_reserved_session_id("queued-1", new_session_id);
+        assert_eq!(id, "queued-1");
+        assert_ne!(new_session_id(), "queued-1");
+
+        with_reserved_session_id("queued-2", || ());
+        assert_ne!(new_session_id(), "queued-2");
+    }
+}
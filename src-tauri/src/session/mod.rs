@@ -1,12 +1,49 @@
+pub(crate) mod auto_complete;
 pub(crate) mod cell_status;
+pub(crate) mod comparison;
+pub(crate) mod context_monitor;
+mod context_pack;
 mod controller;
+pub(crate) mod diff_budget;
+pub(crate) mod durations;
+pub(crate) mod fusion_compose;
+pub(crate) mod fusion_suggest;
+pub(crate) mod hooks;
+mod judge_context;
+pub(crate) mod orphans;
+pub(crate) mod path_policy;
+pub(crate) mod launch_queue;
+pub(crate) mod phase_timeouts;
+pub(crate) mod plan_history;
+pub(crate) mod plan_parser;
+pub(crate) mod policy_script;
 mod polling_intervals;
+mod project_dna;
 mod prompt_contract;
+pub(crate) mod review_findings;
+pub(crate) mod review_stage;
+pub(crate) mod slug;
+pub(crate) mod smoke_probe;
+pub(crate) mod snapshots;
+pub(crate) mod spawn_quota;
+pub(crate) mod swarm_domains;
+pub(crate) mod warm_pool;
 
 #[allow(unused_imports)]
 pub use controller::{
-    AgentInfo, AuthStrategy, CompletionBlockedError, CompletionError, DebateDebaterConfig,
-    DebateDebaterStatus, DebateLaunchConfig, FusionLaunchConfig, FusionVariantConfig,
-    FusionVariantStatus, HiveLaunchConfig, QaWorkerConfig, ResearchLaunchConfig, Session,
-    SessionController, SessionState, SessionType, SwarmLaunchConfig, DEFAULT_MAX_QA_ITERATIONS,
+    AgentHeartbeatInfo, AgentInfo, AuthStrategy, CompletionBlockedError, CompletionError,
+    DebateDebaterConfig, DebateDebaterStatus, DebateLaunchConfig, DirtyTreeAction,
+    FusionJudgeVerdict, FusionLaunchConfig, FusionSpawnPlan, FusionVariantConfig,
+    FusionVariantProgress, FusionVariantStage, FusionVariantStatus, FusionVerdictTally,
+    HiveLaunchConfig, OperatorQuestion, QaWorkerConfig, ResearchLaunchConfig, Session,
+    SessionBranchCommit, SessionBranchStatus, SessionController, SessionState, SessionType,
+    SwarmLaunchConfig, WorkerScaleReport, AGENT_ID_HEADER, DEFAULT_MAX_QA_ITERATIONS,
+    HEARTBEAT_ROLLUP_INTERVAL, SESSION_PERSIST_DEBOUNCE, SESSION_TOKEN_HEADER,
 };
+pub use comparison::SessionComparison;
+pub use context_monitor::ContextPressure;
+pub use launch_queue::QueuedLaunchConfig;
+pub use orphans::OrphanedPtys;
+pub use phase_timeouts::PhaseTimeouts;
+pub use review_stage::ReviewStageConfig;
+pub use spawn_quota::SpawnQuota;
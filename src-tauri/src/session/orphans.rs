@@ -0,0 +1,77 @@
+//! Reconciling PTYs against the sessions that own them.
+//!
+//! A PTY can outlive its session's bookkeeping: the session map is cleared or
+//! an agent entry is dropped while the process keeps running. The watchdog
+//! compares the PTY manager's processes with every session's agents. An
+//! orphan whose ID still names a live session is adopted back into it; any
+//! other orphan is killed. A PTY must be seen orphaned on two consecutive
+//! passes before either happens, so one spawned just before its agent entry is
+//! recorded is left alone.
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use std::collections::HashSet;
+
+/// Watchdog counters, surfaced through the `get_orphaned_ptys` command.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct OrphanedPtys {
+    /// PTYs found orphaned on the last pass, awaiting a second sighting.
+    pub pending: Vec<String>,
+    /// PTYs adopted back into their session since startup.
+    pub adopted: u64,
+    /// PTYs killed since startup.
+    pub killed: u64,
+    pub last_checked: Option<DateTime<Utc>>,
+}
+
+/// The live session `pty_id` names by its `{session_id}-` prefix, if any. Of
+/// several matches the longest session ID wins.
+pub fn owning_session<'a>(
+    pty_id: &str,
+    live_sessions: impl IntoIterator<Item = &'a str>,
+) -> Option<&'a str> {
+    live_sessions
+        .into_iter()
+        .filter(|session_id| {
+            pty_id
+                .strip_prefix(session_id)
+                .is_some_and(|rest| rest.starts_with('-'))
+        })
+        .max_by_key(|session_id| session_id.len())
+}
+
+/// Split `orphans` into the ones seen on the previous pass, which are acted on,
+/// and the rest, which become the new `previous` set.
+pub fn confirm_orphans(
+    orphans: Vec<String>,
+    previous: &HashSet<String>,
+) -> (Vec<String>, Vec<String>) {
+    orphans
+        .into_iter()
+        .partition(|pty_id| previous.contains(pty_id))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_owning_session_matches_whole_id_prefixes() {
+        let sessions = ["abc", "abc-2"];
+        assert_eq!(owning_session("abc-worker-1", sessions), Some("abc"));
+        assert_eq!(owning_session("abc-2-queen", sessions), Some("abc-2"));
+        assert_eq!(owning_session("abcd-queen", sessions), None);
+        assert_eq!(owning_session("abc", sessions), None);
+    }
+
+    #[test]
+    fn test_orphans_are_confirmed_on_the_second_sighting() {
+        let previous: HashSet<String> = ["old-worker-1".to_string()].into();
+        let (confirmed, pending) = confirm_orphans(
+            vec!["old-worker-1".to_string(), "new-worker-2".to_string()],
+            &previous,
+        );
+        assert_eq!(confirmed, vec!["old-worker-1".to_string()]);
+        assert_eq!(pending, vec!["new-worker-2".to_string()]);
+    }
+}
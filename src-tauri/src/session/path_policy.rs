@@ -0,0 +1,126 @@
+//! Per-agent file permissions.
+//!
+//! An [`AgentConfig`] may list `allowed_paths` and `denied_paths` globs, relative
+//! to the agent's workspace. They are rendered into the worker's prompt and
+//! checked against the files it has changed since it started: a change matching
+//! a denied glob, or matching none of a non-empty allow list, is a violation.
+//! Violations are reported to the operator and the agent rather than reverted,
+//! since the worker may legitimately need the Queen to widen its scope.
+//! `.hive-manager/`, where agents write task files and reports, is always allowed.
+
+use serde::Serialize;
+
+use crate::coordination::approvals::glob_matches;
+use crate::pty::AgentConfig;
+
+/// `path-violation` payload.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct PathViolation {
+    pub session_id: String,
+    pub agent_id: String,
+    /// Newly detected paths, relative to the agent's workspace.
+    pub paths: Vec<String>,
+}
+
+/// Whether `config` restricts which files the agent may change.
+pub fn is_restricted(config: &AgentConfig) -> bool {
+    !config.allowed_paths.is_empty() || !config.denied_paths.is_empty()
+}
+
+/// Whether `config` lets the agent change `path`.
+pub fn permits(config: &AgentConfig, path: &str) -> bool {
+    let path = path.trim_start_matches("./");
+    if path == ".hive-manager" || path.starts_with(".hive-manager/") {
+        return true;
+    }
+    let matches_any = |patterns: &[String]| {
+        patterns
+            .iter()
+            .map(|pattern| pattern.trim().trim_start_matches("./"))
+            .filter(|pattern| !pattern.is_empty())
+            .any(|pattern| glob_matches(pattern, path))
+    };
+    if matches_any(&config.denied_paths) {
+        return false;
+    }
+    config.allowed_paths.is_empty() || matches_any(&config.allowed_paths)
+}
+
+/// The `changed` paths `config` does not permit, in their original order.
+pub fn violations(config: &AgentConfig, changed: &[String]) -> Vec<String> {
+    changed
+        .iter()
+        .filter(|path| !permits(config, path))
+        .cloned()
+        .collect()
+}
+
+/// The "File Permissions" prompt section, when the agent has any.
+pub fn prompt_block(config: &AgentConfig) -> Option<String> {
+    if !is_restricted(config) {
+        return None;
+    }
+    let list = |patterns: &[String]| {
+        patterns
+            .iter()
+            .map(|pattern| format!("- `{}`", pattern.trim()))
+            .collect::<Vec<_>>()
+            .join("\n")
+    };
+    let mut block = String::from(
+        "## File Permissions\n\nHive Manager checks every file you change against these globs, \
+         relative to your workspace. Changes outside them are reported to the operator and the \
+         Queen; ask the Queen before touching anything they exclude.",
+    );
+    if !config.allowed_paths.is_empty() {
+        block.push_str("\n\nYou may only change:\n");
+        block.push_str(&list(&config.allowed_paths));
+    }
+    if !config.denied_paths.is_empty() {
+        block.push_str("\n\nYou must not change:\n");
+        block.push_str(&list(&config.denied_paths));
+    }
+    Some(block)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn strings(values: &[&str]) -> Vec<String> {
+        values.iter().map(|value| value.to_string()).collect()
+    }
+
+    #[test]
+    fn test_denied_globs_win_and_allow_lists_fence_everything_else() {
+        let frontend = AgentConfig {
+            allowed_paths: strings(&["src/**", "package.json"]),
+            denied_paths: strings(&["src-tauri/**", "src/generated/**"]),
+            ..AgentConfig::default()
+        };
+        let changed = strings(&[
+            "src/lib/App.svelte",
+            "./package.json",
+            "src-tauri/src/lib.rs",
+            "src/generated/bindings.ts",
+            "README.md",
+            ".hive-manager/tasks/worker-1-task.md",
+        ]);
+        assert_eq!(
+            violations(&frontend, &changed),
+            strings(&["src-tauri/src/lib.rs", "src/generated/bindings.ts", "README.md"])
+        );
+
+        let deny_only = AgentConfig {
+            denied_paths: strings(&["src-tauri/**"]),
+            ..AgentConfig::default()
+        };
+        assert_eq!(violations(&deny_only, &changed), strings(&["src-tauri/src/lib.rs"]));
+        assert!(violations(&AgentConfig::default(), &changed).is_empty());
+
+        let block = prompt_block(&frontend).unwrap();
+        assert!(block.contains("You may only change:\n- `src/**`"), "{block}");
+        assert!(block.contains("You must not change:\n- `src-tauri/**`"), "{block}");
+        assert_eq!(prompt_block(&AgentConfig::default()), None);
+    }
+}
@@ -0,0 +1,156 @@
+//! Time boxes for session phases.
+//!
+//! A launch config may cap how long planning, each worker's task and judging run
+//! ([`PhaseTimeouts`]). The controller checks running phases periodically: at 80%
+//! of the budget the agent gets a heads-up, at 100% a wrap-up instruction, and once
+//! the grace period after that runs out the phase is forced to end.
+
+use std::collections::HashMap;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+/// Phase kinds. Planning and judging clocks are keyed by kind, worker clocks by
+/// the worker's agent ID.
+pub const PLANNING_PHASE: &str = "planning";
+pub const WORKER_PHASE: &str = "worker";
+pub const JUDGING_PHASE: &str = "judging";
+
+/// Maximum seconds per phase; `None` leaves the phase unbounded.
+#[derive(
+    Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, schemars::JsonSchema,
+)]
+pub struct PhaseTimeouts {
+    #[serde(default)]
+    pub planning_secs: Option<u64>,
+    /// Applies to each worker from when it starts until its task is COMPLETED.
+    #[serde(default)]
+    pub worker_secs: Option<u64>,
+    #[serde(default)]
+    pub judging_secs: Option<u64>,
+}
+
+impl PhaseTimeouts {
+    pub fn is_empty(&self) -> bool {
+        self.planning_secs.is_none() && self.worker_secs.is_none() && self.judging_secs.is_none()
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PhaseStage {
+    /// 80% of the budget used.
+    Warning,
+    /// Budget used up; the agent was told to wrap up.
+    WrapUp,
+    /// Grace period over; the phase was forced to end.
+    TimedOut,
+}
+
+impl PhaseStage {
+    /// The furthest stage a phase `elapsed_secs` into a `limit_secs` budget has reached.
+    pub fn reached(elapsed_secs: u64, limit_secs: u64) -> Option<Self> {
+        if elapsed_secs >= limit_secs.saturating_add(grace_secs(limit_secs)) {
+            Some(Self::TimedOut)
+        } else if elapsed_secs >= limit_secs {
+            Some(Self::WrapUp)
+        } else if elapsed_secs.saturating_mul(5) >= limit_secs.saturating_mul(4) {
+            Some(Self::Warning)
+        } else {
+            None
+        }
+    }
+
+    fn next(stage: Option<Self>) -> Option<Self> {
+        match stage {
+            None => Some(Self::Warning),
+            Some(Self::Warning) => Some(Self::WrapUp),
+            Some(Self::WrapUp) => Some(Self::TimedOut),
+            Some(Self::TimedOut) => None,
+        }
+    }
+}
+
+/// How long a phase gets to wrap up after its budget runs out: a tenth of the
+/// budget, at least a minute.
+pub fn grace_secs(limit_secs: u64) -> u64 {
+    (limit_secs / 10).max(60)
+}
+
+/// Where one running phase is on its clock.
+#[derive(Debug, Clone)]
+pub struct PhaseClock {
+    pub started_at: DateTime<Utc>,
+    pub stage: Option<PhaseStage>,
+}
+
+impl PhaseClock {
+    pub fn new(started_at: DateTime<Utc>) -> Self {
+        Self {
+            started_at,
+            stage: None,
+        }
+    }
+
+    /// The next stage to act on at `now`, if the phase has reached it. Stages fire
+    /// once each and in order, so an agent is always told to wrap up before its
+    /// phase is forced to end.
+    pub fn advance(&mut self, now: DateTime<Utc>, limit_secs: u64) -> Option<PhaseStage> {
+        let elapsed = (now - self.started_at).num_seconds().max(0) as u64;
+        let reached = PhaseStage::reached(elapsed, limit_secs)?;
+        let next = PhaseStage::next(self.stage).filter(|next| *next <= reached)?;
+        self.stage = Some(next);
+        Some(next)
+    }
+}
+
+/// A session's budgets and the clocks of its running phases, keyed by phase.
+#[derive(Debug, Clone, Default)]
+pub struct SessionPhaseClocks {
+    pub limits: PhaseTimeouts,
+    pub clocks: HashMap<String, PhaseClock>,
+}
+
+impl SessionPhaseClocks {
+    pub fn new(limits: PhaseTimeouts) -> Self {
+        Self {
+            limits,
+            clocks: HashMap::new(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration;
+
+    #[test]
+    fn test_phase_clock_warns_then_wraps_up_then_times_out() {
+        let start = Utc::now();
+        let at = |secs| start + Duration::seconds(secs);
+        let mut clock = PhaseClock::new(start);
+
+        assert_eq!(clock.advance(at(700), 1200), None);
+        assert_eq!(clock.advance(at(960), 1200), Some(PhaseStage::Warning));
+        assert_eq!(clock.advance(at(1000), 1200), None);
+        assert_eq!(clock.advance(at(1200), 1200), Some(PhaseStage::WrapUp));
+        assert_eq!(clock.advance(at(1300), 1200), None);
+        assert_eq!(clock.advance(at(1320), 1200), Some(PhaseStage::TimedOut));
+        assert_eq!(clock.advance(at(5000), 1200), None);
+
+        // A phase first checked long after its deadline still gets every stage.
+        let mut late = PhaseClock::new(start);
+        assert_eq!(late.advance(at(5000), 1200), Some(PhaseStage::Warning));
+        assert_eq!(late.advance(at(5000), 1200), Some(PhaseStage::WrapUp));
+        assert_eq!(late.advance(at(5000), 1200), Some(PhaseStage::TimedOut));
+    }
+
+    #[test]
+    fn test_short_budgets_get_a_minute_of_grace() {
+        assert_eq!(grace_secs(120), 60);
+        assert_eq!(grace_secs(2700), 270);
+        assert_eq!(PhaseStage::reached(170, 120), Some(PhaseStage::WrapUp));
+        assert_eq!(PhaseStage::reached(180, 120), Some(PhaseStage::TimedOut));
+    }
+}
@@ -0,0 +1,314 @@
+//! Stored `plan.md` revisions and diffs between them.
+//!
+//! Every distinct `plan.md` a planner writes is snapshotted to
+//! `plan-history/rev-N.md` under the session root, so the refinement rounds a user
+//! asks for during `PlanReady` can be compared after the fact.
+
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+pub const PLAN_HISTORY_DIR: &str = "plan-history";
+
+/// Unchanged lines shown around each change in a unified diff.
+const DIFF_CONTEXT: usize = 3;
+
+/// A newly recorded plan revision and the sections it changed.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub struct PlanRevision {
+    pub revision: u32,
+    pub changed_sections: Vec<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub struct PlanDiff {
+    pub rev_a: u32,
+    pub rev_b: u32,
+    /// Unified diff from `rev_a` to `rev_b`; empty when the revisions are identical.
+    pub diff: String,
+    pub changed_sections: Vec<String>,
+}
+
+fn history_dir(session_root: &Path) -> PathBuf {
+    session_root.join(PLAN_HISTORY_DIR)
+}
+
+fn revision_path(session_root: &Path, revision: u32) -> PathBuf {
+    history_dir(session_root).join(format!("rev-{}.md", revision))
+}
+
+/// Stored revision numbers, ascending.
+pub fn revisions(session_root: &Path) -> io::Result<Vec<u32>> {
+    let entries = match fs::read_dir(history_dir(session_root)) {
+        Ok(entries) => entries,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(e),
+    };
+    let mut revisions: Vec<u32> = entries
+        .filter_map(|entry| {
+            let name = entry.ok()?.file_name();
+            name.to_str()?
+                .strip_prefix("rev-")?
+                .strip_suffix(".md")?
+                .parse()
+                .ok()
+        })
+        .collect();
+    revisions.sort_unstable();
+    Ok(revisions)
+}
+
+pub fn read_revision(session_root: &Path, revision: u32) -> io::Result<Option<String>> {
+    match fs::read_to_string(revision_path(session_root, revision)) {
+        Ok(content) => Ok(Some(content)),
+        Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(None),
+        Err(e) => Err(e),
+    }
+}
+
+/// Snapshot `content` as the next revision unless it matches the latest one.
+pub fn record_revision(session_root: &Path, content: &str) -> io::Result<Option<PlanRevision>> {
+    if content.trim().is_empty() {
+        return Ok(None);
+    }
+    let latest = revisions(session_root)?.last().copied();
+    let previous = match latest {
+        Some(revision) => read_revision(session_root, revision)?,
+        None => None,
+    };
+    if previous.as_deref() == Some(content) {
+        return Ok(None);
+    }
+
+    let revision = latest.unwrap_or(0) + 1;
+    fs::create_dir_all(history_dir(session_root))?;
+    fs::write(revision_path(session_root, revision), content)?;
+    Ok(Some(PlanRevision {
+        revision,
+        changed_sections: previous
+            .map(|previous| changed_sections(&previous, content))
+            .unwrap_or_default(),
+    }))
+}
+
+/// Diff two stored revisions.
+pub fn plan_diff(session_root: &Path, rev_a: u32, rev_b: u32) -> io::Result<Option<PlanDiff>> {
+    let (Some(a), Some(b)) = (
+        read_revision(session_root, rev_a)?,
+        read_revision(session_root, rev_b)?,
+    ) else {
+        return Ok(None);
+    };
+    Ok(Some(PlanDiff {
+        rev_a,
+        rev_b,
+        diff: unified_diff(
+            &a,
+            &b,
+            &format!("plan.md@rev-{}", rev_a),
+            &format!("plan.md@rev-{}", rev_b),
+        ),
+        changed_sections: changed_sections(&a, &b),
+    }))
+}
+
+/// Markdown sections as `(heading, body)`, headings without their `#` markers. Text
+/// before the first heading is not a section.
+fn sections(content: &str) -> Vec<(String, String)> {
+    let mut sections: Vec<(String, String)> = Vec::new();
+    for line in content.lines() {
+        let trimmed = line.trim_start();
+        if trimmed.starts_with('#') {
+            let heading = trimmed.trim_start_matches('#').trim().to_string();
+            sections.push((heading, String::new()));
+        } else if let Some((_, body)) = sections.last_mut() {
+            body.push_str(line.trim_end());
+            body.push('\n');
+        }
+    }
+    sections
+}
+
+/// Headings of sections added, removed or edited between `a` and `b`, in `b`'s order
+/// followed by removed ones.
+pub fn changed_sections(a: &str, b: &str) -> Vec<String> {
+    let before = sections(a);
+    let after = sections(b);
+    let body_in = |sections: &[(String, String)], heading: &str| {
+        sections
+            .iter()
+            .filter(|(h, _)| h == heading)
+            .map(|(_, body)| body.trim().to_string())
+            .collect::<Vec<_>>()
+    };
+
+    let mut changed: Vec<String> = Vec::new();
+    for (heading, _) in after.iter().chain(before.iter()) {
+        if changed.contains(heading) {
+            continue;
+        }
+        if body_in(&before, heading) != body_in(&after, heading) {
+            changed.push(heading.clone());
+        }
+    }
+    changed
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Op {
+    Equal,
+    Delete,
+    Insert,
+}
+
+/// Line-level edit script from `a` to `b` via longest common subsequence.
+fn edit_script(a: &[&str], b: &[&str]) -> Vec<Op> {
+    // lcs[i][j] = LCS length of a[i..] and b[j..]
+    let mut lcs = vec![vec![0u32; b.len() + 1]; a.len() + 1];
+    for i in (0..a.len()).rev() {
+        for j in (0..b.len()).rev() {
+            lcs[i][j] = if a[i] == b[j] {
+                lcs[i + 1][j + 1] + 1
+            } else {
+                lcs[i + 1][j].max(lcs[i][j + 1])
+            };
+        }
+    }
+
+    let (mut i, mut j) = (0, 0);
+    let mut ops = Vec::with_capacity(a.len() + b.len());
+    while i < a.len() || j < b.len() {
+        if i < a.len() && j < b.len() && a[i] == b[j] {
+            ops.push(Op::Equal);
+            i += 1;
+            j += 1;
+        } else if j == b.len() || (i < a.len() && lcs[i + 1][j] >= lcs[i][j + 1]) {
+            ops.push(Op::Delete);
+            i += 1;
+        } else {
+            ops.push(Op::Insert);
+            j += 1;
+        }
+    }
+    ops
+}
+
+/// Unified diff of `a` → `b` with `DIFF_CONTEXT` lines of context.
+pub fn unified_diff(a: &str, b: &str, label_a: &str, label_b: &str) -> String {
+    let a_lines: Vec<&str> = a.lines().collect();
+    let b_lines: Vec<&str> = b.lines().collect();
+    let ops = edit_script(&a_lines, &b_lines);
+    if ops.iter().all(|op| *op == Op::Equal) {
+        return String::new();
+    }
+
+    // Line positions in `a` and `b` before each op.
+    let mut positions = Vec::with_capacity(ops.len() + 1);
+    let (mut ai, mut bi) = (0, 0);
+    for op in &ops {
+        positions.push((ai, bi));
+        match op {
+            Op::Equal => {
+                ai += 1;
+                bi += 1;
+            }
+            Op::Delete => ai += 1,
+            Op::Insert => bi += 1,
+        }
+    }
+    positions.push((ai, bi));
+
+    let mut out = format!("--- {}\n+++ {}\n", label_a, label_b);
+    let mut k = 0;
+    while k < ops.len() {
+        if ops[k] == Op::Equal {
+            k += 1;
+            continue;
+        }
+        let start = k.saturating_sub(DIFF_CONTEXT);
+        let mut end = k;
+        loop {
+            while end < ops.len() && ops[end] != Op::Equal {
+                end += 1;
+            }
+            let equal_run = ops[end..].iter().take_while(|op| **op == Op::Equal).count();
+            if end + equal_run == ops.len() || equal_run > 2 * DIFF_CONTEXT {
+                end = (end + DIFF_CONTEXT).min(ops.len());
+                break;
+            }
+            end += equal_run;
+        }
+
+        let (a_start, b_start) = positions[start];
+        let (a_end, b_end) = positions[end];
+        let header_start = |start: usize, len: usize| if len == 0 { start } else { start + 1 };
+        out.push_str(&format!(
+            "@@ -{},{} +{},{} @@\n",
+            header_start(a_start, a_end - a_start),
+            a_end - a_start,
+            header_start(b_start, b_end - b_start),
+            b_end - b_start
+        ));
+        for (op, (ai, bi)) in ops[start..end].iter().zip(&positions[start..end]) {
+            match op {
+                Op::Equal => out.push_str(&format!(" {}\n", a_lines[*ai])),
+                Op::Delete => out.push_str(&format!("-{}\n", a_lines[*ai])),
+                Op::Insert => out.push_str(&format!("+{}\n", b_lines[*bi])),
+            }
+        }
+        k = end;
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PLAN_V1: &str = "# Plan\n\n## Summary\nAdd login.\n\n## Tasks\n- [ ] Backend\n- [ ] Frontend\n\n## Risks\nNone.\n";
+    const PLAN_V2: &str = "# Plan\n\n## Summary\nAdd login.\n\n## Tasks\n- [ ] Backend API\n- [ ] Frontend\n- [ ] Tests\n\n## Rollout\nBehind a flag.\n";
+
+    #[test]
+    fn test_record_revision_skips_unchanged_plans_and_reports_changed_sections() {
+        let dir = tempfile::tempdir().unwrap();
+
+        let first = record_revision(dir.path(), PLAN_V1).unwrap().unwrap();
+        assert_eq!(first.revision, 1);
+        assert!(first.changed_sections.is_empty());
+        assert_eq!(record_revision(dir.path(), PLAN_V1).unwrap(), None);
+
+        let second = record_revision(dir.path(), PLAN_V2).unwrap().unwrap();
+        assert_eq!(second.revision, 2);
+        assert_eq!(second.changed_sections, vec!["Tasks", "Rollout", "Risks"]);
+        assert_eq!(revisions(dir.path()).unwrap(), vec![1, 2]);
+    }
+
+    #[test]
+    fn test_plan_diff_renders_unified_hunks() {
+        let dir = tempfile::tempdir().unwrap();
+        record_revision(dir.path(), PLAN_V1).unwrap();
+        record_revision(dir.path(), PLAN_V2).unwrap();
+
+        let diff = plan_diff(dir.path(), 1, 2).unwrap().unwrap();
+        assert_eq!(
+            diff.diff,
+            "--- plan.md@rev-1\n+++ plan.md@rev-2\n\
+             @@ -4,8 +4,9 @@\n \
+             Add login.\n \n \
+             ## Tasks\n\
+             -- [ ] Backend\n\
+             +- [ ] Backend API\n \
+             - [ ] Frontend\n\
+             +- [ ] Tests\n \n\
+             -## Risks\n\
+             -None.\n\
+             +## Rollout\n\
+             +Behind a flag.\n"
+        );
+        assert!(plan_diff(dir.path(), 1, 3).unwrap().is_none());
+        assert_eq!(plan_diff(dir.path(), 2, 2).unwrap().unwrap().diff, "");
+    }
+}
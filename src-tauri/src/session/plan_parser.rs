@@ -0,0 +1,526 @@
+//! `plan.md` read into a typed [`Plan`].
+//!
+//! Planners write free-form markdown, but the plans they are asked for share a
+//! shape: checklist tasks with a priority marker and an `-> Worker N` assignee,
+//! a files table, a dependencies section and, in a Swarm plan, `### Domain N:
+//! Name` headings. Whenever `plan.md` changes the
+//! parsed model is stored next to it as `plan.json`, so prompts, reports and
+//! other consumers read the same structure instead of re-parsing the markdown.
+
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::workspace::pull_request;
+
+use super::context_pack::mentions;
+
+pub const PLAN_FILE: &str = "plan.md";
+pub const PLAN_JSON_FILE: &str = "plan.json";
+
+/// Header cells that mark a table column as holding file paths.
+const FILE_COLUMNS: &[&str] = &["file", "files", "path", "paths"];
+/// Header cells naming who a file row belongs to.
+const OWNER_COLUMNS: &[&str] = &["owner", "assignee", "worker", "principal", "planner", "domain"];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum TaskPriority {
+    High,
+    Medium,
+    Low,
+}
+
+impl TaskPriority {
+    /// `HIGH`, `[P2]`, `critical` and the like, case-insensitively.
+    pub fn parse(marker: &str) -> Option<Self> {
+        let marker = marker.trim().trim_start_matches('[').trim_end_matches(']');
+        match marker.to_ascii_uppercase().as_str() {
+            "HIGH" | "P1" | "CRITICAL" => Some(Self::High),
+            "MEDIUM" | "MED" | "P2" => Some(Self::Medium),
+            "LOW" | "P3" => Some(Self::Low),
+            _ => None,
+        }
+    }
+
+    /// A bracketed marker only, so a task titled "Low-risk cleanup" keeps its word.
+    fn parse_marker(token: &str) -> Option<Self> {
+        if token.starts_with('[') && token.ends_with(']') {
+            Self::parse(token)
+        } else {
+            None
+        }
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::High => "high",
+            Self::Medium => "medium",
+            Self::Low => "low",
+        }
+    }
+}
+
+/// One checklist item.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub struct PlanTask {
+    /// 1-based position among the plan's tasks; "Task 2" in a dependency.
+    pub number: usize,
+    pub title: String,
+    pub done: bool,
+    pub priority: Option<TaskPriority>,
+    /// Whoever follows the `->` arrow, as written.
+    pub assignee: Option<String>,
+    /// The heading the task sits under.
+    pub section: Option<String>,
+}
+
+/// One path from a files table.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub struct PlanFile {
+    pub path: String,
+    pub owner: Option<String>,
+    pub priority: Option<TaskPriority>,
+    /// The row's remaining cells, such as "Changes Needed".
+    pub notes: Option<String>,
+}
+
+/// One entry of a dependencies section. `task` and `after` are filled when it
+/// reads "Task N depends on Task M".
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub struct PlanDependency {
+    pub description: String,
+    pub task: Option<usize>,
+    pub after: Vec<usize>,
+}
+
+/// A Swarm domain, from a `### Domain N: Name` heading.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub struct PlanDomain {
+    pub number: usize,
+    pub name: String,
+    /// Domains that must be complete before this one starts, from entries such
+    /// as "Domain 2 depends on Domain 1" or "Frontend depends on Backend".
+    pub depends_on: Vec<usize>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub struct Plan {
+    pub title: Option<String>,
+    pub summary: Option<String>,
+    pub tasks: Vec<PlanTask>,
+    pub files: Vec<PlanFile>,
+    pub dependencies: Vec<PlanDependency>,
+    pub domains: Vec<PlanDomain>,
+}
+
+/// Strip a priority marker such as `[HIGH]` from `text`.
+pub fn split_priority(text: &str) -> (String, Option<TaskPriority>) {
+    let Some(priority) = text.split_whitespace().find_map(TaskPriority::parse_marker) else {
+        return (text.to_string(), None);
+    };
+    let cleaned = text
+        .split_whitespace()
+        .filter(|token| TaskPriority::parse_marker(token).is_none())
+        .collect::<Vec<_>>()
+        .join(" ");
+    (cleaned, Some(priority))
+}
+
+/// Split `text` at its `->` (or `→`) into the title and the assignee.
+pub fn split_assignee(text: &str) -> (String, Option<String>) {
+    for separator in ["->", "\u{2192}"] {
+        if let Some((title, assignee)) = text.split_once(separator) {
+            return (title.to_string(), Some(assignee.trim().to_string()));
+        }
+    }
+    (text.to_string(), None)
+}
+
+fn checklist_item(line: &str) -> Option<(bool, &str)> {
+    let rest = line.strip_prefix("- ").or_else(|| line.strip_prefix("* "))?;
+    if let Some(rest) = rest.strip_prefix("[ ]") {
+        Some((false, rest.trim()))
+    } else {
+        rest.strip_prefix("[x]")
+            .or_else(|| rest.strip_prefix("[X]"))
+            .map(|rest| (true, rest.trim()))
+    }
+}
+
+fn list_item(line: &str) -> Option<&str> {
+    if let Some(rest) = line.strip_prefix("- ").or_else(|| line.strip_prefix("* ")) {
+        return Some(rest.trim());
+    }
+    let (number, rest) = line.split_once(". ")?;
+    (!number.is_empty() && number.chars().all(|c| c.is_ascii_digit())).then(|| rest.trim())
+}
+
+fn table_cells(line: &str) -> Option<Vec<&str>> {
+    let inner = line.strip_prefix('|')?;
+    let inner = inner.strip_suffix('|').unwrap_or(inner);
+    Some(inner.split('|').map(str::trim).collect())
+}
+
+fn is_separator_row(cells: &[&str]) -> bool {
+    cells
+        .iter()
+        .all(|cell| !cell.is_empty() && cell.chars().all(|c| matches!(c, '-' | ':' | ' ')))
+}
+
+/// Numbers written as "Task N", in order.
+fn task_refs(text: &str) -> Vec<usize> {
+    let words: Vec<&str> = text.split_whitespace().collect();
+    words
+        .windows(2)
+        .filter(|pair| pair[0].eq_ignore_ascii_case("task"))
+        .filter_map(|pair| {
+            pair[1]
+                .trim_end_matches(|c: char| !c.is_ascii_digit())
+                .parse()
+                .ok()
+        })
+        .collect()
+}
+
+fn dependency(text: &str) -> Option<PlanDependency> {
+    if text.is_empty() || text.to_lowercase().starts_with("none") {
+        return None;
+    }
+    let refs = task_refs(text);
+    let (task, after) = match refs.split_first() {
+        Some((task, after)) if !after.is_empty() && text.to_lowercase().contains("depends on") => {
+            (Some(*task), after.to_vec())
+        }
+        _ => (None, Vec::new()),
+    };
+    Some(PlanDependency {
+        description: text.to_string(),
+        task,
+        after,
+    })
+}
+
+/// `Domain 2: Frontend` as `(2, "Frontend")`.
+fn domain_heading(heading: &str) -> Option<(usize, String)> {
+    let (word, rest) = heading.split_once(' ')?;
+    if !word.eq_ignore_ascii_case("domain") {
+        return None;
+    }
+    let digits = rest.len() - rest.trim_start_matches(|c: char| c.is_ascii_digit()).len();
+    let number = rest[..digits].parse().ok()?;
+    let name = rest[digits..].trim_start_matches([':', '-', ' ', '\u{2013}']).trim();
+    Some((number, name.to_string()))
+}
+
+/// Domains `text` names, by "Domain N" / "Planner N" or by name.
+fn domain_refs(text: &str, domains: &[PlanDomain]) -> Vec<usize> {
+    let words: Vec<&str> = text.split_whitespace().collect();
+    let mut refs: Vec<usize> = words
+        .windows(2)
+        .filter(|pair| {
+            pair[0].eq_ignore_ascii_case("domain") || pair[0].eq_ignore_ascii_case("planner")
+        })
+        .filter_map(|pair| {
+            pair[1]
+                .trim_end_matches(|c: char| !c.is_ascii_digit())
+                .parse()
+                .ok()
+        })
+        .collect();
+    for domain in domains {
+        if !domain.name.is_empty() && mentions(text, std::slice::from_ref(&domain.name)) {
+            refs.push(domain.number);
+        }
+    }
+    refs.retain(|number| domains.iter().any(|domain| domain.number == *number));
+    let mut seen = Vec::new();
+    refs.retain(|number| {
+        let first = !seen.contains(number);
+        seen.push(*number);
+        first
+    });
+    refs
+}
+
+/// Fill in [`PlanDomain::depends_on`] from the plan's dependency entries.
+fn link_domains(plan: &mut Plan) {
+    for dependency in &plan.dependencies {
+        let lower = dependency.description.to_ascii_lowercase();
+        let Some(at) = lower.find("depends on") else {
+            continue;
+        };
+        let dependents = domain_refs(&dependency.description[..at], &plan.domains);
+        let prerequisites = domain_refs(&dependency.description[at..], &plan.domains);
+        for domain in plan.domains.iter_mut() {
+            if !dependents.contains(&domain.number) {
+                continue;
+            }
+            for prerequisite in &prerequisites {
+                if *prerequisite != domain.number && !domain.depends_on.contains(prerequisite) {
+                    domain.depends_on.push(*prerequisite);
+                }
+            }
+        }
+    }
+}
+
+/// Column layout of a files table, taken from its header row.
+struct FileTable {
+    path: usize,
+    owner: Option<usize>,
+    priority: Option<usize>,
+}
+
+impl FileTable {
+    fn from_header(cells: &[&str]) -> Option<Self> {
+        let position = |names: &[&str]| {
+            cells
+                .iter()
+                .position(|cell| names.contains(&cell.to_lowercase().as_str()))
+        };
+        Some(Self {
+            path: position(FILE_COLUMNS)?,
+            owner: position(OWNER_COLUMNS),
+            priority: position(&["priority"]),
+        })
+    }
+
+    fn files(&self, cells: &[&str]) -> Vec<PlanFile> {
+        let cell = |index: Option<usize>| {
+            index
+                .and_then(|index| cells.get(index))
+                .map(|cell| cell.trim_matches('`').trim())
+                .filter(|cell| !cell.is_empty() && !matches!(*cell, "-" | "N/A"))
+        };
+        let owner = cell(self.owner).map(str::to_string);
+        let priority = cell(self.priority).and_then(TaskPriority::parse);
+        let named = [Some(self.path), self.owner, self.priority];
+        let notes = cells
+            .iter()
+            .enumerate()
+            .filter(|(index, _)| !named.contains(&Some(*index)))
+            .map(|(_, cell)| *cell)
+            .filter(|cell| !cell.is_empty() && !matches!(*cell, "-" | "N/A"))
+            .collect::<Vec<_>>()
+            .join("; ");
+        let Some(paths) = cells.get(self.path) else {
+            return Vec::new();
+        };
+        paths
+            .split(',')
+            .map(|path| path.trim().trim_matches('`').trim())
+            // Placeholders such as "(smoke test - no real files)".
+            .filter(|path| !path.is_empty() && !path.starts_with('('))
+            .map(|path| PlanFile {
+                path: path.to_string(),
+                owner: owner.clone(),
+                priority,
+                notes: (!notes.is_empty()).then(|| notes.clone()),
+            })
+            .collect()
+    }
+}
+
+pub fn parse(content: &str) -> Plan {
+    let mut plan = Plan {
+        summary: pull_request::plan_summary(content),
+        ..Plan::default()
+    };
+    let mut section: Option<String> = None;
+    let mut in_dependencies = false;
+    // `Some(None)` inside a table that lists no files.
+    let mut table: Option<Option<FileTable>> = None;
+
+    for line in content.lines() {
+        let trimmed = line.trim();
+
+        if let Some(cells) = table_cells(trimmed) {
+            if is_separator_row(&cells) {
+                continue;
+            }
+            match &table {
+                None => table = Some(FileTable::from_header(&cells)),
+                Some(Some(layout)) => plan.files.extend(layout.files(&cells)),
+                Some(None) => {}
+            }
+            continue;
+        }
+        table = None;
+
+        if let Some(heading) = trimmed.strip_prefix('#') {
+            let level = heading.chars().take_while(|c| *c == '#').count();
+            let heading = heading.trim_start_matches('#').trim();
+            if level == 0 && plan.title.is_none() {
+                plan.title = Some(heading.to_string());
+            } else if level > 0 {
+                if let Some((number, name)) = domain_heading(heading) {
+                    plan.domains.push(PlanDomain {
+                        number,
+                        name,
+                        depends_on: Vec::new(),
+                    });
+                }
+                in_dependencies = heading.to_lowercase().contains("dependenc");
+                section = Some(heading.to_string());
+            }
+        } else if let Some((done, rest)) = checklist_item(trimmed) {
+            let (rest, priority) = split_priority(rest);
+            let (title, assignee) = split_assignee(&rest);
+            plan.tasks.push(PlanTask {
+                number: plan.tasks.len() + 1,
+                title: title.trim().to_string(),
+                done,
+                priority,
+                assignee,
+                section: section.clone(),
+            });
+        } else if in_dependencies {
+            plan.dependencies.extend(list_item(trimmed).and_then(dependency));
+        }
+    }
+    link_domains(&mut plan);
+    plan
+}
+
+/// Parse `plan.md` under `session_root` and store the result beside it as
+/// `plan.json`.
+pub fn store(session_root: &Path, content: &str) -> io::Result<Plan> {
+    let plan = parse(content);
+    let json = serde_json::to_string_pretty(&plan)
+        .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+    fs::write(session_root.join(PLAN_JSON_FILE), json)?;
+    Ok(plan)
+}
+
+/// The session's plan, parsed from `plan.md`; `None` when there is none yet.
+pub fn load(session_root: &Path) -> io::Result<Option<Plan>> {
+    match fs::read_to_string(session_root.join(PLAN_FILE)) {
+        Ok(content) => Ok(Some(parse(&content))),
+        Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(None),
+        Err(e) => Err(e),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PLAN: &str = "# Export Plan
+
+## Summary
+Add CSV export.
+
+## Tasks
+- [ ] [HIGH] Add the export endpoint -> Worker 1
+- [x] [P3] Low-risk cleanup of the settings page \u{2192} worker-2 (Frontend)
+- [ ] Write release notes
+
+## Task Details
+1. Not a task
+
+## Files to Modify
+| File | Owner | Priority | Changes Needed |
+|------|-------|----------|----------------|
+| `src/export.rs`, `src/api.rs` | Worker 1 | HIGH | New endpoint |
+| (smoke test - no real files) | N/A | N/A | N/A |
+
+| Risk | Mitigation |
+|------|------------|
+| Large exports | Stream rows |
+
+## Dependencies
+- Task 3 depends on Task 1 and Task 2 completing.
+- The API must ship before the UI.
+";
+
+    #[test]
+    fn test_parse_reads_tasks_files_and_dependencies() {
+        let plan = parse(PLAN);
+
+        assert_eq!(plan.title.as_deref(), Some("Export Plan"));
+        assert_eq!(plan.summary.as_deref(), Some("Add CSV export."));
+        assert_eq!(
+            plan.tasks[1],
+            PlanTask {
+                number: 2,
+                title: "Low-risk cleanup of the settings page".to_string(),
+                done: true,
+                priority: Some(TaskPriority::Low),
+                assignee: Some("worker-2 (Frontend)".to_string()),
+                section: Some("Tasks".to_string()),
+            }
+        );
+        assert_eq!(plan.tasks.len(), 3);
+        assert_eq!(plan.tasks[0].priority, Some(TaskPriority::High));
+        assert_eq!(plan.tasks[2].assignee, None);
+
+        assert_eq!(
+            plan.files,
+            ["src/export.rs", "src/api.rs"].map(|path| PlanFile {
+                path: path.to_string(),
+                owner: Some("Worker 1".to_string()),
+                priority: Some(TaskPriority::High),
+                notes: Some("New endpoint".to_string()),
+            })
+        );
+
+        assert_eq!(plan.dependencies.len(), 2);
+        assert_eq!(plan.dependencies[0].task, Some(3));
+        assert_eq!(plan.dependencies[0].after, vec![1, 2]);
+        assert_eq!(plan.dependencies[1].task, None);
+    }
+
+    #[test]
+    fn test_parse_links_swarm_domains_through_their_dependencies() {
+        let plan = parse(
+            "# Plan
+
+## Domain Tasks (for Planners)
+
+### Domain 1: Backend API
+- [ ] [HIGH] Add the endpoint -> Planner 1
+
+### Domain 2: Frontend
+- [ ] [MEDIUM] Add the button -> Planner 2
+
+### Domain 3: Docs
+- [ ] [LOW] Document export -> Planner 3
+
+## Cross-Domain Dependencies
+- Domain 2 depends on Domain 1.
+- Docs depends on Frontend and Backend API.
+",
+        );
+
+        let depends_on: Vec<(&str, &[usize])> = plan
+            .domains
+            .iter()
+            .map(|domain| (domain.name.as_str(), domain.depends_on.as_slice()))
+            .collect();
+        assert_eq!(
+            depends_on,
+            vec![
+                ("Backend API", &[][..]),
+                ("Frontend", &[1][..]),
+                ("Docs", &[1, 2][..]),
+            ]
+        );
+        assert_eq!(plan.tasks[1].section.as_deref(), Some("Domain 2: Frontend"));
+    }
+
+    #[test]
+    fn test_store_writes_plan_json_beside_the_markdown() {
+        let dir = tempfile::tempdir().unwrap();
+        assert_eq!(load(dir.path()).unwrap(), None);
+
+        fs::write(dir.path().join(PLAN_FILE), PLAN).unwrap();
+        let stored = store(dir.path(), PLAN).unwrap();
+        let json = fs::read_to_string(dir.path().join(PLAN_JSON_FILE)).unwrap();
+        assert_eq!(serde_json::from_str::<Plan>(&json).unwrap(), stored);
+        assert_eq!(load(dir.path()).unwrap(), Some(stored));
+    }
+}
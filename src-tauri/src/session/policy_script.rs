@@ -0,0 +1,255 @@
+//! Operator policy written in Rhai.
+//!
+//! `scripts/policy.rhai` in the app data dir may define any of these functions;
+//! those it leaves out keep the built-in behaviour:
+//!
+//! - `transform_prompt(ctx, prompt)` returns the prompt an agent is spawned with.
+//!   The session API token and coordination instructions are appended after it,
+//!   so a script cannot remove them.
+//! - `stall_threshold_secs(ctx)` returns how long the agent in `ctx.agent_id` may
+//!   go without a heartbeat before it counts as stalled.
+//! - `allow_spawn(ctx)` returns `true` to let a worker be added, or `false` or a
+//!   reason string to refuse it.
+//!
+//! `ctx` is a copy of the session's state, so a script can read it but not change
+//! it. Scripts get Rhai's standard library without `eval`, bounded in operations,
+//! call depth and data size, plus `log(message)` and `warn(message)`. The script
+//! is reloaded when the file changes; a script that fails to compile or run is
+//! logged and ignored.
+
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+use parking_lot::Mutex;
+use rhai::{Dynamic, Engine, Scope, AST};
+use serde_json::Value;
+
+pub const POLICY_SCRIPT: &str = "policy.rhai";
+
+const MAX_OPERATIONS: u64 = 1_000_000;
+const MAX_CALL_LEVELS: usize = 32;
+const MAX_STRING_SIZE: usize = 4 * 1024 * 1024;
+const MAX_COLLECTION_SIZE: usize = 10_000;
+
+/// What `allow_spawn` decided.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SpawnDecision {
+    Allow,
+    Veto(String),
+}
+
+struct LoadedScript {
+    modified: Option<SystemTime>,
+    ast: AST,
+}
+
+pub struct PolicyScript {
+    path: PathBuf,
+    engine: Engine,
+    loaded: Mutex<Option<LoadedScript>>,
+}
+
+impl PolicyScript {
+    /// The policy script in `scripts_dir`, which need not exist yet.
+    pub fn new(scripts_dir: &Path) -> Self {
+        Self {
+            path: scripts_dir.join(POLICY_SCRIPT),
+            engine: sandboxed_engine(),
+            loaded: Mutex::new(None),
+        }
+    }
+
+    pub fn transform_prompt(&self, ctx: &Value, prompt: &str) -> Option<String> {
+        let result = self.call("transform_prompt", ctx, Some(prompt))?;
+        match result.into_string() {
+            Ok(prompt) => Some(prompt),
+            Err(kind) => {
+                tracing::warn!("transform_prompt in {} returned {}", self.path.display(), kind);
+                None
+            }
+        }
+    }
+
+    pub fn stall_threshold_secs(&self, ctx: &Value) -> Option<u64> {
+        let result = self.call("stall_threshold_secs", ctx, None)?;
+        match result.as_int() {
+            Ok(secs) if secs >= 0 => Some(secs as u64),
+            _ => {
+                tracing::warn!(
+                    "stall_threshold_secs in {} must return a non-negative integer",
+                    self.path.display()
+                );
+                None
+            }
+        }
+    }
+
+    pub fn allow_spawn(&self, ctx: &Value) -> SpawnDecision {
+        let Some(result) = self.call("allow_spawn", ctx, None) else {
+            return SpawnDecision::Allow;
+        };
+        if let Ok(allowed) = result.as_bool() {
+            return if allowed {
+                SpawnDecision::Allow
+            } else {
+                SpawnDecision::Veto("refused by policy script".to_string())
+            };
+        }
+        match result.into_string() {
+            Ok(reason) if !reason.trim().is_empty() => SpawnDecision::Veto(reason),
+            _ => SpawnDecision::Allow,
+        }
+    }
+
+    /// Call `name` if the script defines it. `None` when there is no script, it
+    /// lacks the function, or the call failed.
+    fn call(&self, name: &str, ctx: &Value, prompt: Option<&str>) -> Option<Dynamic> {
+        let ast = self.current_ast()?;
+        if !ast.iter_functions().any(|function| function.name == name) {
+            return None;
+        }
+        let ctx = match rhai::serde::to_dynamic(ctx) {
+            Ok(ctx) => ctx,
+            Err(e) => {
+                tracing::warn!("Failed to pass context to {}: {}", name, e);
+                return None;
+            }
+        };
+        let mut scope = Scope::new();
+        let result = match prompt {
+            Some(prompt) => {
+                self.engine
+                    .call_fn::<Dynamic>(&mut scope, &ast, name, (ctx, prompt.to_string()))
+            }
+            None => self.engine.call_fn::<Dynamic>(&mut scope, &ast, name, (ctx,)),
+        };
+        match result {
+            Ok(result) => Some(result),
+            Err(e) => {
+                tracing::warn!("{} in {} failed: {}", name, self.path.display(), e);
+                None
+            }
+        }
+    }
+
+    /// The compiled script, recompiled when the file's modification time changes.
+    fn current_ast(&self) -> Option<AST> {
+        let modified = match std::fs::metadata(&self.path) {
+            Ok(metadata) => metadata.modified().ok(),
+            Err(_) => {
+                *self.loaded.lock() = None;
+                return None;
+            }
+        };
+        let mut loaded = self.loaded.lock();
+        if let Some(script) = loaded.as_ref() {
+            if script.modified == modified && modified.is_some() {
+                return Some(script.ast.clone());
+            }
+        }
+        let source = std::fs::read_to_string(&self.path).ok()?;
+        match self.engine.compile(&source) {
+            Ok(ast) => {
+                *loaded = Some(LoadedScript {
+                    modified,
+                    ast: ast.clone(),
+                });
+                Some(ast)
+            }
+            Err(e) => {
+                tracing::warn!("Failed to compile {}: {}", self.path.display(), e);
+                *loaded = None;
+                None
+            }
+        }
+    }
+}
+
+fn sandboxed_engine() -> Engine {
+    let mut engine = Engine::new();
+    engine
+        .set_max_operations(MAX_OPERATIONS)
+        .set_max_call_levels(MAX_CALL_LEVELS)
+        .set_max_string_size(MAX_STRING_SIZE)
+        .set_max_array_size(MAX_COLLECTION_SIZE)
+        .set_max_map_size(MAX_COLLECTION_SIZE)
+        .disable_symbol("eval");
+    engine.on_print(|message| tracing::info!("[policy.rhai] {}", message));
+    engine.on_debug(|message, _, _| tracing::debug!("[policy.rhai] {}", message));
+    engine.register_fn("log", |message: &str| {
+        tracing::info!("[policy.rhai] {}", message)
+    });
+    engine.register_fn("warn", |message: &str| {
+        tracing::warn!("[policy.rhai] {}", message)
+    });
+    engine
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    const SCRIPT: &str = r#"
+fn transform_prompt(ctx, prompt) {
+    if ctx.session_type == "Hive" {
+        prompt + "\n\nAlways run cargo fmt."
+    } else {
+        prompt
+    }
+}
+
+fn stall_threshold_secs(ctx) {
+    if ctx.agent_id.ends_with("-queen") { 600 } else { ctx.default_secs }
+}
+
+fn allow_spawn(ctx) {
+    if ctx.workers >= 2 { "at most 2 workers" } else { true }
+}
+"#;
+
+    #[test]
+    fn test_script_functions_shape_policy_and_missing_ones_fall_back() {
+        let dir = tempfile::tempdir().unwrap();
+        let policy = PolicyScript::new(dir.path());
+        let ctx = json!({ "session_id": "s1", "session_type": "Hive", "workers": 1 });
+        assert_eq!(policy.transform_prompt(&ctx, "Build it."), None);
+        assert_eq!(policy.allow_spawn(&ctx), SpawnDecision::Allow);
+
+        std::fs::write(dir.path().join(POLICY_SCRIPT), SCRIPT).unwrap();
+        assert_eq!(
+            policy.transform_prompt(&ctx, "Build it.").as_deref(),
+            Some("Build it.\n\nAlways run cargo fmt.")
+        );
+        assert_eq!(
+            policy.stall_threshold_secs(&json!({ "agent_id": "s1-queen", "default_secs": 180 })),
+            Some(600)
+        );
+        assert_eq!(
+            policy.stall_threshold_secs(&json!({ "agent_id": "s1-worker-1", "default_secs": 180 })),
+            Some(180)
+        );
+        assert_eq!(policy.allow_spawn(&ctx), SpawnDecision::Allow);
+        assert_eq!(
+            policy.allow_spawn(&json!({ "workers": 2 })),
+            SpawnDecision::Veto("at most 2 workers".to_string())
+        );
+
+        // A runaway script is cut off, and one using `eval` does not compile;
+        // both are treated as absent.
+        let runaway = tempfile::tempdir().unwrap();
+        std::fs::write(runaway.path().join(POLICY_SCRIPT), "fn allow_spawn(ctx) { loop {} }")
+            .unwrap();
+        assert_eq!(PolicyScript::new(runaway.path()).allow_spawn(&ctx), SpawnDecision::Allow);
+        let evaluating = tempfile::tempdir().unwrap();
+        std::fs::write(
+            evaluating.path().join(POLICY_SCRIPT),
+            "fn transform_prompt(ctx, prompt) { eval(\"prompt\") }",
+        )
+        .unwrap();
+        assert_eq!(
+            PolicyScript::new(evaluating.path()).transform_prompt(&ctx, "Build it."),
+            None
+        );
+    }
+}
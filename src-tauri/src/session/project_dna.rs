@@ -0,0 +1,47 @@
+//! Project DNA in worker prompts.
+//!
+//! Workers told to read `.ai-docs/project-dna.md` often skip it, so at spawn time
+//! the controller embeds the DNA itself (the session's curated copy, else the
+//! project's) in the worker prompt, cut down to [`ProjectDnaConfig::max_chars`].
+//!
+//! [`ProjectDnaConfig::max_chars`]: crate::storage::ProjectDnaConfig::max_chars
+
+pub const PROJECT_DNA_FILE: &str = ".ai-docs/project-dna.md";
+
+/// `dna` as it goes into a prompt. Past `max_chars` characters it is cut at the
+/// last line break that fits and followed by a pointer to the full file. `None`
+/// when there is nothing to embed.
+pub fn excerpt(dna: &str, max_chars: usize) -> Option<String> {
+    let dna = dna.trim();
+    if dna.is_empty() || max_chars == 0 {
+        return None;
+    }
+    let Some((cut, _)) = dna.char_indices().nth(max_chars) else {
+        return Some(dna.to_string());
+    };
+    let kept = &dna[..cut];
+    let kept = kept.rfind('\n').map_or(kept, |line_end| &kept[..line_end]);
+    Some(format!(
+        "{}\n\n[Project DNA truncated; read {} for the rest.]",
+        kept.trim_end(),
+        PROJECT_DNA_FILE
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_excerpt_keeps_whole_lines_within_the_cap() {
+        let dna = "# Project DNA\n\n- Use thiserror for errors\n- Tests live next to the code\n";
+        assert_eq!(excerpt(dna, 1000).as_deref(), Some(dna.trim()));
+        assert_eq!(excerpt("  \n", 1000), None);
+        assert_eq!(excerpt(dna, 0), None);
+
+        let trimmed = excerpt(dna, 45).unwrap();
+        assert!(trimmed.starts_with("# Project DNA\n\n- Use thiserror for errors\n\n["));
+        assert!(!trimmed.contains("Tests live"));
+        assert!(trimmed.ends_with("read .ai-docs/project-dna.md for the rest.]"));
+    }
+}
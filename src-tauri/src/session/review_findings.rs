@@ -0,0 +1,217 @@
+//! Structured review findings.
+//!
+//! Next to its prose report, a reviewer worker writes what it found as a JSON array
+//! to `reviews/worker-N.json` in the session directory, one object per comment:
+//! `{"file": "src/lib.rs", "line": 42, "severity": "major", "comment": "..."}`.
+//! [`load`] reads every such file back, keeping the entries that validate and
+//! saying what was wrong with the rest, so the resolver and the UI work from the
+//! same list through `GET /api/sessions/{id}/reviews`.
+
+use std::fs;
+use std::io;
+use std::path::{Component, Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+/// Directory under the session directory.
+pub const REVIEWS_DIR: &str = "reviews";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    Critical,
+    Major,
+    Minor,
+    Nit,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ReviewFinding {
+    /// Path relative to the workspace root, with `/` separators.
+    pub file: String,
+    /// 1-based line the comment is about; `None` for a comment on the whole file.
+    #[serde(default)]
+    pub line: Option<u32>,
+    pub severity: Severity,
+    pub comment: String,
+}
+
+/// One reviewer's findings file, as read back.
+#[derive(Debug, Clone, Serialize)]
+pub struct WorkerReview {
+    pub worker: u8,
+    pub path: PathBuf,
+    /// The valid findings, most severe first.
+    pub findings: Vec<ReviewFinding>,
+    /// What was wrong with the file, or with each entry that was dropped.
+    pub errors: Vec<String>,
+}
+
+/// Where worker `index` writes its findings.
+pub fn findings_path(session_root: &Path, index: u8) -> PathBuf {
+    session_root
+        .join(REVIEWS_DIR)
+        .join(format!("worker-{}.json", index))
+}
+
+/// Every `worker-N.json` under `session_root`, by worker index.
+pub fn load(session_root: &Path) -> io::Result<Vec<WorkerReview>> {
+    let entries = match fs::read_dir(session_root.join(REVIEWS_DIR)) {
+        Ok(entries) => entries,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(e),
+    };
+    let mut reviews = Vec::new();
+    for entry in entries {
+        let entry = entry?;
+        let file_name = entry.file_name().to_string_lossy().into_owned();
+        let Some(worker) = file_name
+            .strip_prefix("worker-")
+            .and_then(|rest| rest.strip_suffix(".json"))
+            .and_then(|index| index.parse::<u8>().ok())
+        else {
+            continue;
+        };
+        let (findings, errors) = parse(&fs::read_to_string(entry.path())?);
+        reviews.push(WorkerReview {
+            worker,
+            path: entry.path(),
+            findings,
+            errors,
+        });
+    }
+    reviews.sort_by_key(|review| review.worker);
+    Ok(reviews)
+}
+
+/// The valid findings in `content`, most severe first, and why the others were dropped.
+pub fn parse(content: &str) -> (Vec<ReviewFinding>, Vec<String>) {
+    let entries: Vec<serde_json::Value> = match serde_json::from_str(content) {
+        Ok(entries) => entries,
+        Err(e) => return (Vec::new(), vec![format!("Not a JSON array of findings: {}", e)]),
+    };
+    let mut findings = Vec::new();
+    let mut errors = Vec::new();
+    for (position, entry) in entries.into_iter().enumerate() {
+        let checked = serde_json::from_value::<ReviewFinding>(entry)
+            .map_err(|e| e.to_string())
+            .and_then(validate);
+        match checked {
+            Ok(finding) => findings.push(finding),
+            Err(error) => errors.push(format!("Entry {}: {}", position, error)),
+        }
+    }
+    findings.sort_by_key(|finding| finding.severity);
+    (findings, errors)
+}
+
+fn validate(finding: ReviewFinding) -> Result<ReviewFinding, String> {
+    let file = finding.file.trim().replace('\\', "/");
+    if file.is_empty() {
+        return Err("`file` is empty".to_string());
+    }
+    let path = Path::new(&file);
+    if path.has_root() || file.contains(':') {
+        return Err(format!("`file` must be relative to the workspace: {}", file));
+    }
+    if path.components().any(|part| part == Component::ParentDir) {
+        return Err(format!("`file` must stay inside the workspace: {}", file));
+    }
+    if finding.line == Some(0) {
+        return Err("`line` is 1-based".to_string());
+    }
+    let comment = finding.comment.trim();
+    if comment.is_empty() {
+        return Err("`comment` is empty".to_string());
+    }
+    Ok(ReviewFinding {
+        file,
+        comment: comment.to_string(),
+        ..finding
+    })
+}
+
+/// The "Review Findings" prompt section for a review worker, if its role has one.
+/// `findings_path` is the worker's findings file, spelled the way its CLI sees it.
+pub fn prompt_block(role_type: &str, findings_path: &str, session_id: &str) -> Option<String> {
+    match role_type {
+        "reviewer" | "reviewer-quick" => Some(format!(
+            r#"## Review Findings
+
+Besides any written report, record every finding in {findings_path} as a JSON array, one object per comment:
+
+```json
+[{{"file": "src/lib.rs", "line": 42, "severity": "major", "comment": "The lock is held across the await."}}]
+```
+
+`file` is relative to the workspace root; `line` is 1-based and may be left out for a comment on the whole file; `severity` is one of `critical`, `major`, `minor` or `nit`. Entries that do not fit are dropped and listed under `errors` in GET /api/sessions/{session_id}/reviews, so check that response once the file is written."#
+        )),
+        "resolver" => Some(format!(
+            "## Review Findings\n\nGET /api/sessions/{session_id}/reviews returns the reviewers' \
+             structured findings, one entry per reviewer with its `findings` most severe first. \
+             Resolve them in that order, starting with every `critical` one."
+        )),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_findings_are_validated_and_sorted_by_severity() {
+        let temp = tempfile::tempdir().unwrap();
+        let path = findings_path(temp.path(), 2);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(
+            &path,
+            r#"[
+                {"file": "src\\ui.rs", "severity": "nit", "comment": " Rename this "},
+                {"file": "src/lib.rs", "line": 42, "severity": "critical", "comment": "Panics"},
+                {"file": "../secrets.env", "line": 1, "severity": "major", "comment": "Leak"},
+                {"file": "src/lib.rs", "line": 0, "severity": "minor", "comment": "Off"},
+                {"file": "src/lib.rs", "severity": "blocker", "comment": "Unknown"}
+            ]"#,
+        )
+        .unwrap();
+        fs::write(findings_path(temp.path(), 1), "not json").unwrap();
+        fs::write(temp.path().join(REVIEWS_DIR).join("notes.json"), "[]").unwrap();
+
+        let reviews = load(temp.path()).unwrap();
+        assert_eq!(reviews.len(), 2);
+        assert_eq!(reviews[0].worker, 1);
+        assert!(reviews[0].findings.is_empty());
+        assert!(reviews[0].errors[0].starts_with("Not a JSON array"));
+
+        let review = &reviews[1];
+        assert_eq!(review.path, path);
+        assert_eq!(
+            review.findings,
+            vec![
+                ReviewFinding {
+                    file: "src/lib.rs".to_string(),
+                    line: Some(42),
+                    severity: Severity::Critical,
+                    comment: "Panics".to_string(),
+                },
+                ReviewFinding {
+                    file: "src/ui.rs".to_string(),
+                    line: None,
+                    severity: Severity::Nit,
+                    comment: "Rename this".to_string(),
+                },
+            ]
+        );
+        assert_eq!(review.errors.len(), 3);
+        assert!(review.errors[0].starts_with("Entry 2: `file` must stay inside"));
+        assert!(review.errors[1].starts_with("Entry 3: `line` is 1-based"));
+        assert!(review.errors[2].starts_with("Entry 4: unknown variant"));
+
+        assert!(load(&temp.path().join("missing")).unwrap().is_empty());
+        assert!(prompt_block("reviewer", "reviews/worker-2.json", "s1")
+            .unwrap()
+            .contains("GET /api/sessions/s1/reviews"));
+        assert!(prompt_block("backend", "reviews/worker-2.json", "s1").is_none());
+    }
+}
@@ -0,0 +1,268 @@
+//! Post-run review for Hive sessions.
+//!
+//! A Hive launch may ask for a review stage ([`ReviewStageConfig`]). Once every
+//! worker has finished, the controller runs the review chain: one worker per role,
+//! one after another, each briefed with the diff since the session started. The
+//! default chain is a `reviewer` that writes up findings and a `resolver` that acts
+//! on them. The session is `Reviewing` until the last of them reports COMPLETED.
+
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use crate::pty::{AgentConfig, WorkerRole};
+
+pub const DEFAULT_REVIEW_CHAIN: [&str; 2] = ["reviewer", "resolver"];
+
+fn default_chain() -> Vec<String> {
+    DEFAULT_REVIEW_CHAIN.map(String::from).to_vec()
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, schemars::JsonSchema)]
+pub struct ReviewStageConfig {
+    /// Worker roles to run, in order.
+    #[serde(default = "default_chain")]
+    pub chain: Vec<String>,
+    /// CLI and model for the review workers; the Queen's when unset.
+    #[serde(default)]
+    pub agent_config: Option<AgentConfig>,
+}
+
+impl Default for ReviewStageConfig {
+    fn default() -> Self {
+        Self {
+            chain: default_chain(),
+            agent_config: None,
+        }
+    }
+}
+
+/// The review step running: its place in the chain and the worker running it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ReviewStep {
+    pub step: usize,
+    pub worker_index: u8,
+}
+
+/// A session's review chain and how far it has got.
+#[derive(Debug, Clone)]
+pub struct ReviewStage {
+    pub chain: Vec<String>,
+    pub agent_config: AgentConfig,
+    pub current: Option<ReviewStep>,
+    pub finished: bool,
+}
+
+impl ReviewStage {
+    /// `queen_config` stands in for the review workers' CLI when the config names none.
+    pub fn new(config: ReviewStageConfig, queen_config: &AgentConfig) -> Self {
+        let chain = config
+            .chain
+            .into_iter()
+            .map(|role| role.trim().to_ascii_lowercase())
+            .filter(|role| !role.is_empty())
+            .collect();
+        let agent_config = config.agent_config.unwrap_or_else(|| AgentConfig {
+            label: None,
+            name: None,
+            description: None,
+            role: None,
+            initial_prompt: None,
+            ..queen_config.clone()
+        });
+        Self {
+            chain,
+            agent_config,
+            current: None,
+            finished: false,
+        }
+    }
+
+    /// The step to start once the current one (if any) has completed; `None` when
+    /// the chain is done.
+    pub fn next_step(&self) -> Option<usize> {
+        let next = self.current.map_or(0, |current| current.step + 1);
+        (!self.finished && next < self.chain.len()).then_some(next)
+    }
+
+    /// Config for the worker running `step`, briefed with `task`.
+    pub fn worker_config(&self, step: usize, task: String) -> (AgentConfig, WorkerRole) {
+        let role_type = &self.chain[step];
+        let label = role_label(role_type);
+        let role = WorkerRole::new(role_type, &label, &self.agent_config.cli);
+        let config = AgentConfig {
+            label: Some(label),
+            role: Some(role.clone()),
+            initial_prompt: Some(task),
+            ..self.agent_config.clone()
+        };
+        (config, role)
+    }
+}
+
+fn role_label(role_type: &str) -> String {
+    let mut chars = role_type.chars();
+    match chars.next() {
+        None => String::new(),
+        Some(first) => first.to_uppercase().collect::<String>() + chars.as_str(),
+    }
+}
+
+/// Where step `step` of the chain writes its report.
+pub fn report_path(session_root: &Path, step: usize, role: &str) -> PathBuf {
+    session_root
+        .join("review")
+        .join(format!("{}-{}.md", step + 1, role))
+}
+
+/// What the review stage knows about the session's changes.
+#[derive(Debug, Clone, Default)]
+pub struct ReviewContext<'a> {
+    /// HEAD when the session started.
+    pub base_commit: Option<&'a str>,
+    /// `git diff --stat` against the base.
+    pub diff_stat: Option<&'a str>,
+    /// Worker agent IDs and the commits they finished on.
+    pub worker_commits: Vec<(&'a str, &'a str)>,
+}
+
+/// The task for `step` of `chain`.
+pub fn review_task(
+    chain: &[String],
+    step: usize,
+    session_root: &Path,
+    context: &ReviewContext<'_>,
+) -> String {
+    let role = chain[step].as_str();
+    let report = report_path(session_root, step, role);
+    let mut task = format!(
+        "Review stage, step {} of {}: you are the session's {}. Every worker has finished.\n\n",
+        step + 1,
+        chain.len(),
+        role
+    );
+
+    match (context.base_commit, context.diff_stat) {
+        (Some(base), Some(stat)) if !stat.trim().is_empty() => task.push_str(&format!(
+            "Changes since the session started (`git diff {}`):\n\n```\n{}\n```\n\n",
+            base,
+            stat.trim_end()
+        )),
+        (Some(base), _) => task.push_str(&format!(
+            "The working tree has no changes against {}; check the worker commits.\n\n",
+            base
+        )),
+        _ => task.push_str("The session's starting commit is unknown; use the worker commits.\n\n"),
+    }
+    if !context.worker_commits.is_empty() {
+        task.push_str("Worker commits:\n");
+        for (agent_id, sha) in &context.worker_commits {
+            task.push_str(&format!("- {}: {}\n", agent_id, sha));
+        }
+        task.push('\n');
+    }
+
+    let earlier: Vec<String> = (0..step)
+        .map(|earlier| {
+            crate::workspace::paths::prompt_path(&report_path(
+                session_root,
+                earlier,
+                &chain[earlier],
+            ))
+        })
+        .collect();
+    let report = crate::workspace::paths::prompt_path(&report);
+    let instructions = match role {
+        "reviewer" => format!(
+            "Review these changes for bugs, regressions, missing tests and departures from the plan. \
+             Do not edit code. Write your findings, most severe first, to {}, and record \
+             each one in your findings file too (see Review Findings).",
+            report
+        ),
+        "resolver" if !earlier.is_empty() => format!(
+            "Fix the findings in {} and the structured ones under Review Findings, and \
+             commit the fixes. Write what you fixed, and why anything was left, to {}.",
+            earlier.join(", "),
+            report
+        ),
+        _ => format!(
+            "Carry out the {} pass over these changes and write a summary of it to {}.",
+            role, report
+        ),
+    };
+    task.push_str(&instructions);
+    task.push_str("\n\nMark your task COMPLETED when the report is written.");
+    task
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn queen() -> AgentConfig {
+        AgentConfig {
+            cli: "claude".to_string(),
+            model: Some("opus".to_string()),
+            flags: vec![],
+            label: Some("Queen".to_string()),
+            name: None,
+            description: None,
+            role: None,
+            initial_prompt: Some("Lead the hive".to_string()),
+            allowed_paths: Vec::new(),
+            denied_paths: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_review_chain_runs_each_role_once_in_order() {
+        let mut stage = ReviewStage::new(ReviewStageConfig::default(), &queen());
+        assert_eq!(stage.agent_config.cli, "claude");
+        assert_eq!(stage.agent_config.initial_prompt, None);
+        assert_eq!(stage.next_step(), Some(0));
+
+        stage.current = Some(ReviewStep {
+            step: 0,
+            worker_index: 3,
+        });
+        assert_eq!(stage.next_step(), Some(1));
+        let (config, role) = stage.worker_config(1, "Fix it".to_string());
+        assert_eq!(role.role_type, "resolver");
+        assert_eq!(config.label.as_deref(), Some("Resolver"));
+        assert_eq!(config.initial_prompt.as_deref(), Some("Fix it"));
+
+        stage.current = Some(ReviewStep {
+            step: 1,
+            worker_index: 4,
+        });
+        assert_eq!(stage.next_step(), None);
+
+        let empty: ReviewStageConfig = serde_json::from_str(r#"{ "chain": [" ", ""] }"#).unwrap();
+        assert_eq!(ReviewStage::new(empty, &queen()).next_step(), None);
+    }
+
+    #[test]
+    fn test_resolver_is_pointed_at_the_reviewers_report() {
+        let chain = default_chain();
+        let root = Path::new("/repo/.hive-manager/s1");
+        let context = ReviewContext {
+            base_commit: Some("abc123"),
+            diff_stat: Some(" src/lib.rs | 4 ++--\n 1 file changed\n"),
+            worker_commits: vec![("s1-worker-1", "def456")],
+        };
+
+        let reviewer = review_task(&chain, 0, root, &context);
+        assert!(reviewer.contains("step 1 of 2"));
+        assert!(reviewer.contains("`git diff abc123`"));
+        assert!(reviewer.contains("src/lib.rs | 4 ++--"));
+        assert!(reviewer.contains("- s1-worker-1: def456"));
+        assert!(reviewer.contains("Do not edit code"));
+        assert!(reviewer.contains("/repo/.hive-manager/s1/review/1-reviewer.md"));
+
+        let resolver = review_task(&chain, 1, root, &context);
+        assert!(
+            resolver.contains("Fix the findings in /repo/.hive-manager/s1/review/1-reviewer.md")
+        );
+        assert!(resolver.contains("/repo/.hive-manager/s1/review/2-resolver.md"));
+    }
+}
@@ -0,0 +1,77 @@
+//! Short, human-friendly session names.
+//!
+//! Every session has an `adjective-noun-nn` slug, such as `brave-otter-07`,
+//! that HTTP routes and actions accept wherever they take a session ID. The slug
+//! is derived from the session ID rather than stored, so sessions persisted by
+//! older builds have one too. Two sessions can share a slug; a slug that matches
+//! more than one session is refused and the caller must use the full ID.
+
+const ADJECTIVES: &[&str] = &[
+    "amber", "bold", "brave", "brisk", "calm", "clever", "cosmic", "crisp", "dapper", "eager",
+    "early", "fancy", "fierce", "fluffy", "frosty", "gentle", "giddy", "golden", "grand", "happy",
+    "hardy", "humble", "jolly", "keen", "kind", "lively", "lucky", "mellow", "merry", "mighty",
+    "misty", "nimble", "noble", "plucky", "polite", "proud", "quick", "quiet", "rapid", "rosy",
+    "rusty", "shiny", "silent", "silver", "sleepy", "snowy", "spry", "steady", "stormy", "sunny",
+    "swift", "tidy", "tiny", "tranquil", "trusty", "vivid", "warm", "wild", "wise", "witty",
+    "zany", "zealous", "zesty", "breezy",
+];
+
+const NOUNS: &[&str] = &[
+    "badger", "beacon", "beetle", "bison", "canyon", "cedar", "comet", "condor", "coral", "crane",
+    "delta", "dolphin", "ember", "falcon", "fern", "finch", "fjord", "forest", "fox", "galaxy",
+    "gecko", "glacier", "harbor", "hawk", "heron", "island", "jaguar", "kestrel", "koala", "lagoon",
+    "lantern", "lark", "lemur", "lotus", "lynx", "maple", "meadow", "meteor", "moose", "nebula",
+    "orchid", "osprey", "otter", "owl", "panda", "pebble", "pine", "puffin", "quartz", "raven",
+    "reef", "river", "robin", "salmon", "sparrow", "summit", "tiger", "tundra", "valley", "walrus",
+    "willow", "wombat", "yak", "zebra",
+];
+
+/// The slug for `session_id`. Always the same for the same ID.
+pub fn slug_for(session_id: &str) -> String {
+    // FNV-1a, so slugs stay stable across Rust versions.
+    let hash = session_id
+        .bytes()
+        .fold(0xcbf2_9ce4_8422_2325_u64, |hash, byte| {
+            (hash ^ u64::from(byte)).wrapping_mul(0x0100_0000_01b3)
+        });
+    let adjective = ADJECTIVES[(hash % ADJECTIVES.len() as u64) as usize];
+    let noun = NOUNS[((hash >> 16) % NOUNS.len() as u64) as usize];
+    format!("{}-{}-{:02}", adjective, noun, (hash >> 32) % 100)
+}
+
+/// Whether `value` has the shape of a slug, as opposed to a session ID.
+pub fn is_slug(value: &str) -> bool {
+    let mut parts = value.split('-');
+    let (Some(adjective), Some(noun), Some(number), None) =
+        (parts.next(), parts.next(), parts.next(), parts.next())
+    else {
+        return false;
+    };
+    let is_word = |part: &str| !part.is_empty() && part.bytes().all(|b| b.is_ascii_lowercase());
+    is_word(adjective)
+        && is_word(noun)
+        && number.len() == 2
+        && number.bytes().all(|b| b.is_ascii_digit())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_slugs_are_stable_and_distinguishable_from_ids() {
+        let id = "5f0c7a52-3c1e-4b8e-9d0a-2f6e1c9b7d44";
+        let slug = slug_for(id);
+        assert_eq!(slug, slug_for(id));
+        assert!(is_slug(&slug), "{slug}");
+        assert!(!is_slug(id));
+        for id in ["test-session", "active", "compare", "brave-otter-7", "Brave-otter-07"] {
+            assert!(!is_slug(id), "{id}");
+        }
+
+        let distinct: std::collections::HashSet<String> = (0..200)
+            .map(|_| slug_for(&uuid::Uuid::new_v4().to_string()))
+            .collect();
+        assert!(distinct.len() > 190, "{}", distinct.len());
+    }
+}
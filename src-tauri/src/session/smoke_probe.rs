@@ -0,0 +1,209 @@
+//! Plan-aware smoke test of the task-file chain, with no CLI involved.
+//!
+//! A Hive launched with `smoke_test` and `smoke_probe` starts no Queen, and each of
+//! its workers runs an idle shell instead of a CLI. The backend plays the workers:
+//! once worker N is up it marks `worker-N-task.md` IN_PROGRESS and then COMPLETED
+//! on a timer, and checks that the file watcher's `worker-completed` event got the
+//! session to spawn worker N+1 (or finish after the last). Every step is written
+//! to `smoke-report.json` in the session directory, and the pass/fail summary goes
+//! to the session's coordination log.
+
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+use serde::Serialize;
+
+use super::SessionState;
+
+/// Pause before each status flip, so each write is a separate watcher event.
+pub const STEP_DELAY: Duration = Duration::from_secs(2);
+
+/// How long a spawn or a completion may take before its step fails.
+pub const STEP_TIMEOUT: Duration = Duration::from_secs(30);
+
+pub const REPORT_FILE: &str = "smoke-report.json";
+
+const POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SmokeProbeStep {
+    pub name: String,
+    pub passed: bool,
+    pub detail: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SmokeProbeReport {
+    pub passed: bool,
+    pub steps: Vec<SmokeProbeStep>,
+}
+
+impl SmokeProbeReport {
+    /// One line for the coordination log, naming the first failed step.
+    pub fn summary(&self) -> String {
+        let passed = self.steps.iter().filter(|step| step.passed).count();
+        let mut summary = format!(
+            "Smoke probe {}: {} of {} steps passed",
+            if self.passed { "PASSED" } else { "FAILED" },
+            passed,
+            self.steps.len()
+        );
+        if let Some(failed) = self.steps.iter().find(|step| !step.passed) {
+            summary.push_str(&format!(". {} failed: {}", failed.name, failed.detail));
+        }
+        summary
+    }
+}
+
+/// What a probe worker runs instead of a CLI: a shell that idles until killed.
+pub fn idle_command() -> (String, Vec<String>) {
+    let shell = if cfg!(windows) { "cmd" } else { "sh" };
+    (shell.to_string(), Vec::new())
+}
+
+/// Rewrite the `## Status:` line of the task file at `path` to `status`.
+pub fn set_task_status(path: &Path, status: &str) -> io::Result<()> {
+    let content = fs::read_to_string(path)?;
+    let mut replaced = false;
+    let mut rewritten = String::with_capacity(content.len());
+    for line in content.lines() {
+        if !replaced && line.starts_with("## Status:") {
+            rewritten.push_str(&format!("## Status: {}", status));
+            replaced = true;
+        } else {
+            rewritten.push_str(line);
+        }
+        rewritten.push('\n');
+    }
+    if !replaced {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("{} has no status line", path.display()),
+        ));
+    }
+    fs::write(path, rewritten)
+}
+
+/// Play `workers` sequential workers. `task_file(n)` is worker `n`'s task file and
+/// `state()` the session's state, `None` once the session is gone. Stops at the
+/// first failed step, since the ones after it cannot happen.
+pub fn run(
+    workers: u8,
+    task_file: impl Fn(u8) -> PathBuf,
+    state: impl Fn() -> Option<SessionState>,
+    step_delay: Duration,
+    step_timeout: Duration,
+) -> SmokeProbeReport {
+    let mut steps = Vec::new();
+    let mut step = |name: String, result: Result<String, String>| {
+        let passed = result.is_ok();
+        steps.push(SmokeProbeStep {
+            name,
+            passed,
+            detail: result.unwrap_or_else(|error| error),
+        });
+        passed
+    };
+
+    for index in 1..=workers {
+        let waiting = SessionState::WaitingForWorker(index);
+        let spawned = wait_for(step_timeout, || state().as_ref() == Some(&waiting))
+            .map(|elapsed| format!("waiting for it after {}ms", elapsed.as_millis()))
+            .ok_or_else(|| match state() {
+                Some(state) => format!("the session stayed {:?}", state),
+                None => "the session is gone".to_string(),
+            });
+        if !step(format!("Worker {} spawned", index), spawned) {
+            break;
+        }
+
+        let path = task_file(index);
+        let flipped = ["IN_PROGRESS", "COMPLETED"]
+            .iter()
+            .try_for_each(|status| {
+                std::thread::sleep(step_delay);
+                set_task_status(&path, status)
+            })
+            .map(|()| format!("{} is COMPLETED", path.display()))
+            .map_err(|e| e.to_string());
+        if !step(format!("Worker {} task file flipped", index), flipped) {
+            break;
+        }
+
+        let advanced = wait_for(step_timeout, || state().as_ref() != Some(&waiting))
+            .map(|elapsed| format!("the session moved on after {}ms", elapsed.as_millis()))
+            .ok_or_else(|| "no worker-completed event moved the session on".to_string());
+        if !step(format!("Worker {} completion picked up", index), advanced) {
+            break;
+        }
+    }
+
+    let passed = steps.len() == usize::from(workers) * 3 && steps.iter().all(|step| step.passed);
+    SmokeProbeReport { passed, steps }
+}
+
+/// Poll `done` until it holds, returning how long that took, or `None` on timeout.
+fn wait_for(timeout: Duration, done: impl Fn() -> bool) -> Option<Duration> {
+    let started = Instant::now();
+    loop {
+        if done() {
+            return Some(started.elapsed());
+        }
+        if started.elapsed() >= timeout {
+            return None;
+        }
+        std::thread::sleep(POLL_INTERVAL);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn task_file(dir: &Path, index: u8) -> PathBuf {
+        dir.join(format!("worker-{}-task.md", index))
+    }
+
+    #[test]
+    fn test_probe_passes_when_each_completion_spawns_the_next_worker() {
+        let temp = tempfile::tempdir().unwrap();
+        for index in 1..=2 {
+            let content = format!("# Task Assignment - Worker {}\n\n## Status: ACTIVE\n", index);
+            fs::write(task_file(temp.path(), index), content).unwrap();
+        }
+        // Stands in for watcher -> worker-completed -> spawn_next_worker.
+        let state = || {
+            let next = (1..=2).find(|&index| {
+                let content = fs::read_to_string(task_file(temp.path(), index)).unwrap();
+                !content.contains("Status: COMPLETED")
+            });
+            Some(next.map_or(SessionState::Running, SessionState::WaitingForWorker))
+        };
+
+        let report = run(
+            2,
+            |index| task_file(temp.path(), index),
+            state,
+            Duration::ZERO,
+            Duration::from_secs(1),
+        );
+        assert!(report.passed, "{}", report.summary());
+        assert_eq!(report.steps.len(), 6);
+        let content = fs::read_to_string(task_file(temp.path(), 2)).unwrap();
+        assert!(content.contains("## Status: COMPLETED\n"));
+        assert!(report.summary().starts_with("Smoke probe PASSED: 6 of 6"));
+
+        let stuck = run(
+            1,
+            |index| task_file(temp.path(), index),
+            || Some(SessionState::SpawningWorker(1)),
+            Duration::ZERO,
+            Duration::from_millis(200),
+        );
+        assert!(!stuck.passed);
+        assert_eq!(stuck.steps.len(), 1);
+        assert!(stuck.summary().contains("Worker 1 spawned failed"), "{}", stuck.summary());
+    }
+}
@@ -0,0 +1,196 @@
+//! Periodic snapshots of what the orchestrator believes about a session.
+//!
+//! Every `snapshots.interval_secs`, each running session's in-memory entry, the
+//! status of every worker's task file and the agents' last heartbeats are written
+//! to `snapshots/` in the session's storage directory, unless nothing changed
+//! since the previous snapshot. The newest `snapshots.keep` are kept. Loading a
+//! snapshot shows what Hive Manager believed at that moment, for example why a
+//! sequential spawn never fired; the live session is never touched.
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use chrono::{DateTime, NaiveDateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use super::controller::AgentHeartbeatInfo;
+
+pub const SNAPSHOTS_DIR: &str = "snapshots";
+
+/// Snapshot IDs are their capture time, so they sort in capture order.
+const ID_FORMAT: &str = "%Y%m%dT%H%M%S%.3fZ";
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SessionSnapshot {
+    pub id: String,
+    pub session_id: String,
+    pub taken_at: DateTime<Utc>,
+    /// The session as it was held in memory.
+    pub session: Value,
+    /// Worker agent ID to the status line of its task file, `UNKNOWN` when unreadable.
+    pub task_statuses: BTreeMap<String, String>,
+    pub heartbeats: BTreeMap<String, AgentHeartbeatInfo>,
+}
+
+impl SessionSnapshot {
+    pub fn new(
+        session_id: &str,
+        taken_at: DateTime<Utc>,
+        session: Value,
+        task_statuses: BTreeMap<String, String>,
+        heartbeats: BTreeMap<String, AgentHeartbeatInfo>,
+    ) -> Self {
+        Self {
+            id: taken_at.format(ID_FORMAT).to_string(),
+            session_id: session_id.to_string(),
+            taken_at,
+            session,
+            task_statuses,
+            heartbeats,
+        }
+    }
+
+    /// Whether `other` records the same state, whenever it was taken.
+    fn same_state(&self, other: &Self) -> bool {
+        self.session == other.session
+            && self.task_statuses == other.task_statuses
+            && self.heartbeats == other.heartbeats
+    }
+}
+
+fn snapshot_path(dir: &Path, id: &str) -> PathBuf {
+    dir.join(format!("{}.json", id))
+}
+
+/// IDs of the snapshots in `dir`, oldest first.
+pub fn list(dir: &Path) -> io::Result<Vec<String>> {
+    let entries = match fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(e),
+    };
+    let mut ids: Vec<String> = entries
+        .filter_map(|entry| {
+            let name = entry.ok()?.file_name().to_string_lossy().to_string();
+            let id = name.strip_suffix(".json")?;
+            parse_id(id).map(|_| id.to_string())
+        })
+        .collect();
+    ids.sort();
+    Ok(ids)
+}
+
+pub fn read(dir: &Path, id: &str) -> io::Result<Option<SessionSnapshot>> {
+    if parse_id(id).is_none() {
+        return Ok(None);
+    }
+    match fs::read_to_string(snapshot_path(dir, id)) {
+        Ok(json) => serde_json::from_str(&json)
+            .map(Some)
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e)),
+        Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(None),
+        Err(e) => Err(e),
+    }
+}
+
+/// The newest snapshot taken at or before `at`, a snapshot ID or an RFC 3339 time.
+pub fn read_at(dir: &Path, at: &str) -> io::Result<Option<SessionSnapshot>> {
+    let Some(at) = parse_id(at).or_else(|| {
+        DateTime::parse_from_rfc3339(at)
+            .ok()
+            .map(|at| at.with_timezone(&Utc))
+    }) else {
+        return Ok(None);
+    };
+    let id = list(dir)?
+        .into_iter()
+        .rev()
+        .find(|id| parse_id(id).is_some_and(|taken_at| taken_at <= at));
+    match id {
+        Some(id) => read(dir, &id),
+        None => Ok(None),
+    }
+}
+
+/// Write `snapshot` unless the latest one in `dir` holds the same state, then
+/// drop the oldest beyond `keep`. Returns whether it was written.
+pub fn record(dir: &Path, snapshot: &SessionSnapshot, keep: usize) -> io::Result<bool> {
+    let mut ids = list(dir)?;
+    if let Some(latest) = ids.last() {
+        if read(dir, latest)?.is_some_and(|latest| latest.same_state(snapshot)) {
+            return Ok(false);
+        }
+    }
+    fs::create_dir_all(dir)?;
+    let json = serde_json::to_string_pretty(snapshot)
+        .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+    fs::write(snapshot_path(dir, &snapshot.id), json)?;
+
+    ids.push(snapshot.id.clone());
+    ids.sort();
+    ids.dedup();
+    let excess = ids.len().saturating_sub(keep.max(1));
+    for id in &ids[..excess] {
+        fs::remove_file(snapshot_path(dir, id))?;
+    }
+    Ok(true)
+}
+
+fn parse_id(id: &str) -> Option<DateTime<Utc>> {
+    NaiveDateTime::parse_from_str(id, ID_FORMAT)
+        .ok()
+        .map(|naive| naive.and_utc())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn snapshot(minute: u32, status: &str) -> SessionSnapshot {
+        let taken_at = Utc.with_ymd_and_hms(2026, 3, 1, 12, minute, 0).unwrap();
+        let task_statuses = BTreeMap::from([("s-worker-1".to_string(), status.to_string())]);
+        let heartbeats = BTreeMap::from([(
+            "s-worker-1".to_string(),
+            AgentHeartbeatInfo {
+                last_activity: taken_at,
+                status: "working".to_string(),
+                summary: None,
+            },
+        )]);
+        SessionSnapshot::new(
+            "s",
+            taken_at,
+            serde_json::json!({ "id": "s", "state": "Running" }),
+            task_statuses,
+            heartbeats,
+        )
+    }
+
+    #[test]
+    fn test_snapshots_skip_unchanged_state_prune_and_load_by_time() {
+        let dir = tempfile::tempdir().unwrap();
+        let dir = dir.path().join(SNAPSHOTS_DIR);
+        assert!(list(&dir).unwrap().is_empty());
+
+        assert!(record(&dir, &snapshot(0, "ACTIVE"), 2).unwrap());
+        let mut unchanged = snapshot(1, "ACTIVE");
+        unchanged.heartbeats = snapshot(0, "ACTIVE").heartbeats;
+        assert!(!record(&dir, &unchanged, 2).unwrap());
+        assert!(record(&dir, &snapshot(2, "COMPLETED"), 2).unwrap());
+        assert!(record(&dir, &snapshot(4, "FAILED"), 2).unwrap());
+
+        let ids = list(&dir).unwrap();
+        assert_eq!(ids, vec![snapshot(2, "").id, snapshot(4, "").id]);
+
+        let at = read_at(&dir, "2026-03-01T12:03:30Z").unwrap().unwrap();
+        assert_eq!(at, snapshot(2, "COMPLETED"));
+        assert_eq!(read_at(&dir, &ids[1]).unwrap().unwrap().id, ids[1]);
+        assert_eq!(read_at(&dir, "2026-03-01T12:00:00Z").unwrap(), None);
+        assert_eq!(read_at(&dir, "not a time").unwrap(), None);
+        assert_eq!(read(&dir, "../secrets").unwrap(), None);
+    }
+}
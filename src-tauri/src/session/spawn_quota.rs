@@ -0,0 +1,156 @@
+//! Caps on the workers a session may run at once.
+//!
+//! A launch config can carry a [`SpawnQuota`]: a cap on all live workers and caps
+//! per `role_type`, so a Queen cannot spawn twenty reviewers. `POST
+//! /api/sessions/{id}/workers` answers 409 with the limit when a new worker would
+//! go over, and the operator can replace the quota of a running session with
+//! `session.override_spawn_quota`. Finished, crashed and failed workers do not
+//! count. The quota is persisted with the session, and the live count is rebuilt
+//! from its agents, so neither a restart nor a resume lifts it.
+
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+use std::sync::Arc;
+
+use parking_lot::Mutex;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash, Serialize, Deserialize, JsonSchema)]
+pub struct SpawnQuota {
+    /// Most live workers in the session, counting those spawned at launch.
+    #[serde(default)]
+    pub max_workers: Option<u8>,
+    /// Most live workers per role type, such as `{"reviewer": 2}`. Role types
+    /// are compared case-insensitively.
+    #[serde(default)]
+    pub per_role: BTreeMap<String, u8>,
+}
+
+/// A worker the quota has no room for.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct QuotaExceeded {
+    /// The role whose cap was hit; `None` for the session-wide cap.
+    pub role_type: Option<String>,
+    pub limit: u8,
+    pub live: usize,
+}
+
+impl fmt::Display for QuotaExceeded {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.role_type {
+            Some(role_type) => write!(
+                f,
+                "Spawn quota reached: {} of {} allowed {} workers are live",
+                self.live, self.limit, role_type
+            )?,
+            None => write!(
+                f,
+                "Spawn quota reached: {} of {} allowed workers are live",
+                self.live, self.limit
+            )?,
+        }
+        write!(f, ". Reuse a worker that is on STANDBY, or ask the operator to raise the quota.")
+    }
+}
+
+impl SpawnQuota {
+    pub fn is_empty(&self) -> bool {
+        self.max_workers.is_none() && self.per_role.is_empty()
+    }
+
+    /// Whether one more `role_type` worker fits next to the live workers, given by
+    /// their role types.
+    pub fn check(&self, role_type: &str, live_roles: &[String]) -> Result<(), QuotaExceeded> {
+        if let Some(limit) = self.max_workers {
+            if live_roles.len() >= usize::from(limit) {
+                return Err(QuotaExceeded {
+                    role_type: None,
+                    limit,
+                    live: live_roles.len(),
+                });
+            }
+        }
+        let role_limit = self
+            .per_role
+            .iter()
+            .find(|(role, _)| role.eq_ignore_ascii_case(role_type));
+        if let Some((role, &limit)) = role_limit {
+            let live = live_roles
+                .iter()
+                .filter(|live_role| live_role.eq_ignore_ascii_case(role))
+                .count();
+            if live >= usize::from(limit) {
+                return Err(QuotaExceeded {
+                    role_type: Some(role.clone()),
+                    limit,
+                    live,
+                });
+            }
+        }
+        Ok(())
+    }
+}
+
+/// session_id -> role types of the workers holding a [`SpawnReservation`].
+pub type SpawnReservations = Arc<Mutex<HashMap<String, Vec<String>>>>;
+
+/// Room in a session's quota held for a worker between the quota check and its
+/// spawn. Dropping it frees the room, by which time the spawned worker counts as
+/// live itself.
+pub struct SpawnReservation {
+    reservations: SpawnReservations,
+    session_id: String,
+    role_type: String,
+}
+
+impl SpawnReservation {
+    pub(crate) fn new(reservations: SpawnReservations, session_id: &str, role_type: &str) -> Self {
+        Self {
+            reservations,
+            session_id: session_id.to_string(),
+            role_type: role_type.to_string(),
+        }
+    }
+}
+
+impl Drop for SpawnReservation {
+    fn drop(&mut self) {
+        let mut reservations = self.reservations.lock();
+        let Some(roles) = reservations.get_mut(&self.session_id) else {
+            return;
+        };
+        if let Some(index) = roles.iter().position(|role| role == &self.role_type) {
+            roles.remove(index);
+        }
+        if roles.is_empty() {
+            reservations.remove(&self.session_id);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_quota_caps_each_role_and_the_total() {
+        let quota = SpawnQuota {
+            max_workers: Some(3),
+            per_role: BTreeMap::from([("reviewer".to_string(), 1)]),
+        };
+        let live = vec!["backend".to_string(), "Reviewer".to_string()];
+
+        assert_eq!(quota.check("frontend", &live), Ok(()));
+        let exceeded = quota.check("REVIEWER", &live).unwrap_err();
+        assert_eq!(exceeded.role_type.as_deref(), Some("reviewer"));
+        assert_eq!((exceeded.limit, exceeded.live), (1, 1));
+        assert!(exceeded.to_string().contains("1 of 1 allowed reviewer"), "{exceeded}");
+
+        let live = vec!["backend".to_string(); 3];
+        let exceeded = quota.check("frontend", &live).unwrap_err();
+        assert_eq!(exceeded.role_type, None);
+        assert!(SpawnQuota::default().is_empty());
+        assert_eq!(SpawnQuota::default().check("reviewer", &live), Ok(()));
+    }
+}
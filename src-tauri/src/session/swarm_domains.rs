@@ -0,0 +1,154 @@
+//! Ordering Swarm planners by the cross-domain dependencies in the plan.
+//!
+//! The Master Planner lists each domain under a `### Domain N: Name` heading and
+//! its dependencies as "Domain 2 depends on Domain 1" (see
+//! [`PlanDomain`](super::plan_parser::PlanDomain)). Planners for independent
+//! domains may run side by side, but one whose domain still waits on another is
+//! refused. When a planner is marked completed, each domain whose last
+//! outstanding prerequisite that was becomes startable and is announced with a
+//! `domain-unblocked` event.
+
+use std::collections::{BTreeSet, HashMap};
+
+use serde::Serialize;
+
+use super::context_pack::mentions;
+use super::plan_parser::PlanDomain;
+
+/// `domain-unblocked` payload.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct DomainUnblocked {
+    pub session_id: String,
+    pub domain: usize,
+    pub name: String,
+    /// The domain whose completion released it.
+    pub completed: String,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct DomainSchedule {
+    domains: Vec<PlanDomain>,
+    /// Planner index to the domain it was spawned for.
+    planners: HashMap<u8, usize>,
+    completed: BTreeSet<usize>,
+}
+
+impl DomainSchedule {
+    pub fn new(domains: Vec<PlanDomain>) -> Self {
+        Self {
+            domains,
+            ..Self::default()
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.domains.is_empty()
+    }
+
+    fn domain(&self, number: usize) -> Option<&PlanDomain> {
+        self.domains.iter().find(|domain| domain.number == number)
+    }
+
+    pub fn name(&self, number: usize) -> String {
+        self.domain(number)
+            .map(|domain| domain.name.clone())
+            .filter(|name| !name.is_empty())
+            .unwrap_or_else(|| format!("Domain {number}"))
+    }
+
+    /// The domain a planner spawned for `requested` works on: the one whose name
+    /// it mentions (or that mentions it), else the domain numbered like the
+    /// planner.
+    pub fn resolve(&self, requested: &str, planner_index: u8) -> Option<usize> {
+        let requested = requested.trim();
+        self.domains
+            .iter()
+            .find(|domain| {
+                !requested.is_empty()
+                    && !domain.name.is_empty()
+                    && (mentions(requested, std::slice::from_ref(&domain.name))
+                        || mentions(&domain.name, &[requested.to_string()]))
+            })
+            .or_else(|| self.domain(planner_index as usize))
+            .map(|domain| domain.number)
+    }
+
+    /// Prerequisites of `domain` that are not complete yet.
+    pub fn blockers(&self, domain: usize) -> Vec<usize> {
+        self.domain(domain)
+            .map(|domain| {
+                domain
+                    .depends_on
+                    .iter()
+                    .copied()
+                    .filter(|prerequisite| !self.completed.contains(prerequisite))
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    pub fn assign(&mut self, planner_index: u8, domain: usize) {
+        self.planners.insert(planner_index, domain);
+    }
+
+    /// Mark the domain `planner_index` works on complete. Returns that domain and
+    /// the not yet started domains it was the last prerequisite of.
+    pub fn complete_planner(&mut self, planner_index: u8) -> Option<(usize, Vec<usize>)> {
+        let domain = *self.planners.get(&planner_index)?;
+        if !self.completed.insert(domain) {
+            return None;
+        }
+        let started: BTreeSet<usize> = self.planners.values().copied().collect();
+        let unblocked = self
+            .domains
+            .iter()
+            .filter(|candidate| {
+                candidate.depends_on.contains(&domain)
+                    && !started.contains(&candidate.number)
+                    && !self.completed.contains(&candidate.number)
+            })
+            .map(|candidate| candidate.number)
+            .filter(|candidate| self.blockers(*candidate).is_empty())
+            .collect();
+        Some((domain, unblocked))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn domain(number: usize, name: &str, depends_on: &[usize]) -> PlanDomain {
+        PlanDomain {
+            number,
+            name: name.to_string(),
+            depends_on: depends_on.to_vec(),
+        }
+    }
+
+    #[test]
+    fn test_dependent_domains_wait_until_their_last_prerequisite_completes() {
+        let mut schedule = DomainSchedule::new(vec![
+            domain(1, "Backend API", &[]),
+            domain(2, "Database", &[]),
+            domain(3, "Frontend", &[1, 2]),
+        ]);
+        assert_eq!(schedule.resolve("backend", 7), Some(1));
+        assert_eq!(schedule.resolve("Frontend UI", 1), Some(3));
+        assert_eq!(schedule.resolve("testing", 2), Some(2));
+        assert_eq!(schedule.resolve("testing", 9), None);
+
+        assert!(schedule.blockers(1).is_empty());
+        assert_eq!(schedule.blockers(3), vec![1, 2]);
+
+        schedule.assign(1, 1);
+        schedule.assign(2, 2);
+        assert_eq!(schedule.complete_planner(1), Some((1, vec![])));
+        assert_eq!(schedule.blockers(3), vec![2]);
+        assert_eq!(schedule.complete_planner(2), Some((2, vec![3])));
+        assert!(schedule.blockers(3).is_empty());
+        assert_eq!(schedule.complete_planner(2), None);
+        assert_eq!(schedule.complete_planner(5), None);
+        assert_eq!(schedule.name(3), "Frontend");
+    }
+}
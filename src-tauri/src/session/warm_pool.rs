@@ -0,0 +1,160 @@
+//! Idle CLI processes started ahead of the workers that will use them.
+//!
+//! Starting a CLI (login, model load) takes 10-30s, which sequential spawns pay
+//! once per worker. With `warm_pool.enabled`, each running session whose workers
+//! share one directory (a shared-cell Hive or a Research session) keeps `size`
+//! idle processes per configured CLI and model started there, without a prompt.
+//! `add_worker` takes one whose command line matches the worker's instead of
+//! starting a process, and pastes the role prompt into it. Sessions that give
+//! each worker its own worktree are not warmed: the directory does not exist
+//! before the spawn.
+
+use std::collections::HashMap;
+
+use crate::pty::WARM_PTY_PREFIX;
+
+/// What a warm process was started as: its session's worker directory and the
+/// command line without the prompt.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct WarmKey {
+    pub session_id: String,
+    pub command: String,
+    pub args: Vec<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WarmProcess {
+    pub pty_id: String,
+    pub key: WarmKey,
+}
+
+#[derive(Debug, Default)]
+pub struct WarmPool {
+    /// Oldest first, so the longest-started process is handed over first.
+    idle: Vec<WarmProcess>,
+    launched: u64,
+}
+
+impl WarmPool {
+    /// The PTY ID for the next process started for the pool.
+    pub fn next_pty_id(&mut self) -> String {
+        self.launched += 1;
+        format!("{}{}", WARM_PTY_PREFIX, self.launched)
+    }
+
+    pub fn add(&mut self, process: WarmProcess) {
+        self.idle.push(process);
+    }
+
+    /// Hand over the oldest process started as `key` that `ready` accepts.
+    pub fn take(&mut self, key: &WarmKey, ready: impl Fn(&str) -> bool) -> Option<WarmProcess> {
+        let position = self
+            .idle
+            .iter()
+            .position(|process| &process.key == key && ready(&process.pty_id))?;
+        Some(self.idle.remove(position))
+    }
+
+    /// Drop and return the processes to kill: those `alive` rejects, those no
+    /// `wanted` key asks for, and the newest beyond a key's size.
+    pub fn trim(
+        &mut self,
+        wanted: &[(WarmKey, usize)],
+        alive: impl Fn(&str) -> bool,
+    ) -> Vec<WarmProcess> {
+        let mut room: HashMap<&WarmKey, usize> =
+            wanted.iter().map(|(key, size)| (key, *size)).collect();
+        let mut dropped = Vec::new();
+        self.idle.retain(|process| {
+            let keep = alive(&process.pty_id)
+                && room.get_mut(&process.key).is_some_and(|room| {
+                    let fits = *room > 0;
+                    *room = room.saturating_sub(1);
+                    fits
+                });
+            if !keep {
+                dropped.push(process.clone());
+            }
+            keep
+        });
+        dropped
+    }
+
+    /// How many more processes each `wanted` key needs.
+    pub fn shortfall(&self, wanted: &[(WarmKey, usize)]) -> Vec<(WarmKey, usize)> {
+        wanted
+            .iter()
+            .filter_map(|(key, size)| {
+                let idle = self.idle.iter().filter(|process| &process.key == key).count();
+                let missing = size.saturating_sub(idle);
+                (missing > 0).then(|| (key.clone(), missing))
+            })
+            .collect()
+    }
+
+    /// Drop and return every process kept for `session_id`.
+    pub fn release_session(&mut self, session_id: &str) -> Vec<WarmProcess> {
+        let (released, kept) = self
+            .idle
+            .drain(..)
+            .partition(|process| process.key.session_id == session_id);
+        self.idle = kept;
+        released
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn key(session_id: &str, model: &str) -> WarmKey {
+        WarmKey {
+            session_id: session_id.to_string(),
+            command: "claude".to_string(),
+            args: vec!["--model".to_string(), model.to_string()],
+        }
+    }
+
+    fn launch(pool: &mut WarmPool, key: &WarmKey) -> String {
+        let pty_id = pool.next_pty_id();
+        pool.add(WarmProcess {
+            pty_id: pty_id.clone(),
+            key: key.clone(),
+        });
+        pty_id
+    }
+
+    #[test]
+    fn test_pool_refills_to_size_hands_over_ready_processes_and_trims() {
+        let mut pool = WarmPool::default();
+        let (opus, haiku) = (key("s", "opus"), key("s", "haiku"));
+        let wanted = vec![(opus.clone(), 2), (haiku.clone(), 1)];
+        assert_eq!(pool.shortfall(&wanted), wanted);
+
+        let first = launch(&mut pool, &opus);
+        let second = launch(&mut pool, &opus);
+        assert!(first.starts_with(WARM_PTY_PREFIX) && first != second);
+        assert_eq!(pool.shortfall(&wanted), vec![(haiku.clone(), 1)]);
+
+        assert_eq!(pool.take(&haiku, |_| true), None);
+        assert_eq!(pool.take(&opus, |id| id != first).unwrap().pty_id, second);
+        assert_eq!(pool.take(&opus, |_| true).unwrap().pty_id, first);
+
+        let kept = launch(&mut pool, &opus);
+        let surplus = launch(&mut pool, &opus);
+        let dead = launch(&mut pool, &opus);
+        let unwanted = launch(&mut pool, &key("closed", "opus"));
+        let dropped: Vec<String> = pool
+            .trim(&[(opus.clone(), 2)], |id| id != dead)
+            .into_iter()
+            .map(|process| process.pty_id)
+            .collect();
+        assert_eq!(dropped, vec![dead, unwanted]);
+        assert_eq!(pool.trim(&[(opus.clone(), 1)], |_| true)[0].pty_id, surplus);
+
+        let released = pool.release_session("s");
+        assert_eq!(released.len(), 1);
+        assert_eq!(released[0].pty_id, kept);
+        assert_eq!(pool.take(&opus, |_| true), None);
+    }
+}
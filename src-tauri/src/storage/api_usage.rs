@@ -0,0 +1,149 @@
+//! Per-session log of the HTTP API calls agents make.
+//!
+//! Every call to `/api/sessions/{id}/...` that does not come from the app's own
+//! UI is appended to `sessions/{id}/api-usage.jsonl`: the route, the agent named
+//! by its `X-Hive-Agent-Id` header, the status and the latency. [`summarize`]
+//! folds the log into per-agent counts, so an operator can see which workers use
+//! the coordination endpoints and which ignore them.
+
+use std::collections::BTreeMap;
+use std::fs::{self, OpenOptions};
+use std::io::{self, Write};
+use std::path::Path;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+pub const API_USAGE_FILE: &str = "api-usage.jsonl";
+
+/// Calls without an agent header are counted under this name.
+pub const UNIDENTIFIED: &str = "(unidentified)";
+
+/// One line of `api-usage.jsonl`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ApiCall {
+    pub timestamp: DateTime<Utc>,
+    pub method: String,
+    /// The matched route, e.g. `/api/sessions/{id}/heartbeat`.
+    pub endpoint: String,
+    pub agent_id: Option<String>,
+    /// Whether the call carried the session's API token.
+    pub token_presented: bool,
+    pub status: u16,
+    pub latency_ms: u64,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct AgentApiUsage {
+    pub calls: u64,
+    /// Calls answered with a 4xx or 5xx status.
+    pub errors: u64,
+    pub avg_latency_ms: f64,
+    /// Calls per `METHOD endpoint`.
+    pub endpoints: BTreeMap<String, u64>,
+    pub last_call_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ApiUsageSummary {
+    pub total_calls: u64,
+    /// Calls per `METHOD endpoint` across all agents.
+    pub by_endpoint: BTreeMap<String, u64>,
+    /// Usage per agent ID; calls without one are under [`UNIDENTIFIED`].
+    pub agents: BTreeMap<String, AgentApiUsage>,
+}
+
+pub fn append(log_path: &Path, call: &ApiCall) -> io::Result<()> {
+    let mut line = serde_json::to_string(call).map_err(io::Error::other)?;
+    line.push('\n');
+    OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(log_path)?
+        .write_all(line.as_bytes())
+}
+
+/// Summary of the log at `log_path`; empty when there is no log yet. Lines that
+/// do not parse are skipped.
+pub fn summarize(log_path: &Path) -> io::Result<ApiUsageSummary> {
+    let content = match fs::read_to_string(log_path) {
+        Ok(content) => content,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(ApiUsageSummary::default()),
+        Err(e) => return Err(e),
+    };
+
+    let mut summary = ApiUsageSummary::default();
+    let mut total_latency: BTreeMap<String, u64> = BTreeMap::new();
+    for call in content
+        .lines()
+        .filter_map(|line| serde_json::from_str::<ApiCall>(line).ok())
+    {
+        let endpoint = format!("{} {}", call.method, call.endpoint);
+        let agent_id = call
+            .agent_id
+            .clone()
+            .unwrap_or_else(|| UNIDENTIFIED.to_string());
+        summary.total_calls += 1;
+        *summary.by_endpoint.entry(endpoint.clone()).or_default() += 1;
+        *total_latency.entry(agent_id.clone()).or_default() += call.latency_ms;
+
+        let usage = summary.agents.entry(agent_id).or_default();
+        usage.calls += 1;
+        if call.status >= 400 {
+            usage.errors += 1;
+        }
+        *usage.endpoints.entry(endpoint).or_default() += 1;
+        usage.last_call_at = usage.last_call_at.max(Some(call.timestamp));
+    }
+    for (agent_id, usage) in summary.agents.iter_mut() {
+        usage.avg_latency_ms = total_latency[agent_id] as f64 / usage.calls as f64;
+    }
+    Ok(summary)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn call(agent_id: Option<&str>, endpoint: &str, status: u16, latency_ms: u64) -> ApiCall {
+        ApiCall {
+            timestamp: Utc::now(),
+            method: "POST".to_string(),
+            endpoint: endpoint.to_string(),
+            agent_id: agent_id.map(String::from),
+            token_presented: true,
+            status,
+            latency_ms,
+        }
+    }
+
+    #[test]
+    fn test_summary_counts_calls_per_agent_and_endpoint() {
+        let dir = tempfile::tempdir().unwrap();
+        let log = dir.path().join(API_USAGE_FILE);
+        assert_eq!(summarize(&log).unwrap(), ApiUsageSummary::default());
+
+        let heartbeat = "/api/sessions/{id}/heartbeat";
+        append(&log, &call(Some("s1-worker-1"), heartbeat, 200, 10)).unwrap();
+        append(&log, &call(Some("s1-worker-1"), heartbeat, 500, 30)).unwrap();
+        append(&log, &call(None, "/api/sessions/{id}/learnings", 201, 5)).unwrap();
+        fs::write(
+            &log,
+            fs::read_to_string(&log).unwrap() + "not json\n",
+        )
+        .unwrap();
+
+        let summary = summarize(&log).unwrap();
+        assert_eq!(summary.total_calls, 3);
+        assert_eq!(
+            summary.by_endpoint["POST /api/sessions/{id}/heartbeat"],
+            2
+        );
+        let worker = &summary.agents["s1-worker-1"];
+        assert_eq!(worker.calls, 2);
+        assert_eq!(worker.errors, 1);
+        assert_eq!(worker.avg_latency_ms, 20.0);
+        assert!(worker.last_call_at.is_some());
+        assert_eq!(summary.agents[UNIDENTIFIED].calls, 1);
+    }
+}
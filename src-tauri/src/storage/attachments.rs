@@ -0,0 +1,236 @@
+//! Files that coordination messages point at: diffs, screenshots, log excerpts.
+//!
+//! An agent uploads a file into the session's `coordination/attachments/` and gets
+//! back an ID of the form `<8 hex digits>-<file name>`. A message refers to it with
+//! an `[ATTACH <id>]` token in its content. The token is kept in the
+//! `coordination.log` line as is, so reading the log back lists each message's
+//! references in `CoordinationMessage::attachments`, and the agent on the other end
+//! fetches the file by ID.
+
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+
+/// Directory under the session's `coordination/` directory.
+pub const ATTACHMENTS_DIR: &str = "attachments";
+
+/// Largest file an agent may attach.
+pub const MAX_ATTACHMENT_BYTES: usize = 10 * 1024 * 1024;
+
+const MAX_NAME_CHARS: usize = 80;
+
+/// Name a message too long for its log line is saved under (see [`spill_over`]).
+const SPILLOVER_NAME: &str = "message.txt";
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Attachment {
+    pub id: String,
+    /// The uploaded file name, reduced to characters safe in a path.
+    pub name: String,
+    pub size: u64,
+    pub uploaded_at: DateTime<Utc>,
+    /// The `[ATTACH <id>]` token to put in a message.
+    pub reference: String,
+    pub path: PathBuf,
+}
+
+pub fn reference(id: &str) -> String {
+    format!("[ATTACH {}]", id)
+}
+
+/// IDs of the `[ATTACH <id>]` tokens in `content`, in order and without repeats.
+pub fn references(content: &str) -> Vec<String> {
+    let mut ids: Vec<String> = Vec::new();
+    let mut rest = content;
+    while let Some(start) = rest.find("[ATTACH ") {
+        rest = &rest[start + "[ATTACH ".len()..];
+        let Some(end) = rest.find(']') else {
+            break;
+        };
+        let id = &rest[..end];
+        if is_valid_id(id) && !ids.iter().any(|known| known == id) {
+            ids.push(id.to_string());
+        }
+        rest = &rest[end + 1..];
+    }
+    ids
+}
+
+/// `content` with a token appended for each of `ids` it does not already mention.
+pub fn with_references(content: &str, ids: &[String]) -> String {
+    let present = references(content);
+    let mut content = content.to_string();
+    for id in ids.iter().filter(|id| !present.contains(id)) {
+        content.push(' ');
+        content.push_str(&reference(id));
+    }
+    content
+}
+
+/// Store `bytes` in `dir` under a new ID derived from `file_name`.
+pub fn save(dir: &Path, file_name: &str, bytes: &[u8]) -> io::Result<Attachment> {
+    fs::create_dir_all(dir)?;
+    let name = sanitize_name(file_name);
+    let id = format!("{}-{}", &uuid::Uuid::new_v4().simple().to_string()[..8], name);
+    let path = dir.join(&id);
+    fs::write(&path, bytes)?;
+    Ok(Attachment {
+        reference: reference(&id),
+        id,
+        name,
+        size: bytes.len() as u64,
+        uploaded_at: Utc::now(),
+        path,
+    })
+}
+
+/// `content` as it should go into a log line limited to `max_chars` characters.
+/// Longer content is saved whole as an attachment in `dir`, and the line keeps its
+/// start followed by the attachment's path and reference. A limit of 0 keeps every
+/// message whole.
+pub fn spill_over(dir: &Path, content: &str, max_chars: usize) -> io::Result<String> {
+    if max_chars == 0 || content.chars().count() <= max_chars {
+        return Ok(content.to_string());
+    }
+    let saved = save(dir, SPILLOVER_NAME, content.as_bytes())?;
+    let start: String = content.chars().take(max_chars).collect();
+    // References past the cut still have to reach the reader.
+    let mut ids = references(content);
+    ids.push(saved.id);
+    Ok(with_references(
+        &format!("{}… [full message: {}]", start.trim_end(), saved.path.display()),
+        &ids,
+    ))
+}
+
+/// Where attachment `id` lives in `dir`, or `None` for an ID that could not have
+/// been handed out or a file that is gone.
+pub fn find(dir: &Path, id: &str) -> Option<PathBuf> {
+    if !is_valid_id(id) {
+        return None;
+    }
+    let path = dir.join(id);
+    path.is_file().then_some(path)
+}
+
+/// Attachments in `dir`, oldest first.
+pub fn list(dir: &Path) -> io::Result<Vec<Attachment>> {
+    let entries = match fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(e),
+    };
+    let mut attachments = Vec::new();
+    for entry in entries {
+        let entry = entry?;
+        let id = entry.file_name().to_string_lossy().into_owned();
+        let metadata = entry.metadata()?;
+        if !metadata.is_file() || !is_valid_id(&id) {
+            continue;
+        }
+        let uploaded_at = metadata
+            .modified()
+            .map(DateTime::<Utc>::from)
+            .unwrap_or_else(|_| Utc::now());
+        attachments.push(Attachment {
+            name: id.split_once('-').map_or(id.as_str(), |(_, name)| name).to_string(),
+            reference: reference(&id),
+            size: metadata.len(),
+            uploaded_at,
+            path: entry.path(),
+            id,
+        });
+    }
+    attachments.sort_by(|a, b| (a.uploaded_at, &a.id).cmp(&(b.uploaded_at, &b.id)));
+    Ok(attachments)
+}
+
+/// The MIME type to serve an attachment with, from its extension.
+pub fn content_type(id: &str) -> &'static str {
+    let extension = id.rsplit_once('.').map(|(_, ext)| ext.to_ascii_lowercase());
+    match extension.as_deref() {
+        Some("diff" | "patch" | "log" | "txt" | "md") => "text/plain; charset=utf-8",
+        Some("json") => "application/json",
+        Some("png") => "image/png",
+        Some("jpg" | "jpeg") => "image/jpeg",
+        Some("gif") => "image/gif",
+        Some("webp") => "image/webp",
+        _ => "application/octet-stream",
+    }
+}
+
+fn sanitize_name(file_name: &str) -> String {
+    // Browsers and some clients send the full client-side path.
+    let base = file_name.rsplit(['/', '\\']).next().unwrap_or_default();
+    let name: String = base
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() || "._-".contains(c) { c } else { '_' })
+        .take(MAX_NAME_CHARS)
+        .collect();
+    let name = name.trim_start_matches('.');
+    if name.is_empty() {
+        "attachment".to_string()
+    } else {
+        name.to_string()
+    }
+}
+
+fn is_valid_id(id: &str) -> bool {
+    !id.is_empty()
+        && !id.starts_with('.')
+        && id.len() <= MAX_NAME_CHARS + 9
+        && id.chars().all(|c| c.is_ascii_alphanumeric() || "._-".contains(c))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_saved_attachments_are_found_by_reference() {
+        let temp = tempfile::tempdir().unwrap();
+        let saved = save(temp.path(), "../../etc/fix auth.diff", b"-old\n+new\n").unwrap();
+        assert!(saved.id.ends_with("-fix_auth.diff"), "{}", saved.id);
+        assert_eq!(saved.name, "fix_auth.diff");
+        assert_eq!(saved.path.parent(), Some(temp.path()));
+
+        let content = format!("Review this {} please", saved.reference);
+        assert_eq!(references(&content), vec![saved.id.clone()]);
+        assert_eq!(with_references(&content, &[saved.id.clone()]), content);
+        assert_eq!(
+            with_references("See diff", &[saved.id.clone()]),
+            format!("See diff [ATTACH {}]", saved.id)
+        );
+
+        assert_eq!(find(temp.path(), &saved.id), Some(saved.path.clone()));
+        assert_eq!(find(temp.path(), "../secret"), None);
+        assert_eq!(list(temp.path()).unwrap()[0].name, "fix_auth.diff");
+        assert_eq!(content_type(&saved.id), "text/plain; charset=utf-8");
+    }
+
+    #[test]
+    fn test_long_content_spills_over_into_an_attachment() {
+        let temp = tempfile::tempdir().unwrap();
+        assert_eq!(spill_over(temp.path(), "short", 10).unwrap(), "short");
+        assert_eq!(spill_over(temp.path(), &"x".repeat(50), 0).unwrap(), "x".repeat(50));
+        assert!(list(temp.path()).unwrap().is_empty());
+
+        let content = "Contract follows: [ATTACH 0badc0de-api.md] and the rest of it";
+        let logged = spill_over(temp.path(), content, 17).unwrap();
+        let saved = &list(temp.path()).unwrap()[0];
+        assert_eq!(saved.name, "message.txt");
+        assert_eq!(fs::read_to_string(&saved.path).unwrap(), content);
+        assert_eq!(
+            logged,
+            format!(
+                "Contract follows:… [full message: {}] [ATTACH 0badc0de-api.md] {}",
+                saved.path.display(),
+                saved.reference
+            )
+        );
+        assert_eq!(references(&logged), ["0badc0de-api.md", saved.id.as_str()]);
+    }
+}
@@ -0,0 +1,308 @@
+//! The [`Storage`] trait: persistence for sessions, learnings, the coordination log
+//! and app config, independent of where it lives.
+//!
+//! [`SessionStorage`] is the default, filesystem-backed implementation. Anything
+//! else (a SQLite store, an S3 archive for finished sessions) implements the same
+//! trait. [`MemoryStorage`] keeps everything in process, so HTTP handlers and
+//! actions can be exercised without touching disk.
+//!
+//! Path-based helpers (`session_dir`, `templates_dir`, artifacts, ...) stay on
+//! `SessionStorage`: they hand out locations for agents to write to and have no
+//! meaning for a non-filesystem backend.
+
+use std::collections::HashMap;
+
+use parking_lot::Mutex;
+
+use super::{
+    session_summary, stable_learning_id, AppConfig, Learning, PersistedSession, SessionStorage,
+    SessionSummary, StorageError,
+};
+use crate::coordination::CoordinationMessage;
+
+pub trait Storage: Send + Sync {
+    // --- Sessions ---
+
+    fn save_session(&self, session: &PersistedSession) -> Result<(), StorageError>;
+
+    /// Fails with [`StorageError::SessionNotFound`] when nothing is stored under `session_id`.
+    fn load_session(&self, session_id: &str) -> Result<PersistedSession, StorageError>;
+
+    /// Stored sessions, newest first.
+    fn list_sessions(&self) -> Result<Vec<SessionSummary>, StorageError>;
+
+    fn delete_session(&self, session_id: &str) -> Result<(), StorageError>;
+
+    // --- Learnings ---
+
+    fn append_learning_session(
+        &self,
+        session_id: &str,
+        learning: &Learning,
+    ) -> Result<(), StorageError>;
+
+    /// Learnings in the order they were appended, each with a stable `id`.
+    fn read_learnings_session(&self, session_id: &str) -> Result<Vec<Learning>, StorageError>;
+
+    /// Returns `false` when the session has no learning with `learning_id`.
+    fn delete_learning_session(
+        &self,
+        session_id: &str,
+        learning_id: &str,
+    ) -> Result<bool, StorageError>;
+
+    // --- Coordination ---
+
+    fn append_coordination_log(
+        &self,
+        session_id: &str,
+        message: &CoordinationMessage,
+    ) -> Result<(), StorageError>;
+
+    /// Messages oldest first; a `limit` keeps only the most recent ones.
+    fn read_coordination_log(
+        &self,
+        session_id: &str,
+        limit: Option<usize>,
+    ) -> Result<Vec<CoordinationMessage>, StorageError>;
+
+    /// Messages numbered after `after_seq`, oldest first.
+    fn read_coordination_log_after(
+        &self,
+        session_id: &str,
+        after_seq: u64,
+    ) -> Result<Vec<CoordinationMessage>, StorageError>;
+
+    // --- Config ---
+
+    fn load_config(&self) -> Result<AppConfig, StorageError>;
+
+    fn save_config(&self, config: &AppConfig) -> Result<(), StorageError>;
+}
+
+impl Storage for SessionStorage {
+    fn save_session(&self, session: &PersistedSession) -> Result<(), StorageError> {
+        SessionStorage::save_session(self, session)
+    }
+
+    fn load_session(&self, session_id: &str) -> Result<PersistedSession, StorageError> {
+        SessionStorage::load_session(self, session_id)
+    }
+
+    fn list_sessions(&self) -> Result<Vec<SessionSummary>, StorageError> {
+        SessionStorage::list_sessions(self)
+    }
+
+    fn delete_session(&self, session_id: &str) -> Result<(), StorageError> {
+        SessionStorage::delete_session(self, session_id)
+    }
+
+    fn append_learning_session(
+        &self,
+        session_id: &str,
+        learning: &Learning,
+    ) -> Result<(), StorageError> {
+        SessionStorage::append_learning_session(self, session_id, learning)
+    }
+
+    fn read_learnings_session(&self, session_id: &str) -> Result<Vec<Learning>, StorageError> {
+        SessionStorage::read_learnings_session(self, session_id)
+    }
+
+    fn delete_learning_session(
+        &self,
+        session_id: &str,
+        learning_id: &str,
+    ) -> Result<bool, StorageError> {
+        SessionStorage::delete_learning_session(self, session_id, learning_id)
+    }
+
+    fn append_coordination_log(
+        &self,
+        session_id: &str,
+        message: &CoordinationMessage,
+    ) -> Result<(), StorageError> {
+        SessionStorage::append_coordination_log(self, session_id, message)
+    }
+
+    fn read_coordination_log(
+        &self,
+        session_id: &str,
+        limit: Option<usize>,
+    ) -> Result<Vec<CoordinationMessage>, StorageError> {
+        SessionStorage::read_coordination_log(self, session_id, limit)
+    }
+
+    fn read_coordination_log_after(
+        &self,
+        session_id: &str,
+        after_seq: u64,
+    ) -> Result<Vec<CoordinationMessage>, StorageError> {
+        SessionStorage::read_coordination_log_after(self, session_id, after_seq)
+    }
+
+    fn load_config(&self) -> Result<AppConfig, StorageError> {
+        SessionStorage::load_config(self)
+    }
+
+    fn save_config(&self, config: &AppConfig) -> Result<(), StorageError> {
+        SessionStorage::save_config(self, config)
+    }
+}
+
+/// In-process [`Storage`]; nothing survives the value being dropped.
+pub struct MemoryStorage {
+    sessions: Mutex<HashMap<String, PersistedSession>>,
+    learnings: Mutex<HashMap<String, Vec<Learning>>>,
+    coordination: Mutex<HashMap<String, Vec<CoordinationMessage>>>,
+    config: Mutex<AppConfig>,
+}
+
+impl MemoryStorage {
+    /// Empty storage holding the same default config a fresh `SessionStorage` writes.
+    pub fn new() -> Self {
+        Self {
+            sessions: Mutex::new(HashMap::new()),
+            learnings: Mutex::new(HashMap::new()),
+            coordination: Mutex::new(HashMap::new()),
+            config: Mutex::new(SessionStorage::default_config()),
+        }
+    }
+}
+
+impl Default for MemoryStorage {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Storage for MemoryStorage {
+    fn save_session(&self, session: &PersistedSession) -> Result<(), StorageError> {
+        self.sessions
+            .lock()
+            .insert(session.id.clone(), session.clone());
+        Ok(())
+    }
+
+    fn load_session(&self, session_id: &str) -> Result<PersistedSession, StorageError> {
+        self.sessions
+            .lock()
+            .get(session_id)
+            .cloned()
+            .ok_or_else(|| StorageError::SessionNotFound(session_id.to_string()))
+    }
+
+    fn list_sessions(&self) -> Result<Vec<SessionSummary>, StorageError> {
+        let mut summaries: Vec<SessionSummary> = self
+            .sessions
+            .lock()
+            .values()
+            .cloned()
+            .map(session_summary)
+            .collect();
+        summaries.sort_by(|a, b| b.created_at.cmp(&a.created_at));
+        Ok(summaries)
+    }
+
+    fn delete_session(&self, session_id: &str) -> Result<(), StorageError> {
+        self.sessions.lock().remove(session_id);
+        self.learnings.lock().remove(session_id);
+        self.coordination.lock().remove(session_id);
+        Ok(())
+    }
+
+    fn append_learning_session(
+        &self,
+        session_id: &str,
+        learning: &Learning,
+    ) -> Result<(), StorageError> {
+        let mut learning = learning.clone();
+        if learning.id.is_empty() {
+            learning.id = stable_learning_id(&learning);
+        }
+        self.learnings
+            .lock()
+            .entry(session_id.to_string())
+            .or_default()
+            .push(learning);
+        Ok(())
+    }
+
+    fn read_learnings_session(&self, session_id: &str) -> Result<Vec<Learning>, StorageError> {
+        Ok(self
+            .learnings
+            .lock()
+            .get(session_id)
+            .cloned()
+            .unwrap_or_default())
+    }
+
+    fn delete_learning_session(
+        &self,
+        session_id: &str,
+        learning_id: &str,
+    ) -> Result<bool, StorageError> {
+        let mut learnings = self.learnings.lock();
+        let Some(session_learnings) = learnings.get_mut(session_id) else {
+            return Ok(false);
+        };
+        let before = session_learnings.len();
+        session_learnings.retain(|learning| learning.id != learning_id);
+        Ok(session_learnings.len() != before)
+    }
+
+    fn append_coordination_log(
+        &self,
+        session_id: &str,
+        message: &CoordinationMessage,
+    ) -> Result<(), StorageError> {
+        let mut coordination = self.coordination.lock();
+        let messages = coordination.entry(session_id.to_string()).or_default();
+        let seq = messages.len() as u64 + 1;
+        messages.push(CoordinationMessage {
+            seq: Some(seq),
+            ..message.clone()
+        });
+        Ok(())
+    }
+
+    fn read_coordination_log(
+        &self,
+        session_id: &str,
+        limit: Option<usize>,
+    ) -> Result<Vec<CoordinationMessage>, StorageError> {
+        let coordination = self.coordination.lock();
+        let messages = coordination
+            .get(session_id)
+            .map(Vec::as_slice)
+            .unwrap_or_default();
+        let skip = limit.map_or(0, |limit| messages.len().saturating_sub(limit));
+        Ok(messages[skip..].to_vec())
+    }
+
+    fn read_coordination_log_after(
+        &self,
+        session_id: &str,
+        after_seq: u64,
+    ) -> Result<Vec<CoordinationMessage>, StorageError> {
+        Ok(self
+            .coordination
+            .lock()
+            .get(session_id)
+            .map(Vec::as_slice)
+            .unwrap_or_default()
+            .iter()
+            .filter(|message| message.seq.is_some_and(|seq| seq > after_seq))
+            .cloned()
+            .collect())
+    }
+
+    fn load_config(&self) -> Result<AppConfig, StorageError> {
+        Ok(self.config.lock().clone())
+    }
+
+    fn save_config(&self, config: &AppConfig) -> Result<(), StorageError> {
+        *self.config.lock() = config.clone();
+        Ok(())
+    }
+}
@@ -0,0 +1,497 @@
+//! Size-based rotation and compaction for a session's `coordination.log`.
+//!
+//! The active log rotates to `coordination.log.1` once it reaches
+//! [`MAX_LOG_BYTES`]; older generations shift up to `.N` and the oldest beyond
+//! [`MAX_ROTATIONS`] is dropped. A freshly rotated generation is compacted:
+//! runs of `PROGRESS` updates from the same sender fold into one summary line.
+//! [`read_lines`] spans every generation so readers never see the split, and
+//! [`stats`] keeps running message counts across rotations.
+//!
+//! Lines are `[2026-01-01T10:00:00.123Z #42] FROM → TO: content`: a millisecond
+//! timestamp plus a per-log sequence number handed out by [`append_sequenced`], so
+//! messages written within the same millisecond still have a defined order. Lines
+//! agents append directly (and lines from before sequencing) have no `#seq`.
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use chrono::{DateTime, Utc};
+
+use super::coordination_stats::{self, CoordinationStats};
+
+/// Size at which the active log rotates.
+pub const MAX_LOG_BYTES: u64 = 1024 * 1024;
+
+/// Rotated generations kept (`coordination.log.1` ..= `coordination.log.N`).
+pub const MAX_ROTATIONS: usize = 5;
+
+/// Serializes appends and rotations. Several `SessionStorage` instances share the
+/// same files, so this is process-wide rather than per instance.
+static LOG_LOCK: Mutex<()> = Mutex::new(());
+
+/// Last sequence number handed out per log, so appends don't rescan it. Only
+/// touched while `LOG_LOCK` is held.
+static LAST_SEQ: Mutex<BTreeMap<PathBuf, u64>> = Mutex::new(BTreeMap::new());
+
+const PROGRESS_PREFIX: &str = "PROGRESS";
+
+/// Append `line` to the log at `path`, rotating first if the log is full.
+pub fn append(path: &Path, line: &str, max_bytes: u64, max_rotations: usize) -> io::Result<()> {
+    let _guard = LOG_LOCK
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner());
+    append_locked(path, line, max_bytes, max_rotations)
+}
+
+/// Append the line `format(seq)` with the log's next sequence number, returning
+/// the number used. Numbers keep increasing across rotations.
+pub fn append_sequenced(
+    path: &Path,
+    format: impl FnOnce(u64) -> String,
+    max_bytes: u64,
+    max_rotations: usize,
+) -> io::Result<u64> {
+    let _guard = LOG_LOCK
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner());
+    let mut last_seq = LAST_SEQ
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner());
+
+    let seq = match last_seq.get(path) {
+        Some(last) => last + 1,
+        None => scan_last_seq(path, max_rotations)? + 1,
+    };
+    append_locked(path, &format(seq), max_bytes, max_rotations)?;
+    last_seq.insert(path.to_path_buf(), seq);
+    Ok(seq)
+}
+
+/// `[timestamp #seq] FROM → TO: content`, newline-terminated.
+pub fn format_line(at: DateTime<Utc>, seq: u64, from: &str, to: &str, content: &str) -> String {
+    format!(
+        "[{} #{}] {} → {}: {}\n",
+        at.format("%Y-%m-%dT%H:%M:%S%.3fZ"),
+        seq,
+        from,
+        to,
+        content
+    )
+}
+
+/// Split the bracketed stamp of a line into its timestamp and sequence number.
+pub fn split_stamp(stamp: &str) -> (&str, Option<u64>) {
+    stamp
+        .rsplit_once(" #")
+        .and_then(|(timestamp, seq)| Some((timestamp, Some(seq.parse().ok()?))))
+        .unwrap_or((stamp, None))
+}
+
+/// The highest sequence number in the newest generation that has any; 0 for a
+/// log that was never sequenced.
+fn scan_last_seq(path: &Path, max_rotations: usize) -> io::Result<u64> {
+    for generation in generations(path, max_rotations) {
+        let content = match fs::read_to_string(&generation) {
+            Ok(content) => content,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => continue,
+            Err(e) => return Err(e),
+        };
+        if let Some(seq) = content.lines().filter_map(|line| split_line(line)?.seq).max() {
+            return Ok(seq);
+        }
+    }
+    Ok(0)
+}
+
+fn append_locked(path: &Path, line: &str, max_bytes: u64, max_rotations: usize) -> io::Result<()> {
+    let full = fs::metadata(path).is_ok_and(|meta| meta.len() >= max_bytes);
+    if full {
+        // Count what agents appended directly before the active log is renamed away.
+        sync_stats(path);
+        rotate(path, max_rotations)?;
+        if let Err(e) = coordination_stats::reset_offset(path) {
+            tracing::warn!(
+                "Failed to reset coordination stats for {}: {}",
+                path.display(),
+                e
+            );
+        }
+    }
+
+    let mut file = fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)?;
+    file.write_all(line.as_bytes())?;
+    sync_stats(path);
+    Ok(())
+}
+
+/// Stats are best-effort: a failed update must not lose the message itself.
+fn sync_stats(path: &Path) {
+    if let Err(e) = coordination_stats::sync(path) {
+        tracing::warn!(
+            "Failed to update coordination stats for {}: {}",
+            path.display(),
+            e
+        );
+    }
+}
+
+/// Message and throughput stats for the log at `path`, brought up to date with
+/// any lines agents appended directly.
+pub fn stats(path: &Path) -> io::Result<CoordinationStats> {
+    let _guard = LOG_LOCK
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner());
+    coordination_stats::sync(path)
+}
+
+/// Path of rotated generation `n` (1 = most recent).
+fn rotated_path(path: &Path, n: usize) -> PathBuf {
+    let mut name = path.file_name().unwrap_or_default().to_os_string();
+    name.push(format!(".{}", n));
+    path.with_file_name(name)
+}
+
+/// The active log followed by its rotated generations, newest first.
+fn generations(path: &Path, max_rotations: usize) -> impl Iterator<Item = PathBuf> + '_ {
+    std::iter::once(path.to_path_buf())
+        .chain((1..=max_rotations).map(move |n| rotated_path(path, n)))
+}
+
+fn rotate(path: &Path, max_rotations: usize) -> io::Result<()> {
+    if max_rotations == 0 {
+        return fs::remove_file(path);
+    }
+    let oldest = rotated_path(path, max_rotations);
+    if oldest.exists() {
+        fs::remove_file(&oldest)?;
+    }
+    for n in (1..max_rotations).rev() {
+        let from = rotated_path(path, n);
+        if from.exists() {
+            fs::rename(&from, rotated_path(path, n + 1))?;
+        }
+    }
+    let newest = rotated_path(path, 1);
+    fs::rename(path, &newest)?;
+
+    if let Err(e) = compact_file(&newest) {
+        tracing::warn!("Failed to compact {}: {}", newest.display(), e);
+    }
+    Ok(())
+}
+
+/// Read the last `limit` lines (all lines when `None`) across the rotated
+/// generations and the active log, oldest first.
+pub fn read_lines(
+    path: &Path,
+    limit: Option<usize>,
+    max_rotations: usize,
+) -> io::Result<Vec<String>> {
+    let _guard = LOG_LOCK
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner());
+
+    // Newest generation first so a limited read can stop early.
+    let mut newest_first: Vec<String> = Vec::new();
+    for generation in generations(path, max_rotations) {
+        let content = match fs::read_to_string(&generation) {
+            Ok(content) => content,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => continue,
+            Err(e) => return Err(e),
+        };
+        for line in content.lines().rev() {
+            if limit.is_some_and(|limit| newest_first.len() >= limit) {
+                break;
+            }
+            newest_first.push(line.to_string());
+        }
+        if limit.is_some_and(|limit| newest_first.len() >= limit) {
+            break;
+        }
+    }
+
+    newest_first.reverse();
+    Ok(newest_first)
+}
+
+/// Lines after the one numbered `after_seq`, oldest first, across the rotated
+/// generations, stopping at the first generation that reaches back to it. Lines
+/// agents appended without a number are returned with the next numbered line, so
+/// a caller that keeps the highest number it saw as its cursor gets each line once.
+pub fn read_lines_after(
+    path: &Path,
+    after_seq: u64,
+    max_rotations: usize,
+) -> io::Result<Vec<String>> {
+    let _guard = LOG_LOCK
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner());
+
+    let mut newest_first: Vec<String> = Vec::new();
+    let mut past_unsequenced_tail = false;
+    'generations: for generation in generations(path, max_rotations) {
+        let content = match fs::read_to_string(&generation) {
+            Ok(content) => content,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => continue,
+            Err(e) => return Err(e),
+        };
+        for line in content.lines().rev() {
+            match split_line(line).and_then(|line| line.seq) {
+                Some(seq) if seq <= after_seq => break 'generations,
+                Some(_) => past_unsequenced_tail = true,
+                None if !past_unsequenced_tail => continue,
+                None => {}
+            }
+            newest_first.push(line.to_string());
+        }
+    }
+
+    newest_first.reverse();
+    Ok(newest_first)
+}
+
+/// Rewrite a (rotated, no longer appended-to) generation with its `PROGRESS`
+/// runs folded.
+pub fn compact_file(path: &Path) -> io::Result<()> {
+    let content = fs::read_to_string(path)?;
+    let compacted = compact(&content);
+    if compacted.len() == content.len() {
+        return Ok(());
+    }
+
+    let tmp = path.with_extension("compacting");
+    fs::write(&tmp, compacted)?;
+    fs::rename(&tmp, path)
+}
+
+struct LogLine<'a> {
+    timestamp: &'a str,
+    seq: Option<u64>,
+    from: &'a str,
+    to: &'a str,
+    content: &'a str,
+}
+
+/// Split a `[timestamp #seq] FROM → TO: content` line.
+fn split_line(line: &str) -> Option<LogLine<'_>> {
+    let rest = line.strip_prefix('[')?;
+    let (stamp, rest) = rest.split_once("] ")?;
+    let (timestamp, seq) = split_stamp(stamp);
+    let (from, rest) = rest.split_once(" → ")?;
+    let (to, content) = rest.split_once(": ")?;
+    Some(LogLine {
+        timestamp,
+        seq,
+        from,
+        to,
+        content,
+    })
+}
+
+/// Fold each sender's consecutive `PROGRESS` updates into one line carrying the
+/// latest update and how many it replaces. A sender's run ends at its next
+/// non-`PROGRESS` message; other senders' lines in between don't break it.
+pub fn compact(content: &str) -> String {
+    let lines: Vec<&str> = content.lines().collect();
+    let mut kept: Vec<Option<String>> = lines.iter().map(|line| Some(line.to_string())).collect();
+    let mut runs: Vec<(&str, Vec<usize>)> = Vec::new();
+
+    fn fold(lines: &[&str], kept: &mut [Option<String>], run: &[usize]) {
+        let [first, .., last] = run else {
+            return;
+        };
+        let (Some(first), Some(latest)) = (split_line(lines[*first]), split_line(lines[*last]))
+        else {
+            return;
+        };
+        for index in run {
+            kept[*index] = None;
+        }
+        let update = latest
+            .content
+            .trim_start()
+            .trim_start_matches(PROGRESS_PREFIX)
+            .trim_start_matches(':')
+            .trim();
+        // The summary keeps the latest update's sequence number.
+        let stamp = match latest.seq {
+            Some(seq) => format!("{} #{}", latest.timestamp, seq),
+            None => latest.timestamp.to_string(),
+        };
+        kept[*last] = Some(format!(
+            "[{}] {} → {}: {} ({} updates since {}): {}",
+            stamp,
+            latest.from,
+            latest.to,
+            PROGRESS_PREFIX,
+            run.len(),
+            first.timestamp,
+            update
+        ));
+    }
+
+    for (index, line) in lines.iter().enumerate() {
+        let Some(parsed) = split_line(line) else {
+            continue;
+        };
+        let slot = match runs.iter().position(|(from, _)| *from == parsed.from) {
+            Some(slot) => slot,
+            None => {
+                runs.push((parsed.from, Vec::new()));
+                runs.len() - 1
+            }
+        };
+        let run = &mut runs[slot].1;
+        if parsed.content.trim_start().starts_with(PROGRESS_PREFIX) {
+            run.push(index);
+        } else {
+            fold(&lines, &mut kept, run);
+            run.clear();
+        }
+    }
+    for (_, run) in &runs {
+        fold(&lines, &mut kept, run);
+    }
+
+    let mut out = String::with_capacity(content.len());
+    for line in kept.into_iter().flatten() {
+        out.push_str(&line);
+        out.push('\n');
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn line(minute: u32, from: &str, content: &str) -> String {
+        format!(
+            "[2026-01-01T10:{:02}:00Z] {} → QUEEN: {}\n",
+            minute, from, content
+        )
+    }
+
+    #[test]
+    fn test_append_rotates_and_reads_span_generations() {
+        let dir = tempfile::tempdir().unwrap();
+        let log = dir.path().join("coordination.log");
+
+        for minute in 0..6 {
+            append(
+                &log,
+                &line(minute, "WORKER-1", &format!("step {}", minute)),
+                40,
+                2,
+            )
+            .unwrap();
+        }
+
+        // Every full line exceeds the 40-byte cap, so each append after the first rotates;
+        // only the active log and two generations survive.
+        assert!(rotated_path(&log, 2).exists());
+        assert!(!rotated_path(&log, 3).exists());
+
+        let all = read_lines(&log, None, 2).unwrap();
+        assert_eq!(all.len(), 3);
+        assert!(all[0].ends_with("step 3"));
+        assert!(all[2].ends_with("step 5"));
+
+        let last_two = read_lines(&log, Some(2), 2).unwrap();
+        assert_eq!(last_two, all[1..].to_vec());
+    }
+
+    #[test]
+    fn test_append_sequenced_continues_across_rotations() {
+        let dir = tempfile::tempdir().unwrap();
+        let log = dir.path().join("coordination.log");
+        let at = "2026-01-01T10:00:00.250Z".parse::<DateTime<Utc>>().unwrap();
+
+        // A line without a sequence number, as an agent would append it.
+        append(&log, &line(0, "WORKER-1", "direct"), 1024, 2).unwrap();
+        for expected in 1..=3 {
+            let seq = append_sequenced(
+                &log,
+                |seq| format_line(at, seq, "QUEEN", "WORKER-1", "go"),
+                80,
+                2,
+            )
+            .unwrap();
+            assert_eq!(seq, expected);
+        }
+
+        let lines = read_lines(&log, None, 2).unwrap();
+        let last = split_line(lines.last().unwrap()).unwrap();
+        assert_eq!(last.timestamp, "2026-01-01T10:00:00.250Z");
+        assert_eq!(last.seq, Some(3));
+        assert_eq!(split_line(&lines[0]).unwrap().seq, None);
+
+        // A fresh cache (e.g. after a restart) picks up where the log left off.
+        LAST_SEQ.lock().unwrap().remove(&log);
+        assert_eq!(scan_last_seq(&log, 2).unwrap(), 3);
+    }
+
+    #[test]
+    fn test_read_lines_after_returns_each_line_once() {
+        let dir = tempfile::tempdir().unwrap();
+        let log = dir.path().join("coordination.log");
+        let at = "2026-01-01T10:00:00Z".parse::<DateTime<Utc>>().unwrap();
+        let sequenced = |content: &str| {
+            append_sequenced(
+                &log,
+                |seq| format_line(at, seq, "QUEEN", "WORKER-1", content),
+                1024,
+                2,
+            )
+            .unwrap()
+        };
+
+        sequenced("first");
+        append(&log, &line(1, "WORKER-1", "direct"), 1024, 2).unwrap();
+        let cursor = sequenced("second");
+        append(&log, &line(2, "WORKER-1", "trailing"), 1024, 2).unwrap();
+
+        let read = read_lines_after(&log, 0, 2).unwrap();
+        assert_eq!(read.len(), 3);
+        assert!(read[1].ends_with("direct"));
+        assert!(read[2].ends_with("second"));
+        assert!(read_lines_after(&log, cursor, 2).unwrap().is_empty());
+
+        sequenced("third");
+        let read = read_lines_after(&log, cursor, 2).unwrap();
+        assert_eq!(read.len(), 2);
+        assert!(read[0].ends_with("trailing"));
+        assert!(read[1].ends_with("third"));
+    }
+
+    #[test]
+    fn test_compact_folds_progress_runs_per_sender() {
+        let content = [
+            line(0, "WORKER-1", "PROGRESS: 1/4"),
+            line(1, "WORKER-2", "PROGRESS: started"),
+            line(2, "WORKER-1", "PROGRESS: 2/4"),
+            line(3, "WORKER-1", "PROGRESS: 3/4"),
+            line(4, "WORKER-1", "DONE: all tests pass"),
+            line(5, "WORKER-1", "PROGRESS: cleanup"),
+        ]
+        .concat();
+
+        let compacted = compact(&content);
+        let lines: Vec<&str> = compacted.lines().collect();
+        assert_eq!(
+            lines,
+            vec![
+                "[2026-01-01T10:01:00Z] WORKER-2 → QUEEN: PROGRESS: started",
+                "[2026-01-01T10:03:00Z] WORKER-1 → QUEEN: PROGRESS (3 updates since 2026-01-01T10:00:00Z): 3/4",
+                "[2026-01-01T10:04:00Z] WORKER-1 → QUEEN: DONE: all tests pass",
+                "[2026-01-01T10:05:00Z] WORKER-1 → QUEEN: PROGRESS: cleanup",
+            ]
+        );
+    }
+}
@@ -0,0 +1,264 @@
+//! Running message and throughput statistics for a session's `coordination.log`.
+//!
+//! Agents append to the log directly as well as through Hive Manager, so stats are
+//! folded in from the log itself: [`sync`] reads only the bytes added since the
+//! last pass and records its offset next to the totals in
+//! `coordination-stats.json`. [`super::coordination_log`] syncs before every
+//! rotation, so no line is missed when the active log is renamed away.
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::io::{self, Read, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use super::coordination_log::split_stamp;
+
+const STATS_FILE: &str = "coordination-stats.json";
+
+/// Message categories counted in [`CoordinationStats::by_type`].
+const TASK: &str = "TASK";
+const PROGRESS: &str = "PROGRESS";
+const COMPLETION: &str = "COMPLETION";
+const ERROR: &str = "ERROR";
+const SYSTEM: &str = "SYSTEM";
+const MESSAGE: &str = "MESSAGE";
+pub const CATEGORIES: [&str; 6] = [TASK, PROGRESS, COMPLETION, ERROR, SYSTEM, MESSAGE];
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct WorkerThroughput {
+    /// TASK messages addressed to the worker.
+    pub tasks: u64,
+    /// COMPLETION messages sent by the worker.
+    pub completions: u64,
+    /// ERROR messages sent by the worker.
+    pub errors: u64,
+    /// Every message sent by the worker.
+    pub messages: u64,
+    /// Mean seconds from a TASK to the COMPLETION that answers it.
+    pub avg_task_secs: Option<f64>,
+    /// Share of the worker's messages that were errors.
+    pub error_rate: f64,
+    /// Start times of tasks not yet completed, oldest first.
+    #[serde(default)]
+    pub open_tasks: Vec<DateTime<Utc>>,
+    #[serde(default)]
+    completed_task_secs: i64,
+    #[serde(default)]
+    timed_completions: u64,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct CoordinationStats {
+    pub total_messages: u64,
+    pub by_type: BTreeMap<String, u64>,
+    pub by_sender: BTreeMap<String, u64>,
+    /// Share of all messages that were errors.
+    pub error_rate: f64,
+    pub workers: BTreeMap<String, WorkerThroughput>,
+    pub last_message_at: Option<DateTime<Utc>>,
+}
+
+/// Persisted stats plus how far into the active log they reach.
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct StatsLedger {
+    offset: u64,
+    stats: CoordinationStats,
+}
+
+fn stats_path(log_path: &Path) -> PathBuf {
+    log_path.with_file_name(STATS_FILE)
+}
+
+fn load(log_path: &Path) -> StatsLedger {
+    fs::read_to_string(stats_path(log_path))
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+fn save(log_path: &Path, ledger: &StatsLedger) -> io::Result<()> {
+    let content = serde_json::to_string(ledger).map_err(io::Error::other)?;
+    let path = stats_path(log_path);
+    let tmp = path.with_extension("json.tmp");
+    fs::write(&tmp, content)?;
+    fs::rename(&tmp, path)
+}
+
+/// Fold complete lines appended to the active log since the last sync into the
+/// stats and return them. Callers hold the coordination log lock.
+pub(crate) fn sync(log_path: &Path) -> io::Result<CoordinationStats> {
+    let mut ledger = load(log_path);
+    let len = match fs::metadata(log_path) {
+        Ok(meta) => meta.len(),
+        Err(e) if e.kind() == io::ErrorKind::NotFound => 0,
+        Err(e) => return Err(e),
+    };
+    if len < ledger.offset {
+        // Rotated or truncated behind our back; start over on the new file.
+        ledger.offset = 0;
+    }
+    if len == ledger.offset {
+        return Ok(ledger.stats);
+    }
+
+    let mut file = fs::File::open(log_path)?;
+    file.seek(SeekFrom::Start(ledger.offset))?;
+    let mut added = Vec::new();
+    file.read_to_end(&mut added)?;
+    // Leave a line an agent is still writing for the next pass.
+    let complete = added.iter().rposition(|b| *b == b'\n').map_or(0, |i| i + 1);
+    if complete == 0 {
+        return Ok(ledger.stats);
+    }
+
+    for line in String::from_utf8_lossy(&added[..complete]).lines() {
+        record_line(&mut ledger.stats, line);
+    }
+    ledger.offset += complete as u64;
+    save(log_path, &ledger)?;
+    Ok(ledger.stats)
+}
+
+/// Mark the active log as rotated away: the next sync starts at its beginning.
+pub(crate) fn reset_offset(log_path: &Path) -> io::Result<()> {
+    let mut ledger = load(log_path);
+    ledger.offset = 0;
+    save(log_path, &ledger)
+}
+
+/// Category of a message, from its leading keyword (`COMPLETED: ...`) or, for
+/// unlabelled messages, from who sent it to whom.
+pub fn category(from: &str, to: &str, content: &str) -> &'static str {
+    let keyword = content
+        .trim_start()
+        .split(|c: char| !c.is_ascii_alphabetic())
+        .next()
+        .unwrap_or_default()
+        .to_ascii_uppercase();
+    match keyword.as_str() {
+        "TASK" => TASK,
+        "PROGRESS" => PROGRESS,
+        "COMPLETED" | "COMPLETION" | "COMPLETE" | "DONE" => COMPLETION,
+        "ERROR" | "FAILED" | "BLOCKED" => ERROR,
+        _ if from == "QUEEN" && is_worker(to) => TASK,
+        _ if from == "SYSTEM" => SYSTEM,
+        _ => MESSAGE,
+    }
+}
+
+fn is_worker(name: &str) -> bool {
+    name.starts_with("WORKER")
+}
+
+fn record_line(stats: &mut CoordinationStats, line: &str) {
+    // Format: [2024-02-03T18:52:34.123Z #42] FROM → TO: content
+    let Some((stamp, rest)) = line
+        .strip_prefix('[')
+        .and_then(|rest| rest.split_once("] "))
+    else {
+        return;
+    };
+    let (timestamp, _) = split_stamp(stamp);
+    let Some((from, rest)) = rest.split_once(" → ") else {
+        return;
+    };
+    let Some((to, content)) = rest.split_once(": ") else {
+        return;
+    };
+    let Ok(at) = DateTime::parse_from_rfc3339(timestamp).map(|at| at.with_timezone(&Utc)) else {
+        return;
+    };
+
+    let kind = category(from, to, content);
+    stats.total_messages += 1;
+    *stats.by_type.entry(kind.to_string()).or_default() += 1;
+    *stats.by_sender.entry(from.to_string()).or_default() += 1;
+    stats.last_message_at = Some(at);
+
+    if kind == TASK && is_worker(to) {
+        let worker = stats.workers.entry(to.to_string()).or_default();
+        worker.tasks += 1;
+        worker.open_tasks.push(at);
+    }
+    if is_worker(from) {
+        let worker = stats.workers.entry(from.to_string()).or_default();
+        worker.messages += 1;
+        match kind {
+            COMPLETION => {
+                worker.completions += 1;
+                if !worker.open_tasks.is_empty() {
+                    let started = worker.open_tasks.remove(0);
+                    worker.completed_task_secs += (at - started).num_seconds().max(0);
+                    worker.timed_completions += 1;
+                    worker.avg_task_secs =
+                        Some(worker.completed_task_secs as f64 / worker.timed_completions as f64);
+                }
+            }
+            ERROR => worker.errors += 1,
+            _ => {}
+        }
+        worker.error_rate = worker.errors as f64 / worker.messages as f64;
+    }
+
+    let errors = stats.by_type.get(ERROR).copied().unwrap_or(0);
+    stats.error_rate = errors as f64 / stats.total_messages as f64;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn line(minute: u32, from: &str, to: &str, content: &str) -> String {
+        format!(
+            "[2026-01-01T10:{:02}:00Z] {} → {}: {}\n",
+            minute, from, to, content
+        )
+    }
+
+    #[test]
+    fn test_sync_counts_new_lines_and_times_tasks_per_worker() {
+        let dir = tempfile::tempdir().unwrap();
+        let log = dir.path().join("coordination.log");
+        fs::write(
+            &log,
+            [
+                line(0, "QUEEN", "WORKER-1", "Implement the login endpoint"),
+                line(1, "WORKER-1", "QUEEN", "PROGRESS: 1/2"),
+                line(4, "WORKER-1", "QUEEN", "COMPLETED: endpoint added"),
+                line(5, "QUEEN", "WORKER-2", "TASK: write docs"),
+                line(6, "WORKER-2", "QUEEN", "ERROR: docs folder missing"),
+            ]
+            .concat(),
+        )
+        .unwrap();
+
+        let stats = sync(&log).unwrap();
+        assert_eq!(stats.total_messages, 5);
+        assert_eq!(stats.by_type[TASK], 2);
+        assert_eq!(stats.by_type[ERROR], 1);
+        assert_eq!(stats.by_sender["WORKER-1"], 2);
+        assert_eq!(stats.error_rate, 0.2);
+        assert_eq!(stats.workers["WORKER-1"].avg_task_secs, Some(240.0));
+        assert!(stats.workers["WORKER-1"].open_tasks.is_empty());
+        assert_eq!(stats.workers["WORKER-2"].error_rate, 1.0);
+        assert_eq!(stats.workers["WORKER-2"].open_tasks.len(), 1);
+
+        // Only the appended line is read, and a half-written line waits for its newline.
+        let mut file = fs::OpenOptions::new().append(true).open(&log).unwrap();
+        io::Write::write_all(
+            &mut file,
+            format!(
+                "{}[2026-01-01T10:09:00Z] WORKER-2",
+                line(8, "WORKER-2", "QUEEN", "DONE")
+            )
+            .as_bytes(),
+        )
+        .unwrap();
+        let stats = sync(&log).unwrap();
+        assert_eq!(stats.total_messages, 6);
+        assert_eq!(stats.workers["WORKER-2"].avg_task_secs, Some(180.0));
+    }
+}
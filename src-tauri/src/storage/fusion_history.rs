@@ -0,0 +1,225 @@
+//! Outcomes of Fusion sessions, kept across sessions and projects.
+//!
+//! When the operator merges a Fusion winner, a line is appended to the app-wide
+//! `fusion-history.jsonl`: each variant's CLI and model, whether it won, and the
+//! judges' votes and rubric scores for it. [`leaderboard`] folds the file into win
+//! rates per CLI and model, which is what to look at when picking the variants of
+//! the next Fusion run.
+
+use std::collections::BTreeMap;
+use std::fs::{self, OpenOptions};
+use std::io::{self, Write};
+use std::path::Path;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+pub const FUSION_HISTORY_FILE: &str = "fusion-history.jsonl";
+
+/// One line of `fusion-history.jsonl`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FusionOutcome {
+    pub session_id: String,
+    pub project_path: String,
+    pub recorded_at: DateTime<Utc>,
+    /// Name of the merged variant.
+    pub winner: String,
+    pub variants: Vec<FusionVariantOutcome>,
+    /// Keywords of the session's task (see [`keywords`]), so a new task can be
+    /// matched against the runs most like it.
+    #[serde(default)]
+    pub task_keywords: Vec<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FusionVariantOutcome {
+    pub name: String,
+    pub cli: String,
+    pub model: Option<String>,
+    pub won: bool,
+    /// Judges that named this variant the winner.
+    pub votes: u8,
+    /// Mean of the judges' rubric scores (0-10), if any judge scored it.
+    pub score: Option<f64>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LeaderboardEntry {
+    pub cli: String,
+    pub model: Option<String>,
+    /// Fusion sessions a variant with this CLI and model ran in.
+    pub runs: u32,
+    pub wins: u32,
+    pub win_rate: f64,
+    /// Mean rubric score over the runs that were scored.
+    pub average_score: Option<f64>,
+}
+
+pub fn append(history_path: &Path, outcome: &FusionOutcome) -> io::Result<()> {
+    let mut line = serde_json::to_string(outcome).map_err(io::Error::other)?;
+    line.push('\n');
+    OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(history_path)?
+        .write_all(line.as_bytes())
+}
+
+/// Every outcome in the history at `history_path`, oldest first; empty when nothing
+/// has been recorded. Lines that do not parse are skipped.
+pub fn read(history_path: &Path) -> io::Result<Vec<FusionOutcome>> {
+    let content = match fs::read_to_string(history_path) {
+        Ok(content) => content,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(e),
+    };
+    Ok(content
+        .lines()
+        .filter_map(|line| serde_json::from_str(line).ok())
+        .collect())
+}
+
+/// Win rates per CLI and model in the history at `history_path`, best first.
+pub fn leaderboard(history_path: &Path) -> io::Result<Vec<LeaderboardEntry>> {
+    Ok(rank(&read(history_path)?))
+}
+
+/// Win rates per CLI and model over `outcomes`, best first.
+pub fn rank(outcomes: &[FusionOutcome]) -> Vec<LeaderboardEntry> {
+    // (runs, wins, score sum, scored runs) per (cli, model).
+    let mut totals: BTreeMap<(String, Option<String>), (u32, u32, f64, u32)> = BTreeMap::new();
+    for outcome in outcomes {
+        for variant in &outcome.variants {
+            let key = (variant.cli.clone(), variant.model.clone());
+            let total = totals.entry(key).or_default();
+            total.0 += 1;
+            total.1 += u32::from(variant.won);
+            if let Some(score) = variant.score {
+                total.2 += score;
+                total.3 += 1;
+            }
+        }
+    }
+
+    let mut entries: Vec<LeaderboardEntry> = totals
+        .into_iter()
+        .map(|((cli, model), (runs, wins, score_sum, scored))| LeaderboardEntry {
+            cli,
+            model,
+            runs,
+            wins,
+            win_rate: f64::from(wins) / f64::from(runs),
+            average_score: (scored > 0).then_some(score_sum / f64::from(scored)),
+        })
+        .collect();
+    entries.sort_by(|a, b| {
+        b.win_rate
+            .total_cmp(&a.win_rate)
+            .then(b.runs.cmp(&a.runs))
+            .then_with(|| (&a.cli, &a.model).cmp(&(&b.cli, &b.model)))
+    });
+    entries
+}
+
+const STOP_WORDS: &[&str] = &[
+    "add", "all", "and", "any", "are", "but", "can", "for", "from", "has", "have", "into", "its",
+    "make", "new", "not", "off", "one", "our", "out", "should", "that", "the", "then", "this",
+    "use", "was", "when", "which", "will", "with",
+];
+
+/// The distinct lowercase words of three or more letters in `text` that say
+/// something about the task, sorted.
+pub fn keywords(text: &str) -> Vec<String> {
+    let mut words: Vec<String> = text
+        .split(|c: char| !c.is_alphanumeric())
+        .map(str::to_lowercase)
+        .filter(|word| word.chars().count() >= 3 && !STOP_WORDS.contains(&word.as_str()))
+        .collect();
+    words.sort();
+    words.dedup();
+    words
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn variant(
+        cli: &str,
+        model: Option<&str>,
+        won: bool,
+        score: Option<f64>,
+    ) -> FusionVariantOutcome {
+        FusionVariantOutcome {
+            name: format!("{}-variant", cli),
+            cli: cli.to_string(),
+            model: model.map(str::to_string),
+            won,
+            votes: u8::from(won),
+            score,
+        }
+    }
+
+    #[test]
+    fn test_leaderboard_ranks_cli_and_model_by_win_rate() {
+        let temp = tempfile::tempdir().unwrap();
+        let path = temp.path().join(FUSION_HISTORY_FILE);
+        assert!(leaderboard(&path).unwrap().is_empty());
+
+        let outcome = |session_id: &str, variants| FusionOutcome {
+            session_id: session_id.to_string(),
+            project_path: "/repo".to_string(),
+            recorded_at: Utc::now(),
+            winner: "claude-variant".to_string(),
+            variants,
+            task_keywords: keywords("Add the login form"),
+        };
+        append(
+            &path,
+            &outcome(
+                "f1",
+                vec![
+                    variant("claude", Some("opus"), true, Some(8.0)),
+                    variant("codex", None, false, Some(6.0)),
+                ],
+            ),
+        )
+        .unwrap();
+        append(
+            &path,
+            &outcome(
+                "f2",
+                vec![
+                    variant("claude", Some("opus"), false, None),
+                    variant("codex", None, true, Some(9.0)),
+                    variant("claude", Some("sonnet"), false, Some(5.0)),
+                ],
+            ),
+        )
+        .unwrap();
+        fs::OpenOptions::new()
+            .append(true)
+            .open(&path)
+            .unwrap()
+            .write_all(b"not json\n")
+            .unwrap();
+
+        let board = leaderboard(&path).unwrap();
+        let ranked: Vec<_> = board
+            .iter()
+            .map(|entry| (entry.cli.as_str(), entry.model.as_deref(), entry.runs, entry.wins))
+            .collect();
+        assert_eq!(
+            ranked,
+            vec![
+                ("claude", Some("opus"), 2, 1),
+                ("codex", None, 2, 1),
+                ("claude", Some("sonnet"), 1, 0),
+            ]
+        );
+        assert_eq!(board[0].win_rate, 0.5);
+        assert_eq!(board[0].average_score, Some(8.0));
+        assert_eq!(board[1].average_score, Some(7.5));
+        assert_eq!(read(&path).unwrap()[0].task_keywords, ["form", "login"]);
+    }
+}
@@ -0,0 +1,89 @@
+//! Which of the Hive Manager instances running for one user this process is.
+//!
+//! At startup each instance claims the lowest free `instances/<n>.lock` in the app
+//! data directory by taking an exclusive lock on it. The lock is held until the
+//! process exits, so a crashed instance frees its number. Instance 0 keeps its
+//! sessions in `sessions/` as before; instance `n` keeps them in
+//! `instances/<n>/sessions/`, so two instances never load, persist or resume each
+//! other's sessions. Config, templates and profiles stay shared.
+
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+
+use fs2::FileExt;
+
+/// Most instances that may run at once.
+pub const MAX_INSTANCES: u32 = 16;
+
+/// This process's instance number and the lock file that holds it.
+static CLAIMED: OnceLock<(u32, File)> = OnceLock::new();
+
+/// Claim the lowest free instance number under `base_dir` for this process.
+/// Claiming again returns the number already held.
+pub fn claim(base_dir: &Path) -> io::Result<u32> {
+    if let Some((number, _)) = CLAIMED.get() {
+        return Ok(*number);
+    }
+    let (number, lock) = claim_in(base_dir)?;
+    let _ = CLAIMED.set((number, lock));
+    Ok(current())
+}
+
+/// This process's instance number; 0 until one is claimed.
+pub fn current() -> u32 {
+    CLAIMED.get().map_or(0, |(number, _)| *number)
+}
+
+/// The sessions directory of `instance` under `base_dir`.
+pub fn sessions_dir(base_dir: &Path, instance: u32) -> PathBuf {
+    match instance {
+        0 => base_dir.join("sessions"),
+        n => base_dir.join("instances").join(n.to_string()).join("sessions"),
+    }
+}
+
+fn claim_in(base_dir: &Path) -> io::Result<(u32, File)> {
+    let dir = base_dir.join("instances");
+    fs::create_dir_all(&dir)?;
+    for number in 0..MAX_INSTANCES {
+        let mut file = OpenOptions::new()
+            .create(true)
+            .truncate(false)
+            .write(true)
+            .open(dir.join(format!("{}.lock", number)))?;
+        if file.try_lock_exclusive().is_err() {
+            continue;
+        }
+        file.set_len(0)?;
+        writeln!(file, "{}", std::process::id())?;
+        return Ok((number, file));
+    }
+    Err(io::Error::other(format!(
+        "{} Hive Manager instances are already running",
+        MAX_INSTANCES
+    )))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_instances_claim_the_lowest_free_number() {
+        let temp = tempfile::tempdir().unwrap();
+        let (first, first_lock) = claim_in(temp.path()).unwrap();
+        let (second, _second_lock) = claim_in(temp.path()).unwrap();
+        assert_eq!((first, second), (0, 1));
+
+        drop(first_lock);
+        assert_eq!(claim_in(temp.path()).unwrap().0, 0);
+
+        assert_eq!(sessions_dir(temp.path(), 0), temp.path().join("sessions"));
+        assert_eq!(
+            sessions_dir(temp.path(), 2),
+            temp.path().join("instances").join("2").join("sessions")
+        );
+    }
+}
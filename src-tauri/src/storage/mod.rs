@@ -1,4 +1,4 @@
-use std::collections::{hash_map::DefaultHasher, HashMap};
+use std::collections::{hash_map::DefaultHasher, HashMap, HashSet};
 use std::fs;
 use std::fs::OpenOptions;
 use std::hash::{Hash, Hasher};
@@ -12,7 +12,8 @@ use parking_lot::Mutex;
 use serde::{Deserialize, Serialize};
 use thiserror::Error;
 
-use crate::coordination::CoordinationMessage;
+use crate::adapters::SubmitKey;
+use crate::coordination::{CoordinationMessage, MessageDelivery};
 use crate::domain::{ArtifactBundle, ResolverOutput};
 use crate::session::cell_status::PRIMARY_CELL_ID;
 use crate::session::DEFAULT_MAX_QA_ITERATIONS;
@@ -21,12 +22,30 @@ use crate::templates::SessionTemplate;
 pub mod application_state;
 pub use application_state::{ApplicationStateDb, ApplicationStateRow};
 
+pub mod api_usage;
+
+pub mod attachments;
+
+pub mod backend;
+pub use backend::{MemoryStorage, Storage};
+
+pub mod coordination_log;
+pub mod coordination_stats;
+
+pub mod fusion_history;
+
+pub mod instance;
+
 pub mod run_journal;
 pub use run_journal::RunJournalStore;
 
 pub mod queue;
 pub use queue::QueueRepo;
 
+pub mod scratchpad;
+
+pub mod secrets;
+
 /// Generate a deterministic ID for legacy learnings that lack one.
 /// Uses UUID v5 (SHA-1 namespace hash) from concatenated fields so the same
 /// entry always produces the same ID across reads.
@@ -54,6 +73,36 @@ fn stable_learning_id(learning: &Learning) -> String {
     Uuid::new_v5(&Uuid::NAMESPACE_DNS, content.as_bytes()).to_string()
 }
 
+/// Parse a `learnings.jsonl` file, giving entries without an ID their stable one.
+/// A missing file reads as empty and malformed lines are skipped.
+fn read_learnings_file(path: &Path) -> Result<Vec<Learning>, StorageError> {
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+
+    let content = fs::read_to_string(path)?;
+    let mut learnings = Vec::new();
+
+    for line in content.lines() {
+        if line.trim().is_empty() {
+            continue;
+        }
+        match serde_json::from_str::<Learning>(line) {
+            Ok(mut learning) => {
+                if learning.id.is_empty() {
+                    learning.id = stable_learning_id(&learning);
+                }
+                learnings.push(learning);
+            }
+            Err(e) => {
+                tracing::warn!("Failed to parse learning line: {}. Error: {}", line, e);
+            }
+        }
+    }
+
+    Ok(learnings)
+}
+
 fn deserialize_optional_trimmed_string<'de, D>(deserializer: D) -> Result<Option<String>, D::Error>
 where
     D: serde::Deserializer<'de>,
@@ -69,17 +118,54 @@ where
     }))
 }
 
+/// Schema version stamped on learnings written by this build. Records without a
+/// `schema` field predate categories and confidence and read as version 1.
+pub const LEARNING_SCHEMA_VERSION: u32 = 2;
+
+fn legacy_learning_schema() -> u32 {
+    1
+}
+
+/// What `import_legacy_learnings` moved out of a project's `.ai-docs`.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct LegacyLearningsImport {
+    pub imported: usize,
+    /// Entries the target store already held, or repeated in the legacy file.
+    pub duplicates: usize,
+    /// Lines that did not parse; they survive only in the archive.
+    pub malformed: usize,
+    /// Where the legacy file was archived; `None` when there was nothing to import.
+    pub archived_to: Option<PathBuf>,
+}
+
+/// A learning record. Version 1 records carry task/outcome/keywords; version 2
+/// adds an optional category, confidence and source agent, and accepts judge-style
+/// records (`content`, `category`, `source`) that have no task or outcome.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Learning {
     #[serde(default = "generate_learning_id")]
     pub id: String,
+    #[serde(default = "legacy_learning_schema")]
+    pub schema: u32,
     pub date: String,
     pub session: String,
+    #[serde(default)]
     pub task: String,
+    #[serde(default)]
     pub outcome: String,
+    #[serde(default)]
     pub keywords: Vec<String>,
+    #[serde(alias = "content")]
     pub insight: String,
+    #[serde(default)]
     pub files_touched: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub category: Option<String>,
+    /// How sure the submitting agent is, from 0.0 to 1.0.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub confidence: Option<f64>,
+    #[serde(default, alias = "source", skip_serializing_if = "Option::is_none")]
+    pub source_agent: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -150,6 +236,36 @@ pub struct SessionSummary {
     pub state: String,
 }
 
+fn remove_if_exists(path: &Path) -> Result<(), StorageError> {
+    match fs::remove_file(path) {
+        Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(e.into()),
+        _ => Ok(()),
+    }
+}
+
+/// Listing summary of a persisted session.
+fn session_summary(session: PersistedSession) -> SessionSummary {
+    let session_type = match &session.session_type {
+        SessionTypeInfo::Hive { worker_count } => format!("Hive ({})", worker_count),
+        SessionTypeInfo::Swarm { planner_count } => format!("Swarm ({})", planner_count),
+        SessionTypeInfo::Fusion { variants } => format!("Fusion ({})", variants.len()),
+        SessionTypeInfo::Debate { variants } => format!("Debate ({})", variants.len()),
+        SessionTypeInfo::Solo { cli, .. } => format!("Solo ({})", cli),
+    };
+
+    SessionSummary {
+        id: session.id,
+        name: session.name,
+        color: session.color,
+        session_type,
+        project_path: session.project_path,
+        created_at: session.created_at,
+        last_activity_at: session.last_activity_at.unwrap_or(session.created_at),
+        agent_count: session.agents.len(),
+        state: session.state,
+    }
+}
+
 /// Persisted session metadata
 #[derive(Debug, Clone, Serialize, Deserialize, Hash)]
 pub struct PersistedSession {
@@ -195,6 +311,12 @@ pub struct PersistedSession {
     /// app restarts (defaults to false for sessions persisted before this field).
     #[serde(default)]
     pub no_git: bool,
+    /// URL of the pull request opened for the session branch, once created.
+    #[serde(default)]
+    pub pull_request_url: Option<String>,
+    /// Mirror of `Session::spawn_quota`, so a quota survives restarts and resumes.
+    #[serde(default, skip_serializing_if = "crate::session::SpawnQuota::is_empty")]
+    pub spawn_quota: crate::session::SpawnQuota,
 }
 
 fn default_cli() -> String {
@@ -256,25 +378,43 @@ pub struct SessionRefreshCandidate {
     file_modified_at: SystemTime,
 }
 
+/// Profile whose config lives at the storage root (`config.json`), so installs that
+/// predate profiles keep their existing config.
+pub const DEFAULT_PROFILE: &str = "default";
+
 /// Manages session storage in %APPDATA%/hive-manager
 pub struct SessionStorage {
     base_dir: PathBuf,
+    /// The instance whose sessions this storage holds (see `instance`).
+    instance: u32,
+    /// Config profile that `load_config`/`save_config` read and write.
+    active_profile: Mutex<String>,
+    /// `coordination.max_content_chars` with the config file and modification time it
+    /// was read from, so appending a message does not parse the config every time.
+    max_content_chars: Mutex<Option<(PathBuf, Option<SystemTime>, usize)>>,
     artifact_locks: Mutex<HashMap<String, Arc<Mutex<()>>>>,
     session_sync: Mutex<HashMap<String, SessionSyncState>>,
+    /// Held while a `session.json` and its journal are written or recovered.
+    session_writes: Mutex<()>,
 }
 
 impl SessionStorage {
-    /// Create a new SessionStorage, initializing the base directory if needed
+    /// Create a new SessionStorage, initializing the base directory if needed.
+    /// Sessions are those of the instance this process claimed.
     pub fn new() -> Result<Self, StorageError> {
         let base_dir = Self::get_app_data_dir()?;
-        Self::new_with_base(base_dir)
+        Self::open(base_dir, instance::current())
     }
 
     /// Create a SessionStorage with a custom base directory (for testing)
     pub fn new_with_base(base_dir: PathBuf) -> Result<Self, StorageError> {
+        Self::open(base_dir, 0)
+    }
+
+    fn open(base_dir: PathBuf, instance: u32) -> Result<Self, StorageError> {
         fs::create_dir_all(&base_dir)?;
         fs::create_dir_all(base_dir.join("templates").join("roles"))?;
-        fs::create_dir_all(base_dir.join("sessions"))?;
+        fs::create_dir_all(instance::sessions_dir(&base_dir, instance))?;
 
         // Create default config if it doesn't exist
         let config_path = base_dir.join("config.json");
@@ -283,27 +423,26 @@ impl SessionStorage {
             fs::write(&config_path, serde_json::to_string_pretty(&default_config)?)?;
         }
 
+        let active_profile = fs::read_to_string(base_dir.join("active-profile"))
+            .ok()
+            .map(|name| name.trim().to_string())
+            .filter(|name| validate_profile_name(name).is_ok())
+            .unwrap_or_else(|| DEFAULT_PROFILE.to_string());
+
         Ok(Self {
             base_dir,
+            instance,
+            active_profile: Mutex::new(active_profile),
+            max_content_chars: Mutex::new(None),
             artifact_locks: Mutex::new(HashMap::new()),
             session_sync: Mutex::new(HashMap::new()),
+            session_writes: Mutex::new(()),
         })
     }
 
     /// Get the app data directory path
     fn get_app_data_dir() -> Result<PathBuf, StorageError> {
-        #[cfg(windows)]
-        {
-            std::env::var("APPDATA")
-                .map(|p| PathBuf::from(p).join("hive-manager"))
-                .map_err(|_| StorageError::InvalidPath("APPDATA not set".to_string()))
-        }
-        #[cfg(not(windows))]
-        {
-            std::env::var("HOME")
-                .map(|p| PathBuf::from(p).join(".config").join("hive-manager"))
-                .map_err(|_| StorageError::InvalidPath("HOME not set".to_string()))
-        }
+        crate::workspace::paths::app_data_dir().map_err(StorageError::InvalidPath)
     }
 
     /// Get the base directory path
@@ -324,7 +463,12 @@ impl SessionStorage {
 
     /// Get path to sessions directory
     pub fn sessions_dir(&self) -> PathBuf {
-        self.base_dir.join("sessions")
+        instance::sessions_dir(&self.base_dir, self.instance)
+    }
+
+    /// The instance whose sessions this storage holds.
+    pub fn instance(&self) -> u32 {
+        self.instance
     }
 
     /// Get path to a specific session directory
@@ -379,7 +523,12 @@ impl SessionStorage {
         Ok(())
     }
 
-    /// Save session metadata to disk
+    /// Save session metadata to disk.
+    ///
+    /// The new contents are first synced to a journal next to `session.json`, which
+    /// is then replaced in one rename. A crash part-way leaves either the old file and
+    /// a complete journal, which [`Self::load_session`] replays, or an incomplete
+    /// journal next to the intact old file.
     #[allow(dead_code)]
     pub fn save_session(&self, session: &PersistedSession) -> Result<(), StorageError> {
         let session_dir = self.session_dir(&session.id);
@@ -387,15 +536,50 @@ impl SessionStorage {
             self.create_session_dir(&session.id)?;
         }
 
+        let _write = self.session_writes.lock();
+        let journal = self.session_journal_path(&session.id);
+        let mut file = fs::File::create(&journal)?;
+        serde_json::to_writer_pretty(&mut file, session)?;
+        file.sync_all()?;
+        drop(file);
+
         let session_file = self.session_file_path(&session.id);
         self.atomic_write_json(&session_file, session)?;
+        remove_if_exists(&journal)?;
         self.mark_session_synced(&session.id, session)?;
 
         Ok(())
     }
 
+    /// Finish a `save_session` a crash interrupted: write a complete journal over
+    /// `session.json`, and drop one that was cut short.
+    fn recover_session_journal(&self, session_id: &str) -> Result<(), StorageError> {
+        let journal = self.session_journal_path(session_id);
+        if !journal.exists() {
+            return Ok(());
+        }
+        let _write = self.session_writes.lock();
+        // A save that finished while we waited for the lock removed it.
+        let json = match fs::read_to_string(&journal) {
+            Ok(json) => json,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(()),
+            Err(e) => return Err(e.into()),
+        };
+        match serde_json::from_str::<PersistedSession>(&json) {
+            Ok(session) => {
+                tracing::warn!("Replaying the interrupted save of session {}", session_id);
+                self.atomic_write_json(&self.session_file_path(session_id), &session)?;
+            }
+            Err(e) => {
+                tracing::warn!("Dropping the incomplete journal of session {}: {}", session_id, e);
+            }
+        }
+        remove_if_exists(&journal)
+    }
+
     /// Load session metadata from disk
     pub fn load_session(&self, session_id: &str) -> Result<PersistedSession, StorageError> {
+        self.recover_session_journal(session_id)?;
         let session_file = self.session_file_path(session_id);
         if !session_file.exists() {
             return Err(StorageError::SessionNotFound(session_id.to_string()));
@@ -502,33 +686,7 @@ impl SessionStorage {
             if entry.file_type()?.is_dir() {
                 let session_id = entry.file_name().to_string_lossy().to_string();
                 if let Ok(session) = self.load_session(&session_id) {
-                    let session_type = match &session.session_type {
-                        SessionTypeInfo::Hive { worker_count } => {
-                            format!("Hive ({})", worker_count)
-                        }
-                        SessionTypeInfo::Swarm { planner_count } => {
-                            format!("Swarm ({})", planner_count)
-                        }
-                        SessionTypeInfo::Fusion { variants } => {
-                            format!("Fusion ({})", variants.len())
-                        }
-                        SessionTypeInfo::Debate { variants } => {
-                            format!("Debate ({})", variants.len())
-                        }
-                        SessionTypeInfo::Solo { cli, .. } => format!("Solo ({})", cli),
-                    };
-
-                    summaries.push(SessionSummary {
-                        id: session.id,
-                        name: session.name,
-                        color: session.color,
-                        session_type,
-                        project_path: session.project_path,
-                        created_at: session.created_at,
-                        last_activity_at: session.last_activity_at.unwrap_or(session.created_at),
-                        agent_count: session.agents.len(),
-                        state: session.state,
-                    });
+                    summaries.push(session_summary(session));
                 }
             }
         }
@@ -539,6 +697,18 @@ impl SessionStorage {
         Ok(summaries)
     }
 
+    /// IDs of every stored session, without loading them.
+    pub fn session_ids(&self) -> Vec<String> {
+        let Ok(entries) = fs::read_dir(self.sessions_dir()) else {
+            return Vec::new();
+        };
+        entries
+            .flatten()
+            .filter(|entry| entry.file_type().is_ok_and(|kind| kind.is_dir()))
+            .map(|entry| entry.file_name().to_string_lossy().to_string())
+            .collect()
+    }
+
     /// Delete a session and all its files
     #[allow(dead_code)]
     pub fn delete_session(&self, session_id: &str) -> Result<(), StorageError> {
@@ -546,12 +716,144 @@ impl SessionStorage {
         if session_dir.exists() {
             fs::remove_dir_all(session_dir)?;
         }
+        let token_path = self.session_api_token_path(session_id);
+        if token_path.exists() {
+            fs::remove_file(token_path)?;
+        }
+        Ok(())
+    }
+
+    /// Kept outside the session directory so issuing a token never makes a session look
+    /// initialized to `save_session`.
+    fn session_api_token_path(&self, session_id: &str) -> PathBuf {
+        self.base_dir.join("session-tokens").join(session_id)
+    }
+
+    /// Persist the API token issued to a session's agents.
+    pub fn save_session_api_token(
+        &self,
+        session_id: &str,
+        token: &str,
+    ) -> Result<(), StorageError> {
+        let path = self.session_api_token_path(session_id);
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(path, token)?;
         Ok(())
     }
 
-    /// Get the config file path
+    /// Load a session's API token; `None` if none was ever issued.
+    pub fn load_session_api_token(&self, session_id: &str) -> Result<Option<String>, StorageError> {
+        match fs::read_to_string(self.session_api_token_path(session_id)) {
+            Ok(token) => Ok(Some(token.trim().to_string())),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    /// Get the config file path of the active profile
     pub fn config_path(&self) -> PathBuf {
-        self.base_dir.join("config.json")
+        self.profile_config_path(&self.active_profile.lock())
+    }
+
+    /// The default profile keeps the root `config.json`; named profiles live under
+    /// `profiles/<name>/config.json`.
+    fn profile_config_path(&self, profile: &str) -> PathBuf {
+        if profile == DEFAULT_PROFILE {
+            self.base_dir.join("config.json")
+        } else {
+            self.base_dir
+                .join("profiles")
+                .join(profile)
+                .join("config.json")
+        }
+    }
+
+    /// Name of the config profile currently in use.
+    pub fn active_profile(&self) -> String {
+        self.active_profile.lock().clone()
+    }
+
+    /// List every config profile, default first and the rest sorted by name.
+    pub fn list_profiles(&self) -> Result<Vec<String>, StorageError> {
+        let mut profiles = Vec::new();
+        let profiles_dir = self.base_dir.join("profiles");
+        if profiles_dir.exists() {
+            for entry in fs::read_dir(profiles_dir)? {
+                let entry = entry?;
+                let name = entry.file_name().to_string_lossy().to_string();
+                if validate_profile_name(&name).is_ok()
+                    && name != DEFAULT_PROFILE
+                    && entry.path().join("config.json").is_file()
+                {
+                    profiles.push(name);
+                }
+            }
+        }
+        profiles.sort();
+        profiles.insert(0, DEFAULT_PROFILE.to_string());
+        Ok(profiles)
+    }
+
+    /// Make `profile` the active config profile, creating it from the default config
+    /// if it does not exist yet, and persist the choice for the next launch. A new
+    /// profile never inherits another profile's CLI registry, env, or API port. A
+    /// profile whose config cannot be read is refused and the active one is kept.
+    pub fn switch_profile(&self, profile: &str) -> Result<AppConfig, StorageError> {
+        let config = self.open_profile(profile)?;
+        fs::write(self.base_dir.join("active-profile"), profile)?;
+        self.set_active_profile(profile);
+        Ok(config)
+    }
+
+    /// Make `profile` the active config profile for this run only, as
+    /// [`Self::switch_profile`] does but leaving the saved choice alone.
+    pub fn use_profile(&self, profile: &str) -> Result<AppConfig, StorageError> {
+        let config = self.open_profile(profile)?;
+        self.set_active_profile(profile);
+        Ok(config)
+    }
+
+    /// Apply the `--profile` launch argument. A profile that is malformed or cannot
+    /// be created falls back to the default one; neither replaces the saved choice.
+    pub fn use_launch_profile(&self, profile: &str) {
+        let Err(e) = self.use_profile(profile) else {
+            return;
+        };
+        tracing::warn!(
+            "Failed to switch to config profile {}, using {}: {}",
+            profile,
+            DEFAULT_PROFILE,
+            e
+        );
+        if let Err(e) = self.use_profile(DEFAULT_PROFILE) {
+            tracing::warn!("Failed to switch to config profile {}: {}", DEFAULT_PROFILE, e);
+        }
+    }
+
+    /// The config of `profile`, written from the default config first if it does
+    /// not exist yet.
+    fn open_profile(&self, profile: &str) -> Result<AppConfig, StorageError> {
+        validate_profile_name(profile).map_err(StorageError::InvalidPath)?;
+
+        let config_path = self.profile_config_path(profile);
+        if !config_path.exists() {
+            if let Some(parent) = config_path.parent() {
+                fs::create_dir_all(parent)?;
+            }
+            fs::write(
+                &config_path,
+                serde_json::to_string_pretty(&Self::default_config())?,
+            )?;
+        }
+
+        Ok(serde_json::from_str(&fs::read_to_string(&config_path)?)?)
+    }
+
+    fn set_active_profile(&self, profile: &str) {
+        *self.active_profile.lock() = profile.to_string();
+        *self.max_content_chars.lock() = None;
     }
 
     /// Load the app config
@@ -572,6 +874,7 @@ impl SessionStorage {
     pub fn save_config(&self, config: &AppConfig) -> Result<(), StorageError> {
         let json = serde_json::to_string_pretty(config)?;
         fs::write(self.config_path(), json)?;
+        *self.max_content_chars.lock() = None;
         Ok(())
     }
 
@@ -587,6 +890,9 @@ impl SessionStorage {
                 model_flag: Some("--model".to_string()),
                 default_model: "opus".to_string(),
                 env: None,
+                submit_key: None,
+                path_prepend: Vec::new(),
+                env_allowlist: None,
             },
         );
 
@@ -602,6 +908,9 @@ impl SessionStorage {
                     env.insert("OPENCODE_YOLO".to_string(), "true".to_string());
                     env
                 }),
+                submit_key: None,
+                path_prepend: Vec::new(),
+                env_allowlist: None,
             },
         );
 
@@ -613,6 +922,9 @@ impl SessionStorage {
                 model_flag: Some("-m".to_string()),
                 default_model: "gpt-5.6-sol".to_string(),
                 env: None,
+                submit_key: None,
+                path_prepend: Vec::new(),
+                env_allowlist: None,
             },
         );
 
@@ -624,6 +936,9 @@ impl SessionStorage {
                 model_flag: None, // Cursor uses global model setting
                 default_model: "composer-2.5".to_string(),
                 env: None,
+                submit_key: None,
+                path_prepend: Vec::new(),
+                env_allowlist: None,
             },
         );
 
@@ -635,6 +950,9 @@ impl SessionStorage {
                 model_flag: None,        // Model selected via /model command in TUI
                 default_model: "glm-5.1".to_string(),
                 env: None,
+                submit_key: None,
+                path_prepend: Vec::new(),
+                env_allowlist: None,
             },
         );
 
@@ -646,6 +964,9 @@ impl SessionStorage {
                 model_flag: Some("-m".to_string()),
                 default_model: "qwen3-coder".to_string(),
                 env: None,
+                submit_key: None,
+                path_prepend: Vec::new(),
+                env_allowlist: None,
             },
         );
 
@@ -755,13 +1076,37 @@ impl SessionStorage {
             api: ApiConfig {
                 enabled: true,
                 port: 18800,
+                offline: false,
+                socket_path: None,
             },
             global_wiki_path: default_global_wiki_path(),
             knowledge_wiki_folders: None,
+            checkpoints: CheckpointConfig::default(),
+            approvals: ApprovalConfig::default(),
+            grpc: GrpcConfig::default(),
+            concurrency: ConcurrencyConfig::default(),
+            preflight: PreflightMode::default(),
+            diff_budget: DiffBudgetConfig::default(),
+            locale: None,
+            project_dna: ProjectDnaConfig::default(),
+            file_watch: FileWatchConfig::default(),
+            context_monitor: ContextMonitorConfig::default(),
+            fusion_worktrees: FusionWorktreeConfig::default(),
+            exclude_session_artifacts: default_exclude_session_artifacts(),
+            auto_complete: AutoCompleteConfig::default(),
+            hooks: Vec::new(),
+            coordination: CoordinationConfig::default(),
+            inbox_digest: InboxDigestConfig::default(),
+            editor: EditorConfig::default(),
+            snapshots: SnapshotConfig::default(),
+            warm_pool: WarmPoolConfig::default(),
         }
     }
 
-    /// Append a message to the coordination log
+    /// Append a message to the coordination log under the session's next sequence
+    /// number, rotating it to `coordination.log.1` (and compacting the rotated
+    /// generation) once it reaches `coordination_log::MAX_LOG_BYTES`. Content over
+    /// the configured `coordination.max_content_chars` spills over into an attachment.
     pub fn append_coordination_log(
         &self,
         session_id: &str,
@@ -771,29 +1116,55 @@ impl SessionStorage {
             .session_dir(session_id)
             .join("coordination")
             .join("coordination.log");
+        let content = attachments::spill_over(
+            &self.attachments_dir(session_id),
+            &attachments::with_references(&message.content, &message.attachments),
+            self.coordination_max_content_chars(),
+        )?;
 
-        let line = format!(
-            "[{}] {} → {}: {}\n",
-            message.timestamp.format("%Y-%m-%dT%H:%M:%SZ"),
-            message.from,
-            message.to,
-            message.content
-        );
-
-        use std::fs::OpenOptions;
-        use std::io::Write;
-
-        let mut file = OpenOptions::new()
-            .create(true)
-            .append(true)
-            .open(log_path)?;
-
-        file.write_all(line.as_bytes())?;
+        coordination_log::append_sequenced(
+            &log_path,
+            |seq| {
+                coordination_log::format_line(
+                    message.timestamp,
+                    seq,
+                    &message.from,
+                    &message.to,
+                    &content,
+                )
+            },
+            coordination_log::MAX_LOG_BYTES,
+            coordination_log::MAX_ROTATIONS,
+        )?;
 
         Ok(())
     }
 
-    /// Read the coordination log
+    /// The active config's `coordination.max_content_chars`, read again only once the
+    /// config file was saved through this storage or changed on disk.
+    fn coordination_max_content_chars(&self) -> usize {
+        let path = self.config_path();
+        let modified = fs::metadata(&path)
+            .and_then(|metadata| metadata.modified())
+            .ok();
+        let mut cached = self.max_content_chars.lock();
+        if let Some((cached_path, cached_modified, max_chars)) = cached.as_ref() {
+            if *cached_path == path && modified.is_some() && *cached_modified == modified {
+                return *max_chars;
+            }
+        }
+        let max_chars = self
+            .load_config()
+            .map(|config| config.coordination)
+            .unwrap_or_default()
+            .max_content_chars;
+        *cached = Some((path, modified, max_chars));
+        max_chars
+    }
+
+    /// Read the coordination log, spanning rotated generations, ordered by timestamp
+    /// and then sequence number. A `limit` keeps only the most recent lines and stops
+    /// reading older generations early.
     pub fn read_coordination_log(
         &self,
         session_id: &str,
@@ -804,45 +1175,257 @@ impl SessionStorage {
             .join("coordination")
             .join("coordination.log");
 
-        if !log_path.exists() {
-            return Ok(vec![]);
-        }
-
-        let content = fs::read_to_string(log_path)?;
-        let lines: Vec<&str> = content.lines().collect();
-
-        let lines_to_parse = if let Some(limit) = limit {
-            lines.iter().rev().take(limit).rev().collect::<Vec<_>>()
-        } else {
-            lines.iter().collect()
-        };
+        let lines =
+            coordination_log::read_lines(&log_path, limit, coordination_log::MAX_ROTATIONS)?;
 
         let mut messages = Vec::new();
-        for line in lines_to_parse {
+        for line in &lines {
             if let Some(msg) = Self::parse_coordination_line(line) {
                 messages.push(msg);
             }
         }
+        // Stable, so equal keys (unsequenced lines in the same millisecond) stay in
+        // file order.
+        messages.sort_by_key(|message| (message.timestamp, message.seq));
 
         Ok(messages)
     }
 
+    /// Messages logged after the one numbered `after_seq`, for callers that poll
+    /// the log with the highest `seq` they have seen as a cursor.
+    pub fn read_coordination_log_after(
+        &self,
+        session_id: &str,
+        after_seq: u64,
+    ) -> Result<Vec<CoordinationMessage>, StorageError> {
+        let log_path = self
+            .session_dir(session_id)
+            .join("coordination")
+            .join("coordination.log");
+
+        let lines = coordination_log::read_lines_after(
+            &log_path,
+            after_seq,
+            coordination_log::MAX_ROTATIONS,
+        )?;
+
+        Ok(lines
+            .iter()
+            .filter_map(|line| Self::parse_coordination_line(line))
+            .collect())
+    }
+
+    /// TASK deliveries recorded for the session, oldest first.
+    pub fn load_message_deliveries(
+        &self,
+        session_id: &str,
+    ) -> Result<Vec<MessageDelivery>, StorageError> {
+        Ok(self
+            .read_optional_json(&self.message_deliveries_path(session_id))?
+            .unwrap_or_default())
+    }
+
+    pub fn save_message_deliveries(
+        &self,
+        session_id: &str,
+        deliveries: &[MessageDelivery],
+    ) -> Result<(), StorageError> {
+        let path = self.message_deliveries_path(session_id);
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        self.atomic_write_json(&path, &deliveries)
+    }
+
+    /// When each inbox's messages were last covered by a digest, keyed by inbox name.
+    pub fn load_inbox_digest_cursors(
+        &self,
+        session_id: &str,
+    ) -> Result<HashMap<String, DateTime<Utc>>, StorageError> {
+        Ok(self
+            .read_optional_json(&self.inbox_digest_cursors_path(session_id))?
+            .unwrap_or_default())
+    }
+
+    pub fn save_inbox_digest_cursors(
+        &self,
+        session_id: &str,
+        cursors: &HashMap<String, DateTime<Utc>>,
+    ) -> Result<(), StorageError> {
+        let path = self.inbox_digest_cursors_path(session_id);
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        self.atomic_write_json(&path, cursors)
+    }
+
+    fn inbox_digest_cursors_path(&self, session_id: &str) -> PathBuf {
+        self.session_dir(session_id)
+            .join("coordination")
+            .join("inbox-digests.json")
+    }
+
+    fn message_deliveries_path(&self, session_id: &str) -> PathBuf {
+        self.session_dir(session_id)
+            .join("coordination")
+            .join("deliveries.json")
+    }
+
+    /// Message counts and per-worker throughput for the session's coordination log.
+    pub fn coordination_stats(
+        &self,
+        session_id: &str,
+    ) -> Result<coordination_stats::CoordinationStats, StorageError> {
+        let log_path = self
+            .session_dir(session_id)
+            .join("coordination")
+            .join("coordination.log");
+        Ok(coordination_log::stats(&log_path)?)
+    }
+
+    /// Append an agent's API call to the session's `api-usage.jsonl`. Calls for
+    /// sessions without a directory are dropped rather than creating one.
+    pub fn record_api_call(
+        &self,
+        session_id: &str,
+        call: &api_usage::ApiCall,
+    ) -> Result<(), StorageError> {
+        let session_dir = self.session_dir(session_id);
+        if !session_dir.is_dir() {
+            return Ok(());
+        }
+        Ok(api_usage::append(
+            &session_dir.join(api_usage::API_USAGE_FILE),
+            call,
+        )?)
+    }
+
+    /// Per-agent and per-endpoint counts for the session's `api-usage.jsonl`.
+    pub fn api_usage_summary(
+        &self,
+        session_id: &str,
+    ) -> Result<api_usage::ApiUsageSummary, StorageError> {
+        Ok(api_usage::summarize(
+            &self.session_dir(session_id).join(api_usage::API_USAGE_FILE),
+        )?)
+    }
+
+    /// The session's `coordination/attachments/` directory.
+    pub fn attachments_dir(&self, session_id: &str) -> PathBuf {
+        self.session_dir(session_id)
+            .join("coordination")
+            .join(attachments::ATTACHMENTS_DIR)
+    }
+
+    /// Store a file for coordination messages to reference. Fails with
+    /// [`StorageError::SessionNotFound`] for a session without a directory.
+    pub fn save_attachment(
+        &self,
+        session_id: &str,
+        file_name: &str,
+        bytes: &[u8],
+    ) -> Result<attachments::Attachment, StorageError> {
+        if !self.session_dir(session_id).is_dir() {
+            return Err(StorageError::SessionNotFound(session_id.to_string()));
+        }
+        Ok(attachments::save(
+            &self.attachments_dir(session_id),
+            file_name,
+            bytes,
+        )?)
+    }
+
+    pub fn list_attachments(
+        &self,
+        session_id: &str,
+    ) -> Result<Vec<attachments::Attachment>, StorageError> {
+        Ok(attachments::list(&self.attachments_dir(session_id))?)
+    }
+
+    /// The session's `scratchpad/` directory.
+    pub fn scratchpad_dir(&self, session_id: &str) -> PathBuf {
+        self.session_dir(session_id).join(scratchpad::SCRATCHPAD_DIR)
+    }
+
+    /// Set a scratchpad key. Fails with [`StorageError::SessionNotFound`] for a
+    /// session without a directory.
+    pub fn put_scratchpad_entry(
+        &self,
+        session_id: &str,
+        key: &str,
+        value: &str,
+        updated_by: Option<&str>,
+    ) -> Result<scratchpad::ScratchpadEntry, StorageError> {
+        if !self.session_dir(session_id).is_dir() {
+            return Err(StorageError::SessionNotFound(session_id.to_string()));
+        }
+        Ok(scratchpad::put(
+            &self.scratchpad_dir(session_id),
+            key,
+            value,
+            updated_by,
+        )?)
+    }
+
+    pub fn scratchpad_entry(
+        &self,
+        session_id: &str,
+        key: &str,
+    ) -> Result<Option<scratchpad::ScratchpadEntry>, StorageError> {
+        Ok(scratchpad::get(&self.scratchpad_dir(session_id), key)?)
+    }
+
+    pub fn list_scratchpad(
+        &self,
+        session_id: &str,
+    ) -> Result<Vec<scratchpad::ScratchpadEntry>, StorageError> {
+        Ok(scratchpad::list(&self.scratchpad_dir(session_id))?)
+    }
+
+    /// Append a merged Fusion session's outcome to the app-wide `fusion-history.jsonl`.
+    pub fn record_fusion_outcome(
+        &self,
+        outcome: &fusion_history::FusionOutcome,
+    ) -> Result<(), StorageError> {
+        Ok(fusion_history::append(
+            &self.base_dir.join(fusion_history::FUSION_HISTORY_FILE),
+            outcome,
+        )?)
+    }
+
+    /// Every recorded Fusion outcome, oldest first.
+    pub fn fusion_history(&self) -> Result<Vec<fusion_history::FusionOutcome>, StorageError> {
+        Ok(fusion_history::read(
+            &self.base_dir.join(fusion_history::FUSION_HISTORY_FILE),
+        )?)
+    }
+
+    /// Win rates per CLI and model across every recorded Fusion session.
+    pub fn cli_leaderboard(&self) -> Result<Vec<fusion_history::LeaderboardEntry>, StorageError> {
+        Ok(fusion_history::leaderboard(
+            &self.base_dir.join(fusion_history::FUSION_HISTORY_FILE),
+        )?)
+    }
+
     /// Parse a coordination log line
     fn parse_coordination_line(line: &str) -> Option<CoordinationMessage> {
-        // Format: [2024-02-03T18:52:34Z] FROM → TO: content
+        // Format: [2024-02-03T18:52:34.123Z #42] FROM → TO: content
         let re = regex::Regex::new(r"^\[([^\]]+)\] ([^ ]+) → ([^:]+): (.*)$").ok()?;
         let caps = re.captures(line)?;
 
-        let timestamp = DateTime::parse_from_rfc3339(&caps[1])
+        let (timestamp, seq) = coordination_log::split_stamp(&caps[1]);
+        let timestamp = DateTime::parse_from_rfc3339(timestamp)
             .ok()?
             .with_timezone(&Utc);
 
         Some(CoordinationMessage {
             id: uuid::Uuid::new_v4().to_string(),
             timestamp,
+            seq,
             from: caps[2].to_string(),
             to: caps[3].to_string(),
             content: caps[4].to_string(),
+            attachments: attachments::references(&caps[4]),
             message_type: crate::coordination::MessageType::Task,
         })
     }
@@ -908,6 +1491,19 @@ impl SessionStorage {
         .map_err(|e| StorageError::InvalidPath(format!("Join error in read conversation: {}", e)))?
     }
 
+    /// Every message in the conversation, read on the calling thread.
+    pub fn conversation_messages(
+        &self,
+        session_id: &str,
+        agent_id: &str,
+    ) -> Result<Vec<ConversationMessage>, StorageError> {
+        let path = self.conversation_file_path(session_id, agent_id);
+        if !path.exists() {
+            return Ok(Vec::new());
+        }
+        Ok(parse_conversation_messages(&fs::read_to_string(path)?))
+    }
+
     fn conversation_file_path(&self, session_id: &str, agent_id: &str) -> PathBuf {
         self.session_dir(session_id)
             .join("conversations")
@@ -945,6 +1541,11 @@ impl SessionStorage {
         project_path.join(".ai-docs")
     }
 
+    /// Lessons not tied to any one session: `{app data}/lessons/`.
+    fn global_lessons_dir(&self) -> PathBuf {
+        self.base_dir.join("lessons")
+    }
+
     /// Get the session-scoped lessons directory
     /// Stores learnings and project DNA in .hive-manager/{session_id}/lessons/
     fn session_lessons_dir(&self, session_id: &str) -> PathBuf {
@@ -1015,32 +1616,7 @@ impl SessionStorage {
     /// DEPRECATED: Use read_learnings_session for new code
     pub fn read_learnings(&self, project_path: &Path) -> Result<Vec<Learning>, StorageError> {
         let learnings_file = Self::ai_docs_dir(project_path).join("learnings.jsonl");
-
-        if !learnings_file.exists() {
-            return Ok(Vec::new());
-        }
-
-        let content = fs::read_to_string(learnings_file)?;
-        let mut learnings = Vec::new();
-
-        for line in content.lines() {
-            if line.trim().is_empty() {
-                continue;
-            }
-            match serde_json::from_str::<Learning>(line) {
-                Ok(mut learning) => {
-                    if learning.id.is_empty() {
-                        learning.id = stable_learning_id(&learning);
-                    }
-                    learnings.push(learning);
-                }
-                Err(e) => {
-                    tracing::warn!("Failed to parse learning line: {}. Error: {}", line, e);
-                }
-            }
-        }
-
-        Ok(learnings)
+        read_learnings_file(&learnings_file)
     }
 
     /// Delete a learning by ID from the session-scoped learnings file
@@ -1104,32 +1680,71 @@ impl SessionStorage {
     /// Reads from .hive-manager/{session_id}/lessons/learnings.jsonl
     pub fn read_learnings_session(&self, session_id: &str) -> Result<Vec<Learning>, StorageError> {
         let learnings_file = self.session_lessons_dir(session_id).join("learnings.jsonl");
+        read_learnings_file(&learnings_file)
+    }
 
-        if !learnings_file.exists() {
-            return Ok(Vec::new());
+    /// Move a project's legacy `.ai-docs/learnings.jsonl` into the session's
+    /// lessons, or the global store when `session_id` is `None`. Entries get
+    /// their stable IDs, ones the target already holds are skipped, and the
+    /// legacy file is kept as `.ai-docs/archive/learnings-<timestamp>.jsonl`.
+    pub fn import_legacy_learnings(
+        &self,
+        project_path: &Path,
+        session_id: Option<&str>,
+    ) -> Result<LegacyLearningsImport, StorageError> {
+        let ai_docs_dir = Self::ai_docs_dir(project_path);
+        let legacy_file = ai_docs_dir.join("learnings.jsonl");
+        if !legacy_file.exists() {
+            return Ok(LegacyLearningsImport::default());
         }
 
-        let content = fs::read_to_string(learnings_file)?;
-        let mut learnings = Vec::new();
+        let target_dir = match session_id {
+            Some(session_id) => self.session_lessons_dir(session_id),
+            None => self.global_lessons_dir(),
+        };
+        fs::create_dir_all(target_dir.join("archive"))?;
+        let target_file = target_dir.join("learnings.jsonl");
 
-        for line in content.lines() {
-            if line.trim().is_empty() {
+        let content = fs::read_to_string(&legacy_file)?;
+        let mut seen: HashSet<String> = read_learnings_file(&target_file)?
+            .into_iter()
+            .map(|learning| learning.id)
+            .collect();
+        let mut report = LegacyLearningsImport::default();
+        let mut lines = String::new();
+        for line in content.lines().filter(|line| !line.trim().is_empty()) {
+            let Ok(mut learning) = serde_json::from_str::<Learning>(line) else {
+                report.malformed += 1;
                 continue;
+            };
+            if learning.id.is_empty() {
+                learning.id = stable_learning_id(&learning);
             }
-            match serde_json::from_str::<Learning>(line) {
-                Ok(mut learning) => {
-                    if learning.id.is_empty() {
-                        learning.id = stable_learning_id(&learning);
-                    }
-                    learnings.push(learning);
-                }
-                Err(e) => {
-                    tracing::warn!("Failed to parse learning line: {}. Error: {}", line, e);
-                }
+            if !seen.insert(learning.id.clone()) {
+                report.duplicates += 1;
+                continue;
             }
+            lines.push_str(&serde_json::to_string(&learning)?);
+            lines.push('\n');
+            report.imported += 1;
         }
 
-        Ok(learnings)
+        OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&target_file)?
+            .write_all(lines.as_bytes())?;
+
+        // The archive keeps every line, malformed ones included.
+        let archive_dir = ai_docs_dir.join("archive");
+        fs::create_dir_all(&archive_dir)?;
+        let archive = archive_dir.join(format!(
+            "learnings-{}.jsonl",
+            Utc::now().format("%Y%m%dT%H%M%S%.3fZ")
+        ));
+        fs::rename(&legacy_file, &archive)?;
+        report.archived_to = Some(archive);
+        Ok(report)
     }
 
     /// Read .ai-docs/project-dna.md content (project-scoped, legacy)
@@ -1318,6 +1933,8 @@ impl SessionStorage {
 
         let mut temp = tempfile::NamedTempFile::new_in(parent).map_err(StorageError::Io)?;
         serde_json::to_writer_pretty(&mut temp, value)?;
+        // On disk before the rename, so a crash cannot leave `path` empty.
+        temp.as_file().sync_all()?;
         temp.persist(path).map_err(|e| StorageError::Io(e.error))?;
         Ok(())
     }
@@ -1334,10 +1951,19 @@ impl SessionStorage {
         Ok(Some(value))
     }
 
+    /// Where agents of `session_id` drop API calls in offline mode.
+    pub fn outbox_dir(&self, session_id: &str) -> PathBuf {
+        self.session_dir(session_id).join("outbox")
+    }
+
     fn session_file_path(&self, session_id: &str) -> PathBuf {
         self.session_dir(session_id).join("session.json")
     }
 
+    fn session_journal_path(&self, session_id: &str) -> PathBuf {
+        self.session_dir(session_id).join("session.json.journal")
+    }
+
     fn session_file_modified_at(
         &self,
         session_id: &str,
@@ -1439,6 +2065,100 @@ pub struct AppConfig {
     /// believed was excluded is neither.
     #[serde(default)]
     pub knowledge_wiki_folders: Option<Vec<String>>,
+    /// Periodic WIP snapshots of worker worktrees. Off unless enabled.
+    #[serde(default)]
+    pub checkpoints: CheckpointConfig,
+    /// Agent requests that wait for operator approval. Nothing is gated by default.
+    #[serde(default)]
+    pub approvals: ApprovalConfig,
+    /// gRPC mirror of the HTTP API. Off unless enabled; needs a `grpc` feature build.
+    #[serde(default)]
+    pub grpc: GrpcConfig,
+    /// Caps on concurrent sessions and live agents. Unlimited by default.
+    #[serde(default)]
+    pub concurrency: ConcurrencyConfig,
+    /// What a launch does when one of its CLIs is missing or logged out.
+    #[serde(default)]
+    pub preflight: PreflightMode,
+    /// Lines a worker may change before it is warned and then stopped. Off by default.
+    #[serde(default)]
+    pub diff_budget: DiffBudgetConfig,
+    /// Language for generated prompts (`ja`, `pt-br`): templates named
+    /// `<name>.<locale>.md` are preferred, English otherwise. English when unset.
+    #[serde(default)]
+    pub locale: Option<String>,
+    /// Project DNA embedded in worker prompts at spawn time.
+    #[serde(default)]
+    pub project_dna: ProjectDnaConfig,
+    /// Native change notifications or polling for session files.
+    #[serde(default)]
+    pub file_watch: FileWatchConfig,
+    /// Reading agents' context-window hints and nudging them to compact.
+    #[serde(default)]
+    pub context_monitor: ContextMonitorConfig,
+    /// Where Fusion variant worktrees are created.
+    #[serde(default)]
+    pub fusion_worktrees: FusionWorktreeConfig,
+    /// Add the session artifact dirs to the project's `.git/info/exclude` when a
+    /// session is created, unless something already ignores them.
+    #[serde(default = "default_exclude_session_artifacts")]
+    pub exclude_session_artifacts: bool,
+    /// Completing sessions whose agents have all settled.
+    #[serde(default)]
+    pub auto_complete: AutoCompleteConfig,
+    /// Commands run on session events.
+    #[serde(default)]
+    pub hooks: Vec<HookConfig>,
+    /// How long a coordination log line may get.
+    #[serde(default)]
+    pub coordination: CoordinationConfig,
+    /// Periodic digests of unread inbox messages, injected into each agent's PTY.
+    #[serde(default)]
+    pub inbox_digest: InboxDigestConfig,
+    /// The editor `POST /api/open-file` opens files in.
+    #[serde(default)]
+    pub editor: EditorConfig,
+    /// Periodic snapshots of running sessions, for inspecting past orchestrator state.
+    #[serde(default)]
+    pub snapshots: SnapshotConfig,
+    /// Idle CLI processes kept ready for workers. Off unless enabled.
+    #[serde(default)]
+    pub warm_pool: WarmPoolConfig,
+}
+
+fn default_exclude_session_artifacts() -> bool {
+    true
+}
+
+/// Profile names become directory names, so keep them to a short slug.
+pub fn validate_profile_name(name: &str) -> Result<(), String> {
+    if name.is_empty() || name.len() > 64 {
+        return Err("Profile name must be 1-64 characters".to_string());
+    }
+    if !name
+        .chars()
+        .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+    {
+        return Err(format!(
+            "Invalid profile name '{}': use letters, digits, '-' or '_'",
+            name
+        ));
+    }
+    Ok(())
+}
+
+/// Read the `--profile <name>` / `--profile=<name>` launch argument.
+pub fn profile_arg(args: impl IntoIterator<Item = String>) -> Option<String> {
+    let mut args = args.into_iter();
+    while let Some(arg) = args.next() {
+        if arg == "--profile" {
+            return args.next();
+        }
+        if let Some(profile) = arg.strip_prefix("--profile=") {
+            return Some(profile.to_string());
+        }
+    }
+    None
 }
 
 /// Default location of the global LLM wiki used by Research mode.
@@ -1450,6 +2170,15 @@ fn default_global_wiki_path() -> Option<String> {
 pub struct ApiConfig {
     pub enabled: bool,
     pub port: u16,
+    /// For machines where agents cannot reach the port: prompts tell agents to write
+    /// each API call as a JSON file in their session's outbox (see `http::outbox`).
+    #[serde(default)]
+    pub offline: bool,
+    /// Also serve the API on this Unix domain socket, `{pid}` replaced with the
+    /// process ID, and have agents' curl go through it (see `http::transport`).
+    /// Not available on Windows.
+    #[serde(default)]
+    pub socket_path: Option<String>,
 }
 
 impl Default for ApiConfig {
@@ -1457,6 +2186,437 @@ impl Default for ApiConfig {
         Self {
             enabled: true, // Enabled by default for Queen to spawn workers
             port: 18800,
+            offline: false,
+            socket_path: None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GrpcConfig {
+    pub enabled: bool,
+    pub port: u16,
+}
+
+impl Default for GrpcConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            port: 18801,
+        }
+    }
+}
+
+/// Launch-time handling of CLIs that would leave agents at a login screen.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PreflightMode {
+    /// Log the problems and launch anyway.
+    #[default]
+    Warn,
+    /// Refuse the launch.
+    Block,
+    /// Skip the checks.
+    Off,
+}
+
+/// How the task watcher learns about changed session files.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum WatchMode {
+    /// Poll on network shares and WSL-mounted Windows drives, where native
+    /// notifications miss changes; native everywhere else.
+    #[default]
+    Auto,
+    /// Always use the platform's change notifications.
+    Native,
+    /// Always poll.
+    Poll,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FileWatchConfig {
+    #[serde(default)]
+    pub mode: WatchMode,
+    /// How often a polling watcher rescans the session's directories.
+    #[serde(default = "default_poll_interval_ms")]
+    pub poll_interval_ms: u64,
+}
+
+fn default_poll_interval_ms() -> u64 {
+    2000
+}
+
+impl Default for FileWatchConfig {
+    fn default() -> Self {
+        Self {
+            mode: WatchMode::default(),
+            poll_interval_ms: default_poll_interval_ms(),
+        }
+    }
+}
+
+/// Base directory for Fusion variant worktrees. Each session's worktrees go in
+/// `<base>/<session-id>/`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FusionWorktreeLocation {
+    /// `fusion-worktrees/` in the app data dir, away from the project's file
+    /// watchers and build tools.
+    #[default]
+    AppData,
+    /// `<project>.hive-fusion/` next to the project.
+    Sibling,
+    /// `.hive-fusion/` inside the project.
+    Project,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FusionWorktreeConfig {
+    #[serde(default)]
+    pub location: FusionWorktreeLocation,
+    /// A base directory of your own; overrides `location`. `~` is expanded.
+    #[serde(default)]
+    pub dir: Option<String>,
+}
+
+/// When an agent's CLI reports it is nearly out of context, send it the CLI's
+/// compact command (`/compact`) once, instead of leaving it to hit the limit.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ContextMonitorConfig {
+    #[serde(default)]
+    pub auto_compact: bool,
+    /// Context left, in percent, at or below which an agent counts as critical.
+    #[serde(default = "default_compact_below_percent")]
+    pub compact_below_percent: u8,
+}
+
+fn default_compact_below_percent() -> u8 {
+    15
+}
+
+impl Default for ContextMonitorConfig {
+    fn default() -> Self {
+        Self {
+            auto_compact: false,
+            compact_below_percent: default_compact_below_percent(),
+        }
+    }
+}
+
+/// Completing a session by itself once every agent has been idle or completed
+/// for `idle_secs` and every worker's task is COMPLETED. The session completes
+/// `grace_secs` after that is announced, unless an agent becomes active again.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AutoCompleteConfig {
+    #[serde(default = "default_auto_complete_enabled")]
+    pub enabled: bool,
+    #[serde(default = "default_auto_complete_idle_secs")]
+    pub idle_secs: u64,
+    #[serde(default = "default_auto_complete_grace_secs")]
+    pub grace_secs: u64,
+}
+
+fn default_auto_complete_enabled() -> bool {
+    true
+}
+
+fn default_auto_complete_idle_secs() -> u64 {
+    300
+}
+
+fn default_auto_complete_grace_secs() -> u64 {
+    120
+}
+
+impl Default for AutoCompleteConfig {
+    fn default() -> Self {
+        Self {
+            enabled: default_auto_complete_enabled(),
+            idle_secs: default_auto_complete_idle_secs(),
+            grace_secs: default_auto_complete_grace_secs(),
+        }
+    }
+}
+
+/// A coordination message longer than `max_content_chars` characters is logged cut
+/// short, pointing at an attachment that holds all of it. 0 lifts the limit.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CoordinationConfig {
+    #[serde(default = "default_max_content_chars")]
+    pub max_content_chars: usize,
+}
+
+fn default_max_content_chars() -> usize {
+    2000
+}
+
+impl Default for CoordinationConfig {
+    fn default() -> Self {
+        Self {
+            max_content_chars: default_max_content_chars(),
+        }
+    }
+}
+
+/// Every `interval_secs`, each running agent with unread messages in its
+/// conversation inbox gets a digest of up to `max_items` of them typed into its
+/// terminal, and those messages count as delivered.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct InboxDigestConfig {
+    #[serde(default = "default_inbox_digest_enabled")]
+    pub enabled: bool,
+    #[serde(default = "default_inbox_digest_interval_secs")]
+    pub interval_secs: u64,
+    #[serde(default = "default_inbox_digest_max_items")]
+    pub max_items: usize,
+}
+
+fn default_inbox_digest_enabled() -> bool {
+    true
+}
+
+fn default_inbox_digest_interval_secs() -> u64 {
+    300
+}
+
+fn default_inbox_digest_max_items() -> usize {
+    5
+}
+
+impl Default for InboxDigestConfig {
+    fn default() -> Self {
+        Self {
+            enabled: default_inbox_digest_enabled(),
+            interval_secs: default_inbox_digest_interval_secs(),
+            max_items: default_inbox_digest_max_items(),
+        }
+    }
+}
+
+/// Every `interval_secs`, each running session whose state changed gets a
+/// snapshot (see `session::snapshots`); the newest `keep` are kept per session.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SnapshotConfig {
+    #[serde(default = "default_snapshots_enabled")]
+    pub enabled: bool,
+    #[serde(default = "default_snapshot_interval_secs")]
+    pub interval_secs: u64,
+    #[serde(default = "default_snapshots_keep")]
+    pub keep: usize,
+}
+
+fn default_snapshots_enabled() -> bool {
+    true
+}
+
+fn default_snapshot_interval_secs() -> u64 {
+    60
+}
+
+fn default_snapshots_keep() -> usize {
+    500
+}
+
+impl Default for SnapshotConfig {
+    fn default() -> Self {
+        Self {
+            enabled: default_snapshots_enabled(),
+            interval_secs: default_snapshot_interval_secs(),
+            keep: default_snapshots_keep(),
+        }
+    }
+}
+
+/// Idle CLI processes started ahead of the workers that will use them (see
+/// `session::warm_pool`): `size` per entry in each session whose workers share
+/// one directory.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WarmPoolConfig {
+    #[serde(default)]
+    pub enabled: bool,
+    #[serde(default = "default_warm_pools")]
+    pub pools: Vec<WarmPoolEntry>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WarmPoolEntry {
+    pub cli: String,
+    /// The CLI's default model when omitted.
+    #[serde(default)]
+    pub model: Option<String>,
+    #[serde(default = "default_warm_pool_size")]
+    pub size: u8,
+}
+
+fn default_warm_pools() -> Vec<WarmPoolEntry> {
+    vec![WarmPoolEntry {
+        cli: "claude".to_string(),
+        model: None,
+        size: default_warm_pool_size(),
+    }]
+}
+
+fn default_warm_pool_size() -> u8 {
+    1
+}
+
+impl Default for WarmPoolConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            pools: default_warm_pools(),
+        }
+    }
+}
+
+/// How to open a file at a location in the operator's editor. `{file}`, `{line}`
+/// and `{column}` in `args` are replaced with the location; VS Code's `code` is
+/// the default.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EditorConfig {
+    /// Program to run, started directly rather than through a shell.
+    #[serde(default = "default_editor_command")]
+    pub command: String,
+    #[serde(default = "default_editor_args")]
+    pub args: Vec<String>,
+}
+
+fn default_editor_command() -> String {
+    "code".to_string()
+}
+
+fn default_editor_args() -> Vec<String> {
+    vec!["--goto".to_string(), "{file}:{line}:{column}".to_string()]
+}
+
+impl Default for EditorConfig {
+    fn default() -> Self {
+        Self {
+            command: default_editor_command(),
+            args: default_editor_args(),
+        }
+    }
+}
+
+/// A command run when a session reaches `event` (`session-completed`,
+/// `plan-ready` or `judge-verdict`), see [`crate::session::hooks`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HookConfig {
+    pub event: String,
+    /// Program to run, started directly rather than through a shell.
+    pub command: String,
+    #[serde(default)]
+    pub args: Vec<String>,
+    /// Seconds before the command is killed.
+    #[serde(default = "default_hook_timeout_secs")]
+    pub timeout_secs: u64,
+}
+
+fn default_hook_timeout_secs() -> u64 {
+    60
+}
+
+/// Global launch limits. A launch that would exceed either waits in the launch
+/// queue until running sessions free enough capacity.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ConcurrencyConfig {
+    /// Sessions running at once.
+    #[serde(default)]
+    pub max_concurrent_sessions: Option<usize>,
+    /// Live agents across all running sessions.
+    #[serde(default)]
+    pub max_total_agents: Option<usize>,
+}
+
+/// Lines one worker may change (`git diff --numstat` against the commit it started
+/// from) before it is told to split the work up, and before it is stopped.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DiffBudgetConfig {
+    #[serde(default)]
+    pub warn_lines: Option<u64>,
+    #[serde(default)]
+    pub stop_lines: Option<u64>,
+}
+
+impl DiffBudgetConfig {
+    pub fn is_enabled(&self) -> bool {
+        self.warn_lines.is_some() || self.stop_lines.is_some()
+    }
+}
+
+/// Whether worker prompts carry the project DNA itself rather than only a pointer
+/// to `.ai-docs/project-dna.md`, and how much of it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ProjectDnaConfig {
+    #[serde(default = "default_embed_project_dna")]
+    pub embed_in_worker_prompts: bool,
+    /// Longer DNA is cut at a line break and the worker pointed at the full file.
+    #[serde(default = "default_project_dna_max_chars")]
+    pub max_chars: usize,
+}
+
+fn default_embed_project_dna() -> bool {
+    true
+}
+
+fn default_project_dna_max_chars() -> usize {
+    4000
+}
+
+impl Default for ProjectDnaConfig {
+    fn default() -> Self {
+        Self {
+            embed_in_worker_prompts: default_embed_project_dna(),
+            max_chars: default_project_dna_max_chars(),
+        }
+    }
+}
+
+/// How often worker worktrees are snapshotted to `refs/checkpoints/`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CheckpointConfig {
+    pub enabled: bool,
+    pub interval_minutes: u64,
+}
+
+impl Default for CheckpointConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            interval_minutes: 10,
+        }
+    }
+}
+
+/// Which agent requests are parked until the operator approves them.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ApprovalConfig {
+    /// Spawning a worker beyond this many in one session needs approval.
+    #[serde(default)]
+    pub max_workers: Option<usize>,
+    /// Branch globs (`main`, `release/*`) a push to needs approval.
+    #[serde(default)]
+    pub protected_branches: Vec<String>,
+    /// Path globs (`migrations/**`, `*.lock`) whose deletion needs approval.
+    #[serde(default)]
+    pub protected_paths: Vec<String>,
+    /// How long a parked request waits before it is rejected.
+    #[serde(default = "default_approval_timeout_secs")]
+    pub timeout_secs: u64,
+}
+
+fn default_approval_timeout_secs() -> u64 {
+    600
+}
+
+impl Default for ApprovalConfig {
+    fn default() -> Self {
+        Self {
+            max_workers: None,
+            protected_branches: Vec::new(),
+            protected_paths: Vec::new(),
+            timeout_secs: default_approval_timeout_secs(),
         }
     }
 }
@@ -1469,6 +2629,18 @@ pub struct CliConfig {
     pub model_flag: Option<String>,
     pub default_model: String,
     pub env: Option<HashMap<String, String>>,
+    /// Key pressed after text is typed into the CLI, instead of its built-in one
+    /// (see `CliRegistry::submit_key`).
+    #[serde(default)]
+    pub submit_key: Option<SubmitKey>,
+    /// Directories put in front of the CLI's PATH, after its login shell's profile
+    /// has run, so it uses pinned versions of node, python and the like.
+    #[serde(default)]
+    pub path_prepend: Vec<String>,
+    /// Host environment variables the CLI keeps. When set, everything else is
+    /// dropped and the login shell's profile is skipped; `env` still applies.
+    #[serde(default)]
+    pub env_allowlist: Option<Vec<String>>,
 }
 
 /// Default settings for a role
@@ -1533,6 +2705,90 @@ mod tests {
         assert_eq!(evaluator.model, "opus");
     }
 
+    #[test]
+    fn test_switch_profile_isolates_config_and_persists_choice() {
+        let (storage, temp_dir) = create_test_storage();
+
+        let mut demo = storage.switch_profile("demo").unwrap();
+        assert_eq!(storage.active_profile(), "demo");
+        demo.api.port = 18900;
+        demo.clis.remove("claude");
+        storage.save_config(&demo).unwrap();
+        assert!(temp_dir.path().join("profiles/demo/config.json").is_file());
+        assert_eq!(
+            storage.list_profiles().unwrap(),
+            vec!["default".to_string(), "demo".to_string()]
+        );
+
+        let reopened = SessionStorage::new_with_base(temp_dir.path().to_path_buf()).unwrap();
+        assert_eq!(reopened.active_profile(), "demo");
+        assert_eq!(reopened.load_config().unwrap().api.port, 18900);
+
+        let default = reopened.switch_profile(DEFAULT_PROFILE).unwrap();
+        assert_eq!(default.api.port, 18800);
+        assert!(default.clis.contains_key("claude"));
+        assert!(storage.switch_profile("../work").is_err());
+
+        std::fs::create_dir_all(temp_dir.path().join("profiles/broken")).unwrap();
+        std::fs::write(temp_dir.path().join("profiles/broken/config.json"), "{").unwrap();
+        assert!(reopened.switch_profile("broken").is_err());
+        assert_eq!(reopened.active_profile(), DEFAULT_PROFILE);
+    }
+
+    #[test]
+    fn test_launch_profile_leaves_the_saved_choice_alone() {
+        let (storage, temp_dir) = create_test_storage();
+        storage.switch_profile("demo").unwrap();
+        std::fs::create_dir_all(temp_dir.path().join("profiles/broken")).unwrap();
+        std::fs::write(temp_dir.path().join("profiles/broken/config.json"), "{").unwrap();
+        let saved = || std::fs::read_to_string(temp_dir.path().join("active-profile")).unwrap();
+
+        storage.use_launch_profile("broken");
+        assert_eq!(storage.active_profile(), DEFAULT_PROFILE);
+        assert_eq!(saved(), "demo");
+
+        storage.use_launch_profile("work");
+        assert_eq!(storage.active_profile(), "work");
+        assert_eq!(saved(), "demo");
+
+        let reopened = SessionStorage::new_with_base(temp_dir.path().to_path_buf()).unwrap();
+        assert_eq!(reopened.active_profile(), "demo");
+    }
+
+    #[test]
+    fn test_coordination_messages_spill_over_at_the_configured_length() {
+        let (storage, _temp_dir) = create_test_storage();
+        storage.create_session_dir("spill").unwrap();
+        let mut config = storage.load_config().unwrap();
+        config.coordination.max_content_chars = 12;
+        storage.save_config(&config).unwrap();
+
+        let long = "Schema: {\"id\": \"uuid\", \"name\": \"string\"}";
+        storage
+            .append_coordination_log("spill", &CoordinationMessage::task("QUEEN", "ALL", long))
+            .unwrap();
+
+        let logged = storage.read_coordination_log("spill", None).unwrap();
+        let spilled = storage.list_attachments("spill").unwrap();
+        assert!(logged[0].content.starts_with("Schema: {\"id…"));
+        assert_eq!(logged[0].attachments, [spilled[0].id.clone()]);
+        assert_eq!(fs::read_to_string(&spilled[0].path).unwrap(), long);
+    }
+
+    #[test]
+    fn test_profile_arg_accepts_both_forms() {
+        let args = |list: &[&str]| list.iter().map(|s| s.to_string()).collect::<Vec<_>>();
+        assert_eq!(
+            profile_arg(args(&["hive-manager", "--profile", "demo"])).as_deref(),
+            Some("demo")
+        );
+        assert_eq!(
+            profile_arg(args(&["hive-manager", "--profile=work"])).as_deref(),
+            Some("work")
+        );
+        assert_eq!(profile_arg(args(&["hive-manager"])), None);
+    }
+
     #[test]
     fn test_default_config_excludes_removed_gemini_and_antigravity() {
         // gemini and antigravity were removed as spawnable CLIs; GPT-5.6 work
@@ -1587,6 +2843,8 @@ mod tests {
             worktree_path: None,
             worktree_branch: None,
             no_git: false,
+            pull_request_url: None,
+            spawn_quota: crate::session::SpawnQuota::default(),
         }
     }
 
@@ -1651,6 +2909,7 @@ mod tests {
     fn test_learning_serialization_roundtrip() {
         let learning = Learning {
             id: "test-id-123".to_string(),
+            schema: LEARNING_SCHEMA_VERSION,
             date: "2024-01-01".to_string(),
             session: "test-session".to_string(),
             task: "test task".to_string(),
@@ -1658,6 +2917,9 @@ mod tests {
             keywords: vec!["rust".to_string(), "api".to_string()],
             insight: "test insight".to_string(),
             files_touched: vec!["src/file.rs".to_string()],
+            category: None,
+            confidence: None,
+            source_agent: None,
         };
 
         let json = serde_json::to_string(&learning).unwrap();
@@ -1723,6 +2985,34 @@ mod tests {
         assert_eq!(learning.id, id2); // Deterministic
     }
 
+    #[test]
+    fn test_learning_schema_versions_deserialize() {
+        let v1: Learning = serde_json::from_str(
+            r#"{"id": "a", "date": "2024-01-03", "session": "s", "task": "t",
+                "outcome": "success", "keywords": [], "insight": "i", "files_touched": []}"#,
+        )
+        .unwrap();
+        assert_eq!(v1.schema, 1);
+        assert!(v1.category.is_none() && v1.confidence.is_none());
+
+        // Judge-style records name the insight `content` and the agent `source`.
+        let v2: Learning = serde_json::from_str(
+            r#"{"id": "b", "schema": 2, "date": "2024-01-03", "session": "s",
+                "content": "Variant B split the parser", "category": "architecture",
+                "confidence": 0.8, "source": "fusion-judge"}"#,
+        )
+        .unwrap();
+        assert_eq!(v2.insight, "Variant B split the parser");
+        assert_eq!(v2.category.as_deref(), Some("architecture"));
+        assert_eq!(v2.confidence, Some(0.8));
+        assert_eq!(v2.source_agent.as_deref(), Some("fusion-judge"));
+        assert!(v2.task.is_empty() && v2.keywords.is_empty());
+
+        let json = serde_json::to_value(&v1).unwrap();
+        assert!(json.get("category").is_none());
+        assert_eq!(json["schema"], 1);
+    }
+
     #[test]
     fn test_persisted_agent_blank_commit_sha_deserializes_to_none() {
         let json = r#"{
@@ -1746,6 +3036,28 @@ mod tests {
         assert_eq!(agent.commit_sha, None);
     }
 
+    #[test]
+    fn test_load_session_replays_a_complete_journal_and_drops_a_torn_one() {
+        let (storage, _temp_dir) = create_test_storage();
+        let session = sample_persisted_session("journal-session");
+        storage.save_session(&session).unwrap();
+        let journal = storage.session_journal_path(&session.id);
+        assert!(!journal.exists());
+
+        // Crashed after the journal was synced but before session.json was replaced.
+        let mut interrupted = session.clone();
+        interrupted.state = "Completed".to_string();
+        std::fs::write(&journal, serde_json::to_string(&interrupted).unwrap()).unwrap();
+        let loaded = storage.load_session(&session.id).unwrap();
+        assert_eq!(loaded.state, "Completed");
+        assert!(!journal.exists());
+
+        // Crashed while the journal itself was being written.
+        std::fs::write(&journal, r#"{"id": "journal-sess"#).unwrap();
+        assert_eq!(storage.load_session(&session.id).unwrap().state, "Completed");
+        assert!(!journal.exists());
+    }
+
     #[test]
     fn test_load_session_if_newer_and_clean_uses_cached_hash() {
         let (storage, _temp_dir) = create_test_storage();
@@ -1801,6 +3113,7 @@ mod tests {
 
         let learning = Learning {
             id: "test-learning-1".to_string(),
+            schema: LEARNING_SCHEMA_VERSION,
             date: "2024-01-01".to_string(),
             session: session_id.to_string(),
             task: "test task".to_string(),
@@ -1808,6 +3121,9 @@ mod tests {
             keywords: vec!["test".to_string()],
             insight: "test insight".to_string(),
             files_touched: vec!["src/file.rs".to_string()],
+            category: None,
+            confidence: None,
+            source_agent: None,
         };
 
         // Append learning
@@ -1833,6 +3149,7 @@ mod tests {
         // Append 3 learnings
         let learning1 = Learning {
             id: "learning-1".to_string(),
+            schema: LEARNING_SCHEMA_VERSION,
             date: "2024-01-01".to_string(),
             session: session_id.to_string(),
             task: "task 1".to_string(),
@@ -1840,10 +3157,14 @@ mod tests {
             keywords: vec![],
             insight: "insight 1".to_string(),
             files_touched: vec![],
+            category: None,
+            confidence: None,
+            source_agent: None,
         };
 
         let learning2 = Learning {
             id: "learning-2".to_string(),
+            schema: LEARNING_SCHEMA_VERSION,
             date: "2024-01-02".to_string(),
             session: session_id.to_string(),
             task: "task 2".to_string(),
@@ -1851,10 +3172,14 @@ mod tests {
             keywords: vec![],
             insight: "insight 2".to_string(),
             files_touched: vec![],
+            category: None,
+            confidence: None,
+            source_agent: None,
         };
 
         let learning3 = Learning {
             id: "learning-3".to_string(),
+            schema: LEARNING_SCHEMA_VERSION,
             date: "2024-01-03".to_string(),
             session: session_id.to_string(),
             task: "task 3".to_string(),
@@ -1862,6 +3187,9 @@ mod tests {
             keywords: vec![],
             insight: "insight 3".to_string(),
             files_touched: vec![],
+            category: None,
+            confidence: None,
+            source_agent: None,
         };
 
         storage
@@ -1898,6 +3226,7 @@ mod tests {
         // Append a learning
         let learning = Learning {
             id: "learning-1".to_string(),
+            schema: LEARNING_SCHEMA_VERSION,
             date: "2024-01-01".to_string(),
             session: session_id.to_string(),
             task: "task 1".to_string(),
@@ -1905,6 +3234,9 @@ mod tests {
             keywords: vec![],
             insight: "insight 1".to_string(),
             files_touched: vec![],
+            category: None,
+            confidence: None,
+            source_agent: None,
         };
 
         storage
@@ -1934,6 +3266,88 @@ mod tests {
         assert_eq!(learnings.len(), 0);
     }
 
+    #[test]
+    fn test_filesystem_and_memory_backends_behave_alike() {
+        let (fs_storage, _temp_dir) = create_test_storage();
+        let memory = MemoryStorage::new();
+        let backends: [&dyn Storage; 2] = [&fs_storage, &memory];
+
+        for backend in backends {
+            assert!(matches!(
+                backend.load_session("backend-session"),
+                Err(StorageError::SessionNotFound(_))
+            ));
+            backend
+                .save_session(&sample_persisted_session("backend-session"))
+                .unwrap();
+            let summaries = backend.list_sessions().unwrap();
+            assert_eq!(summaries.len(), 1);
+            assert_eq!(summaries[0].session_type, "Hive (1)");
+
+            let learning = Learning {
+                id: String::new(),
+                schema: LEARNING_SCHEMA_VERSION,
+                date: "2026-01-01".to_string(),
+                session: "backend-session".to_string(),
+                task: "Add storage trait".to_string(),
+                outcome: "success".to_string(),
+                keywords: vec!["storage".to_string()],
+                insight: "Backends share one contract".to_string(),
+                files_touched: vec![],
+                category: None,
+                confidence: None,
+                source_agent: None,
+            };
+            backend
+                .append_learning_session("backend-session", &learning)
+                .unwrap();
+            let learnings = backend.read_learnings_session("backend-session").unwrap();
+            assert_eq!(learnings.len(), 1);
+            assert_eq!(learnings[0].id, stable_learning_id(&learning));
+            assert!(backend
+                .delete_learning_session("backend-session", &learnings[0].id)
+                .unwrap());
+            assert!(!backend
+                .delete_learning_session("backend-session", &learnings[0].id)
+                .unwrap());
+
+            // Same millisecond for all three: the sequence number orders them.
+            let at = Utc::now();
+            for content in ["first", "second", "third"] {
+                let mut message = CoordinationMessage::system("QUEEN", content);
+                message.timestamp = at;
+                backend
+                    .append_coordination_log("backend-session", &message)
+                    .unwrap();
+            }
+            let recent: Vec<(String, Option<u64>)> = backend
+                .read_coordination_log("backend-session", Some(2))
+                .unwrap()
+                .into_iter()
+                .map(|message| (message.content, message.seq))
+                .collect();
+            assert_eq!(
+                recent,
+                vec![("second".to_string(), Some(2)), ("third".to_string(), Some(3))]
+            );
+            let after: Vec<Option<u64>> = backend
+                .read_coordination_log_after("backend-session", 1)
+                .unwrap()
+                .into_iter()
+                .map(|message| message.seq)
+                .collect();
+            assert_eq!(after, vec![Some(2), Some(3)]);
+
+            let mut config = backend.load_config().unwrap();
+            config.api.port = 18899;
+            backend.save_config(&config).unwrap();
+            assert_eq!(backend.load_config().unwrap().api.port, 18899);
+
+            backend.delete_session("backend-session").unwrap();
+            assert!(backend.list_sessions().unwrap().is_empty());
+        }
+    }
+
     #[test]
     fn test_read_learnings_skips_malformed_lines() {
         let (storage, _temp_dir) = create_test_storage();
@@ -1963,6 +3377,63 @@ invalid json line
         assert_eq!(learnings[2].id, "valid-3");
     }
 
+    #[test]
+    fn test_import_legacy_learnings_dedupes_and_archives() {
+        let (storage, temp_dir) = create_test_storage();
+        let session_id = "test-session-legacy-import";
+        storage.create_session_dir(session_id).unwrap();
+
+        let project = temp_dir.path().join("project");
+        let ai_docs = project.join(".ai-docs");
+        std::fs::create_dir_all(&ai_docs).unwrap();
+        let content = r#"{"date":"2024-01-01","session":"old","task":"t1","outcome":"success","insight":"no id yet"}
+{"id":"kept","date":"2024-01-02","session":"old","insight":"already imported"}
+not json
+{"date":"2024-01-01","session":"old","task":"t1","outcome":"success","insight":"no id yet"}
+"#;
+        std::fs::write(ai_docs.join("learnings.jsonl"), content).unwrap();
+
+        let existing = Learning {
+            id: "kept".to_string(),
+            schema: LEARNING_SCHEMA_VERSION,
+            date: "2024-01-02".to_string(),
+            session: session_id.to_string(),
+            task: String::new(),
+            outcome: String::new(),
+            keywords: vec![],
+            insight: "already imported".to_string(),
+            files_touched: vec![],
+            category: None,
+            confidence: None,
+            source_agent: None,
+        };
+        storage
+            .append_learning_session(session_id, &existing)
+            .unwrap();
+
+        let report = storage
+            .import_legacy_learnings(&project, Some(session_id))
+            .unwrap();
+        assert_eq!(report.imported, 1);
+        assert_eq!(report.duplicates, 2);
+        assert_eq!(report.malformed, 1);
+
+        let learnings = storage.read_learnings_session(session_id).unwrap();
+        assert_eq!(learnings.len(), 2);
+        assert!(!learnings[1].id.is_empty());
+        assert_eq!(learnings[1].insight, "no id yet");
+
+        assert!(!ai_docs.join("learnings.jsonl").exists());
+        let archive = report.archived_to.unwrap();
+        assert!(archive.starts_with(ai_docs.join("archive")));
+        assert_eq!(std::fs::read_to_string(archive).unwrap(), content);
+
+        // A second run finds nothing left to import.
+        let again = storage.import_legacy_learnings(&project, None).unwrap();
+        assert_eq!(again.imported, 0);
+        assert!(again.archived_to.is_none());
+    }
+
     #[test]
     fn test_primary_cell_save_artifact_waits_for_existing_lock() {
         let (storage, _temp_dir) = create_test_storage();
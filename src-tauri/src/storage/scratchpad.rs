@@ -0,0 +1,131 @@
+//! A session's scratchpad: named text values the agents share.
+//!
+//! Workers use it for the things several of them need to read while they work in
+//! parallel, such as an API contract, an interface sketch or a JSON schema, which
+//! are too long to pass around in `coordination.log` lines. Each key is a file
+//! `<key>.json` in the session's `scratchpad/` directory. A write replaces the file
+//! with a rename, so a reader never sees half a value; the last writer wins.
+
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+/// Directory under the session directory.
+pub const SCRATCHPAD_DIR: &str = "scratchpad";
+
+/// Largest value a key may hold.
+pub const MAX_VALUE_BYTES: usize = 256 * 1024;
+
+const MAX_KEY_CHARS: usize = 64;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ScratchpadEntry {
+    pub key: String,
+    pub value: String,
+    /// Agent ID the write was made with, if the request named one.
+    pub updated_by: Option<String>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Whether `key` can name an entry: 1 to 64 ASCII letters, digits, `.`, `_` or `-`,
+/// not starting with a dot.
+pub fn is_valid_key(key: &str) -> bool {
+    !key.is_empty()
+        && !key.starts_with('.')
+        && key.len() <= MAX_KEY_CHARS
+        && key.chars().all(|c| c.is_ascii_alphanumeric() || "._-".contains(c))
+}
+
+/// Set `key` in the scratchpad at `dir` to `value`, replacing any earlier value.
+pub fn put(
+    dir: &Path,
+    key: &str,
+    value: &str,
+    updated_by: Option<&str>,
+) -> io::Result<ScratchpadEntry> {
+    if !is_valid_key(key) {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("Invalid scratchpad key: {}", key),
+        ));
+    }
+    fs::create_dir_all(dir)?;
+    let entry = ScratchpadEntry {
+        key: key.to_string(),
+        value: value.to_string(),
+        updated_by: updated_by.map(str::to_string),
+        updated_at: Utc::now(),
+    };
+    let json = serde_json::to_vec_pretty(&entry).map_err(io::Error::other)?;
+    // Concurrent writers of one key each stage their own file.
+    let staged = dir.join(format!(".{}.{}.tmp", key, uuid::Uuid::new_v4().simple()));
+    fs::write(&staged, json)?;
+    fs::rename(&staged, dir.join(format!("{}.json", key)))?;
+    Ok(entry)
+}
+
+/// The entry under `key`, or `None` when nothing was written to it.
+pub fn get(dir: &Path, key: &str) -> io::Result<Option<ScratchpadEntry>> {
+    if !is_valid_key(key) {
+        return Ok(None);
+    }
+    match fs::read(dir.join(format!("{}.json", key))) {
+        Ok(bytes) => serde_json::from_slice(&bytes)
+            .map(Some)
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e)),
+        Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(None),
+        Err(e) => Err(e),
+    }
+}
+
+/// Every entry in the scratchpad at `dir`, by key.
+pub fn list(dir: &Path) -> io::Result<Vec<ScratchpadEntry>> {
+    let entries = match fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(e),
+    };
+    let mut scratchpad = Vec::new();
+    for entry in entries {
+        let file_name = entry?.file_name().to_string_lossy().into_owned();
+        let Some(key) = file_name.strip_suffix(".json") else {
+            continue;
+        };
+        if let Some(entry) = get(dir, key)? {
+            scratchpad.push(entry);
+        }
+    }
+    scratchpad.sort_by(|a, b| a.key.cmp(&b.key));
+    Ok(scratchpad)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_scratchpad_values_are_replaced_by_key() {
+        let temp = tempfile::tempdir().unwrap();
+        let dir = temp.path().join(SCRATCHPAD_DIR);
+        assert_eq!(get(&dir, "api-contract").unwrap(), None);
+        assert!(list(&dir).unwrap().is_empty());
+
+        put(&dir, "api-contract", "GET /users -> User[]", Some("s-worker-1")).unwrap();
+        put(&dir, "schema.json", "{}", None).unwrap();
+        let replaced = put(&dir, "api-contract", "GET /users -> Page<User>", None).unwrap();
+
+        let stored = get(&dir, "api-contract").unwrap().unwrap();
+        assert_eq!(stored, replaced);
+        assert_eq!(stored.value, "GET /users -> Page<User>");
+        assert_eq!(stored.updated_by, None);
+        let keys: Vec<_> = list(&dir).unwrap().into_iter().map(|entry| entry.key).collect();
+        assert_eq!(keys, ["api-contract", "schema.json"]);
+
+        assert!(put(&dir, "../escape", "x", None).is_err());
+        assert!(!is_valid_key(".hidden"));
+        assert_eq!(get(&dir, "../api-contract").unwrap(), None);
+    }
+}
@@ -0,0 +1,186 @@
+//! OS-keychain secrets for CLI environment variables.
+//!
+//! `CliConfig.env` values may reference a secret as `${secret:NAME}` instead of holding an
+//! API key in plaintext `config.json`. The reference is stored in the config; the value
+//! lives in the OS keychain (via the `keyring` crate) under the `hive-manager` service and
+//! is only resolved by [`resolve_env`] when an agent process is spawned. Logs name the
+//! variables through [`env_keys`] and never show a value: a plaintext key is as secret
+//! as one in the keychain.
+
+use std::collections::HashMap;
+
+use thiserror::Error;
+
+/// Keychain service name every Hive Manager secret is stored under.
+pub const KEYRING_SERVICE: &str = "hive-manager";
+
+const SECRET_REF_PREFIX: &str = "${secret:";
+
+#[derive(Error, Debug)]
+pub enum SecretError {
+    #[error("Invalid secret name: {0}")]
+    InvalidName(String),
+    #[error("Secret not found in keychain: {0}")]
+    Missing(String),
+    #[error("Unterminated secret reference in env var {0}")]
+    Unterminated(String),
+    #[error("Keychain error: {0}")]
+    Keyring(String),
+}
+
+/// Where `${secret:NAME}` references are looked up.
+pub trait SecretSource {
+    fn get_secret(&self, name: &str) -> Result<Option<String>, SecretError>;
+}
+
+/// The OS keychain (Windows Credential Manager, macOS Keychain, Secret Service).
+pub struct KeyringSecrets;
+
+impl KeyringSecrets {
+    fn entry(name: &str) -> Result<keyring::Entry, SecretError> {
+        validate_secret_name(name)?;
+        keyring::Entry::new(KEYRING_SERVICE, name).map_err(|e| SecretError::Keyring(e.to_string()))
+    }
+
+    /// Store (or replace) a secret.
+    pub fn set(name: &str, value: &str) -> Result<(), SecretError> {
+        Self::entry(name)?
+            .set_password(value)
+            .map_err(|e| SecretError::Keyring(e.to_string()))
+    }
+
+    /// Remove a secret. Removing a secret that does not exist is not an error.
+    pub fn delete(name: &str) -> Result<(), SecretError> {
+        match Self::entry(name)?.delete_credential() {
+            Ok(()) | Err(keyring::Error::NoEntry) => Ok(()),
+            Err(e) => Err(SecretError::Keyring(e.to_string())),
+        }
+    }
+}
+
+impl SecretSource for KeyringSecrets {
+    fn get_secret(&self, name: &str) -> Result<Option<String>, SecretError> {
+        match Self::entry(name)?.get_password() {
+            Ok(value) => Ok(Some(value)),
+            Err(keyring::Error::NoEntry) => Ok(None),
+            Err(e) => Err(SecretError::Keyring(e.to_string())),
+        }
+    }
+}
+
+/// Secret names are keychain account names; keep them to env-var style identifiers.
+pub fn validate_secret_name(name: &str) -> Result<(), SecretError> {
+    if name.is_empty()
+        || name.len() > 128
+        || !name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-' || c == '.')
+    {
+        return Err(SecretError::InvalidName(name.to_string()));
+    }
+    Ok(())
+}
+
+/// Resolve every `${secret:NAME}` reference in `env` values. Values without a reference
+/// pass through unchanged; a reference to a missing secret fails the whole spawn rather
+/// than launching the CLI with a literal placeholder.
+pub fn resolve_env(
+    env: &HashMap<String, String>,
+    source: &dyn SecretSource,
+) -> Result<HashMap<String, String>, SecretError> {
+    env.iter()
+        .map(|(key, value)| Ok((key.clone(), resolve_value(key, value, source)?)))
+        .collect()
+}
+
+fn resolve_value(key: &str, value: &str, source: &dyn SecretSource) -> Result<String, SecretError> {
+    let mut resolved = String::with_capacity(value.len());
+    let mut rest = value;
+    while let Some(start) = rest.find(SECRET_REF_PREFIX) {
+        resolved.push_str(&rest[..start]);
+        let after = &rest[start + SECRET_REF_PREFIX.len()..];
+        let end = after
+            .find('}')
+            .ok_or_else(|| SecretError::Unterminated(key.to_string()))?;
+        let name = &after[..end];
+        validate_secret_name(name)?;
+        let secret = source
+            .get_secret(name)?
+            .ok_or_else(|| SecretError::Missing(name.to_string()))?;
+        resolved.push_str(&secret);
+        rest = &after[end + 1..];
+    }
+    resolved.push_str(rest);
+    Ok(resolved)
+}
+
+/// The variable names of an env map, sorted; what is safe to log about it.
+pub fn env_keys(env: &HashMap<String, String>) -> Vec<&str> {
+    let mut keys: Vec<&str> = env.keys().map(String::as_str).collect();
+    keys.sort_unstable();
+    keys
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct MapSecrets(HashMap<String, String>);
+
+    impl SecretSource for MapSecrets {
+        fn get_secret(&self, name: &str) -> Result<Option<String>, SecretError> {
+            Ok(self.0.get(name).cloned())
+        }
+    }
+
+    fn env(pairs: &[(&str, &str)]) -> HashMap<String, String> {
+        pairs
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn test_resolve_env_substitutes_secret_references() {
+        let source = MapSecrets(env(&[("OPENAI_KEY", "sk-test")]));
+        let resolved = resolve_env(
+            &env(&[
+                ("OPENAI_API_KEY", "${secret:OPENAI_KEY}"),
+                ("AUTH_HEADER", "Bearer ${secret:OPENAI_KEY}"),
+                ("OPENCODE_YOLO", "true"),
+            ]),
+            &source,
+        )
+        .unwrap();
+
+        assert_eq!(resolved["OPENAI_API_KEY"], "sk-test");
+        assert_eq!(resolved["AUTH_HEADER"], "Bearer sk-test");
+        assert_eq!(resolved["OPENCODE_YOLO"], "true");
+    }
+
+    #[test]
+    fn test_resolve_env_rejects_missing_or_malformed_references() {
+        let source = MapSecrets(HashMap::new());
+        assert!(matches!(
+            resolve_env(&env(&[("KEY", "${secret:ABSENT}")]), &source),
+            Err(SecretError::Missing(name)) if name == "ABSENT"
+        ));
+        assert!(matches!(
+            resolve_env(&env(&[("KEY", "${secret:ABSENT")]), &source),
+            Err(SecretError::Unterminated(key)) if key == "KEY"
+        ));
+        assert!(matches!(
+            resolve_env(&env(&[("KEY", "${secret:bad name}")]), &source),
+            Err(SecretError::InvalidName(_))
+        ));
+    }
+
+    #[test]
+    fn test_env_keys_leave_every_value_out() {
+        let env = env(&[
+            ("OPENAI_API_KEY", "${secret:OPENAI_KEY}"),
+            ("ANTHROPIC_API_KEY", "sk-plaintext"),
+        ]);
+        assert_eq!(env_keys(&env), vec!["ANTHROPIC_API_KEY", "OPENAI_API_KEY"]);
+    }
+}
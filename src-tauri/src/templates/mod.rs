@@ -136,6 +136,148 @@ pub struct RolePack {
 pub struct TemplateCatalog {
     pub templates: Vec<SessionTemplate>,
     pub role_packs: Vec<RolePack>,
+    /// Built-in and on-disk prompt templates with the placeholders each expects.
+    #[serde(default)]
+    pub prompt_templates: Vec<PromptTemplateInfo>,
+}
+
+/// A prompt template and the placeholders parsed out of it.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct PromptTemplateInfo {
+    /// Template name, e.g. `queen-hive` or `roles/backend`.
+    pub name: String,
+    pub is_builtin: bool,
+    /// A `<name>.md` file in the templates directory adds or overrides it.
+    pub on_disk: bool,
+    pub variables: Vec<TemplateVariable>,
+}
+
+/// A prompt template's effective text alongside its description.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct PromptTemplate {
+    #[serde(flatten)]
+    pub info: PromptTemplateInfo,
+    pub content: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct TemplateVariable {
+    pub name: String,
+    /// Used as a `{{#if name}}` block condition rather than substituted text.
+    pub conditional: bool,
+    /// Filled in by the engine itself on every render; anything else comes from
+    /// the launch that renders the template and is left verbatim when missing.
+    pub supplied_by_engine: bool,
+    pub description: Option<String>,
+}
+
+/// Placeholders the engine substitutes regardless of the caller's variables.
+const ENGINE_VARIABLES: &[(&str, &str)] = &[
+    ("session_id", "ID of the session being launched"),
+    ("project_path", "Directory the agent works in"),
+    ("task", "The agent's task, or \"Awaiting instructions\""),
+    ("api_base_url", "Base URL of the Hive Manager HTTP API"),
+    ("heartbeat_cadence", "How often the agent should post a heartbeat"),
+    (
+        "heartbeat_interval_secs",
+        "Maximum seconds between heartbeats",
+    ),
+    (
+        "queen_heartbeat_snippet",
+        "curl command posting a Queen heartbeat",
+    ),
+    (
+        "evaluator_idle_heartbeat_snippet",
+        "curl command posting an idle Evaluator heartbeat",
+    ),
+    (
+        "generic_heartbeat_snippet",
+        "curl command posting a heartbeat; needs `agent_id`, `heartbeat_status` and `heartbeat_summary`",
+    ),
+    (
+        "workers_list",
+        "Roster of the session's workers (Queen and planner templates)",
+    ),
+    ("planners_list", "Roster of the session's planners (Swarm Queen)"),
+    ("domain", "The planner's domain (planner template)"),
+];
+
+/// Template names map onto `<templates_dir>/<name>.md`, so allow only slug
+/// segments separated by `/` (`queen-hive`, `roles/backend`). The last segment
+/// may carry a locale suffix (`queen-hive.ja`).
+pub fn validate_template_name(name: &str) -> Result<(), TemplateError> {
+    let is_slug = |part: &str| {
+        !part.is_empty()
+            && part
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+    };
+    let (dirs, file) = name
+        .rsplit_once('/')
+        .map_or((None, name), |(dirs, file)| (Some(dirs), file));
+    let (stem, locale) = file
+        .split_once('.')
+        .map_or((file, None), |(stem, locale)| (stem, Some(locale)));
+    let valid = !name.is_empty()
+        && name.len() <= 128
+        && dirs.is_none_or(|dirs| dirs.split('/').all(is_slug))
+        && is_slug(stem)
+        && locale.is_none_or(|locale| normalize_locale(locale).as_deref() == Some(locale));
+    if valid {
+        Ok(())
+    } else {
+        Err(TemplateError::Invalid(format!(
+            "invalid template name '{}'",
+            name
+        )))
+    }
+}
+
+/// A prompt locale as it appears in template names: lowercase, `-` between
+/// subtags (`ja`, `pt-br`). `None` for blank or malformed values and for English,
+/// which the unsuffixed templates already are.
+pub fn normalize_locale(locale: &str) -> Option<String> {
+    let locale = locale.trim().to_ascii_lowercase().replace('_', "-");
+    let valid = locale.len() <= 16
+        && locale
+            .split('-')
+            .all(|subtag| !subtag.is_empty() && subtag.chars().all(|c| c.is_ascii_alphanumeric()));
+    (valid && locale != "en" && !locale.starts_with("en-")).then_some(locale)
+}
+
+/// Every `{{name}}` and `{{#if name}}` placeholder in a template, in order of
+/// first use.
+pub fn template_variables(template: &str) -> Vec<TemplateVariable> {
+    let mut variables: Vec<TemplateVariable> = Vec::new();
+    let mut rest = template;
+    while let Some(start) = rest.find("{{") {
+        rest = &rest[start + 2..];
+        let Some(end) = rest.find("}}") else {
+            break;
+        };
+        let tag = rest[..end].trim();
+        let (name, conditional) = match tag.strip_prefix("#if ") {
+            Some(name) => (name.trim(), true),
+            None => (tag, false),
+        };
+        if name.is_empty() || !name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_') {
+            continue;
+        }
+        rest = &rest[end + 2..];
+
+        if let Some(existing) = variables.iter_mut().find(|v| v.name == name) {
+            existing.conditional |= conditional;
+            continue;
+        }
+        let engine = ENGINE_VARIABLES.iter().find(|(key, _)| *key == name);
+        variables.push(TemplateVariable {
+            name: name.to_string(),
+            conditional,
+            supplied_by_engine: engine.is_some(),
+            description: engine.map(|(_, description)| description.to_string()),
+        });
+    }
+    variables
 }
 
 pub fn builtin_session_templates() -> Vec<SessionTemplate> {
@@ -295,6 +437,8 @@ pub fn builtin_role_packs() -> Vec<RolePack> {
 pub struct TemplateEngine {
     templates_dir: PathBuf,
     builtin_templates: HashMap<String, String>,
+    /// Preferred prompt language; see [`Self::with_locale`].
+    locale: Option<String>,
 }
 
 impl TemplateEngine {
@@ -303,11 +447,46 @@ impl TemplateEngine {
         let mut engine = Self {
             templates_dir,
             builtin_templates: HashMap::new(),
+            locale: None,
         };
         engine.load_builtin_templates();
+        engine.load_injection_templates();
         engine
     }
 
+    /// Render in `locale`: a template `<name>.<locale>` (on disk or built in) is
+    /// used in place of `<name>` where one exists, and `<name>` otherwise.
+    pub fn with_locale(mut self, locale: Option<&str>) -> Self {
+        self.locale = locale.and_then(normalize_locale);
+        self
+    }
+
+    /// Built-in nudges for `queen_inject` / `operator_inject`, rendered through
+    /// [`Self::render_injection`].
+    fn load_injection_templates(&mut self) {
+        for (name, template) in [
+            (
+                "injections/check-inbox",
+                "Check your inbox now: read the new messages in {{inbox_path}}, act on anything addressed to you, then carry on with your current task.",
+            ),
+            (
+                "injections/commit-now",
+                "Commit your work now: stage your changes in {{workspace_path}} and `git commit` them with a message describing what you finished. Do not push. Then carry on with your current task.",
+            ),
+            (
+                "injections/read-plan",
+                "Re-read the session plan at {{plan_path}} and check your remaining work against it before you continue.",
+            ),
+            (
+                "injections/worker-roster",
+                "Current workers in session {{session_id}}:\n{{workers_list}}\n\nReassign or unblock anyone who is idle or stuck.",
+            ),
+        ] {
+            self.builtin_templates
+                .insert(name.to_string(), template.to_string());
+        }
+    }
+
     /// Load built-in templates
     fn load_builtin_templates(&mut self) {
         // Backend worker role template
@@ -1826,6 +2005,38 @@ You are a Planner agent managing the {{domain}} domain in a Swarm session.
         self.render_prompt_text(&template, context)
     }
 
+    /// Render a named injection (`check-inbox` or `injections/check-inbox`). Fails
+    /// rather than returning text that still contains unfilled placeholders.
+    pub fn render_injection(
+        &self,
+        name: &str,
+        workers: &[WorkerInfo],
+        context: &PromptContext,
+    ) -> Result<String, TemplateError> {
+        let template_name = if name.starts_with("injections/") {
+            name.to_string()
+        } else {
+            format!("injections/{}", name)
+        };
+        validate_template_name(&template_name)?;
+
+        let rendered = self
+            .render_template(&template_name, context)?
+            .replace("{{workers_list}}", &self.format_workers_list(workers));
+        let missing: Vec<String> = template_variables(&rendered)
+            .into_iter()
+            .map(|variable| variable.name)
+            .collect();
+        if !missing.is_empty() {
+            return Err(TemplateError::Invalid(format!(
+                "template {} needs variables: {}",
+                template_name,
+                missing.join(", ")
+            )));
+        }
+        Ok(rendered.trim().to_string())
+    }
+
     /// Render queen prompt for a session
     pub fn render_queen_prompt(
         &self,
@@ -2115,24 +2326,37 @@ You are a Planner agent managing the {{domain}} domain in a Swarm session.
         lines.join("\n")
     }
 
-    /// Get a template by name
+    /// Get a template by name, preferring its translation into the engine's locale
     fn get_template(&self, name: &str) -> Result<String, TemplateError> {
+        if let Some(locale) = &self.locale {
+            if let Some(template) = self.find_template(&format!("{}.{}", name, locale))? {
+                return Ok(template);
+            }
+        }
+        self.find_template(name)?
+            .ok_or_else(|| TemplateError::NotFound(name.to_string()))
+    }
+
+    fn find_template(&self, name: &str) -> Result<Option<String>, TemplateError> {
         // First check for custom template on disk
-        let template_path = self.templates_dir.join(format!("{}.md", name));
+        let template_path = self.template_path(name);
         if template_path.exists() {
-            return fs::read_to_string(template_path).map_err(TemplateError::from);
+            return Ok(Some(fs::read_to_string(template_path)?));
         }
 
         // Fall back to built-in template
-        self.builtin_templates
-            .get(name)
-            .cloned()
-            .ok_or_else(|| TemplateError::NotFound(name.to_string()))
+        Ok(self.builtin_templates.get(name).cloned())
     }
 
     /// Save a custom template
     pub fn save_template(&self, name: &str, content: &str) -> Result<(), TemplateError> {
-        let template_path = self.templates_dir.join(format!("{}.md", name));
+        validate_template_name(name)?;
+        if content.trim().is_empty() {
+            return Err(TemplateError::Invalid(
+                "template content must not be empty".to_string(),
+            ));
+        }
+        let template_path = self.template_path(name);
 
         // Ensure parent directory exists
         if let Some(parent) = template_path.parent() {
@@ -2147,15 +2371,22 @@ You are a Planner agent managing the {{domain}} domain in a Swarm session.
     pub fn list_templates(&self) -> Vec<String> {
         let mut templates: Vec<String> = self.builtin_templates.keys().cloned().collect();
 
-        // Add custom templates from disk
-        if let Ok(entries) = fs::read_dir(&self.templates_dir) {
+        // Add custom templates from disk, including one level of folders (`roles/*.md`)
+        let mut dirs = vec![(self.templates_dir.clone(), String::new())];
+        while let Some((dir, prefix)) = dirs.pop() {
+            let Ok(entries) = fs::read_dir(&dir) else {
+                continue;
+            };
             for entry in entries.flatten() {
-                if let Some(name) = entry.file_name().to_str() {
-                    if name.ends_with(".md") {
-                        let template_name = name.trim_end_matches(".md").to_string();
-                        if !templates.contains(&template_name) {
-                            templates.push(template_name);
-                        }
+                let Some(name) = entry.file_name().to_str().map(str::to_string) else {
+                    continue;
+                };
+                if prefix.is_empty() && entry.path().is_dir() {
+                    dirs.push((entry.path(), format!("{}/", name)));
+                } else if let Some(stem) = name.strip_suffix(".md") {
+                    let template_name = format!("{}{}", prefix, stem);
+                    if !templates.contains(&template_name) {
+                        templates.push(template_name);
                     }
                 }
             }
@@ -2164,6 +2395,49 @@ You are a Planner agent managing the {{domain}} domain in a Swarm session.
         templates.sort();
         templates
     }
+
+    /// Describe a template and the placeholders it expects.
+    pub fn template_info(&self, name: &str) -> Result<PromptTemplateInfo, TemplateError> {
+        Ok(self.load_template(name)?.info)
+    }
+
+    /// A template's effective text: the on-disk override if any, else the built-in.
+    pub fn load_template(&self, name: &str) -> Result<PromptTemplate, TemplateError> {
+        validate_template_name(name)?;
+        let content = self.get_template(name)?;
+        Ok(PromptTemplate {
+            info: PromptTemplateInfo {
+                name: name.to_string(),
+                is_builtin: self.builtin_templates.contains_key(name),
+                on_disk: self.template_path(name).exists(),
+                variables: template_variables(&content),
+            },
+            content,
+        })
+    }
+
+    /// Remove a template's on-disk file, restoring the built-in if there is one.
+    /// Returns `false` when there was no file to remove.
+    pub fn delete_template(&self, name: &str) -> Result<bool, TemplateError> {
+        validate_template_name(name)?;
+        match fs::remove_file(self.template_path(name)) {
+            Ok(()) => Ok(true),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(false),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    fn template_path(&self, name: &str) -> PathBuf {
+        self.templates_dir.join(format!("{}.md", name))
+    }
+
+    /// [`Self::template_info`] for every available template.
+    pub fn template_catalog(&self) -> Vec<PromptTemplateInfo> {
+        self.list_templates()
+            .iter()
+            .filter_map(|name| self.template_info(name).ok())
+            .collect()
+    }
 }
 
 impl Default for TemplateEngine {
@@ -2180,8 +2454,9 @@ mod tests {
 
     use super::{
         builtin_role_packs, builtin_session_templates, heartbeat_cadence_label, heartbeat_snippet,
-        normalize_api_base_url, PromptContext, SessionTemplate, TemplateCatalog, TemplateEngine,
-        TemplateError, DEFAULT_API_BASE_URL, HEARTBEAT_MAX_INTERVAL_SECS,
+        normalize_api_base_url, template_variables, PromptContext, SessionTemplate,
+        TemplateCatalog, TemplateEngine, TemplateError, DEFAULT_API_BASE_URL,
+        HEARTBEAT_MAX_INTERVAL_SECS,
     };
 
     #[test]
@@ -2197,6 +2472,7 @@ mod tests {
         let catalog = TemplateCatalog {
             templates: builtin_session_templates(),
             role_packs: builtin_role_packs(),
+            prompt_templates: Vec::new(),
         };
 
         assert!(catalog.templates.len() >= 3);
@@ -2204,6 +2480,158 @@ mod tests {
         assert!(catalog.templates.iter().all(|template| template.is_builtin));
     }
 
+    #[test]
+    fn template_variables_lists_placeholders_in_order_of_first_use() {
+        let variables = template_variables(
+            "{{task}} in {{worktree_path}}\n{{#if smoke_test}}quick{{/if}} {{task}} {{ not a var }}",
+        );
+        let names: Vec<&str> = variables.iter().map(|v| v.name.as_str()).collect();
+        assert_eq!(names, vec!["task", "worktree_path", "smoke_test"]);
+        assert!(variables[0].supplied_by_engine);
+        assert!(variables[0].description.is_some());
+        assert!(!variables[1].supplied_by_engine);
+        assert!(variables[2].conditional);
+    }
+
+    #[test]
+    fn template_catalog_includes_builtin_and_on_disk_templates() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir_all(dir.path().join("roles")).unwrap();
+        std::fs::write(
+            dir.path().join("roles").join("reviewer.md"),
+            "Review {{branch}} for {{session_id}}",
+        )
+        .unwrap();
+        let engine = TemplateEngine::new(dir.path().to_path_buf());
+
+        let catalog = engine.template_catalog();
+        let backend = catalog
+            .iter()
+            .find(|info| info.name == "roles/backend")
+            .unwrap();
+        assert!(backend.is_builtin && !backend.on_disk);
+        assert!(backend.variables.iter().any(|v| v.name == "task"));
+
+        let reviewer = engine.template_info("roles/reviewer").unwrap();
+        assert!(!reviewer.is_builtin && reviewer.on_disk);
+        let names: Vec<&str> = reviewer.variables.iter().map(|v| v.name.as_str()).collect();
+        assert_eq!(names, vec!["branch", "session_id"]);
+        assert!(catalog.contains(&reviewer));
+
+        assert!(matches!(
+            engine.template_info("../secrets"),
+            Err(TemplateError::Invalid(_))
+        ));
+        assert!(matches!(
+            engine.template_info("roles/missing"),
+            Err(TemplateError::NotFound(_))
+        ));
+    }
+
+    #[test]
+    fn saved_templates_override_builtins_until_deleted() {
+        let dir = tempfile::tempdir().unwrap();
+        let engine = TemplateEngine::new(dir.path().to_path_buf());
+        let builtin = engine.load_template("roles/backend").unwrap();
+
+        engine
+            .save_template("roles/backend", "Backend override for {{task}}")
+            .unwrap();
+        let custom = engine.load_template("roles/backend").unwrap();
+        assert_eq!(custom.content, "Backend override for {{task}}");
+        assert!(custom.info.is_builtin && custom.info.on_disk);
+        assert!(engine
+            .list_templates()
+            .contains(&"roles/backend".to_string()));
+
+        assert!(engine.delete_template("roles/backend").unwrap());
+        assert!(!engine.delete_template("roles/backend").unwrap());
+        assert_eq!(engine.load_template("roles/backend").unwrap(), builtin);
+
+        assert!(matches!(
+            engine.save_template("roles/../../escape", "x"),
+            Err(TemplateError::Invalid(_))
+        ));
+        assert!(matches!(
+            engine.save_template("notes", "   "),
+            Err(TemplateError::Invalid(_))
+        ));
+    }
+
+    #[test]
+    fn localized_templates_are_preferred_and_fall_back_to_english() {
+        let dir = tempfile::tempdir().unwrap();
+        let engine = TemplateEngine::new(dir.path().to_path_buf()).with_locale(Some(" JA "));
+        engine
+            .save_template("roles/backend.ja", "バックエンド担当: {{task}}")
+            .unwrap();
+        let context = PromptContext {
+            task: Some("APIを実装する".to_string()),
+            ..PromptContext::default()
+        };
+
+        let role = WorkerRole::new("backend", "Backend", "claude");
+        assert_eq!(
+            engine.render_worker_prompt(&role, &context).unwrap(),
+            "バックエンド担当: APIを実装する"
+        );
+        let frontend = engine
+            .render_worker_prompt(&WorkerRole::new("frontend", "Frontend", "claude"), &context);
+        assert!(frontend.unwrap().contains("Frontend Worker Role"));
+        let english = TemplateEngine::new(dir.path().to_path_buf()).with_locale(Some("en-US"));
+        assert!(english
+            .render_worker_prompt(&role, &context)
+            .unwrap()
+            .contains("Backend Worker Role"));
+
+        assert!(engine
+            .list_templates()
+            .contains(&"roles/backend.ja".to_string()));
+        assert!(matches!(
+            engine.template_info("queen-hive.pt-br"),
+            Err(TemplateError::NotFound(_))
+        ));
+        for name in ["roles/backend.en", "roles.ja/backend", "queen-hive.ja.md"] {
+            assert!(matches!(
+                engine.save_template(name, "x"),
+                Err(TemplateError::Invalid(_))
+            ));
+        }
+    }
+
+    #[test]
+    fn render_injection_fills_session_context_and_rejects_missing_variables() {
+        let engine = TemplateEngine::default();
+        let mut variables = HashMap::new();
+        variables.insert(
+            "inbox_path".to_string(),
+            "/repo/.hive-manager/s1/conversations/worker-1.md".to_string(),
+        );
+        let context = PromptContext {
+            session_id: "s1".to_string(),
+            variables,
+            ..PromptContext::default()
+        };
+
+        let nudge = engine
+            .render_injection("check-inbox", &[], &context)
+            .unwrap();
+        assert!(nudge.contains("/repo/.hive-manager/s1/conversations/worker-1.md"));
+        let roster = engine
+            .render_injection("injections/worker-roster", &[], &context)
+            .unwrap();
+        assert!(roster.contains("No workers assigned yet."));
+
+        match engine.render_injection("read-plan", &[], &context) {
+            Err(TemplateError::Invalid(message)) => assert!(message.contains("plan_path")),
+            other => panic!("expected a missing-variable error, got {:?}", other),
+        }
+        assert!(matches!(
+            engine.render_injection("no-such-nudge", &[], &context),
+            Err(TemplateError::NotFound(_))
+        ));
+    }
+
     #[test]
     fn builtin_hives_use_opus_queens_and_gpt56_coding_principals() {
         let templates = builtin_session_templates();
@@ -1,8 +1,14 @@
-use notify::{Event, RecommendedWatcher, RecursiveMode, Watcher};
+mod mounts;
+
+use notify::{Config, Event, PollWatcher, RecursiveMode, Watcher};
 use serde::Serialize;
-use std::path::Path;
-use std::sync::{mpsc::channel, Arc, Mutex};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::{mpsc::channel, Arc, Mutex, Weak};
 use std::time::{Duration, Instant};
+use crate::session::plan_history;
+use crate::session::plan_parser;
+use crate::storage::{FileWatchConfig, WatchMode};
 use crate::tauri_shim::{AppHandle, Emitter};
 
 #[derive(Clone, Serialize)]
@@ -19,6 +25,14 @@ struct FusionVariantCompletedPayload {
     task_file: String,
 }
 
+#[derive(Clone, Serialize)]
+struct FusionVariantFailedPayload {
+    session_id: String,
+    variant_index: u8,
+    task_file: String,
+    status: String,
+}
+
 #[derive(Clone, Serialize)]
 struct DebateRoundCompletedPayload {
     session_id: String,
@@ -34,6 +48,47 @@ struct AgentTaskCompletedPayload {
     task_file: String,
 }
 
+/// `plan-update` payload. `file` is the file whose change triggered the update and
+/// `status` what it now reports (`COMPLETED`, `BLOCKED`, a peer event type, ...).
+/// `revision` and `changed_sections` are set when the change is a new plan.md
+/// revision, so the UI can highlight what the planner changed.
+#[derive(Clone, Serialize)]
+struct PlanUpdatePayload {
+    session_id: String,
+    file: Option<String>,
+    status: Option<String>,
+    revision: Option<u32>,
+    changed_sections: Vec<String>,
+}
+
+/// Per-file debounce for `plan-update`: editors and agents often write a file
+/// several times in quick succession, but a change to one file never holds back
+/// the update for another.
+struct PlanUpdateThrottle {
+    debounce: Duration,
+    last_emit: HashMap<PathBuf, Instant>,
+}
+
+impl PlanUpdateThrottle {
+    fn new(debounce: Duration) -> Self {
+        Self {
+            debounce,
+            last_emit: HashMap::new(),
+        }
+    }
+
+    /// Whether an update for `path` may go out now; records it if so.
+    fn allow(&mut self, path: &Path, now: Instant) -> bool {
+        if let Some(last) = self.last_emit.get(path) {
+            if now.duration_since(*last) < self.debounce {
+                return false;
+            }
+        }
+        self.last_emit.insert(path.to_path_buf(), now);
+        true
+    }
+}
+
 #[derive(Clone, Serialize)]
 struct PeerEventPayload {
     session_id: String,
@@ -41,9 +96,24 @@ struct PeerEventPayload {
     path: String,
 }
 
+/// Subdirectories of the session root whose files the watcher reacts to.
+const SESSION_SUBDIRS: [&str; 3] = ["tasks", "peer", "contracts"];
+
+/// First and longest wait between attempts to re-establish a failed watch.
+const REWATCH_BACKOFF_START: Duration = Duration::from_millis(500);
+const REWATCH_BACKOFF_MAX: Duration = Duration::from_secs(30);
+
+/// What the notify callback hands to the event thread.
+enum WatchMessage {
+    Event(Event),
+    Error(notify::Error),
+}
+
 pub struct TaskFileWatcher {
-    #[allow(dead_code)] // Must keep watcher alive to maintain file watching
-    watcher: RecommendedWatcher,
+    // Must keep watcher alive to maintain file watching. The event thread only
+    // holds a `Weak`, so dropping this stops the thread too.
+    #[allow(dead_code)]
+    watcher: Arc<Mutex<Box<dyn Watcher + Send>>>,
     #[allow(dead_code)]
     session_id: String,
 }
@@ -56,47 +126,84 @@ impl TaskFileWatcher {
         debate_worktrees_path: &Path,
         session_id: &str,
         app_handle: AppHandle,
+        file_watch: FileWatchConfig,
     ) -> Result<Self, notify::Error> {
         let (tx, rx) = channel();
-        let debounce = Duration::from_millis(500);
-        let last_emit = Arc::new(Mutex::new(Instant::now() - debounce));
-
-        let mut watcher = notify::recommended_watcher(move |res: Result<Event, _>| {
-            if let Ok(event) = res {
-                let _ = tx.send(event);
-            }
-        })?;
-
-        // Watch the tasks directory
-        let tasks_path = session_path.join("tasks");
-        std::fs::create_dir_all(&tasks_path).ok();
-        watcher.watch(&tasks_path, RecursiveMode::NonRecursive)?;
-        std::fs::create_dir_all(worktrees_path).ok();
-        watcher.watch(worktrees_path, RecursiveMode::Recursive)?;
-        std::fs::create_dir_all(fusion_worktrees_path).ok();
-        watcher.watch(fusion_worktrees_path, RecursiveMode::Recursive)?;
-        std::fs::create_dir_all(debate_worktrees_path).ok();
-        watcher.watch(debate_worktrees_path, RecursiveMode::Recursive)?;
-        let peer_path = session_path.join("peer");
-        std::fs::create_dir_all(&peer_path).ok();
-        watcher.watch(&peer_path, RecursiveMode::NonRecursive)?;
-        let contracts_path = session_path.join("contracts");
-        std::fs::create_dir_all(&contracts_path).ok();
-        watcher.watch(&contracts_path, RecursiveMode::NonRecursive)?;
-
+        let mut throttle = PlanUpdateThrottle::new(Duration::from_millis(500));
+
+        let handler = move |res: Result<Event, notify::Error>| {
+            let _ = tx.send(match res {
+                Ok(event) => WatchMessage::Event(event),
+                Err(e) => WatchMessage::Error(e),
+            });
+        };
+        let poll = match file_watch.mode {
+            WatchMode::Native => false,
+            WatchMode::Poll => true,
+            WatchMode::Auto => mounts::needs_polling(session_path),
+        };
+        let mut watcher: Box<dyn Watcher + Send> = if poll {
+            tracing::info!(
+                "Polling session files for {} every {}ms",
+                session_id,
+                file_watch.poll_interval_ms
+            );
+            let config = Config::default()
+                .with_poll_interval(Duration::from_millis(file_watch.poll_interval_ms));
+            Box::new(PollWatcher::new(handler, config)?)
+        } else {
+            Box::new(notify::recommended_watcher(handler)?)
+        };
+
+        // The session root is watched recursively, so `tasks/` and friends are
+        // covered even if something deletes and recreates them later.
+        let session_path = session_path.to_path_buf();
+        let roots = vec![
+            session_path.clone(),
+            worktrees_path.to_path_buf(),
+            fusion_worktrees_path.to_path_buf(),
+            debate_worktrees_path.to_path_buf(),
+        ];
+        Self::watch_all(watcher.as_mut(), &session_path, &roots)?;
+
+        let watcher = Arc::new(Mutex::new(watcher));
+        let weak_watcher = Arc::downgrade(&watcher);
         let session_id_owned = session_id.to_string();
         let app_handle_clone = app_handle.clone();
-        let last_emit_clone = Arc::clone(&last_emit);
 
         std::thread::spawn(move || {
-            while let Ok(event) = rx.recv() {
-                Self::handle_event(
-                    &event,
-                    &session_id_owned,
-                    &app_handle_clone,
-                    &last_emit_clone,
-                    debounce,
-                );
+            while let Ok(message) = rx.recv() {
+                match message {
+                    WatchMessage::Event(mut event) => {
+                        let root_removed = event.kind.is_remove()
+                            && event.paths.iter().any(|path| roots.contains(path));
+                        if root_removed {
+                            // A watched directory itself went away; recreate it and watch again.
+                            if !Self::rewatch(&weak_watcher, &session_path, &roots, &session_id_owned)
+                            {
+                                break;
+                            }
+                            continue;
+                        }
+                        event.paths.retain(|path| Self::is_relevant(&session_path, path));
+                        Self::handle_event(
+                            &event,
+                            &session_id_owned,
+                            &app_handle_clone,
+                            &mut throttle,
+                        );
+                    }
+                    WatchMessage::Error(e) => {
+                        tracing::warn!(
+                            "Task watcher for {} reported an error: {}",
+                            session_id_owned,
+                            e
+                        );
+                        if !Self::rewatch(&weak_watcher, &session_path, &roots, &session_id_owned) {
+                            break;
+                        }
+                    }
+                }
             }
         });
 
@@ -106,14 +213,73 @@ impl TaskFileWatcher {
         })
     }
 
-    fn is_debounced(last_emit: &Arc<Mutex<Instant>>, debounce: Duration) -> bool {
-        let mut last = last_emit.lock().unwrap();
-        let now = Instant::now();
-        if now.duration_since(*last) < debounce {
+    /// Create the watched directories (an agent's first write is often long after
+    /// the session starts) and watch each root recursively. Safe to call again to
+    /// re-establish the watches.
+    fn watch_all(
+        watcher: &mut dyn Watcher,
+        session_path: &Path,
+        roots: &[PathBuf],
+    ) -> Result<(), notify::Error> {
+        for subdir in SESSION_SUBDIRS {
+            std::fs::create_dir_all(session_path.join(subdir))?;
+        }
+        for root in roots {
+            std::fs::create_dir_all(root)?;
+            let _ = watcher.unwatch(root);
+            watcher.watch(root, RecursiveMode::Recursive)?;
+        }
+        Ok(())
+    }
+
+    /// Re-establish the watches, backing off exponentially until it works. Returns
+    /// false if the `TaskFileWatcher` has been dropped in the meantime.
+    fn rewatch(
+        watcher: &Weak<Mutex<Box<dyn Watcher + Send>>>,
+        session_path: &Path,
+        roots: &[PathBuf],
+        session_id: &str,
+    ) -> bool {
+        let mut delay = REWATCH_BACKOFF_START;
+        loop {
+            let Some(watcher) = watcher.upgrade() else {
+                return false;
+            };
+            let result = Self::watch_all(watcher.lock().unwrap().as_mut(), session_path, roots);
+            match result {
+                Ok(()) => {
+                    tracing::info!("Re-established task watcher for {}", session_id);
+                    return true;
+                }
+                Err(e) => tracing::warn!(
+                    "Failed to re-establish task watcher for {} (retrying in {:?}): {}",
+                    session_id,
+                    delay,
+                    e
+                ),
+            }
+            drop(watcher);
+            std::thread::sleep(delay);
+            delay = (delay * 2).min(REWATCH_BACKOFF_MAX);
+        }
+    }
+
+    /// Whether a changed path is one the watcher reacts to. Under the session root
+    /// that is plan.md itself and files directly inside [`SESSION_SUBDIRS`]; logs,
+    /// recordings and plan history are ignored. Paths in the worktrees pass through.
+    fn is_relevant(session_path: &Path, path: &Path) -> bool {
+        let Ok(relative) = path.strip_prefix(session_path) else {
             return true;
+        };
+        let parts: Vec<_> = relative
+            .components()
+            .filter_map(|component| component.as_os_str().to_str())
+            .collect();
+        match parts.as_slice() {
+            [file] => *file == "plan.md",
+            [dir, _] => SESSION_SUBDIRS.contains(dir),
+            _ => false,
         }
-        *last = now;
-        false
     }
 
     fn extract_worker_id(path: &Path) -> Option<u8> {
@@ -138,6 +304,13 @@ impl TaskFileWatcher {
         None
     }
 
+    fn failed_task_status(content: &str) -> Option<&'static str> {
+        ["BLOCKED", "FAILED"].into_iter().find(|status| {
+            content.contains(&format!("Status: {}", status))
+                || content.contains(&format!("**Status**: {}", status))
+        })
+    }
+
     fn extract_debate_round(path: &Path) -> Option<(u8, u8)> {
         let filename = path.file_name()?.to_str()?;
         if filename.starts_with("debate-debater-") && filename.ends_with("-task.md") {
@@ -191,12 +364,16 @@ impl TaskFileWatcher {
         event: &Event,
         session_id: &str,
         app_handle: &AppHandle,
-        last_emit: &Arc<Mutex<Instant>>,
-        debounce: Duration,
+        throttle: &mut PlanUpdateThrottle,
     ) {
-        let mut should_emit_plan_update = false;
-
+        // Every path in the event is handled; one that triggers nothing must not
+        // stop the ones after it from being looked at.
         for path in &event.paths {
+            if path.file_name().and_then(|name| name.to_str()) == Some("plan.md") {
+                Self::record_plan_revision(path, session_id, app_handle);
+                continue;
+            }
+
             if let Some(event_type) = Self::contract_event_type(path) {
                 let _ = app_handle.emit(
                     event_type,
@@ -206,7 +383,7 @@ impl TaskFileWatcher {
                         path: path.to_string_lossy().to_string(),
                     },
                 );
-                should_emit_plan_update = true;
+                Self::emit_plan_update(path, event_type, session_id, app_handle, throttle);
                 continue;
             }
 
@@ -219,7 +396,7 @@ impl TaskFileWatcher {
                         path: path.to_string_lossy().to_string(),
                     },
                 );
-                should_emit_plan_update = true;
+                Self::emit_plan_update(path, event_type, session_id, app_handle, throttle);
                 continue;
             }
 
@@ -278,21 +455,81 @@ impl TaskFileWatcher {
                         let _ = app_handle.emit("evaluator-task-completed", payload);
                     }
 
-                    should_emit_plan_update = true;
+                    Self::emit_plan_update(path, "COMPLETED", session_id, app_handle, throttle);
+                } else if let (Some(variant_index), Some(status)) =
+                    (fusion_variant_index, Self::failed_task_status(&content))
+                {
+                    let payload = FusionVariantFailedPayload {
+                        session_id: session_id.to_string(),
+                        variant_index,
+                        task_file: path.to_string_lossy().to_string(),
+                        status: status.to_string(),
+                    };
+                    let _ = app_handle.emit("fusion-variant-failed", payload);
+                    Self::emit_plan_update(path, status, session_id, app_handle, throttle);
                 }
             }
         }
+    }
 
-        if should_emit_plan_update && !Self::is_debounced(last_emit, debounce) {
-            let _ = app_handle.emit("plan-update", session_id);
+    /// Announce that `path` changed to `status`, unless it was announced moments ago.
+    fn emit_plan_update(
+        path: &Path,
+        status: &str,
+        session_id: &str,
+        app_handle: &AppHandle,
+        throttle: &mut PlanUpdateThrottle,
+    ) {
+        if !throttle.allow(path, Instant::now()) {
+            return;
+        }
+        let _ = app_handle.emit(
+            "plan-update",
+            PlanUpdatePayload {
+                session_id: session_id.to_string(),
+                file: Some(path.to_string_lossy().to_string()),
+                status: Some(status.to_string()),
+                revision: None,
+                changed_sections: Vec::new(),
+            },
+        );
+    }
+
+    /// Snapshot a changed plan.md into the session's plan history, store its parsed
+    /// form as plan.json and announce the new revision. Rewrites that leave the
+    /// content unchanged are not announced.
+    fn record_plan_revision(path: &Path, session_id: &str, app_handle: &AppHandle) {
+        let (Some(session_root), Ok(content)) = (path.parent(), std::fs::read_to_string(path))
+        else {
+            return;
+        };
+        if let Err(e) = plan_parser::store(session_root, &content) {
+            tracing::warn!("Failed to store structured plan for {}: {}", session_id, e);
+        }
+        match plan_history::record_revision(session_root, &content) {
+            Ok(Some(revision)) => {
+                let _ = app_handle.emit(
+                    "plan-update",
+                    PlanUpdatePayload {
+                        session_id: session_id.to_string(),
+                        file: Some(path.to_string_lossy().to_string()),
+                        status: None,
+                        revision: Some(revision.revision),
+                        changed_sections: revision.changed_sections,
+                    },
+                );
+            }
+            Ok(None) => {}
+            Err(e) => tracing::warn!("Failed to record plan revision for {}: {}", session_id, e),
         }
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use super::TaskFileWatcher;
-    use std::path::PathBuf;
+    use super::{PlanUpdateThrottle, TaskFileWatcher};
+    use std::path::{Path, PathBuf};
+    use std::time::{Duration, Instant};
 
     #[test]
     fn test_extract_worker_id() {
@@ -356,6 +593,26 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_failed_task_status() {
+        assert_eq!(
+            TaskFileWatcher::failed_task_status("## Status: BLOCKED\n"),
+            Some("BLOCKED")
+        );
+        assert_eq!(
+            TaskFileWatcher::failed_task_status("**Status**: FAILED"),
+            Some("FAILED")
+        );
+        assert_eq!(
+            TaskFileWatcher::failed_task_status("## Status: ACTIVE"),
+            None
+        );
+        assert_eq!(
+            TaskFileWatcher::failed_task_status("## Status: COMPLETED"),
+            None
+        );
+    }
+
     #[test]
     fn test_extract_debate_round() {
         assert_eq!(
@@ -445,4 +702,35 @@ mod tests {
             None
         );
     }
+
+    #[test]
+    fn test_plan_update_throttle_is_per_file() {
+        let mut throttle = PlanUpdateThrottle::new(Duration::from_millis(500));
+        let start = Instant::now();
+        let worker = Path::new("tasks/worker-1-task.md");
+        let contract = Path::new("contracts/milestone-1.md");
+
+        assert!(throttle.allow(worker, start));
+        assert!(!throttle.allow(worker, start + Duration::from_millis(100)));
+        assert!(throttle.allow(contract, start + Duration::from_millis(100)));
+        assert!(throttle.allow(worker, start + Duration::from_millis(600)));
+    }
+
+    #[test]
+    fn test_is_relevant_filters_session_root() {
+        let session = Path::new("/p/.hive-manager/s1");
+        assert!(TaskFileWatcher::is_relevant(session, &session.join("plan.md")));
+        assert!(TaskFileWatcher::is_relevant(session, &session.join("tasks/worker-1-task.md")));
+        assert!(TaskFileWatcher::is_relevant(session, &session.join("peer/qa-verdict.json")));
+        assert!(!TaskFileWatcher::is_relevant(session, &session.join("notes.md")));
+        assert!(!TaskFileWatcher::is_relevant(session, &session.join("recordings/a.cast")));
+        assert!(!TaskFileWatcher::is_relevant(
+            session,
+            &session.join("tasks/old/worker-1-task.md")
+        ));
+        assert!(TaskFileWatcher::is_relevant(
+            session,
+            Path::new("/p/.hive-manager/worktrees/s1/worker-1/worker-1-task.md")
+        ));
+    }
 }
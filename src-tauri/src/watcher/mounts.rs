@@ -0,0 +1,91 @@
+//! Detecting filesystems where native change notifications cannot be trusted.
+//!
+//! inotify, FSEvents and ReadDirectoryChangesW only see changes made through the
+//! local kernel. On NFS/SMB shares, and on the Windows drives WSL mounts under
+//! `/mnt/c` (9p or drvfs), writes from the other side of the mount never produce
+//! an event, so the watcher has to poll there instead.
+
+use std::path::Path;
+
+/// Filesystem types (as listed in `/proc/mounts`) that need polling.
+const POLLED_FS_TYPES: &[&str] = &[
+    "nfs", "nfs4", "cifs", "smbfs", "smb3", "9p", "v9fs", "drvfs", "afs", "ceph", "glusterfs",
+    "davfs", "fuse.sshfs", "fuse.rclone",
+];
+
+/// Whether `path` is on a network share or a WSL-mounted Windows drive.
+pub fn needs_polling(path: &Path) -> bool {
+    let path = path.canonicalize().unwrap_or_else(|_| path.to_path_buf());
+    if cfg!(windows) {
+        // UNC paths (`\\server\share`, canonicalized as `\\?\UNC\server\share`).
+        let path = path.to_string_lossy();
+        return path.starts_with(r"\\?\UNC\")
+            || (path.starts_with(r"\\") && !path.starts_with(r"\\?\"));
+    }
+    match std::fs::read_to_string("/proc/mounts") {
+        Ok(mounts) => fs_type(&mounts, &path).is_some_and(|fs| POLLED_FS_TYPES.contains(&fs)),
+        Err(_) => false,
+    }
+}
+
+/// The filesystem type of the mount `path` lives on, per a `/proc/mounts` listing:
+/// the entry with the longest mount point that contains `path`.
+fn fs_type<'a>(mounts: &'a str, path: &Path) -> Option<&'a str> {
+    mounts
+        .lines()
+        .filter_map(|line| {
+            let mut fields = line.split_whitespace();
+            let _device = fields.next()?;
+            let mount_point = unescape(fields.next()?);
+            let fs_type = fields.next()?;
+            path.starts_with(&mount_point)
+                .then_some((mount_point.len(), fs_type))
+        })
+        .max_by_key(|(len, _)| *len)
+        .map(|(_, fs_type)| fs_type)
+}
+
+/// `/proc/mounts` writes spaces, tabs, newlines and backslashes in mount points as
+/// octal escapes (`\040`).
+fn unescape(field: &str) -> String {
+    let mut out = String::with_capacity(field.len());
+    let mut rest = field;
+    while let Some(index) = rest.find('\\') {
+        out.push_str(&rest[..index]);
+        let escape = rest.get(index + 1..index + 4);
+        match escape.and_then(|digits| u8::from_str_radix(digits, 8).ok()) {
+            Some(byte) => {
+                out.push(byte as char);
+                rest = &rest[index + 4..];
+            }
+            None => {
+                out.push('\\');
+                rest = &rest[index + 1..];
+            }
+        }
+    }
+    out.push_str(rest);
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fs_type_picks_the_longest_mount_point() {
+        let mounts = "\
+/dev/sdb / ext4 rw,relatime 0 0
+drvfs /mnt/c 9p rw,noatime,dirsync,aname=drvfs 0 0
+server:/export /home/me/shared\\040projects nfs4 rw 0 0
+";
+        assert_eq!(fs_type(mounts, Path::new("/home/me/repo")), Some("ext4"));
+        assert_eq!(fs_type(mounts, Path::new("/mnt/c/Users/me/repo")), Some("9p"));
+        assert_eq!(
+            fs_type(mounts, Path::new("/home/me/shared projects/app")),
+            Some("nfs4")
+        );
+        // A prefix that is not a whole path component does not match.
+        assert_eq!(fs_type(mounts, Path::new("/mnt/cdrom")), Some("ext4"));
+    }
+}
@@ -0,0 +1,260 @@
+//! Periodic git snapshots of a worker's uncommitted progress.
+//!
+//! A checkpoint is a commit of the worktree's current files (tracked and
+//! untracked, minus `.hive-manager/`) on top of `HEAD`, stored under
+//! `refs/checkpoints/<session-id>/<worker>`. It is built through a throwaway
+//! index, so the worker's own index, working tree and branch are never touched.
+//! Each update is kept in the ref's reflog, which is what [`list_checkpoints`]
+//! reads, so a crashed agent's partial work stays recoverable with
+//! `git checkout <commit> -- .`.
+
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use serde::{Deserialize, Serialize};
+
+use super::git::{output_with_timeout, GIT_COMMAND_TIMEOUT};
+
+#[cfg(windows)]
+use std::os::windows::process::CommandExt;
+
+#[cfg(windows)]
+const CREATE_NO_WINDOW: u32 = 0x08000000;
+
+pub const CHECKPOINT_REF_PREFIX: &str = "refs/checkpoints";
+
+/// Identity recorded on checkpoint commits, so snapshots work in repos without
+/// a configured user.
+const CHECKPOINT_AUTHOR: (&str, &str) = ("Hive Manager", "hive-manager@localhost");
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Checkpoint {
+    /// Worktree the snapshot was taken from (`worker-1`, ...).
+    pub worker: String,
+    pub ref_name: String,
+    pub commit: String,
+    /// RFC 3339 time the snapshot was taken.
+    pub created_at: String,
+    pub message: String,
+}
+
+pub fn checkpoint_ref(session_id: &str, worker: &str) -> String {
+    format!("{}/{}/{}", CHECKPOINT_REF_PREFIX, session_id, worker)
+}
+
+/// Snapshot the worktree's current files to `ref_name`. Returns the new commit,
+/// or `None` when there is nothing to save: no changes against `HEAD`, or none
+/// since the previous checkpoint.
+pub fn create_checkpoint(
+    worktree_path: &Path,
+    ref_name: &str,
+    label: &str,
+) -> Result<Option<String>, String> {
+    let tree = snapshot_tree(worktree_path)?;
+    let head = git(worktree_path, &["rev-parse", "HEAD"], None)?;
+    if git(worktree_path, &["rev-parse", "HEAD^{tree}"], None)? == tree {
+        return Ok(None);
+    }
+    let previous_tree = git(
+        worktree_path,
+        &[
+            "rev-parse",
+            "--verify",
+            "-q",
+            &format!("{}^{{tree}}", ref_name),
+        ],
+        None,
+    )
+    .unwrap_or_default();
+    if previous_tree == tree {
+        return Ok(None);
+    }
+
+    let message = format!(
+        "checkpoint: {} at {}",
+        label,
+        head.get(..12).unwrap_or(&head)
+    );
+    let commit = git(
+        worktree_path,
+        &["commit-tree", &tree, "-p", &head, "-m", &message],
+        None,
+    )?;
+    git(
+        worktree_path,
+        &[
+            "update-ref",
+            "--create-reflog",
+            "-m",
+            &message,
+            ref_name,
+            &commit,
+        ],
+        None,
+    )?;
+    Ok(Some(commit))
+}
+
+/// Write the worktree's files to a tree object through a temporary index.
+fn snapshot_tree(worktree_path: &Path) -> Result<String, String> {
+    let index =
+        std::env::temp_dir().join(format!("hive-checkpoint-{}.index", uuid::Uuid::new_v4()));
+    // Starting from the real index lets `git add` reuse its stat cache.
+    let seeded = git(worktree_path, &["rev-parse", "--git-path", "index"], None)
+        .map(|path| resolve(worktree_path, &path))
+        .and_then(|path| std::fs::copy(path, &index).map_err(|e| e.to_string()))
+        .is_ok();
+
+    let result = (|| {
+        if !seeded {
+            git(worktree_path, &["read-tree", "HEAD"], Some(&index))?;
+        }
+        git(
+            worktree_path,
+            &["add", "-A", "--", ".", ":(exclude).hive-manager"],
+            Some(&index),
+        )?;
+        git(worktree_path, &["write-tree"], Some(&index))
+    })();
+    let _ = std::fs::remove_file(&index);
+    result
+}
+
+fn resolve(base: &Path, path: &str) -> PathBuf {
+    let path = PathBuf::from(path);
+    if path.is_absolute() {
+        path
+    } else {
+        base.join(path)
+    }
+}
+
+/// Every checkpoint recorded for a session, newest first per worker.
+pub fn list_checkpoints(repo_path: &Path, session_id: &str) -> Result<Vec<Checkpoint>, String> {
+    let prefix = format!("{}/{}/", CHECKPOINT_REF_PREFIX, session_id);
+    let refs = git(
+        repo_path,
+        &["for-each-ref", "--format=%(refname)", &prefix],
+        None,
+    )?;
+
+    let mut checkpoints = Vec::new();
+    for ref_name in refs.lines().filter(|line| !line.is_empty()) {
+        let worker = ref_name.strip_prefix(&prefix).unwrap_or(ref_name);
+        let log = git(
+            repo_path,
+            &["log", "-g", "--format=%H%x1f%cI%x1f%s", ref_name],
+            None,
+        )?;
+        for entry in log.lines() {
+            let mut fields = entry.splitn(3, '\x1f');
+            let (Some(commit), Some(created_at), Some(message)) =
+                (fields.next(), fields.next(), fields.next())
+            else {
+                continue;
+            };
+            checkpoints.push(Checkpoint {
+                worker: worker.to_string(),
+                ref_name: ref_name.to_string(),
+                commit: commit.to_string(),
+                created_at: created_at.to_string(),
+                message: message.to_string(),
+            });
+        }
+    }
+    Ok(checkpoints)
+}
+
+/// Run git and return its trimmed stdout, optionally against another index file.
+fn git(cwd: &Path, args: &[&str], index: Option<&Path>) -> Result<String, String> {
+    let mut cmd = Command::new("git");
+    cmd.args(args)
+        .current_dir(cwd)
+        .env("GIT_TERMINAL_PROMPT", "0")
+        .env("GIT_AUTHOR_NAME", CHECKPOINT_AUTHOR.0)
+        .env("GIT_AUTHOR_EMAIL", CHECKPOINT_AUTHOR.1)
+        .env("GIT_COMMITTER_NAME", CHECKPOINT_AUTHOR.0)
+        .env("GIT_COMMITTER_EMAIL", CHECKPOINT_AUTHOR.1);
+    if let Some(index) = index {
+        cmd.env("GIT_INDEX_FILE", index);
+    }
+
+    #[cfg(windows)]
+    cmd.creation_flags(CREATE_NO_WINDOW);
+
+    let output = output_with_timeout(&mut cmd, GIT_COMMAND_TIMEOUT)
+        .map_err(|e| format!("Failed to run git: {}", e))?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr).trim().to_string();
+        return Err(if stderr.is_empty() {
+            format!("git {} failed", args.join(" "))
+        } else {
+            stderr
+        });
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn run(repo: &Path, args: &[&str]) -> String {
+        git(repo, args, None).unwrap()
+    }
+
+    #[test]
+    fn test_checkpoint_snapshots_wip_without_touching_the_worktree() {
+        let dir = tempfile::tempdir().unwrap();
+        let repo = dir.path();
+        run(repo, &["init", "-b", "main"]);
+        std::fs::write(repo.join("README.md"), "base\n").unwrap();
+        run(repo, &["add", "README.md"]);
+        run(repo, &["commit", "-m", "initial commit"]);
+
+        let ref_name = checkpoint_ref("session-1", "worker-1");
+        assert_eq!(
+            create_checkpoint(repo, &ref_name, "worker-1").unwrap(),
+            None
+        );
+
+        std::fs::write(repo.join("README.md"), "base\nwip\n").unwrap();
+        std::fs::write(repo.join("new.rs"), "fn main() {}\n").unwrap();
+        std::fs::create_dir_all(repo.join(".hive-manager")).unwrap();
+        std::fs::write(repo.join(".hive-manager/task.md"), "task\n").unwrap();
+        let first = create_checkpoint(repo, &ref_name, "worker-1")
+            .unwrap()
+            .expect("uncommitted changes should be checkpointed");
+        assert_eq!(
+            run(repo, &["show", &format!("{first}:new.rs")]),
+            "fn main() {}"
+        );
+        assert!(git(
+            repo,
+            &["show", &format!("{first}:.hive-manager/task.md")],
+            None
+        )
+        .is_err());
+        // The worker's own index and branch are untouched.
+        assert_eq!(run(repo, &["diff", "--cached", "--name-only"]), "");
+        assert_eq!(run(repo, &["rev-list", "--count", "HEAD"]), "1");
+
+        assert_eq!(
+            create_checkpoint(repo, &ref_name, "worker-1").unwrap(),
+            None
+        );
+        std::fs::write(repo.join("new.rs"), "fn main() { run(); }\n").unwrap();
+        let second = create_checkpoint(repo, &ref_name, "worker-1")
+            .unwrap()
+            .unwrap();
+
+        let checkpoints = list_checkpoints(repo, "session-1").unwrap();
+        let commits: Vec<&str> = checkpoints.iter().map(|c| c.commit.as_str()).collect();
+        assert_eq!(commits, vec![second.as_str(), first.as_str()]);
+        assert_eq!(checkpoints[0].worker, "worker-1");
+        assert!(checkpoints[0]
+            .message
+            .starts_with("checkpoint: worker-1 at "));
+        assert!(list_checkpoints(repo, "session-2").unwrap().is_empty());
+    }
+}
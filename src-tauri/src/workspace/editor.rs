@@ -0,0 +1,128 @@
+//! Open a file at a line in the operator's editor (see `EditorConfig`).
+//!
+//! Coordination messages and learnings name files relative to the project, so a
+//! relative path is resolved against the session's project. Any path, absolute or
+//! not, must lie under the project or one of the session's worktrees.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use crate::cli::health::resolve_executable;
+use crate::storage::{canonicalize_within, EditorConfig, StorageError};
+
+#[cfg(windows)]
+use std::os::windows::process::CommandExt;
+
+#[cfg(windows)]
+const CREATE_NO_WINDOW: u32 = 0x08000000;
+
+/// The existing file `path` names. `roots` are the session's project, first, and its
+/// worktrees. A relative path is resolved against the project and an absolute one
+/// must already lie under one of the roots; neither may leave it through a symlink.
+pub fn resolve_file(roots: &[PathBuf], path: &Path) -> Result<PathBuf, StorageError> {
+    let project = roots.first().ok_or_else(|| {
+        StorageError::InvalidPath(format!("no project to resolve against: {}", path.display()))
+    })?;
+    let file = if path.is_absolute() {
+        let file = fs::canonicalize(path)?;
+        let inside = roots
+            .iter()
+            .filter_map(|root| fs::canonicalize(root).ok())
+            .any(|root| file.starts_with(root));
+        if !inside {
+            return Err(StorageError::InvalidPath(format!(
+                "not under the session's project or worktrees: {}",
+                path.display()
+            )));
+        }
+        file
+    } else {
+        canonicalize_within(project, path)?
+    };
+    if !file.is_file() {
+        return Err(StorageError::InvalidPath(format!("not a file: {}", path.display())));
+    }
+    Ok(file)
+}
+
+/// The editor's arguments with the location filled in. Lines and columns are
+/// 1-based.
+pub fn editor_args(config: &EditorConfig, file: &Path, line: u32, column: u32) -> Vec<String> {
+    let file = file.to_string_lossy();
+    config
+        .args
+        .iter()
+        .map(|arg| {
+            arg.replace("{file}", &file)
+                .replace("{line}", &line.max(1).to_string())
+                .replace("{column}", &column.max(1).to_string())
+        })
+        .collect()
+}
+
+/// Start the editor on `file` at `line`:`column` without waiting for it.
+pub fn open_in_editor(
+    config: &EditorConfig,
+    file: &Path,
+    line: u32,
+    column: u32,
+) -> Result<(), String> {
+    let args = editor_args(config, file, line, column);
+    // The editor is started directly, never through a shell. On Windows that finds
+    // `.cmd` launchers such as `code.cmd` too, and std quotes the arguments for them
+    // or refuses ones it cannot quote, so a file name cannot smuggle in commands.
+    let program = resolve_executable(&config.command)
+        .ok_or_else(|| format!("Editor not found on PATH: {}", config.command))?;
+    let mut cmd = Command::new(program);
+    #[cfg(windows)]
+    cmd.creation_flags(CREATE_NO_WINDOW);
+    cmd.args(&args);
+    if let Some(dir) = file.parent().filter(|dir| dir.is_dir()) {
+        cmd.current_dir(dir);
+    }
+
+    let mut child = cmd
+        .spawn()
+        .map_err(|e| format!("Failed to start {}: {}", config.command, e))?;
+    // Reap the launcher so it does not linger as a zombie.
+    std::thread::spawn(move || {
+        let _ = child.wait();
+    });
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_relative_paths_stay_in_the_project_and_fill_the_editor_args() {
+        let project = tempfile::tempdir().unwrap();
+        let root = project.path();
+        std::fs::create_dir_all(root.join("src")).unwrap();
+        std::fs::write(root.join("src/main.rs"), "fn main() {}\n").unwrap();
+
+        let elsewhere = tempfile::tempdir().unwrap();
+        let outside = elsewhere.path().join("secrets.txt");
+        std::fs::write(&outside, "token\n").unwrap();
+        let roots = [root.to_path_buf()];
+        let with_elsewhere = [root.to_path_buf(), elsewhere.path().to_path_buf()];
+
+        let file = resolve_file(&roots, Path::new("./src/main.rs")).unwrap();
+        assert_eq!(file, root.join("src/main.rs").canonicalize().unwrap());
+        assert_eq!(resolve_file(&roots, &file).unwrap(), file);
+        assert!(resolve_file(&roots, &outside).is_err());
+        assert!(resolve_file(&with_elsewhere, &outside).is_ok());
+        assert!(resolve_file(&roots, Path::new("../secrets.txt")).is_err());
+        assert!(resolve_file(&roots, Path::new("src")).is_err());
+        assert!(resolve_file(&[], Path::new("src/main.rs")).is_err());
+        assert!(matches!(
+            resolve_file(&roots, Path::new("src/missing.rs")),
+            Err(StorageError::Io(_))
+        ));
+
+        let args = editor_args(&EditorConfig::default(), Path::new("/work/app/lib.rs"), 12, 0);
+        assert_eq!(args, vec!["--goto", "/work/app/lib.rs:12:1"]);
+    }
+}
@@ -3,8 +3,11 @@
 //! Provides branch naming conventions and dirty state detection
 //! for cell-based worktree operations.
 
+use std::io::{Read, Write};
 use std::path::{Path, PathBuf};
-use std::process::Command;
+use std::process::{Command, Output, Stdio};
+use std::thread::JoinHandle;
+use std::time::{Duration, Instant};
 
 #[cfg(windows)]
 use std::os::windows::process::CommandExt;
@@ -16,6 +19,16 @@ use crate::domain::{CellType, SessionMode};
 use crate::runtime::WorktreeManager;
 use crate::session::{Session, SessionType};
 
+/// Upper bound on a single git invocation. A fetch stuck on the network or an
+/// auth prompt is killed instead of stalling whoever is waiting on it.
+pub const GIT_COMMAND_TIMEOUT: Duration = Duration::from_secs(120);
+
+const GIT_POLL_INTERVAL: Duration = Duration::from_millis(25);
+
+/// Pathspecs leaving out the session and Fusion directories Hive Manager keeps
+/// inside a project.
+const SESSION_DIRS: [&str; 2] = [":(exclude).hive-manager", ":(exclude).hive-fusion"];
+
 /// Generate a branch name for a cell based on session mode and cell type.
 ///
 /// # Naming Conventions
@@ -86,6 +99,86 @@ pub fn current_head(worktree_path: &Path) -> Result<String, String> {
     Ok(output.trim().to_string())
 }
 
+/// The commit `revision` names, as a full SHA.
+pub fn resolve_commit(worktree_path: &Path, revision: &str) -> Result<String, String> {
+    if revision.starts_with('-') {
+        return Err(format!("Invalid revision: {}", revision));
+    }
+    let commit = run_git(
+        worktree_path,
+        &["rev-parse", "--verify", "--quiet", &format!("{}^{{commit}}", revision)],
+    )?;
+    match commit.trim() {
+        "" => Err(format!("Unknown revision: {}", revision)),
+        commit => Ok(commit.to_string()),
+    }
+}
+
+/// Uncommitted changes outside Hive Manager's own directories, one
+/// `git status --porcelain` entry (`XY path`) each, untracked files included.
+pub fn uncommitted_changes(worktree_path: &Path) -> Result<Vec<String>, String> {
+    let output = run_git(
+        worktree_path,
+        &["status", "--porcelain", "--", ".", SESSION_DIRS[0], SESSION_DIRS[1]],
+    )?;
+    Ok(output
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .map(str::to_string)
+        .collect())
+}
+
+/// Stash every change `uncommitted_changes` reports, untracked files included, and
+/// return the stash commit.
+pub fn stash_changes(worktree_path: &Path, message: &str) -> Result<String, String> {
+    run_git(
+        worktree_path,
+        &[
+            "stash",
+            "push",
+            "--include-untracked",
+            "-m",
+            message,
+            "--",
+            ".",
+            SESSION_DIRS[0],
+            SESSION_DIRS[1],
+        ],
+    )?;
+    resolve_commit(worktree_path, "stash@{0}")
+}
+
+/// `git diff --stat` of the working tree against `base`, so uncommitted changes
+/// count as well as commits made since.
+pub fn diff_stat_since(worktree_path: &Path, base: &str) -> Result<String, String> {
+    let output = run_git(worktree_path, &["diff", "--stat", base, "--"])?;
+    Ok(output.trim_end().to_string())
+}
+
+/// Lines added plus lines deleted in the working tree against `base`, per
+/// `git diff --numstat`.
+pub fn lines_changed_since(worktree_path: &Path, base: &str) -> Result<u64, String> {
+    let output = run_git(worktree_path, &["diff", "--numstat", base, "--"])?;
+    Ok(crate::session::diff_budget::numstat_lines(&output))
+}
+
+/// Paths changed in the working tree against `base`, plus untracked files that
+/// are not ignored, relative to the repository root.
+pub fn files_changed_since(worktree_path: &Path, base: &str) -> Result<Vec<String>, String> {
+    let changed = run_git(worktree_path, &["diff", "--name-only", "-z", base, "--"])?;
+    let untracked = run_git(
+        worktree_path,
+        &["ls-files", "--others", "--exclude-standard", "--full-name", "-z"],
+    )?;
+    let mut paths: Vec<String> = Vec::new();
+    for path in changed.split('\0').chain(untracked.split('\0')) {
+        if !path.is_empty() && !paths.iter().any(|seen| seen == path) {
+            paths.push(path.to_string());
+        }
+    }
+    Ok(paths)
+}
+
 /// Check if a branch exists locally.
 pub fn branch_exists(worktree_path: &Path, branch_name: &str) -> Result<bool, String> {
     match run_git(
@@ -101,6 +194,62 @@ pub fn branch_exists(worktree_path: &Path, branch_name: &str) -> Result<bool, St
     }
 }
 
+/// Validate an operator-supplied branch name against git's ref naming rules
+/// (the subset enforced by `git check-ref-format --branch`).
+pub fn validate_branch_name(branch: &str) -> Result<(), String> {
+    let invalid = branch.is_empty()
+        || branch == "@"
+        || branch.starts_with('-')
+        || branch.starts_with('/')
+        || branch.ends_with('/')
+        || branch.ends_with('.')
+        || branch.ends_with(".lock")
+        || branch.contains("..")
+        || branch.contains("//")
+        || branch.contains("@{")
+        || branch
+            .split('/')
+            .any(|component| component.starts_with('.'))
+        || branch.chars().any(|c| {
+            c.is_ascii_control()
+                || c.is_whitespace()
+                || matches!(c, '~' | '^' | ':' | '?' | '*' | '[' | '\\')
+        });
+
+    if invalid {
+        Err(format!("Invalid branch name: {}", branch))
+    } else {
+        Ok(())
+    }
+}
+
+/// Check whether `commit` is reachable from `branch`.
+pub fn is_commit_on_branch(repo_path: &Path, commit: &str, branch: &str) -> Result<bool, String> {
+    let mut cmd = Command::new("git");
+    cmd.args(["merge-base", "--is-ancestor", commit, branch])
+        .current_dir(repo_path);
+
+    #[cfg(windows)]
+    cmd.creation_flags(CREATE_NO_WINDOW);
+
+    let output = output_with_timeout(&mut cmd, GIT_COMMAND_TIMEOUT)
+        .map_err(|e| format!("Failed to run git: {}", e))?;
+
+    // `--is-ancestor` exits 1 for "not an ancestor" and >1 for real errors.
+    match output.status.code() {
+        Some(0) => Ok(true),
+        Some(1) => Ok(false),
+        _ => {
+            let stderr = String::from_utf8_lossy(&output.stderr).trim().to_string();
+            Err(if stderr.is_empty() {
+                "Git command failed".to_string()
+            } else {
+                stderr
+            })
+        }
+    }
+}
+
 /// Fetch the latest state of a branch from origin.
 /// Returns Ok(()) on success, Err on failure (e.g. no remote, network issues).
 pub fn fetch_origin_branch(project_path: &Path, branch: &str) -> Result<(), String> {
@@ -251,7 +400,13 @@ pub fn remove_session_worktree_cell(
     Ok(())
 }
 
-pub fn cleanup_session_worktrees(session: &Session) -> Result<(), String> {
+/// Remove the session's worktrees. `fusion_root` is where a Fusion session's
+/// variant worktrees were recorded, when that is not the in-project default; the
+/// directory is removed too once empty.
+pub fn cleanup_session_worktrees(
+    session: &Session,
+    fusion_root: Option<&Path>,
+) -> Result<(), String> {
     let manager = WorktreeManager::new(&session.project_path);
     let worktrees = manager
         .list_worktrees()
@@ -259,7 +414,9 @@ pub fn cleanup_session_worktrees(session: &Session) -> Result<(), String> {
 
     let session_prefixes = match &session.session_type {
         SessionType::Fusion { .. } => {
-            vec![session.project_path.join(".hive-fusion").join(&session.id)]
+            let mut prefixes = vec![session.project_path.join(".hive-fusion").join(&session.id)];
+            prefixes.extend(fusion_root.map(Path::to_path_buf));
+            prefixes
         }
         SessionType::Debate { .. } => {
             vec![session.project_path.join(".hive-debate").join(&session.id)]
@@ -296,6 +453,10 @@ pub fn cleanup_session_worktrees(session: &Session) -> Result<(), String> {
     if let Err(err) = manager.prune_worktrees() {
         cleanup_errors.push(format!("worktree prune: {}", err.message));
     }
+    if let Some(root) = fusion_root {
+        // Only succeeds once every worktree under it is gone.
+        let _ = std::fs::remove_dir(root);
+    }
 
     if cleanup_errors.is_empty() {
         Ok(())
@@ -305,17 +466,50 @@ pub fn cleanup_session_worktrees(session: &Session) -> Result<(), String> {
 }
 
 /// Run a git command in the specified directory.
-fn run_git(cwd: &Path, args: &[&str]) -> Result<String, String> {
+pub(crate) fn run_git(cwd: &Path, args: &[&str]) -> Result<String, String> {
     let mut cmd = Command::new("git");
-    cmd.args(args).current_dir(cwd);
+    cmd.args(args)
+        .current_dir(cwd)
+        .env("GIT_TERMINAL_PROMPT", "0");
 
     #[cfg(windows)]
     cmd.creation_flags(CREATE_NO_WINDOW);
 
-    let output = cmd
-        .output()
+    let output = output_with_timeout(&mut cmd, GIT_COMMAND_TIMEOUT)
         .map_err(|e| format!("Failed to run git: {}", e))?;
+    git_stdout(args, output)
+}
+
+/// Run a git command on `tokio::process` without blocking a runtime thread.
+///
+/// The child is killed once `timeout` elapses, and also when the returned
+/// future is dropped, so callers cancel a git call simply by abandoning it
+/// (an aborted task, the losing branch of a `tokio::select!`).
+pub async fn run_git_async(cwd: &Path, args: &[&str], timeout: Duration) -> Result<String, String> {
+    let mut cmd = tokio::process::Command::new("git");
+    cmd.args(args)
+        .current_dir(cwd)
+        .env("GIT_TERMINAL_PROMPT", "0")
+        .stdin(Stdio::null())
+        .kill_on_drop(true);
 
+    #[cfg(windows)]
+    cmd.creation_flags(CREATE_NO_WINDOW);
+
+    let output = tokio::time::timeout(timeout, cmd.output())
+        .await
+        .map_err(|_| {
+            format!(
+                "git {} timed out after {}s",
+                args.join(" "),
+                timeout.as_secs()
+            )
+        })?
+        .map_err(|e| format!("Failed to run git: {}", e))?;
+    git_stdout(args, output)
+}
+
+fn git_stdout(args: &[&str], output: Output) -> Result<String, String> {
     if !output.status.success() {
         // Some git commands fail with specific meanings we can detect
         let stderr = String::from_utf8_lossy(&output.stderr).trim().to_string();
@@ -333,6 +527,70 @@ fn run_git(cwd: &Path, args: &[&str]) -> Result<String, String> {
     Ok(String::from_utf8_lossy(&output.stdout).to_string())
 }
 
+/// Blocking `Command::output` with a deadline: the child is killed and a
+/// `TimedOut` error returned if it has not exited within `timeout`. Pipes are
+/// drained on helper threads so a chatty child cannot stall on a full pipe.
+pub fn output_with_timeout(cmd: &mut Command, timeout: Duration) -> std::io::Result<Output> {
+    output_with_input_and_timeout(cmd, None, timeout)
+}
+
+/// [`output_with_timeout`] with `input` written to the child's stdin (closed
+/// right after) instead of stdin being null.
+pub fn output_with_input_and_timeout(
+    cmd: &mut Command,
+    input: Option<Vec<u8>>,
+    timeout: Duration,
+) -> std::io::Result<Output> {
+    cmd.stdin(if input.is_some() {
+        Stdio::piped()
+    } else {
+        Stdio::null()
+    })
+    .stdout(Stdio::piped())
+    .stderr(Stdio::piped());
+    let mut child = cmd.spawn()?;
+    if let (Some(input), Some(mut stdin)) = (input, child.stdin.take()) {
+        // A child that never reads its stdin must not block us on a full pipe.
+        std::thread::spawn(move || {
+            let _ = stdin.write_all(&input);
+        });
+    }
+    let stdout = drain_pipe(child.stdout.take());
+    let stderr = drain_pipe(child.stderr.take());
+
+    let deadline = Instant::now() + timeout;
+    let status = loop {
+        if let Some(status) = child.try_wait()? {
+            break status;
+        }
+        if Instant::now() >= deadline {
+            let _ = child.kill();
+            let _ = child.wait();
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::TimedOut,
+                format!("timed out after {}s", timeout.as_secs()),
+            ));
+        }
+        std::thread::sleep(GIT_POLL_INTERVAL);
+    };
+
+    Ok(Output {
+        status,
+        stdout: stdout.join().unwrap_or_default(),
+        stderr: stderr.join().unwrap_or_default(),
+    })
+}
+
+fn drain_pipe<R: Read + Send + 'static>(pipe: Option<R>) -> JoinHandle<Vec<u8>> {
+    std::thread::spawn(move || {
+        let mut buf = Vec::new();
+        if let Some(mut pipe) = pipe {
+            let _ = pipe.read_to_end(&mut buf);
+        }
+        buf
+    })
+}
+
 fn is_missing_worktree_error(message: &str) -> bool {
     let lower = message.to_lowercase();
     lower.contains("is not a working tree")
@@ -345,6 +603,7 @@ fn is_missing_worktree_error(message: &str) -> bool {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::http::tests::{init_git_repo_for_test, run_git_for_test};
 
     #[test]
     fn test_generate_branch_name_hive() {
@@ -375,6 +634,27 @@ mod tests {
         assert_eq!(branch, "resolver/session-789");
     }
 
+    #[test]
+    fn test_validate_branch_name() {
+        assert!(validate_branch_name("feat/session-branches").is_ok());
+        assert!(validate_branch_name("fix/issue-42").is_ok());
+
+        for invalid in [
+            "",
+            "-feat",
+            "feat/",
+            "feat//x",
+            "feat..x",
+            "feat x",
+            "feat/.hidden",
+            "feat.lock",
+            "feat~1",
+            "feat@{1}",
+        ] {
+            assert!(validate_branch_name(invalid).is_err(), "{invalid:?}");
+        }
+    }
+
     #[test]
     fn test_generate_branch_name_fusion_resolver() {
         let branch = generate_branch_name(
@@ -385,4 +665,57 @@ mod tests {
         );
         assert_eq!(branch, "resolver/session-abc");
     }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_output_with_timeout_kills_hung_command() {
+        let started = Instant::now();
+        let mut cmd = Command::new("sleep");
+        cmd.arg("30");
+        let err = output_with_timeout(&mut cmd, Duration::from_millis(100)).unwrap_err();
+        assert_eq!(err.kind(), std::io::ErrorKind::TimedOut);
+        assert!(started.elapsed() < Duration::from_secs(10));
+    }
+
+    #[test]
+    fn test_uncommitted_changes_are_found_and_stashed_without_session_dirs() {
+        let repo = tempfile::tempdir().unwrap();
+        let dir = repo.path();
+        init_git_repo_for_test(dir);
+        std::fs::write(dir.join("lib.rs"), "fn base() {}\n").unwrap();
+        run_git_for_test(dir, &["add", "."]);
+        run_git_for_test(dir, &["commit", "-qm", "Base"]);
+        let base = resolve_commit(dir, "HEAD").unwrap();
+
+        std::fs::create_dir_all(dir.join(".hive-manager")).unwrap();
+        std::fs::write(dir.join(".hive-manager/plan.md"), "plan\n").unwrap();
+        assert!(uncommitted_changes(dir).unwrap().is_empty());
+
+        std::fs::write(dir.join("lib.rs"), "fn feature() {}\n").unwrap();
+        std::fs::write(dir.join("notes.md"), "scratch\n").unwrap();
+        assert_eq!(
+            uncommitted_changes(dir).unwrap(),
+            vec![" M lib.rs".to_string(), "?? notes.md".to_string()]
+        );
+
+        let stash = stash_changes(dir, "before launch").unwrap();
+        assert_ne!(stash, base);
+        assert!(uncommitted_changes(dir).unwrap().is_empty());
+        assert!(dir.join(".hive-manager/plan.md").exists());
+        assert_eq!(resolve_commit(dir, "HEAD").unwrap(), base);
+    }
+
+    #[tokio::test]
+    async fn test_run_git_async_reports_git_errors() {
+        let dir = tempfile::tempdir().unwrap();
+        let version = run_git_async(dir.path(), &["--version"], GIT_COMMAND_TIMEOUT)
+            .await
+            .unwrap();
+        assert!(version.starts_with("git version"));
+        assert!(
+            run_git_async(dir.path(), &["rev-parse", "HEAD"], GIT_COMMAND_TIMEOUT)
+                .await
+                .is_err()
+        );
+    }
 }
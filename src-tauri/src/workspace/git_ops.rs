@@ -0,0 +1,308 @@
+//! Git operations a Queen asks the backend to carry out.
+//!
+//! Instead of running git itself, a Queen sends a short batch of
+//! [`GitOperation`]s to `POST /api/sessions/{id}/git`. Each one is checked before
+//! it runs. A commit needs a message and something to commit, and never picks up
+//! `.hive-manager/`. A new branch needs a valid name that is not taken yet. A push
+//! goes to `origin` for an existing local branch and is never forced. The batch
+//! stops at the first operation that fails; the ones after it are skipped.
+
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+use super::git::{branch_exists, current_head, resolve_commit, run_git, validate_branch_name};
+use super::pull_request::push_branch;
+
+/// Most operations accepted in one batch.
+pub const MAX_GIT_OPERATIONS: usize = 10;
+
+const EXCLUDED: &str = ":(exclude).hive-manager";
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "op", rename_all = "snake_case")]
+pub enum GitOperation {
+    /// Commit `paths`, or every change outside `.hive-manager/` when none are given.
+    Commit {
+        message: String,
+        #[serde(default)]
+        paths: Vec<String>,
+    },
+    /// Create `name` at `start_point` (`HEAD` by default) and, with `checkout`,
+    /// switch to it.
+    CreateBranch {
+        name: String,
+        #[serde(default)]
+        start_point: Option<String>,
+        #[serde(default)]
+        checkout: bool,
+    },
+    /// Push `branch` to `origin`, setting it as the upstream.
+    Push { branch: String },
+}
+
+impl GitOperation {
+    /// One-line description for logs and results.
+    pub fn summary(&self) -> String {
+        match self {
+            Self::Commit { message, .. } => {
+                format!("commit \"{}\"", message.lines().next().unwrap_or("").trim())
+            }
+            Self::CreateBranch { name, .. } => format!("create branch {}", name),
+            Self::Push { branch } => format!("push {}", branch),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum GitOperationStatus {
+    Done,
+    Failed,
+    /// Not attempted because an earlier operation failed.
+    Skipped,
+}
+
+impl GitOperationStatus {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Done => "done",
+            Self::Failed => "failed",
+            Self::Skipped => "skipped",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GitOperationOutcome {
+    pub operation: String,
+    pub status: GitOperationStatus,
+    /// The new commit, for a commit that went through.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub commit: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// Check `operation` against the repository at `repo` without changing anything.
+pub fn preflight(repo: &Path, operation: &GitOperation) -> Result<(), String> {
+    match operation {
+        GitOperation::Commit { message, paths } => {
+            if message.trim().is_empty() {
+                return Err("Commit message is empty".to_string());
+            }
+            for path in paths {
+                check_commit_path(path)?;
+            }
+            let unmerged = run_git(repo, &["diff", "--name-only", "--diff-filter=U"])?;
+            if !unmerged.trim().is_empty() {
+                return Err(format!(
+                    "Resolve the unmerged files first: {}",
+                    unmerged.split_whitespace().collect::<Vec<_>>().join(", ")
+                ));
+            }
+            let mut args = vec!["status", "--porcelain", "--"];
+            if paths.is_empty() {
+                args.extend([".", EXCLUDED]);
+            } else {
+                args.extend(paths.iter().map(String::as_str));
+            }
+            if run_git(repo, &args)?.trim().is_empty() {
+                return Err("Nothing to commit".to_string());
+            }
+            Ok(())
+        }
+        GitOperation::CreateBranch {
+            name, start_point, ..
+        } => {
+            validate_branch_name(name)?;
+            if branch_exists(repo, name)? {
+                return Err(format!("Branch {} already exists", name));
+            }
+            if let Some(start_point) = start_point {
+                resolve_commit(repo, start_point)?;
+            }
+            Ok(())
+        }
+        GitOperation::Push { branch } => {
+            validate_branch_name(branch)?;
+            if !branch_exists(repo, branch)? {
+                return Err(format!("No local branch named {}", branch));
+            }
+            run_git(repo, &["remote", "get-url", "origin"])
+                .map_err(|_| "The repository has no origin remote".to_string())?;
+            Ok(())
+        }
+    }
+}
+
+/// Carry out `operation`, which passed [`preflight`]. Returns the new commit for
+/// a commit.
+pub fn execute(repo: &Path, operation: &GitOperation) -> Result<Option<String>, String> {
+    match operation {
+        GitOperation::Commit { message, paths } => {
+            let mut add = vec!["add", "-A", "--"];
+            let mut commit = vec!["commit", "-m", message.as_str()];
+            if paths.is_empty() {
+                add.extend([".", EXCLUDED]);
+            } else {
+                add.extend(paths.iter().map(String::as_str));
+                // Only these paths, even when other changes are already staged.
+                commit.push("--");
+                commit.extend(paths.iter().map(String::as_str));
+            }
+            run_git(repo, &add)?;
+            run_git(repo, &commit)?;
+            current_head(repo).map(Some)
+        }
+        GitOperation::CreateBranch {
+            name,
+            start_point,
+            checkout,
+        } => {
+            let mut args = vec!["branch", name.as_str()];
+            if let Some(start_point) = start_point {
+                args.push(start_point.as_str());
+            }
+            run_git(repo, &args)?;
+            if *checkout {
+                run_git(repo, &["switch", name.as_str()])?;
+            }
+            Ok(None)
+        }
+        GitOperation::Push { branch } => push_branch(repo, branch).map(|_| None),
+    }
+}
+
+/// Check and run `operations` in order, stopping at the first failure.
+pub fn run_batch(repo: &Path, operations: &[GitOperation]) -> Vec<GitOperationOutcome> {
+    let mut failed = false;
+    operations
+        .iter()
+        .map(|operation| {
+            let operation_summary = operation.summary();
+            if failed {
+                return GitOperationOutcome {
+                    operation: operation_summary,
+                    status: GitOperationStatus::Skipped,
+                    commit: None,
+                    error: None,
+                };
+            }
+            match preflight(repo, operation).and_then(|_| execute(repo, operation)) {
+                Ok(commit) => GitOperationOutcome {
+                    operation: operation_summary,
+                    status: GitOperationStatus::Done,
+                    commit,
+                    error: None,
+                },
+                Err(error) => {
+                    failed = true;
+                    GitOperationOutcome {
+                        operation: operation_summary,
+                        status: GitOperationStatus::Failed,
+                        commit: None,
+                        error: Some(error),
+                    }
+                }
+            }
+        })
+        .collect()
+}
+
+/// Commit paths must stay inside the repository and out of `.hive-manager/`.
+fn check_commit_path(path: &str) -> Result<(), String> {
+    let trimmed = path.trim_start_matches("./");
+    let escapes = trimmed.is_empty()
+        || trimmed.starts_with('/')
+        || trimmed.starts_with('\\')
+        || trimmed.starts_with(':')
+        || trimmed.contains(":\\")
+        || trimmed.split(['/', '\\']).any(|segment| segment == "..");
+    if escapes {
+        return Err(format!("Invalid path: {}", path));
+    }
+    if trimmed == ".hive-manager" || trimmed.starts_with(".hive-manager/") {
+        return Err(format!("Session files are never committed: {}", path));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::http::tests::{init_git_repo_for_test, run_git_for_test as git};
+
+    fn commit(message: &str, paths: &[&str]) -> GitOperation {
+        GitOperation::Commit {
+            message: message.to_string(),
+            paths: paths.iter().map(|path| path.to_string()).collect(),
+        }
+    }
+
+    #[test]
+    fn test_batch_runs_checked_operations_and_stops_at_the_first_failure() {
+        let repo = tempfile::tempdir().unwrap();
+        let dir = repo.path();
+        init_git_repo_for_test(dir);
+        std::fs::write(dir.join("lib.rs"), "fn base() {}\n").unwrap();
+        git(dir, &["add", "."]);
+        git(dir, &["commit", "-qm", "Base"]);
+
+        std::fs::write(dir.join("lib.rs"), "fn feature() {}\n").unwrap();
+        std::fs::write(dir.join("notes.md"), "scratch\n").unwrap();
+        std::fs::create_dir_all(dir.join(".hive-manager")).unwrap();
+        std::fs::write(dir.join(".hive-manager/plan.md"), "plan\n").unwrap();
+
+        assert!(preflight(dir, &commit("  ", &[])).is_err());
+        assert!(preflight(dir, &commit("Sneak", &["../outside.rs"])).is_err());
+        assert!(preflight(dir, &commit("Sneak", &[".hive-manager/plan.md"])).is_err());
+
+        let outcomes = run_batch(
+            dir,
+            &[
+                commit("Add feature", &["lib.rs"]),
+                GitOperation::CreateBranch {
+                    name: "hive/feature".to_string(),
+                    start_point: None,
+                    checkout: true,
+                },
+                GitOperation::Push {
+                    branch: "hive/feature".to_string(),
+                },
+                commit("Never runs", &[]),
+            ],
+        );
+        let statuses: Vec<GitOperationStatus> =
+            outcomes.iter().map(|outcome| outcome.status).collect();
+        assert_eq!(
+            statuses,
+            vec![
+                GitOperationStatus::Done,
+                GitOperationStatus::Done,
+                GitOperationStatus::Failed,
+                GitOperationStatus::Skipped,
+            ]
+        );
+        assert_eq!(outcomes[0].commit.as_deref(), Some(current_head(dir).unwrap().as_str()));
+        assert!(outcomes[2].error.as_deref().unwrap().contains("origin"));
+
+        // Only the named path was committed, and the new branch is checked out.
+        let status = run_git(dir, &["status", "--porcelain"]).unwrap();
+        assert!(status.contains("notes.md"), "{status}");
+        assert!(!status.contains("lib.rs"), "{status}");
+        assert_eq!(
+            run_git(dir, &["rev-parse", "--abbrev-ref", "HEAD"]).unwrap().trim(),
+            "hive/feature"
+        );
+        assert!(preflight(
+            dir,
+            &GitOperation::CreateBranch {
+                name: "hive/feature".to_string(),
+                start_point: None,
+                checkout: false,
+            }
+        )
+        .is_err());
+    }
+}
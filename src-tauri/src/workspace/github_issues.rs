@@ -0,0 +1,295 @@
+//! Mirror a session's plan tasks onto GitHub through the GitHub CLI (`gh`).
+//!
+//! Once a plan is ready it can be linked to a parent issue in one of two ways:
+//! every task becomes a sub-issue of the parent, or the parent gets a single
+//! comment with a checklist of the tasks. The link is kept in `plan-issues.json`
+//! in the session directory. A periodic sync then closes a task's sub-issue, or
+//! ticks its box in the checklist, once the task is done. Tasks are matched by
+//! their position in the plan.
+
+use std::fs;
+use std::path::Path;
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use super::pull_request::run_tool;
+use crate::session::plan_parser::PlanTask;
+
+pub const PLAN_ISSUES_FILE: &str = "plan-issues.json";
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum PlanIssueMode {
+    /// One sub-issue per task, closed when the task is done.
+    #[default]
+    SubIssues,
+    /// One checklist comment on the parent issue, ticked as tasks are done.
+    Checklist,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LinkedTask {
+    /// 1-based position among the plan's tasks.
+    pub number: usize,
+    pub title: String,
+    /// The task's sub-issue, in sub-issue mode.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub issue_url: Option<String>,
+    pub closed: bool,
+}
+
+/// How a session's plan is mirrored on GitHub.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PlanIssueLink {
+    pub parent: u64,
+    pub mode: PlanIssueMode,
+    /// The checklist comment, in checklist mode.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub comment_id: Option<u64>,
+    pub tasks: Vec<LinkedTask>,
+}
+
+impl PlanIssueLink {
+    pub fn is_settled(&self) -> bool {
+        self.tasks.iter().all(|task| task.closed)
+    }
+}
+
+/// The plan's GitHub link, if it has one.
+pub fn load(session_root: &Path) -> Result<Option<PlanIssueLink>, String> {
+    let path = session_root.join(PLAN_ISSUES_FILE);
+    match fs::read_to_string(&path) {
+        Ok(json) => serde_json::from_str(&json)
+            .map(Some)
+            .map_err(|e| format!("Failed to parse {}: {}", path.display(), e)),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+        Err(e) => Err(format!("Failed to read {}: {}", path.display(), e)),
+    }
+}
+
+pub fn store(session_root: &Path, link: &PlanIssueLink) -> Result<(), String> {
+    let json = serde_json::to_string_pretty(link)
+        .map_err(|e| format!("Failed to serialize the plan's GitHub link: {}", e))?;
+    fs::write(session_root.join(PLAN_ISSUES_FILE), json)
+        .map_err(|e| format!("Failed to write {}: {}", PLAN_ISSUES_FILE, e))
+}
+
+/// The issue number in `reference`: `42`, `#42` or an issue URL.
+pub fn parse_issue_number(reference: &str) -> Option<u64> {
+    let reference = reference.trim().trim_end_matches('/');
+    let number = match reference.rsplit_once("/issues/") {
+        Some((_, number)) => number,
+        None => reference.trim_start_matches('#'),
+    };
+    number.parse().ok().filter(|number| *number > 0)
+}
+
+/// The checklist comment posted on the parent issue in checklist mode.
+pub fn checklist_body(session_id: &str, tasks: &[LinkedTask]) -> String {
+    let mut body = String::from("### Plan tasks\n\n");
+    for task in tasks {
+        let mark = if task.closed { "x" } else { " " };
+        body.push_str(&format!("- [{}] {}\n", mark, task.title));
+    }
+    body.push_str(&format!(
+        "\n_Kept up to date by Hive Manager for session `{}`._\n",
+        session_id
+    ));
+    body
+}
+
+/// Create the sub-issues or checklist for `tasks` under issue `parent` of the
+/// repository at `repo`.
+pub fn link_plan(
+    repo: &Path,
+    session_id: &str,
+    parent: u64,
+    mode: PlanIssueMode,
+    tasks: &[PlanTask],
+) -> Result<PlanIssueLink, String> {
+    let mut linked: Vec<LinkedTask> = tasks
+        .iter()
+        .map(|task| LinkedTask {
+            number: task.number,
+            title: task.title.clone(),
+            issue_url: None,
+            closed: false,
+        })
+        .collect();
+
+    let comment_id = match mode {
+        PlanIssueMode::SubIssues => {
+            for (created, task) in linked.iter_mut().enumerate() {
+                let body = format!(
+                    "Task {} of the plan for #{}, from Hive session `{}`.",
+                    task.number, parent, session_id
+                );
+                let url = create_issue(repo, &task.title, &body)
+                    .and_then(|url| add_sub_issue(repo, parent, &url).map(|_| url))
+                    .map_err(|e| {
+                        format!(
+                            "Created {} of {} sub-issues, then failed: {}",
+                            created,
+                            tasks.len(),
+                            e
+                        )
+                    })?;
+                task.issue_url = Some(url);
+            }
+            None
+        }
+        PlanIssueMode::Checklist => {
+            Some(post_comment(repo, parent, &checklist_body(session_id, &linked))?)
+        }
+    };
+
+    Ok(PlanIssueLink {
+        parent,
+        mode,
+        comment_id,
+        tasks: linked,
+    })
+}
+
+/// Close the sub-issues, or tick the checklist boxes, of the tasks numbered in
+/// `done`. Returns the numbers of the tasks closed by this call; `link` is only
+/// updated for what GitHub accepted.
+pub fn close_tasks(
+    repo: &Path,
+    session_id: &str,
+    link: &mut PlanIssueLink,
+    done: &[usize],
+) -> Result<Vec<usize>, String> {
+    let mut closed = Vec::new();
+    match link.mode {
+        PlanIssueMode::SubIssues => {
+            for task in &mut link.tasks {
+                if task.closed || !done.contains(&task.number) {
+                    continue;
+                }
+                if let Some(url) = task.issue_url.as_deref() {
+                    let args = ["issue", "close", url, "--reason", "completed"];
+                    if let Err(e) = run_tool("gh", repo, &args) {
+                        // Keep what was closed so far; the rest is retried next pass.
+                        if closed.is_empty() {
+                            return Err(e);
+                        }
+                        tracing::warn!("Failed to close {}: {}", url, e);
+                        break;
+                    }
+                }
+                task.closed = true;
+                closed.push(task.number);
+            }
+        }
+        PlanIssueMode::Checklist => {
+            let mut tasks = link.tasks.clone();
+            for task in tasks.iter_mut().filter(|task| !task.closed) {
+                if done.contains(&task.number) {
+                    task.closed = true;
+                    closed.push(task.number);
+                }
+            }
+            if closed.is_empty() {
+                return Ok(closed);
+            }
+            if let Some(comment_id) = link.comment_id {
+                let endpoint = format!("repos/{{owner}}/{{repo}}/issues/comments/{}", comment_id);
+                let body = format!("body={}", checklist_body(session_id, &tasks));
+                run_tool("gh", repo, &["api", "-X", "PATCH", &endpoint, "-f", &body])?;
+            }
+            link.tasks = tasks;
+        }
+    }
+    Ok(closed)
+}
+
+/// Open an issue with `gh issue create` and return its URL.
+fn create_issue(repo: &Path, title: &str, body: &str) -> Result<String, String> {
+    let output = run_tool("gh", repo, &["issue", "create", "--title", title, "--body", body])?;
+    last_url(&output)
+        .ok_or_else(|| format!("gh did not report an issue URL: {}", output.trim()))
+}
+
+/// Attach the issue at `child_url` to `parent` as a sub-issue. The sub-issues API
+/// takes the child's database ID rather than its number.
+fn add_sub_issue(repo: &Path, parent: u64, child_url: &str) -> Result<(), String> {
+    let child = parse_issue_number(child_url)
+        .ok_or_else(|| format!("Not an issue URL: {}", child_url))?;
+    let child_id = run_tool(
+        "gh",
+        repo,
+        &["api", &format!("repos/{{owner}}/{{repo}}/issues/{}", child), "--jq", ".id"],
+    )?;
+    let field = format!("sub_issue_id={}", child_id.trim());
+    let endpoint = format!("repos/{{owner}}/{{repo}}/issues/{}/sub_issues", parent);
+    run_tool("gh", repo, &["api", "-X", "POST", &endpoint, "-F", &field]).map(|_| ())
+}
+
+/// Comment on `issue` and return the comment's ID, taken from the
+/// `#issuecomment-<id>` URL that `gh issue comment` prints.
+fn post_comment(repo: &Path, issue: u64, body: &str) -> Result<u64, String> {
+    let issue = issue.to_string();
+    let output = run_tool("gh", repo, &["issue", "comment", &issue, "--body", body])?;
+    last_url(&output)
+        .and_then(|url| url.rsplit_once("#issuecomment-")?.1.parse().ok())
+        .ok_or_else(|| format!("gh did not report a comment URL: {}", output.trim()))
+}
+
+fn last_url(output: &str) -> Option<String> {
+    output
+        .lines()
+        .rev()
+        .map(str::trim)
+        .find(|line| line.starts_with("http"))
+        .map(str::to_string)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_issue_references_and_checklist_body() {
+        assert_eq!(parse_issue_number("42"), Some(42));
+        assert_eq!(parse_issue_number(" #42 "), Some(42));
+        assert_eq!(
+            parse_issue_number("https://github.com/acme/app/issues/42/"),
+            Some(42)
+        );
+        assert_eq!(parse_issue_number("https://github.com/acme/app/pull/42"), None);
+        assert_eq!(parse_issue_number("#0"), None);
+
+        let tasks = vec![
+            LinkedTask {
+                number: 1,
+                title: "Add export endpoint".to_string(),
+                issue_url: None,
+                closed: true,
+            },
+            LinkedTask {
+                number: 2,
+                title: "Wire up the button".to_string(),
+                issue_url: None,
+                closed: false,
+            },
+        ];
+        let body = checklist_body("abc", &tasks);
+        assert!(body.contains("- [x] Add export endpoint\n"), "{body}");
+        assert!(body.contains("- [ ] Wire up the button\n"), "{body}");
+        assert!(body.contains("session `abc`"), "{body}");
+
+        let dir = tempfile::tempdir().unwrap();
+        assert_eq!(load(dir.path()).unwrap(), None);
+        let link = PlanIssueLink {
+            parent: 7,
+            mode: PlanIssueMode::Checklist,
+            comment_id: Some(99),
+            tasks,
+        };
+        store(dir.path(), &link).unwrap();
+        assert_eq!(load(dir.path()).unwrap(), Some(link.clone()));
+        assert!(!link.is_settled());
+    }
+}
@@ -6,7 +6,15 @@
 //! # Architecture
 //!
 //! - [`manager`] - `WorkspaceManager` for high-level cell-based operations
+//! - [`editor`] - Opening a file at a line in the operator's editor
 //! - [`git`] - Git-specific helpers (branch naming, dirty state)
+//! - [`git_ops`] - Checked commit, branch and push operations run for the Queen
+//! - [`github_issues`] - Plan tasks mirrored as GitHub sub-issues or a checklist comment
+//! - [`pull_request`] - PR body composition and `gh`-based PR creation
+//! - [`checkpoint`] - Periodic WIP snapshots of worker worktrees under `refs/checkpoints/`
+//! - [`paths`] - Host path spelling for prompts (WSL vs native), home and app data dirs
+//! - [`project_artifacts`] - `.gitignore` coverage and cleanup of `.hive-manager/` session dirs
+//! - [`read_only`] - Snapshots of the main checkout that undo a judge's edits
 //!
 //! # Workspace Rules
 //!
@@ -22,7 +30,15 @@
 //! - Fusion candidate: `fusion/<session-id>/<candidate-name>`
 //! - Resolver: `resolver/<session-id>`
 
+pub mod checkpoint;
+pub mod editor;
 pub mod git;
+pub mod git_ops;
+pub mod github_issues;
 pub mod manager;
+pub mod paths;
+pub mod project_artifacts;
+pub mod pull_request;
+pub mod read_only;
 
 pub use manager::{WorkspaceError, WorkspaceManager, WorkspaceStatus};
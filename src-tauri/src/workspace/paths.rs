@@ -0,0 +1,180 @@
+//! Host path handling shared by storage, prompts and CLI launch.
+//!
+//! Hive Manager runs on Windows and Unix hosts, and on Windows some agent CLIs
+//! (Cursor) run inside WSL, where `C:\Users\x` is spelled `/mnt/c/Users/x`. Paths
+//! written into prompts go through a [`PathStyle`] picked for the CLI that reads
+//! them, and the WSL distro and agent binary come from one [`WslTarget`].
+
+use std::path::{Path, PathBuf};
+
+pub const DEFAULT_WSL_DISTRO: &str = "Ubuntu";
+pub const DEFAULT_WSL_BINARY_PATH: &str = "/root/.local/bin/agent";
+
+/// How the CLI reading a prompt spells host paths.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PathStyle {
+    /// The host path with forward slashes. Windows CLIs, Git Bash and Unix shells
+    /// all accept `C:/Users/x`.
+    Native,
+    /// Drive paths mapped under `/mnt/<drive>/` for CLIs running inside WSL.
+    Wsl,
+}
+
+impl PathStyle {
+    /// `cli` may be the agent CLI name or the command it was mapped to, so both
+    /// `cursor` and `wsl` run under WSL.
+    pub fn for_cli(cli: &str) -> Self {
+        match cli.trim() {
+            "cursor" | "wsl" => Self::Wsl,
+            _ => Self::Native,
+        }
+    }
+
+    pub fn render(self, path: &Path) -> String {
+        self.render_str(&path.to_string_lossy())
+    }
+
+    pub fn render_str(self, path: &str) -> String {
+        match self {
+            Self::Native => path.replace('\\', "/"),
+            Self::Wsl => to_wsl_path(path),
+        }
+    }
+}
+
+/// `path` with forward slashes, for prompts every supported CLI reads natively.
+pub fn prompt_path(path: &Path) -> String {
+    PathStyle::Native.render(path)
+}
+
+/// Map a Windows drive path into WSL (`D:\foo` -> `/mnt/d/foo`). Other paths only
+/// get forward slashes.
+pub fn to_wsl_path(path: &str) -> String {
+    let forward_slash_path = path.replace('\\', "/");
+    let bytes = forward_slash_path.as_bytes();
+
+    if bytes.len() >= 2 && bytes[0].is_ascii_alphabetic() && bytes[1] == b':' {
+        let drive = bytes[0].to_ascii_lowercase() as char;
+        let rest = forward_slash_path[2..].trim_start_matches('/');
+        if rest.is_empty() {
+            format!("/mnt/{drive}")
+        } else {
+            format!("/mnt/{drive}/{rest}")
+        }
+    } else {
+        forward_slash_path
+    }
+}
+
+/// The current user's home directory: `USERPROFILE` on Windows, `HOME` elsewhere.
+pub fn home_dir() -> Option<PathBuf> {
+    let var = if cfg!(windows) { "USERPROFILE" } else { "HOME" };
+    std::env::var_os(var)
+        .filter(|home| !home.is_empty())
+        .map(PathBuf::from)
+}
+
+/// Where Hive Manager keeps its own state: `%APPDATA%\hive-manager` on Windows,
+/// `~/.config/hive-manager` elsewhere.
+pub fn app_data_dir() -> Result<PathBuf, String> {
+    if cfg!(windows) {
+        std::env::var_os("APPDATA")
+            .map(|appdata| PathBuf::from(appdata).join("hive-manager"))
+            .ok_or_else(|| "APPDATA not set".to_string())
+    } else {
+        std::env::var_os("HOME")
+            .map(|home| PathBuf::from(home).join(".config").join("hive-manager"))
+            .ok_or_else(|| "HOME not set".to_string())
+    }
+}
+
+/// Expand a leading `~` in a path to the user's home directory so the value can
+/// be safely embedded in a shell command (a tilde inside quotes is NOT expanded
+/// by the shell). Returns the input unchanged if it doesn't start with `~` or
+/// the home directory cannot be determined.
+pub fn expand_tilde(path: &str) -> String {
+    let Some(rest) = path.strip_prefix('~') else {
+        return path.to_string();
+    };
+    // Only expand the current user's home (`~`, `~/...`, `~\...`). A bare `~user`
+    // form refers to another user's home and is not something we resolve — leave
+    // it untouched rather than mangling it into `<home>/user`.
+    if !rest.is_empty() && !rest.starts_with('/') && !rest.starts_with('\\') {
+        return path.to_string();
+    }
+    match home_dir() {
+        Some(home) => {
+            let home = home.to_string_lossy();
+            let rest = rest.trim_start_matches(['/', '\\']);
+            if rest.is_empty() {
+                home.into_owned()
+            } else {
+                format!("{}/{}", home.trim_end_matches(['/', '\\']), rest)
+            }
+        }
+        None => path.to_string(),
+    }
+}
+
+/// The WSL distro and in-distro agent binary that WSL-backed CLIs launch.
+/// `HIVE_WSL_DISTRO` and `HIVE_WSL_BINARY_PATH` override the defaults.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WslTarget {
+    pub distro: String,
+    pub binary_path: String,
+}
+
+impl WslTarget {
+    pub fn from_env() -> Self {
+        let var = |name: &str, default: &str| {
+            std::env::var(name)
+                .ok()
+                .filter(|value| !value.trim().is_empty())
+                .unwrap_or_else(|| default.to_string())
+        };
+        Self {
+            distro: var("HIVE_WSL_DISTRO", DEFAULT_WSL_DISTRO),
+            binary_path: var("HIVE_WSL_BINARY_PATH", DEFAULT_WSL_BINARY_PATH),
+        }
+    }
+
+    /// Arguments to `wsl` that start the agent binary in the distro.
+    pub fn launch_args(&self) -> Vec<String> {
+        vec![
+            "-d".to_string(),
+            self.distro.clone(),
+            self.binary_path.clone(),
+        ]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn to_wsl_path_converts_windows_drive_paths() {
+        assert_eq!(to_wsl_path(r"D:\foo\bar"), "/mnt/d/foo/bar");
+        assert_eq!(to_wsl_path("D:/foo/bar"), "/mnt/d/foo/bar");
+        assert_eq!(to_wsl_path(r"C:\Users\x"), "/mnt/c/Users/x");
+        assert_eq!(to_wsl_path("/tmp/x"), "/tmp/x");
+    }
+
+    #[test]
+    fn path_style_follows_the_cli_that_reads_the_prompt() {
+        let path = r"C:\repo\.hive-manager\tasks\worker-1-task.md";
+        assert_eq!(
+            PathStyle::for_cli("claude").render_str(path),
+            "C:/repo/.hive-manager/tasks/worker-1-task.md"
+        );
+        assert_eq!(
+            PathStyle::for_cli("cursor").render_str(path),
+            "/mnt/c/repo/.hive-manager/tasks/worker-1-task.md"
+        );
+        assert_eq!(PathStyle::for_cli(" wsl "), PathStyle::Wsl);
+        assert_eq!(
+            PathStyle::Wsl.render(Path::new("/home/x/repo")),
+            "/home/x/repo"
+        );
+    }
+}
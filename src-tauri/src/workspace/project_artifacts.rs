@@ -0,0 +1,342 @@
+//! Hive Manager's footprint inside a project repo.
+//!
+//! Sessions write their state to `.hive-manager/<session-id>/` and their worktrees
+//! to `.hive-manager/worktrees/<session-id>/` (`.hive-fusion/` and `.hive-debate/`
+//! for Fusion and Debate variants). This module keeps those roots out of git, either
+//! through the shared `.gitignore` or the clone's own `.git/info/exclude`, and
+//! finds and removes the per-session directories once a session is over.
+
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use serde::{Deserialize, Serialize};
+
+use super::git::{output_with_timeout, GIT_COMMAND_TIMEOUT};
+use crate::runtime::WorktreeManager;
+
+#[cfg(windows)]
+use std::os::windows::process::CommandExt;
+
+#[cfg(windows)]
+const CREATE_NO_WINDOW: u32 = 0x08000000;
+
+/// Directories under the project root that hold session artifacts.
+pub const ARTIFACT_ROOTS: [&str; 3] = [".hive-manager", ".hive-fusion", ".hive-debate"];
+
+const GITIGNORE_HEADER: &str = "# Hive Manager session artifacts";
+
+/// One session's directory under an artifact root.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SessionArtifact {
+    pub session_id: String,
+    pub path: PathBuf,
+    /// Holds git worktrees, which are unregistered before the directory is removed.
+    pub worktrees: bool,
+    pub bytes: u64,
+}
+
+/// Outcome of `SessionController::cleanup_project_artifacts`.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ArtifactCleanupReport {
+    pub project_path: String,
+    /// Nothing was deleted; `artifacts` lists what would be.
+    pub dry_run: bool,
+    pub artifacts: Vec<SessionArtifact>,
+    pub freed_bytes: u64,
+    /// Sessions whose artifacts were kept because they are still active.
+    pub skipped_active: Vec<String>,
+    /// Directories no known session owns, kept unless unknown ones were included.
+    pub skipped_unknown: Vec<String>,
+    /// `.gitignore` entries the project is missing; see [`ensure_gitignored`].
+    pub missing_gitignore_entries: Vec<String>,
+    pub errors: Vec<String>,
+}
+
+/// Artifact roots that `.gitignore` does not cover, as `.gitignore` entries
+/// (`/.hive-manager/`). `.hive-manager` is always checked; the Fusion and Debate
+/// roots only once they exist. Empty outside a git repository.
+pub fn missing_gitignore_entries(project_path: &Path) -> Vec<String> {
+    ARTIFACT_ROOTS
+        .iter()
+        .filter(|root| **root == ".hive-manager" || project_path.join(root).is_dir())
+        .filter(|root| is_ignored(project_path, root) == Some(false))
+        .map(|root| format!("/{}/", root))
+        .collect()
+}
+
+/// `None` when `project_path` is not inside a git work tree.
+fn is_ignored(project_path: &Path, root: &str) -> Option<bool> {
+    let mut cmd = Command::new("git");
+    cmd.args(["check-ignore", "-q", &format!("{}/", root)])
+        .current_dir(project_path)
+        .env("GIT_TERMINAL_PROMPT", "0");
+
+    #[cfg(windows)]
+    cmd.creation_flags(CREATE_NO_WINDOW);
+
+    match output_with_timeout(&mut cmd, GIT_COMMAND_TIMEOUT)
+        .ok()?
+        .status
+        .code()
+    {
+        Some(0) => Some(true),
+        Some(1) => Some(false),
+        _ => None,
+    }
+}
+
+/// Append the missing artifact roots to the project's `.gitignore`, creating it if
+/// needed. Returns the entries added.
+pub fn ensure_gitignored(project_path: &Path) -> Result<Vec<String>, String> {
+    let entries = missing_gitignore_entries(project_path);
+    if entries.is_empty() {
+        return Ok(entries);
+    }
+
+    let path = project_path.join(".gitignore");
+    let existing = match fs::read_to_string(&path) {
+        Ok(content) => content,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => String::new(),
+        Err(e) => return Err(format!("Failed to read {}: {}", path.display(), e)),
+    };
+    let mut block = String::new();
+    if !existing.is_empty() {
+        if !existing.ends_with('\n') {
+            block.push('\n');
+        }
+        block.push('\n');
+    }
+    block.push_str(GITIGNORE_HEADER);
+    block.push('\n');
+    for entry in &entries {
+        block.push_str(entry);
+        block.push('\n');
+    }
+
+    fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&path)
+        .and_then(|mut file| file.write_all(block.as_bytes()))
+        .map_err(|e| format!("Failed to update {}: {}", path.display(), e))?;
+    Ok(entries)
+}
+
+/// What [`ensure_excluded`] wrote.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ExcludeUpdate {
+    /// The repository's `info/exclude` file.
+    pub path: PathBuf,
+    /// Entries appended. They are unanchored (`.hive-manager/`), so they also
+    /// match when the project is a subdirectory of the repository.
+    pub added: Vec<String>,
+}
+
+/// Add every artifact root nothing ignores yet to the repository's
+/// `info/exclude`, which is local to this clone, so agent commits cannot pick up
+/// orchestration files without touching the project's shared `.gitignore`. Roots
+/// are covered before they exist. `None` outside a git repository.
+pub fn ensure_excluded(project_path: &Path) -> Result<Option<ExcludeUpdate>, String> {
+    let mut added = Vec::new();
+    for root in ARTIFACT_ROOTS {
+        match is_ignored(project_path, root) {
+            None => return Ok(None),
+            Some(true) => {}
+            Some(false) => added.push(format!("{}/", root)),
+        }
+    }
+
+    // `--git-path` resolves to the common dir from linked worktrees as well.
+    let relative = super::git::run_git(project_path, &["rev-parse", "--git-path", "info/exclude"])?;
+    let path = project_path.join(relative.trim());
+    if added.is_empty() {
+        return Ok(Some(ExcludeUpdate { path, added }));
+    }
+
+    let existing = match fs::read_to_string(&path) {
+        Ok(content) => content,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => String::new(),
+        Err(e) => return Err(format!("Failed to read {}: {}", path.display(), e)),
+    };
+    let mut block = String::new();
+    if !existing.is_empty() && !existing.ends_with('\n') {
+        block.push('\n');
+    }
+    block.push_str(GITIGNORE_HEADER);
+    block.push('\n');
+    for entry in &added {
+        block.push_str(entry);
+        block.push('\n');
+    }
+
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)
+            .map_err(|e| format!("Failed to create {}: {}", parent.display(), e))?;
+    }
+    fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&path)
+        .and_then(|mut file| file.write_all(block.as_bytes()))
+        .map_err(|e| format!("Failed to update {}: {}", path.display(), e))?;
+    Ok(Some(ExcludeUpdate { path, added }))
+}
+
+/// Every per-session directory under the project's artifact roots.
+pub fn session_artifacts(project_path: &Path) -> Vec<SessionArtifact> {
+    let hive_root = project_path.join(".hive-manager");
+    let mut parents = vec![
+        (hive_root.clone(), false),
+        (hive_root.join("worktrees"), true),
+    ];
+    parents.extend(
+        ARTIFACT_ROOTS[1..]
+            .iter()
+            .map(|root| (project_path.join(root), true)),
+    );
+
+    let mut artifacts = Vec::new();
+    for (parent, worktrees) in parents {
+        let Ok(entries) = fs::read_dir(&parent) else {
+            continue;
+        };
+        for entry in entries.flatten() {
+            let path = entry.path();
+            if !path.is_dir() || (!worktrees && entry.file_name() == "worktrees") {
+                continue;
+            }
+            artifacts.push(SessionArtifact {
+                session_id: entry.file_name().to_string_lossy().to_string(),
+                bytes: dir_size(&path),
+                path,
+                worktrees,
+            });
+        }
+    }
+    artifacts.sort_by(|a, b| a.session_id.cmp(&b.session_id).then(a.path.cmp(&b.path)));
+    artifacts
+}
+
+fn dir_size(path: &Path) -> u64 {
+    let Ok(entries) = fs::read_dir(path) else {
+        return 0;
+    };
+    entries
+        .flatten()
+        .map(|entry| match entry.file_type() {
+            Ok(kind) if kind.is_dir() => dir_size(&entry.path()),
+            Ok(_) => entry.metadata().map(|meta| meta.len()).unwrap_or(0),
+            Err(_) => 0,
+        })
+        .sum()
+}
+
+/// Delete an artifact directory, first unregistering any git worktrees in it so
+/// the repository is not left with dangling worktree entries.
+pub fn remove_artifact(project_path: &Path, artifact: &SessionArtifact) -> Result<(), String> {
+    if artifact.worktrees {
+        let manager = WorktreeManager::new(project_path);
+        if let Ok(worktrees) = manager.list_worktrees() {
+            for worktree in worktrees
+                .iter()
+                .filter(|worktree| worktree.path.starts_with(&artifact.path))
+            {
+                if let Err(err) = manager.remove_worktree(&worktree.path, true) {
+                    tracing::debug!(
+                        "Falling back to deleting worktree {}: {}",
+                        worktree.path.display(),
+                        err.message
+                    );
+                }
+            }
+        }
+        if artifact.path.exists() {
+            fs::remove_dir_all(&artifact.path)
+                .map_err(|e| format!("{}: {}", artifact.path.display(), e))?;
+        }
+        let _ = manager.prune_worktrees();
+        return Ok(());
+    }
+
+    fs::remove_dir_all(&artifact.path).map_err(|e| format!("{}: {}", artifact.path.display(), e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_gitignore_entries_are_appended_once_and_session_dirs_are_found() {
+        let dir = tempfile::tempdir().unwrap();
+        let project = dir.path();
+        let status = Command::new("git")
+            .args(["init", "-q"])
+            .current_dir(project)
+            .status()
+            .unwrap();
+        assert!(status.success());
+        fs::write(project.join(".gitignore"), "target/").unwrap();
+        fs::create_dir_all(project.join(".hive-manager/s1/tasks")).unwrap();
+        fs::write(project.join(".hive-manager/s1/plan.md"), "plan").unwrap();
+        fs::create_dir_all(project.join(".hive-manager/worktrees/s1/worker-1")).unwrap();
+        fs::create_dir_all(project.join(".hive-fusion/s2")).unwrap();
+
+        assert_eq!(
+            missing_gitignore_entries(project),
+            vec!["/.hive-manager/", "/.hive-fusion/"]
+        );
+        assert_eq!(
+            ensure_gitignored(project).unwrap(),
+            vec!["/.hive-manager/", "/.hive-fusion/"]
+        );
+        assert_eq!(
+            fs::read_to_string(project.join(".gitignore")).unwrap(),
+            "target/\n\n# Hive Manager session artifacts\n/.hive-manager/\n/.hive-fusion/\n"
+        );
+        assert!(ensure_gitignored(project).unwrap().is_empty());
+
+        let artifacts = session_artifacts(project);
+        let found: Vec<(&str, bool)> = artifacts
+            .iter()
+            .map(|artifact| (artifact.session_id.as_str(), artifact.worktrees))
+            .collect();
+        assert_eq!(found, vec![("s1", false), ("s1", true), ("s2", true)]);
+        assert_eq!(artifacts[0].bytes, 4);
+
+        remove_artifact(project, &artifacts[0]).unwrap();
+        assert!(!project.join(".hive-manager/s1").exists());
+        assert!(project.join(".hive-manager/worktrees/s1").exists());
+    }
+
+    #[test]
+    fn test_artifact_roots_are_excluded_locally_unless_already_ignored() {
+        let dir = tempfile::tempdir().unwrap();
+        let project = dir.path();
+        assert_eq!(ensure_excluded(project).unwrap(), None);
+
+        let status = Command::new("git")
+            .args(["init", "-q"])
+            .current_dir(project)
+            .status()
+            .unwrap();
+        assert!(status.success());
+        fs::write(project.join(".gitignore"), "/.hive-debate/\n").unwrap();
+
+        let update = ensure_excluded(project).unwrap().unwrap();
+        assert_eq!(update.added, vec![".hive-manager/", ".hive-fusion/"]);
+        let exclude = fs::read_to_string(project.join(".git/info/exclude")).unwrap();
+        assert!(
+            exclude.ends_with("# Hive Manager session artifacts\n.hive-manager/\n.hive-fusion/\n")
+        );
+        // The shared .gitignore is left alone.
+        assert_eq!(
+            fs::read_to_string(project.join(".gitignore")).unwrap(),
+            "/.hive-debate/\n"
+        );
+        assert!(missing_gitignore_entries(project).is_empty());
+
+        assert!(ensure_excluded(project).unwrap().unwrap().added.is_empty());
+    }
+}
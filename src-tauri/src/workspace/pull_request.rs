@@ -0,0 +1,260 @@
+//! Pull request helpers: compose a PR body from a session report, push the
+//! session branch, and open the PR through the GitHub CLI (`gh`).
+
+use std::path::Path;
+use std::process::Command;
+
+use super::git::{output_with_timeout, GIT_COMMAND_TIMEOUT};
+use crate::session::durations::{format_secs, SessionDurations};
+
+#[cfg(windows)]
+use std::os::windows::process::CommandExt;
+
+#[cfg(windows)]
+const CREATE_NO_WINDOW: u32 = 0x08000000;
+
+/// A completed worker task as listed in the PR body.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CompletedTask {
+    pub label: String,
+    pub summary: Option<String>,
+}
+
+/// Session facts the PR body is composed from.
+#[derive(Debug, Clone, Default)]
+pub struct PullRequestReport {
+    pub session_id: String,
+    pub plan_summary: Option<String>,
+    pub completed_tasks: Vec<CompletedTask>,
+    pub learnings: Vec<String>,
+    pub durations: Option<SessionDurations>,
+}
+
+/// Extract the summary of a `plan.md`: the section under the first `Summary` or
+/// `Overview` heading, falling back to the first non-heading paragraph.
+pub fn plan_summary(plan: &str) -> Option<String> {
+    let lines: Vec<&str> = plan.lines().collect();
+    let summary_heading = lines.iter().position(|line| {
+        let trimmed = line.trim_start();
+        if !trimmed.starts_with('#') {
+            return false;
+        }
+        let title = trimmed.trim_start_matches('#').trim().to_lowercase();
+        title.contains("summary") || title.contains("overview")
+    });
+
+    let section: Vec<&str> = match summary_heading {
+        Some(start) => lines[start + 1..]
+            .iter()
+            .take_while(|line| !line.trim_start().starts_with('#'))
+            .copied()
+            .collect(),
+        None => lines
+            .iter()
+            .skip_while(|line| line.trim().is_empty() || line.trim_start().starts_with('#'))
+            .take_while(|line| !line.trim().is_empty() && !line.trim_start().starts_with('#'))
+            .copied()
+            .collect(),
+    };
+
+    let text = section.join("\n").trim().to_string();
+    (!text.is_empty()).then_some(text)
+}
+
+/// Extract the first paragraph of a task file's `## Result` section.
+pub fn task_result_summary(task: &str) -> Option<String> {
+    let paragraph: Vec<&str> = task
+        .lines()
+        .skip_while(|line| !line.trim_start().starts_with("## Result"))
+        .skip(1)
+        .skip_while(|line| line.trim().is_empty())
+        .take_while(|line| !line.trim().is_empty() && !line.trim_start().starts_with('#'))
+        .collect();
+    let text = paragraph.join(" ").trim().to_string();
+    (!text.is_empty()).then_some(text)
+}
+
+/// Render the PR body markdown for a session report.
+pub fn compose_pull_request_body(report: &PullRequestReport) -> String {
+    let mut body = String::from("## Summary\n\n");
+    body.push_str(
+        report
+            .plan_summary
+            .as_deref()
+            .unwrap_or("_No plan summary recorded._"),
+    );
+    body.push_str("\n\n## Completed Tasks\n\n");
+    if report.completed_tasks.is_empty() {
+        body.push_str("_No completed tasks recorded._\n");
+    }
+    for task in &report.completed_tasks {
+        match &task.summary {
+            Some(summary) => body.push_str(&format!("- **{}**: {}\n", task.label, summary)),
+            None => body.push_str(&format!("- **{}**\n", task.label)),
+        }
+    }
+
+    if !report.learnings.is_empty() {
+        body.push_str("\n## Learnings\n\n");
+        for learning in &report.learnings {
+            body.push_str(&format!("- {}\n", learning));
+        }
+    }
+
+    if let Some(durations) = report.durations.as_ref().filter(|d| !d.phases.is_empty()) {
+        body.push_str(&format!(
+            "\n## Timing\n\n{} session, {} total\n\n",
+            durations.session_type,
+            format_secs(durations.total_secs)
+        ));
+        let mut listed = Vec::new();
+        for span in &durations.phases {
+            if listed.contains(&span.phase.as_str()) {
+                continue;
+            }
+            listed.push(span.phase.as_str());
+            body.push_str(&format!(
+                "- {}: {}\n",
+                span.phase,
+                format_secs(durations.phase_totals[&span.phase])
+            ));
+        }
+        for agent in &durations.agents {
+            body.push_str(&format!(
+                "- `{}` ({}): {}\n",
+                agent.agent_id,
+                agent.role,
+                format_secs(agent.secs)
+            ));
+        }
+    }
+
+    body.push_str(&format!("\n---\nHive session `{}`\n", report.session_id));
+    body
+}
+
+/// Push `branch` to `origin`, setting it as the upstream.
+pub fn push_branch(worktree_path: &Path, branch: &str) -> Result<(), String> {
+    run_tool("git", worktree_path, &["push", "-u", "origin", branch]).map(|_| ())
+}
+
+/// Open a pull request for `branch` with `gh pr create` and return its URL.
+pub fn open_pull_request(
+    worktree_path: &Path,
+    branch: &str,
+    base: Option<&str>,
+    title: &str,
+    body_file: &Path,
+    draft: bool,
+) -> Result<String, String> {
+    let body_file = body_file.to_string_lossy().to_string();
+    let mut args = vec![
+        "pr",
+        "create",
+        "--head",
+        branch,
+        "--title",
+        title,
+        "--body-file",
+        body_file.as_str(),
+    ];
+    if let Some(base) = base {
+        args.extend(["--base", base]);
+    }
+    if draft {
+        args.push("--draft");
+    }
+
+    let output = run_tool("gh", worktree_path, &args)?;
+    // `gh pr create` prints the PR URL as the last line of stdout.
+    output
+        .lines()
+        .rev()
+        .map(str::trim)
+        .find(|line| line.starts_with("http"))
+        .map(str::to_string)
+        .ok_or_else(|| format!("gh did not report a pull request URL: {}", output.trim()))
+}
+
+pub(crate) fn run_tool(program: &str, cwd: &Path, args: &[&str]) -> Result<String, String> {
+    let mut cmd = Command::new(program);
+    cmd.args(args).current_dir(cwd);
+
+    #[cfg(windows)]
+    cmd.creation_flags(CREATE_NO_WINDOW);
+
+    let output = output_with_timeout(&mut cmd, GIT_COMMAND_TIMEOUT)
+        .map_err(|e| format!("Failed to run {}: {}", program, e))?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr).trim().to_string();
+        return Err(if stderr.is_empty() {
+            format!("{} command failed", program)
+        } else {
+            stderr
+        });
+    }
+
+    Ok(String::from_utf8_lossy(&output.stdout).to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_plan_summary_prefers_summary_section() {
+        let plan = "# Plan\n\nIntro text.\n\n## Summary\n\nAdd login flow.\nKeep API stable.\n\n## Tasks\n- one\n";
+        assert_eq!(
+            plan_summary(plan).as_deref(),
+            Some("Add login flow.\nKeep API stable.")
+        );
+    }
+
+    #[test]
+    fn test_plan_summary_falls_back_to_first_paragraph() {
+        let plan = "# Plan\n\nRefactor the parser.\nAdd tests.\n\n## Tasks\n";
+        assert_eq!(
+            plan_summary(plan).as_deref(),
+            Some("Refactor the parser.\nAdd tests.")
+        );
+        assert_eq!(plan_summary("# Plan\n"), None);
+    }
+
+    #[test]
+    fn test_task_result_summary_reads_result_section() {
+        let task =
+            "## Status: COMPLETED\n\n## Result\n\nAdded the endpoint\nwith tests.\n\nMore notes.\n";
+        assert_eq!(
+            task_result_summary(task).as_deref(),
+            Some("Added the endpoint with tests.")
+        );
+        assert_eq!(task_result_summary("## Status: COMPLETED\n"), None);
+    }
+
+    #[test]
+    fn test_compose_pull_request_body_lists_tasks_and_learnings() {
+        let body = compose_pull_request_body(&PullRequestReport {
+            session_id: "session-1".to_string(),
+            plan_summary: Some("Add login flow.".to_string()),
+            completed_tasks: vec![
+                CompletedTask {
+                    label: "Backend".to_string(),
+                    summary: Some("Added the endpoint.".to_string()),
+                },
+                CompletedTask {
+                    label: "Frontend".to_string(),
+                    summary: None,
+                },
+            ],
+            learnings: vec!["Prefer the action registry.".to_string()],
+            durations: None,
+        });
+
+        assert!(body.starts_with("## Summary\n\nAdd login flow.\n\n## Completed Tasks\n"));
+        assert!(body.contains("- **Backend**: Added the endpoint.\n"));
+        assert!(body.contains("- **Frontend**\n"));
+        assert!(body.contains("## Learnings\n\n- Prefer the action registry.\n"));
+        assert!(body.ends_with("Hive session `session-1`\n"));
+    }
+}
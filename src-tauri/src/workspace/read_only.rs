@@ -0,0 +1,161 @@
+//! Holding an agent that must not edit code, such as a Fusion or Debate judge,
+//! to a read-only main checkout.
+//!
+//! Before the agent starts, the current state of the tracked files is captured
+//! with `git stash create`. That writes a commit without touching the index or
+//! the working tree, and falls back to `HEAD` when nothing is modified. While
+//! the agent runs, every tracked file that differs from the snapshot is restored
+//! from it. The operator's own uncommitted changes therefore survive, and only
+//! the agent's edits are undone. `.hive-manager/`, where judges write their
+//! reports, is left alone.
+//!
+//! Git cannot tell who made a change, so anything the operator changes in the
+//! main checkout while a judge is guarded is reverted along with the judge's
+//! edits. Untracked files are never touched, but a file staged with `git add`
+//! during that window is unstaged and deleted.
+
+use std::path::Path;
+
+use super::git::{current_head, run_git};
+
+const EXCLUDED: &str = ":(exclude).hive-manager";
+
+/// Commit holding the tracked files as they are now.
+pub fn snapshot(repo: &Path) -> Result<String, String> {
+    // `stash create` records a commit, so it needs an identity even where none
+    // is configured.
+    let stash = run_git(
+        repo,
+        &[
+            "-c",
+            "user.name=Hive Manager",
+            "-c",
+            "user.email=hive-manager@localhost",
+            "stash",
+            "create",
+        ],
+    )?;
+    match stash.trim() {
+        "" => current_head(repo),
+        commit => Ok(commit.to_string()),
+    }
+}
+
+/// Tracked files that differ from a snapshot.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Edits {
+    /// Files in the snapshot that were changed or deleted.
+    pub changed: Vec<String>,
+    /// Files added to the index that the snapshot does not have.
+    pub added: Vec<String>,
+}
+
+impl Edits {
+    pub fn is_empty(&self) -> bool {
+        self.changed.is_empty() && self.added.is_empty()
+    }
+
+    /// Every edited path, changed ones first.
+    pub fn paths(&self) -> impl Iterator<Item = &str> {
+        self.changed.iter().chain(&self.added).map(String::as_str)
+    }
+}
+
+/// Tracked files whose contents differ from `snapshot`.
+pub fn modified_since(repo: &Path, snapshot: &str) -> Result<Edits, String> {
+    // Without `--no-renames` a renamed file would only be listed under its new name.
+    let output = run_git(
+        repo,
+        &[
+            "diff",
+            "--name-status",
+            "--no-renames",
+            "-z",
+            snapshot,
+            "--",
+            ".",
+            EXCLUDED,
+        ],
+    )?;
+    let mut edits = Edits::default();
+    let mut fields = output.split('\0').filter(|field| !field.is_empty());
+    while let (Some(status), Some(path)) = (fields.next(), fields.next()) {
+        if status == "A" {
+            edits.added.push(path.to_string());
+        } else {
+            edits.changed.push(path.to_string());
+        }
+    }
+    Ok(edits)
+}
+
+/// Put `edits` back the way they were in `snapshot`. Changed files are restored
+/// without touching the index; added ones are unstaged and deleted, since
+/// `git restore` fails on paths the snapshot does not have.
+pub fn restore(repo: &Path, snapshot: &str, edits: &Edits) -> Result<(), String> {
+    if !edits.changed.is_empty() {
+        let source = format!("--source={}", snapshot);
+        let mut args = vec!["restore", source.as_str(), "--worktree", "--"];
+        args.extend(edits.changed.iter().map(String::as_str));
+        run_git(repo, &args)?;
+    }
+    if !edits.added.is_empty() {
+        let mut args = vec!["rm", "--cached", "--force", "--quiet", "--"];
+        args.extend(edits.added.iter().map(String::as_str));
+        run_git(repo, &args)?;
+        for path in &edits.added {
+            match std::fs::remove_file(repo.join(path)) {
+                Ok(()) => {}
+                Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+                Err(e) => return Err(format!("Failed to delete {}: {}", path, e)),
+            }
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::http::tests::{init_git_repo_for_test, run_git_for_test as git};
+
+    #[test]
+    fn test_restore_undoes_only_changes_made_after_the_snapshot() {
+        let repo = tempfile::tempdir().unwrap();
+        let dir = repo.path();
+        init_git_repo_for_test(dir);
+        std::fs::write(dir.join("lib.rs"), "fn base() {}\n").unwrap();
+        std::fs::write(dir.join("notes.md"), "base\n").unwrap();
+        git(dir, &["add", "."]);
+        git(dir, &["commit", "-qm", "Base"]);
+
+        // The operator's uncommitted edit predates the snapshot and is kept.
+        std::fs::write(dir.join("notes.md"), "operator wip\n").unwrap();
+        let snapshot = snapshot(dir).unwrap();
+
+        std::fs::write(dir.join("lib.rs"), "fn judge_edit() {}\n").unwrap();
+        std::fs::remove_file(dir.join("notes.md")).unwrap();
+        std::fs::create_dir_all(dir.join(".hive-manager")).unwrap();
+        std::fs::write(dir.join(".hive-manager/report.md"), "verdict\n").unwrap();
+
+        std::fs::write(dir.join("judge.rs"), "fn added() {}\n").unwrap();
+        git(dir, &["add", "judge.rs"]);
+
+        let modified = modified_since(dir, &snapshot).unwrap();
+        assert_eq!(modified.changed, vec!["lib.rs".to_string(), "notes.md".to_string()]);
+        assert_eq!(modified.added, vec!["judge.rs".to_string()]);
+        restore(dir, &snapshot, &modified).unwrap();
+
+        assert_eq!(
+            std::fs::read_to_string(dir.join("lib.rs")).unwrap(),
+            "fn base() {}\n"
+        );
+        assert_eq!(
+            std::fs::read_to_string(dir.join("notes.md")).unwrap(),
+            "operator wip\n"
+        );
+        assert!(!dir.join("judge.rs").exists());
+        assert!(dir.join(".hive-manager/report.md").exists());
+        assert!(modified_since(dir, &snapshot).unwrap().is_empty());
+    }
+}
@@ -1,14 +1,59 @@
+import { invoke } from '@tauri-apps/api/core';
+
 const DEFAULT_API_BASE = 'http://localhost:18800';
 
-function resolveApiBase(): string {
+interface InstanceInfo {
+  instance: number;
+  api_port: number | null;
+  socket_path: string | null;
+  ui_token: string;
+}
+
+function envApiBase(): string | null {
   const envBase = import.meta.env.VITE_API_BASE;
   if (typeof envBase === 'string' && envBase.length > 0) return envBase;
-  return DEFAULT_API_BASE;
+  return null;
 }
 
-export const API_BASE = resolveApiBase();
+export let API_BASE = envApiBase() ?? DEFAULT_API_BASE;
+
+/** Token the backend accepts from this window on API writes; see `apiFetch`. */
+let uiToken: string | null = null;
+
+/**
+ * Point the API base at the port this instance's backend bound, which is not
+ * 18800 when another Hive Manager instance already holds it, and pick up the
+ * UI's API token. The server may still be starting, so the port is asked for a
+ * few times.
+ */
+export async function initApiBase(): Promise<void> {
+  try {
+    for (let attempt = 0; attempt < 5; attempt++) {
+      const info = await invoke<InstanceInfo>('get_instance_info');
+      uiToken = info.ui_token;
+      if (envApiBase()) return;
+      if (info.api_port) {
+        API_BASE = `http://localhost:${info.api_port}`;
+        return;
+      }
+      await new Promise((resolve) => setTimeout(resolve, 100));
+    }
+  } catch (err) {
+    console.warn('Failed to read the API port, keeping the default:', err);
+  }
+}
 
 export function apiUrl(path: string): string {
   const base = API_BASE.endsWith('/') ? API_BASE : `${API_BASE}/`;
   return new URL(path.replace(/^\/+/, ''), base).toString();
 }
+
+/**
+ * `fetch` against the API, sending the UI's token so session-scoped writes are
+ * accepted without a session token.
+ */
+export function apiFetch(path: string, init: RequestInit = {}): Promise<Response> {
+  const headers = new Headers(init.headers);
+  if (uiToken) headers.set('X-Hive-UI-Token', uiToken);
+  return fetch(apiUrl(path), { ...init, headers });
+}
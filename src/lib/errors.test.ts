@@ -0,0 +1,25 @@
+import { describe, expect, it } from 'vitest';
+
+import { errorCode, errorMessage, isRetryable } from './errors';
+
+describe('backend errors', () => {
+  it('reads the message and code from structured command errors', () => {
+    const error = {
+      code: 'spawn_failed',
+      message: 'Failed to spawn command: claude',
+      retryable: true,
+      context: { agent_id: 's1-worker-1' },
+    };
+
+    expect(errorMessage(error)).toBe('Failed to spawn command: claude');
+    expect(errorCode(error)).toBe('spawn_failed');
+    expect(isRetryable(error)).toBe(true);
+  });
+
+  it('falls back to plain errors and strings', () => {
+    expect(errorMessage(new Error('boom'))).toBe('boom');
+    expect(errorMessage('Session not found')).toBe('Session not found');
+    expect(errorCode('Session not found')).toBeNull();
+    expect(isRetryable(new Error('boom'))).toBe(false);
+  });
+});
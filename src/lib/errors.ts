@@ -0,0 +1,43 @@
+/** Kinds of failure the backend reports, mirroring `ErrorCode` in `src-tauri/src/error.rs`. */
+export type ErrorCode =
+  | 'not_found'
+  | 'validation_failed'
+  | 'conflict'
+  | 'spawn_failed'
+  | 'git_failed'
+  | 'cli_missing'
+  | 'internal';
+
+/** What a rejected `invoke()` throws. */
+export interface HiveError {
+  code: ErrorCode;
+  message: string;
+  retryable: boolean;
+  context?: Record<string, unknown>;
+}
+
+export function isHiveError(error: unknown): error is HiveError {
+  return (
+    typeof error === 'object' &&
+    error !== null &&
+    typeof (error as HiveError).code === 'string' &&
+    typeof (error as HiveError).message === 'string'
+  );
+}
+
+/** Text to show for anything a `catch` can receive. */
+export function errorMessage(error: unknown): string {
+  if (isHiveError(error)) return error.message;
+  if (error instanceof Error) return error.message;
+  return String(error);
+}
+
+/** The backend's error code, or `null` for errors that did not come from it. */
+export function errorCode(error: unknown): ErrorCode | null {
+  return isHiveError(error) ? error.code : null;
+}
+
+/** Whether trying the same request again may succeed. */
+export function isRetryable(error: unknown): boolean {
+  return isHiveError(error) && error.retryable;
+}
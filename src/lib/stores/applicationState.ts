@@ -1,5 +1,6 @@
 import { writable } from 'svelte/store';
 import { apiUrl } from '$lib/config';
+import { errorMessage } from '$lib/errors';
 
 /**
  * A single row of SQLite-backed application state. `value` is parsed JSON (matches the
@@ -93,14 +94,14 @@ function createApplicationStateStore() {
         await snapshot(sessionId);
       } catch (err) {
         if (activeSessionId === sessionId) {
-          update((state) => ({ ...state, loading: false, error: String(err) }));
+          update((state) => ({ ...state, loading: false, error: errorMessage(err) }));
         }
       }
 
       pollTimer = setInterval(() => {
         if (activeSessionId !== sessionId) return;
         pollOnce(sessionId).catch((err) => {
-          update((state) => ({ ...state, error: String(err) }));
+          update((state) => ({ ...state, error: errorMessage(err) }));
         });
       }, APPLICATION_STATE_POLL_INTERVAL);
     },
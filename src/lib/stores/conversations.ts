@@ -1,6 +1,7 @@
 import { writable } from 'svelte/store';
 import { listen } from '@tauri-apps/api/event';
-import { apiUrl } from '$lib/config';
+import { apiFetch, apiUrl } from '$lib/config';
+import { errorMessage } from '$lib/errors';
 
 export interface ConversationMessage {
   id?: string;
@@ -22,9 +23,21 @@ export interface ConversationMessage {
 
 export interface HeartbeatInfo {
   agent_id: string;
+  /** The agent's display name; its ID when it has no label. */
+  label?: string;
   status: string;
   summary: string;
   timestamp: string;
+  /** What the agent's CLI last said about its context window. */
+  context?: ContextPressure | null;
+}
+
+export interface ContextPressure {
+  percent_left: number | null;
+  low: boolean;
+  compacted_at: string | null;
+  nudged_at: string | null;
+  updated_at: string | null;
 }
 
 interface ConversationState {
@@ -188,15 +201,15 @@ function createConversationStore() {
             return state;
           }
 
-          return { ...state, loading: false, error: String(err) };
+          return { ...state, loading: false, error: errorMessage(err) };
         });
       }
     },
 
     async sendMessage(sessionId: string, agentId: string, from: string, content: string) {
       try {
-        const resp = await fetch(
-          apiUrl(`/api/sessions/${sessionId}/conversations/${agentId}/append`),
+        const resp = await apiFetch(
+          `/api/sessions/${sessionId}/conversations/${agentId}/append`,
           {
             method: 'POST',
             headers: { 'Content-Type': 'application/json' },
@@ -206,7 +219,7 @@ function createConversationStore() {
         if (!resp.ok) throw new Error(`HTTP ${resp.status}`);
         await this.pollMessages();
       } catch (err) {
-        update((state) => ({ ...state, error: String(err) }));
+        update((state) => ({ ...state, error: errorMessage(err) }));
       }
     },
 
@@ -290,7 +303,7 @@ function createHeartbeatStore() {
 
         const data = await resp.json();
         // Backend returns { sessions: [...] }; only populate the requested session.
-        const sessions: Array<{ id: string; agents?: Array<{ id?: string; agent_id?: string; status?: string; summary?: string; last_activity?: string }> }> =
+        const sessions: Array<{ id: string; agents?: Array<{ id?: string; agent_id?: string; label?: string; status?: string; summary?: string; last_activity?: string; context?: ContextPressure | null }> }> =
           Array.isArray(data?.sessions) ? data.sessions : [];
         const session = sessions.find((s) => s.id === sessionId);
         if (!session) {
@@ -306,9 +319,11 @@ function createHeartbeatStore() {
           const timestamp = agent.last_activity ?? '';
           agents[id] = {
             agent_id: id,
+            label: agent.label || id,
             status: agent.status || 'unknown',
             summary: agent.summary || '',
             timestamp,
+            context: agent.context ?? null,
           };
           if (isHeartbeatStale(timestamp)) {
             staleAgents.add(id);
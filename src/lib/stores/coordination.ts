@@ -1,20 +1,27 @@
 import { writable, derived } from 'svelte/store';
 import { invoke } from '@tauri-apps/api/core';
 import { listen } from '@tauri-apps/api/event';
+import { errorMessage } from '$lib/errors';
 
 export type MessageType = 'Task' | 'Progress' | 'Completion' | 'Error' | 'System' | 'Judge' | 'PeerFeedback' | 'MilestoneReady' | 'QaVerdict';
 
 export interface CoordinationMessage {
   id: string;
   timestamp: string;
+  /** Position in the session's coordination log; null for lines agents appended directly. */
+  seq?: number | null;
   from: string;
   to: string;
   content: string;
+  /** IDs of files under `coordination/attachments/` named by `[ATTACH <id>]` in the content. */
+  attachments?: string[];
   message_type: MessageType;
 }
 
 export interface WorkerStateInfo {
   id: string;
+  /** Custom display name ("Payments Backend"), when the worker has one. */
+  label?: string | null;
   role: WorkerRole;
   cli: string;
   status: string;
@@ -33,7 +40,11 @@ export interface QueenInjectRequest {
   session_id: string;
   queen_id: string;
   target_worker_id: string;
+  /** Raw text; leave empty when sending a named `template` instead. */
   message: string;
+  /** Injection template such as `check-inbox` or `commit-now`. */
+  template?: string;
+  variables?: Record<string, string>;
 }
 
 export interface AddWorkerRequest {
@@ -54,11 +65,59 @@ export interface AddWorkerRequest {
   parent_id?: string;
 }
 
+/** A question an agent is blocked on until the operator answers it. */
+export interface OperatorQuestion {
+  id: string;
+  session_id: string;
+  agent_id: string;
+  question: string;
+  options: string[];
+  asked_at: string;
+  answer: string | null;
+  answered_at: string | null;
+}
+
+export type GatedAction =
+  | { kind: 'spawn_worker'; worker_count: number }
+  | { kind: 'push'; branch: string }
+  | { kind: 'delete_files'; paths: string[] };
+
+/** An agent request parked until the operator approves or rejects it. */
+export type ApprovalRequest = GatedAction & {
+  id: string;
+  session_id: string;
+  agent_id: string | null;
+  reason: string;
+  requested_at: string;
+};
+
+/** A TASK typed into a worker's terminal, acknowledged by its next PROGRESS update. */
+export interface MessageDelivery {
+  message_id: string;
+  to: string;
+  content: string;
+  delivered_at: string;
+  acknowledged_at: string | null;
+}
+
+export type FusionVariantStage = 'creating_worktree' | 'spawning' | 'running' | 'failed';
+
+export interface FusionVariantProgress {
+  session_id: string;
+  variant_index: number;
+  variant_name: string;
+  stage: FusionVariantStage;
+  error: string | null;
+}
+
 interface CoordinationState {
   log: CoordinationMessage[];
   workers: WorkerStateInfo[];
+  questions: OperatorQuestion[];
+  approvals: ApprovalRequest[];
   fusionState: {
     completedVariants: string[];
+    variantProgress: FusionVariantProgress[];
     judgeReport: string | null;
     evaluationReady: boolean;
   };
@@ -71,8 +130,11 @@ function createCoordinationStore() {
   const { subscribe, set, update } = writable<CoordinationState>({
     log: [],
     workers: [],
+    questions: [],
+    approvals: [],
     fusionState: {
       completedVariants: [],
+      variantProgress: [],
       judgeReport: null,
       evaluationReady: false,
     },
@@ -96,6 +158,25 @@ function createCoordinationStore() {
     });
   });
 
+  // Listen for questions agents ask the operator, and for their answers
+  listen<OperatorQuestion>('operator-question', (event) => {
+    update((state) => ({
+      ...state,
+      questions: [
+        ...state.questions.filter((q) => q.id !== event.payload.id),
+        event.payload,
+      ].sort((a, b) => a.asked_at.localeCompare(b.asked_at)),
+    }));
+  });
+
+  // Listen for agent requests that wait on operator approval
+  listen<ApprovalRequest>('approval-required', (event) => {
+    update((state) => ({
+      ...state,
+      approvals: [...state.approvals.filter((a) => a.id !== event.payload.id), event.payload],
+    }));
+  });
+
   // Listen for fusion variant completion
   listen<{ variant: string }>('fusion-variant-completed', (event) => {
     update((state) => ({
@@ -107,6 +188,24 @@ function createCoordinationStore() {
     }));
   });
 
+  // Listen for per-variant spawn progress (variants come up in parallel after launch)
+  listen<FusionVariantProgress>('fusion-variant-progress', (event) => {
+    update((state) => ({
+      ...state,
+      fusionState: {
+        ...state.fusionState,
+        variantProgress: [
+          ...state.fusionState.variantProgress.filter(
+            (p) =>
+              p.session_id !== event.payload.session_id ||
+              p.variant_index !== event.payload.variant_index
+          ),
+          event.payload,
+        ],
+      },
+    }));
+  });
+
   // Listen for judge evaluation ready
   listen<{ report: string }>('judge-evaluation-ready', (event) => {
     update((state) => ({
@@ -130,6 +229,8 @@ function createCoordinationStore() {
         workers: sessionId === state.sessionId ? state.workers : [],
         fusionState: sessionId === state.sessionId ? state.fusionState : {
           completedVariants: [],
+          // Keyed by session_id, and a launch can report progress before the switch.
+          variantProgress: state.fusionState.variantProgress,
           judgeReport: null,
           evaluationReady: false,
         },
@@ -149,7 +250,7 @@ function createCoordinationStore() {
           loading: false,
         }));
       } catch (err) {
-        update((state) => ({ ...state, loading: false, error: String(err) }));
+        update((state) => ({ ...state, loading: false, error: errorMessage(err) }));
       }
     },
 
@@ -165,7 +266,7 @@ function createCoordinationStore() {
           loading: false,
         }));
       } catch (err) {
-        update((state) => ({ ...state, loading: false, error: String(err) }));
+        update((state) => ({ ...state, loading: false, error: errorMessage(err) }));
       }
     },
 
@@ -185,11 +286,67 @@ function createCoordinationStore() {
       try {
         await invoke('queen_inject', { request });
       } catch (err) {
-        update((state) => ({ ...state, error: String(err) }));
+        update((state) => ({ ...state, error: errorMessage(err) }));
         throw err;
       }
     },
 
+    async loadQuestions(sessionId: string) {
+      try {
+        const questions = await invoke<OperatorQuestion[]>('list_operator_questions', {
+          sessionId,
+        });
+        update((state) => ({ ...state, questions }));
+      } catch (err) {
+        update((state) => ({ ...state, error: errorMessage(err) }));
+      }
+    },
+
+    /** Types the answer into the asking agent's terminal. */
+    async answerQuestion(sessionId: string, questionId: string, answer: string) {
+      try {
+        await invoke<OperatorQuestion>('answer_operator_question', {
+          sessionId,
+          questionId,
+          answer,
+        });
+      } catch (err) {
+        update((state) => ({ ...state, error: errorMessage(err) }));
+        throw err;
+      }
+    },
+
+    async loadApprovals(sessionId?: string) {
+      try {
+        const approvals = await invoke<ApprovalRequest[]>('list_pending_approvals', {
+          sessionId: sessionId ?? null,
+        });
+        update((state) => ({ ...state, approvals }));
+      } catch (err) {
+        update((state) => ({ ...state, error: errorMessage(err) }));
+      }
+    },
+
+    /** Lets the parked request go ahead, or fails it back to the agent. */
+    async approveAction(approvalId: string, approved: boolean) {
+      try {
+        await invoke<ApprovalRequest>('approve_action', { approvalId, approved });
+      } finally {
+        update((state) => ({
+          ...state,
+          approvals: state.approvals.filter((a) => a.id !== approvalId),
+        }));
+      }
+    },
+
+    /** TASK messages no worker acknowledged, for re-sending dropped instructions. */
+    async listUnacknowledged(sessionId: string, agentId?: string) {
+      return invoke<MessageDelivery[]>('list_unacknowledged_messages', {
+        sessionId,
+        agentId: agentId ?? null,
+      });
+    },
+
     async addWorker(request: AddWorkerRequest) {
       update((state) => ({ ...state, loading: true, error: null }));
       try {
@@ -197,7 +354,7 @@ function createCoordinationStore() {
         update((state) => ({ ...state, loading: false }));
         return agentInfo;
       } catch (err) {
-        update((state) => ({ ...state, loading: false, error: String(err) }));
+        update((state) => ({ ...state, loading: false, error: errorMessage(err) }));
         throw err;
       }
     },
@@ -223,7 +380,7 @@ function createCoordinationStore() {
           ...payload,
         });
       } catch (err) {
-        update((state) => ({ ...state, error: String(err) }));
+        update((state) => ({ ...state, error: errorMessage(err) }));
         throw err;
       }
     },
@@ -263,3 +420,10 @@ export const messagesByType = derived(coordination, ($coordination) => {
 export const activeWorkersCount = derived(coordination, ($coordination) =>
   $coordination.workers.filter((w) => w.status === 'Running').length
 );
+
+// Derived store for questions still waiting on the operator
+export const pendingQuestions = derived(coordination, ($coordination) =>
+  $coordination.questions.filter(
+    (q) => q.answer === null && q.session_id === $coordination.sessionId
+  )
+);
@@ -1,5 +1,5 @@
 import { writable } from 'svelte/store';
-import { apiUrl } from '$lib/config';
+import { apiFetch } from '$lib/config';
 
 /**
  * One-shot operator-selection context (#128 Ctrl+I "select -> instruct").
@@ -67,7 +67,7 @@ function createPendingContextStore() {
       mirror = ctx;
       set({ current: ctx });
       try {
-        await fetch(apiUrl(`/api/sessions/${ctx.sessionId}/application-state`), {
+        await apiFetch(`/api/sessions/${ctx.sessionId}/application-state`, {
           method: 'POST',
           headers: { 'content-type': 'application/json' },
           body: JSON.stringify({ key: PENDING_CONTEXT_KEY, value: ctx }),
@@ -85,8 +85,8 @@ function createPendingContextStore() {
     async consume(sessionId: string): Promise<PendingContext | null> {
       let taken: PendingContext | null = null;
       try {
-        const resp = await fetch(
-          apiUrl(`/api/sessions/${sessionId}/application-state/take`),
+        const resp = await apiFetch(
+          `/api/sessions/${sessionId}/application-state/take`,
           {
             method: 'POST',
             headers: { 'content-type': 'application/json' },
@@ -1,7 +1,7 @@
 import { get } from 'svelte/store';
 import { describe, expect, it } from 'vitest';
 
-import { scratchTerminals, shellCommand } from './scratchTerminals';
+import { defaultScratchShell, scratchTerminals, shellCommand } from './scratchTerminals';
 
 describe('scratch terminal panes', () => {
   it('keeps pane and focus state isolated by session', () => {
@@ -38,4 +38,11 @@ describe('scratch terminal panes', () => {
     expect(shellCommand('powershell')).toEqual({ command: 'powershell.exe', args: ['-NoLogo'] });
     expect(shellCommand('cmd')).toEqual({ command: 'cmd.exe', args: [] });
   });
+
+  it('leaves the login shell for the backend to resolve and defaults to it off Windows', () => {
+    expect(shellCommand('login')).toEqual({ command: '', args: [] });
+    expect(defaultScratchShell('Win32')).toBe('powershell');
+    expect(defaultScratchShell('MacIntel')).toBe('login');
+    expect(defaultScratchShell('Linux x86_64')).toBe('login');
+  });
 });
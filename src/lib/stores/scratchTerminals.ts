@@ -1,6 +1,6 @@
 import { writable } from 'svelte/store';
 
-export type ScratchShell = 'powershell' | 'cmd';
+export type ScratchShell = 'powershell' | 'cmd' | 'login';
 
 export interface ScratchTerminalPane {
   kind: 'scratch';
@@ -28,10 +28,26 @@ function scratchId(sessionId: string): string {
   return `scratch:${sessionId}:${suffix}`;
 }
 
+const SHELL_TITLES: Record<ScratchShell, string> = {
+  powershell: 'PowerShell',
+  cmd: 'Command Prompt',
+  login: 'Login Shell',
+};
+
+/** The backend resolves the login shell itself (`$SHELL -l`), so it is sent without a command. */
 export function shellCommand(shell: ScratchShell): { command: string; args: string[] } {
-  return shell === 'powershell'
-    ? { command: 'powershell.exe', args: ['-NoLogo'] }
-    : { command: 'cmd.exe', args: [] };
+  switch (shell) {
+    case 'powershell':
+      return { command: 'powershell.exe', args: ['-NoLogo'] };
+    case 'cmd':
+      return { command: 'cmd.exe', args: [] };
+    case 'login':
+      return { command: '', args: [] };
+  }
+}
+
+export function defaultScratchShell(platform: string): ScratchShell {
+  return platform.toLowerCase().startsWith('win') ? 'powershell' : 'login';
 }
 
 function createScratchTerminalStore() {
@@ -44,7 +60,7 @@ function createScratchTerminalStore() {
         kind: 'scratch',
         id: scratchId(sessionId),
         sessionId,
-        title: shell === 'powershell' ? 'PowerShell' : 'Command Prompt',
+        title: SHELL_TITLES[shell],
         cwd,
         shell,
         createdAt: new Date().toISOString(),
@@ -1,5 +1,6 @@
 import { writable } from 'svelte/store';
 import { apiUrl } from '$lib/config';
+import { errorMessage } from '$lib/errors';
 
 export const SESSION_FILES_POLL_INTERVAL = 5000;
 
@@ -157,10 +158,6 @@ async function responseError(response: Response, fallback: string): Promise<Erro
   return new Error(detail || `${fallback} (HTTP ${response.status})`);
 }
 
-function errorMessage(error: unknown): string {
-  return error instanceof Error ? error.message : String(error);
-}
-
 function createSessionFilesStore() {
   const { subscribe, set, update } = writable<SessionFilesState>(initialState());
   let activeListRequest = 0;
@@ -2,8 +2,11 @@ import { writable, derived } from 'svelte/store';
 import { invoke } from '@tauri-apps/api/core';
 import { listen } from '@tauri-apps/api/event';
 import type { CellStatus, WorkspaceStrategy } from '$lib/types/domain';
+import type { CliHealthEntry } from '$lib/components/AgentConfigEditor.svelte';
 import { applicationState } from './applicationState';
+import type { CoordinationMessage, MessageDelivery } from './coordination';
 import { ui } from './ui';
+import { errorMessage } from '$lib/errors';
 
 export type AgentRole =
   | 'MasterPlanner'
@@ -20,8 +23,17 @@ export type AgentStatus =
   | 'Running' 
   | { WaitingForInput: string } 
   | 'Completed' 
+  | { Crashed: string }
   | { Error: string };
 
+export interface AgentExitInfo {
+  exit_code: number | null;
+  signal: string | null;
+  stopped: boolean;
+  last_output: string[];
+  exited_at: string;
+}
+
 export interface WorkerRole {
   role_type: string;
   label: string;
@@ -38,6 +50,8 @@ export interface AgentConfig {
   description?: string;
   role?: WorkerRole;
   initial_prompt?: string;
+  allowed_paths?: string[];
+  denied_paths?: string[];
 }
 
 export interface AgentInfo {
@@ -46,6 +60,7 @@ export interface AgentInfo {
   status: AgentStatus;
   config: AgentConfig;
   parent_id: string | null;
+  exit_info?: AgentExitInfo | null;
 }
 
 export type DelegationMode = 'disabled' | 'auto' | 'encouraged';
@@ -67,6 +82,27 @@ export interface HiveLaunchPolicy extends Omit<HiveExecutionPolicy, 'workspace_s
   workspace_strategy: Extract<WorkspaceStrategy, 'shared_cell' | 'isolated_cell'>;
 }
 
+/** Maximum seconds per phase; omitted phases are unbounded. */
+export interface PhaseTimeouts {
+  planning_secs?: number;
+  worker_secs?: number;
+  judging_secs?: number;
+}
+
+/** Caps on the live workers a Queen may spawn, in total and per role type. */
+export interface SpawnQuota {
+  max_workers?: number;
+  per_role?: Record<string, number>;
+}
+
+/** Workers run one after another once every Hive worker has finished. */
+export interface ReviewStageConfig {
+  /** Worker roles in order; `['reviewer', 'resolver']` when omitted. */
+  chain?: string[];
+  /** Defaults to the Queen's CLI and model. */
+  agent_config?: AgentConfig;
+}
+
 export interface HiveLaunchConfig {
   name?: string;
   color?: string;
@@ -80,6 +116,13 @@ export interface HiveLaunchConfig {
   evaluator_config?: AgentConfig;
   qa_workers?: QaWorkerConfig[];
   smoke_test?: boolean;
+  smoke_probe?: boolean;
+  branch_name?: string;
+  phase_timeouts?: PhaseTimeouts;
+  review_stage?: ReviewStageConfig;
+  spawn_quota?: SpawnQuota;
+  /** Run the session's CLIs below normal OS priority, with paced terminal output. */
+  background?: boolean;
 }
 
 export interface ResearchLaunchConfig {
@@ -120,6 +163,11 @@ export interface FusionLaunchConfig {
   judge_config: { cli: string; model?: string; flags?: string[]; label?: string };
   queen_config?: { cli: string; model?: string; flags?: string[]; label?: string };
   with_planning: boolean;
+  quorum?: number;
+  max_variant_retries?: number;
+  phase_timeouts?: PhaseTimeouts;
+  background?: boolean;
+  dirty_tree?: 'abort' | 'stash';
 }
 
 export interface DebateDebaterConfig {
@@ -142,6 +190,8 @@ export interface DebateLaunchConfig {
   with_planning: boolean;
   default_cli: string;
   default_model?: string;
+  phase_timeouts?: PhaseTimeouts;
+  background?: boolean;
 }
 
 export interface PlannerConfig {
@@ -164,6 +214,8 @@ export interface SwarmLaunchConfig {
   evaluator_config?: AgentConfig;
   qa_workers?: QaWorkerConfig[];
   smoke_test?: boolean;
+  phase_timeouts?: PhaseTimeouts;
+  background?: boolean;
 }
 
 export interface SoloLaunchConfig {
@@ -179,7 +231,24 @@ export interface SoloLaunchConfig {
   qa_workers?: QaWorkerConfig[];
 }
 
+/** A launch config tagged with its mode, as `preflight_check` takes it. */
+export type PreflightLaunch =
+  | { mode: 'hive' | 'solo'; config: HiveLaunchConfig }
+  | { mode: 'research'; config: ResearchLaunchConfig }
+  | { mode: 'swarm'; config: SwarmLaunchConfig }
+  | { mode: 'fusion'; config: FusionLaunchConfig }
+  | { mode: 'debate'; config: DebateLaunchConfig };
+
+export interface PreflightReport {
+  /** The launch's CLIs, then `gh`. */
+  clis: CliHealthEntry[];
+  /** Missing or logged-out CLIs; agents on them would sit at a login screen. */
+  blockers: string[];
+  warnings: string[];
+}
+
 export type SessionState =
+  | 'Queued'
   | 'Planning'
   | 'PlanReady'
   | 'Starting'
@@ -191,6 +260,7 @@ export type SessionState =
   | 'QaMaxRetriesExceeded'
   | 'PrinceRemediation'
   | 'QaInconclusive'
+  | { Reviewing: { step: number; role: string } }
   | 'Paused'
   | 'Completed'
   | 'Closed'
@@ -210,6 +280,8 @@ export function sessionStateToCellStatus(state: SessionState | unknown): CellSta
   const key = serdeEnumVariantName(state) ?? 'Unknown';
 
   switch (key) {
+    case 'Queued':
+      return 'queued';
     case 'Planning':
     case 'PlanReady':
       return 'preparing';
@@ -227,6 +299,7 @@ export function sessionStateToCellStatus(state: SessionState | unknown): CellSta
     case 'MergingWinner':
     case 'QaInProgress':
     case 'PrinceRemediation':
+    case 'Reviewing':
     case 'Running':
       return 'running';
     case 'AwaitingVerdictSelection':
@@ -274,6 +347,8 @@ export interface Session {
   /** Git worktree path for the session primary workspace (Tauri Session), when set. */
   worktree_path?: string | null;
   worktree_branch?: string | null;
+  /** Pull request opened for the session branch via `create_pull_request`. */
+  pull_request_url?: string | null;
   /** Present on a resumed session (#125): per-step classification for the resume modal. */
   resume_report?: ResumeReport | null;
 }
@@ -331,6 +406,17 @@ export interface ResumeReport {
   interrupted: RunJournalEntry[];
   /** Ledger effects that could not be confirmed and need human attention. */
   uncertain: LedgerEntry[];
+  /** How each agent of an unfinished session was restored (backend only). */
+  agents?: AgentReconciliation[];
+}
+
+export type AgentResumeState = 'running' | 'completed' | 'missing_pty';
+
+export interface AgentReconciliation {
+  agent_id: string;
+  state: AgentResumeState;
+  /** The worker's task-file status, when it has one. */
+  task_status?: string | null;
 }
 
 export interface RunJournalResponse {
@@ -347,6 +433,228 @@ export async function getRunJournal(sessionId: string): Promise<RunJournalRespon
   return invoke<RunJournalResponse>('get_run_journal', { sessionId });
 }
 
+export interface Checkpoint {
+  worker: string;
+  ref_name: string;
+  commit: string;
+  created_at: string;
+  message: string;
+}
+
+/** List the WIP checkpoints recorded for a session's worker worktrees. */
+export async function listCheckpoints(sessionId: string): Promise<Checkpoint[]> {
+  return invoke<Checkpoint[]>('list_checkpoints', { id: sessionId });
+}
+
+export interface PhaseDuration {
+  phase: string;
+  started_at: string;
+  ended_at: string | null;
+  secs: number;
+}
+
+export interface AgentDuration {
+  agent_id: string;
+  role: string;
+  started_at: string;
+  ended_at: string | null;
+  secs: number;
+  running: boolean;
+}
+
+export interface SessionDurations {
+  session_id: string;
+  session_type: string;
+  total_secs: number;
+  phases: PhaseDuration[];
+  phase_totals: Record<string, number>;
+  agents: AgentDuration[];
+}
+
+/** Wall-clock time a session spent per phase and per agent. */
+export async function getSessionDurations(sessionId: string): Promise<SessionDurations> {
+  return invoke<SessionDurations>('get_session_durations', { id: sessionId });
+}
+
+export interface RecordingInfo {
+  agent_id: string;
+  path: string;
+  started_at: string | null;
+  duration_secs: number;
+  width: number;
+  height: number;
+  size_bytes: number;
+}
+
+export interface SessionRecordings {
+  enabled: boolean;
+  recordings: RecordingInfo[];
+}
+
+/** Record the agent terminals spawned for a session from now on as asciinema `.cast` files. */
+export async function setSessionRecording(sessionId: string, enabled: boolean): Promise<void> {
+  await invoke('set_session_recording', { sessionId, enabled });
+}
+
+export async function listRecordings(sessionId: string): Promise<SessionRecordings> {
+  return invoke<SessionRecordings>('list_recordings', { sessionId });
+}
+
+export interface SessionArtifact {
+  session_id: string;
+  path: string;
+  worktrees: boolean;
+  bytes: number;
+}
+
+export interface ArtifactCleanupReport {
+  project_path: string;
+  dry_run: boolean;
+  artifacts: SessionArtifact[];
+  freed_bytes: number;
+  skipped_active: string[];
+  skipped_unknown: string[];
+  missing_gitignore_entries: string[];
+  errors: string[];
+}
+
+export type PlanIssueMode = 'sub_issues' | 'checklist';
+
+export interface PlanIssueLink {
+  parent: number;
+  mode: PlanIssueMode;
+  /** The checklist comment, in checklist mode. */
+  comment_id?: number;
+  tasks: { number: number; title: string; issue_url?: string; closed: boolean }[];
+}
+
+export interface FullSessionSnapshot {
+  session: Session;
+  heartbeats: Record<string, { last_activity: string; status: string; summary?: string | null }>;
+  /** TASK messages workers have not acknowledged yet. */
+  pending_injections: MessageDelivery[];
+  /** Worker agent ID to the status line of its task file. */
+  task_statuses: Record<string, string>;
+  /** The newest coordination messages, oldest first. */
+  coordination: CoordinationMessage[];
+  plan: {
+    /** `null` until a plan has been written. */
+    plan: {
+      title?: string | null;
+      summary?: string | null;
+      tasks: { number: number; title: string; done: boolean; assignee?: string | null }[];
+    } | null;
+    tasks_done: number;
+    tasks_total: number;
+  };
+}
+
+export interface WorkerScaleReport {
+  session_id: string;
+  target_count: number;
+  worker_count: number;
+  added: string[];
+  retired: string[];
+  /** Surplus workers still mid-task; scale again once they finish. */
+  pending_retirement: number;
+}
+
+/**
+ * Remove the `.hive-manager/` directories finished sessions left in a project.
+ * Only lists them unless `dryRun` is `false`.
+ */
+export async function cleanupProjectArtifacts(
+  projectPath: string,
+  dryRun = true,
+  includeUnknown = false,
+): Promise<ArtifactCleanupReport> {
+  return invoke<ArtifactCleanupReport>('cleanup_project_artifacts', {
+    projectPath,
+    dryRun,
+    includeUnknown,
+  });
+}
+
+/** Append missing `.hive-manager/` entries to the project's `.gitignore`. */
+export async function ensureProjectGitignore(projectPath: string): Promise<{ added: string[] }> {
+  return invoke<{ added: string[] }>('ensure_project_gitignore', { projectPath });
+}
+
+export type FusionPick =
+  | { kind: 'commit'; variant: string; commit: string }
+  | { kind: 'file'; variant: string; path: string };
+
+export interface FusionPickOutcome {
+  pick: FusionPick;
+  status: 'applied' | 'unchanged' | 'conflicted' | 'failed';
+  /** Files the pick clashed on; it was backed out. */
+  conflicts: string[];
+  message: string | null;
+}
+
+export interface FusionComposeReport {
+  branch: string;
+  base: string;
+  head: string;
+  picks: FusionPickOutcome[];
+}
+
+/**
+ * Build an integration branch (`fusion/<session>/composed` by default) from
+ * commits and files picked out of several Fusion variants, applied in order.
+ */
+export async function composeFusionResult(
+  sessionId: string,
+  picks: FusionPick[],
+  branch?: string,
+): Promise<FusionComposeReport> {
+  return invoke<FusionComposeReport>('compose_fusion_result', {
+    sessionId,
+    picks,
+    branch: branch ?? null,
+  });
+}
+
+/**
+ * Two to four Fusion variants for a task, from the CLI leaderboard and past
+ * runs on similar tasks, ready to drop into a `FusionLaunchConfig`.
+ */
+export async function suggestFusionVariants(
+  taskDescription: string,
+): Promise<FusionVariantConfig[]> {
+  return invoke<FusionVariantConfig[]>('suggest_fusion_variants', { taskDescription });
+}
+
+/** What spawning an agent would run; see `previewAgentCommand`. */
+export interface AgentCommandPreview {
+  command: string;
+  args: string[];
+  cwd: string | null;
+  /** Names of the configured env variables; their values are never returned. */
+  env_keys: string[];
+  path_prepend: string[];
+  env_allowlist: string[] | null;
+  /** `command` and `args` quoted to paste into a terminal. */
+  command_line: string;
+}
+
+/**
+ * The command line, directory and environment an agent with `config` would be
+ * spawned with, without spawning it. Pass `promptPath` to include the prompt
+ * file argument a launch adds.
+ */
+export async function previewAgentCommand(
+  config: AgentConfig,
+  cwd?: string,
+  promptPath?: string,
+): Promise<AgentCommandPreview> {
+  return invoke<AgentCommandPreview>('preview_agent_command', {
+    config,
+    cwd: cwd ?? null,
+    promptPath: promptPath ?? null,
+  });
+}
+
 const WRITE_STEP_KINDS = new Set<StepKind>([
   'worker_spawn',
   'evaluator_spawn',
@@ -359,7 +667,8 @@ export function isResumeReportEmpty(report: ResumeReport): boolean {
   return (
     report.skipped.length === 0 &&
     report.interrupted.length === 0 &&
-    report.uncertain.length === 0
+    report.uncertain.length === 0 &&
+    (report.agents?.length ?? 0) === 0
   );
 }
 
@@ -443,7 +752,7 @@ function createSessionsStore() {
         const sessions = await invoke<Session[]>('list_sessions');
         update((state) => ({ ...state, sessions, loading: false }));
       } catch (err) {
-        update((state) => ({ ...state, loading: false, error: String(err) }));
+        update((state) => ({ ...state, loading: false, error: errorMessage(err) }));
       }
     },
 
@@ -468,7 +777,7 @@ function createSessionsStore() {
         });
         return session;
       } catch (err) {
-        update((state) => ({ ...state, loading: false, error: String(err) }));
+        update((state) => ({ ...state, loading: false, error: errorMessage(err) }));
         throw err;
       }
     },
@@ -514,7 +823,7 @@ function createSessionsStore() {
         });
         return session;
       } catch (err) {
-        update((state) => ({ ...state, loading: false, error: String(err) }));
+        update((state) => ({ ...state, loading: false, error: errorMessage(err) }));
         throw err;
       }
     },
@@ -530,7 +839,7 @@ function createSessionsStore() {
           return { ...state };
         });
       } catch (err) {
-        update((state) => ({ ...state, error: String(err) }));
+        update((state) => ({ ...state, error: errorMessage(err) }));
         throw err;
       }
     },
@@ -546,11 +855,16 @@ function createSessionsStore() {
           return { ...state };
         });
       } catch (err) {
-        update((state) => ({ ...state, error: String(err) }));
+        update((state) => ({ ...state, error: errorMessage(err) }));
         throw err;
       }
     },
 
+    /** Check that every CLI the launch uses is installed and logged in. */
+    async preflightCheck(launch: PreflightLaunch): Promise<PreflightReport> {
+      return invoke<PreflightReport>('preflight_check', { config: launch });
+    },
+
     async launchHiveV2(config: HiveLaunchConfig) {
       update((state) => ({ ...state, loading: true, error: null }));
       try {
@@ -566,7 +880,7 @@ function createSessionsStore() {
         });
         return session;
       } catch (err) {
-        update((state) => ({ ...state, loading: false, error: String(err) }));
+        update((state) => ({ ...state, loading: false, error: errorMessage(err) }));
         throw err;
       }
     },
@@ -586,7 +900,7 @@ function createSessionsStore() {
         });
         return session;
       } catch (err) {
-        update((state) => ({ ...state, loading: false, error: String(err) }));
+        update((state) => ({ ...state, loading: false, error: errorMessage(err) }));
         throw err;
       }
     },
@@ -606,7 +920,7 @@ function createSessionsStore() {
         });
         return session;
       } catch (err) {
-        update((state) => ({ ...state, loading: false, error: String(err) }));
+        update((state) => ({ ...state, loading: false, error: errorMessage(err) }));
         throw err;
       }
     },
@@ -626,7 +940,7 @@ function createSessionsStore() {
         });
         return session;
       } catch (err) {
-        update((state) => ({ ...state, loading: false, error: String(err) }));
+        update((state) => ({ ...state, loading: false, error: errorMessage(err) }));
         throw err;
       }
     },
@@ -646,7 +960,7 @@ function createSessionsStore() {
         });
         return session;
       } catch (err) {
-        update((state) => ({ ...state, loading: false, error: String(err) }));
+        update((state) => ({ ...state, loading: false, error: errorMessage(err) }));
         throw err;
       }
     },
@@ -684,7 +998,7 @@ function createSessionsStore() {
         });
         return session;
       } catch (err) {
-        update((state) => ({ ...state, loading: false, error: String(err) }));
+        update((state) => ({ ...state, loading: false, error: errorMessage(err) }));
         throw err;
       }
     },
@@ -700,7 +1014,7 @@ function createSessionsStore() {
           return { ...state };
         });
       } catch (err) {
-        update((state) => ({ ...state, error: String(err) }));
+        update((state) => ({ ...state, error: errorMessage(err) }));
         throw err;
       }
     },
@@ -731,7 +1045,58 @@ function createSessionsStore() {
         });
         return session;
       } catch (err) {
-        update((state) => ({ ...state, loading: false, error: String(err) }));
+        update((state) => ({ ...state, loading: false, error: errorMessage(err) }));
+        throw err;
+      }
+    },
+
+    /** Load a session created elsewhere from `<projectPath>/.hive-manager/<sessionId>/`. */
+    async adoptSession(projectPath: string, sessionId: string) {
+      update((state) => ({ ...state, loading: true, error: null }));
+      try {
+        const session = await invoke<Session>('adopt_session', { projectPath, sessionId });
+        update((state) => ({
+          ...state,
+          sessions: [...state.sessions, session],
+          activeSessionId: session.id,
+          loading: false,
+        }));
+        return session;
+      } catch (err) {
+        update((state) => ({ ...state, loading: false, error: errorMessage(err) }));
+        throw err;
+      }
+    },
+
+    /** Cut an agent off from the session API and coordination messages; its terminal keeps running. */
+    async quarantineAgent(sessionId: string, agentId: string) {
+      try {
+        await invoke('quarantine_agent', { sessionId, agentId });
+      } catch (err) {
+        update((state) => ({ ...state, error: errorMessage(err) }));
+        throw err;
+      }
+    },
+
+    async releaseAgent(sessionId: string, agentId: string) {
+      try {
+        await invoke('release_agent', { sessionId, agentId });
+      } catch (err) {
+        update((state) => ({ ...state, error: errorMessage(err) }));
+        throw err;
+      }
+    },
+
+    /** Launches waiting for running sessions to free capacity, next to start first. */
+    async listQueuedSessions(): Promise<Session[]> {
+      return invoke<Session[]>('list_queued_sessions');
+    },
+
+    async cancelQueuedSession(sessionId: string) {
+      try {
+        await invoke('cancel_queued_session', { sessionId });
+      } catch (err) {
+        update((state) => ({ ...state, error: errorMessage(err) }));
         throw err;
       }
     },
@@ -740,7 +1105,7 @@ function createSessionsStore() {
       try {
         await invoke('apply_fusion_winner', { sessionId, variantName });
       } catch (err) {
-        update((state) => ({ ...state, error: String(err) }));
+        update((state) => ({ ...state, error: errorMessage(err) }));
         throw err;
       }
     },
@@ -757,7 +1122,116 @@ function createSessionsStore() {
         });
         return session;
       } catch (err) {
-        update((state) => ({ ...state, error: String(err) }));
+        update((state) => ({ ...state, error: errorMessage(err) }));
+        throw err;
+      }
+    },
+
+    /** Escalate a Solo session into a Hive; its agent stays on as Worker 1. */
+    async promoteSession(id: string, workers: AgentConfig[]) {
+      try {
+        const session = await invoke<Session>('promote_session', { id, workers });
+        update((state) => {
+          const idx = state.sessions.findIndex((s) => s.id === session.id);
+          if (idx >= 0) {
+            state.sessions[idx] = session;
+          }
+          return { ...state };
+        });
+        return session;
+      } catch (err) {
+        update((state) => ({ ...state, error: errorMessage(err) }));
+        throw err;
+      }
+    },
+
+    /** Spawn or retire idle workers until the Hive has `targetCount` of them. */
+    async scaleWorkers(id: string, targetCount: number) {
+      try {
+        return await invoke<WorkerScaleReport>('scale_workers', { id, targetCount });
+      } catch (err) {
+        update((state) => ({ ...state, error: errorMessage(err) }));
+        throw err;
+      }
+    },
+
+    /** Replace a running session's spawn quota; an empty quota lifts it. */
+    async overrideSpawnQuota(id: string, quota: SpawnQuota) {
+      try {
+        await invoke('override_spawn_quota', {
+          id,
+          maxWorkers: quota.max_workers,
+          perRole: quota.per_role,
+        });
+      } catch (err) {
+        update((state) => ({ ...state, error: errorMessage(err) }));
+        throw err;
+      }
+    },
+
+    /** Re-run a Fusion session's judge, or add one second-opinion judge per config. */
+    async runFusionJudges(id: string, judges: AgentConfig[] = []) {
+      try {
+        return await invoke<{ session_id: string; judge_ids: string[] }>('run_fusion_judges', {
+          id,
+          judges,
+        });
+      } catch (err) {
+        update((state) => ({ ...state, error: errorMessage(err) }));
+        throw err;
+      }
+    },
+
+    /** Load everything shown for a session in one call, e.g. to rebuild the UI after a reload. */
+    async getFullSessionSnapshot(sessionId: string, coordinationLimit?: number) {
+      try {
+        const snapshot = await invoke<FullSessionSnapshot>('get_full_session_snapshot', {
+          sessionId,
+          coordinationLimit,
+        });
+        update((state) => {
+          const exists = state.sessions.some((s) => s.id === sessionId);
+          return {
+            ...state,
+            sessions: exists
+              ? state.sessions.map((s) => (s.id === sessionId ? snapshot.session : s))
+              : [...state.sessions, snapshot.session],
+          };
+        });
+        return snapshot;
+      } catch (err) {
+        update((state) => ({ ...state, error: errorMessage(err) }));
+        throw err;
+      }
+    },
+
+    /** Mirror a ready plan's tasks onto GitHub issue `parentIssue` (a number or URL). */
+    async linkPlanIssues(id: string, parentIssue: string, mode: PlanIssueMode = 'sub_issues') {
+      try {
+        return await invoke<PlanIssueLink>('link_plan_issues', { id, parentIssue, mode });
+      } catch (err) {
+        update((state) => ({ ...state, error: errorMessage(err) }));
+        throw err;
+      }
+    },
+
+    /** Open a file of session `sessionId` at a line in the configured editor. */
+    async openInEditor(sessionId: string, path: string, line?: number, column?: number) {
+      try {
+        await invoke('open_file_in_editor', { path, line, column, sessionId });
+      } catch (err) {
+        update((state) => ({ ...state, error: errorMessage(err) }));
+        throw err;
+      }
+    },
+
+    /** Queue a follow-up prompt for a Solo session; returns the prompts still pending. */
+    async queueSoloTask(id: string, prompt: string) {
+      try {
+        const result = await invoke<{ pending: string[] }>('queue_solo_task', { id, prompt });
+        return result.pending;
+      } catch (err) {
+        update((state) => ({ ...state, error: errorMessage(err) }));
         throw err;
       }
     },
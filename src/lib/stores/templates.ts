@@ -1,4 +1,5 @@
 import { writable } from 'svelte/store';
+import { invoke } from '@tauri-apps/api/core';
 import type { RolePack, SessionTemplate } from '../types/domain';
 import { apiUrl } from '../config';
 
@@ -106,3 +107,30 @@ function createTemplatesStore() {
 
 export const templates = createTemplatesStore();
 export const selectedTemplate = writable<SessionTemplate | null>(null);
+
+export interface TemplateVariable {
+    name: string;
+    conditional: boolean;
+    supplied_by_engine: boolean;
+    description: string | null;
+}
+
+export interface PromptTemplateInfo {
+    name: string;
+    is_builtin: boolean;
+    on_disk: boolean;
+    variables: TemplateVariable[];
+}
+
+export interface PromptTemplate extends PromptTemplateInfo {
+    content: string;
+}
+
+/** Prompt templates (built-in and on-disk overrides) for the settings UI. */
+export const promptTemplates = {
+    list: () => invoke<PromptTemplateInfo[]>('list_templates'),
+    get: (name: string) => invoke<PromptTemplate>('get_template', { name }),
+    save: (name: string, content: string) =>
+        invoke<PromptTemplate>('save_template', { name, content }),
+    delete: (name: string) => invoke<void>('delete_template', { name }),
+};
@@ -1,5 +1,5 @@
 import { writable } from 'svelte/store';
-import { apiUrl } from '$lib/config';
+import { apiFetch } from '$lib/config';
 
 export type LayoutMode = 'focused';
 
@@ -35,7 +35,7 @@ function persistUiState(key: string, value: unknown) {
     key,
     setTimeout(() => {
       persistTimers.delete(key);
-      fetch(apiUrl(`/api/sessions/${sessionId}/application-state`), {
+      apiFetch(`/api/sessions/${sessionId}/application-state`, {
         method: 'POST',
         headers: { 'content-type': 'application/json' },
         body: JSON.stringify({ key, value }),
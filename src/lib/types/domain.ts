@@ -119,6 +119,7 @@ export type EventType =
     | 'agent_failed'
     | 'artifact_updated'
     | 'resolver_selected_candidate'
+    | 'phase_timeout'
     | 'lagged';
 
 export type Severity = 'info' | 'warning' | 'error';
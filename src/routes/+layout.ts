@@ -1,5 +1,12 @@
+import { initApiBase } from '$lib/config';
+
 // Tauri doesn't have a Node.js server to do proper SSR
 // so we use adapter-static with a fallback to index.html to put the site in SPA mode
 // See: https://svelte.dev/docs/kit/single-page-apps
 // See: https://v2.tauri.app/start/frontend/sveltekit/ for more info
 export const ssr = false;
+
+// Learn which port this instance's API is on before any store calls it.
+export const load = async () => {
+  await initApiBase();
+};